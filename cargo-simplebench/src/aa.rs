@@ -0,0 +1,316 @@
+use crate::plan::plan_benchmark;
+use crate::{output, topology};
+use anyhow::Result;
+use colored::*;
+use simplebench_runtime::baseline::{
+    judge_against_history, storage_key, BaselineData, ComparisonResult,
+};
+use simplebench_runtime::config::{BenchmarkConfig, ComparisonConfig};
+use simplebench_runtime::filter::BenchFilter;
+use simplebench_runtime::output::{name_column_width, truncate_middle};
+use simplebench_runtime::{BenchResult, BenchmarkInfo};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Width of the table's columns after the benchmark name
+const NAME_RESERVED: usize = 36;
+
+/// Machine id of the in-memory windows; nothing is written under it
+const AA_MACHINE_ID: &str = "aa";
+
+/// Benchmarks to run and how often
+pub struct AaOptions {
+    pub iterations: usize,
+    pub bench_filter: Option<BenchFilter>,
+    pub wide: bool,
+}
+
+/// Verdicts of one benchmark's back-to-back runs of unchanged code
+#[derive(Debug)]
+pub struct AaReport {
+    pub benchmark_name: String,
+    /// Every run after the first, judged against the session's earlier runs
+    pub comparisons: Vec<ComparisonResult>,
+    /// False positive rate `plan` predicts from the same runs
+    pub predicted_false_positive_rate: Option<f64>,
+    /// Smallest threshold `plan` expects to hold at this noise level
+    pub suggested_threshold: Option<f64>,
+}
+
+impl AaReport {
+    /// Comparisons flagged as a change in either direction; all of them are
+    /// false positives since the code didn't change
+    pub fn flagged(&self) -> usize {
+        self.comparisons
+            .iter()
+            .filter(|c| c.is_regression || c.is_improvement)
+            .count()
+    }
+
+    pub fn false_positive_rate(&self) -> f64 {
+        if self.comparisons.is_empty() {
+            return 0.0;
+        }
+        self.flagged() as f64 / self.comparisons.len() as f64
+    }
+
+    /// Flagged more often than the confidence level allows
+    pub fn is_too_tight(&self, config: &ComparisonConfig) -> bool {
+        self.false_positive_rate() > 1.0 - config.confidence_level
+    }
+}
+
+/// Run every matching benchmark `iterations` times back to back and report how
+/// often the comparison pipeline flags unchanged code, without saving baselines
+pub fn run_aa(workspace_root: &Path, options: &AaOptions) -> Result<()> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    simplebench_runtime::output::set_time_format(config.output.time_format());
+
+    let runner = crate::build_runner(workspace_root)?;
+    let benchmarks: Vec<BenchmarkInfo> = crate::discover_benchmarks(&runner, workspace_root)?
+        .into_iter()
+        .filter(|b| {
+            options
+                .bench_filter
+                .as_ref()
+                .map_or(true, |filter| filter.matches(&b.name))
+        })
+        .collect();
+    if benchmarks.is_empty() {
+        anyhow::bail!("No benchmarks found (the filter may have excluded all of them)");
+    }
+
+    let core = topology::get_usable_cores().first().copied().unwrap_or(1);
+    let env = HashMap::from([(
+        "SIMPLEBENCH_WORKSPACE_ROOT".to_string(),
+        workspace_root.display().to_string(),
+    )]);
+
+    println!();
+    println!(
+        "{}",
+        format!(
+            "A/A test: {} runs of {} benchmarks on core {}, no baselines saved",
+            options.iterations,
+            benchmarks.len(),
+            core
+        )
+        .green()
+        .bold()
+    );
+
+    let mut reports = Vec::new();
+    'benchmarks: for info in &benchmarks {
+        let mut runs = Vec::with_capacity(options.iterations);
+        for iteration in 1..=options.iterations {
+            let result = match crate::run_benchmark_blocking(info, core, &runner, &env) {
+                Ok(result) => result,
+                Err(error) => {
+                    println!("  {} {}", "✗".red(), error);
+                    continue 'benchmarks;
+                }
+            };
+            if result.skipped {
+                println!(
+                    "  {} skipped: {}",
+                    info.name,
+                    result.skip_reason.as_deref().unwrap_or("skip_if")
+                );
+                continue 'benchmarks;
+            }
+            println!(
+                "{}",
+                format!("  {} run {}/{}", info.name, iteration, options.iterations).dimmed()
+            );
+            runs.push(result);
+        }
+        reports.push(aa_report(info, &runs, &config.comparison));
+    }
+    println!();
+
+    if reports.is_empty() {
+        anyhow::bail!("No benchmark completed its A/A runs");
+    }
+    print_aa(&reports, &config.comparison, options.wide);
+    Ok(())
+}
+
+/// Judge a benchmark's runs (oldest first) and ask `plan` what it would have predicted
+fn aa_report(info: &BenchmarkInfo, runs: &[BenchResult], config: &ComparisonConfig) -> AaReport {
+    let history: Vec<BaselineData> = runs
+        .iter()
+        .map(|run| BaselineData::from_bench_result(run, AA_MACHINE_ID.to_string(), false))
+        .collect();
+    let samples = runs.last().map_or(0, |run| run.samples);
+    let plan = plan_benchmark(info.name.clone(), &history, samples, config);
+
+    AaReport {
+        benchmark_name: storage_key(&info.module, &info.name),
+        comparisons: judge_consecutive(runs, &history, config),
+        predicted_false_positive_rate: plan.as_ref().map(|p| p.false_positive_rate),
+        suggested_threshold: plan.map(|p| p.suggested_threshold),
+    }
+}
+
+/// Judge every run after the first as a session would: against the preceding
+/// `window_size` runs it didn't flag
+///
+/// `history` holds `runs` converted to baselines, index-aligned.
+fn judge_consecutive(
+    runs: &[BenchResult],
+    history: &[BaselineData],
+    config: &ComparisonConfig,
+) -> Vec<ComparisonResult> {
+    let mut accepted: Vec<BaselineData> = history.iter().take(1).cloned().collect();
+    let mut comparisons = Vec::with_capacity(runs.len().saturating_sub(1));
+
+    for (run, baseline) in runs.iter().zip(history).skip(1) {
+        let window = &accepted[accepted.len().saturating_sub(config.window_size)..];
+        let comparison = judge_against_history(run, window, config);
+
+        if config.improvement_resets_window && comparison.is_improvement {
+            accepted.clear();
+        }
+        if !comparison.is_regression {
+            accepted.push(baseline.clone());
+        }
+        comparisons.push(comparison);
+    }
+    comparisons
+}
+
+/// Print per-benchmark false positive counts next to the predicted rate
+fn print_aa(reports: &[AaReport], config: &ComparisonConfig, wide: bool) {
+    println!("{}", "A/A results".green().bold());
+    println!(
+        "{}",
+        format!(
+            "threshold {}%, confidence {}, window {}, cp threshold {}",
+            config.threshold, config.confidence_level, config.window_size, config.cp_threshold
+        )
+        .dimmed()
+    );
+    println!();
+
+    let width = name_column_width(
+        reports
+            .iter()
+            .map(|report| report.benchmark_name.as_str())
+            .chain(["Benchmark"]),
+        NAME_RESERVED,
+        output::table_width(wide),
+    );
+    println!(
+        "{:<width$} {:>9} {:>8} {:>8} {:>9}",
+        "Benchmark".bold(),
+        "Compared".bold(),
+        "Flagged".bold(),
+        "False +".bold(),
+        "Predicted".bold()
+    );
+    println!("{}", "─".repeat(width + NAME_RESERVED).dimmed());
+
+    for report in reports {
+        let flagged = report.flagged();
+        let flagged_label = if report.is_too_tight(config) {
+            flagged.to_string().red().bold()
+        } else {
+            flagged.to_string().green()
+        };
+        let predicted = report
+            .predicted_false_positive_rate
+            .map_or("-".to_string(), |rate| format!("{:.1}%", rate * 100.0));
+
+        println!(
+            "{:<width$} {:>9} {:>8} {:>8} {:>9}",
+            truncate_middle(&report.benchmark_name, width),
+            report.comparisons.len(),
+            flagged_label,
+            format!("{:.1}%", report.false_positive_rate() * 100.0),
+            predicted
+        );
+
+        if report.is_too_tight(config) {
+            let mut advice = format!(
+                "{}: {}/{} comparisons flagged — your threshold is too tight for this benchmark's noise",
+                report.benchmark_name,
+                flagged,
+                report.comparisons.len()
+            );
+            if let Some(threshold) = report.suggested_threshold {
+                advice.push_str(&format!(" (plan suggests {}%)", threshold));
+            }
+            println!("  {} {}", "↳".dimmed(), advice.yellow());
+        }
+    }
+
+    let compared: usize = reports.iter().map(|r| r.comparisons.len()).sum();
+    let flagged: usize = reports.iter().map(|r| r.flagged()).sum();
+    let too_tight = reports.iter().filter(|r| r.is_too_tight(config)).count();
+
+    println!("{}", "─".repeat(width + NAME_RESERVED).dimmed());
+    println!(
+        "{} of {} comparisons of unchanged code flagged; {} of {} benchmarks exceed the {:.0}% the confidence level allows",
+        flagged,
+        compared,
+        too_tight,
+        reports.len(),
+        (1.0 - config.confidence_level) * 100.0
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// A run alternating between `mean - 5` and `mean + 5` nanoseconds
+    fn run(mean: u64) -> BenchResult {
+        let mut result = BenchResult {
+            name: "bench".to_string(),
+            module: "tests".to_string(),
+            samples: 100,
+            all_timings: (0..100)
+                .map(|i| Duration::from_nanos(if i % 2 == 0 { mean - 5 } else { mean + 5 }))
+                .collect(),
+            ..Default::default()
+        };
+        result.percentiles.mean = Duration::from_nanos(mean);
+        result
+    }
+
+    fn judge(means: &[u64]) -> AaReport {
+        let runs: Vec<BenchResult> = means.iter().map(|&mean| run(mean)).collect();
+        let history: Vec<BaselineData> = runs
+            .iter()
+            .map(|r| BaselineData::from_bench_result(r, AA_MACHINE_ID.to_string(), false))
+            .collect();
+        AaReport {
+            benchmark_name: "tests_bench".to_string(),
+            comparisons: judge_consecutive(&runs, &history, &ComparisonConfig::default()),
+            predicted_false_positive_rate: None,
+            suggested_threshold: None,
+        }
+    }
+
+    #[test]
+    fn test_judge_consecutive_counts_false_positives() {
+        let config = ComparisonConfig::default();
+
+        let stable = judge(&[1000, 1001, 999, 1000, 1001]);
+        assert_eq!(stable.comparisons.len(), 4);
+        assert_eq!(stable.flagged(), 0);
+        assert!(!stable.is_too_tight(&config));
+
+        // A noisy run is flagged once and kept out of the window, so the next
+        // ordinary run still compares against the quiet ones
+        let noisy = judge(&[1000, 1001, 999, 1000, 1500, 1000]);
+        assert_eq!(noisy.comparisons.len(), 5);
+        assert!(noisy.comparisons[3].is_regression);
+        assert_eq!(noisy.flagged(), 1);
+        assert!((noisy.false_positive_rate() - 0.2).abs() < 1e-9);
+        assert!(noisy.is_too_tight(&config));
+    }
+}
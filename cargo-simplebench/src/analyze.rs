@@ -1,94 +1,214 @@
 use anyhow::{Context, Result};
 use colored::*;
-use simplebench_runtime::baseline::BaselineManager;
-use simplebench_runtime::{CpuAnalysis, Statistics};
+use simplebench_runtime::baseline::{
+    machines_disagree, storage_key, storage_name, summarize_machines, BaselineData, BaselineManager,
+};
+use simplebench_runtime::changepoint::{find_change_points, ChangePoint};
+use simplebench_runtime::config::{BenchmarkConfig, ComparisonConfig};
+use simplebench_runtime::perf_counters::format_count;
+use simplebench_runtime::statistics::{
+    autocorrelogram, detect_periodicity, histogram, linear_trend, median, DEFAULT_HISTOGRAM_BINS,
+    MAX_PERIOD, PERIODICITY_THRESHOLD,
+};
+use simplebench_runtime::{
+    percentile_label, snapshot_at, CoverageRule, CpuAnalysis, PerfSample, PerfSummary, Statistics,
+};
 use std::path::Path;
 
+/// Slowest samples placed on the run's timeline
+const SLOWEST_SAMPLES: usize = 5;
+
 pub fn run_analysis(
     workspace_root: &Path,
     benchmark_name: &str,
     run_timestamp: Option<String>,
     last_n: Option<usize>,
+    histogram_bins: Option<usize>,
+    periodicity: bool,
+    extremes: usize,
 ) -> Result<()> {
-    let baseline_manager = BaselineManager::with_root_dir(workspace_root.join(".benches"))?;
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    let baseline_manager = BaselineManager::for_workspace(workspace_root, &config.baseline)?;
+    simplebench_runtime::output::set_time_format(config.output.time_format());
+    let coverage = config.measurement.cpu_coverage_rule();
 
-    // Try to find the benchmark by searching all crate directories
-    let (crate_name, bench_name) = find_benchmark(&baseline_manager, benchmark_name)?;
+    let found = find_benchmark(&baseline_manager, benchmark_name)?;
 
     if let Some(timestamp) = run_timestamp {
         // Analyze specific run
-        analyze_single_run(&baseline_manager, &crate_name, &bench_name, &timestamp)?;
+        analyze_single_run(
+            &baseline_manager,
+            &found,
+            &timestamp,
+            histogram_bins,
+            periodicity,
+            extremes,
+            coverage,
+        )?;
     } else if let Some(n) = last_n {
         // Compare last N runs
-        analyze_multiple_runs(&baseline_manager, &crate_name, &bench_name, n)?;
+        analyze_multiple_runs(&baseline_manager, &found, n, &config.comparison, coverage)?;
     } else {
         // Analyze latest run + show history
-        analyze_latest_with_history(&baseline_manager, &crate_name, &bench_name)?;
+        analyze_latest_with_history(
+            &baseline_manager,
+            &found,
+            &config.comparison,
+            histogram_bins,
+            periodicity,
+            coverage,
+        )?;
     }
 
     Ok(())
 }
 
-/// Find the benchmark by searching through all crate directories
-fn find_benchmark(
+/// A stored benchmark resolved from a name given on the command line
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct FoundBenchmark {
+    /// Crate and benchmark names addressing its stored runs
+    pub crate_name: String,
+    pub bench_name: String,
+    /// Module and name recorded in its latest run
+    pub module: String,
+    pub name: String,
+}
+
+impl FoundBenchmark {
+    /// `<module>::<name>` heading shown above its runs
+    pub fn title(&self) -> String {
+        format!("{}::{}", self.module, self.name)
+    }
+}
+
+/// Find a stored benchmark by the directory its runs are stored in
+/// ([`storage_key`]), by bare name, or by name qualified with its module or
+/// the tail of its module path (`benchmarks::bench_step`)
+pub(crate) fn find_benchmark(
     baseline_manager: &BaselineManager,
     benchmark_name: &str,
-) -> Result<(String, String)> {
-    // Check if the benchmark name already includes crate name (contains "_")
-    if benchmark_name.contains('_') {
-        // Try to split into crate and bench name
-        if let Some(idx) = benchmark_name.find('_') {
-            let crate_name = &benchmark_name[..idx];
-            let bench_name = &benchmark_name[idx + 1..];
-
-            // Check if this combination exists
-            if baseline_manager.has_baseline(crate_name, bench_name) {
-                return Ok((crate_name.to_string(), bench_name.to_string()));
+) -> Result<FoundBenchmark> {
+    let (module, name) = match benchmark_name.rsplit_once("::") {
+        Some((module, name)) => (Some(module), name),
+        None => (None, benchmark_name),
+    };
+    // Every directory holding the benchmark ends with its bare name
+    let tail = benchmark_name
+        .rsplit(['-', ':'])
+        .next()
+        .unwrap_or(benchmark_name);
+
+    let mut by_key = None;
+    let mut by_name = Vec::new();
+    for stored in baseline_manager.stored_benchmarks(None)? {
+        if !stored.dir_name.ends_with(tail) {
+            continue;
+        }
+        let Some(run) =
+            baseline_manager.load_baseline(&stored.crate_name, &stored.benchmark_name)?
+        else {
+            continue;
+        };
+        let found = if run.module.is_empty() {
+            // Runs from before modules were recorded only have the directory name
+            FoundBenchmark {
+                module: stored.crate_name.clone(),
+                name: stored.benchmark_name.clone(),
+                crate_name: stored.crate_name,
+                bench_name: stored.benchmark_name,
+            }
+        } else {
+            let (crate_name, bench_name) = storage_name(&run.module, &run.benchmark_name);
+            FoundBenchmark {
+                crate_name: crate_name.to_string(),
+                bench_name,
+                module: run.module.clone(),
+                name: run.benchmark_name,
             }
+        };
+
+        if stored.dir_name == benchmark_name
+            || storage_key(&found.module, &found.name) == benchmark_name
+        {
+            by_key = Some(found);
+        } else if found.name == name
+            && module.map_or(true, |module| {
+                found.module == module || found.module.ends_with(&format!("::{}", module))
+            })
+        {
+            by_name.push(found);
         }
     }
+    if let Some(found) = by_key {
+        return Ok(found);
+    }
 
-    // If not found, search through machine directory for matching benchmark
-    anyhow::bail!(
-        "Benchmark '{}' not found. Use format: <crate_name>_<benchmark_name>",
-        benchmark_name
-    )
+    match by_name.len() {
+        1 => Ok(by_name.remove(0)),
+        0 => anyhow::bail!(
+            "Benchmark '{}' not found. Use format: <crate_name>_[<module>-]<benchmark_name>",
+            benchmark_name
+        ),
+        _ => anyhow::bail!(
+            "Benchmark name '{}' is ambiguous ({}). Use format: <crate_name>_[<module>-]<benchmark_name>",
+            benchmark_name,
+            by_name
+                .iter()
+                .map(|found| storage_key(&found.module, &found.name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
 }
 
 /// Analyze a single run and display detailed statistics
+#[allow(clippy::too_many_arguments)]
 fn analyze_single_run(
     baseline_manager: &BaselineManager,
-    crate_name: &str,
-    bench_name: &str,
+    found: &FoundBenchmark,
     timestamp: &str,
+    histogram_bins: Option<usize>,
+    periodicity: bool,
+    extremes: usize,
+    coverage: CoverageRule,
 ) -> Result<()> {
+    let (crate_name, bench_name) = (found.crate_name.as_str(), found.bench_name.as_str());
     let run_data = baseline_manager
         .load_run(crate_name, bench_name, timestamp)?
         .context(format!("Run '{}' not found", timestamp))?;
 
-    println!(
-        "{}",
-        format!("Benchmark: {}::{}", crate_name, bench_name)
-            .cyan()
-            .bold()
-    );
+    println!("{}", format!("Benchmark: {}", found.title()).cyan().bold());
     println!("{}", format!("Run: {}", timestamp).dimmed());
-    println!(
-        "{}",
-        format!("Samples: {}", run_data.statistics.sample_count).dimmed()
-    );
+    if let Some(line) = commit_line(&run_data) {
+        println!("{}", line.dimmed());
+    }
+    println!("{}", samples_line(&run_data).dimmed());
+    print_timer_overhead(run_data.timer_overhead_ns);
     println!();
 
     print_statistics(&run_data.statistics);
+    print_perf_counters(&run_data.perf_samples);
 
     // Print CPU analysis if available
     if !run_data.cpu_samples.is_empty() {
         println!();
-        print_cpu_analysis(&run_data.cpu_samples);
+        print_cpu_analysis(&run_data, coverage);
     }
 
     println!();
-    print_outlier_analysis(&run_data.samples, &run_data.statistics);
+    print_outlier_analysis(
+        &run_data.samples,
+        &run_data.statistics,
+        &run_data.involuntary_switches,
+    );
+    print_histogram(&run_data, histogram_bins);
+    if periodicity {
+        print_periodicity(&run_data.samples);
+    }
+    print_extremes(&run_data, extremes);
+    print_pathological_samples(&run_data);
 
     Ok(())
 }
@@ -96,29 +216,40 @@ fn analyze_single_run(
 /// Analyze the latest run and show historical comparison
 fn analyze_latest_with_history(
     baseline_manager: &BaselineManager,
-    crate_name: &str,
-    bench_name: &str,
+    found: &FoundBenchmark,
+    config: &ComparisonConfig,
+    histogram_bins: Option<usize>,
+    periodicity: bool,
+    coverage: CoverageRule,
 ) -> Result<()> {
+    let (crate_name, bench_name) = (found.crate_name.as_str(), found.bench_name.as_str());
     let latest = baseline_manager
         .load_baseline(crate_name, bench_name)?
         .context("No baseline found for this benchmark")?;
 
-    println!(
-        "{}",
-        format!("Benchmark: {}::{}", crate_name, bench_name)
-            .cyan()
-            .bold()
-    );
+    println!("{}", format!("Benchmark: {}", found.title()).cyan().bold());
     println!("{}", format!("Latest Run: {}", latest.timestamp).dimmed());
-    println!(
-        "{}",
-        format!("Samples: {}", latest.statistics.sample_count).dimmed()
-    );
+    if let Some(line) = commit_line(&latest) {
+        println!("{}", line.dimmed());
+    }
+    println!("{}", samples_line(&latest).dimmed());
+    print_timer_overhead(latest.timer_overhead_ns);
     println!();
 
     print_statistics(&latest.statistics);
+    print_perf_counters(&latest.perf_samples);
     println!();
-    print_outlier_analysis(&latest.samples, &latest.statistics);
+    print_outlier_analysis(
+        &latest.samples,
+        &latest.statistics,
+        &latest.involuntary_switches,
+    );
+    print_histogram(&latest, histogram_bins);
+    if periodicity {
+        print_periodicity(&latest.samples);
+    }
+    print_slowest_samples(&latest);
+    print_pathological_samples(&latest);
     println!();
 
     // Show historical comparison
@@ -136,46 +267,248 @@ fn analyze_latest_with_history(
             crate_name,
             bench_name,
             &runs[runs.len().saturating_sub(n)..],
+            &[],
+            coverage,
+        )?;
+        print_trend(
+            baseline_manager,
+            crate_name,
+            bench_name,
+            &runs[runs.len().saturating_sub(config.window_size + 1)..],
+            config,
         )?;
     }
 
+    // Show side-by-side view when other machines share this .benches tree
+    print_machine_comparison(baseline_manager, crate_name, bench_name, config)?;
+
     Ok(())
 }
 
-/// Analyze and compare multiple runs
-fn analyze_multiple_runs(
+/// Print the latest mean and verdict of every machine with history for this benchmark
+fn print_machine_comparison(
     baseline_manager: &BaselineManager,
     crate_name: &str,
     bench_name: &str,
+    config: &ComparisonConfig,
+) -> Result<()> {
+    let summaries = summarize_machines(baseline_manager, crate_name, bench_name, config)?;
+    if summaries.len() < 2 {
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "{}",
+        format!("Per-Machine Comparison ({} machines):", summaries.len())
+            .green()
+            .bold()
+    );
+    println!(
+        "{:<18} {:>6} {:<22} {:>12} {:>10}",
+        "Machine".bold(),
+        "Runs".bold(),
+        "Latest Run".bold(),
+        "Mean".bold(),
+        "Verdict".bold()
+    );
+    println!("{}", "─".repeat(72).dimmed());
+
+    for summary in &summaries {
+        let is_local = summary.machine_id == baseline_manager.machine_id();
+        let machine_label = if is_local {
+            format!("{} (this)", summary.machine_id)
+        } else {
+            summary.machine_id.clone()
+        };
+
+        let verdict = match summary.comparison.comparison {
+            Some(ref c) if summary.comparison.is_regression => {
+                format!("REGRESS {}", c.format_change()).red().bold()
+            }
+            Some(ref c) if c.percentage_change < -5.0 => {
+                format!("IMPROVE {}", c.format_change()).green()
+            }
+            Some(ref c) => format!("STABLE {}", c.format_change()).cyan(),
+            None => "NEW".blue(),
+        };
+
+        println!(
+            "{:<18} {:>6} {:<22} {:>12} {:>10}",
+            machine_label,
+            summary.run_count,
+            summary
+                .latest
+                .timestamp
+                .chars()
+                .take(19)
+                .collect::<String>(),
+            format_ns(summary.latest.statistics.mean),
+            verdict
+        );
+    }
+
+    if machines_disagree(&summaries) {
+        println!();
+        println!(
+            "  {} {}",
+            "⚠".yellow(),
+            "Machines disagree about a regression - likely a microarchitecture-specific change"
+                .yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// Analyze and compare multiple runs
+fn analyze_multiple_runs(
+    baseline_manager: &BaselineManager,
+    found: &FoundBenchmark,
     n: usize,
+    config: &ComparisonConfig,
+    coverage: CoverageRule,
 ) -> Result<()> {
+    let (crate_name, bench_name) = (found.crate_name.as_str(), found.bench_name.as_str());
     let runs = baseline_manager.list_runs(crate_name, bench_name)?;
 
     if runs.is_empty() {
-        anyhow::bail!("No runs found for benchmark {}::{}", crate_name, bench_name);
+        anyhow::bail!("No runs found for benchmark {}", found.title());
     }
 
     let runs_to_analyze = &runs[runs.len().saturating_sub(n)..];
 
-    println!(
-        "{}",
-        format!("Benchmark: {}::{}", crate_name, bench_name)
-            .cyan()
-            .bold()
-    );
+    println!("{}", format!("Benchmark: {}", found.title()).cyan().bold());
     println!(
         "{}",
         format!("Comparing last {} runs:", runs_to_analyze.len()).dimmed()
     );
     println!();
 
-    print_historical_table(baseline_manager, crate_name, bench_name, runs_to_analyze)?;
+    let change_points = history_change_points(
+        baseline_manager,
+        crate_name,
+        bench_name,
+        runs_to_analyze,
+        config,
+    )?;
+    print_historical_table(
+        baseline_manager,
+        crate_name,
+        bench_name,
+        runs_to_analyze,
+        &change_points,
+        coverage,
+    )?;
 
     Ok(())
 }
 
+/// Level shifts in the means of the runs at `timestamps`, each `index` the
+/// position in `timestamps` of the first run after the shift
+fn history_change_points(
+    baseline_manager: &BaselineManager,
+    crate_name: &str,
+    bench_name: &str,
+    timestamps: &[String],
+    config: &ComparisonConfig,
+) -> Result<Vec<ChangePoint>> {
+    let mut positions = Vec::with_capacity(timestamps.len());
+    let mut means = Vec::with_capacity(timestamps.len());
+    let mut std_errors = Vec::with_capacity(timestamps.len());
+    for (position, timestamp) in timestamps.iter().enumerate() {
+        if let Some(run_data) = baseline_manager.load_run(crate_name, bench_name, timestamp)? {
+            let stats = &run_data.statistics;
+            positions.push(position);
+            means.push(stats.mean as f64);
+            std_errors.push((stats.variance / stats.sample_count.max(1) as f64).sqrt());
+        }
+    }
+
+    // A run's mean is known no better than its standard error
+    let noise_floor = median(&std_errors);
+    Ok(
+        find_change_points(&means, noise_floor, config.hazard_rate, config.cp_threshold)
+            .into_iter()
+            .map(|change_point| ChangePoint {
+                index: positions[change_point.index],
+                ..change_point
+            })
+            .collect(),
+    )
+}
+
+/// "── change point detected here (p=0.94): 100.00 μs -> 120.00 μs (+20.0%) ──"
+fn format_change_point_row(change_point: &ChangePoint) -> String {
+    let change_pct =
+        (change_point.mean_after - change_point.mean_before) / change_point.mean_before * 100.0;
+    format!(
+        "── change point detected here (p={:.2}): {} -> {} ({:+.1}%) ──",
+        change_point.probability,
+        format_ns(change_point.mean_before as u128),
+        format_ns(change_point.mean_after as u128),
+        change_pct
+    )
+}
+
+/// "Commit: 3f2a9c1 (main, uncommitted changes)"; None for a run measured
+/// outside a git repository
+pub(crate) fn commit_line(run_data: &BaselineData) -> Option<String> {
+    let git = run_data.git.as_ref()?;
+    let mut notes: Vec<&str> = git.branch.iter().map(String::as_str).collect();
+    if git.dirty {
+        notes.push("uncommitted changes");
+    }
+    Some(if notes.is_empty() {
+        format!("Commit: {}", git.short_commit())
+    } else {
+        format!("Commit: {} ({})", git.short_commit(), notes.join(", "))
+    })
+}
+
+/// "Samples: N", noting leading samples dropped by `discard_samples`
+pub(crate) fn samples_line(run_data: &BaselineData) -> String {
+    let mut notes = Vec::new();
+    if run_data.discarded_samples > 0 {
+        notes.push(format!("first {} discarded", run_data.discarded_samples));
+    }
+    if run_data.measures_drop {
+        notes.push("drop only".to_string());
+    }
+    if run_data.reservoir_sampled {
+        notes.push(format!(
+            "{} kept as a reservoir; percentiles estimated",
+            run_data.samples.len()
+        ));
+    }
+
+    if notes.is_empty() {
+        format!("Samples: {}", run_data.statistics.sample_count)
+    } else {
+        format!(
+            "Samples: {} ({})",
+            run_data.statistics.sample_count,
+            notes.join(", ")
+        )
+    }
+}
+
+/// Print the calibrated timer overhead that was subtracted from each sample
+fn print_timer_overhead(timer_overhead_ns: Option<u64>) {
+    if let Some(ns) = timer_overhead_ns {
+        println!(
+            "{}",
+            format!(
+                "Timer overhead: {} (subtracted from each sample)",
+                format_ns(ns as u128)
+            )
+            .dimmed()
+        );
+    }
+}
+
 /// Print summary statistics in a formatted table
-fn print_statistics(stats: &Statistics) {
+pub(crate) fn print_statistics(stats: &Statistics) {
     println!("{}", "Summary Statistics".cyan().bold());
     println!("{}", "─".repeat(50).dimmed());
 
@@ -186,9 +519,14 @@ fn print_statistics(stats: &Statistics) {
     };
 
     println!("  {}  {}", "Mean:".cyan(), format_ns(stats.mean));
-    println!("  {}  {}", "Median (p50):".cyan(), format_ns(stats.median));
-    println!("  {}  {}", "p90:".cyan(), format_ns(stats.p90));
-    println!("  {}  {}", "p99:".cyan(), format_ns(stats.p99));
+    for (p, ns) in stats.percentile_values() {
+        let label = if p == 50.0 {
+            "Median (p50):".to_string()
+        } else {
+            format!("{}:", percentile_label(p))
+        };
+        println!("  {}  {}", label.cyan(), format_ns(ns));
+    }
     println!();
     println!(
         "  {}  {} ({:.1}%)",
@@ -201,6 +539,17 @@ fn print_statistics(stats: &Statistics) {
         "Variance:".cyan(),
         format_ns_squared(stats.variance)
     );
+    println!(
+        "  {}  {} (robust CV {:.1}%)",
+        "MAD:".cyan(),
+        format_ns(stats.mad.round() as u128),
+        stats.robust_cv * 100.0
+    );
+    println!(
+        "  {}  {}",
+        "IQR:".cyan(),
+        format_ns(stats.iqr.round() as u128)
+    );
     println!(
         "  {}  {} - {}",
         "Range:".cyan(),
@@ -210,8 +559,167 @@ fn print_statistics(stats: &Statistics) {
     println!("{}", "─".repeat(50).dimmed());
 }
 
+/// Print mean hardware counters, IPC and cache miss rate, if they were recorded
+fn print_perf_counters(perf_samples: &[PerfSample]) {
+    let Some(summary) = PerfSummary::from_samples(perf_samples) else {
+        return;
+    };
+
+    println!();
+    println!("{}", "Hardware Counters (per sample)".cyan().bold());
+    println!("{}", "─".repeat(50).dimmed());
+    println!(
+        "  {}  {}",
+        "Instructions:".cyan(),
+        format_count(summary.mean_instructions)
+    );
+    println!(
+        "  {}  {}",
+        "Cycles:".cyan(),
+        format_count(summary.mean_cycles)
+    );
+    if let Some(ipc) = summary.ipc() {
+        println!("  {}  {:.2}", "IPC:".cyan(), ipc);
+    }
+    println!(
+        "  {}  {}",
+        "LLC misses:".cyan(),
+        format_count(summary.mean_cache_misses)
+    );
+    if let Some(mpki) = summary.misses_per_kilo_instruction() {
+        println!("  {}  {:.2} per 1k instructions", "Miss rate:".cyan(), mpki);
+    }
+    println!("{}", "─".repeat(50).dimmed());
+}
+
 /// Print outlier analysis in a formatted table
-fn print_outlier_analysis(samples: &[u128], stats: &Statistics) {
+///
+/// `switches` holds the involuntary context switches of each sample; when it
+/// lines up with `samples`, flagged samples that were switched out are marked.
+/// Print the run's samples as a bar per log-scaled bin, rebinned into `bins`
+/// when given and falling back to the stored histogram otherwise
+fn print_histogram(run_data: &BaselineData, bins: Option<usize>) {
+    const BAR_WIDTH: usize = 40;
+
+    let hist = match bins {
+        Some(bins) => histogram(&run_data.samples, bins),
+        None => run_data
+            .histogram
+            .clone()
+            .or_else(|| histogram(&run_data.samples, DEFAULT_HISTOGRAM_BINS)),
+    };
+    let Some(hist) = hist else {
+        return;
+    };
+
+    println!();
+    println!("{}", "Histogram".cyan().bold());
+    println!("{}", "─".repeat(50).dimmed());
+
+    let total: usize = hist.counts.iter().sum();
+    if hist.counts.len() == 1 {
+        let noun = if total == 1 { "sample" } else { "samples" };
+        println!(
+            "  all {} {} at {}",
+            total,
+            noun,
+            format_ns(hist.edges[0] as u128)
+        );
+        return;
+    }
+
+    let tallest = hist.counts.iter().copied().max().unwrap_or(0).max(1);
+    for (i, &count) in hist.counts.iter().enumerate() {
+        // Any occupied bin gets at least one block so sparse modes stay visible
+        let width = if count == 0 {
+            0
+        } else {
+            (count * BAR_WIDTH + tallest - 1) / tallest
+        };
+        println!(
+            "  {:>10} – {:<10} {:<width$} {:>6} ({:>4.1}%)",
+            format_ns(hist.edges[i] as u128),
+            format_ns(hist.edges[i + 1] as u128),
+            "█".repeat(width).green(),
+            count,
+            count as f64 / total as f64 * 100.0,
+            width = BAR_WIDTH
+        );
+    }
+}
+
+/// Print the samples' autocorrelation as a bar per range of lags (the
+/// strongest lag of each range) and the dominant period, if any
+fn print_periodicity(samples: &[u128]) {
+    const BAR_WIDTH: usize = 40;
+    const ROWS: usize = 24;
+
+    println!();
+    println!("{}", "Periodicity".cyan().bold());
+    println!("{}", "─".repeat(50).dimmed());
+
+    let series: Vec<f64> = samples.iter().map(|&s| s as f64).collect();
+    let acf = autocorrelogram(&series, (series.len() / 3).min(MAX_PERIOD));
+    if acf.is_empty() {
+        println!("  too few distinct samples for an autocorrelation");
+        return;
+    }
+
+    let found = detect_periodicity(samples);
+    let lags_per_row = (acf.len() + ROWS - 1) / ROWS;
+    for (row, chunk) in acf.chunks(lags_per_row).enumerate() {
+        let first_lag = row * lags_per_row + 1;
+        let (offset, &r) = chunk
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .expect("chunks are never empty");
+        let lags = if chunk.len() == 1 {
+            first_lag.to_string()
+        } else {
+            format!("{}-{}", first_lag, first_lag + chunk.len() - 1)
+        };
+        let bar = "█".repeat((r.max(0.0) * BAR_WIDTH as f64).round() as usize);
+        let marker = match found {
+            Some(p) if (first_lag..first_lag + chunk.len()).contains(&p.period) => {
+                format!("◀ lag {}", p.period)
+            }
+            _ => String::new(),
+        };
+        let bar = if r >= PERIODICITY_THRESHOLD {
+            bar.yellow()
+        } else {
+            bar.green()
+        };
+        println!(
+            "  lag {:>11} {:<width$} {:>6.2} (lag {}) {}",
+            lags,
+            bar,
+            r,
+            first_lag + offset,
+            marker.yellow(),
+            width = BAR_WIDTH
+        );
+    }
+
+    println!();
+    match found {
+        Some(p) => println!(
+            "  {} (autocorrelation {:.2}); the distribution is structured rather than noisy",
+            p.format().yellow(),
+            p.strength
+        ),
+        None => println!("  No repeating pattern found"),
+    }
+}
+
+fn print_outlier_analysis(samples: &[u128], stats: &Statistics, switches: &[u64]) {
+    let switches_of = |idx: usize| {
+        (switches.len() == samples.len())
+            .then(|| switches[idx])
+            .filter(|&n| n > 0)
+    };
+
     println!("{}", "Outlier Analysis".cyan().bold());
     println!("{}", "─".repeat(50).dimmed());
 
@@ -252,6 +760,18 @@ fn print_outlier_analysis(samples: &[u128], stats: &Statistics) {
         iqr_outliers.len(),
         (iqr_outliers.len() as f64 / samples.len() as f64) * 100.0
     );
+    if switches.len() == samples.len() && !iqr_outliers.is_empty() {
+        let preempted = iqr_outliers
+            .iter()
+            .filter(|(idx, _)| switches_of(*idx).is_some())
+            .count();
+        println!(
+            "    {}  {} of {} outliers coincided with a context switch",
+            "Preempted:".dimmed(),
+            preempted,
+            iqr_outliers.len()
+        );
+    }
     println!();
 
     // Z-score method
@@ -289,7 +809,11 @@ fn print_outlier_analysis(samples: &[u128], stats: &Statistics) {
             } else {
                 0.0
             };
-            println!("    #{}: {} ({:+.1}%)", idx, format_ns(*sample), diff_pct);
+            let mut line = format!("    #{}: {} ({:+.1}%)", idx, format_ns(*sample), diff_pct);
+            if let Some(n) = switches_of(*idx) {
+                line.push_str(&format!(" — preempted ({} context switches)", n));
+            }
+            println!("{}", line);
         }
         if iqr_outliers.len() > 5 {
             println!("    {} more outliers...", iqr_outliers.len() - 5);
@@ -299,47 +823,228 @@ fn print_outlier_analysis(samples: &[u128], stats: &Statistics) {
     println!("{}", "─".repeat(50).dimmed());
 }
 
+/// Indices of a run's most extreme samples
+#[derive(Debug, PartialEq)]
+struct Extremes {
+    /// Slowest first
+    slowest: Vec<usize>,
+    /// Fastest first, none of them also among `slowest`
+    fastest: Vec<usize>,
+}
+
+/// The `n` slowest and `n` fastest of `samples`; equal samples go to the
+/// earlier one. With fewer than `2n` samples the slowest take precedence.
+fn extreme_samples(samples: &[u128], n: usize) -> Extremes {
+    let mut order: Vec<usize> = (0..samples.len()).collect();
+    order.sort_by(|&a, &b| samples[b].cmp(&samples[a]).then(a.cmp(&b)));
+    let slowest: Vec<usize> = order.iter().copied().take(n).collect();
+
+    let mut fastest: Vec<usize> = order.split_off(slowest.len());
+    fastest.sort_by(|&a, &b| samples[a].cmp(&samples[b]).then(a.cmp(&b)));
+    fastest.truncate(n);
+
+    Extremes { slowest, fastest }
+}
+
+/// "+12.3% vs median"
+fn format_off_median(sample: u128, median: u128) -> String {
+    if median == 0 {
+        return String::new();
+    }
+    let pct = (sample as f64 - median as f64) / median as f64 * 100.0;
+    format!("{:+.1}% vs median", pct)
+}
+
+/// ", 3400 MHz, 65.0°C" for the CPU snapshot closest to `offset_ms`
+fn format_snapshot_near(run_data: &BaselineData, offset_ms: u64) -> String {
+    let Some(snapshot) = snapshot_at(&run_data.cpu_samples, offset_ms) else {
+        return String::new();
+    };
+    let mut text = String::new();
+    if let Some(mhz) = snapshot.frequency_mhz() {
+        text.push_str(&format!(", {:.0} MHz", mhz));
+    }
+    if let Some(celsius) = snapshot.temperature_celsius() {
+        text.push_str(&format!(", {:.1}°C", celsius));
+    }
+    text
+}
+
+/// Print the `n` slowest and fastest samples with how far each is from the
+/// median and, where the run stored offsets, when it ran and the CPU state then
+fn print_extremes(run_data: &BaselineData, n: usize) {
+    let samples = &run_data.samples;
+    if samples.is_empty() || n == 0 {
+        return;
+    }
+    let offsets =
+        (run_data.sample_offsets_ms.len() == samples.len()).then_some(&run_data.sample_offsets_ms);
+    let extremes = extreme_samples(samples, n);
+
+    println!();
+    println!("{}", "Extremes".cyan().bold());
+    println!("{}", "─".repeat(50).dimmed());
+    if run_data.reservoir_sampled {
+        println!("{}", "  (indices into the stored reservoir)".dimmed());
+    }
+    for (label, indices) in [
+        ("Slowest", &extremes.slowest),
+        ("Fastest", &extremes.fastest),
+    ] {
+        if indices.is_empty() {
+            continue;
+        }
+        println!("  {}:", label.yellow());
+        for &idx in indices {
+            let mut line = format!(
+                "    #{}: {} ({})",
+                idx,
+                format_ns(samples[idx]),
+                format_off_median(samples[idx], run_data.statistics.median)
+            );
+            if let Some(offsets) = offsets {
+                line.push_str(&format!(" at t={}ms", offsets[idx]));
+                line.push_str(&format_snapshot_near(run_data, offsets[idx]));
+            }
+            println!("{}", line);
+        }
+    }
+    println!("{}", "─".repeat(50).dimmed());
+}
+
+/// Print when the slowest samples ran, with the CPU state closest to each.
+///
+/// Needs per-sample offsets, which older runs don't store.
+fn print_slowest_samples(run_data: &BaselineData) {
+    let samples = &run_data.samples;
+    let offsets = &run_data.sample_offsets_ms;
+    if samples.is_empty() || offsets.len() != samples.len() {
+        return;
+    }
+
+    let slowest = extreme_samples(samples, SLOWEST_SAMPLES).slowest;
+
+    println!();
+    println!(
+        "{}",
+        format!("Slowest {} Samples", slowest.len()).cyan().bold()
+    );
+    println!("{}", "─".repeat(50).dimmed());
+    for idx in slowest {
+        let mut line = format!(
+            "  #{}: {} at t={}ms",
+            idx,
+            format_ns(samples[idx]),
+            offsets[idx]
+        );
+        if let Some(snapshot) = snapshot_at(&run_data.cpu_samples, offsets[idx]) {
+            if let Some(celsius) = snapshot.temperature_celsius() {
+                line.push_str(&format!(", temp={:.1}°C", celsius));
+            }
+            if let Some(mhz) = snapshot.frequency_mhz() {
+                line.push_str(&format!(", {:.0} MHz", mhz));
+            }
+        }
+        println!("{}", line);
+    }
+    println!("{}", "─".repeat(50).dimmed());
+}
+
+/// Print the samples the watchdog flagged, with the CPU state closest to each
+fn print_pathological_samples(run_data: &BaselineData) {
+    let flagged = &run_data.pathological_samples;
+    if flagged.is_empty() {
+        return;
+    }
+
+    println!();
+    println!(
+        "{}",
+        format!("Pathological Samples ({})", flagged.len())
+            .red()
+            .bold()
+    );
+    println!("{}", "─".repeat(50).dimmed());
+    for sample in flagged {
+        let mut line = format!(
+            "  #{}: {}",
+            sample.index,
+            format_ns(sample.duration_ns as u128)
+        );
+        if let Some(multiple) = sample.median_multiple() {
+            line.push_str(&format!(" ({:.0}× the running median)", multiple));
+        }
+        // Indices count all samples, which a reservoir doesn't line up with
+        let in_series = |len: usize| !run_data.reservoir_sampled && sample.index < len;
+        if in_series(run_data.sample_offsets_ms.len()) {
+            let offset = run_data.sample_offsets_ms[sample.index];
+            line.push_str(&format!(" at t={}ms", offset));
+            if let Some(snapshot) = snapshot_at(&run_data.cpu_samples, offset) {
+                if let Some(mhz) = snapshot.frequency_mhz() {
+                    line.push_str(&format!(", {:.0} MHz", mhz));
+                }
+            }
+        }
+        if in_series(run_data.involuntary_switches.len()) {
+            let switches = run_data.involuntary_switches[sample.index];
+            if switches > 0 {
+                line.push_str(&format!(", {} context switches", switches));
+            }
+        }
+        println!("{}", line);
+    }
+    println!("{}", "─".repeat(50).dimmed());
+}
+
 /// Print historical comparison table
 fn print_historical_table(
     baseline_manager: &BaselineManager,
     crate_name: &str,
     bench_name: &str,
     timestamps: &[String],
+    change_points: &[ChangePoint],
+    coverage: CoverageRule,
 ) -> Result<()> {
     println!(
-        "{:<22} {:>12} {:>12} {:>12} {:>10}",
+        "{:<22} {:<9} {:>12} {:>12} {:>12} {:>10}",
         "Run".bold(),
+        "Commit".bold(),
         "Mean".bold(),
         "Median".bold(),
         "p90".bold(),
-        "Variance".bold()
+        "CV".bold()
     );
-    println!("{}", "─".repeat(72).dimmed());
+    println!("{}", "─".repeat(82).dimmed());
 
-    for timestamp in timestamps {
+    for (position, timestamp) in timestamps.iter().enumerate() {
+        for change_point in change_points.iter().filter(|c| c.index == position) {
+            println!("{}", format_change_point_row(change_point).yellow().bold());
+        }
         if let Some(run_data) = baseline_manager.load_run(crate_name, bench_name, timestamp)? {
             let stats = &run_data.statistics;
-            let variance_pct = if stats.mean > 0 {
-                (stats.std_dev / stats.mean as f64) * 100.0
-            } else {
-                0.0
-            };
 
             println!(
-                "{:<22} {:>12} {:>12} {:>12} {:>9.1}%",
+                "{:<22} {:<9} {:>12} {:>12} {:>12} {:>9.1}%",
                 timestamp,
+                run_data
+                    .git
+                    .as_ref()
+                    .map_or("-".to_string(), |git| git.label()),
                 format_ns(stats.mean),
                 format_ns(stats.median),
                 format_ns(stats.p90),
-                variance_pct
+                stats.robust_cv * 100.0
             );
 
             // Print CPU info if available
             if !run_data.cpu_samples.is_empty() {
-                let analysis = CpuAnalysis::from_snapshots(&run_data.cpu_samples, None);
+                let analysis = cpu_analysis(&run_data, coverage);
                 if let Some(cpu_stats) = analysis.format_stats_line() {
                     println!("  {}{}", "    ".dimmed(), cpu_stats.dimmed());
                 }
+                if let Some(note) = analysis.insufficient_samples_note() {
+                    println!("  {}{}", "    ".dimmed(), note.dimmed());
+                }
                 // Show warnings if any
                 for warning in &analysis.warnings {
                     println!("  {}{}", "    ".dimmed(), warning.format());
@@ -351,17 +1056,46 @@ fn print_historical_table(
     Ok(())
 }
 
-/// Format nanoseconds in a human-readable way
-fn format_ns(ns: u128) -> String {
-    if ns < 1_000 {
-        format!("{} ns", ns)
-    } else if ns < 1_000_000 {
-        format!("{:.2} µs", ns as f64 / 1_000.0)
-    } else if ns < 1_000_000_000 {
-        format!("{:.2} ms", ns as f64 / 1_000_000.0)
+/// Print the least-squares trend of the runs' means, highlighted when it
+/// drifts past `drift_threshold`
+fn print_trend(
+    baseline_manager: &BaselineManager,
+    crate_name: &str,
+    bench_name: &str,
+    timestamps: &[String],
+    config: &ComparisonConfig,
+) -> Result<()> {
+    let mut means = Vec::with_capacity(timestamps.len());
+    for timestamp in timestamps {
+        if let Some(run_data) = baseline_manager.load_run(crate_name, bench_name, timestamp)? {
+            means.push(run_data.statistics.mean as f64);
+        }
+    }
+    let Some(trend) = linear_trend(&means) else {
+        return Ok(());
+    };
+
+    let line = format!(
+        "Trend: {}, {:+.1}% over the last {} runs",
+        trend.format(),
+        trend.drift_pct(),
+        trend.runs
+    );
+    if config
+        .drift_threshold
+        .is_some_and(|threshold| trend.drift_pct() > threshold)
+    {
+        println!("{}", line.yellow().bold());
     } else {
-        format!("{:.2} s", ns as f64 / 1_000_000_000.0)
+        println!("{}", line.dimmed());
     }
+    Ok(())
+}
+
+/// Format nanoseconds in a human-readable way
+pub(crate) fn format_ns(ns: u128) -> String {
+    let (number, unit) = simplebench_runtime::output::time_format().parts(ns);
+    format!("{} {}", number, unit)
 }
 
 /// Format variance (ns²) in a human-readable way
@@ -369,15 +1103,25 @@ fn format_ns_squared(variance: f64) -> String {
     if variance < 1_000_000.0 {
         format!("{:.0} ns²", variance)
     } else if variance < 1_000_000_000_000.0 {
-        format!("{:.2} µs²", variance / 1_000_000.0)
+        format!("{:.2} μs²", variance / 1_000_000.0)
     } else {
         format!("{:.2} ms²", variance / 1_000_000_000_000.0)
     }
 }
 
-/// Print CPU analysis for a set of samples
-fn print_cpu_analysis(cpu_samples: &[simplebench_runtime::CpuSnapshot]) {
-    let analysis = CpuAnalysis::from_snapshots(cpu_samples, None);
+/// Analyze a run's CPU snapshots, and its wall vs CPU time when both were recorded
+pub(crate) fn cpu_analysis(run_data: &BaselineData, coverage: CoverageRule) -> CpuAnalysis {
+    let duration_ms = run_data.sample_offsets_ms.iter().max().copied();
+    let analysis = CpuAnalysis::from_snapshots(&run_data.cpu_samples, None, duration_ms, coverage);
+    match run_data.cpu_time_statistics {
+        Some(ref cpu) => analysis.with_cpu_time(run_data.statistics.mean as f64, cpu.mean as f64),
+        None => analysis,
+    }
+}
+
+/// Print CPU analysis of a stored run
+fn print_cpu_analysis(run_data: &BaselineData, coverage: CoverageRule) {
+    let analysis = cpu_analysis(run_data, coverage);
 
     println!("{}", "CPU Analysis".cyan().bold());
     println!("{}", "─".repeat(50).dimmed());
@@ -408,6 +1152,19 @@ fn print_cpu_analysis(cpu_samples: &[simplebench_runtime::CpuSnapshot]) {
         println!();
     }
 
+    // Too short a run only ever gets its start and end readings
+    if analysis.coverage.coverable() {
+        println!(
+            "  {}  {}",
+            "Coverage:".yellow(),
+            analysis.coverage.format().dimmed()
+        );
+        if let Some(note) = analysis.insufficient_samples_note() {
+            println!("    {}", note.yellow());
+        }
+        println!();
+    }
+
     if !analysis.warnings.is_empty() {
         println!("  {}", "Warnings:".red().bold());
         for warning in &analysis.warnings {
@@ -417,3 +1174,129 @@ fn print_cpu_analysis(cpu_samples: &[simplebench_runtime::CpuSnapshot]) {
 
     println!("{}", "─".repeat(50).dimmed());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_benchmark_in_submodule() {
+        use simplebench_runtime::BenchResult;
+        use std::time::Duration;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        for (module, name) in [
+            ("game_entities::benchmarks", "bench_entity_creation"),
+            ("game_entities::physics::benchmarks", "bench_step"),
+            ("game_math::benchmarks", "bench_step"),
+        ] {
+            let result = BenchResult {
+                name: name.to_string(),
+                module: module.to_string(),
+                samples: 1,
+                all_timings: vec![Duration::from_nanos(100)],
+                ..Default::default()
+            };
+            let (crate_name, _) = storage_name(module, name);
+            manager.save_baseline(crate_name, &result, false).unwrap();
+        }
+
+        // The stored key, the bare name and module-qualified names all resolve,
+        // and the title keeps the crate name whole
+        for query in [
+            "game_entities_benchmarks-bench_entity_creation",
+            "bench_entity_creation",
+            "benchmarks::bench_entity_creation",
+            "game_entities::benchmarks::bench_entity_creation",
+        ] {
+            let found = find_benchmark(&manager, query).unwrap();
+            assert_eq!(
+                found.title(),
+                "game_entities::benchmarks::bench_entity_creation"
+            );
+            assert!(manager
+                .load_baseline(&found.crate_name, &found.bench_name)
+                .unwrap()
+                .is_some());
+        }
+        let found = find_benchmark(&manager, "physics::benchmarks::bench_step").unwrap();
+        assert_eq!(
+            found.title(),
+            "game_entities::physics::benchmarks::bench_step"
+        );
+
+        // A name stored under two modules needs its module or key
+        let ambiguous = find_benchmark(&manager, "bench_step").unwrap_err();
+        assert_eq!(
+            ambiguous.to_string(),
+            "Benchmark name 'bench_step' is ambiguous (game_entities_physics-benchmarks-bench_step, \
+             game_math_benchmarks-bench_step). Use format: <crate_name>_[<module>-]<benchmark_name>"
+        );
+        let missing = find_benchmark(&manager, "bench_missing").unwrap_err();
+        assert_eq!(
+            missing.to_string(),
+            "Benchmark 'bench_missing' not found. Use format: <crate_name>_[<module>-]<benchmark_name>"
+        );
+    }
+
+    #[test]
+    fn test_extreme_samples_selection_and_order() {
+        let samples = [50, 90, 10, 70, 10, 90, 30, 60, 20, 80];
+
+        let extremes = extreme_samples(&samples, 3);
+        // Equal samples keep their order in the run
+        assert_eq!(extremes.slowest, vec![1, 5, 9]);
+        assert_eq!(extremes.fastest, vec![2, 4, 8]);
+
+        assert_eq!(
+            extreme_samples(&samples, 0),
+            Extremes {
+                slowest: vec![],
+                fastest: vec![],
+            }
+        );
+        assert_eq!(
+            extreme_samples(&[], 5),
+            Extremes {
+                slowest: vec![],
+                fastest: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_extreme_samples_never_overlap() {
+        let samples = [30, 10, 40, 20];
+
+        let extremes = extreme_samples(&samples, 3);
+        assert_eq!(extremes.slowest, vec![2, 0, 3]);
+        assert_eq!(extremes.fastest, vec![1]);
+
+        let all = extreme_samples(&samples, 10);
+        assert_eq!(all.slowest, vec![2, 0, 3, 1]);
+        assert!(all.fastest.is_empty());
+    }
+
+    #[test]
+    fn test_format_change_point_row() {
+        let change_point = ChangePoint {
+            index: 3,
+            probability: 0.9412,
+            mean_before: 100_000.0,
+            mean_after: 120_000.0,
+        };
+        assert_eq!(
+            format_change_point_row(&change_point),
+            "── change point detected here (p=0.94): 100.00 μs -> 120.00 μs (+20.0%) ──"
+        );
+    }
+
+    #[test]
+    fn test_format_off_median() {
+        assert_eq!(format_off_median(150, 100), "+50.0% vs median");
+        assert_eq!(format_off_median(96, 100), "-4.0% vs median");
+        assert_eq!(format_off_median(100, 100), "+0.0% vs median");
+        assert_eq!(format_off_median(5, 0), "");
+    }
+}
@@ -0,0 +1,259 @@
+use crate::analyze::{commit_line, find_benchmark, print_statistics, samples_line};
+use crate::output;
+use anyhow::{Context, Result};
+use colored::*;
+use serde::Serialize;
+use simplebench_runtime::baseline::{BaselineData, BaselineManager};
+use simplebench_runtime::config::{BenchmarkConfig, WarmupMode};
+use simplebench_runtime::disk_usage::format_bytes;
+use simplebench_runtime::filter::BenchFilter;
+use simplebench_runtime::output::{name_column_width, truncate_middle};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Width of the list's columns after the benchmark name
+const NAME_RESERVED: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+/// One benchmark's stored runs on this machine
+#[derive(Debug, Clone, Serialize)]
+struct StoredBenchmark {
+    /// `<crate>_<benchmark>` directory name
+    benchmark: String,
+    runs: usize,
+    /// Timestamps of the oldest and newest run
+    first_run: Option<String>,
+    last_run: Option<String>,
+    bytes: u64,
+}
+
+fn open_manager(workspace_root: &Path) -> Result<BaselineManager> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    simplebench_runtime::output::set_time_format(config.output.time_format());
+    Ok(BaselineManager::for_workspace(
+        workspace_root,
+        &config.baseline,
+    )?)
+}
+
+/// List this machine's benchmarks with their run counts, date range and size
+pub fn run_list(workspace_root: &Path, format: OutputFormat, wide: bool) -> Result<()> {
+    let baseline_manager = open_manager(workspace_root)?;
+    let sizes: HashMap<String, u64> = baseline_manager
+        .disk_usage()?
+        .benchmarks
+        .into_iter()
+        .filter(|usage| usage.machine_id == baseline_manager.machine_id())
+        .map(|usage| (usage.benchmark, usage.bytes))
+        .collect();
+
+    let mut stored = Vec::new();
+    for benchmark in baseline_manager.list_benchmarks()? {
+        // Any split at an underscore names the same directory
+        let (crate_name, bench_name) = benchmark.split_once('_').unwrap_or((&benchmark, ""));
+        let runs = baseline_manager.list_runs(crate_name, bench_name)?;
+        stored.push(StoredBenchmark {
+            runs: runs.len(),
+            first_run: runs.first().cloned(),
+            last_run: runs.last().cloned(),
+            bytes: sizes.get(&benchmark).copied().unwrap_or(0),
+            benchmark,
+        });
+    }
+
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&stored)?);
+        return Ok(());
+    }
+    if stored.is_empty() {
+        anyhow::bail!("No stored baselines found for this machine");
+    }
+
+    let width = name_column_width(
+        stored
+            .iter()
+            .map(|bench| bench.benchmark.as_str())
+            .chain(["Benchmark"]),
+        NAME_RESERVED,
+        output::table_width(wide),
+    );
+    println!(
+        "{:<width$} {:>5} {:>10} {:>10} {:>11}",
+        "Benchmark".bold(),
+        "Runs".bold(),
+        "First".bold(),
+        "Last".bold(),
+        "Size".bold()
+    );
+    println!("{}", "─".repeat(width + NAME_RESERVED).dimmed());
+    for bench in &stored {
+        println!(
+            "{:<width$} {:>5} {:>10} {:>10} {:>11}",
+            truncate_middle(&bench.benchmark, width),
+            bench.runs,
+            run_date(bench.first_run.as_deref()),
+            run_date(bench.last_run.as_deref()),
+            format_bytes(bench.bytes)
+        );
+    }
+    println!("{}", "─".repeat(width + NAME_RESERVED).dimmed());
+    println!(
+        "{} runs of {} benchmarks, {}",
+        stored.iter().map(|bench| bench.runs).sum::<usize>(),
+        stored.len(),
+        format_bytes(stored.iter().map(|bench| bench.bytes).sum()).bold()
+    );
+    Ok(())
+}
+
+/// Print a stored run, the latest without `run`
+pub fn run_show(
+    workspace_root: &Path,
+    benchmark: &str,
+    run: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let baseline_manager = open_manager(workspace_root)?;
+    let found = find_benchmark(&baseline_manager, benchmark)?;
+    let (crate_name, bench_name) = (&found.crate_name, &found.bench_name);
+    let run = match run {
+        Some(run) => run,
+        None => baseline_manager
+            .list_runs(crate_name, bench_name)?
+            .pop()
+            .context(format!("No stored runs of '{}'", benchmark))?,
+    };
+    let run_data = baseline_manager
+        .load_run(crate_name, bench_name, &run)?
+        .context(format!("Run '{}' not found", run))?;
+
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&run_data)?);
+    } else {
+        print_run(&found.title(), &run, &run_data);
+    }
+    Ok(())
+}
+
+fn print_run(title: &str, run: &str, run_data: &BaselineData) {
+    println!("{}", format!("Benchmark: {}", title).cyan().bold());
+    println!("{}", format!("Run: {}", run).dimmed());
+    println!("{}", format!("Machine: {}", run_data.machine_id).dimmed());
+    if let Some(line) = commit_line(run_data) {
+        println!("{}", line.dimmed());
+    }
+    println!("{}", samples_line(run_data).dimmed());
+    if let Some(settings) = run_data.settings {
+        let warmup = match settings.warmup_mode {
+            WarmupMode::Fixed => format!("{}ms warmup", settings.warmup_ms),
+            WarmupMode::Adaptive => format!("adaptive warmup ({}ms fallback)", settings.warmup_ms),
+        };
+        println!(
+            "{}",
+            format!(
+                "Settings: {} samples{}, {}",
+                settings.samples,
+                if settings.adaptive { " (adaptive)" } else { "" },
+                warmup
+            )
+            .dimmed()
+        );
+    }
+    if let Some(ref toolchain) = run_data.toolchain {
+        let parts: Vec<String> = [
+            toolchain.rustc_version.clone(),
+            toolchain.target.clone(),
+            toolchain
+                .opt_level
+                .as_ref()
+                .map(|level| format!("opt-level {}", level)),
+            toolchain.cpu_model.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        println!("{}", format!("Toolchain: {}", parts.join(", ")).dimmed());
+    }
+    if run_data.was_regression {
+        println!("{}", "Flagged as a regression".red());
+    } else if run_data.was_improvement {
+        println!("{}", "Flagged as an improvement".green());
+    }
+    println!();
+    print_statistics(&run_data.statistics);
+    println!();
+    println!(
+        "{}",
+        "All stored fields, samples included: --format json".dimmed()
+    );
+}
+
+/// Delete one stored run of a benchmark, or without `run` all of them
+pub fn run_rm(workspace_root: &Path, benchmark: &str, run: Option<String>) -> Result<()> {
+    let baseline_manager = open_manager(workspace_root)?;
+    let found = find_benchmark(&baseline_manager, benchmark)?;
+    let _lock = baseline_manager
+        .lock_writer()
+        .context("Can't remove runs while another session writes baselines")?;
+
+    let removed =
+        baseline_manager.remove_runs(&found.crate_name, &found.bench_name, run.as_deref())?;
+    match (run, removed) {
+        (Some(run), 0) => anyhow::bail!("Run '{}' not found", run),
+        (Some(run), _) => println!("Removed run {} of {}", run, benchmark),
+        (None, removed) => println!("Removed {} runs of {}", removed, benchmark),
+    }
+    Ok(())
+}
+
+/// Accept the latest regression of every matching benchmark as its new level
+pub fn run_accept(workspace_root: &Path, bench_filter: Option<&BenchFilter>) -> Result<()> {
+    let baseline_manager = open_manager(workspace_root)?;
+    let _lock = baseline_manager
+        .lock_writer()
+        .context("Can't accept regressions while another session writes baselines")?;
+
+    let mut accepted = 0;
+    for stored in baseline_manager.stored_benchmarks(bench_filter)? {
+        if let Some(regression) =
+            baseline_manager.accept_regression(&stored.crate_name, &stored.benchmark_name)?
+        {
+            println!(
+                "{} {} ({} {} from {})",
+                "ACCEPT".green().bold(),
+                stored.dir_name,
+                regression.runs,
+                if regression.runs == 1 { "run" } else { "runs" },
+                regression.since
+            );
+            accepted += 1;
+        }
+    }
+
+    if accepted == 0 {
+        println!("No regression-flagged runs to accept");
+    } else {
+        println!();
+        println!(
+            "{}",
+            format!(
+                "{} benchmarks rebaselined; their windows now start at the accepted run",
+                accepted
+            )
+            .dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// "2025-01-15" of a "2025-01-15T10-30-00" run timestamp
+fn run_date(run: Option<&str>) -> &str {
+    run.map_or("-", |run| run.get(..10).unwrap_or(run))
+}
@@ -0,0 +1,215 @@
+use crate::analyze::{commit_line, find_benchmark, format_ns};
+use crate::versus::BOOTSTRAP_RESAMPLES;
+use anyhow::{Context, Result};
+use colored::*;
+use simplebench_runtime::baseline::{detect_regression, BaselineData, BaselineManager};
+use simplebench_runtime::columns::Column;
+use simplebench_runtime::config::BenchmarkConfig;
+use simplebench_runtime::statistics::bootstrap_ratio_ci;
+use std::path::Path;
+
+/// Stored runs listed on each side of a timestamp that doesn't exist
+const NEAREST_RUNS: usize = 2;
+
+/// Benchmark and the two runs to compare
+pub struct DiffOptions {
+    pub benchmark: String,
+    /// Reference run
+    pub run_a: String,
+    /// Run judged against `run_a`
+    pub run_b: String,
+}
+
+/// Compare two stored runs of a benchmark, the second judged against the first
+/// as a session would judge it against a window of one run
+pub fn run_diff(workspace_root: &Path, options: &DiffOptions) -> Result<()> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    let baseline_manager = BaselineManager::for_workspace(workspace_root, &config.baseline)?;
+    simplebench_runtime::output::set_time_format(config.output.time_format());
+    let columns = config
+        .output
+        .columns_or(Column::TERMINAL_DEFAULT)
+        .map_err(|e| anyhow::anyhow!("{} in [output] columns", e))?;
+
+    let found = find_benchmark(&baseline_manager, &options.benchmark)?;
+    let runs = baseline_manager.list_runs(&found.crate_name, &found.bench_name)?;
+    let load = |run: &str| -> Result<BaselineData> {
+        if !runs.iter().any(|stored| stored == run) {
+            anyhow::bail!("{}", missing_run_message(&runs, run));
+        }
+        baseline_manager
+            .load_run(&found.crate_name, &found.bench_name, run)?
+            .context(format!("Run '{}' not found", run))
+    };
+    let mut run_a = load(&options.run_a)?;
+    let run_b = load(&options.run_b)?;
+    // A is the reference whatever it was judged as when it was stored
+    run_a.was_regression = false;
+
+    let result = detect_regression(
+        &run_b.to_bench_result(),
+        std::slice::from_ref(&run_a),
+        &config.comparison,
+    );
+    let comparison = result
+        .comparison
+        .as_ref()
+        .context("The runs could not be compared")?;
+
+    println!("{}", format!("Diff: {}", found.title()).cyan().bold());
+    for (label, run, data) in [("A", &options.run_a, &run_a), ("B", &options.run_b, &run_b)] {
+        let commit = commit_line(data).map_or(String::new(), |line| format!(", {}", line));
+        println!("{}", format!("{}: {}{}", label, run, commit).dimmed());
+    }
+    println!();
+    print_side_by_side(&run_a, &run_b);
+    println!();
+
+    let confidence_level = config.comparison.confidence_level;
+    let samples =
+        |run: &BaselineData| -> Vec<f64> { run.samples.iter().map(|&ns| ns as f64).collect() };
+    if let Some((low, high)) = bootstrap_ratio_ci(
+        &samples(&run_b),
+        &samples(&run_a),
+        BOOTSTRAP_RESAMPLES,
+        confidence_level,
+    ) {
+        println!(
+            "  Time ratio B/A: {:.3} ({:.0}% CI {:.3} – {:.3})",
+            comparison.current_mean.as_nanos() as f64 / comparison.baseline_mean.as_nanos() as f64,
+            confidence_level * 100.0,
+            low,
+            high
+        );
+    }
+    if let Some(p) = comparison.mann_whitney_p {
+        println!("  Mann-Whitney:   p = {:.4}", p);
+    }
+    if let Some(d) = comparison.effect_size {
+        println!("  Effect size:    d = {:.2}", d);
+    }
+    println!();
+
+    crate::output::print_comparison(
+        comparison,
+        &options.benchmark,
+        result.is_regression,
+        &columns,
+    );
+    Ok(())
+}
+
+/// Mean, percentiles, spread and sample count of both runs, with B's change
+fn print_side_by_side(run_a: &BaselineData, run_b: &BaselineData) {
+    let (a, b) = (&run_a.statistics, &run_b.statistics);
+    println!(
+        "  {:<10} {:>12} {:>12} {:>9}",
+        "".bold(),
+        "A".bold(),
+        "B".bold(),
+        "Change".bold()
+    );
+    println!("  {}", "─".repeat(46).dimmed());
+
+    let mut rows = vec![("Mean".to_string(), a.mean, b.mean)];
+    let b_percentiles = b.percentile_values();
+    for (p, a_ns) in a.percentile_values() {
+        if let Some(&(_, b_ns)) = b_percentiles.iter().find(|(q, _)| *q == p) {
+            rows.push((simplebench_runtime::percentile_label(p), a_ns, b_ns));
+        }
+    }
+    rows.push(("Std dev".to_string(), a.std_dev as u128, b.std_dev as u128));
+    for (label, a_ns, b_ns) in rows {
+        println!(
+            "  {:<10} {:>12} {:>12} {:>9}",
+            label,
+            format_ns(a_ns),
+            format_ns(b_ns),
+            format_change(a_ns as f64, b_ns as f64)
+        );
+    }
+    println!(
+        "  {:<10} {:>11.1}% {:>11.1}%",
+        "Robust CV",
+        a.robust_cv * 100.0,
+        b.robust_cv * 100.0
+    );
+    println!(
+        "  {:<10} {:>12} {:>12}",
+        "Samples", a.sample_count, b.sample_count
+    );
+}
+
+/// "+7.2%"; "-" without a reference value
+fn format_change(a: f64, b: f64) -> String {
+    if a > 0.0 {
+        format!("{:+.1}%", (b - a) / a * 100.0)
+    } else {
+        "-".to_string()
+    }
+}
+
+/// Error for a run that isn't stored, listing the stored runs sorting around
+/// `wanted`
+fn missing_run_message(runs: &[String], wanted: &str) -> String {
+    let nearest = nearest_runs(runs, wanted);
+    if nearest.is_empty() {
+        format!(
+            "Run '{}' not found: the benchmark has no stored runs",
+            wanted
+        )
+    } else {
+        format!(
+            "Run '{}' not found. Nearest stored runs: {}",
+            wanted,
+            nearest.join(", ")
+        )
+    }
+}
+
+/// Up to [`NEAREST_RUNS`] stored runs on each side of `wanted`
+///
+/// Run timestamps sort chronologically, so these are the runs closest in time.
+fn nearest_runs<'a>(runs: &'a [String], wanted: &str) -> Vec<&'a str> {
+    let at = runs.partition_point(|run| run.as_str() < wanted);
+    runs[at.saturating_sub(NEAREST_RUNS)..(at + NEAREST_RUNS).min(runs.len())]
+        .iter()
+        .map(String::as_str)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_runs_around_missing_timestamp() {
+        let runs: Vec<String> = (1..=6)
+            .map(|day| format!("2025-01-0{}T10-00-00", day))
+            .collect();
+
+        assert_eq!(
+            nearest_runs(&runs, "2025-01-04T09-00-00"),
+            vec![
+                "2025-01-02T10-00-00",
+                "2025-01-03T10-00-00",
+                "2025-01-04T10-00-00",
+                "2025-01-05T10-00-00"
+            ]
+        );
+        assert_eq!(
+            nearest_runs(&runs, "2024-12-31T00-00-00"),
+            vec!["2025-01-01T10-00-00", "2025-01-02T10-00-00"]
+        );
+        assert_eq!(
+            nearest_runs(&runs, "2026"),
+            vec!["2025-01-05T10-00-00", "2025-01-06T10-00-00"]
+        );
+        assert_eq!(
+            missing_run_message(&[], "2025-01-01T00-00-00"),
+            "Run '2025-01-01T00-00-00' not found: the benchmark has no stored runs"
+        );
+    }
+}
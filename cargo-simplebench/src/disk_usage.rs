@@ -0,0 +1,326 @@
+use crate::output;
+use anyhow::{Context, Result};
+use colored::*;
+use simplebench_runtime::baseline::{storage_name, BaselineManager};
+use simplebench_runtime::config::{BaselineConfig, BenchmarkConfig};
+use simplebench_runtime::disk_usage::{
+    format_bytes, DiskUsage, PruneReport, RetentionPolicy, StaleBaseline, BYTES_PER_MB,
+};
+use simplebench_runtime::output::{name_column_width, truncate_middle};
+use simplebench_runtime::BenchmarkInfo;
+use std::path::Path;
+
+/// Width of the table's columns after the benchmark name
+const NAME_RESERVED: usize = 38;
+
+/// Stale baselines named in the end-of-run footer before it elides the rest
+const STALE_NAMES_SHOWN: usize = 3;
+
+/// Enforce `[baseline] max_disk_mb` and print the end-of-run usage status line
+pub fn report_after_run(workspace_root: &Path, config: &BaselineConfig) -> Result<()> {
+    let baseline_manager = BaselineManager::for_workspace(workspace_root, config)?;
+
+    let pruned = match config.max_disk_mb {
+        Some(max_mb) => Some(
+            baseline_manager
+                .prune_to_size((max_mb * BYTES_PER_MB as f64) as u64, config.min_runs)?,
+        ),
+        None => None,
+    };
+    let usage = baseline_manager.record_disk_usage()?;
+
+    print_status_line(&usage);
+    if let (Some(report), Some(max_mb)) = (pruned, config.max_disk_mb) {
+        print_prune_line(&report, max_mb, config.min_runs, &usage);
+    }
+    Ok(())
+}
+
+/// Print a per-benchmark size breakdown of `.benches`, largest first
+pub fn run_du(workspace_root: &Path, wide: bool) -> Result<()> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    let baseline_manager = BaselineManager::for_workspace(workspace_root, &config.baseline)?;
+    let usage = baseline_manager.disk_usage()?;
+    if usage.benchmarks.is_empty() {
+        anyhow::bail!("No stored baselines found in .benches");
+    }
+
+    println!("{}", "Baseline disk usage".green().bold());
+    println!();
+    let width = name_column_width(
+        usage
+            .benchmarks
+            .iter()
+            .map(|bench| bench.benchmark.as_str())
+            .chain(["Benchmark"]),
+        NAME_RESERVED,
+        output::table_width(wide),
+    );
+    println!(
+        "{:<width$} {:<18} {:>6} {:>11}",
+        "Benchmark".bold(),
+        "Machine".bold(),
+        "Runs".bold(),
+        "Size".bold()
+    );
+    println!("{}", "─".repeat(width + NAME_RESERVED).dimmed());
+
+    for bench in &usage.benchmarks {
+        let machine = if bench.machine_id == baseline_manager.machine_id() {
+            format!("{} (this)", short_id(&bench.machine_id))
+        } else {
+            short_id(&bench.machine_id).to_string()
+        };
+        println!(
+            "{:<width$} {:<18} {:>6} {:>11}",
+            truncate_middle(&bench.benchmark, width),
+            machine,
+            bench.runs,
+            format_bytes(bench.bytes)
+        );
+    }
+
+    println!("{}", "─".repeat(width + NAME_RESERVED).dimmed());
+    println!(
+        "{} runs of {} benchmarks, {}",
+        usage.total_runs(),
+        usage.benchmarks.len(),
+        format_bytes(usage.total_bytes()).bold()
+    );
+    Ok(())
+}
+
+/// Remove the runs of every benchmark beyond `--keep` or `--older-than`
+///
+/// Regression-flagged runs are kept, as by the `[baseline]` retention limits.
+pub fn run_clean(
+    workspace_root: &Path,
+    older_than: Option<f64>,
+    keep: Option<usize>,
+) -> Result<()> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    if !config.baseline.root_dir(workspace_root).is_dir() {
+        println!("No stored baselines found in .benches");
+        return Ok(());
+    }
+    let baseline_manager = BaselineManager::for_workspace(workspace_root, &config.baseline)?;
+    let report = baseline_manager.prune_all(RetentionPolicy {
+        max_runs: keep,
+        max_age_days: older_than,
+    })?;
+    println!(
+        "Removed {} runs ({}) from .benches",
+        report.removed_runs,
+        format_bytes(report.freed_bytes)
+    );
+    Ok(())
+}
+
+/// Print a footer naming this machine's baselines of benchmarks not in
+/// `benchmarks`, after a run of every benchmark of the build
+pub fn report_stale(
+    workspace_root: &Path,
+    config: &BaselineConfig,
+    benchmarks: &[BenchmarkInfo],
+) -> Result<()> {
+    let baseline_manager = BaselineManager::for_workspace(workspace_root, config)?;
+    let stale = baseline_manager.stale_baselines(
+        registered(benchmarks)
+            .iter()
+            .map(|(crate_name, name)| (*crate_name, name.as_str())),
+    )?;
+    if let Some(footer) = stale_footer(&stale) {
+        println!("{}", footer.yellow());
+    }
+    Ok(())
+}
+
+/// Remove this machine's baselines of benchmarks not in `benchmarks`
+pub fn run_clean_stale(workspace_root: &Path, benchmarks: &[BenchmarkInfo]) -> Result<()> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    if !config.baseline.root_dir(workspace_root).is_dir() {
+        println!("No stored baselines found in .benches");
+        return Ok(());
+    }
+    let baseline_manager = BaselineManager::for_workspace(workspace_root, &config.baseline)?;
+    let _lock = baseline_manager
+        .lock_writer()
+        .context("Can't remove baselines while another session writes them")?;
+
+    let stale = baseline_manager.stale_baselines(
+        registered(benchmarks)
+            .iter()
+            .map(|(crate_name, name)| (*crate_name, name.as_str())),
+    )?;
+    if stale.is_empty() {
+        println!("No stale baselines found in .benches");
+        return Ok(());
+    }
+    for baseline in &stale {
+        println!("{} {}", "Removing".dimmed(), baseline.benchmark);
+    }
+    let report = baseline_manager.remove_stale_baselines(&stale)?;
+    println!(
+        "Removed {} stale {} ({} runs, {}) from .benches",
+        stale.len(),
+        if stale.len() == 1 {
+            "baseline"
+        } else {
+            "baselines"
+        },
+        report.removed_runs,
+        format_bytes(report.freed_bytes)
+    );
+    Ok(())
+}
+
+/// `(crate, stored name)` pairs of `benchmarks`, as their runs are stored under
+fn registered(benchmarks: &[BenchmarkInfo]) -> Vec<(&str, String)> {
+    benchmarks
+        .iter()
+        .map(|bench| storage_name(&bench.module, &bench.name))
+        .collect()
+}
+
+/// "3 stale baselines found (c_a, c_b, c_c, …) — run `cargo simplebench clean --stale` to remove"
+fn stale_footer(stale: &[StaleBaseline]) -> Option<String> {
+    if stale.is_empty() {
+        return None;
+    }
+    let mut names: Vec<&str> = stale
+        .iter()
+        .take(STALE_NAMES_SHOWN)
+        .map(|baseline| baseline.benchmark.as_str())
+        .collect();
+    if stale.len() > STALE_NAMES_SHOWN {
+        names.push("…");
+    }
+    Some(format!(
+        "{} stale {} found ({}) — run `cargo simplebench clean --stale` to remove",
+        stale.len(),
+        if stale.len() == 1 {
+            "baseline"
+        } else {
+            "baselines"
+        },
+        names.join(", ")
+    ))
+}
+
+/// Leading part of a machine ID, enough to tell machines apart in a table
+fn short_id(machine_id: &str) -> &str {
+    machine_id.get(..12).unwrap_or(machine_id)
+}
+
+/// "Baselines: 12.3 MiB in .benches (+96.0 KiB since last run, ~1.1 MiB/day)"
+fn status_line(usage: &DiskUsage) -> String {
+    let total = usage.total_bytes();
+    let mut growth = Vec::new();
+    if let Some(previous) = usage.previous_bytes {
+        growth.push(format!(
+            "{} since last run",
+            signed_bytes(total as f64 - previous as f64)
+        ));
+    }
+    if let Some(per_day) = usage.growth_per_day {
+        growth.push(format!("~{}/day", signed_bytes(per_day)));
+    }
+
+    if growth.is_empty() {
+        format!("Baselines: {} in .benches", format_bytes(total))
+    } else {
+        format!(
+            "Baselines: {} in .benches ({})",
+            format_bytes(total),
+            growth.join(", ")
+        )
+    }
+}
+
+fn signed_bytes(bytes: f64) -> String {
+    let sign = if bytes < 0.0 { "-" } else { "+" };
+    format!("{}{}", sign, format_bytes(bytes.abs() as u64))
+}
+
+fn print_status_line(usage: &DiskUsage) {
+    println!("{}", status_line(usage).dimmed());
+}
+
+fn print_prune_line(report: &PruneReport, max_mb: f64, min_runs: usize, usage: &DiskUsage) {
+    if report.removed_runs > 0 {
+        println!(
+            "{}",
+            format!(
+                "Pruned {} oldest runs ({}) to stay under max_disk_mb = {}",
+                report.removed_runs,
+                format_bytes(report.freed_bytes),
+                max_mb
+            )
+            .dimmed()
+        );
+    }
+    if usage.total_bytes() as f64 > max_mb * BYTES_PER_MB as f64 {
+        println!(
+            "{} .benches exceeds max_disk_mb = {} with only the newest {} runs of each benchmark left",
+            "Warning:".yellow().bold(),
+            max_mb,
+            min_runs
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simplebench_runtime::disk_usage::BenchmarkUsage;
+
+    #[test]
+    fn test_status_line_growth() {
+        let mut usage = DiskUsage {
+            benchmarks: vec![BenchmarkUsage {
+                machine_id: "m".into(),
+                benchmark: "c_b".into(),
+                bytes: 3 * BYTES_PER_MB,
+                runs: 10,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(status_line(&usage), "Baselines: 3.0 MiB in .benches");
+
+        usage.previous_bytes = Some(3 * BYTES_PER_MB - 2048);
+        usage.growth_per_day = Some(BYTES_PER_MB as f64);
+        assert_eq!(
+            status_line(&usage),
+            "Baselines: 3.0 MiB in .benches (+2.0 KiB since last run, ~+1.0 MiB/day)"
+        );
+    }
+
+    #[test]
+    fn test_stale_footer_elides_long_lists() {
+        let stale = |names: &[&str]| -> Vec<StaleBaseline> {
+            names
+                .iter()
+                .map(|name| StaleBaseline {
+                    benchmark: name.to_string(),
+                    runs: 1,
+                    bytes: 100,
+                })
+                .collect()
+        };
+        assert_eq!(stale_footer(&[]), None);
+        assert_eq!(
+            stale_footer(&stale(&["c_a"])).unwrap(),
+            "1 stale baseline found (c_a) — run `cargo simplebench clean --stale` to remove"
+        );
+        assert_eq!(
+            stale_footer(&stale(&["c_a", "c_b", "c_c", "c_d"])).unwrap(),
+            "4 stale baselines found (c_a, c_b, c_c, …) — run `cargo simplebench clean --stale` to remove"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use anyhow::{Context, Result};
+use colored::*;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use simplebench_runtime::archive::{ArchiveEntry, ImportOutcome};
+use simplebench_runtime::baseline::BaselineManager;
+use simplebench_runtime::config::BenchmarkConfig;
+use simplebench_runtime::disk_usage::format_bytes;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+pub struct ExportOptions<'a> {
+    pub output: &'a Path,
+    pub bench_filter: Option<&'a str>,
+    pub last: Option<usize>,
+}
+
+pub struct ImportOptions<'a> {
+    pub archive: &'a Path,
+    pub merge: bool,
+    pub remap_machine: bool,
+}
+
+fn open_manager(workspace_root: &Path) -> Result<BaselineManager> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    Ok(BaselineManager::for_workspace(
+        workspace_root,
+        &config.baseline,
+    )?)
+}
+
+/// Write the stored runs of every machine to a `.tar.gz` archive
+pub fn run_export(workspace_root: &Path, options: &ExportOptions) -> Result<()> {
+    let baseline_manager = open_manager(workspace_root)?;
+    let entries = baseline_manager.archive_entries(options.bench_filter, options.last)?;
+    if entries.is_empty() {
+        anyhow::bail!("No stored baselines to export");
+    }
+
+    let file = File::create(options.output)
+        .with_context(|| format!("Failed to create {}", options.output.display()))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    let mut benchmarks = BTreeSet::new();
+    let mut machines = BTreeSet::new();
+    let mut runs = 0;
+    for (entry, path) in &entries {
+        archive
+            .append_path_with_name(path, entry.archive_path())
+            .with_context(|| format!("Failed to add {}", path.display()))?;
+        benchmarks.insert((&entry.machine_id, &entry.benchmark));
+        machines.insert(&entry.machine_id);
+        runs += usize::from(entry.is_run());
+    }
+    archive.into_inner()?.finish()?;
+
+    println!(
+        "Exported {} runs of {} benchmarks ({} {}) to {} ({})",
+        runs,
+        benchmarks.len(),
+        machines.len(),
+        if machines.len() == 1 {
+            "machine"
+        } else {
+            "machines"
+        },
+        options.output.display(),
+        format_bytes(std::fs::metadata(options.output)?.len())
+    );
+    Ok(())
+}
+
+/// Restore the runs of an archive written by [`run_export`]
+///
+/// Every entry is validated before anything is written. Stored runs are never
+/// replaced: an archived run of the same name is skipped.
+pub fn run_import(workspace_root: &Path, options: &ImportOptions) -> Result<()> {
+    let baseline_manager = open_manager(workspace_root)?;
+    let entries = read_archive(options.archive)?;
+    if entries.is_empty() {
+        anyhow::bail!("{} holds no stored runs", options.archive.display());
+    }
+
+    let machines: BTreeSet<&str> = entries
+        .iter()
+        .map(|(entry, _)| entry.machine_id.as_str())
+        .collect();
+    if options.remap_machine && machines.len() > 1 {
+        anyhow::bail!(
+            "{} holds runs of {} machines; --remap-machine needs an archive of one",
+            options.archive.display(),
+            machines.len()
+        );
+    }
+    if !options.merge {
+        let stored = baseline_manager.archive_entries(None, None)?.len();
+        if stored > 0 {
+            anyhow::bail!(
+                "{} already holds {} stored files; pass --merge to add the archive's runs to them",
+                baseline_manager.root_dir().display(),
+                stored
+            );
+        }
+    }
+
+    let _lock = baseline_manager
+        .lock_writer()
+        .context("Can't import while another session writes baselines")?;
+    let mut imported = 0;
+    let mut skipped = 0;
+    for (mut entry, contents) in entries {
+        if options.remap_machine {
+            entry.machine_id = baseline_manager.machine_id().to_string();
+        }
+        match baseline_manager.import_entry(&entry, &contents)? {
+            ImportOutcome::Imported => imported += 1,
+            ImportOutcome::AlreadyStored => skipped += 1,
+        }
+    }
+
+    println!(
+        "Imported {} files from {} into {}",
+        imported,
+        options.archive.display(),
+        baseline_manager.root_dir().display()
+    );
+    if skipped > 0 {
+        println!(
+            "{}",
+            format!("{} already stored and kept", skipped).dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Every entry of a `.tar.gz` archive with its contents, validated
+fn read_archive(path: &Path) -> Result<Vec<(ArchiveEntry, Vec<u8>)>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut entries = Vec::new();
+    for entry in archive
+        .entries()
+        .with_context(|| format!("{} is not a .tar.gz archive", path.display()))?
+    {
+        let mut entry = entry?;
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let parsed = ArchiveEntry::parse(&name)?;
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        parsed.validate(&contents)?;
+        entries.push((parsed, contents));
+    }
+    Ok(entries)
+}
@@ -0,0 +1,451 @@
+//! `[hooks]` commands run on a session's verdicts
+//!
+//! Hooks run once the last benchmark finished and every baseline was saved:
+//! a profiler capture started mid-session would disturb the benchmarks still
+//! being measured, and by then the payload can name the saved run file. A
+//! session that saves nothing (another process holds the writer lock) runs no
+//! hooks.
+
+use crate::SessionResults;
+use anyhow::{bail, Result};
+use colored::*;
+use serde_json::{json, Value};
+use simplebench_runtime::baseline::{storage_name, BaselineManager, ComparisonResult};
+use simplebench_runtime::config::HooksConfig;
+use simplebench_runtime::BenchResult;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How often a running hook is checked for having exited
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    Regression,
+    Improvement,
+    Complete,
+}
+
+impl HookEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::Regression => "regression",
+            HookEvent::Improvement => "improvement",
+            HookEvent::Complete => "complete",
+        }
+    }
+
+    fn command(self, config: &HooksConfig) -> Option<&str> {
+        match self {
+            HookEvent::Regression => config.on_regression.as_deref(),
+            HookEvent::Improvement => config.on_improvement.as_deref(),
+            HookEvent::Complete => config.on_complete.as_deref(),
+        }
+    }
+}
+
+/// A benchmark's verdict a hook is due for
+#[derive(Debug, Clone)]
+pub struct Verdict {
+    pub event: HookEvent,
+    pub benchmark: String,
+    pub crate_name: String,
+    /// Name the benchmark's runs are stored under within its crate
+    pub stored_name: String,
+    pub comparison: ComparisonResult,
+    /// Summary of the measured run (samples and timings in nanoseconds)
+    pub result: Value,
+    /// The run as saved, filled in once the session's saves are on disk
+    pub baseline_path: Option<PathBuf>,
+}
+
+impl Verdict {
+    /// The verdict for `comparison` when `config` has a hook for it
+    pub fn due(
+        config: &HooksConfig,
+        result: &BenchResult,
+        comparison: &ComparisonResult,
+    ) -> Option<Self> {
+        let event = if comparison.is_regression || comparison.is_alloc_regression() {
+            HookEvent::Regression
+        } else if comparison.is_improvement {
+            HookEvent::Improvement
+        } else {
+            return None;
+        };
+        event.command(config)?;
+
+        let nanos = |percentile: f64| result.percentiles.get(percentile).map(|d| d.as_nanos());
+        let (crate_name, stored_name) = storage_name(&result.module, &result.name);
+        Some(Self {
+            event,
+            benchmark: result.name.clone(),
+            crate_name: crate_name.to_string(),
+            stored_name,
+            comparison: comparison.clone(),
+            result: json!({
+                "module": result.module,
+                "group": result.group,
+                "samples": result.samples,
+                "mean_ns": result.percentiles.mean.as_nanos(),
+                "p50_ns": nanos(50.0),
+                "p90_ns": nanos(90.0),
+                "p99_ns": nanos(99.0),
+            }),
+            baseline_path: None,
+        })
+    }
+
+    /// Point at the newest run of the benchmark, the one this session saved
+    pub fn locate_baseline(&mut self, baseline_manager: &BaselineManager) {
+        self.baseline_path = baseline_manager
+            .list_runs(&self.crate_name, &self.stored_name)
+            .ok()
+            .and_then(|runs| runs.last().cloned())
+            .map(|ts| baseline_manager.run_path(&self.crate_name, &self.stored_name, &ts));
+    }
+
+    fn payload(&self) -> Value {
+        json!({
+            "event": self.event.name(),
+            "benchmark": self.benchmark,
+            "crate": self.crate_name,
+            "is_regression": self.comparison.is_regression,
+            "is_improvement": self.comparison.is_improvement,
+            "toolchain_changed": self.comparison.toolchain_changed(),
+            "comparison": self.comparison.comparison,
+            "result": self.result,
+            "baseline_path": self.baseline_path,
+        })
+    }
+
+    fn env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![
+            ("SIMPLEBENCH_HOOK_EVENT", self.event.name().to_string()),
+            ("SIMPLEBENCH_HOOK_BENCHMARK", self.benchmark.clone()),
+            ("SIMPLEBENCH_HOOK_CRATE", self.crate_name.clone()),
+        ];
+        if let Some(ref comparison) = self.comparison.comparison {
+            env.push((
+                "SIMPLEBENCH_HOOK_CHANGE_PERCENT",
+                format!("{:.2}", comparison.percentage_change),
+            ));
+            env.push((
+                "SIMPLEBENCH_HOOK_MEAN_NS",
+                comparison.current_mean.as_nanos().to_string(),
+            ));
+        }
+        if let Some(ref path) = self.baseline_path {
+            env.push(("SIMPLEBENCH_HOOK_BASELINE_PATH", path.display().to_string()));
+        }
+        env
+    }
+}
+
+/// Run the session's hooks in order, then `on_complete`
+///
+/// Returns how many failed or timed out; each is reported as it happens.
+pub fn run_session_hooks(
+    config: &HooksConfig,
+    workspace_root: &Path,
+    session: &SessionResults,
+) -> usize {
+    if config.is_empty() || !session.saved {
+        return 0;
+    }
+    let timeout = Duration::from_secs_f64(config.timeout_secs.max(0.0));
+    let mut failed = 0;
+    let mut run = |event: HookEvent, subject: &str, payload: Value, env: Vec<(&str, String)>| {
+        let Some(command) = event.command(config) else {
+            return;
+        };
+        println!(
+            "{}",
+            format!("Running on_{} hook for {}", event.name(), subject).dimmed()
+        );
+        if let Err(e) = run_hook(command, workspace_root, &payload, &env, timeout) {
+            eprintln!(
+                "{} on_{} hook for {}: {}",
+                "Warning:".yellow().bold(),
+                event.name(),
+                subject,
+                e
+            );
+            failed += 1;
+        }
+    };
+
+    for verdict in &session.verdicts {
+        run(
+            verdict.event,
+            &verdict.benchmark,
+            verdict.payload(),
+            verdict.env(),
+        );
+    }
+
+    let names = |filter: fn(&ComparisonResult) -> bool| -> Vec<&str> {
+        session
+            .comparisons
+            .iter()
+            .filter(|c| filter(c))
+            .map(|c| c.benchmark_name.as_str())
+            .collect()
+    };
+    let regressions = names(|c| c.is_regression || c.is_alloc_regression());
+    let improvements = names(|c| c.is_improvement);
+    let failures: Vec<&str> = session.failures.iter().map(|f| f.name.as_str()).collect();
+    let env = vec![
+        (
+            "SIMPLEBENCH_HOOK_EVENT",
+            HookEvent::Complete.name().to_string(),
+        ),
+        (
+            "SIMPLEBENCH_HOOK_REGRESSIONS",
+            regressions.len().to_string(),
+        ),
+        (
+            "SIMPLEBENCH_HOOK_IMPROVEMENTS",
+            improvements.len().to_string(),
+        ),
+        ("SIMPLEBENCH_HOOK_FAILURES", failures.len().to_string()),
+    ];
+    let payload = json!({
+        "event": HookEvent::Complete.name(),
+        "benchmarks": session.comparisons.len(),
+        "regressions": regressions,
+        "improvements": improvements,
+        "failures": failures,
+        "skipped": session.skipped.len(),
+    });
+    run(HookEvent::Complete, "the session", payload, env);
+
+    failed
+}
+
+/// Run `command` through the shell in `workspace_root` with `payload` on stdin,
+/// killing it after `timeout`
+///
+/// Fails when the hook can't start, exits non-zero or times out.
+fn run_hook(
+    command: &str,
+    workspace_root: &Path,
+    payload: &Value,
+    env: &[(&str, String)],
+    timeout: Duration,
+) -> Result<()> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let mut child = shell
+        .arg(command)
+        .current_dir(workspace_root)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("failed to start '{}': {}", command, e))?;
+
+    // Written from a thread, so a hook that doesn't read its stdin can't
+    // block the timeout; one that exits without reading it closes the pipe
+    let mut stdin = child.stdin.take();
+    let bytes = serde_json::to_vec_pretty(payload)?;
+    let writer = std::thread::spawn(move || {
+        if let Some(ref mut stdin) = stdin {
+            let _ = stdin.write_all(&bytes);
+        }
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            let _ = writer.join();
+            bail!(
+                "timed out after {:.0}s and was killed",
+                timeout.as_secs_f64()
+            );
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+    let _ = writer.join();
+
+    match status.code() {
+        Some(0) => Ok(()),
+        Some(code) => bail!("exited with code {}", code),
+        None => bail!("was terminated by a signal"),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use simplebench_runtime::baseline::WindowOutcome;
+    use simplebench_runtime::BenchFailure;
+
+    /// Write a hook that records its stdin to `<name>.json` and its event
+    /// variables to `<name>.env`, next to itself
+    fn write_recording_hook(dir: &Path, name: &str, exit_code: i32) -> String {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(format!("{}.sh", name));
+        std::fs::write(
+            &path,
+            format!(
+                "#!/bin/sh\ncat > {name}.json\nenv | grep '^SIMPLEBENCH_HOOK_' | sort > {name}.env\nexit {exit_code}\n"
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        format!("./{}.sh", name)
+    }
+
+    fn recorded_payload(dir: &Path, name: &str) -> Value {
+        serde_json::from_str(&std::fs::read_to_string(dir.join(format!("{}.json", name))).unwrap())
+            .unwrap()
+    }
+
+    fn comparison(name: &str, is_regression: bool) -> ComparisonResult {
+        ComparisonResult {
+            benchmark_name: name.to_string(),
+            group: None,
+            comparison: None,
+            is_regression,
+            reference: None,
+            quality_failure: None,
+            is_improvement: false,
+            window: WindowOutcome::Populated,
+        }
+    }
+
+    fn session(saved: bool) -> SessionResults {
+        let mut result = BenchResult {
+            name: "bench_slow".to_string(),
+            module: "game_math::benches".to_string(),
+            ..Default::default()
+        };
+        result.samples = 100;
+        let config = HooksConfig {
+            on_regression: Some("./on_regression.sh".to_string()),
+            ..Default::default()
+        };
+        let regressed = comparison("bench_slow", true);
+        let mut verdict = Verdict::due(&config, &result, &regressed).unwrap();
+        verdict.baseline_path = Some(PathBuf::from(
+            ".benches/m/game_math_benches-bench_slow/run.json",
+        ));
+
+        SessionResults {
+            comparisons: vec![regressed, comparison("bench_fast", false)],
+            failures: vec![BenchFailure {
+                name: "bench_broken".to_string(),
+                message: "panicked".to_string(),
+                ..Default::default()
+            }],
+            skipped: Vec::new(),
+            verdicts: vec![verdict],
+            saved,
+        }
+    }
+
+    #[test]
+    fn test_hooks_receive_payload_and_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = HooksConfig {
+            on_regression: Some(write_recording_hook(dir.path(), "on_regression", 0)),
+            on_complete: Some(write_recording_hook(dir.path(), "on_complete", 0)),
+            ..Default::default()
+        };
+
+        assert_eq!(run_session_hooks(&config, dir.path(), &session(true)), 0);
+
+        let payload = recorded_payload(dir.path(), "on_regression");
+        assert_eq!(payload["event"], "regression");
+        assert_eq!(payload["benchmark"], "bench_slow");
+        assert_eq!(payload["crate"], "game_math");
+        assert_eq!(payload["result"]["samples"], 100);
+        assert_eq!(
+            payload["baseline_path"],
+            ".benches/m/game_math_benches-bench_slow/run.json"
+        );
+        let env = std::fs::read_to_string(dir.path().join("on_regression.env")).unwrap();
+        assert!(env.contains("SIMPLEBENCH_HOOK_BENCHMARK=bench_slow\n"));
+        assert!(env.contains("SIMPLEBENCH_HOOK_EVENT=regression\n"));
+
+        let summary = recorded_payload(dir.path(), "on_complete");
+        assert_eq!(summary["event"], "complete");
+        assert_eq!(summary["benchmarks"], 2);
+        assert_eq!(summary["regressions"], json!(["bench_slow"]));
+        assert_eq!(summary["failures"], json!(["bench_broken"]));
+    }
+
+    #[test]
+    fn test_hooks_skipped_when_nothing_was_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = HooksConfig {
+            on_regression: Some(write_recording_hook(dir.path(), "on_regression", 0)),
+            on_complete: Some(write_recording_hook(dir.path(), "on_complete", 0)),
+            ..Default::default()
+        };
+
+        assert_eq!(run_session_hooks(&config, dir.path(), &session(false)), 0);
+        assert!(!dir.path().join("on_regression.json").exists());
+        assert!(!dir.path().join("on_complete.json").exists());
+    }
+
+    #[test]
+    fn test_failing_and_hanging_hooks_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = HooksConfig {
+            on_regression: Some(write_recording_hook(dir.path(), "on_regression", 3)),
+            on_complete: Some("sleep 10".to_string()),
+            timeout_secs: 0.2,
+            ..Default::default()
+        };
+        assert_eq!(run_session_hooks(&config, dir.path(), &session(true)), 2);
+
+        let payload = json!({});
+        let started = Instant::now();
+        let err = run_hook(
+            "sleep 10",
+            dir.path(),
+            &payload,
+            &[],
+            Duration::from_millis(200),
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let err =
+            run_hook("exit 3", dir.path(), &payload, &[], Duration::from_secs(5)).unwrap_err();
+        assert_eq!(err.to_string(), "exited with code 3");
+    }
+
+    #[test]
+    fn test_verdict_due_only_with_a_configured_hook() {
+        let result = BenchResult::default();
+        let config = HooksConfig {
+            on_improvement: Some("true".to_string()),
+            ..Default::default()
+        };
+        assert!(Verdict::due(&config, &result, &comparison("b", true)).is_none());
+        assert!(Verdict::due(&config, &result, &comparison("b", false)).is_none());
+
+        let mut improved = comparison("b", false);
+        improved.is_improvement = true;
+        let verdict = Verdict::due(&config, &result, &improved).unwrap();
+        assert_eq!(verdict.event, HookEvent::Improvement);
+    }
+}
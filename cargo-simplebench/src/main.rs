@@ -1,39 +1,87 @@
+mod aa;
 mod analyze;
+mod baselines;
 mod compile;
+mod diff;
+mod disk_usage;
+mod export;
+mod hooks;
 mod metadata;
 mod output;
+mod plan;
 mod progress;
+mod remote;
+mod replay;
+mod report;
 mod rlib_selection;
 mod runner_gen;
+mod scheduler;
+mod serve;
+mod source_scan;
+mod suite;
 mod topology;
+mod versus;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use colored::*;
 use simplebench_runtime::{
-    baseline::{BaselineManager, ComparisonResult},
-    config::BenchmarkConfig,
-    BenchResult, BenchmarkInfo,
+    baseline::{storage_name, BaselineManager, BaselineWriter, ComparisonResult, WindowOutcome},
+    columns::Column,
+    config::{format_duration_compact, parse_duration, BenchmarkConfig},
+    debug_assertion_crates,
+    filter::{self, BenchFilter},
+    progress::{ProgressLine, ProgressMessage},
+    BenchFailure, BenchResult, BenchmarkInfo, RunnerError,
 };
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Exit code of a `--strict-quality` run with a benchmark over its CV bar,
+/// distinct from the 1 of a regression
+const QUALITY_FAIL_EXIT_CODE: i32 = 3;
+
+/// Exit code of a run whose hook failed with `[hooks] strict = true`
+const HOOK_FAIL_EXIT_CODE: i32 = 4;
 
 /// Configuration for running benchmarks
 struct RunConfig {
-    bench_filter: Option<String>,
+    bench_filter: Option<BenchFilter>,
+    bench_group: Option<String>,
     samples: Option<usize>,
-    warmup_duration: Option<u64>,
+    warmup_duration: Option<Duration>,
+    max_time: Option<Duration>,
+    cooldown: Option<Duration>,
+    early_verdict: bool,
     threshold: Option<f64>,
     ci: bool,
+    /// Exit with [`QUALITY_FAIL_EXIT_CODE`] when a benchmark fails its CV
+    /// quality bar (`--strict-quality`)
+    strict_quality: bool,
+    /// Run benchmarks whose `min_free_memory` isn't met (`--ignore-preconditions`)
+    ignore_preconditions: bool,
     window: Option<usize>,
     confidence: Option<f64>,
     cp_threshold: Option<f64>,
     hazard_rate: Option<f64>,
+    /// Pool every machine's normalized runs into the window
+    /// (`--compare-across-machines`)
+    compare_across_machines: bool,
     parallel: bool,
     jobs: Option<usize>,
+    /// Rounds to split each benchmark's samples into (`--interleave`)
+    interleave: Option<usize>,
+    numa_node: Option<usize>,
     quiet: bool,
+    columns: Option<Vec<Column>>,
+    serve: Option<u16>,
+    allow_debug: bool,
+    /// Never truncate benchmark names to the terminal width (`--wide`)
+    wide: bool,
 }
 
 /// SimpleBench - Simple microbenchmarking for Rust
@@ -48,23 +96,51 @@ struct Args {
     /// Workspace root directory (default: current directory)
     #[arg(long, global = true)]
     workspace_root: Option<PathBuf>,
+
+    /// Print benchmark names in full instead of fitting them to the terminal
+    #[arg(long, global = true)]
+    wide: bool,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Run benchmarks (default command)
     Run {
-        /// Run only benchmarks matching this name (substring match)
+        /// Run only benchmarks matching this name: a substring, or a glob
+        /// over the whole name when it has `*` or `?`
         #[arg(long)]
         bench: Option<String>,
 
+        /// Run only the benchmark named by --bench exactly
+        #[arg(long, requires = "bench")]
+        exact: bool,
+
+        /// Run only benchmarks in this group (from `#[bench(group = "...")]`)
+        #[arg(long)]
+        bench_group: Option<String>,
+
         /// Number of timing samples per benchmark
         #[arg(long)]
         samples: Option<usize>,
 
-        /// Warmup duration in seconds (default: 3)
+        /// Warmup duration, e.g. "3", "500ms", "1m" (bare numbers are seconds; default: 3s)
+        #[arg(long, value_parser = parse_duration)]
+        warmup_duration: Option<Duration>,
+
+        /// Time budget per benchmark for warmup plus measurement, e.g. "10", "30s";
+        /// sampling stops early once it's used up (after at least 30 samples)
+        #[arg(long, value_parser = parse_duration)]
+        max_time: Option<Duration>,
+
+        /// Longest wait between benchmarks for the CPU to cool below
+        /// `cooldown_max_temp_c` (default: 60°C), e.g. "30", "1m"
+        #[arg(long, value_parser = parse_duration)]
+        cooldown: Option<Duration>,
+
+        /// Stop sampling a benchmark once its verdict against the baseline
+        /// window is statistically certain
         #[arg(long)]
-        warmup_duration: Option<u64>,
+        early_verdict: bool,
 
         /// Regression threshold percentage (default: 5.0)
         #[arg(long)]
@@ -74,6 +150,15 @@ enum Commands {
         #[arg(long)]
         ci: bool,
 
+        /// Exit with code 3 when a benchmark's CV exceeds `[measurement] max_cv_pct`
+        #[arg(long)]
+        strict_quality: bool,
+
+        /// Run benchmarks whose preconditions (`min_free_memory`) aren't met
+        /// instead of skipping them
+        #[arg(long)]
+        ignore_preconditions: bool,
+
         /// Window size for historical comparison (default: 10)
         #[arg(long)]
         window: Option<usize>,
@@ -90,6 +175,11 @@ enum Commands {
         #[arg(long)]
         hazard_rate: Option<f64>,
 
+        /// Compare against the runs of every machine sharing .benches, each
+        /// normalized by its machine's median, instead of this machine's only
+        #[arg(long)]
+        compare_across_machines: bool,
+
         /// Run benchmarks in parallel (one per physical core, excluding core 0)
         #[arg(long)]
         parallel: bool,
@@ -98,27 +188,286 @@ enum Commands {
         #[arg(long, short = 'j')]
         jobs: Option<usize>,
 
+        /// Split each benchmark's samples into ROUNDS runs and cycle through
+        /// all benchmarks once per round, so slow drift (thermals, background
+        /// load) doesn't penalize the benchmarks that run last
+        #[arg(long, value_name = "ROUNDS", conflicts_with_all = ["parallel", "jobs"],
+              value_parser = clap::value_parser!(u32).range(1..))]
+        interleave: Option<u32>,
+
+        /// Schedule benchmarks only on cores of this NUMA node
+        #[arg(long)]
+        numa_node: Option<usize>,
+
         /// Suppress progress bars
         #[arg(long, short = 'q')]
         quiet: bool,
+
+        /// Fields to print per benchmark, e.g. "mean,p99,cv,delta" (overrides
+        /// `[output] columns`)
+        #[arg(long, value_delimiter = ',', value_parser = Column::parse)]
+        columns: Option<Vec<Column>>,
+
+        /// Serve live results and history on 127.0.0.1:<PORT> while running
+        /// (requires the `serve` feature)
+        #[arg(long, value_name = "PORT")]
+        serve: Option<u16>,
+
+        /// Run benchmark crates compiled with debug assertions instead of refusing
+        #[arg(long)]
+        allow_debug: bool,
     },
 
     /// Clean existing benchmark results
-    Clean {},
+    ///
+    /// Without flags, removes the whole .benches directory.
+    Clean {
+        /// Only remove runs older than this many days
+        #[arg(long, value_name = "DAYS", conflicts_with = "stale")]
+        older_than: Option<f64>,
+
+        /// Only remove runs beyond the newest N of each benchmark
+        #[arg(long, value_name = "N", conflicts_with = "stale")]
+        keep: Option<usize>,
+
+        /// Only remove this machine's baselines of benchmarks no longer in
+        /// the build (renamed or deleted)
+        #[arg(long)]
+        stale: bool,
+    },
+
+    /// List, inspect and remove stored baselines
+    #[command(visible_alias = "baselines")]
+    Baseline {
+        #[command(subcommand)]
+        command: BaselineCommand,
+    },
 
     /// Analyze benchmark results
     Analyze {
         /// Benchmark name (e.g., "game_math_vector_add" or "crate_name_bench_name")
-        benchmark_name: String,
+        #[arg(required_unless_present = "suite")]
+        benchmark_name: Option<String>,
 
         /// Analyze a specific run by timestamp (e.g., "2025-01-15T10-30-00")
         #[arg(long)]
         run: Option<String>,
 
-        /// Analyze the last N runs
+        /// Analyze the last N runs (with --suite, of each benchmark; default 10)
         #[arg(long)]
         last: Option<usize>,
+
+        /// Report the trend, CV, flaps and age of every benchmark with stored
+        /// history, most concerning first
+        #[arg(long, conflicts_with_all = ["benchmark_name", "run", "histogram", "periodicity"])]
+        suite: bool,
+
+        /// Print the --suite report as JSON
+        #[arg(long, requires = "suite")]
+        json: bool,
+
+        /// Rebin the sample histogram into this many log-scaled bins
+        /// (default: the 20 stored with the run)
+        #[arg(long, value_name = "BINS",
+              value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        histogram: Option<usize>,
+
+        /// Show the autocorrelation of the samples by lag and any dominant period
+        #[arg(long)]
+        periodicity: bool,
+
+        /// Slowest and fastest samples listed by the Extremes section of `--run`
+        #[arg(long, value_name = "N", default_value_t = 5)]
+        extremes: usize,
     },
+
+    /// Compare two benchmarks sample by sample (Mann-Whitney U and a bootstrap
+    /// CI of their time ratio) and print which one is faster
+    Versus {
+        /// First benchmark (e.g., "crate_name_bench_name")
+        bench_a: String,
+
+        /// Second benchmark
+        bench_b: String,
+
+        /// Run both now, interleaved on one core, instead of using their latest stored runs
+        #[arg(long)]
+        run_fresh: bool,
+    },
+
+    /// Compare two stored runs of a benchmark: statistics side by side and the
+    /// verdict B would get against A
+    Diff {
+        /// Benchmark name (e.g., "crate_name_bench_name")
+        benchmark: String,
+
+        /// Reference run timestamp (e.g., "2025-01-15T10-30-00")
+        run_a: String,
+
+        /// Timestamp of the run judged against it
+        run_b: String,
+    },
+
+    /// Accept the latest regression of each benchmark as its new performance
+    /// level: its flagged runs are cleared and windows start at the first of them
+    Accept {
+        /// Accept only benchmarks whose `<crate>_<bench>` directory matches
+        /// this: a substring, or a glob over the whole name when it has `*` or `?`
+        #[arg(long)]
+        bench: Option<String>,
+    },
+
+    /// Write the stored runs of every machine to one .tar.gz archive
+    Export {
+        /// Archive to write
+        #[arg(long, short = 'o', default_value = "baselines.tar.gz")]
+        output: PathBuf,
+
+        /// Export only benchmarks matching this name (substring match)
+        #[arg(long)]
+        bench: Option<String>,
+
+        /// Export only the newest N runs of each benchmark
+        #[arg(long)]
+        last: Option<usize>,
+    },
+
+    /// Restore stored runs from an archive written by `export`
+    ///
+    /// Runs already stored are never replaced.
+    Import {
+        /// Archive to read
+        archive: PathBuf,
+
+        /// Add the archive's runs to existing baselines instead of requiring
+        /// an empty .benches
+        #[arg(long)]
+        merge: bool,
+
+        /// Import every run as this machine's (the archive must hold one machine)
+        #[arg(long)]
+        remap_machine: bool,
+    },
+
+    /// Re-judge stored runs with different comparison settings, without running benchmarks
+    Replay {
+        /// Report only the last N runs of each benchmark (earlier runs still form the window)
+        #[arg(long)]
+        last: Option<usize>,
+
+        /// Replay only benchmarks whose `<crate>_<bench>` directory matches
+        /// this: a substring, or a glob over the whole name when it has `*` or `?`
+        #[arg(long)]
+        bench: Option<String>,
+
+        /// Regression threshold percentage
+        #[arg(long)]
+        threshold: Option<f64>,
+
+        /// Statistical confidence level
+        #[arg(long)]
+        confidence: Option<f64>,
+
+        /// Window size for historical comparison
+        #[arg(long)]
+        window: Option<usize>,
+
+        /// Change point probability threshold
+        #[arg(long)]
+        cp_threshold: Option<f64>,
+
+        /// Bayesian hazard rate
+        #[arg(long)]
+        hazard_rate: Option<f64>,
+    },
+
+    /// Run each benchmark several times back to back without changing anything
+    /// and report how often the comparison flags it, without saving baselines
+    Aa {
+        /// Runs per benchmark; each run after the first is compared to the earlier ones
+        #[arg(long, default_value_t = 10,
+              value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(2..))]
+        iterations: usize,
+
+        /// Test only benchmarks matching this name: a substring, or a glob
+        /// over the whole name when it has `*` or `?`
+        #[arg(long)]
+        bench: Option<String>,
+    },
+
+    /// Estimate from stored history how likely each benchmark is to detect a
+    /// change of the threshold, without running anything
+    Plan {
+        /// Plan only benchmarks whose `<crate>_<bench>` directory matches
+        /// this: a substring, or a glob over the whole name when it has `*` or `?`
+        #[arg(long)]
+        bench: Option<String>,
+
+        /// Samples per run to plan for (default: from the configuration)
+        #[arg(long)]
+        samples: Option<usize>,
+
+        /// Regression threshold percentage to plan for
+        #[arg(long)]
+        threshold: Option<f64>,
+    },
+
+    /// Render the latest stored results as a markdown or HTML report, or a
+    /// pull request comment digest
+    Report {
+        /// Output format
+        #[arg(long, value_enum, default_value = "markdown")]
+        format: report::ReportFormat,
+
+        /// Write the report to this file instead of stdout
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+
+        /// Include only benchmarks whose `<crate>_<bench>` directory matches
+        /// this: a substring, or a glob over the whole name when it has `*` or `?`
+        #[arg(long)]
+        bench: Option<String>,
+
+        /// Smallest change (percent) listed in the pr-comment digest's table
+        #[arg(long, default_value_t = 1.0)]
+        min_change: f64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BaselineCommand {
+    /// List this machine's benchmarks with their run counts, date range and size
+    List {
+        #[arg(long, value_enum, default_value = "table")]
+        format: baselines::OutputFormat,
+    },
+
+    /// Print a stored run (default: the latest)
+    Show {
+        /// Benchmark name (e.g., "crate_name_bench_name")
+        benchmark: String,
+
+        /// Run timestamp (e.g., "2025-01-15T10-30-00")
+        #[arg(long)]
+        run: Option<String>,
+
+        /// `json` prints every stored field, samples included
+        #[arg(long, value_enum, default_value = "table")]
+        format: baselines::OutputFormat,
+    },
+
+    /// Delete a stored run, or all runs of a benchmark on this machine
+    Rm {
+        /// Benchmark name (e.g., "crate_name_bench_name")
+        benchmark: String,
+
+        /// Delete only the run with this timestamp
+        #[arg(long)]
+        run: Option<String>,
+    },
+
+    /// Show disk usage of .benches per benchmark, largest first
+    Du {},
 }
 
 fn main() -> Result<()> {
@@ -144,67 +493,488 @@ fn main() -> Result<()> {
             benchmark_name,
             run,
             last,
+            histogram,
+            periodicity,
+            extremes,
+            suite,
+            json,
+        }) => {
+            if suite {
+                return suite::run_suite(&workspace_root, last, json, cli_args.wide);
+            }
+            let benchmark_name = benchmark_name.context("A benchmark name is required")?;
+            return analyze::run_analysis(
+                &workspace_root,
+                &benchmark_name,
+                run,
+                last,
+                histogram,
+                periodicity,
+                extremes,
+            );
+        }
+        Some(Commands::Versus {
+            bench_a,
+            bench_b,
+            run_fresh,
+        }) => {
+            return versus::run_versus(
+                &workspace_root,
+                &versus::VersusOptions {
+                    bench_a,
+                    bench_b,
+                    run_fresh,
+                    wide: cli_args.wide,
+                },
+            );
+        }
+        Some(Commands::Diff {
+            benchmark,
+            run_a,
+            run_b,
+        }) => {
+            return diff::run_diff(
+                &workspace_root,
+                &diff::DiffOptions {
+                    benchmark,
+                    run_a,
+                    run_b,
+                },
+            );
+        }
+        Some(Commands::Accept { bench }) => {
+            let filter = bench.map(|pattern| BenchFilter::new(&pattern, false));
+            return baselines::run_accept(&workspace_root, filter.as_ref());
+        }
+        Some(Commands::Export {
+            output,
+            bench,
+            last,
+        }) => {
+            return export::run_export(
+                &workspace_root,
+                &export::ExportOptions {
+                    output: &output,
+                    bench_filter: bench.as_deref(),
+                    last,
+                },
+            );
+        }
+        Some(Commands::Import {
+            archive,
+            merge,
+            remap_machine,
+        }) => {
+            return export::run_import(
+                &workspace_root,
+                &export::ImportOptions {
+                    archive: &archive,
+                    merge,
+                    remap_machine,
+                },
+            );
+        }
+        Some(Commands::Replay {
+            last,
+            bench,
+            threshold,
+            confidence,
+            window,
+            cp_threshold,
+            hazard_rate,
         }) => {
-            return analyze::run_analysis(&workspace_root, &benchmark_name, run, last);
+            return replay::run_replay(
+                &workspace_root,
+                &replay::ReplayOptions {
+                    last,
+                    bench_filter: bench.map(|pattern| BenchFilter::new(&pattern, false)),
+                    threshold,
+                    confidence,
+                    window,
+                    cp_threshold,
+                    hazard_rate,
+                    wide: cli_args.wide,
+                },
+            );
+        }
+        Some(Commands::Aa { iterations, bench }) => {
+            return aa::run_aa(
+                &workspace_root,
+                &aa::AaOptions {
+                    iterations,
+                    bench_filter: bench.map(|pattern| BenchFilter::new(&pattern, false)),
+                    wide: cli_args.wide,
+                },
+            );
+        }
+        Some(Commands::Plan {
+            bench,
+            samples,
+            threshold,
+        }) => {
+            return plan::run_plan(
+                &workspace_root,
+                &plan::PlanOptions {
+                    bench_filter: bench.map(|pattern| BenchFilter::new(&pattern, false)),
+                    samples,
+                    threshold,
+                    wide: cli_args.wide,
+                },
+            );
+        }
+        Some(Commands::Report {
+            format,
+            output,
+            bench,
+            min_change,
+        }) => {
+            return report::run_report(
+                &workspace_root,
+                &report::ReportOptions {
+                    format,
+                    output,
+                    bench_filter: bench.map(|pattern| BenchFilter::new(&pattern, false)),
+                    min_change_pct: min_change,
+                },
+            );
+        }
+        Some(Commands::Baseline { command }) => {
+            return match command {
+                BaselineCommand::List { format } => {
+                    baselines::run_list(&workspace_root, format, cli_args.wide)
+                }
+                BaselineCommand::Show {
+                    benchmark,
+                    run,
+                    format,
+                } => baselines::run_show(&workspace_root, &benchmark, run, format),
+                BaselineCommand::Rm { benchmark, run } => {
+                    baselines::run_rm(&workspace_root, &benchmark, run)
+                }
+                BaselineCommand::Du {} => disk_usage::run_du(&workspace_root, cli_args.wide),
+            };
         }
-        Some(Commands::Clean {}) => {
-            println!("Cleaning .benches directory!");
-            return std::fs::remove_dir_all(workspace_root.join(".benches"))
-                .map_err(anyhow::Error::msg);
+        Some(Commands::Clean { stale: true, .. }) => {
+            let runner_binary = build_runner(&workspace_root)?;
+            let benchmarks = discover_benchmarks(&runner_binary, &workspace_root)?;
+            return disk_usage::run_clean_stale(&workspace_root, &benchmarks);
+        }
+        Some(Commands::Clean {
+            older_than: None,
+            keep: None,
+            ..
+        }) => {
+            let mut config = BenchmarkConfig::from_file(workspace_root.join("simplebench.toml"))
+                .unwrap_or_default();
+            config.apply_env_overrides();
+            let benches_dir = config.baseline.root_dir(&workspace_root);
+            println!("Cleaning {} directory!", benches_dir.display());
+            return std::fs::remove_dir_all(benches_dir).map_err(anyhow::Error::msg);
+        }
+        Some(Commands::Clean {
+            older_than, keep, ..
+        }) => {
+            return disk_usage::run_clean(&workspace_root, older_than, keep);
         }
         Some(Commands::Run {
             bench,
+            exact,
+            bench_group,
             samples,
             warmup_duration,
+            max_time,
+            cooldown,
+            early_verdict,
             threshold,
             ci,
+            strict_quality,
+            ignore_preconditions,
             window,
             confidence,
             cp_threshold,
             hazard_rate,
+            compare_across_machines,
             parallel,
             jobs,
+            interleave,
+            numa_node,
             quiet,
+            columns,
+            serve,
+            allow_debug,
         }) => {
             // Explicit run command
             RunConfig {
-                bench_filter: bench,
+                bench_filter: bench.map(|pattern| BenchFilter::new(&pattern, exact)),
+                bench_group,
                 samples,
                 warmup_duration,
+                max_time,
+                cooldown,
+                early_verdict,
                 threshold,
                 ci,
+                strict_quality,
+                ignore_preconditions,
                 window,
                 confidence,
                 cp_threshold,
                 hazard_rate,
+                compare_across_machines,
                 parallel: parallel || jobs.is_some(),
                 jobs,
+                interleave: interleave.map(|rounds| rounds as usize),
+                numa_node,
                 quiet,
+                columns,
+                serve,
+                allow_debug,
+                wide: cli_args.wide,
             }
         }
         None => {
             // No subcommand - default to running all benchmarks (sequential)
             RunConfig {
                 bench_filter: None,
+                bench_group: None,
                 samples: None,
                 warmup_duration: None,
+                max_time: None,
+                cooldown: None,
+                early_verdict: false,
                 threshold: None,
                 ci: false,
+                strict_quality: false,
+                ignore_preconditions: false,
                 window: None,
                 confidence: None,
                 cp_threshold: None,
                 hazard_rate: None,
+                compare_across_machines: false,
                 parallel: false,
                 jobs: None,
+                interleave: None,
+                numa_node: None,
                 quiet: false,
+                columns: None,
+                serve: None,
+                allow_debug: false,
+                wide: cli_args.wide,
             }
         }
     };
 
+    let runner_binary = build_runner(&workspace_root)?;
+
+    // Step 5: Discover benchmarks via --list
+    println!("{}", "Discovering benchmarks".green().bold());
+    let benchmarks = discover_benchmarks(&runner_binary, &workspace_root)?;
+    if let Some(filter) = &run_config.bench_filter {
+        warn_listing_mismatch(&workspace_root, &benchmarks, filter);
+    }
+
+    let benchmarks = select_benchmarks(
+        benchmarks,
+        run_config.bench_filter.as_ref(),
+        run_config.bench_group.as_deref(),
+    );
+
+    if benchmarks.is_empty() {
+        eprintln!("{}", "error: No benchmarks found!".red().bold());
+        if run_config.bench_filter.is_some() || run_config.bench_group.is_some() {
+            eprintln!(
+                "{}",
+                "       (filter may have excluded all benchmarks)".dimmed()
+            );
+        }
+        std::process::exit(1);
+    }
+
+    println!(
+        "     {} {} benchmarks",
+        "Found".dimmed(),
+        benchmarks.len().to_string().green().bold()
+    );
+
+    // Unoptimized benchmarks would poison every baseline they're compared against
+    let debug_crates = debug_assertion_crates(
+        benchmarks
+            .iter()
+            .map(|b| (b.module.as_str(), b.debug_assertions)),
+    );
+    if !debug_crates.is_empty() {
+        if !run_config.allow_debug {
+            eprintln!(
+                "{} benchmark crates compiled with debug assertions: {}",
+                "error:".red().bold(),
+                debug_crates.join(", ")
+            );
+            eprintln!(
+                "{}",
+                "       Their timings would not reflect optimized code. Pass --allow-debug to run them anyway"
+                    .dimmed()
+            );
+            std::process::exit(1);
+        }
+        println!(
+            "     {} {}",
+            "WARNING:".yellow().bold(),
+            format!(
+                "running benchmarks compiled with debug assertions ({}); timings are not representative",
+                debug_crates.join(", ")
+            )
+            .yellow()
+        );
+    }
+    println!();
+
+    // Load configuration (needed for baseline comparisons)
+    let mut config = BenchmarkConfig::load();
+    if let Some(cooldown) = run_config.cooldown {
+        config.measurement.cooldown_secs = cooldown.as_secs_f64();
+    }
+    if run_config.compare_across_machines {
+        config.comparison.compare_across_machines = true;
+    }
+    if let Some(ref columns) = run_config.columns {
+        config.output.columns = Some(columns.iter().map(|c| c.name().to_string()).collect());
+    }
+    simplebench_runtime::output::set_time_format(config.output.time_format());
+    config
+        .output
+        .columns_or(Column::TERMINAL_DEFAULT)
+        .map_err(|e| anyhow::anyhow!("{} in [output] columns", e))?;
+    // Reject an unusable sample count once here instead of in every runner
+    let mut checked = config.clone();
+    if let Some(samples) = run_config.samples {
+        checked.measurement.samples = samples;
+    }
+    checked
+        .validate()
+        .map_err(|e| anyhow::anyhow!("{} in [measurement]", e))?;
+
+    // Resolve per-benchmark core pins before anything starts running
+    let pins = scheduler::resolve_core_pins(&benchmarks, &config.cores, topology::get_cpu_count())?;
+
+    // Runs other jobs uploaded to `[baseline] remote_url`, before any comparison
+    let remote = remote::RemoteBaselines::open(&config.baseline, &benchmarks);
+    if let Some(ref remote) = remote {
+        remote.pull(&workspace_root, &config.baseline);
+    }
+
+    // Optional live view, served until the run finishes
+    let live = serve::LiveSession::default();
+    let server = match run_config.serve {
+        Some(port) => {
+            let baseline_manager =
+                BaselineManager::for_workspace(&workspace_root, &config.baseline)?;
+            let handle = serve::start(port, baseline_manager, live.clone())?;
+            println!(
+                "     {} http://127.0.0.1:{}",
+                "Serving live results at".dimmed(),
+                port
+            );
+            println!();
+            Some(handle)
+        }
+        None => None,
+    };
+    let live = server.as_ref().map(|_| &live);
+
+    // Step 6: Run benchmarks (results and comparisons printed inline)
+    let session = if run_config.parallel {
+        run_benchmarks_parallel(
+            &runner_binary,
+            &workspace_root,
+            &benchmarks,
+            pins,
+            &run_config,
+            &config,
+            live,
+        )?
+    } else {
+        run_benchmarks_sequential(
+            &runner_binary,
+            &workspace_root,
+            &benchmarks,
+            pins,
+            &run_config,
+            &config,
+            live,
+        )?
+    };
+    if let Some(server) = server {
+        server.stop();
+    }
+
+    // Step 7: Print summary
+    output::print_summary(
+        &session.comparisons,
+        &session.failures,
+        &session.skipped,
+        &config.comparison,
+    );
+
+    // Upload this session's runs while the hooks and disk accounting run
+    let upload =
+        remote.map(|remote| remote.spawn_push(workspace_root.clone(), config.baseline.clone()));
+
+    // Hooks, once nothing is being measured and every baseline is saved
+    let hook_failures = hooks::run_session_hooks(&config.hooks, &workspace_root, &session);
+
+    // Disk usage of .benches, pruned to `[baseline] max_disk_mb` first
+    if let Err(e) = disk_usage::report_after_run(&workspace_root, &config.baseline) {
+        eprintln!("Warning: Could not measure .benches disk usage: {}", e);
+    }
+
+    // Baselines a full run left untouched belong to renamed or deleted benchmarks
+    if run_config.bench_filter.is_none() && run_config.bench_group.is_none() {
+        if let Err(e) = disk_usage::report_stale(&workspace_root, &config.baseline, &benchmarks) {
+            eprintln!("Warning: Could not look for stale baselines: {}", e);
+        }
+    }
+
+    if let Some(upload) = upload {
+        upload.finish();
+    }
+
+    // Exit with error if CI mode and regressions detected
+    if run_config.ci {
+        let regression_count = session
+            .comparisons
+            .iter()
+            .filter(|c| c.is_regression || c.is_alloc_regression())
+            .count();
+        if regression_count > 0 {
+            std::process::exit(1);
+        }
+    }
+
+    if run_config.strict_quality
+        && session
+            .comparisons
+            .iter()
+            .any(|c| c.quality_failure.is_some())
+    {
+        std::process::exit(QUALITY_FAIL_EXIT_CODE);
+    }
+
+    if config.hooks.strict && hook_failures > 0 {
+        std::process::exit(HOOK_FAIL_EXIT_CODE);
+    }
+
+    Ok(())
+}
+
+/// Analyze the workspace, build its benchmark crates and compile the runner
+///
+/// Returns the path of the runner binary. Exits when the workspace has no
+/// benchmark crates.
+fn build_runner(workspace_root: &Path) -> Result<PathBuf> {
     // Step 1: Analyze workspace
     println!("{}", "Analyzing workspace...".green().bold());
     let workspace_info =
-        metadata::analyze_workspace(&workspace_root).context("Failed to analyze workspace")?;
+        metadata::analyze_workspace(workspace_root).context("Failed to analyze workspace")?;
 
     if workspace_info.benchmark_crates.is_empty() {
         eprintln!("{}", "error: No benchmark crates found!".red().bold());
@@ -248,12 +1018,9 @@ fn main() -> Result<()> {
         .map(|c| c.name.clone())
         .collect();
 
-    let rlibs = rlib_selection::build_and_select_rlibs(
-        &workspace_root,
-        &benchmark_crate_names,
-        &target_dir,
-    )
-    .context("Failed to build and select rlibs")?;
+    let rlibs =
+        rlib_selection::build_and_select_rlibs(workspace_root, &benchmark_crate_names, &target_dir)
+            .context("Failed to build and select rlibs")?;
 
     println!(
         "     {} {} rlib files",
@@ -280,9 +1047,12 @@ fn main() -> Result<()> {
 
     // Step 3: Generate runner
     println!("{}", "Generating benchmark runner".green().bold());
+    let info = runner_gen::runner_info(&workspace_info.benchmark_crates, &rlibs)
+        .context("Failed to collect runner build metadata")?;
     let runner_path = runner_gen::write_runner(
         &workspace_info.target_directory,
         &workspace_info.benchmark_crates,
+        &info,
     )
     .context("Failed to write runner")?;
     println!();
@@ -296,72 +1066,7 @@ fn main() -> Result<()> {
     compile::compile_runner(&runner_path, &runner_binary, &rlibs, &deps_dir)
         .context("Failed to compile runner")?;
 
-    // Step 5: Discover benchmarks via --list
-    println!("{}", "Discovering benchmarks".green().bold());
-    let benchmarks = discover_benchmarks(&runner_binary, &workspace_root)?;
-
-    // Apply filter if specified
-    let benchmarks: Vec<BenchmarkInfo> = if let Some(ref filter) = run_config.bench_filter {
-        benchmarks
-            .into_iter()
-            .filter(|b| b.name.contains(filter))
-            .collect()
-    } else {
-        benchmarks
-    };
-
-    if benchmarks.is_empty() {
-        eprintln!("{}", "error: No benchmarks found!".red().bold());
-        if run_config.bench_filter.is_some() {
-            eprintln!(
-                "{}",
-                "       (filter may have excluded all benchmarks)".dimmed()
-            );
-        }
-        std::process::exit(1);
-    }
-
-    println!(
-        "     {} {} benchmarks",
-        "Found".dimmed(),
-        benchmarks.len().to_string().green().bold()
-    );
-    println!();
-
-    // Load configuration (needed for baseline comparisons)
-    let config = BenchmarkConfig::load();
-
-    // Step 6: Run benchmarks (results and comparisons printed inline)
-    let (_results, comparisons) = if run_config.parallel {
-        run_benchmarks_parallel(
-            &runner_binary,
-            &workspace_root,
-            &benchmarks,
-            &run_config,
-            &config,
-        )?
-    } else {
-        run_benchmarks_sequential(
-            &runner_binary,
-            &workspace_root,
-            &benchmarks,
-            &run_config,
-            &config,
-        )?
-    };
-
-    // Step 7: Print summary
-    output::print_summary(&comparisons, &config.comparison);
-
-    // Exit with error if CI mode and regressions detected
-    if run_config.ci {
-        let regression_count = comparisons.iter().filter(|c| c.is_regression).count();
-        if regression_count > 0 {
-            std::process::exit(1);
-        }
-    }
-
-    Ok(())
+    Ok(runner_binary)
 }
 
 /// Discover benchmarks by running the runner with --list
@@ -383,11 +1088,81 @@ fn discover_benchmarks(runner: &Path, workspace_root: &Path) -> Result<Vec<Bench
     let benchmarks: Vec<BenchmarkInfo> =
         serde_json::from_slice(&output.stdout).context("Failed to parse benchmark list JSON")?;
 
+    // Runs are stored and runners addressed by crate and name, so two
+    // benchmarks sharing them would mix histories and run the same code
+    let collisions = simplebench_runtime::storage_collisions(
+        benchmarks
+            .iter()
+            .map(|b| (b.module.as_str(), b.name.as_str())),
+    );
+    if !collisions.is_empty() {
+        let lines: Vec<String> = collisions
+            .iter()
+            .map(|collision| format!("  {}", collision.format()))
+            .collect();
+        anyhow::bail!(
+            "Benchmarks would share a baseline directory; rename one of each:\n{}",
+            lines.join("\n")
+        );
+    }
+
     Ok(benchmarks)
 }
 
+/// Warn when the benchmarks `filter` matches in the sources differ from the
+/// ones the runner lists, so a filter that hits nothing says why
+fn warn_listing_mismatch(
+    workspace_root: &Path,
+    benchmarks: &[BenchmarkInfo],
+    filter: &BenchFilter,
+) {
+    let Ok(workspace_info) = metadata::analyze_workspace(workspace_root) else {
+        return;
+    };
+    let mismatch = source_scan::check_listing(&workspace_info.benchmark_crates, benchmarks, filter);
+    if mismatch.is_empty() {
+        return;
+    }
+
+    let print_names = |names: &std::collections::BTreeMap<String, Vec<String>>| {
+        for (crate_name, names) in names {
+            eprintln!("       {} {}: {}", "•".cyan(), crate_name, names.join(", "));
+        }
+    };
+    if !mismatch.missing.is_empty() {
+        eprintln!(
+            "{} --bench '{}' matches benchmarks in the sources that the runner doesn't list:",
+            "warning:".yellow().bold(),
+            filter
+        );
+        print_names(&mismatch.missing);
+        eprintln!(
+            "{}",
+            "       They aren't compiled in: check for a cfg that's off or a file no `mod` declares"
+                .dimmed()
+        );
+    }
+    if !mismatch.extra.is_empty() {
+        eprintln!(
+            "{} the runner lists benchmarks matching '{}' that aren't in the sources:",
+            "warning:".yellow().bold(),
+            filter
+        );
+        print_names(&mismatch.extra);
+        eprintln!(
+            "{}",
+            "       Unless a macro generates them the build is stale: run `cargo clean` and retry"
+                .dimmed()
+        );
+    }
+}
+
 /// Build environment variables for runner execution
-fn build_runner_env(workspace_root: &Path, run_config: &RunConfig) -> HashMap<String, String> {
+fn build_runner_env(
+    workspace_root: &Path,
+    run_config: &RunConfig,
+    config: &BenchmarkConfig,
+) -> HashMap<String, String> {
     let mut env = HashMap::new();
 
     env.insert(
@@ -410,7 +1185,32 @@ fn build_runner_env(workspace_root: &Path, run_config: &RunConfig) -> HashMap<St
     if let Some(warmup_duration) = run_config.warmup_duration {
         env.insert(
             "SIMPLEBENCH_WARMUP_DURATION".to_string(),
-            warmup_duration.to_string(),
+            format_duration_compact(warmup_duration),
+        );
+    }
+
+    if let Some(max_time) = run_config.max_time {
+        env.insert(
+            "SIMPLEBENCH_MAX_TIME_SECS".to_string(),
+            max_time.as_secs_f64().to_string(),
+        );
+    }
+
+    if let Some(cooldown) = run_config.cooldown {
+        env.insert(
+            "SIMPLEBENCH_COOLDOWN_SECS".to_string(),
+            cooldown.as_secs_f64().to_string(),
+        );
+    }
+
+    if run_config.early_verdict {
+        env.insert("SIMPLEBENCH_EARLY_VERDICT".to_string(), "1".to_string());
+    }
+
+    if run_config.ignore_preconditions {
+        env.insert(
+            "SIMPLEBENCH_IGNORE_PRECONDITIONS".to_string(),
+            "1".to_string(),
         );
     }
 
@@ -436,31 +1236,123 @@ fn build_runner_env(workspace_root: &Path, run_config: &RunConfig) -> HashMap<St
         );
     }
 
+    if run_config.compare_across_machines {
+        env.insert(
+            "SIMPLEBENCH_COMPARE_ACROSS_MACHINES".to_string(),
+            "true".to_string(),
+        );
+    }
+
     if run_config.quiet {
         env.insert("SIMPLEBENCH_QUIET".to_string(), "1".to_string());
     }
 
+    if run_config.allow_debug {
+        env.insert("SIMPLEBENCH_ALLOW_DEBUG".to_string(), "1".to_string());
+    }
+
+    // Resolved here, so the runner stores runs where every subcommand looks
+    if config.baseline.dir.is_some() {
+        env.insert(
+            "SIMPLEBENCH_BASELINE_DIR".to_string(),
+            config
+                .baseline
+                .root_dir(workspace_root)
+                .display()
+                .to_string(),
+        );
+    }
+
+    if let Some(ref machine_id) = config.baseline.machine_id {
+        env.insert("SIMPLEBENCH_MACHINE_ID".to_string(), machine_id.clone());
+    }
+
     env
 }
 
+/// Everything collected while running a session of benchmarks
+struct SessionResults {
+    comparisons: Vec<ComparisonResult>,
+    failures: Vec<BenchFailure>,
+    /// Benchmarks skipped by `skip_if` or an unmet precondition
+    skipped: Vec<BenchResult>,
+    /// Verdicts a `[hooks]` command is configured for
+    verdicts: Vec<hooks::Verdict>,
+    /// The session's baselines were saved (false when comparing only)
+    saved: bool,
+}
+
+/// Usable cores for this run, restricted to one NUMA node with `--numa-node`
+fn usable_cores(run_config: &RunConfig) -> Result<Vec<usize>> {
+    let Some(node) = run_config.numa_node else {
+        return Ok(topology::get_usable_cores());
+    };
+
+    let mut by_node = topology::get_usable_cores_by_node();
+    match by_node.remove(&node) {
+        Some(cores) => Ok(cores),
+        None => anyhow::bail!(
+            "NUMA node {} has no usable cores (nodes: {})",
+            node,
+            by_node
+                .keys()
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
 /// Run benchmarks sequentially (one at a time on core 1)
+///
+/// Pinned benchmarks still run on their own core, one at a time like the rest.
 fn run_benchmarks_sequential(
     runner: &Path,
     workspace_root: &Path,
     benchmarks: &[BenchmarkInfo],
+    pins: HashMap<String, usize>,
     run_config: &RunConfig,
     config: &BenchmarkConfig,
-) -> Result<(Vec<BenchResult>, Vec<ComparisonResult>)> {
-    let cores = vec![1]; // Sequential always uses core 1
+    live: Option<&serve::LiveSession>,
+) -> Result<SessionResults> {
+    // Sequential uses core 1 (unless --numa-node rules it out), or the next
+    // usable core if an override reserved it
+    let usable = usable_cores(run_config)?;
+    let reserved: HashSet<usize> = pins.values().copied().collect();
+    let core = run_config
+        .numa_node
+        .is_none()
+        .then_some(1)
+        .into_iter()
+        .chain(usable)
+        .find(|core| !reserved.contains(core))
+        .unwrap_or(1);
+    let cores = vec![core];
     output::print_run_header(benchmarks.len(), 1, false);
 
+    if let Some(rounds) = run_config.interleave {
+        return run_benchmarks_interleaved(
+            runner,
+            workspace_root,
+            benchmarks,
+            &pins,
+            core,
+            rounds,
+            run_config,
+            config,
+            live,
+        );
+    }
+
+    let scheduler = scheduler::CoreScheduler::new(benchmarks, &cores, pins)?;
     run_benchmarks_with_cores(
         runner,
         workspace_root,
-        benchmarks,
-        &cores,
+        scheduler,
+        1,
         run_config,
         config,
+        live,
     )
 }
 
@@ -469,16 +1361,18 @@ fn run_benchmarks_parallel(
     runner: &Path,
     workspace_root: &Path,
     benchmarks: &[BenchmarkInfo],
+    pins: HashMap<String, usize>,
     run_config: &RunConfig,
     config: &BenchmarkConfig,
-) -> Result<(Vec<BenchResult>, Vec<ComparisonResult>)> {
+    live: Option<&serve::LiveSession>,
+) -> Result<SessionResults> {
     let mut cores = if let Some(jobs) = run_config.jobs {
         // User specified number of cores
-        let available = topology::get_usable_cores();
+        let available = usable_cores(run_config)?;
         available.into_iter().take(jobs).collect()
     } else {
         // Auto-detect physical cores
-        topology::get_usable_cores()
+        usable_cores(run_config)?
     };
 
     // Ensure we have at least one core
@@ -488,26 +1382,42 @@ fn run_benchmarks_parallel(
 
     output::print_run_header(benchmarks.len(), cores.len(), true);
 
+    let scheduler = scheduler::CoreScheduler::new(benchmarks, &cores, pins)?;
     run_benchmarks_with_cores(
         runner,
         workspace_root,
-        benchmarks,
-        &cores,
+        scheduler,
+        usize::MAX,
         run_config,
         config,
+        live,
     )
 }
 
+/// The discovered benchmarks `filter` and `group` select, ordered so each
+/// group runs contiguously
+fn select_benchmarks(
+    benchmarks: Vec<BenchmarkInfo>,
+    filter: Option<&BenchFilter>,
+    group: Option<&str>,
+) -> Vec<BenchmarkInfo> {
+    let mut benchmarks: Vec<BenchmarkInfo> = benchmarks
+        .into_iter()
+        .filter(|b| filter.map_or(true, |filter| filter.matches(&b.name)))
+        .filter(|b| group.map_or(true, |group| b.group.as_deref() == Some(group)))
+        .collect();
+    benchmarks.sort_by(|a, b| a.group.cmp(&b.group));
+    benchmarks
+}
+
 /// Message types sent from benchmark runner threads
 enum RunnerMessage {
     /// Progress update from stderr
-    Progress(progress::ProgressMessage),
-    /// Benchmark completed with result
+    Progress(ProgressMessage<'static>),
+    /// Benchmark completed with result or failure details
     Complete {
-        name: String,
         core: usize,
-        result: Box<Result<BenchResult, String>>,
-        stderr_lines: Vec<String>,
+        result: Box<Result<BenchResult, BenchFailure>>,
     },
 }
 
@@ -523,10 +1433,15 @@ fn spawn_benchmark_on_core(
 
     let bench_name = bench.name.clone();
     let tx = tx.clone();
+    let selected = BenchFilter::Exact(bench.name.clone());
+    let (filter_env, filter_value) = selected.env();
 
     let mut child = Command::new(runner)
         .env("SIMPLEBENCH_SINGLE_BENCH", "1")
-        .env("SIMPLEBENCH_BENCH_FILTER", &bench.name)
+        .env(filter_env, filter_value)
+        // Runners built against an older runtime read the name from here
+        .env(filter::FILTER_ENV, &bench.name)
+        .env(filter::MODULE_ENV, &bench.module)
         .env("SIMPLEBENCH_PIN_CORE", core.to_string())
         .envs(base_env)
         .stdout(Stdio::piped())
@@ -551,11 +1466,15 @@ fn spawn_benchmark_on_core(
 
         // Stream stderr lines for progress updates
         for line in stderr_reader.lines().map_while(Result::ok) {
-            if let Ok(wrapper) = serde_json::from_str::<progress::ProgressWrapper>(&line) {
-                let _ = tx.send(RunnerMessage::Progress(wrapper.progress));
-            } else {
+            match simplebench_runtime::progress::parse_progress_line(&line) {
+                Some(ProgressLine::Message(msg)) => {
+                    let _ = tx.send(RunnerMessage::Progress(msg));
+                }
+                Some(ProgressLine::UnsupportedVersion(version)) => {
+                    progress::warn_unsupported_version(version);
+                }
                 // Non-progress line (errors, warnings)
-                stderr_lines.push(line);
+                None => stderr_lines.push(line),
             }
         }
 
@@ -565,57 +1484,272 @@ fn spawn_benchmark_on_core(
         // Wait for child to complete
         let status = child.wait();
 
-        let result = match status {
-            Ok(s) if s.success() => serde_json::from_str::<BenchResult>(&stdout_content)
-                .map_err(|e| format!("Failed to parse result: {}\nstdout: {}", e, stdout_content)),
-            Ok(s) => Err(format!("Benchmark failed with status: {}", s)),
-            Err(e) => Err(format!("Failed to wait for benchmark: {}", e)),
+        let (message, exit_code) = match status {
+            Ok(s) if s.success() => match serde_json::from_str::<BenchResult>(&stdout_content) {
+                Ok(result) => {
+                    let _ = tx.send(RunnerMessage::Complete {
+                        core,
+                        result: Box::new(Ok(result)),
+                    });
+                    return;
+                }
+                Err(e) => (format!("Failed to parse result: {}", e), s.code()),
+            },
+            Ok(s) => {
+                // The runner prints a structured error as its last stdout line
+                // when the benchmark itself failed (e.g. setup returned an
+                // error) or it has no benchmark of that name
+                let message = stdout_content
+                    .lines()
+                    .last()
+                    .and_then(|line| serde_json::from_str::<RunnerError>(line).ok())
+                    .map(|e| e.message())
+                    .unwrap_or_else(|| format!("Benchmark failed with status: {}", s));
+                (message, s.code())
+            }
+            Err(e) => (format!("Failed to wait for benchmark: {}", e), None),
         };
 
+        let failure = BenchFailure::from_output(
+            &bench_name,
+            message,
+            exit_code,
+            &stdout_content,
+            &stderr_lines,
+        );
+
         let _ = tx.send(RunnerMessage::Complete {
-            name: bench_name,
             core,
-            result: Box::new(result),
-            stderr_lines,
+            result: Box::new(Err(failure)),
         });
     });
 }
 
+/// Run one benchmark on `core` and wait for its result, without saving it
+fn run_benchmark_blocking(
+    bench: &BenchmarkInfo,
+    core: usize,
+    runner: &Path,
+    base_env: &HashMap<String, String>,
+) -> Result<BenchResult> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    spawn_benchmark_on_core(bench, core, runner, base_env, &tx);
+    drop(tx);
+
+    for message in rx {
+        if let RunnerMessage::Complete { result, .. } = message {
+            return (*result)
+                .map_err(|failure| anyhow::anyhow!("{} failed: {}", bench.name, failure.message));
+        }
+    }
+    anyhow::bail!("Runner for {} exited without a result", bench.name)
+}
+
+/// Wait for the CPU to cool down before the next benchmark starts on `core`
+fn wait_for_cooldown(
+    core: usize,
+    config: &BenchmarkConfig,
+    progress_display: &progress::BenchmarkProgress,
+) {
+    let timeout = Duration::try_from_secs_f64(config.measurement.cooldown_secs).unwrap_or_default();
+    let monitor = simplebench_runtime::cpu_monitor::CpuMonitor::new(core);
+    if let Some(cooldown) = simplebench_runtime::cpu_monitor::cool_down(
+        &monitor,
+        config.measurement.cooldown_max_temp_c,
+        timeout,
+    ) {
+        progress_display.suspend(|| {
+            println!("{}", format!("  Cooldown: {}", cooldown.format()).dimmed());
+        });
+    }
+}
+
+/// Prints, compares and records each finished benchmark of a session
+struct SessionSink<'a> {
+    config: &'a BenchmarkConfig,
+    live: Option<&'a serve::LiveSession>,
+    columns: Vec<Column>,
+    baseline_manager: Option<Arc<BaselineManager>>,
+    /// Saves baselines in the background, so a long queue of results isn't
+    /// held up by serialization
+    baseline_writer: Option<BaselineWriter>,
+    progress_display: progress::BenchmarkProgress,
+    seen_groups: HashSet<String>,
+    results: SessionResults,
+}
+
+impl<'a> SessionSink<'a> {
+    fn new(
+        workspace_root: &Path,
+        run_config: &RunConfig,
+        config: &'a BenchmarkConfig,
+        live: Option<&'a serve::LiveSession>,
+    ) -> Self {
+        let baseline_manager = BaselineManager::for_workspace(workspace_root, &config.baseline)
+            .ok()
+            .map(|bm| {
+                Arc::new(
+                    bm.with_follow_renames(config.comparison.follow_renames)
+                        .with_improvement_resets_window(config.comparison.improvement_resets_window)
+                        .with_compare_across_machines(config.comparison.compare_across_machines)
+                        .with_retention(config.baseline.retention()),
+                )
+            });
+        let baseline_writer = baseline_manager
+            .clone()
+            .map(|bm| BaselineWriter::spawn(bm, None));
+        Self {
+            config,
+            live,
+            // Validated when the configuration was loaded
+            columns: config
+                .output
+                .columns_or(Column::TERMINAL_DEFAULT)
+                .unwrap_or_else(|_| Column::TERMINAL_DEFAULT.to_vec()),
+            results: SessionResults {
+                comparisons: Vec::new(),
+                failures: Vec::new(),
+                skipped: Vec::new(),
+                verdicts: Vec::new(),
+                saved: baseline_writer.as_ref().is_some_and(|w| w.is_saving()),
+            },
+            baseline_writer,
+            baseline_manager,
+            progress_display: progress::BenchmarkProgress::new(run_config.quiet, run_config.wide),
+            seen_groups: HashSet::new(),
+        }
+    }
+
+    /// Report a benchmark that finished on `core`
+    fn record(&mut self, core: usize, result: Result<BenchResult, BenchFailure>) {
+        let config = self.config;
+        let columns = &self.columns;
+        match result {
+            Ok(ref bench_result) => {
+                // Header before the first result of each group
+                let new_group = bench_result
+                    .group
+                    .as_ref()
+                    .filter(|group| self.seen_groups.insert(group.to_string()));
+
+                if bench_result.skipped {
+                    // Nothing was measured: no comparison and no baseline
+                    self.progress_display.suspend(|| {
+                        if let Some(group) = new_group {
+                            output::print_group_header(group);
+                        }
+                        output::print_benchmark_result(bench_result, core, columns);
+                        println!();
+                    });
+                    if let Some(live) = self.live {
+                        live.record_result(bench_result, None);
+                    }
+                    self.results.skipped.push(bench_result.clone());
+                } else {
+                    // Process baseline comparison
+                    let comparison = process_single_result_baseline(
+                        bench_result,
+                        self.baseline_manager
+                            .as_deref()
+                            .zip(self.baseline_writer.as_mut()),
+                        config,
+                    );
+
+                    // Suspend progress bars while printing output
+                    let comparison_ref = &comparison;
+                    self.progress_display.suspend(|| {
+                        if let Some(group) = new_group {
+                            output::print_group_header(group);
+                        }
+                        output::print_benchmark_result(bench_result, core, columns);
+                        output::print_comparison_result(comparison_ref, columns);
+                        if config.comparison.warn_underpowered {
+                            if let Some(ref c) = comparison_ref.comparison {
+                                simplebench_runtime::output::print_power_warning(
+                                    c,
+                                    config.comparison.confidence_level,
+                                );
+                            }
+                        }
+                        println!();
+                    });
+                    if let Some(live) = self.live {
+                        live.record_result(bench_result, Some(&comparison));
+                    }
+
+                    if self.results.saved {
+                        self.results.verdicts.extend(hooks::Verdict::due(
+                            &config.hooks,
+                            bench_result,
+                            &comparison,
+                        ));
+                    }
+                    self.results.comparisons.push(comparison);
+                }
+            }
+            Err(failure) => {
+                self.progress_display.suspend(|| {
+                    output::print_failure(&failure);
+                });
+                if let Some(live) = self.live {
+                    live.record_failure(&failure);
+                }
+                self.results.failures.push(failure);
+            }
+        }
+    }
+
+    /// Wait for the queued baselines and hand over the session's results
+    fn finish(mut self) -> SessionResults {
+        if let Some(writer) = self.baseline_writer {
+            for failure in writer.finish() {
+                eprintln!(
+                    "Warning: Failed to save baseline for {}: {}",
+                    failure.benchmark_name, failure.error
+                );
+            }
+        }
+        if let Some(ref baseline_manager) = self.baseline_manager {
+            for verdict in &mut self.results.verdicts {
+                verdict.locate_baseline(baseline_manager);
+            }
+        }
+        self.results
+    }
+}
+
 /// Run benchmarks using specified cores, spawning one runner per benchmark
 /// Returns both results and comparisons (printed inline as each benchmark completes)
+///
+/// The scheduler decides which benchmark runs on which core; at most
+/// `max_concurrent` runners are alive at once.
 fn run_benchmarks_with_cores(
     runner: &Path,
     workspace_root: &Path,
-    benchmarks: &[BenchmarkInfo],
-    cores: &[usize],
+    mut scheduler: scheduler::CoreScheduler,
+    max_concurrent: usize,
     run_config: &RunConfig,
     config: &BenchmarkConfig,
-) -> Result<(Vec<BenchResult>, Vec<ComparisonResult>)> {
+    live: Option<&serve::LiveSession>,
+) -> Result<SessionResults> {
     use std::sync::mpsc;
 
-    let base_env = build_runner_env(workspace_root, run_config);
-    let mut all_results = Vec::new();
-    let mut all_comparisons = Vec::new();
-
-    // Initialize baseline manager and progress display
-    let baseline_manager = BaselineManager::new().ok();
-    let mut progress_display = progress::BenchmarkProgress::new(run_config.quiet);
+    let base_env = build_runner_env(workspace_root, run_config, config);
+    let mut sink = SessionSink::new(workspace_root, run_config, config, live);
 
     // Queue-based job system: cores immediately pick up next benchmark when free
     let (tx, rx) = mpsc::channel::<RunnerMessage>();
 
-    let mut pending_benchmarks: VecDeque<_> = benchmarks.iter().collect();
-    let mut free_cores: Vec<usize> = cores.to_vec();
     let mut running = 0;
 
     // Initially fill all available cores
-    while let Some(core) = free_cores.pop() {
-        if let Some(bench) = pending_benchmarks.pop_front() {
-            spawn_benchmark_on_core(bench, core, runner, &base_env, &tx);
-            running += 1;
-        } else {
-            free_cores.push(core);
-            break;
+    while running < max_concurrent {
+        match scheduler.next() {
+            Some((bench, core)) => {
+                spawn_benchmark_on_core(bench, core, runner, &base_env, &tx);
+                running += 1;
+            }
+            None => break,
         }
     }
 
@@ -623,112 +1757,366 @@ fn run_benchmarks_with_cores(
     while running > 0 {
         match rx.recv() {
             Ok(RunnerMessage::Progress(msg)) => {
-                progress_display.update(&msg);
+                sink.progress_display.update(&msg);
             }
-            Ok(RunnerMessage::Complete {
-                name,
-                core,
-                result,
-                stderr_lines,
-            }) => {
-                match *result {
-                    Ok(ref bench_result) => {
-                        // Process baseline comparison
-                        let comparison =
-                            process_single_result_baseline(bench_result, &baseline_manager, config);
-
-                        // Suspend progress bars while printing output
-                        let bench_result = bench_result.clone();
-                        let comparison_ref = &comparison;
-                        progress_display.suspend(|| {
-                            output::print_benchmark_result(&bench_result, core);
-                            output::print_comparison_result(comparison_ref);
-                            println!();
-                        });
-
-                        all_comparisons.push(comparison);
-                        all_results.push(bench_result);
-                    }
-                    Err(ref e) => {
-                        let error_msg = e.clone();
-                        let stderr_lines = stderr_lines.clone();
-                        progress_display.suspend(|| {
-                            eprintln!(
-                                "{} Failed benchmark {}: {}",
-                                "ERROR".red().bold(),
-                                name,
-                                error_msg
-                            );
-                            for line in &stderr_lines {
-                                eprintln!("  {}", line);
-                            }
-                        });
+            Ok(RunnerMessage::Complete { core, result }) => {
+                sink.record(core, *result);
+
+                running -= 1;
+                scheduler.release(core);
+
+                // A core is now free - immediately start whatever can run next
+                while running < max_concurrent {
+                    match scheduler.next() {
+                        Some((bench, core)) => {
+                            wait_for_cooldown(core, config, &sink.progress_display);
+                            spawn_benchmark_on_core(bench, core, runner, &base_env, &tx);
+                            running += 1;
+                        }
+                        None => break,
                     }
                 }
+            }
+            Err(_) => break, // Channel closed
+        }
+    }
 
-                running -= 1;
+    Ok(sink.finish())
+}
 
-                // Core is now free - immediately start next benchmark
-                if let Some(bench) = pending_benchmarks.pop_front() {
-                    spawn_benchmark_on_core(bench, core, runner, &base_env, &tx);
-                    running += 1;
+/// Run every benchmark once per round on one core, round-robin, and pool the
+/// rounds of each benchmark (`--interleave`)
+///
+/// The sample budget is split evenly over the rounds, so drift over the
+/// session lands on all benchmarks alike. Each round is a full runner
+/// invocation with its own warmup; results are reported once all rounds ran.
+/// A benchmark that fails or is skipped drops out of the later rounds.
+#[allow(clippy::too_many_arguments)]
+fn run_benchmarks_interleaved(
+    runner: &Path,
+    workspace_root: &Path,
+    benchmarks: &[BenchmarkInfo],
+    pins: &HashMap<String, usize>,
+    core: usize,
+    rounds: usize,
+    run_config: &RunConfig,
+    config: &BenchmarkConfig,
+    live: Option<&serve::LiveSession>,
+) -> Result<SessionResults> {
+    let total = run_config.samples.unwrap_or(config.measurement.samples);
+    let shares = simplebench_runtime::interleave::split_samples(total, rounds);
+    let mut sink = SessionSink::new(workspace_root, run_config, config, live);
+    let mut parts: Vec<Vec<BenchResult>> = vec![Vec::new(); benchmarks.len()];
+    let mut finished = vec![false; benchmarks.len()];
+    let mut first = true;
+
+    for share in shares {
+        let mut env = build_runner_env(workspace_root, run_config, config);
+        env.insert("SIMPLEBENCH_SAMPLES".to_string(), share.to_string());
+
+        for (i, bench) in benchmarks.iter().enumerate() {
+            if finished[i] {
+                continue;
+            }
+            let core = pins.get(&bench.name).copied().unwrap_or(core);
+            if !first {
+                wait_for_cooldown(core, config, &sink.progress_display);
+            }
+            first = false;
+
+            let (tx, rx) = std::sync::mpsc::channel();
+            spawn_benchmark_on_core(bench, core, runner, &env, &tx);
+            drop(tx);
+            for message in rx {
+                match message {
+                    RunnerMessage::Progress(msg) => sink.progress_display.update(&msg),
+                    RunnerMessage::Complete { core, result } => match *result {
+                        Ok(result) if !result.skipped => parts[i].push(result),
+                        other => {
+                            finished[i] = true;
+                            parts[i].clear();
+                            sink.record(core, other);
+                        }
+                    },
                 }
             }
-            Err(_) => break, // Channel closed
         }
     }
 
-    Ok((all_results, all_comparisons))
+    for (bench, rounds) in benchmarks.iter().zip(parts) {
+        if let Some(merged) = simplebench_runtime::interleave::merge_rounds(rounds) {
+            let core = pins.get(&bench.name).copied().unwrap_or(core);
+            sink.record(core, Ok(merged));
+        }
+    }
+    Ok(sink.finish())
 }
 
 /// Process a single benchmark result against baselines (no printing)
 fn process_single_result_baseline(
     result: &BenchResult,
-    baseline_manager: &Option<BaselineManager>,
+    baselines: Option<(&BaselineManager, &mut BaselineWriter)>,
     config: &BenchmarkConfig,
 ) -> ComparisonResult {
-    let crate_name = result.module.split("::").next().unwrap_or("unknown");
+    let (crate_name, benchmark_name) = storage_name(&result.module, &result.name);
+    let comparison_config = config.comparison.for_benchmark(&result.name);
 
-    if let Some(ref bm) = baseline_manager {
+    if let Some((bm, writer)) = baselines {
         // Load recent baselines for window-based comparison
-        if let Ok(historical) =
-            bm.load_recent_baselines(crate_name, &result.name, config.comparison.window_size)
-        {
-            if !historical.is_empty() {
+        let outcome = match bm.load_comparable_window(
+            crate_name,
+            &benchmark_name,
+            comparison_config.window_size,
+            result.settings.as_ref(),
+        ) {
+            Ok(window) if !window.runs.is_empty() => {
                 // Use CPD-based comparison
-                let comp_result = simplebench_runtime::baseline::detect_regression_with_cpd(
+                let comp_result = simplebench_runtime::baseline::judge_against_history(
                     result,
-                    &historical,
-                    config.comparison.threshold,
-                    config.comparison.confidence_level,
-                    config.comparison.cp_threshold,
-                    config.comparison.hazard_rate,
+                    &window.runs,
+                    &comparison_config,
                 );
 
-                // Save baseline
-                if let Err(e) = bm.save_baseline(crate_name, result, comp_result.is_regression) {
-                    eprintln!(
-                        "Warning: Failed to save baseline for {}: {}",
-                        result.name, e
-                    );
-                }
+                writer.save(crate_name, result, Some(&comp_result));
 
                 return comp_result;
             }
-        }
+            Ok(window) => window.outcome,
+            Err(_) => WindowOutcome::EmptyNoHistory,
+        };
 
-        // First run - no baseline
-        if let Err(e) = bm.save_baseline(crate_name, result, false) {
-            eprintln!(
-                "Warning: Failed to save baseline for {}: {}",
-                result.name, e
-            );
+        // First run on this machine - other machines may still have history
+        let reference = bm
+            .load_reference_baselines(crate_name, &benchmark_name, comparison_config.window_size)
+            .unwrap_or(None);
+
+        writer.save(crate_name, result, None);
+
+        return ComparisonResult {
+            benchmark_name: result.name.clone(),
+            group: result.group.clone(),
+            comparison: None,
+            is_regression: false,
+            reference: None,
+            quality_failure: None,
+            is_improvement: false,
+            window: outcome,
         }
+        .with_reference(result, reference, &comparison_config)
+        .with_quality_check(result);
     }
 
     ComparisonResult {
         benchmark_name: result.name.clone(),
+        group: result.group.clone(),
         comparison: None,
         is_regression: false,
+        reference: None,
+        quality_failure: None,
+        is_improvement: false,
+        window: WindowOutcome::EmptyNoHistory,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    /// Write an executable shell script that stands in for the generated runner
+    #[cfg(unix)]
+    fn write_mock_runner(dir: &Path, script: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join("mock_runner.sh");
+        std::fs::write(&path, format!("#!/bin/sh\n{}", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn run_mock(runner: &Path) -> Box<Result<BenchResult, BenchFailure>> {
+        let bench = BenchmarkInfo {
+            name: "bench_mock".to_string(),
+            module: "mock_crate".to_string(),
+            group: None,
+            core: None,
+            debug_assertions: false,
+        };
+        let (tx, rx) = mpsc::channel();
+        spawn_benchmark_on_core(&bench, 1, runner, &HashMap::new(), &tx);
+
+        loop {
+            match rx.recv().unwrap() {
+                RunnerMessage::Progress(_) => continue,
+                RunnerMessage::Complete { result, .. } => return result,
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_bench_filter_selects_and_hands_over_one_benchmark() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        // Lists its benchmarks, or reports which one it was asked to run
+        let runner = write_mock_runner(
+            temp_dir.path(),
+            r#"if [ "$1" = "--list" ]; then
+  echo '[{"name":"bench_sort","module":"algos"},{"name":"bench_sort_large","module":"algos"},{"name":"my_bench_sort","module":"algos::extra"},{"name":"bench_sort","module":"algos::extra"}]'
+  exit 0
+fi
+echo "{\"error\":{\"name\":\"$SIMPLEBENCH_BENCH_EXACT\",\"message\":\"$SIMPLEBENCH_BENCH_MODULE\"}}"
+exit 1
+"#,
+        );
+        let discovered = || discover_benchmarks(&runner, temp_dir.path()).unwrap();
+
+        let selected = |pattern: &str, exact: bool| -> Vec<String> {
+            let filter = BenchFilter::new(pattern, exact);
+            select_benchmarks(discovered(), Some(&filter), None)
+                .iter()
+                .map(|b| format!("{}::{}", b.module, b.name))
+                .collect()
+        };
+        assert_eq!(
+            selected("bench_sort", false),
+            vec![
+                "algos::bench_sort",
+                "algos::bench_sort_large",
+                "algos::extra::my_bench_sort",
+                "algos::extra::bench_sort",
+            ]
+        );
+        assert_eq!(
+            selected("bench_sort", true),
+            vec!["algos::bench_sort", "algos::extra::bench_sort"]
+        );
+        assert_eq!(
+            selected("bench_sort*", false),
+            vec![
+                "algos::bench_sort",
+                "algos::bench_sort_large",
+                "algos::extra::bench_sort",
+            ]
+        );
+        assert!(selected("bench_hash", false).is_empty());
+        assert!(selected("bench_sort*", true).is_empty());
+        assert_eq!(select_benchmarks(discovered(), None, None).len(), 4);
+
+        // Each spawned runner gets its benchmark's exact name and module
+        let (tx, rx) = mpsc::channel();
+        spawn_benchmark_on_core(&discovered()[3], 1, &runner, &HashMap::new(), &tx);
+        let failure = loop {
+            match rx.recv().unwrap() {
+                RunnerMessage::Progress(_) => continue,
+                RunnerMessage::Complete { result, .. } => break result.unwrap_err(),
+            }
+        };
+        assert_eq!(failure.name, "bench_sort");
+        assert_eq!(failure.message, "algos::extra");
+    }
+
+    #[test]
+    fn test_clean_stale_rejects_age_and_count_limits() {
+        let parse = |args: &[&str]| Args::try_parse_from(args);
+        assert!(parse(&["cargo-simplebench", "clean", "--stale"]).is_ok());
+        assert!(parse(&[
+            "cargo-simplebench",
+            "clean",
+            "--keep",
+            "3",
+            "--older-than",
+            "7"
+        ])
+        .is_ok());
+        for limit in [["--keep", "3"], ["--older-than", "7"]] {
+            let error =
+                parse(&["cargo-simplebench", "clean", "--stale", limit[0], limit[1]]).unwrap_err();
+            assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_runner_produces_bench_failure() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let runner = write_mock_runner(
+            temp_dir.path(),
+            r#"echo '{"progress":{"bench":"bench_mock","phase":"complete"}}' >&2
+echo "some stdout"
+echo "thread 'main' panicked at src/lib.rs:1:1:" >&2
+echo "setup exploded" >&2
+exit 101
+"#,
+        );
+
+        let failure = run_mock(&runner).unwrap_err();
+        assert_eq!(failure.name, "bench_mock");
+        assert_eq!(failure.exit_code, Some(101));
+        assert_eq!(failure.panic_message.as_deref(), Some("setup exploded"));
+        assert_eq!(failure.stdout_tail, vec!["some stdout"]);
+        // Progress lines are not part of the captured stderr
+        assert_eq!(failure.stderr_tail.len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_structured_runner_error_message_is_used() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let runner = write_mock_runner(
+            temp_dir.path(),
+            r#"echo "ERROR: setup for benchmark 'bench_mock' failed: missing fixture" >&2
+echo '{"error":{"name":"bench_mock","message":"setup failed: missing fixture"}}'
+exit 1
+"#,
+        );
+
+        let failure = run_mock(&runner).unwrap_err();
+        assert_eq!(failure.message, "setup failed: missing fixture");
+        assert_eq!(failure.exit_code, Some(1));
+        assert_eq!(failure.stderr_tail.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_runner_is_handed_the_exact_name() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let runner = write_mock_runner(
+            temp_dir.path(),
+            r#"echo "{\"error\":{\"name\":\"bench_mock\",\"message\":\"exact=$SIMPLEBENCH_BENCH_EXACT filter=$SIMPLEBENCH_BENCH_FILTER\"},\"suggestions\":[\"bench_mock_large\"]}"
+exit 1
+"#,
+        );
+
+        let failure = run_mock(&runner).unwrap_err();
+        assert_eq!(
+            failure.message,
+            "exact=bench_mock filter=bench_mock (did you mean bench_mock_large?)"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_discovery_rejects_colliding_benchmarks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let runner = write_mock_runner(
+            temp_dir.path(),
+            r#"echo '[{"name":"bench_step","module":"physics::broad"},{"name":"bench_step","module":"physics::narrow"},{"name":"c","module":"a_b"},{"name":"b_c","module":"a"}]'
+"#,
+        );
+
+        let error = discover_benchmarks(&runner, temp_dir.path()).unwrap_err();
+        assert!(error.to_string().ends_with("a_b_c: a::b_c, a_b::c"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unparseable_output_produces_bench_failure() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let runner = write_mock_runner(temp_dir.path(), "echo 'not json'\nexit 0\n");
+
+        let failure = run_mock(&runner).unwrap_err();
+        assert!(failure.message.starts_with("Failed to parse result"));
+        assert_eq!(failure.exit_code, Some(0));
+        assert_eq!(failure.stdout_tail, vec!["not json"]);
     }
 }
@@ -12,6 +12,8 @@ pub struct WorkspaceInfo {
 #[derive(Debug, Clone)]
 pub struct BenchmarkCrate {
     pub name: String,
+    /// Directory holding the crate's library root
+    pub src_dir: PathBuf,
 }
 
 /// Parse workspace metadata and identify benchmark crates
@@ -35,9 +37,15 @@ pub fn analyze_workspace(workspace_root: &Path) -> Result<WorkspaceInfo> {
         }
 
         // Check if this package depends on simplebench-runtime and has a lib target
-        if depends_on_simplebench_runtime(package) && has_lib_target(package) {
+        if !depends_on_simplebench_runtime(package) {
+            continue;
+        }
+        if let Some(lib_root) = lib_target_root(package) {
             benchmark_crates.push(BenchmarkCrate {
                 name: package.name.clone(),
+                src_dir: lib_root
+                    .parent()
+                    .map_or_else(|| lib_root.clone(), Path::to_path_buf),
             });
         }
     }
@@ -61,12 +69,16 @@ fn depends_on_simplebench_runtime(package: &Package) -> bool {
     })
 }
 
-/// Check if a package has a library target (rlib/lib)
-fn has_lib_target(package: &Package) -> bool {
-    package.targets.iter().any(|target| {
-        target
-            .kind
-            .iter()
-            .any(|kind| kind == "lib" || kind == "rlib")
-    })
+/// Root source file of a package's library target (rlib/lib), if it has one
+fn lib_target_root(package: &Package) -> Option<PathBuf> {
+    package
+        .targets
+        .iter()
+        .find(|target| {
+            target
+                .kind
+                .iter()
+                .any(|kind| kind == "lib" || kind == "rlib")
+        })
+        .map(|target| target.src_path.clone().into_std_path_buf())
 }
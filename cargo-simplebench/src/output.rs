@@ -5,66 +5,108 @@
 
 use colored::*;
 use simplebench_runtime::{
-    baseline::ComparisonResult, config::ComparisonConfig, BenchResult, Comparison,
+    baseline::ComparisonResult, columns::Column, config::ComparisonConfig, BenchFailure,
+    BenchResult, Comparison,
 };
-use std::time::Duration;
-
-/// Format a duration in human-readable form
-pub fn format_duration(duration: Duration) -> String {
-    let nanos = duration.as_nanos();
-
-    if nanos < 1_000 {
-        format!("{}ns", nanos)
-    } else if nanos < 1_000_000 {
-        format!("{:.2}μs", nanos as f64 / 1_000.0)
-    } else if nanos < 1_000_000_000 {
-        format!("{:.2}ms", nanos as f64 / 1_000_000.0)
-    } else {
-        format!("{:.2}s", nanos as f64 / 1_000_000_000.0)
-    }
+
+/// Width assumed when neither the terminal nor `COLUMNS` tells, as when piping
+const FALLBACK_TERMINAL_WIDTH: usize = 80;
+
+/// Columns of `term`, or of `COLUMNS` when it is not a terminal
+pub fn terminal_width(term: &console::Term) -> usize {
+    term.size_checked()
+        .map(|(_, columns)| columns as usize)
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(FALLBACK_TERMINAL_WIDTH)
+}
+
+/// Width tables on stdout fit benchmark names into; None with `--wide`,
+/// which never truncates names
+pub fn table_width(wide: bool) -> Option<usize> {
+    (!wide).then(|| terminal_width(&console::Term::stdout()))
 }
 
 /// Print a single benchmark result (called as each benchmark completes)
-pub fn print_benchmark_result(result: &BenchResult, core: usize) {
+pub fn print_benchmark_result(result: &BenchResult, core: usize, columns: &[Column]) {
+    if result.skipped {
+        println!(
+            "{}",
+            simplebench_runtime::output::format_skipped_result(result)
+        );
+        return;
+    }
+
     let bench_name = format!("{}::{}", result.module, result.name);
-    let mean_str = format_duration(result.percentiles.mean);
-    let p50_str = format_duration(result.percentiles.p50);
-    let p90_str = format_duration(result.percentiles.p90);
-    let p99_str = format_duration(result.percentiles.p99);
-
-    // Calculate coefficient of variation (CV) from raw timings if available
-    let cv_str = if !result.all_timings.is_empty() {
-        let samples_ns: Vec<u128> = result.all_timings.iter().map(|d| d.as_nanos()).collect();
-        let stats = simplebench_runtime::calculate_statistics(&samples_ns);
-        let cv_pct = if stats.mean > 0 {
-            (stats.std_dev / stats.mean as f64) * 100.0
-        } else {
-            0.0
-        };
-        format!(" (CV: {:.1}%)", cv_pct)
-    } else {
-        String::new()
-    };
+    let fields = simplebench_runtime::output::format_result_columns(result, columns);
+    let separator = if fields.is_empty() { "" } else { " " };
 
     println!(
-        "{} {} mean: {}{}, p50: {}, p90: {}, p99: {} [core {}]",
+        "{} {} {}{}[core {}]",
         "BENCH".green().bold(),
         bench_name.cyan(),
-        mean_str.cyan().bold(),
-        cv_str.dimmed(),
-        p50_str.dimmed(),
-        p90_str.dimmed(),
-        p99_str.dimmed(),
+        fields,
+        separator,
         core.to_string().yellow()
     );
 
+    // Print wall vs CPU time if they diverge (blocking or sleeping benchmarks)
+    if let Some(times) = simplebench_runtime::output::format_wall_vs_cpu(result) {
+        println!("        {} {}", "Time:".dimmed(), times);
+    }
+    if let Some(warning) = simplebench_runtime::output::format_scheduling_warning(result) {
+        println!("        {}", warning.yellow());
+    }
+    if result.clock == simplebench_runtime::ClockMode::Cpu {
+        println!("        {} thread CPU time", "Clock:".dimmed());
+    }
+    if result.measures_drop {
+        println!("        {} drop of the setup value", "Measures:".dimmed());
+    }
+
     // Print warmup stats if available
-    if let (Some(warmup_ms), Some(warmup_iters)) = (result.warmup_ms, result.warmup_iterations) {
+    if let Some(warmup) = simplebench_runtime::output::format_warmup_line(result, columns) {
+        println!("{}", warmup);
+    }
+
+    if result.watchdog_stopped {
+        println!(
+            "        {}",
+            format!("(stopped by the watchdog after {} samples)", result.samples).dimmed()
+        );
+    } else if result.truncated {
+        println!(
+            "        {}",
+            format!("(time budget reached after {} samples)", result.samples).dimmed()
+        );
+    }
+
+    if result.interleaved_rounds > 1 {
+        println!(
+            "        {}",
+            format!(
+                "(pooled from {} interleaved rounds)",
+                result.interleaved_rounds
+            )
+            .dimmed()
+        );
+    }
+
+    if let Some(line) = simplebench_runtime::output::format_pathological_line(result) {
+        println!("        {}", line.yellow());
+    }
+    if let Some(failure) = result.quality_failure {
+        println!("        {}", failure.format().red().bold());
+    }
+
+    if let Some(verdict) = result.early_verdict {
         println!(
-            "        {} {}ms ({} iterations)",
-            "Warmup:".dimmed(),
-            warmup_ms,
-            warmup_iters
+            "        {}",
+            format!(
+                "(stopped early after {} samples: {})",
+                result.samples,
+                verdict.label()
+            )
+            .dimmed()
         );
     }
 
@@ -72,6 +114,13 @@ pub fn print_benchmark_result(result: &BenchResult, core: usize) {
     if let Some(cpu_stats) = format_cpu_stats(&result.cpu_samples) {
         println!("        {}", cpu_stats.dimmed());
     }
+    if let Some(energy_uj) = result.energy_uj {
+        println!(
+            "        {} {}",
+            "Energy:".dimmed(),
+            simplebench_runtime::energy::format_energy(energy_uj, result.mean_watts)
+        );
+    }
 }
 
 /// Format CPU statistics from samples
@@ -126,121 +175,157 @@ fn format_cpu_stats(cpu_samples: &[simplebench_runtime::CpuSnapshot]) -> Option<
 }
 
 /// Print comparison result for a benchmark
-pub fn print_comparison(comparison: &Comparison, benchmark_name: &str, is_regression: bool) {
-    let change_symbol = if comparison.percentage_change > 0.0 {
-        "↗"
-    } else {
-        "↘"
-    };
-    let percentage_str = format!("{:.1}%", comparison.percentage_change.abs());
-    let baseline_str = format_duration(comparison.baseline_mean);
-    let current_str = format_duration(comparison.current_mean);
-
-    let baseline_suffix = if comparison.baseline_count > 1 {
-        format!(" (n={})", comparison.baseline_count)
-    } else {
-        String::new()
-    };
-
-    let base_line = if is_regression {
-        format!(
-            "        {} {} {} (mean: {} -> {}{})",
-            "REGRESS".red().bold(),
-            change_symbol,
-            percentage_str.red().bold(),
-            baseline_str.dimmed(),
-            current_str.red(),
-            baseline_suffix.dimmed()
-        )
-    } else if comparison.percentage_change < -5.0 {
-        format!(
-            "        {} {} {} (mean: {} -> {}{})",
-            "IMPROVE".green().bold(),
-            change_symbol,
-            percentage_str.green(),
-            baseline_str.dimmed(),
-            current_str.green(),
-            baseline_suffix.dimmed()
-        )
-    } else {
-        format!(
-            "        {} {} {} (mean: {} -> {}{})",
-            "STABLE".cyan(),
-            change_symbol,
-            percentage_str.dimmed(),
-            baseline_str.dimmed(),
-            current_str.dimmed(),
-            baseline_suffix.dimmed()
-        )
-    };
+pub fn print_comparison(
+    comparison: &Comparison,
+    benchmark_name: &str,
+    is_regression: bool,
+    columns: &[Column],
+) {
+    println!(
+        "{}",
+        simplebench_runtime::output::format_comparison_columns(comparison, is_regression, columns)
+    );
 
-    // Add statistical info if available
-    let mut stats_parts = Vec::new();
+    if let Some(note) = comparison.provisional_note() {
+        println!("        {} {}", "⚠".yellow(), note.yellow());
+    }
 
-    if let Some(z_score) = comparison.z_score {
-        stats_parts.push(format!("z={:.2}", z_score));
+    if let Some(ref suspect) = comparison.suspect {
+        println!("        {} {}", "⚠".yellow(), suspect.format().yellow());
     }
 
-    if let Some(cp_prob) = comparison.change_probability {
-        stats_parts.push(format!("cp={:.0}%", cp_prob * 100.0));
+    if let Some(ref allocs) = comparison.allocs {
+        println!(
+            "        {}",
+            simplebench_runtime::output::format_alloc_comparison(allocs)
+        );
     }
 
-    if !stats_parts.is_empty() {
-        println!("{}", base_line);
-        println!("        {}", stats_parts.join(", ").dimmed());
-    } else {
-        println!("{}", base_line);
+    for change in &comparison.env_changes {
+        println!("        {} {}", "⚠".yellow(), change.format().yellow());
+    }
+
+    if let Some(ref change) = comparison.commit_change {
+        println!("        {}", change.format().dimmed());
+    }
+
+    for change in &comparison.toolchain_changes {
+        println!("        {}", format!("note: {}", change.format()).dimmed());
+    }
+
+    if let Some(note) = comparison.accepted_note() {
+        println!("        {}", note.dimmed());
     }
 
     // Suppress unused variable warning
     let _ = benchmark_name;
 }
 
-/// Print "NEW" message for first baseline
-pub fn print_new_baseline(benchmark_name: &str) {
-    println!(
-        "        {} {} (establishing baseline)",
-        "NEW".blue().bold(),
-        benchmark_name.bright_white()
-    );
+/// Print a header introducing the benchmarks of a group
+pub fn print_group_header(group: &str) {
+    println!("{} {}\n", "GROUP".magenta().bold(), group.bold());
+}
+
+/// Print "NEW" message for first baseline, oriented by another machine's
+/// history when there is one
+pub fn print_new_baseline(benchmark_name: &str, reference: Option<&Comparison>) {
+    match reference.and_then(simplebench_runtime::output::format_reference_note) {
+        Some(note) => println!(
+            "        {} {} ({})",
+            "NEW".blue().bold(),
+            benchmark_name.bright_white(),
+            note
+        ),
+        None => println!(
+            "        {} {} (establishing baseline)",
+            "NEW".blue().bold(),
+            benchmark_name.bright_white()
+        ),
+    }
 }
 
 /// Print comparison result (handles both existing comparison and new baseline cases)
-pub fn print_comparison_result(comparison_result: &ComparisonResult) {
+pub fn print_comparison_result(comparison_result: &ComparisonResult, columns: &[Column]) {
     if let Some(ref comparison) = comparison_result.comparison {
         print_comparison(
             comparison,
             &comparison_result.benchmark_name,
             comparison_result.is_regression,
+            columns,
+        );
+    } else if let Some(note) = comparison_result.window.exclusion_note() {
+        simplebench_runtime::output::print_excluded_history_line(
+            &comparison_result.benchmark_name,
+            &note,
         );
     } else {
-        print_new_baseline(&comparison_result.benchmark_name);
+        print_new_baseline(
+            &comparison_result.benchmark_name,
+            comparison_result.reference.as_ref(),
+        );
+    }
+}
+
+/// Print details of a failed benchmark
+pub fn print_failure(failure: &BenchFailure) {
+    eprintln!(
+        "{} Failed benchmark {}: {}",
+        "ERROR".red().bold(),
+        failure.name,
+        failure.message
+    );
+    if let Some(ref panic_message) = failure.panic_message {
+        eprintln!("  {} {}", "panicked:".red(), panic_message);
+    }
+    for line in &failure.stderr_tail {
+        eprintln!("  {}", line);
     }
 }
 
 /// Print summary footer
-pub fn print_summary(comparisons: &[ComparisonResult], config: &ComparisonConfig) {
+pub fn print_summary(
+    comparisons: &[ComparisonResult],
+    failures: &[BenchFailure],
+    skipped: &[BenchResult],
+    config: &ComparisonConfig,
+) {
+    // Too noisy to judge, so counted apart from every verdict
+    let quality_failures = comparisons
+        .iter()
+        .filter(|c| c.quality_failure.is_some())
+        .count();
     let regressions = comparisons.iter().filter(|c| c.is_regression).count();
     let improvements = comparisons
         .iter()
         .filter(|c| {
-            c.comparison
-                .as_ref()
-                .map(|comp| comp.percentage_change < -5.0)
-                .unwrap_or(false)
+            c.quality_failure.is_none()
+                && !c.is_regression
+                && c.comparison
+                    .as_ref()
+                    .map(|comp| comp.percentage_change < -5.0)
+                    .unwrap_or(false)
         })
         .count();
     let new_benchmarks = comparisons
         .iter()
-        .filter(|c| c.comparison.is_none())
+        .filter(|c| c.quality_failure.is_none() && c.is_new())
         .count();
-    let stable = comparisons.len() - regressions - improvements - new_benchmarks;
+    let excluded = comparisons
+        .iter()
+        .filter(|c| c.quality_failure.is_none() && c.history_excluded())
+        .count();
+    let stable = comparisons.len()
+        - regressions
+        - improvements
+        - new_benchmarks
+        - excluded
+        - quality_failures;
 
     println!("{}", "─".repeat(80).dimmed());
     println!(
-        "{} {} total: {} {}, {} {}, {} {}{}",
+        "{} {} total: {} {}, {} {}, {} {}{}{}{}{}{}",
         "Summary:".cyan().bold(),
-        comparisons.len(),
+        comparisons.len() + failures.len() + skipped.len(),
         stable,
         "stable".dimmed(),
         improvements,
@@ -255,17 +340,66 @@ pub fn print_summary(comparisons: &[ComparisonResult], config: &ComparisonConfig
             format!(", {} {}", new_benchmarks, "new".blue())
         } else {
             String::new()
+        },
+        if excluded > 0 {
+            format!(", {} {}", excluded, "provisional".yellow())
+        } else {
+            String::new()
+        },
+        if quality_failures > 0 {
+            format!(", {} {}", quality_failures, "quality-failed".red())
+        } else {
+            String::new()
+        },
+        if !failures.is_empty() {
+            format!(", {} {}", failures.len(), "failed".red().bold())
+        } else {
+            String::new()
+        },
+        if !skipped.is_empty() {
+            format!(", {} {}", skipped.len(), "skipped".yellow())
+        } else {
+            String::new()
         }
     );
 
+    for line in simplebench_runtime::output::format_group_breakdown(comparisons) {
+        println!("{}", line);
+    }
+    for line in simplebench_runtime::output::format_skip_reasons(skipped) {
+        println!("{}", line);
+    }
+
     if regressions > 0 {
         println!(
-            "{} {} regression(s) detected (threshold: {}%)",
+            "{} {} regression(s) detected (threshold: {}%{})",
             "Warning:".yellow().bold(),
             regressions,
-            config.threshold
+            config.threshold,
+            config
+                .p99_threshold
+                .map_or(String::new(), |t| format!(", p99 threshold: {}%", t))
         );
     }
+
+    let alloc_regressions = comparisons
+        .iter()
+        .filter(|c| c.is_alloc_regression())
+        .count();
+    if alloc_regressions > 0 {
+        println!(
+            "{} {} allocation regression(s) detected (threshold: {}%)",
+            "Warning:".yellow().bold(),
+            alloc_regressions,
+            config.alloc_threshold.unwrap_or_default()
+        );
+    }
+    if let Some(line) = simplebench_runtime::output::format_suspect_warning(comparisons) {
+        println!("{}", line);
+    }
+    for line in simplebench_runtime::output::format_drift_warnings(comparisons, config) {
+        println!("{}", line);
+    }
 }
 
 /// Print header showing benchmark count and core usage
@@ -0,0 +1,278 @@
+use crate::output;
+use crate::replay::load_history;
+use anyhow::Result;
+use colored::*;
+use simplebench_runtime::baseline::{BaselineData, BaselineManager};
+use simplebench_runtime::config::{BenchmarkConfig, ComparisonConfig};
+use simplebench_runtime::filter::BenchFilter;
+use simplebench_runtime::output::{name_column_width, truncate_middle};
+use simplebench_runtime::statistics::{
+    false_positive_rate, mean, minimum_detectable_effect, power_at_effect, samples_for_power,
+    standard_deviation,
+};
+use std::path::Path;
+
+/// Width of the table's columns after the benchmark name
+const NAME_RESERVED: usize = 39;
+
+/// Power below which a configuration is reported as inadequate
+const ADEQUATE_POWER: f64 = 0.8;
+
+/// Settings supplied on the command line, overriding the config file
+pub struct PlanOptions {
+    pub bench_filter: Option<BenchFilter>,
+    pub samples: Option<usize>,
+    pub threshold: Option<f64>,
+    pub wide: bool,
+}
+
+/// How well one benchmark's comparison can resolve a change of the threshold
+#[derive(Debug)]
+pub struct BenchPlan {
+    pub benchmark_name: String,
+    /// Coefficient of variation of the latest run's samples, in percent
+    pub sample_cv: f64,
+    /// Coefficient of variation of the window's run means, in percent; None
+    /// with fewer than two runs
+    pub window_cv: Option<f64>,
+    /// Probability of flagging a true change of `threshold`
+    pub power: f64,
+    /// Probability of flagging an unchanged benchmark
+    pub false_positive_rate: f64,
+    /// Samples per run for 80% power; None if the window's spread alone is too large
+    pub suggested_samples: Option<usize>,
+    /// Smallest threshold detected with 80% power at the planned sample count
+    pub suggested_threshold: f64,
+}
+
+impl BenchPlan {
+    pub fn is_adequate(&self) -> bool {
+        self.power >= ADEQUATE_POWER
+    }
+}
+
+/// Estimate detection power per benchmark from stored history, before running anything
+pub fn run_plan(workspace_root: &Path, options: &PlanOptions) -> Result<()> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    let baseline_manager = BaselineManager::for_workspace(workspace_root, &config.baseline)?;
+    simplebench_runtime::output::set_time_format(config.output.time_format());
+    if let Some(threshold) = options.threshold {
+        config.comparison.threshold = threshold;
+    }
+
+    // Adaptive sampling can stop at any count up to its cap; plan for the cap
+    let samples = options.samples.unwrap_or(if config.measurement.adaptive {
+        config.measurement.max_samples
+    } else {
+        config.measurement.samples
+    });
+
+    let mut plans = Vec::new();
+    for stored in baseline_manager.stored_benchmarks(options.bench_filter.as_ref())? {
+        let history = load_history(
+            &baseline_manager,
+            &stored.crate_name,
+            &stored.benchmark_name,
+        )?;
+        if let Some(plan) = plan_benchmark(stored.dir_name, &history, samples, &config.comparison) {
+            plans.push(plan);
+        }
+    }
+
+    if plans.is_empty() {
+        anyhow::bail!("No stored benchmark history found for this machine");
+    }
+
+    print_plan(&plans, samples, &config.comparison, options.wide);
+    Ok(())
+}
+
+/// Plan one benchmark from its history (oldest first); None without usable runs
+pub(crate) fn plan_benchmark(
+    benchmark_name: String,
+    history: &[BaselineData],
+    samples: usize,
+    config: &ComparisonConfig,
+) -> Option<BenchPlan> {
+    let latest = history.last()?;
+    let latest_mean = latest.statistics.mean as f64;
+    if latest_mean <= 0.0 || samples == 0 {
+        return None;
+    }
+    let sample_cv = latest.statistics.std_dev / latest_mean * 100.0;
+
+    // The window a comparison would see: recent runs not flagged as regressions
+    let means: Vec<f64> = history
+        .iter()
+        .filter(|b| !b.was_regression)
+        .map(|b| b.statistics.mean as f64)
+        .collect();
+    let window = &means[means.len().saturating_sub(config.window_size)..];
+    let window_cv = (window.len() >= 2).then(|| standard_deviation(window) / mean(window) * 100.0);
+
+    let mde = minimum_detectable_effect(
+        100.0,
+        window_cv.unwrap_or(0.0),
+        sample_cv / (samples as f64).sqrt(),
+        config.confidence_level,
+    )?;
+
+    Some(BenchPlan {
+        benchmark_name,
+        sample_cv,
+        window_cv,
+        power: power_at_effect(config.threshold, mde, config.confidence_level),
+        false_positive_rate: false_positive_rate(config.threshold, mde, config.confidence_level),
+        suggested_samples: samples_for_power(
+            config.threshold,
+            sample_cv,
+            window_cv.unwrap_or(0.0),
+            config.confidence_level,
+        ),
+        suggested_threshold: mde.ceil(),
+    })
+}
+
+/// Advice for an underpowered benchmark
+fn advice(plan: &BenchPlan, samples: usize, threshold: f64) -> String {
+    let head = format!(
+        "power {:.0}% for {}% change at n={}",
+        plan.power * 100.0,
+        threshold,
+        samples
+    );
+    match plan.suggested_samples {
+        Some(n) => format!(
+            "{} — increase samples to ~{} or threshold to {}%",
+            head, n, plan.suggested_threshold
+        ),
+        None => format!(
+            "{} — run-to-run spread exceeds what more samples can fix; raise threshold to {}%",
+            head, plan.suggested_threshold
+        ),
+    }
+}
+
+fn print_plan(plans: &[BenchPlan], samples: usize, config: &ComparisonConfig, wide: bool) {
+    println!("{}", "Planning detection power".green().bold());
+    println!(
+        "{}",
+        format!(
+            "threshold {}%, confidence {}, window {}, {} samples per run",
+            config.threshold, config.confidence_level, config.window_size, samples
+        )
+        .dimmed()
+    );
+    println!();
+
+    let width = name_column_width(
+        plans
+            .iter()
+            .map(|plan| plan.benchmark_name.as_str())
+            .chain(["Benchmark"]),
+        NAME_RESERVED,
+        output::table_width(wide),
+    );
+    println!(
+        "{:<width$} {:>9} {:>10} {:>7} {:>9}",
+        "Benchmark".bold(),
+        "Sample CV".bold(),
+        "Window CV".bold(),
+        "Power".bold(),
+        "False +".bold()
+    );
+    println!("{}", "─".repeat(width + NAME_RESERVED).dimmed());
+
+    for plan in plans {
+        let power = format!("{:.0}%", plan.power * 100.0);
+        let power_label = if plan.is_adequate() {
+            power.green()
+        } else {
+            power.red().bold()
+        };
+        let window_cv = plan
+            .window_cv
+            .map_or("-".to_string(), |cv| format!("{:.2}%", cv));
+
+        println!(
+            "{:<width$} {:>9} {:>10} {:>7} {:>9}",
+            truncate_middle(&plan.benchmark_name, width),
+            format!("{:.2}%", plan.sample_cv),
+            window_cv,
+            power_label,
+            format!("{:.1}%", plan.false_positive_rate * 100.0)
+        );
+
+        if !plan.is_adequate() {
+            println!(
+                "  {} {}",
+                "↳".dimmed(),
+                advice(plan, samples, config.threshold).yellow()
+            );
+        }
+    }
+
+    let underpowered = plans.iter().filter(|p| !p.is_adequate()).count();
+    println!("{}", "─".repeat(width + NAME_RESERVED).dimmed());
+    println!(
+        "{} of {} benchmarks can't reliably detect a {}% change with this configuration",
+        underpowered,
+        plans.len(),
+        config.threshold
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simplebench_runtime::BenchResult;
+    use std::time::Duration;
+
+    /// A run alternating between `mean - spread` and `mean + spread` nanoseconds
+    fn run(mean: u64, spread: u64) -> BaselineData {
+        let result = BenchResult {
+            name: "bench".to_string(),
+            module: "tests".to_string(),
+            all_timings: (0..100)
+                .map(|i| {
+                    let offset = if i % 2 == 0 {
+                        spread
+                    } else {
+                        spread.wrapping_neg()
+                    };
+                    Duration::from_nanos(mean.wrapping_add(offset))
+                })
+                .collect(),
+            ..Default::default()
+        };
+        BaselineData::from_bench_result(&result, "test-machine".into(), false)
+    }
+
+    #[test]
+    fn test_plan_flags_noisy_benchmark() {
+        let config = ComparisonConfig::default();
+
+        // 1% sample noise: 100 samples resolve a 5% change easily
+        let quiet = vec![run(1000, 10), run(1000, 10)];
+        let plan = plan_benchmark("quiet".into(), &quiet, 100, &config).unwrap();
+        assert!(plan.is_adequate());
+        assert!(plan.false_positive_rate < 0.05);
+
+        // 30% sample noise needs far more samples
+        let noisy = vec![run(1000, 300)];
+        let plan = plan_benchmark("noisy".into(), &noisy, 100, &config).unwrap();
+        assert!(!plan.is_adequate());
+        assert!(plan.window_cv.is_none());
+        assert!(plan.suggested_samples.unwrap() > 100);
+        assert!(plan.suggested_threshold > config.threshold);
+        assert!(advice(&plan, 100, config.threshold).contains("increase samples to ~"));
+
+        // Window means 10% apart: no sample count helps
+        let drifting = vec![run(900, 1), run(1100, 1), run(900, 1), run(1100, 1)];
+        let plan = plan_benchmark("drifting".into(), &drifting, 100, &config).unwrap();
+        assert!(!plan.is_adequate());
+        assert!(plan.suggested_samples.is_none());
+    }
+}
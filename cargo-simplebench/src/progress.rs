@@ -1,38 +1,47 @@
 //! Progress bar display for benchmark execution.
 //!
-//! Parses JSON progress messages from runner stderr and displays
-//! indicatif progress bars in the terminal.
+//! Parses JSON progress messages from runner stderr (see
+//! `simplebench_runtime::progress` for the protocol) and displays indicatif
+//! progress bars in the terminal.
 
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use serde::Deserialize;
+use simplebench_runtime::output::{name_column_width, truncate_middle};
+use simplebench_runtime::progress::{ProgressMessage, ProgressPhase, PROGRESS_PROTOCOL_VERSION};
+use simplebench_runtime::PathologicalSample;
 use std::collections::HashMap;
 use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-/// Wrapper for progress messages from the runtime.
-#[derive(Debug, Deserialize)]
-pub struct ProgressWrapper {
-    pub progress: ProgressMessage,
+/// Warn (once per session) that a runner's progress messages can't be read
+pub fn warn_unsupported_version(version: u32) {
+    static WARNED: AtomicBool = AtomicBool::new(false);
+    if !WARNED.swap(true, Ordering::Relaxed) {
+        eprintln!(
+            "warning: benchmark runner uses progress protocol v{}, this cargo-simplebench supports v{}; progress bars disabled (update cargo-simplebench)",
+            version, PROGRESS_PROTOCOL_VERSION
+        );
+    }
 }
 
-/// Progress message from the benchmark runner.
-#[derive(Debug, Deserialize)]
-pub struct ProgressMessage {
-    pub bench: String,
-    #[serde(flatten)]
-    pub phase: ProgressPhase,
+/// "warning: my_bench: sample #17 took 4.00 s (2000000× the running median)"
+fn pathological_sample_warning(bench: &str, sample: &PathologicalSample) -> String {
+    let mut warning = format!(
+        "warning: {}: sample #{} took {}",
+        bench,
+        sample.index,
+        crate::analyze::format_ns(sample.duration_ns as u128)
+    );
+    if let Some(multiple) = sample.median_multiple() {
+        warning.push_str(&format!(" ({:.0}× the running median)", multiple));
+    }
+    warning
 }
 
-/// Progress phase during benchmark execution.
-#[derive(Debug, Clone, PartialEq, Deserialize)]
-#[serde(tag = "phase")]
-pub enum ProgressPhase {
-    #[serde(rename = "warmup")]
-    Warmup { elapsed_ms: u64, target_ms: u64 },
-    #[serde(rename = "samples")]
-    Samples { current: u32, total: u32 },
-    #[serde(rename = "complete")]
-    Complete,
-}
+/// Width of a bar line after the name prefix: the bar, counts and message
+const BAR_RESERVED: usize = 56;
+
+/// Narrowest the name prefix gets, so bars of short names still line up
+const MIN_PREFIX_WIDTH: usize = 20;
 
 /// Which phase a benchmark is currently in.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -53,17 +62,43 @@ pub struct BenchmarkProgress {
     benches: HashMap<String, BenchState>,
     is_tty: bool,
     quiet: bool,
+    /// Terminal columns names are fitted into; None with `--wide`
+    terminal_width: Option<usize>,
+    /// Current width of the name prefix, grown as longer names appear
+    prefix_width: usize,
+}
+
+fn bar_style(phase: DisplayPhase, prefix_width: usize) -> ProgressStyle {
+    let template = match phase {
+        DisplayPhase::Warmup => format!(
+            "{{prefix:>{}.cyan.bold}} [{{bar:30.yellow/dim}}] {{msg}}",
+            prefix_width
+        ),
+        DisplayPhase::Samples => format!(
+            "{{prefix:>{}.cyan.bold}} [{{bar:30.green/dim}}] {{pos:>5}}/{{len:5}} {{msg}}",
+            prefix_width
+        ),
+    };
+    ProgressStyle::default_bar()
+        .template(&template)
+        .unwrap()
+        .progress_chars("━━╺")
 }
 
 impl BenchmarkProgress {
     /// Create a new progress display manager.
-    pub fn new(quiet: bool) -> Self {
+    ///
+    /// Names are middle-truncated to fit the terminal unless `wide`.
+    pub fn new(quiet: bool, wide: bool) -> Self {
         let is_tty = std::io::stderr().is_terminal();
         Self {
             multi: MultiProgress::new(),
             benches: HashMap::new(),
             is_tty,
             quiet,
+            terminal_width: (!wide)
+                .then(|| crate::output::terminal_width(&console::Term::stderr())),
+            prefix_width: MIN_PREFIX_WIDTH,
         }
     }
 
@@ -73,7 +108,27 @@ impl BenchmarkProgress {
     }
 
     /// Update progress based on a parsed message.
+    ///
+    /// Watchdog warnings are printed even without a terminal, unless quiet.
     pub fn update(&mut self, msg: &ProgressMessage) {
+        if let ProgressPhase::PathologicalSample {
+            index,
+            duration_ns,
+            median_ns,
+        } = msg.phase
+        {
+            if !self.quiet {
+                let sample = PathologicalSample {
+                    index: index as usize,
+                    duration_ns,
+                    median_ns,
+                };
+                let warning = pathological_sample_warning(&msg.bench, &sample);
+                self.multi.suspend(|| eprintln!("{}", warning));
+            }
+            return;
+        }
+
         if !self.is_enabled() {
             return;
         }
@@ -91,19 +146,31 @@ impl BenchmarkProgress {
             ProgressPhase::Complete => {
                 self.finish_bench(&msg.bench);
             }
+            ProgressPhase::PathologicalSample { .. } => {}
+        }
+    }
+
+    /// Prefix showing `bench`, first widening every bar's prefix if its name
+    /// is the longest so far and the terminal has room
+    fn fit_prefix(&mut self, bench: &str) -> String {
+        let width = name_column_width([bench], BAR_RESERVED, self.terminal_width);
+        if width > self.prefix_width {
+            self.prefix_width = width;
+            for (name, state) in &self.benches {
+                state.bar.set_style(bar_style(state.phase, width));
+                state.bar.set_prefix(truncate_middle(name, width));
+            }
         }
+        truncate_middle(bench, self.prefix_width)
     }
 
     fn update_warmup(&mut self, bench: &str, elapsed_ms: u64, target_ms: u64) {
+        let prefix = self.fit_prefix(bench);
+        let style = bar_style(DisplayPhase::Warmup, self.prefix_width);
         let state = self.benches.entry(bench.to_string()).or_insert_with(|| {
             let pb = self.multi.add(ProgressBar::new(target_ms));
-            pb.set_style(
-                ProgressStyle::default_bar()
-                    .template("{prefix:>20.cyan.bold} [{bar:30.yellow/dim}] {msg}")
-                    .unwrap()
-                    .progress_chars("━━╺"),
-            );
-            pb.set_prefix(bench.to_string());
+            pb.set_style(style.clone());
+            pb.set_prefix(prefix.clone());
             BenchState {
                 bar: pb,
                 phase: DisplayPhase::Warmup,
@@ -114,13 +181,8 @@ impl BenchmarkProgress {
         if state.phase != DisplayPhase::Warmup {
             state.bar.finish_and_clear();
             let pb = self.multi.add(ProgressBar::new(target_ms));
-            pb.set_style(
-                ProgressStyle::default_bar()
-                    .template("{prefix:>20.cyan.bold} [{bar:30.yellow/dim}] {msg}")
-                    .unwrap()
-                    .progress_chars("━━╺"),
-            );
-            pb.set_prefix(bench.to_string());
+            pb.set_style(style);
+            pb.set_prefix(prefix);
             state.bar = pb;
             state.phase = DisplayPhase::Warmup;
         }
@@ -148,14 +210,10 @@ impl BenchmarkProgress {
             }
 
             // Create new samples bar
+            let prefix = self.fit_prefix(bench);
             let pb = self.multi.add(ProgressBar::new(total as u64));
-            pb.set_style(
-                ProgressStyle::default_bar()
-                    .template("{prefix:>20.cyan.bold} [{bar:30.green/dim}] {pos:>5}/{len:5} {msg}")
-                    .unwrap()
-                    .progress_chars("━━╺"),
-            );
-            pb.set_prefix(bench.to_string());
+            pb.set_style(bar_style(DisplayPhase::Samples, self.prefix_width));
+            pb.set_prefix(prefix);
             pb.set_message("sampling");
             pb.tick();
 
@@ -0,0 +1,199 @@
+//! Runs kept in a central service, `[baseline] remote_url`
+//!
+//! The service stores the files of a `.benches` tree (behind the
+//! `remote-baselines` feature):
+//!
+//! - `GET <url>/<machine>/<crate>_<benchmark>/` lists a benchmark's files as a
+//!   JSON array of names; 404 when it has none
+//! - `GET <url>/<machine>/<crate>_<benchmark>/<file>` returns one
+//! - `PUT <url>/<machine>/<crate>_<benchmark>/<file>` stores one
+//!
+//! Every request carries `Authorization: Bearer $SIMPLEBENCH_REMOTE_TOKEN`
+//! when it is set. Runs are fetched into `.benches` before benchmarks run and
+//! uploaded in the background after the summary, so the service is never on
+//! the measurement path; an unreachable service only warns.
+
+use colored::*;
+use simplebench_runtime::baseline::{storage_key, BaselineManager};
+use simplebench_runtime::config::BaselineConfig;
+use simplebench_runtime::remote::BaselineStore;
+use simplebench_runtime::BenchmarkInfo;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+/// The service of `[baseline] remote_url` and the benchmarks of a session
+pub struct RemoteBaselines {
+    url: String,
+    store: Arc<dyn BaselineStore>,
+    /// `<crate>_<benchmark>` names of the session's benchmarks
+    benchmarks: Vec<String>,
+}
+
+/// Uploads started by [`RemoteBaselines::spawn_push`]
+pub struct PendingUpload {
+    url: String,
+    handle: JoinHandle<io::Result<usize>>,
+}
+
+impl RemoteBaselines {
+    /// The configured service for `benchmarks`; None without `remote_url`, or
+    /// with a warning when the `remote-baselines` feature is disabled
+    pub fn open(config: &BaselineConfig, benchmarks: &[BenchmarkInfo]) -> Option<Self> {
+        let url = config.remote_url.as_ref()?;
+        let store = connect(url)?;
+        Some(Self {
+            url: url.clone(),
+            store,
+            benchmarks: benchmarks
+                .iter()
+                .map(|bench| storage_key(&bench.module, &bench.name))
+                .collect(),
+        })
+    }
+
+    /// Fetch this machine's runs the service holds and `.benches` doesn't
+    pub fn pull(&self, workspace_root: &Path, config: &BaselineConfig) {
+        let pulled = BaselineManager::for_workspace(workspace_root, config).and_then(|manager| {
+            let _lock = manager.lock_writer()?;
+            manager.pull_runs(self.store.as_ref(), &self.benchmarks)
+        });
+        match pulled {
+            Ok(0) => {}
+            Ok(fetched) => println!(
+                "     {} {} runs from {}",
+                "Fetched".dimmed(),
+                fetched,
+                self.url
+            ),
+            Err(e) => eprintln!(
+                "Warning: Could not fetch baselines from {}: {}; comparing against .benches only",
+                self.url, e
+            ),
+        }
+    }
+
+    /// Upload this machine's runs the service lacks on a background thread
+    pub fn spawn_push(self, workspace_root: PathBuf, config: BaselineConfig) -> PendingUpload {
+        let handle = std::thread::spawn(move || {
+            let manager = BaselineManager::for_workspace(&workspace_root, &config)?;
+            manager.push_runs(self.store.as_ref(), &self.benchmarks)
+        });
+        PendingUpload {
+            url: self.url,
+            handle,
+        }
+    }
+}
+
+impl PendingUpload {
+    /// Wait for the uploads and report them
+    pub fn finish(self) {
+        let uploaded = self
+            .handle
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::new(io::ErrorKind::Other, "upload panicked")));
+        match uploaded {
+            Ok(0) => {}
+            Ok(uploaded) => println!(
+                "{}",
+                format!("Uploaded {} runs to {}", uploaded, self.url).dimmed()
+            ),
+            Err(e) => eprintln!(
+                "Warning: Could not upload baselines to {}: {}; they stay in .benches and are uploaded after the next run",
+                self.url, e
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "remote-baselines")]
+fn connect(url: &str) -> Option<Arc<dyn BaselineStore>> {
+    Some(Arc::new(http::HttpStore::new(url)))
+}
+
+#[cfg(not(feature = "remote-baselines"))]
+fn connect(_url: &str) -> Option<Arc<dyn BaselineStore>> {
+    eprintln!(
+        "Warning: [baseline] remote_url is set, but cargo-simplebench was built without the `remote-baselines` feature; using .benches only"
+    );
+    None
+}
+
+#[cfg(feature = "remote-baselines")]
+mod http {
+    use simplebench_runtime::archive::ArchiveEntry;
+    use simplebench_runtime::remote::BaselineStore;
+    use std::io::{self, Read};
+    use std::time::Duration;
+
+    /// Environment variable holding the service's bearer token
+    const TOKEN_ENV: &str = "SIMPLEBENCH_REMOTE_TOKEN";
+
+    /// [`BaselineStore`] over the HTTP protocol of the module docs
+    pub struct HttpStore {
+        base_url: String,
+        token: Option<String>,
+        agent: ureq::Agent,
+    }
+
+    impl HttpStore {
+        pub fn new(url: &str) -> Self {
+            Self {
+                base_url: url.trim_end_matches('/').to_string(),
+                token: std::env::var(TOKEN_ENV).ok().filter(|t| !t.is_empty()),
+                agent: ureq::AgentBuilder::new()
+                    .timeout_connect(Duration::from_secs(5))
+                    .timeout(Duration::from_secs(30))
+                    .build(),
+            }
+        }
+
+        fn request(&self, method: &str, path: &str) -> ureq::Request {
+            let request = self
+                .agent
+                .request(method, &format!("{}/{}", self.base_url, path));
+            match self.token {
+                Some(ref token) => request.set("Authorization", &format!("Bearer {}", token)),
+                None => request,
+            }
+        }
+    }
+
+    fn io_error(e: impl std::fmt::Display) -> io::Error {
+        io::Error::new(io::ErrorKind::Other, e.to_string())
+    }
+
+    impl BaselineStore for HttpStore {
+        fn list(&self, machine_id: &str, benchmark: &str) -> io::Result<Vec<String>> {
+            let response = match self
+                .request("GET", &format!("{}/{}/", machine_id, benchmark))
+                .call()
+            {
+                Ok(response) => response,
+                Err(ureq::Error::Status(404, _)) => return Ok(Vec::new()),
+                Err(e) => return Err(io_error(e)),
+            };
+            serde_json::from_str(&response.into_string()?).map_err(io_error)
+        }
+
+        fn get(&self, entry: &ArchiveEntry) -> io::Result<Vec<u8>> {
+            let response = self
+                .request("GET", &entry.archive_path())
+                .call()
+                .map_err(io_error)?;
+            let mut contents = Vec::new();
+            response.into_reader().read_to_end(&mut contents)?;
+            Ok(contents)
+        }
+
+        fn put(&self, entry: &ArchiveEntry, contents: &[u8]) -> io::Result<()> {
+            self.request("PUT", &entry.archive_path())
+                .set("Content-Type", "application/json")
+                .send_bytes(contents)
+                .map_err(io_error)?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use crate::output;
+use anyhow::Result;
+use colored::*;
+use simplebench_runtime::baseline::{replay_history, BaselineData, BaselineManager, ReplayReport};
+use simplebench_runtime::config::{BenchmarkConfig, ComparisonConfig};
+use simplebench_runtime::filter::BenchFilter;
+use simplebench_runtime::output::{name_column_width, truncate_middle};
+use std::path::Path;
+
+/// Width of the table's columns after the benchmark name
+const NAME_RESERVED: usize = 38;
+
+/// Comparison settings supplied on the command line, overriding the config file
+pub struct ReplayOptions {
+    pub last: Option<usize>,
+    pub bench_filter: Option<BenchFilter>,
+    pub threshold: Option<f64>,
+    pub confidence: Option<f64>,
+    pub window: Option<usize>,
+    pub cp_threshold: Option<f64>,
+    pub hazard_rate: Option<f64>,
+    pub wide: bool,
+}
+
+impl ReplayOptions {
+    fn apply(&self, config: &mut ComparisonConfig) {
+        if let Some(threshold) = self.threshold {
+            config.threshold = threshold;
+        }
+        if let Some(confidence) = self.confidence {
+            config.confidence_level = confidence;
+        }
+        if let Some(window) = self.window {
+            config.window_size = window;
+        }
+        if let Some(cp_threshold) = self.cp_threshold {
+            config.cp_threshold = cp_threshold;
+        }
+        if let Some(hazard_rate) = self.hazard_rate {
+            config.hazard_rate = hazard_rate;
+        }
+    }
+}
+
+/// Re-judge stored history with the given settings without running anything
+pub fn run_replay(workspace_root: &Path, options: &ReplayOptions) -> Result<()> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    let baseline_manager = BaselineManager::for_workspace(workspace_root, &config.baseline)?;
+    simplebench_runtime::output::set_time_format(config.output.time_format());
+    options.apply(&mut config.comparison);
+
+    let reports = replay_all(&baseline_manager, options, &config.comparison)?;
+    if reports.is_empty() {
+        anyhow::bail!("No stored benchmark history found for this machine");
+    }
+
+    print_replay(&reports, &config.comparison, options.wide);
+    Ok(())
+}
+
+/// Replay every matching benchmark stored for this machine
+fn replay_all(
+    baseline_manager: &BaselineManager,
+    options: &ReplayOptions,
+    config: &ComparisonConfig,
+) -> Result<Vec<ReplayReport>> {
+    let mut reports = Vec::new();
+
+    for stored in baseline_manager.stored_benchmarks(options.bench_filter.as_ref())? {
+        let history = load_history(baseline_manager, &stored.crate_name, &stored.benchmark_name)?;
+        if history.is_empty() {
+            continue;
+        }
+
+        let mut report = replay_history(&history, config, options.last);
+        report.benchmark_name = stored.dir_name;
+        reports.push(report);
+    }
+
+    Ok(reports)
+}
+
+/// All stored runs of a benchmark on this machine, oldest first
+pub(crate) fn load_history(
+    baseline_manager: &BaselineManager,
+    crate_name: &str,
+    bench_name: &str,
+) -> Result<Vec<BaselineData>> {
+    let mut history = Vec::new();
+    for timestamp in baseline_manager.list_runs(crate_name, bench_name)? {
+        if let Some(run) = baseline_manager.load_run(crate_name, bench_name, &timestamp)? {
+            history.push(run);
+        }
+    }
+    Ok(history)
+}
+
+/// Print per-benchmark verdict counts and totals
+fn print_replay(reports: &[ReplayReport], config: &ComparisonConfig, wide: bool) {
+    println!("{}", "Replaying stored history".green().bold());
+    println!(
+        "{}",
+        format!(
+            "threshold {}%, confidence {}, window {}, cp threshold {}, hazard rate {}",
+            config.threshold,
+            config.confidence_level,
+            config.window_size,
+            config.cp_threshold,
+            config.hazard_rate
+        )
+        .dimmed()
+    );
+    println!();
+
+    let width = name_column_width(
+        reports
+            .iter()
+            .map(|report| report.benchmark_name.as_str())
+            .chain(["Benchmark"]),
+        NAME_RESERVED,
+        output::table_width(wide),
+    );
+    println!(
+        "{:<width$} {:>6} {:>12} {:>7} {:>9}",
+        "Benchmark".bold(),
+        "Runs".bold(),
+        "Regressions".bold(),
+        "Flaps".bold(),
+        "Changed".bold()
+    );
+    println!("{}", "─".repeat(width + NAME_RESERVED).dimmed());
+
+    for report in reports {
+        let regressions = report.regressions();
+        let regressions_label = if regressions > 0 {
+            regressions.to_string().red().bold()
+        } else {
+            regressions.to_string().green()
+        };
+        let flaps = report.flaps();
+        let flaps_label = if flaps > 0 {
+            flaps.to_string().yellow()
+        } else {
+            flaps.to_string().normal()
+        };
+
+        println!(
+            "{:<width$} {:>6} {:>12} {:>7} {:>9}",
+            truncate_middle(&report.benchmark_name, width),
+            report.runs.len(),
+            regressions_label,
+            flaps_label,
+            report.changed_verdicts()
+        );
+
+        for run in report.runs.iter().filter(|r| r.comparison.is_regression) {
+            if let Some(ref c) = run.comparison.comparison {
+                println!(
+                    "  {} {} {:+.1}%",
+                    "↳".dimmed(),
+                    run.timestamp.dimmed(),
+                    c.percentage_change
+                );
+            }
+        }
+    }
+
+    let runs: usize = reports.iter().map(|r| r.runs.len()).sum();
+    let regressions: usize = reports.iter().map(|r| r.regressions()).sum();
+    let flaps: usize = reports.iter().map(|r| r.flaps()).sum();
+    let changed: usize = reports.iter().map(|r| r.changed_verdicts()).sum();
+
+    println!("{}", "─".repeat(width + NAME_RESERVED).dimmed());
+    println!(
+        "{} runs replayed across {} benchmarks: {} regressions, {} flaps, {} verdicts differ from the recorded ones",
+        runs,
+        reports.len(),
+        regressions,
+        flaps,
+        changed
+    );
+}
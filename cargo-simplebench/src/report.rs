@@ -0,0 +1,760 @@
+use crate::analyze::{cpu_analysis, format_ns};
+use crate::replay::load_history;
+use anyhow::{Context, Result};
+use simplebench_runtime::baseline::{
+    detect_regression, BaselineData, BaselineManager, ComparisonResult, Exclusion, ExclusionReason,
+    WindowOutcome,
+};
+use simplebench_runtime::columns::{Column, ColumnValue};
+use simplebench_runtime::config::{BenchmarkConfig, ComparisonConfig};
+use simplebench_runtime::filter::BenchFilter;
+use simplebench_runtime::{CoverageRule, CpuAnalysis, CpuMonitor, CpuSnapshot};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Number of buckets a CPU trace is averaged down to
+const TRACE_WIDTH: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+    /// Compact digest for a pull request comment
+    PrComment,
+}
+
+pub struct ReportOptions {
+    pub format: ReportFormat,
+    pub output: Option<PathBuf>,
+    pub bench_filter: Option<BenchFilter>,
+    /// Changes smaller than this percentage are left out of the pr-comment
+    /// digest's table of changed benchmarks
+    pub min_change_pct: f64,
+}
+
+/// Latest stored run of every benchmark, judged against the runs before it
+pub struct Report {
+    pub entries: Vec<ReportEntry>,
+    pub environment: Environment,
+}
+
+pub struct ReportEntry {
+    pub name: String,
+    pub latest: BaselineData,
+    pub comparison: ComparisonResult,
+    /// Frequency/temperature analysis of the latest run, so a reader can tell
+    /// whether a change happened on a throttled or cold machine
+    pub cpu_analysis: CpuAnalysis,
+}
+
+/// Session context printed as the report's appendix
+pub struct Environment {
+    pub machine_id: String,
+    /// Benchmarks of the report by the core they were pinned to
+    pub cores: BTreeMap<usize, Vec<String>>,
+    /// Scaling governor of each used core, read when the report is generated
+    pub governors: BTreeMap<usize, String>,
+}
+
+impl Report {
+    /// Every CPU warning raised in the report, with the benchmark it belongs to
+    pub fn warnings(&self) -> Vec<(&str, String)> {
+        self.entries
+            .iter()
+            .flat_map(|e| {
+                e.cpu_analysis
+                    .warnings
+                    .iter()
+                    .map(move |w| (e.name.as_str(), w.format()))
+            })
+            .collect()
+    }
+}
+
+/// Render a report of the stored results without running anything
+pub fn run_report(workspace_root: &Path, options: &ReportOptions) -> Result<()> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    let baseline_manager = BaselineManager::for_workspace(workspace_root, &config.baseline)?;
+    simplebench_runtime::output::set_time_format(config.output.time_format());
+
+    let columns = config
+        .output
+        .columns_or(Column::REPORT_DEFAULT)
+        .map_err(|e| anyhow::anyhow!("{} in [output] columns", e))?;
+
+    let report = build_report(&baseline_manager, options, &config)?;
+    if report.entries.is_empty() {
+        anyhow::bail!("No stored benchmark results found for this machine");
+    }
+
+    let rendered = match options.format {
+        ReportFormat::Markdown => render_markdown(&report, &columns),
+        ReportFormat::Html => render_html(&report, &columns),
+        ReportFormat::PrComment => render_pr_comment(&report, &columns, options.min_change_pct),
+    };
+
+    match options.output {
+        Some(ref path) => std::fs::write(path, rendered)
+            .with_context(|| format!("Failed to write report to {}", path.display())),
+        None => {
+            print!("{}", rendered);
+            Ok(())
+        }
+    }
+}
+
+fn build_report(
+    baseline_manager: &BaselineManager,
+    options: &ReportOptions,
+    config: &BenchmarkConfig,
+) -> Result<Report> {
+    let mut entries = Vec::new();
+
+    for stored in baseline_manager.stored_benchmarks(options.bench_filter.as_ref())? {
+        let mut history =
+            load_history(baseline_manager, &stored.crate_name, &stored.benchmark_name)?;
+        let Some(latest) = history.pop() else {
+            continue;
+        };
+        entries.push(report_entry(
+            stored.dir_name,
+            latest,
+            &history,
+            &config.comparison,
+            config.measurement.max_cv_pct,
+            config.measurement.cpu_coverage_rule(),
+        ));
+    }
+
+    let mut cores: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for entry in &entries {
+        if let Some(core) = entry.latest.core {
+            cores.entry(core).or_default().push(entry.name.clone());
+        }
+    }
+    let governors = cores
+        .keys()
+        .filter_map(|&core| CpuMonitor::new(core).read_governor().map(|g| (core, g)))
+        .collect();
+
+    Ok(Report {
+        entries,
+        environment: Environment {
+            machine_id: baseline_manager.machine_id().to_string(),
+            cores,
+            governors,
+        },
+    })
+}
+
+/// Compare `latest` against the unflagged runs before it, as the session did
+fn report_entry(
+    name: String,
+    latest: BaselineData,
+    earlier: &[BaselineData],
+    config: &ComparisonConfig,
+    max_cv_pct: Option<f64>,
+    coverage: CoverageRule,
+) -> ReportEntry {
+    let mut accepted: Vec<&BaselineData> = earlier.iter().filter(|b| !b.was_regression).collect();
+    if config.improvement_resets_window {
+        if let Some(start) = accepted.iter().rposition(|b| b.was_improvement) {
+            accepted.drain(..start);
+        }
+    }
+    let window: Vec<BaselineData> = accepted[accepted.len().saturating_sub(config.window_size)..]
+        .iter()
+        .map(|b| (*b).clone())
+        .collect();
+
+    let mut current = latest.to_bench_result();
+    current.check_quality(max_cv_pct);
+    let excluded = Exclusion {
+        reason: ExclusionReason::FlaggedRegression,
+        runs: earlier.len() - accepted.len(),
+    };
+    let mut comparison = detect_regression(&current, &window, config)
+        .with_quality_check(&current)
+        .with_window(WindowOutcome::from_exclusions(window.len(), &[excluded]));
+    comparison.benchmark_name = name.clone();
+
+    ReportEntry {
+        cpu_analysis: cpu_analysis(&latest, coverage),
+        name,
+        latest,
+        comparison,
+    }
+}
+
+/// Verdict label matching the terminal output
+fn verdict(result: &ComparisonResult) -> &'static str {
+    match result.comparison {
+        _ if result.quality_failure.is_some() => "QUALITY FAIL",
+        None if result.history_excluded() => "PROVISIONAL",
+        None => "NEW",
+        Some(ref c) if result.is_regression && c.percentage_change <= c.threshold => {
+            if result.is_tail_regression() {
+                "REGRESS p99"
+            } else {
+                "REGRESS"
+            }
+        }
+        Some(_) if result.is_regression => "REGRESS",
+        Some(ref c) if c.provisional => "PROVISIONAL",
+        Some(ref c) if c.percentage_change < -5.0 => "IMPROVE",
+        Some(_) => "STABLE",
+    }
+}
+
+/// Table cells of `columns` for an entry, "-" where a value wasn't recorded
+fn cells(entry: &ReportEntry, columns: &[Column]) -> Vec<String> {
+    let result = entry.latest.to_bench_result();
+    let stats = &entry.latest.statistics;
+    columns
+        .iter()
+        .map(|&column| {
+            // Timings come from the stored statistics over every sample of the run
+            let stored = match column {
+                Column::Mean => Some(stats.mean),
+                Column::P50 => Some(stats.median),
+                Column::P90 => Some(stats.p90),
+                Column::P99 => Some(stats.p99),
+                Column::Min => Some(stats.min),
+                Column::Max => Some(stats.max),
+                _ => None,
+            };
+            if let Some(ns) = stored {
+                return format_ns(ns);
+            }
+            match column.value(&result, entry.comparison.comparison.as_ref()) {
+                Some(ColumnValue::Time(duration)) => format_ns(duration.as_nanos()),
+                Some(ColumnValue::Text(text)) => text,
+                Some(ColumnValue::Times(times)) => times
+                    .into_iter()
+                    .map(|(label, timing)| format!("{} {}", label, format_ns(timing.as_nanos())))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                None => "-".to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Average `values` down to at most `width` buckets, preserving their order
+fn downsample(values: &[f64], width: usize) -> Vec<f64> {
+    if values.len() <= width {
+        return values.to_vec();
+    }
+    (0..width)
+        .map(|i| {
+            let bucket = &values[i * values.len() / width..(i + 1) * values.len() / width];
+            bucket.iter().sum::<f64>() / bucket.len() as f64
+        })
+        .collect()
+}
+
+fn range(values: &[f64]) -> (f64, f64) {
+    values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        })
+}
+
+/// Unicode block sparkline ("▁▃▆█") of `values`, scaled to their own range
+pub fn sparkline(values: &[f64]) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let values = downsample(values, TRACE_WIDTH);
+    let (lo, hi) = range(&values);
+    values
+        .iter()
+        .map(|&v| {
+            if hi > lo {
+                LEVELS[(((v - lo) / (hi - lo)) * 7.0).round() as usize]
+            } else {
+                LEVELS[0]
+            }
+        })
+        .collect()
+}
+
+/// Inline SVG polyline of `values`, scaled to their own range
+pub fn svg_sparkline(values: &[f64]) -> String {
+    const WIDTH: f64 = 160.0;
+    const HEIGHT: f64 = 24.0;
+    let values = downsample(values, TRACE_WIDTH);
+    let (lo, hi) = range(&values);
+    let step = WIDTH / (values.len().max(2) - 1) as f64;
+
+    let points: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            let y = if hi > lo {
+                HEIGHT - 1.0 - (v - lo) / (hi - lo) * (HEIGHT - 2.0)
+            } else {
+                HEIGHT / 2.0
+            };
+            format!("{:.1},{:.1}", i as f64 * step, y)
+        })
+        .collect();
+
+    format!(
+        r#"<svg width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}"><polyline fill="none" stroke="currentColor" stroke-width="1.5" points="{}"/></svg>"#,
+        points.join(" ")
+    )
+}
+
+/// Frequency (MHz) and temperature (°C) traces of a run's CPU snapshots, in
+/// snapshot order (the monitor samples at intervals across the measurement)
+fn cpu_traces(cpu_samples: &[CpuSnapshot]) -> (Vec<f64>, Vec<f64>) {
+    let frequency = cpu_samples
+        .iter()
+        .filter_map(|s| s.frequency_mhz())
+        .collect();
+    let temperature = cpu_samples
+        .iter()
+        .filter_map(|s| s.temperature_celsius())
+        .collect();
+    (frequency, temperature)
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Markdown table of `entries` with one column per entry of `columns`
+fn markdown_table<'a>(
+    out: &mut String,
+    entries: impl IntoIterator<Item = &'a ReportEntry>,
+    columns: &[Column],
+) {
+    let headers: Vec<&str> = columns.iter().map(|c| c.header()).collect();
+    let _ = writeln!(out, "| Benchmark | {} | Verdict |", headers.join(" | "));
+    let _ = writeln!(out, "|{}", "---|".repeat(columns.len() + 2));
+    for entry in entries {
+        let _ = writeln!(
+            out,
+            "| `{}` | {} | {} |",
+            entry.name,
+            cells(entry, columns).join(" | "),
+            verdict(&entry.comparison)
+        );
+    }
+}
+
+/// Markdown list of the machine, cores and CPU warnings of a report
+fn markdown_environment(out: &mut String, report: &Report) {
+    let env = &report.environment;
+    let _ = writeln!(out, "- Machine: `{}`", env.machine_id);
+    for (core, benches) in &env.cores {
+        let governor = env.governors.get(core).map_or("unknown", String::as_str);
+        let _ = writeln!(
+            out,
+            "- Core {} (governor: {}): {}",
+            core,
+            governor,
+            benches.join(", ")
+        );
+    }
+    let warnings = report.warnings();
+    let _ = writeln!(out, "- CPU warnings: {}", warnings.len());
+    for (name, warning) in warnings {
+        let _ = writeln!(out, "  - `{}`: {}", name, warning);
+    }
+}
+
+pub fn render_markdown(report: &Report, columns: &[Column]) -> String {
+    let mut out = String::new();
+    out.push_str("# SimpleBench Report\n\n");
+    markdown_table(&mut out, &report.entries, columns);
+
+    for entry in &report.entries {
+        let (frequency, temperature) = cpu_traces(&entry.latest.cpu_samples);
+        if frequency.is_empty() && temperature.is_empty() && entry.cpu_analysis.warnings.is_empty()
+        {
+            continue;
+        }
+
+        let _ = writeln!(out, "\n### `{}`\n", entry.name);
+        if !frequency.is_empty() {
+            let (lo, hi) = range(&frequency);
+            let _ = writeln!(
+                out,
+                "- Frequency: `{}` {:.0}–{:.0} MHz",
+                sparkline(&frequency),
+                lo,
+                hi
+            );
+        }
+        if !temperature.is_empty() {
+            let (lo, hi) = range(&temperature);
+            let _ = writeln!(
+                out,
+                "- Temperature: `{}` {:.0}–{:.0}°C",
+                sparkline(&temperature),
+                lo,
+                hi
+            );
+        }
+        for warning in &entry.cpu_analysis.warnings {
+            let _ = writeln!(out, "\n> {}", warning.format());
+        }
+    }
+
+    out.push_str("\n## Environment\n\n");
+    markdown_environment(&mut out, report);
+
+    out
+}
+
+/// Hidden markers around a pr-comment digest, so a bot can find and replace
+/// the comment it posted on an earlier push
+pub const PR_COMMENT_START: &str = "<!-- simplebench:pr-comment:start -->";
+pub const PR_COMMENT_END: &str = "<!-- simplebench:pr-comment:end -->";
+
+fn plural(count: usize, noun: &str) -> String {
+    format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
+}
+
+/// Compact digest for a pull request: a verdict line, the benchmarks that
+/// changed by at least `min_change_pct`, and the full table and environment
+/// folded into collapsible sections
+pub fn render_pr_comment(report: &Report, columns: &[Column], min_change_pct: f64) -> String {
+    let count = |label: &str| {
+        report
+            .entries
+            .iter()
+            .filter(|e| verdict(&e.comparison) == label)
+            .count()
+    };
+    let regressions = count("REGRESS");
+    let mut tally = Vec::new();
+    for (label, noun) in [
+        ("IMPROVE", "improvement"),
+        ("NEW", "new benchmark"),
+        ("QUALITY FAIL", "quality failure"),
+    ] {
+        let n = count(label);
+        if n > 0 {
+            tally.push(plural(n, noun));
+        }
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", PR_COMMENT_START);
+    out.push_str("### SimpleBench\n\n");
+    if regressions == 0 {
+        out.push_str("✅ no regressions");
+    } else {
+        let _ = write!(out, "❌ {}", plural(regressions, "regression"));
+    }
+    if !tally.is_empty() {
+        let _ = write!(out, " · {}", tally.join(" · "));
+    }
+    out.push_str("\n\n");
+
+    // New and failed benchmarks have no change to filter on but still need a look
+    let changed: Vec<&ReportEntry> = report
+        .entries
+        .iter()
+        .filter(|e| {
+            e.comparison.is_regression
+                || e.comparison.quality_failure.is_some()
+                || e.comparison
+                    .comparison
+                    .as_ref()
+                    .map_or(true, |c| c.percentage_change.abs() >= min_change_pct)
+        })
+        .collect();
+    if changed.is_empty() {
+        let _ = writeln!(out, "No benchmark changed by {}% or more.", min_change_pct);
+    } else {
+        markdown_table(&mut out, changed, columns);
+    }
+
+    let _ = writeln!(
+        out,
+        "\n<details><summary>All {}</summary>\n",
+        plural(report.entries.len(), "benchmark")
+    );
+    markdown_table(&mut out, &report.entries, columns);
+    out.push_str("\n</details>\n");
+
+    out.push_str("\n<details><summary>Environment</summary>\n\n");
+    markdown_environment(&mut out, report);
+    out.push_str("\n</details>\n");
+    let _ = writeln!(out, "{}", PR_COMMENT_END);
+
+    out
+}
+
+pub fn render_html(report: &Report, columns: &[Column]) -> String {
+    let mut out = String::new();
+    out.push_str(concat!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>SimpleBench Report</title>\n",
+        "<style>body{font-family:sans-serif;margin:2em}table{border-collapse:collapse}",
+        "td,th{padding:4px 10px;border-bottom:1px solid #ddd;text-align:left}",
+        ".warn{color:#b26a00}.trace{color:#3366cc}</style></head><body>\n",
+        "<h1>SimpleBench Report</h1>\n<table>\n",
+    ));
+    out.push_str("<tr><th>Benchmark</th>");
+    for column in columns {
+        let _ = write!(out, "<th>{}</th>", column.header());
+    }
+    out.push_str("<th>Verdict</th><th>Frequency</th><th>Temperature</th></tr>\n");
+
+    for entry in &report.entries {
+        let (frequency, temperature) = cpu_traces(&entry.latest.cpu_samples);
+        let trace = |values: &[f64], unit: &str| {
+            if values.is_empty() {
+                return "-".to_string();
+            }
+            let (lo, hi) = range(values);
+            format!(
+                "<span class=\"trace\">{}</span> {:.0}–{:.0}{}",
+                svg_sparkline(values),
+                lo,
+                hi,
+                unit
+            )
+        };
+
+        let cells: String = cells(entry, columns)
+            .iter()
+            .map(|cell| format!("<td>{}</td>", escape_html(cell)))
+            .collect();
+        let _ = writeln!(
+            out,
+            "<tr><td><code>{}</code></td>{}<td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape_html(&entry.name),
+            cells,
+            verdict(&entry.comparison),
+            trace(&frequency, " MHz"),
+            trace(&temperature, "°C")
+        );
+        for warning in &entry.cpu_analysis.warnings {
+            let _ = writeln!(
+                out,
+                "<tr><td></td><td colspan=\"{}\" class=\"warn\">{}</td></tr>",
+                columns.len() + 3,
+                escape_html(&warning.format())
+            );
+        }
+    }
+    out.push_str("</table>\n");
+
+    let env = &report.environment;
+    out.push_str("<h2>Environment</h2>\n<ul>\n");
+    let _ = writeln!(
+        out,
+        "<li>Machine: <code>{}</code></li>",
+        escape_html(&env.machine_id)
+    );
+    for (core, benches) in &env.cores {
+        let governor = env.governors.get(core).map_or("unknown", String::as_str);
+        let _ = writeln!(
+            out,
+            "<li>Core {} (governor: {}): {}</li>",
+            core,
+            escape_html(governor),
+            escape_html(&benches.join(", "))
+        );
+    }
+    let warnings = report.warnings();
+    let _ = writeln!(out, "<li>CPU warnings: {}", warnings.len());
+    if !warnings.is_empty() {
+        out.push_str("<ul>\n");
+        for (name, warning) in warnings {
+            let _ = writeln!(
+                out,
+                "<li><code>{}</code>: {}</li>",
+                escape_html(name),
+                escape_html(&warning)
+            );
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</li>\n</ul>\n</body></html>\n");
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simplebench_runtime::BenchResult;
+    use std::time::{Duration, Instant};
+
+    fn entry(name: &str, temps: &[i32]) -> ReportEntry {
+        let result = BenchResult {
+            name: name.to_string(),
+            module: "tests".to_string(),
+            all_timings: vec![Duration::from_nanos(1000); 10],
+            cpu_samples: temps
+                .iter()
+                .zip(0..)
+                .map(|(&t, i)| CpuSnapshot {
+                    timestamp: Instant::now(),
+                    elapsed_ms_from_start: i * 100,
+                    frequency_khz: Some(3_000_000),
+                    temperature_millic: Some(t * 1000),
+                    thread_usage: None,
+                    preempted_samples: None,
+                    energy_uj: None,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let mut latest = BaselineData::from_bench_result(&result, "test-machine".into(), false);
+        latest.core = Some(2);
+        report_entry(
+            name.to_string(),
+            latest,
+            &[],
+            &ComparisonConfig::default(),
+            None,
+            CoverageRule::default(),
+        )
+    }
+
+    /// Ten readings climbing from 60°C to 90°C
+    const THROTTLING: &[i32] = &[60, 60, 65, 70, 75, 80, 85, 90, 90, 90];
+
+    fn report(entries: Vec<ReportEntry>) -> Report {
+        Report {
+            entries,
+            environment: Environment {
+                machine_id: "test-machine".to_string(),
+                cores: BTreeMap::from([(2, vec!["tests_a".to_string()])]),
+                governors: BTreeMap::from([(2, "performance".to_string())]),
+            },
+        }
+    }
+
+    #[test]
+    fn test_sparkline_scales_to_range() {
+        assert_eq!(sparkline(&[1.0, 2.0, 3.0, 8.0]), "▁▂▃█");
+        assert_eq!(sparkline(&[5.0, 5.0]), "▁▁");
+        assert_eq!(
+            sparkline(&(0..400).map(f64::from).collect::<Vec<_>>())
+                .chars()
+                .count(),
+            TRACE_WIDTH
+        );
+        assert_eq!(svg_sparkline(&[1.0, 2.0]).matches(',').count(), 2);
+    }
+
+    #[test]
+    fn test_markdown_report_calls_out_throttling() {
+        let report = report(vec![entry("tests_a", THROTTLING)]);
+        let markdown = render_markdown(&report, Column::REPORT_DEFAULT);
+
+        assert!(markdown.contains("| `tests_a` | 1.00 μs | 1.00 μs | - | NEW |"));
+        assert!(markdown.contains("- Temperature: `▁▁▂▃▅▆▇███` 60–90°C"));
+        assert!(markdown.contains("> ⚠ Thermal throttling detected"));
+        assert!(markdown.contains("- Core 2 (governor: performance): tests_a"));
+        assert!(markdown.contains("- CPU warnings: 1"));
+    }
+
+    #[test]
+    fn test_markdown_report_follows_columns() {
+        let report = report(vec![entry("tests_a", &[60, 60])]);
+
+        let columns = Column::parse_list(&["mean", "cv", "samples", "delta"]).unwrap();
+        let markdown = render_markdown(&report, &columns);
+        assert!(markdown.contains(
+            "| Benchmark | Mean | CV | Samples | Change | Verdict |\n|---|---|---|---|---|---|\n"
+        ));
+        assert!(markdown.contains("| `tests_a` | 1.00 μs | 0.0% | 10 | - | NEW |"));
+
+        let markdown = render_markdown(&report, &[Column::Max, Column::Z]);
+        assert!(markdown.contains("| Benchmark | Max | z | Verdict |\n|---|---|---|---|\n"));
+        assert!(markdown.contains("| `tests_a` | 1.00 μs | - | NEW |"));
+    }
+
+    /// Stored run whose samples sit at `ns`, each `jitter` ns apart
+    fn run(name: &str, ns: u64, jitter: u64) -> BaselineData {
+        let result = BenchResult {
+            name: name.to_string(),
+            module: "tests".to_string(),
+            all_timings: (0..20)
+                .map(|i| Duration::from_nanos(ns + (i % 2) * jitter))
+                .collect(),
+            ..Default::default()
+        };
+        let mut run = BaselineData::from_bench_result(&result, "test-machine".into(), false);
+        // Rebuilt from the statistics, as for stored runs
+        run.percentiles = None;
+        run
+    }
+
+    #[test]
+    fn test_report_marks_excluded_history_provisional() {
+        let mut flagged = run("tests_a", 2000, 10);
+        flagged.was_regression = true;
+        let entry = report_entry(
+            "tests_a".to_string(),
+            run("tests_a", 2000, 10),
+            &[flagged],
+            &ComparisonConfig::default(),
+            None,
+            CoverageRule::default(),
+        );
+        assert!(entry.comparison.history_excluded());
+        assert_eq!(verdict(&entry.comparison), "PROVISIONAL");
+
+        let markdown = render_markdown(&report(vec![entry]), &[Column::Mean]);
+        assert!(markdown.contains("| `tests_a` | 2.00 μs | PROVISIONAL |"));
+    }
+
+    /// Latest run at `ns` after ten runs around 1 µs
+    fn judged(name: &str, ns: u64, jitter: u64) -> ReportEntry {
+        let history: Vec<BaselineData> = (0..10).map(|i| run(name, 995 + i % 3 * 5, 10)).collect();
+        report_entry(
+            name.to_string(),
+            run(name, ns, jitter),
+            &history,
+            &ComparisonConfig::default(),
+            Some(20.0),
+            CoverageRule::default(),
+        )
+    }
+
+    #[test]
+    fn test_pr_comment_snapshot() {
+        let report = report(vec![
+            judged("tests_regress", 1500, 10),
+            judged("tests_regress_more", 2000, 10),
+            judged("tests_improve", 600, 10),
+            judged("tests_stable", 1003, 10),
+            judged("tests_noisy", 1000, 2000),
+            report_entry(
+                "tests_new".to_string(),
+                run("tests_new", 1000, 10),
+                &[],
+                &ComparisonConfig::default(),
+                None,
+                CoverageRule::default(),
+            ),
+        ]);
+        let comment = render_pr_comment(&report, &[Column::Mean, Column::Delta], 1.0);
+        assert_eq!(comment, include_str!("../tests/snapshots/pr_comment.md"));
+    }
+
+    #[test]
+    fn test_html_report_inlines_traces() {
+        let report = report(vec![entry("tests_a", THROTTLING)]);
+        let html = render_html(&report, Column::REPORT_DEFAULT);
+
+        assert!(html.contains("<svg"));
+        assert!(html.contains("class=\"warn\">⚠ Thermal throttling detected"));
+        assert!(html.contains("<li>Core 2 (governor: performance): tests_a</li>"));
+    }
+}
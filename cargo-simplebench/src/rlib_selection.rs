@@ -6,6 +6,9 @@ use std::io::BufRead;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// `-C opt-level` the benchmark crates are compiled with
+pub const BENCH_OPT_LEVEL: &str = "3";
+
 /// Build workspace crates with dev-dependencies and cfg(test) enabled
 ///
 /// Uses a hybrid approach:
@@ -100,7 +103,8 @@ pub fn build_and_select_rlibs(
             .arg(&src_path)
             .args(["--crate-name", &normalized_name])
             .args(["--crate-type", "rlib"])
-            .args(["-C", "opt-level=3"])
+            .arg("-C")
+            .arg(format!("opt-level={}", BENCH_OPT_LEVEL))
             .arg("--cfg")
             .arg("test")
             .arg("-L")
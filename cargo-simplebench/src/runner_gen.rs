@@ -1,14 +1,70 @@
 use crate::metadata::BenchmarkCrate;
-use std::path::Path;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use simplebench_runtime::{RunnerCrate, RunnerInfo};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Build metadata to embed in the runner: the benchmark crates' rlib hashes,
+/// the compiler version and host target, and the current time
+pub fn runner_info(
+    benchmark_crates: &[BenchmarkCrate],
+    rlibs: &HashMap<String, PathBuf>,
+) -> Result<RunnerInfo> {
+    let output = Command::new("rustc")
+        .arg("-vV")
+        .output()
+        .context("Failed to execute rustc -vV")?;
+    let (rustc_version, target) = parse_rustc_verbose(&String::from_utf8_lossy(&output.stdout));
+
+    let mut crates = Vec::new();
+    for crate_info in benchmark_crates {
+        let name = crate_info.name.replace('-', "_");
+        let Some(rlib) = rlibs.get(&name) else {
+            continue;
+        };
+        let bytes =
+            std::fs::read(rlib).with_context(|| format!("Failed to read {}", rlib.display()))?;
+        crates.push(RunnerCrate {
+            name,
+            rlib_hash: format!("{:x}", Sha256::digest(&bytes)),
+        });
+    }
+
+    Ok(RunnerInfo {
+        built_at: chrono::Utc::now().to_rfc3339(),
+        runtime_version: simplebench_runtime::VERSION.to_string(),
+        rustc_version,
+        // The benchmark crates are compiled for the host, without --target
+        target,
+        opt_level: crate::rlib_selection::BENCH_OPT_LEVEL.to_string(),
+        crates,
+    })
+}
+
+/// `rustc --version` line and host triple of `rustc -vV` output
+fn parse_rustc_verbose(output: &str) -> (String, String) {
+    let rustc_version = output.lines().next().unwrap_or("").trim().to_string();
+    let target = output
+        .lines()
+        .find_map(|line| line.strip_prefix("host:"))
+        .unwrap_or("")
+        .trim()
+        .to_string();
+    (rustc_version, target)
+}
 
 /// Generate runner.rs source code
 ///
 /// Creates a Rust program that:
 /// 1. Declares extern crate for all benchmark crates
 /// 2. Supports `--list` flag to output benchmark names as JSON
-/// 3. Runs a single benchmark when SIMPLEBENCH_BENCH_FILTER is set (exact match)
-/// 4. Falls back to streaming mode for backwards compatibility
-pub fn generate_runner(benchmark_crates: &[BenchmarkCrate]) -> String {
+/// 3. Supports `--info` flag to output the embedded build metadata `info` as JSON
+/// 4. Runs a single benchmark when SIMPLEBENCH_SINGLE_BENCH is set, the one
+///    SIMPLEBENCH_BENCH_EXACT names
+/// 5. Falls back to streaming mode for backwards compatibility
+pub fn generate_runner(benchmark_crates: &[BenchmarkCrate], info: &RunnerInfo) -> String {
     let mut code = String::new();
 
     // Add header comment
@@ -27,9 +83,30 @@ pub fn generate_runner(benchmark_crates: &[BenchmarkCrate]) -> String {
 
     code.push('\n');
 
+    // Embed build metadata, printed by --info
+    code.push_str(&format!("const BUILT_AT: &str = {:?};\n", info.built_at));
+    code.push_str(&format!(
+        "const RUNTIME_VERSION: &str = {:?};\n",
+        info.runtime_version
+    ));
+    code.push_str(&format!(
+        "const RUSTC_VERSION: &str = {:?};\n",
+        info.rustc_version
+    ));
+    code.push_str(&format!("const TARGET: &str = {:?};\n", info.target));
+    code.push_str(&format!("const OPT_LEVEL: &str = {:?};\n", info.opt_level));
+    code.push_str("const BENCH_CRATES: &[(&str, &str)] = &[\n");
+    for crate_info in &info.crates {
+        code.push_str(&format!(
+            "    ({:?}, {:?}),\n",
+            crate_info.name, crate_info.rlib_hash
+        ));
+    }
+    code.push_str("];\n\n");
+
     // Add main function with three modes:
     // 1. --list: Output benchmark names as JSON
-    // 2. SIMPLEBENCH_BENCH_FILTER set: Run single benchmark, output JSON
+    // 2. SIMPLEBENCH_SINGLE_BENCH set: Run single benchmark, output JSON
     // 3. Neither: Fall back to streaming mode (backwards compatibility)
     code.push_str("fn main() {\n");
     code.push_str("    let args: Vec<String> = std::env::args().collect();\n\n");
@@ -51,8 +128,29 @@ pub fn generate_runner(benchmark_crates: &[BenchmarkCrate]) -> String {
     code.push_str("        return;\n");
     code.push_str("    }\n\n");
 
+    code.push_str("    // Print embedded build metadata as JSON\n");
+    code.push_str("    if args.iter().any(|a| a == \"--info\") {\n");
+    code.push_str("        simplebench_runtime::print_runner_info_json(\n");
+    code.push_str("            BUILT_AT,\n");
+    code.push_str("            RUNTIME_VERSION,\n");
+    code.push_str("            RUSTC_VERSION,\n");
+    code.push_str("            TARGET,\n");
+    code.push_str("            OPT_LEVEL,\n");
+    code.push_str("            BENCH_CRATES,\n");
+    code.push_str("        );\n");
+    code.push_str("        return;\n");
+    code.push_str("    }\n\n");
+
+    code.push_str("    // Record the build with every run measured\n");
+    code.push_str(
+        "    simplebench_runtime::toolchain::set_build(RUSTC_VERSION, TARGET, OPT_LEVEL);\n\n",
+    );
+
     code.push_str("    // Load configuration (file + env overrides)\n");
-    code.push_str("    let config = simplebench_runtime::BenchmarkConfig::load();\n\n");
+    code.push_str("    let config = simplebench_runtime::BenchmarkConfig::load();\n");
+    code.push_str(
+        "    simplebench_runtime::output::set_time_format(config.output.time_format());\n\n",
+    );
 
     // Mode 2: Single benchmark execution (parallel worker mode)
     code.push_str("    // Mode 2: Single benchmark execution (for parallel mode)\n");
@@ -96,8 +194,9 @@ pub fn generate_runner(benchmark_crates: &[BenchmarkCrate]) -> String {
 pub fn write_runner(
     target_dir: &Path,
     benchmark_crates: &[BenchmarkCrate],
-) -> Result<std::path::PathBuf, std::io::Error> {
-    let runner_code = generate_runner(benchmark_crates);
+    info: &RunnerInfo,
+) -> Result<PathBuf, std::io::Error> {
+    let runner_code = generate_runner(benchmark_crates, info);
     let runner_path = target_dir.join("simplebench_runner.rs");
     std::fs::write(&runner_path, runner_code)?;
     Ok(runner_path)
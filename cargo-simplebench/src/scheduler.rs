@@ -0,0 +1,213 @@
+//! Core assignment for benchmark runs
+//!
+//! Benchmarks are handed out to free cores in order. Benchmarks pinned to a
+//! specific core (via `[cores] overrides` or `#[bench(core = N)]`) always run on
+//! that core, which is reserved out of the general pool. Several benchmarks
+//! pinned to the same core simply queue up behind each other.
+
+use anyhow::{bail, Result};
+use simplebench_runtime::{config::CoresConfig, BenchmarkInfo};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Resolve the pinned core for each benchmark that requests one.
+///
+/// Config overrides take precedence over the benchmark attribute. Errors if a
+/// requested core doesn't exist, or is core 0 without `allow_core_zero`.
+pub fn resolve_core_pins(
+    benchmarks: &[BenchmarkInfo],
+    cores_config: &CoresConfig,
+    cpu_count: usize,
+) -> Result<HashMap<String, usize>> {
+    let mut pins = HashMap::new();
+
+    for bench in benchmarks {
+        let requested = cores_config
+            .overrides
+            .get(&bench.name)
+            .copied()
+            .or(bench.core);
+
+        if let Some(core) = requested {
+            if core >= cpu_count {
+                bail!(
+                    "benchmark '{}' is pinned to core {}, but only {} core(s) exist (0-{})",
+                    bench.name,
+                    core,
+                    cpu_count,
+                    cpu_count.saturating_sub(1)
+                );
+            }
+            if core == 0 && !cores_config.allow_core_zero {
+                bail!(
+                    "benchmark '{}' is pinned to core 0, which is reserved for the system; \
+                     set `allow_core_zero = true` under [cores] to allow it",
+                    bench.name
+                );
+            }
+            pins.insert(bench.name.clone(), core);
+        }
+    }
+
+    Ok(pins)
+}
+
+/// Hands out benchmarks to free cores, honoring per-benchmark core pins
+pub struct CoreScheduler<'a> {
+    pending: VecDeque<&'a BenchmarkInfo>,
+    pins: HashMap<String, usize>,
+    free_general: Vec<usize>,
+    busy_pinned: HashSet<usize>,
+}
+
+impl<'a> CoreScheduler<'a> {
+    /// Create a scheduler over `pool`, reserving every pinned core out of it
+    pub fn new(
+        benchmarks: &'a [BenchmarkInfo],
+        pool: &[usize],
+        pins: HashMap<String, usize>,
+    ) -> Result<Self> {
+        let reserved: HashSet<usize> = pins.values().copied().collect();
+        let mut free_general: Vec<usize> = pool
+            .iter()
+            .copied()
+            .filter(|core| !reserved.contains(core))
+            .collect();
+
+        // Pop from the end, so reverse to hand out the lowest cores first
+        free_general.reverse();
+
+        let has_unpinned = benchmarks.iter().any(|b| !pins.contains_key(&b.name));
+        if has_unpinned && free_general.is_empty() {
+            bail!(
+                "all cores in the pool ({:?}) are reserved by core overrides; \
+                 no core left for the remaining benchmarks",
+                pool
+            );
+        }
+
+        Ok(Self {
+            pending: benchmarks.iter().collect(),
+            pins,
+            free_general,
+            busy_pinned: HashSet::new(),
+        })
+    }
+
+    /// Next benchmark that can start now, with the core to run it on
+    ///
+    /// Picks the earliest pending benchmark whose core is available, so a
+    /// benchmark waiting on a busy pinned core doesn't hold up the others.
+    pub fn next(&mut self) -> Option<(&'a BenchmarkInfo, usize)> {
+        let idx = self
+            .pending
+            .iter()
+            .position(|bench| match self.pins.get(&bench.name) {
+                Some(core) => !self.busy_pinned.contains(core),
+                None => !self.free_general.is_empty(),
+            })?;
+        let bench = self.pending.remove(idx)?;
+
+        let core = match self.pins.get(&bench.name) {
+            Some(&core) => {
+                self.busy_pinned.insert(core);
+                core
+            }
+            None => self.free_general.pop()?,
+        };
+
+        Some((bench, core))
+    }
+
+    /// Mark a core as free again after its benchmark completed
+    pub fn release(&mut self, core: usize) {
+        if !self.busy_pinned.remove(&core) {
+            self.free_general.push(core);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bench(name: &str, core: Option<usize>) -> BenchmarkInfo {
+        BenchmarkInfo {
+            name: name.to_string(),
+            module: "test_crate".to_string(),
+            group: None,
+            core,
+            debug_assertions: false,
+        }
+    }
+
+    #[test]
+    fn test_config_override_wins_over_attribute() {
+        let benchmarks = vec![bench("a", Some(3)), bench("b", Some(5)), bench("c", None)];
+        let mut config = CoresConfig::default();
+        config.overrides.insert("a".to_string(), 7);
+
+        let pins = resolve_core_pins(&benchmarks, &config, 8).unwrap();
+
+        assert_eq!(pins.get("a"), Some(&7));
+        assert_eq!(pins.get("b"), Some(&5));
+        assert_eq!(pins.get("c"), None);
+    }
+
+    #[test]
+    fn test_invalid_pins_are_rejected() {
+        let config = CoresConfig::default();
+
+        // Core doesn't exist
+        assert!(resolve_core_pins(&[bench("a", Some(8))], &config, 8).is_err());
+
+        // Core 0 without explicit allow
+        assert!(resolve_core_pins(&[bench("a", Some(0))], &config, 8).is_err());
+
+        let allow_zero = CoresConfig {
+            allow_core_zero: true,
+            ..Default::default()
+        };
+        assert!(resolve_core_pins(&[bench("a", Some(0))], &allow_zero, 8).is_ok());
+    }
+
+    #[test]
+    fn test_pinned_cores_reserved_and_conflicts_serialized() {
+        let benchmarks = vec![
+            bench("pinned_1", None),
+            bench("pinned_2", None),
+            bench("free_1", None),
+            bench("free_2", None),
+        ];
+        let pins: HashMap<String, usize> = [("pinned_1", 2), ("pinned_2", 2)]
+            .into_iter()
+            .map(|(name, core)| (name.to_string(), core))
+            .collect();
+
+        let mut scheduler = CoreScheduler::new(&benchmarks, &[1, 2], pins).unwrap();
+
+        // pinned_1 takes core 2; pinned_2 must wait for it; core 2 is never
+        // handed to the unpinned benchmarks
+        let (first, core) = scheduler.next().unwrap();
+        assert_eq!((first.name.as_str(), core), ("pinned_1", 2));
+        let (second, core) = scheduler.next().unwrap();
+        assert_eq!((second.name.as_str(), core), ("free_1", 1));
+        assert!(scheduler.next().is_none());
+
+        scheduler.release(2);
+        let (third, core) = scheduler.next().unwrap();
+        assert_eq!((third.name.as_str(), core), ("pinned_2", 2));
+
+        scheduler.release(1);
+        let (fourth, core) = scheduler.next().unwrap();
+        assert_eq!((fourth.name.as_str(), core), ("free_2", 1));
+        assert!(scheduler.next().is_none());
+    }
+
+    #[test]
+    fn test_pool_fully_reserved_is_an_error() {
+        let benchmarks = vec![bench("pinned", None), bench("free", None)];
+        let pins: HashMap<String, usize> = [("pinned".to_string(), 1)].into_iter().collect();
+
+        assert!(CoreScheduler::new(&benchmarks, &[1], pins).is_err());
+    }
+}
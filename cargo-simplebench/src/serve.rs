@@ -0,0 +1,368 @@
+//! Live results over HTTP for `--serve <port>`
+//!
+//! The result-processing loop records each finished benchmark into a
+//! [`LiveSession`]; a server thread (behind the `serve` feature) only ever takes
+//! a read lock on it, so serving requests never holds up benchmark execution.
+
+use serde::Serialize;
+use simplebench_runtime::baseline::{BaselineManager, ComparisonResult};
+use simplebench_runtime::filter::BenchFilter;
+use simplebench_runtime::{BenchFailure, BenchResult};
+use std::sync::{Arc, RwLock};
+
+/// One benchmark of the current session, as served by `/api/last-run`
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveResult {
+    pub name: String,
+    pub module: String,
+    pub group: Option<String>,
+    pub samples: usize,
+    pub mean_ns: u128,
+    pub p50_ns: u128,
+    pub p90_ns: u128,
+    pub p99_ns: u128,
+    /// Change against the baseline window, if there was one
+    pub percentage_change: Option<f64>,
+    pub is_regression: bool,
+    pub skipped: bool,
+    /// Why the benchmark was skipped, e.g. an unmet `min_free_memory`
+    pub skip_reason: Option<String>,
+    /// Failure message when the benchmark failed to run
+    pub error: Option<String>,
+}
+
+/// One stored run, as served by `/api/history/<bench>`
+#[cfg_attr(not(feature = "serve"), allow(dead_code))]
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryRow {
+    pub timestamp: String,
+    pub samples: usize,
+    pub mean_ns: u128,
+    pub median_ns: u128,
+    pub p90_ns: u128,
+    pub p99_ns: u128,
+    pub std_dev_ns: f64,
+    pub was_regression: bool,
+}
+
+/// Results of the running session, shared with the server thread
+#[derive(Debug, Clone, Default)]
+pub struct LiveSession {
+    results: Arc<RwLock<Vec<LiveResult>>>,
+}
+
+impl LiveSession {
+    /// Record a finished benchmark and its comparison verdict
+    pub fn record_result(&self, result: &BenchResult, comparison: Option<&ComparisonResult>) {
+        let live = LiveResult {
+            name: result.name.clone(),
+            module: result.module.clone(),
+            group: result.group.clone(),
+            samples: result.samples,
+            mean_ns: result.percentiles.mean.as_nanos(),
+            p50_ns: result.percentiles.p50().as_nanos(),
+            p90_ns: result.percentiles.p90().as_nanos(),
+            p99_ns: result.percentiles.p99().as_nanos(),
+            percentage_change: comparison
+                .and_then(|c| c.comparison.as_ref())
+                .map(|c| c.percentage_change),
+            is_regression: comparison.is_some_and(|c| c.is_regression),
+            skipped: result.skipped,
+            skip_reason: result.skip_reason.clone(),
+            error: None,
+        };
+        self.push(live);
+    }
+
+    /// Record a benchmark that failed to run
+    pub fn record_failure(&self, failure: &BenchFailure) {
+        self.push(LiveResult {
+            name: failure.name.clone(),
+            module: String::new(),
+            group: None,
+            samples: 0,
+            mean_ns: 0,
+            p50_ns: 0,
+            p90_ns: 0,
+            p99_ns: 0,
+            percentage_change: None,
+            is_regression: false,
+            skipped: false,
+            skip_reason: None,
+            error: Some(failure.message.clone()),
+        });
+    }
+
+    /// Snapshot of the results recorded so far
+    #[cfg_attr(not(feature = "serve"), allow(dead_code))]
+    pub fn snapshot(&self) -> Vec<LiveResult> {
+        self.results
+            .read()
+            .map(|results| results.clone())
+            .unwrap_or_default()
+    }
+
+    fn push(&self, live: LiveResult) {
+        if let Ok(mut results) = self.results.write() {
+            results.push(live);
+        }
+    }
+}
+
+/// Stored history of a `<crate>_<benchmark>` directory on this machine, oldest first
+#[cfg_attr(not(feature = "serve"), allow(dead_code))]
+pub fn history_rows(
+    baseline_manager: &BaselineManager,
+    benchmark: &str,
+) -> std::io::Result<Vec<HistoryRow>> {
+    let filter = BenchFilter::Exact(benchmark.to_string());
+    let Some(stored) = baseline_manager.stored_benchmarks(Some(&filter))?.pop() else {
+        return Ok(vec![]);
+    };
+
+    let mut rows = Vec::new();
+    for timestamp in baseline_manager.list_runs(&stored.crate_name, &stored.benchmark_name)? {
+        if let Some(run) =
+            baseline_manager.load_run(&stored.crate_name, &stored.benchmark_name, &timestamp)?
+        {
+            rows.push(HistoryRow {
+                timestamp,
+                samples: run.statistics.sample_count,
+                mean_ns: run.statistics.mean,
+                median_ns: run.statistics.median,
+                p90_ns: run.statistics.p90,
+                p99_ns: run.statistics.p99,
+                std_dev_ns: run.statistics.std_dev,
+                was_regression: run.was_regression,
+            });
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(feature = "serve")]
+pub use server::start;
+
+/// Fails: serving needs the `serve` feature
+#[cfg(not(feature = "serve"))]
+pub fn start(
+    _port: u16,
+    _baseline_manager: BaselineManager,
+    _session: LiveSession,
+) -> anyhow::Result<ServerHandle> {
+    anyhow::bail!("--serve requires cargo-simplebench built with the `serve` feature")
+}
+
+/// Stand-in for the running server when the `serve` feature is disabled
+#[cfg(not(feature = "serve"))]
+pub struct ServerHandle;
+
+#[cfg(not(feature = "serve"))]
+impl ServerHandle {
+    pub fn stop(self) {}
+}
+
+#[cfg(feature = "serve")]
+mod server {
+    use super::{history_rows, LiveSession};
+    use anyhow::Result;
+    use simplebench_runtime::baseline::BaselineManager;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread::JoinHandle;
+    use std::time::Duration;
+    use tiny_http::{Header, Request, Response, Server};
+
+    /// Single-page viewer polling the API below
+    const VIEWER_HTML: &str = include_str!("viewer.html");
+
+    /// How often the server thread checks for shutdown between requests
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Running server; dropping it stops the thread
+    pub struct ServerHandle {
+        shutdown: Arc<AtomicBool>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl ServerHandle {
+        /// Stop serving and wait for the server thread to exit (done on drop)
+        pub fn stop(self) {}
+    }
+
+    impl Drop for ServerHandle {
+        fn drop(&mut self) {
+            self.shutdown.store(true, Ordering::Relaxed);
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+
+    /// Serve `session` and the stored history on `127.0.0.1:<port>` from a new thread
+    pub fn start(
+        port: u16,
+        baseline_manager: BaselineManager,
+        session: LiveSession,
+    ) -> Result<ServerHandle> {
+        let server = Server::http(("127.0.0.1", port))
+            .map_err(|e| anyhow::anyhow!("Failed to listen on port {}: {}", port, e))?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let shutdown = Arc::clone(&shutdown);
+            std::thread::spawn(move || {
+                while !shutdown.load(Ordering::Relaxed) {
+                    match server.recv_timeout(POLL_INTERVAL) {
+                        Ok(Some(request)) => handle(request, &baseline_manager, &session),
+                        Ok(None) => {}
+                        Err(_) => break,
+                    }
+                }
+            })
+        };
+
+        Ok(ServerHandle {
+            shutdown,
+            thread: Some(thread),
+        })
+    }
+
+    fn handle(request: Request, baseline_manager: &BaselineManager, session: &LiveSession) {
+        let path = request.url().split('?').next().unwrap_or("").to_string();
+
+        let response = match path.as_str() {
+            "/" | "/index.html" => content(VIEWER_HTML.to_string(), "text/html; charset=utf-8"),
+            "/api/benchmarks" => json(baseline_manager.list_benchmarks()),
+            "/api/last-run" => json(Ok::<_, std::io::Error>(session.snapshot())),
+            _ => match path.strip_prefix("/api/history/") {
+                Some(bench) if !bench.is_empty() && !bench.contains(['/', '.']) => {
+                    json(history_rows(baseline_manager, bench))
+                }
+                _ => Response::from_string("not found").with_status_code(404),
+            },
+        };
+
+        let _ = request.respond(response);
+    }
+
+    fn json<T: serde::Serialize>(data: std::io::Result<T>) -> Response<std::io::Cursor<Vec<u8>>> {
+        match data
+            .map_err(|e| e.to_string())
+            .and_then(|d| serde_json::to_string(&d).map_err(|e| e.to_string()))
+        {
+            Ok(body) => content(body, "application/json"),
+            Err(e) => Response::from_string(e).with_status_code(500),
+        }
+    }
+
+    fn content(body: String, content_type: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+        let header = Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes())
+            .expect("static header is valid");
+        Response::from_string(body).with_header(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simplebench_runtime::Percentiles;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn bench_result(name: &str, mean_ms: u64) -> BenchResult {
+        BenchResult {
+            name: name.to_string(),
+            module: "my_crate::benches".to_string(),
+            samples: 10,
+            percentiles: Percentiles {
+                values: vec![
+                    (50.0, Duration::from_millis(mean_ms)),
+                    (90.0, Duration::from_millis(mean_ms)),
+                    (99.0, Duration::from_millis(mean_ms)),
+                ],
+                mean: Duration::from_millis(mean_ms),
+            },
+            all_timings: vec![Duration::from_millis(mean_ms); 10],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_live_session_records_results_in_order() {
+        let live = LiveSession::default();
+        let shared = live.clone();
+
+        live.record_result(&bench_result("bench_a", 5), None);
+        live.record_failure(&BenchFailure {
+            name: "bench_b".to_string(),
+            message: "setup failed: missing fixture".to_string(),
+            ..Default::default()
+        });
+
+        // Clones share the same results, as the server thread does
+        let snapshot = shared.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].name, "bench_a");
+        assert_eq!(snapshot[0].mean_ns, 5_000_000);
+        assert_eq!(snapshot[0].percentage_change, None);
+        assert_eq!(
+            snapshot[1].error.as_deref(),
+            Some("setup failed: missing fixture")
+        );
+    }
+
+    #[test]
+    fn test_history_rows_are_chronological() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        manager
+            .save_baseline("my_crate", &bench_result("bench_a", 5), false)
+            .unwrap();
+
+        let rows = history_rows(&manager, "my_crate_benches-bench_a").unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].mean_ns, 5_000_000);
+        assert_eq!(rows[0].samples, 10);
+        assert!(history_rows(&manager, "my_crate_missing")
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            manager.list_benchmarks().unwrap(),
+            vec!["my_crate_benches-bench_a"]
+        );
+    }
+
+    #[cfg(feature = "serve")]
+    #[test]
+    fn test_server_serves_live_results_and_stops() {
+        use std::io::{Read, Write};
+
+        let get = |port: u16, path: &str| -> String {
+            let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+            write!(stream, "GET {} HTTP/1.0\r\n\r\n", path).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        let live = LiveSession::default();
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let server = start(port, manager, live.clone()).unwrap();
+
+        live.record_result(&bench_result("bench_a", 5), None);
+        let last_run = get(port, "/api/last-run");
+        assert!(last_run.contains("\"name\":\"bench_a\""), "{}", last_run);
+        assert!(get(port, "/").contains("<html"));
+        assert!(get(port, "/api/history/../etc").starts_with("HTTP/1.0 404"));
+
+        drop(server);
+        assert!(std::net::TcpStream::connect(("127.0.0.1", port)).is_err());
+    }
+}
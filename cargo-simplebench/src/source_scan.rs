@@ -0,0 +1,264 @@
+//! Benchmarks declared in the benchmark crates' sources
+//!
+//! The runner only knows the benchmarks that were compiled into it. A `#[bench]`
+//! function behind a `cfg` that's off, in a file no `mod` declares, or edited
+//! after the runner was built is invisible to `--list`, so a `--bench` filter
+//! naming it silently matches nothing. Scanning the sources tells the two apart.
+
+use crate::metadata::BenchmarkCrate;
+use simplebench_runtime::filter::BenchFilter;
+use simplebench_runtime::BenchmarkInfo;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use syn::visit::Visit;
+
+/// Names of the `#[bench]` functions in one source file, in order of appearance
+///
+/// Any attribute path ending in `bench` counts (`#[bench]`,
+/// `#[simplebench::bench(...)]`), inline modules included. None when the file
+/// doesn't parse.
+pub fn scan_source(source: &str) -> Option<Vec<String>> {
+    let file = syn::parse_file(source).ok()?;
+    let mut visitor = BenchVisitor::default();
+    visitor.visit_file(&file);
+    Some(visitor.names)
+}
+
+#[derive(Default)]
+struct BenchVisitor {
+    names: Vec<String>,
+}
+
+impl<'ast> Visit<'ast> for BenchVisitor {
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        let is_bench = item.attrs.iter().any(|attr| {
+            attr.path()
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "bench")
+        });
+        if is_bench {
+            self.names.push(item.sig.ident.to_string());
+        }
+        syn::visit::visit_item_fn(self, item);
+    }
+}
+
+/// Benchmark names declared under `src_dir`, every `.rs` file included
+///
+/// Unreadable and unparsable files are skipped: the compiler reports those.
+pub fn scan_dir(src_dir: &Path) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    let mut pending = vec![src_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                let Ok(source) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+                names.extend(scan_source(&source).unwrap_or_default());
+            }
+        }
+    }
+    names
+}
+
+/// Filtered benchmarks the runner and the sources disagree on, keyed by crate
+#[derive(Debug, Default, PartialEq)]
+pub struct ListingMismatch {
+    /// Declared in the sources but not listed by the runner
+    pub missing: BTreeMap<String, Vec<String>>,
+    /// Listed by the runner but no longer in the sources
+    pub extra: BTreeMap<String, Vec<String>>,
+}
+
+impl ListingMismatch {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty()
+    }
+}
+
+/// Compare the runner's listing with the scanned sources (crate name, names)
+/// for the benchmarks `filter` selects
+///
+/// Crates the sources weren't scanned for are left out of both sides.
+pub fn compare_listing(
+    listed: &[BenchmarkInfo],
+    declared: &[(String, BTreeSet<String>)],
+    filter: &BenchFilter,
+) -> ListingMismatch {
+    let mut mismatch = ListingMismatch::default();
+    for (crate_name, declared_names) in declared {
+        let crate_ident = crate_name.replace('-', "_");
+        let listed_names: BTreeSet<&str> = listed
+            .iter()
+            .filter(|b| b.module.split("::").next() == Some(crate_ident.as_str()))
+            .map(|b| b.name.as_str())
+            .filter(|name| filter.matches(name))
+            .collect();
+
+        let missing: Vec<String> = declared_names
+            .iter()
+            .filter(|name| filter.matches(name) && !listed_names.contains(name.as_str()))
+            .cloned()
+            .collect();
+        let extra: Vec<String> = listed_names
+            .iter()
+            .filter(|name| !declared_names.contains(**name))
+            .map(|name| name.to_string())
+            .collect();
+
+        if !missing.is_empty() {
+            mismatch.missing.insert(crate_name.clone(), missing);
+        }
+        if !extra.is_empty() {
+            mismatch.extra.insert(crate_name.clone(), extra);
+        }
+    }
+    mismatch
+}
+
+/// Scan every benchmark crate and compare with the runner's listing
+pub fn check_listing(
+    crates: &[BenchmarkCrate],
+    listed: &[BenchmarkInfo],
+    filter: &BenchFilter,
+) -> ListingMismatch {
+    let declared: Vec<(String, BTreeSet<String>)> = crates
+        .iter()
+        .map(|c| (c.name.clone(), scan_dir(&c.src_dir)))
+        .collect();
+    compare_listing(listed, &declared, filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = r#"
+        use simplebench_macros::bench;
+
+        pub fn helper() {}
+
+        #[cfg(test)]
+        mod benchmarks {
+            use super::*;
+
+            #[bench]
+            fn bench_plain() {}
+
+            #[simplebench_macros::bench(group = "io", setup = || vec![0u8; 16])]
+            fn bench_with_setup(data: &Vec<u8>) {
+                helper();
+            }
+
+            #[test]
+            fn test_not_a_bench() {}
+
+            mod nested {
+                #[cfg(feature = "slow")]
+                #[bench]
+                fn bench_behind_cfg() {}
+            }
+        }
+    "#;
+
+    fn info(name: &str, module: &str) -> BenchmarkInfo {
+        BenchmarkInfo {
+            name: name.to_string(),
+            module: module.to_string(),
+            group: None,
+            core: None,
+            debug_assertions: false,
+        }
+    }
+
+    #[test]
+    fn test_scan_source_finds_bench_functions() {
+        assert_eq!(
+            scan_source(FIXTURE).unwrap(),
+            vec!["bench_plain", "bench_with_setup", "bench_behind_cfg"]
+        );
+        assert_eq!(scan_source("fn main() {}").unwrap(), Vec::<String>::new());
+        assert!(scan_source("fn broken( {").is_none());
+    }
+
+    #[test]
+    fn test_scan_dir_walks_every_source_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("physics")).unwrap();
+        std::fs::write(dir.path().join("lib.rs"), FIXTURE).unwrap();
+        std::fs::write(
+            dir.path().join("physics").join("collide.rs"),
+            "#[bench]\nfn bench_collide() {}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("broken.rs"), "fn broken( {").unwrap();
+        std::fs::write(
+            dir.path().join("notes.txt"),
+            "#[bench]\nfn bench_text() {}\n",
+        )
+        .unwrap();
+
+        let names: Vec<String> = scan_dir(dir.path()).into_iter().collect();
+        assert_eq!(
+            names,
+            vec![
+                "bench_behind_cfg",
+                "bench_collide",
+                "bench_plain",
+                "bench_with_setup"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare_listing_reports_missing_and_extra() {
+        let declared = vec![
+            (
+                "game-physics".to_string(),
+                BTreeSet::from([
+                    "bench_collide".to_string(),
+                    "bench_collide_sweep".to_string(),
+                    "bench_raycast".to_string(),
+                ]),
+            ),
+            (
+                "game-entities".to_string(),
+                BTreeSet::from(["bench_collide_entities".to_string()]),
+            ),
+        ];
+        let listed = vec![
+            info("bench_collide", "game_physics::tests"),
+            info("bench_collide_old", "game_physics::tests"),
+            info("bench_raycast", "game_physics::tests"),
+            info("bench_collide_entities", "game_entities::tests"),
+            info("bench_collide_unscanned", "other_crate"),
+        ];
+
+        let mismatch = compare_listing(&listed, &declared, &BenchFilter::new("collide", false));
+        assert_eq!(
+            mismatch.missing,
+            BTreeMap::from([(
+                "game-physics".to_string(),
+                vec!["bench_collide_sweep".to_string()]
+            )])
+        );
+        assert_eq!(
+            mismatch.extra,
+            BTreeMap::from([(
+                "game-physics".to_string(),
+                vec!["bench_collide_old".to_string()]
+            )])
+        );
+
+        assert!(
+            compare_listing(&listed, &declared, &BenchFilter::new("raycast", false)).is_empty()
+        );
+    }
+}
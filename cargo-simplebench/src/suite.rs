@@ -0,0 +1,286 @@
+use crate::output;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use colored::*;
+use serde::Serialize;
+use simplebench_runtime::baseline::{count_flaps, BaselineManager};
+use simplebench_runtime::config::BenchmarkConfig;
+use simplebench_runtime::duration_from_nanos;
+use simplebench_runtime::output::{
+    format_duration_human_readable, name_column_width, truncate_middle,
+};
+use simplebench_runtime::run_index::BenchmarkHistory;
+use simplebench_runtime::statistics::{linear_trend, median, Trend};
+use std::path::Path;
+
+/// Width of the table's columns after the benchmark name
+const NAME_RESERVED: usize = 52;
+
+/// Runs the trend, CV and flaps are taken over without `--last`
+const DEFAULT_SUITE_RUNS: usize = 10;
+
+/// Concern added per flap, on the scale of a percent of drift
+const FLAP_CONCERN: f64 = 5.0;
+
+/// How one benchmark's recent history looks
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkHealth {
+    /// `<crate>_<benchmark>` directory name
+    pub benchmark: String,
+    /// Stored runs, not only the ones assessed
+    pub runs: usize,
+    /// Mean of the latest run in nanoseconds
+    pub latest_mean_ns: u128,
+    /// Fit through the assessed runs' means; None with fewer than three
+    pub trend: Option<Trend>,
+    /// Median robust CV of the assessed runs, in percent
+    pub median_cv_pct: f64,
+    /// Flagged runs among the assessed ones whose next run wasn't flagged
+    pub flaps: usize,
+    pub days_since_last_run: f64,
+    /// Sort key, highest first: upward drift over the assessed runs in percent,
+    /// weighted by the fit's R² so noise isn't read as a trend, plus
+    /// [`FLAP_CONCERN`] per flap and the median CV
+    pub concern: f64,
+}
+
+impl BenchmarkHealth {
+    /// Assess the last `last` runs of `history` as of `now`
+    fn assess(history: &BenchmarkHistory, last: usize, now: DateTime<Utc>) -> Option<Self> {
+        let latest = history.runs.last()?;
+        let recent = &history.runs[history.runs.len().saturating_sub(last.max(1))..];
+
+        let means: Vec<f64> = recent.iter().map(|run| run.mean as f64).collect();
+        let trend = linear_trend(&means);
+        let cvs: Vec<f64> = recent.iter().map(|run| run.robust_cv * 100.0).collect();
+        let median_cv_pct = median(&cvs);
+        let flagged: Vec<bool> = recent.iter().map(|run| run.was_regression).collect();
+        let flaps = count_flaps(&flagged);
+
+        let drift = trend.map_or(0.0, |t| t.drift_pct().max(0.0) * t.r_squared);
+        Some(Self {
+            benchmark: history.benchmark.clone(),
+            runs: history.runs.len(),
+            latest_mean_ns: latest.mean,
+            trend,
+            median_cv_pct,
+            flaps,
+            days_since_last_run: (now - latest.timestamp).num_seconds().max(0) as f64 / 86_400.0,
+            concern: drift + flaps as f64 * FLAP_CONCERN + median_cv_pct,
+        })
+    }
+}
+
+/// Assess every benchmark with stored history, most concerning first
+fn assess_suite(
+    histories: &[BenchmarkHistory],
+    last: usize,
+    now: DateTime<Utc>,
+) -> Vec<BenchmarkHealth> {
+    let mut suite: Vec<BenchmarkHealth> = histories
+        .iter()
+        .filter_map(|history| BenchmarkHealth::assess(history, last, now))
+        .collect();
+    suite.sort_by(|a, b| {
+        b.concern
+            .total_cmp(&a.concern)
+            .then_with(|| a.benchmark.cmp(&b.benchmark))
+    });
+    suite
+}
+
+/// Report the health of every benchmark with stored history on this machine
+///
+/// Reads only the run index (`.benches/runs.json`), never the samples of runs
+/// already indexed.
+pub fn run_suite(workspace_root: &Path, last: Option<usize>, json: bool, wide: bool) -> Result<()> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    let baseline_manager = BaselineManager::for_workspace(workspace_root, &config.baseline)?;
+    simplebench_runtime::output::set_time_format(config.output.time_format());
+
+    let last = last.unwrap_or(DEFAULT_SUITE_RUNS);
+    let suite = assess_suite(&baseline_manager.run_summaries()?, last, Utc::now());
+    if suite.is_empty() {
+        anyhow::bail!("No stored benchmark history found for this machine");
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&suite)?);
+    } else {
+        print_suite(
+            &suite,
+            last,
+            config.comparison.threshold,
+            config.measurement.max_cv_pct,
+            wide,
+        );
+    }
+    Ok(())
+}
+
+fn print_suite(
+    suite: &[BenchmarkHealth],
+    last: usize,
+    threshold: f64,
+    max_cv_pct: Option<f64>,
+    wide: bool,
+) {
+    println!("{}", "Suite health".green().bold());
+    println!(
+        "{}",
+        format!(
+            "over the last {} runs of each benchmark, most concerning first",
+            last
+        )
+        .dimmed()
+    );
+    println!();
+
+    let width = name_column_width(
+        suite
+            .iter()
+            .map(|health| health.benchmark.as_str())
+            .chain(["Benchmark"]),
+        NAME_RESERVED,
+        output::table_width(wide),
+    );
+    println!(
+        "{:<width$} {:>5} {:>11} {:>16} {:>7} {:>6} {:>5}",
+        "Benchmark".bold(),
+        "Runs".bold(),
+        "Latest".bold(),
+        "Trend".bold(),
+        "CV".bold(),
+        "Flaps".bold(),
+        "Age".bold()
+    );
+    println!("{}", "─".repeat(width + NAME_RESERVED).dimmed());
+
+    for health in suite {
+        let trend_label = match health.trend {
+            Some(trend) => {
+                let label = format!("{:+.1}%/run R²{:.1}", trend.slope_pct(), trend.r_squared);
+                if trend.drift_pct() * trend.r_squared > threshold {
+                    label.red().bold()
+                } else {
+                    label.normal()
+                }
+            }
+            None => "-".dimmed(),
+        };
+        let cv_label = format!("{:.1}%", health.median_cv_pct);
+        let cv_label = if max_cv_pct.is_some_and(|max| health.median_cv_pct > max) {
+            cv_label.yellow()
+        } else {
+            cv_label.normal()
+        };
+        let flaps_label = if health.flaps > 0 {
+            health.flaps.to_string().yellow()
+        } else {
+            health.flaps.to_string().normal()
+        };
+
+        println!(
+            "{:<width$} {:>5} {:>11} {:>16} {:>7} {:>6} {:>5}",
+            truncate_middle(&health.benchmark, width),
+            health.runs,
+            format_duration_human_readable(duration_from_nanos(health.latest_mean_ns)),
+            trend_label,
+            cv_label,
+            flaps_label,
+            format_age(health.days_since_last_run)
+        );
+    }
+
+    println!("{}", "─".repeat(width + NAME_RESERVED).dimmed());
+    let drifting = suite
+        .iter()
+        .filter(|h| {
+            h.trend
+                .is_some_and(|t| t.drift_pct() * t.r_squared > threshold)
+        })
+        .count();
+    let flapping = suite.iter().filter(|h| h.flaps > 0).count();
+    println!(
+        "{} benchmarks, {} drifting past the {}% threshold, {} flapping",
+        suite.len(),
+        drifting,
+        threshold,
+        flapping
+    );
+}
+
+/// e.g. "5h", "3d"
+fn format_age(days: f64) -> String {
+    if days < 1.0 {
+        format!("{:.0}h", days * 24.0)
+    } else {
+        format!("{:.0}d", days)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simplebench_runtime::run_index::RunSummary;
+
+    fn history(benchmark: &str, means: &[u128], flagged: &[usize]) -> BenchmarkHistory {
+        let start = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        BenchmarkHistory {
+            benchmark: benchmark.to_string(),
+            runs: means
+                .iter()
+                .enumerate()
+                .map(|(i, &mean)| RunSummary {
+                    run: format!("run{}", i),
+                    timestamp: start + chrono::Duration::days(i as i64),
+                    mean,
+                    robust_cv: 0.01,
+                    was_regression: flagged.contains(&i),
+                    was_improvement: false,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_suite_sorted_by_concern() {
+        let now = DateTime::parse_from_rfc3339("2025-01-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let histories = vec![
+            history("c_steady", &[100, 100, 100, 100, 100], &[]),
+            history("c_flaky", &[100, 130, 100, 130, 100], &[1, 3]),
+            history("c_drifting", &[100, 110, 120, 130, 140], &[]),
+        ];
+
+        let suite = assess_suite(&histories, 10, now);
+        let order: Vec<&str> = suite.iter().map(|h| h.benchmark.as_str()).collect();
+        assert_eq!(order, vec!["c_drifting", "c_flaky", "c_steady"]);
+
+        assert_eq!(suite[0].latest_mean_ns, 140);
+        assert!(suite[0].trend.unwrap().slope_pct() > 0.0);
+        assert_eq!(suite[1].flaps, 2);
+        assert!((suite[2].median_cv_pct - 1.0).abs() < 1e-9);
+        assert!((suite[2].days_since_last_run - 5.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_suite_assesses_only_the_last_runs() {
+        let now = Utc::now();
+        // A regression flagged long ago no longer counts as a flap
+        let histories = vec![history("c_b", &[100, 200, 100, 100, 100, 100], &[1])];
+
+        let all = assess_suite(&histories, 10, now);
+        assert_eq!(all[0].flaps, 1);
+
+        let recent = assess_suite(&histories, 3, now);
+        assert_eq!(recent[0].flaps, 0);
+        assert_eq!(recent[0].runs, 6);
+        assert_eq!(recent[0].trend.unwrap().runs, 3);
+    }
+}
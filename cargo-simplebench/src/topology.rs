@@ -1,9 +1,10 @@
 //! CPU topology detection for parallel benchmark execution
 //!
 //! This module detects physical CPU cores and returns a list of usable cores,
-//! excluding core 0 which is reserved for system processes.
+//! excluding core 0 which is reserved for system processes. Cores are grouped by
+//! NUMA node so a run can stay on one socket.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::Path;
 
@@ -12,15 +13,22 @@ use std::path::Path;
 /// Returns one logical CPU per physical core, excluding core 0.
 /// Falls back to `vec![1]` if detection fails.
 pub fn get_usable_cores() -> Vec<usize> {
-    match detect_physical_cores() {
-        Ok(cores) => {
-            if cores.is_empty() {
-                vec![1] // Fallback
-            } else {
-                cores
-            }
-        }
-        Err(_) => vec![1], // Fallback on any error
+    get_usable_cores_by_node()
+        .into_values()
+        .flatten()
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Usable cores grouped by NUMA node, each group sorted.
+///
+/// CPUs without a node link in sysfs count as node 0.
+/// Falls back to `{0: [1]}` if detection fails.
+pub fn get_usable_cores_by_node() -> BTreeMap<usize, Vec<usize>> {
+    match detect_physical_cores(Path::new("/sys/devices/system/cpu")) {
+        Ok(cores) if !cores.is_empty() => cores,
+        _ => BTreeMap::from([(0, vec![1])]), // Fallback on any error
     }
 }
 
@@ -28,18 +36,17 @@ pub fn get_usable_cores() -> Vec<usize> {
 ///
 /// On Linux, reads /sys/devices/system/cpu/cpuN/topology/thread_siblings_list
 /// to identify which logical CPUs share a physical core (hyperthreading).
-/// Returns one CPU per physical core, excluding core 0.
-fn detect_physical_cores() -> Result<Vec<usize>, std::io::Error> {
-    let cpu_base = Path::new("/sys/devices/system/cpu");
-
+/// Returns one CPU per physical core, excluding core 0, keyed by the NUMA node
+/// from the CPU's `nodeK` entry.
+fn detect_physical_cores(cpu_base: &Path) -> Result<BTreeMap<usize, Vec<usize>>, std::io::Error> {
     if !cpu_base.exists() {
         // Not Linux or sysfs not available
-        return Ok(vec![1]);
+        return Ok(BTreeMap::from([(0, vec![1])]));
     }
 
     // Track which physical cores we've already selected a CPU from
     let mut seen_siblings: HashSet<String> = HashSet::new();
-    let mut usable_cores: Vec<usize> = Vec::new();
+    let mut usable_cores: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
 
     // Enumerate CPUs (cpu0, cpu1, ...)
     let mut cpu_dirs: Vec<_> = fs::read_dir(cpu_base)?
@@ -81,18 +88,21 @@ fn detect_physical_cores() -> Result<Vec<usize>, std::io::Error> {
         // If we haven't seen this sibling group, add this CPU
         if !seen_siblings.contains(&siblings) {
             seen_siblings.insert(siblings);
-            usable_cores.push(cpu_num);
+            let node =
+                simplebench_runtime::numa::node_in_cpu_dir(&entry.path()).unwrap_or_default();
+            usable_cores.entry(node).or_default().push(cpu_num);
         }
     }
 
     // Sort for consistent ordering
-    usable_cores.sort();
+    for cores in usable_cores.values_mut() {
+        cores.sort();
+    }
 
     Ok(usable_cores)
 }
 
 /// Get the total number of logical CPUs available.
-#[allow(dead_code)]
 pub fn get_cpu_count() -> usize {
     std::thread::available_parallelism()
         .map(|p| p.get())
@@ -118,6 +128,44 @@ mod tests {
         );
     }
 
+    /// A fake sysfs CPU directory: `cpuN/topology/thread_siblings_list` and `cpuN/nodeK`
+    fn fake_cpu(root: &Path, cpu: usize, siblings: &str, node: Option<usize>) {
+        let dir = root.join(format!("cpu{}", cpu));
+        fs::create_dir_all(dir.join("topology")).unwrap();
+        fs::write(dir.join("topology/thread_siblings_list"), siblings).unwrap();
+        if let Some(node) = node {
+            fs::create_dir(dir.join(format!("node{}", node))).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_detect_physical_cores_groups_by_node() {
+        let root = tempfile::tempdir().unwrap();
+        // Two sockets; cores 1-3 each have a hyperthread sibling
+        fake_cpu(root.path(), 0, "0", Some(0));
+        fake_cpu(root.path(), 1, "1,5", Some(0));
+        fake_cpu(root.path(), 2, "2,6", Some(1));
+        fake_cpu(root.path(), 3, "3,7", Some(1));
+        fake_cpu(root.path(), 5, "1,5", Some(0));
+        fake_cpu(root.path(), 6, "2,6", Some(1));
+        fake_cpu(root.path(), 7, "3,7", Some(1));
+        fs::create_dir(root.path().join("cpufreq")).unwrap();
+
+        let cores = detect_physical_cores(root.path()).unwrap();
+        assert_eq!(cores, BTreeMap::from([(0, vec![1]), (1, vec![2, 3])]));
+    }
+
+    #[test]
+    fn test_detect_physical_cores_without_node_links() {
+        let root = tempfile::tempdir().unwrap();
+        fake_cpu(root.path(), 0, "0", None);
+        fake_cpu(root.path(), 1, "1", None);
+        fake_cpu(root.path(), 2, "2", None);
+
+        let cores = detect_physical_cores(root.path()).unwrap();
+        assert_eq!(cores, BTreeMap::from([(0, vec![1, 2])]));
+    }
+
     #[test]
     fn test_get_usable_cores_sorted() {
         let cores = get_usable_cores();
@@ -0,0 +1,274 @@
+use crate::analyze::{find_benchmark, format_ns};
+use crate::{output, topology};
+use anyhow::{Context, Result};
+use colored::*;
+use simplebench_runtime::baseline::{storage_key, BaselineManager};
+use simplebench_runtime::config::BenchmarkConfig;
+use simplebench_runtime::output::{name_column_width, truncate_middle};
+use simplebench_runtime::statistics::{bootstrap_ratio_ci, mann_whitney_u, mean};
+use simplebench_runtime::BenchmarkInfo;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Bootstrap resamples drawn for the ratio confidence interval
+pub(crate) const BOOTSTRAP_RESAMPLES: usize = 2000;
+
+/// Width of a mean line after the benchmark name
+const NAME_RESERVED: usize = 36;
+
+/// Benchmarks to compare and where their samples come from
+pub struct VersusOptions {
+    pub bench_a: String,
+    pub bench_b: String,
+    pub run_fresh: bool,
+    pub wide: bool,
+}
+
+/// Per-sample comparison of two benchmarks' timings
+#[derive(Debug, Clone, PartialEq)]
+struct Versus {
+    mean_a: f64,
+    mean_b: f64,
+    /// Bootstrap interval of `mean_a / mean_b`
+    ratio_ci: (f64, f64),
+    /// Two-sided Mann-Whitney p-value
+    p_value: f64,
+}
+
+impl Versus {
+    fn compare(a: &[f64], b: &[f64], confidence_level: f64) -> Option<Self> {
+        Some(Self {
+            mean_a: mean(a),
+            mean_b: mean(b),
+            ratio_ci: bootstrap_ratio_ci(a, b, BOOTSTRAP_RESAMPLES, confidence_level)?,
+            p_value: mann_whitney_u(a, b)?.p_value,
+        })
+    }
+
+    fn ratio(&self) -> f64 {
+        self.mean_a / self.mean_b
+    }
+
+    /// Significant when the distributions differ and the whole interval is on one side of 1
+    fn is_significant(&self, confidence_level: f64) -> bool {
+        let (low, high) = self.ratio_ci;
+        self.p_value < 1.0 - confidence_level && (high < 1.0 || low > 1.0)
+    }
+
+    /// "a is 1.85× faster than b" when significant, otherwise "no significant difference"
+    fn verdict(&self, name_a: &str, name_b: &str, confidence_level: f64) -> String {
+        if !self.is_significant(confidence_level) {
+            return format!(
+                "no significant difference between {} and {}",
+                name_a, name_b
+            );
+        }
+        let ratio = self.ratio();
+        if ratio < 1.0 {
+            format!("{} is {:.2}× faster than {}", name_a, 1.0 / ratio, name_b)
+        } else {
+            format!("{} is {:.2}× slower than {}", name_a, ratio, name_b)
+        }
+    }
+}
+
+/// Compare two benchmarks sample by sample and print a verdict
+pub fn run_versus(workspace_root: &Path, options: &VersusOptions) -> Result<()> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    simplebench_runtime::output::set_time_format(config.output.time_format());
+    let confidence_level = config.comparison.confidence_level;
+
+    let (samples_a, samples_b, source) = if options.run_fresh {
+        run_fresh(workspace_root, &options.bench_a, &options.bench_b)?
+    } else {
+        let (a, b) = load_stored(workspace_root, &options.bench_a, &options.bench_b)?;
+        (a, b, "latest stored runs".to_string())
+    };
+
+    let versus = Versus::compare(&samples_a, &samples_b, confidence_level)
+        .context("Not enough distinct samples to compare (need at least one per benchmark)")?;
+
+    println!(
+        "{}",
+        format!("Versus: {} vs {}", options.bench_a, options.bench_b)
+            .cyan()
+            .bold()
+    );
+    println!("{}", format!("Samples: {}", source).dimmed());
+    println!();
+    let names = [options.bench_a.as_str(), options.bench_b.as_str()];
+    let width = name_column_width(names, NAME_RESERVED, output::table_width(options.wide));
+    for (name, samples, mean) in [
+        (&options.bench_a, &samples_a, versus.mean_a),
+        (&options.bench_b, &samples_b, versus.mean_b),
+    ] {
+        println!(
+            "  {:<width$} mean {:>11}  ({} samples)",
+            truncate_middle(name, width),
+            format_ns(mean as u128),
+            samples.len()
+        );
+    }
+    println!();
+
+    let percent = confidence_level * 100.0;
+    println!(
+        "  Time ratio:     {:.3} ({:.0}% CI {:.3} – {:.3})",
+        versus.ratio(),
+        percent,
+        versus.ratio_ci.0,
+        versus.ratio_ci.1
+    );
+    println!("  Mann-Whitney:   p = {:.4}", versus.p_value);
+    println!();
+
+    let verdict = versus.verdict(&options.bench_a, &options.bench_b, confidence_level);
+    if versus.is_significant(confidence_level) {
+        println!(
+            "{} {} ({:.0}% confidence)",
+            "Verdict:".bold(),
+            verdict.green().bold(),
+            percent
+        );
+    } else {
+        println!("{} {}", "Verdict:".bold(), verdict.dimmed());
+    }
+    Ok(())
+}
+
+/// Samples of the latest stored run of each benchmark on this machine
+fn load_stored(
+    workspace_root: &Path,
+    bench_a: &str,
+    bench_b: &str,
+) -> Result<(Vec<f64>, Vec<f64>)> {
+    let mut config =
+        BenchmarkConfig::from_file(workspace_root.join("simplebench.toml")).unwrap_or_default();
+    config.apply_env_overrides();
+    let baseline_manager = BaselineManager::for_workspace(workspace_root, &config.baseline)?;
+    let load = |name: &str| -> Result<Vec<f64>> {
+        let found = find_benchmark(&baseline_manager, name)?;
+        let run = baseline_manager
+            .load_baseline(&found.crate_name, &found.bench_name)?
+            .context(format!("No stored runs for '{}'", name))?;
+        Ok(run.samples.iter().map(|&ns| ns as f64).collect())
+    };
+    Ok((load(bench_a)?, load(bench_b)?))
+}
+
+/// Build the runner and run both benchmarks back to back on one core, in ABBA order
+///
+/// Interleaving is per run: each runner process measures one benchmark in
+/// full, and the A-B-B-A order cancels slow drift (thermals, background load)
+/// between the two. Samples of both runs of a benchmark are pooled.
+fn run_fresh(
+    workspace_root: &Path,
+    bench_a: &str,
+    bench_b: &str,
+) -> Result<(Vec<f64>, Vec<f64>, String)> {
+    let runner = crate::build_runner(workspace_root)?;
+    let benchmarks = crate::discover_benchmarks(&runner, workspace_root)?;
+    let info_a = resolve(&benchmarks, bench_a)?;
+    let info_b = resolve(&benchmarks, bench_b)?;
+
+    let core = topology::get_usable_cores().first().copied().unwrap_or(1);
+    let env = HashMap::from([(
+        "SIMPLEBENCH_WORKSPACE_ROOT".to_string(),
+        workspace_root.display().to_string(),
+    )]);
+
+    println!();
+    println!(
+        "{}",
+        format!(
+            "Running {} and {} interleaved on core {}",
+            bench_a, bench_b, core
+        )
+        .green()
+        .bold()
+    );
+
+    let mut samples_a = run_once(info_a, core, &runner, &env)?;
+    let mut samples_b = run_once(info_b, core, &runner, &env)?;
+    samples_b.extend(run_once(info_b, core, &runner, &env)?);
+    samples_a.extend(run_once(info_a, core, &runner, &env)?);
+    println!();
+
+    Ok((
+        samples_a,
+        samples_b,
+        format!("fresh, ABBA interleaved on core {}", core),
+    ))
+}
+
+/// Run one benchmark to completion and return its wall-time samples in nanoseconds
+fn run_once(
+    info: &BenchmarkInfo,
+    core: usize,
+    runner: &Path,
+    env: &HashMap<String, String>,
+) -> Result<Vec<f64>> {
+    let result = crate::run_benchmark_blocking(info, core, runner, env)?;
+    println!(
+        "{}",
+        format!("  {} done ({} samples)", info.name, result.samples).dimmed()
+    );
+    Ok(result
+        .all_timings
+        .iter()
+        .map(|timing| timing.as_nanos() as f64)
+        .collect())
+}
+
+/// Find a discovered benchmark by the directory its runs are stored in
+/// ([`storage_key`]) or bare name
+fn resolve<'a>(benchmarks: &'a [BenchmarkInfo], name: &str) -> Result<&'a BenchmarkInfo> {
+    if let Some(bench) = benchmarks
+        .iter()
+        .find(|b| storage_key(&b.module, &b.name) == name)
+    {
+        return Ok(bench);
+    }
+
+    let mut bare = benchmarks.iter().filter(|b| b.name == name);
+    match (bare.next(), bare.next()) {
+        (Some(bench), None) => Ok(bench),
+        (Some(_), Some(_)) => anyhow::bail!(
+            "Benchmark name '{}' is ambiguous. Use format: <crate_name>_[<module>-]<benchmark_name>",
+            name
+        ),
+        (None, _) => anyhow::bail!(
+            "Benchmark '{}' not found. Use format: <crate_name>_[<module>-]<benchmark_name>",
+            name
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verdict_requires_significance() {
+        let fast: Vec<f64> = (0..100).map(|i| 100.0 + (i % 7) as f64).collect();
+        let slow: Vec<f64> = (0..100).map(|i| 200.0 + (i % 7) as f64).collect();
+
+        let versus = Versus::compare(&fast, &slow, 0.95).unwrap();
+        assert!(versus.is_significant(0.95));
+        assert_eq!(versus.verdict("a", "b", 0.95), "a is 1.97× faster than b");
+        assert_eq!(
+            Versus::compare(&slow, &fast, 0.95)
+                .unwrap()
+                .verdict("b", "a", 0.95),
+            "b is 1.97× slower than a"
+        );
+
+        let same = Versus::compare(&fast, &fast, 0.95).unwrap();
+        assert_eq!(
+            same.verdict("a", "b", 0.95),
+            "no significant difference between a and b"
+        );
+    }
+}
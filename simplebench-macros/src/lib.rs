@@ -35,8 +35,9 @@ use syn::{parse_macro_input, Expr, ItemFn, Meta};
 /// ```rust,ignore
 /// // Owning: benchmark takes ownership (for operations that consume/mutate data)
 /// #[bench(setup_each = || vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3])]
-/// fn bench_sort(mut data: Vec<i32>) {
+/// fn bench_sort(mut data: Vec<i32>) -> Vec<i32> {
 ///     data.sort();
+///     data
 /// }
 ///
 /// // Borrowing: benchmark takes reference (for fresh read-only data each sample)
@@ -44,10 +45,131 @@ use syn::{parse_macro_input, Expr, ItemFn, Meta};
 /// fn bench_normalize(vectors: &Vec<Vec3>) {
 ///     for v in vectors { v.normalize(); }
 /// }
+///
+/// // Mutable borrow: mutate fresh data in place
+/// #[bench(setup_each = || vec![0u8; 4096])]
+/// fn bench_fill(buf: &mut Vec<u8>) {
+///     buf.fill(0xAB);
+/// }
 /// ```
 ///
 /// The setup expression runs before every sample. The benchmark function can take
-/// either `T` (ownership) or `&T` (reference) depending on whether it consumes the data.
+/// `T` (ownership), `&T` (reference) or `&mut T` (in-place mutation, e.g. filling a
+/// buffer without reallocating it each sample) depending on how it uses the data.
+/// An owning benchmark may return the data, or whatever it did not consume; the
+/// returned value is dropped after timing, like the data of `&T` and `&mut T`.
+///
+/// # Fallible Setup
+///
+/// ```rust,ignore
+/// #[bench(setup = || std::fs::read("fixtures/input.bin"))]
+/// fn bench_parse(data: &Vec<u8>) {
+///     parse(data);
+/// }
+/// ```
+///
+/// Both `setup` and `setup_each` may return `Result<T, E>` with `E: Display`. An
+/// error fails the benchmark with a message naming it instead of panicking. The
+/// parameter type must match `T` exactly (`&Vec<u8>`, not `&[u8]`).
+///
+/// # Conditional Benchmarks
+///
+/// ```rust,ignore
+/// #[bench(skip_if = || !std::path::Path::new("fixtures/large").exists())]
+/// fn bench_large_fixture() {
+///     process_fixtures();
+/// }
+/// ```
+///
+/// `skip_if` takes a closure evaluated once at run time. When it returns true the
+/// benchmark is reported as skipped without running warmup, and no baseline is saved.
+///
+/// ```rust,ignore
+/// #[bench(min_free_memory = "8GB", setup = build_index)]
+/// fn bench_index_lookup(index: &Index) {
+///     index.lookup(42);
+/// }
+/// ```
+///
+/// `min_free_memory` skips the benchmark before setup when less memory than that
+/// is available ("SKIPPED (needs 8GB free, 3.1GB available)"), unless the run
+/// ignores preconditions (`cargo simplebench --ignore-preconditions`).
+///
+/// # Throughput
+///
+/// ```rust,ignore
+/// #[bench(throughput_bytes = 4096, setup = || vec![0u8; 4096])]
+/// fn bench_checksum(data: &Vec<u8>) {
+///     checksum(data);
+/// }
+/// ```
+///
+/// `throughput_bytes` / `throughput_elems` declare how much work one call
+/// processes; the report then shows a rate (e.g. GiB/s or Melem/s) derived from
+/// the mean alongside the timings.
+///
+/// # Groups
+///
+/// ```rust,ignore
+/// #[bench(group = "serialization")]
+/// fn bench_encode() {
+///     encode_message();
+/// }
+/// ```
+///
+/// Groups are independent of module paths: results are printed under a group
+/// header and `cargo simplebench --bench-group serialization` runs only that group.
+///
+/// # Pinned Core
+///
+/// ```rust,ignore
+/// #[bench(core = 7)]
+/// fn bench_entity_update_loop() {
+///     update_entities();
+/// }
+/// ```
+///
+/// The orchestrator always runs this benchmark on core 7, reserving it out of the
+/// general pool. `[cores] overrides` in `simplebench.toml` takes precedence.
+///
+/// # Quality Bar
+///
+/// ```rust,ignore
+/// #[bench(max_cv_pct = 25.0)]
+/// fn bench_network_roundtrip() {
+///     roundtrip();
+/// }
+/// ```
+///
+/// Overrides `[measurement] max_cv_pct` for this benchmark: a run whose CV
+/// exceeds it fails its quality check and gets a SUSPECT verdict.
+///
+/// # Manual Timer
+///
+/// ```rust,ignore
+/// #[bench(manual_timer)]
+/// fn bench_handle_request(timer: &mut simplebench_runtime::Timer) {
+///     let request = decode_request(next_payload());  // not measured
+///     timer.start();
+///     handle(request);
+///     timer.stop();
+/// }
+/// ```
+///
+/// Only the time between `start()` and `stop()` is recorded for each sample. The
+/// benchmark takes a single `&mut Timer` parameter and can't be combined with
+/// `setup`/`setup_each`; calling neither method fails the benchmark at run time.
+///
+/// # Measuring Drop
+///
+/// ```rust,ignore
+/// #[bench(measure = drop, setup_each = build_graph)]
+/// fn bench_drop_graph(graph: Graph) {}
+/// ```
+///
+/// `setup_each` builds a fresh value outside timing and each sample times
+/// exactly `drop(graph)`. The body must be empty; the parameter only names the
+/// type being dropped.
 #[proc_macro_attribute]
 pub fn bench(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args with Punctuated::<Meta, Token![,]>::parse_terminated);
@@ -60,19 +182,155 @@ pub fn bench(args: TokenStream, input: TokenStream) -> TokenStream {
     // Parse optional parameters from attributes
     let mut setup_expr: Option<Expr> = None;
     let mut setup_each_expr: Option<Expr> = None;
+    let mut measure_drop = false;
+    let mut attrs = BenchAttrs::default();
 
     for arg in args {
-        if let Meta::NameValue(nv) = arg {
-            let ident = nv.path.get_ident().map(|i| i.to_string());
+        let nv = match arg {
+            Meta::NameValue(nv) => nv,
+            Meta::Path(ref path) if path.is_ident("manual_timer") => {
+                attrs.manual_timer = true;
+                continue;
+            }
+            other => {
+                return syn::Error::new_spanned(
+                    &other,
+                    format!(
+                        "expected `key = value` in #[bench(...)]; accepted keys: {}",
+                        accepted_keys_list()
+                    ),
+                )
+                .to_compile_error()
+                .into();
+            }
+        };
 
-            match ident.as_deref() {
-                Some("setup") => {
-                    setup_expr = Some(nv.value);
+        let ident = nv.path.get_ident().map(|i| i.to_string());
+
+        match ident.as_deref() {
+            Some("setup") => {
+                setup_expr = Some(nv.value);
+            }
+            Some("setup_each") => {
+                setup_each_expr = Some(nv.value);
+            }
+            Some("skip_if") => {
+                attrs.skip_if = Some(nv.value);
+            }
+            Some("min_free_memory") => match nv.value {
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(ref size),
+                    ..
+                }) => match simplebench_runtime::config::parse_size(&size.value()) {
+                    Ok(bytes) => attrs.min_free_memory = Some(bytes),
+                    Err(e) => return syn::Error::new_spanned(size, e).to_compile_error().into(),
+                },
+                other => {
+                    return syn::Error::new_spanned(
+                        other,
+                        "`min_free_memory` must be a string literal, e.g. #[bench(min_free_memory = \"8GB\")]",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            },
+            Some("measure") => match nv.value {
+                Expr::Path(ref path) if path.path.is_ident("drop") => measure_drop = true,
+                other => {
+                    return syn::Error::new_spanned(
+                        other,
+                        "`measure` only accepts `drop`, e.g. #[bench(measure = drop, setup_each = ...)]",
+                    )
+                    .to_compile_error()
+                    .into();
                 }
-                Some("setup_each") => {
-                    setup_each_expr = Some(nv.value);
+            },
+            Some("throughput_bytes") | Some("throughput_elems") => {
+                if attrs.throughput.is_some() {
+                    return syn::Error::new_spanned(
+                        &nv.path,
+                        "cannot use both `throughput_bytes` and `throughput_elems` - choose one",
+                    )
+                    .to_compile_error()
+                    .into();
                 }
-                _ => {}
+                let variant = if ident.as_deref() == Some("throughput_bytes") {
+                    format_ident!("Bytes")
+                } else {
+                    format_ident!("Elements")
+                };
+                attrs.throughput = Some((variant, nv.value));
+            }
+            Some("core") => match nv.value {
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(ref core),
+                    ..
+                }) => match core.base10_parse::<usize>() {
+                    Ok(core) => attrs.core = Some(core),
+                    Err(e) => return e.to_compile_error().into(),
+                },
+                other => {
+                    return syn::Error::new_spanned(
+                        other,
+                        "`core` must be an integer literal, e.g. #[bench(core = 7)]",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            },
+            Some("max_cv_pct") => match nv.value {
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Float(ref pct),
+                    ..
+                }) => match pct.base10_parse::<f64>() {
+                    Ok(pct) => attrs.max_cv_pct = Some(pct),
+                    Err(e) => return e.to_compile_error().into(),
+                },
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(ref pct),
+                    ..
+                }) => match pct.base10_parse::<f64>() {
+                    Ok(pct) => attrs.max_cv_pct = Some(pct),
+                    Err(e) => return e.to_compile_error().into(),
+                },
+                other => {
+                    return syn::Error::new_spanned(
+                        other,
+                        "`max_cv_pct` must be a number literal, e.g. #[bench(max_cv_pct = 25.0)]",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            },
+            Some("group") => match nv.value {
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(group),
+                    ..
+                }) => {
+                    attrs.group = Some(group.value());
+                }
+                other => {
+                    return syn::Error::new_spanned(
+                        other,
+                        "`group` must be a string literal, e.g. #[bench(group = \"serialization\")]",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            },
+            _ => {
+                let path = &nv.path;
+                let key = quote!(#path).to_string().replace(' ', "");
+                return syn::Error::new_spanned(
+                    &nv.path,
+                    format!(
+                        "unknown #[bench] attribute `{}`; accepted keys: {}",
+                        key,
+                        accepted_keys_list()
+                    ),
+                )
+                .to_compile_error()
+                .into();
             }
         }
     }
@@ -88,6 +346,62 @@ pub fn bench(args: TokenStream, input: TokenStream) -> TokenStream {
     }
 
     // Validate attribute/parameter combinations
+    if measure_drop {
+        if attrs.manual_timer || setup_expr.is_some() {
+            return syn::Error::new_spanned(
+                &input_fn.sig,
+                "`measure = drop` cannot be combined with `setup` or `manual_timer`",
+            )
+            .to_compile_error()
+            .into();
+        }
+        let Some(setup_each) = setup_each_expr else {
+            return syn::Error::new_spanned(
+                &input_fn.sig,
+                "#[bench(measure = drop)] requires `setup_each = ...` to build the value to drop",
+            )
+            .to_compile_error()
+            .into();
+        };
+        if input_fn.sig.inputs.len() != 1 || !matches!(param_kind(&input_fn), ParamKind::Owned) {
+            return syn::Error::new_spanned(
+                &input_fn.sig,
+                "#[bench(measure = drop)] requires a single owned parameter naming the dropped type",
+            )
+            .to_compile_error()
+            .into();
+        }
+        if !input_fn.block.stmts.is_empty() {
+            return syn::Error::new_spanned(
+                &input_fn.block,
+                "#[bench(measure = drop)] times only the drop of the setup value; the function body must be empty",
+            )
+            .to_compile_error()
+            .into();
+        }
+        return generate_measure_drop(fn_name, &fn_name_str, &input_fn, setup_each, &attrs);
+    }
+
+    if attrs.manual_timer {
+        if setup_expr.is_some() || setup_each_expr.is_some() {
+            return syn::Error::new_spanned(
+                &input_fn.sig,
+                "`manual_timer` cannot be combined with `setup` or `setup_each`",
+            )
+            .to_compile_error()
+            .into();
+        }
+        if input_fn.sig.inputs.len() != 1 || !matches!(param_kind(&input_fn), ParamKind::MutRef) {
+            return syn::Error::new_spanned(
+                &input_fn.sig,
+                "#[bench(manual_timer)] requires a single `&mut simplebench_runtime::Timer` parameter",
+            )
+            .to_compile_error()
+            .into();
+        }
+        return generate_with_manual_timer(fn_name, &fn_name_str, &input_fn, &attrs);
+    }
+
     if let Some(setup_each) = setup_each_expr {
         // setup_each requires a parameter
         if !has_params {
@@ -98,7 +412,7 @@ pub fn bench(args: TokenStream, input: TokenStream) -> TokenStream {
             .to_compile_error()
             .into();
         }
-        return generate_with_setup_each(fn_name, &fn_name_str, &input_fn, setup_each);
+        return generate_with_setup_each(fn_name, &fn_name_str, &input_fn, setup_each, &attrs);
     }
 
     if let Some(setup) = setup_expr {
@@ -111,15 +425,26 @@ pub fn bench(args: TokenStream, input: TokenStream) -> TokenStream {
             .to_compile_error()
             .into();
         }
-        if !is_reference_param(&input_fn) {
-            return syn::Error::new_spanned(
-                &input_fn.sig,
-                "#[bench(setup = ...)] requires &T parameter; for T (ownership), use setup_each",
-            )
-            .to_compile_error()
-            .into();
+        match param_kind(&input_fn) {
+            ParamKind::Ref => {}
+            ParamKind::MutRef => {
+                return syn::Error::new_spanned(
+                    &input_fn.sig,
+                    "#[bench(setup = ...)] requires &T parameter; for &mut T, use setup_each",
+                )
+                .to_compile_error()
+                .into();
+            }
+            ParamKind::Owned => {
+                return syn::Error::new_spanned(
+                    &input_fn.sig,
+                    "#[bench(setup = ...)] requires &T parameter; for T (ownership), use setup_each",
+                )
+                .to_compile_error()
+                .into();
+            }
         }
-        generate_with_setup(fn_name, &fn_name_str, &input_fn, setup)
+        generate_with_setup(fn_name, &fn_name_str, &input_fn, setup, &attrs)
     } else {
         // No setup - benchmark must not have parameters
         if has_params {
@@ -130,44 +455,132 @@ pub fn bench(args: TokenStream, input: TokenStream) -> TokenStream {
             .to_compile_error()
             .into();
         }
-        generate_simple(fn_name, &fn_name_str, &input_fn)
+        generate_simple(fn_name, &fn_name_str, &input_fn, &attrs)
     }
 }
 
-/// Check if the first parameter of the function is a reference type
-fn is_reference_param(input_fn: &ItemFn) -> bool {
-    if let Some(syn::FnArg::Typed(pat_type)) = input_fn.sig.inputs.first() {
-        if let syn::Type::Reference(_) = &*pat_type.ty {
-            return true;
-        }
-    }
-    false
+/// Per-benchmark metadata parsed from the attribute, applied to the result
+/// after measurement.
+#[derive(Default)]
+struct BenchAttrs {
+    /// `Throughput` variant name and the per-call amount expression
+    throughput: Option<(syn::Ident, Expr)>,
+    /// Logical group name from `group = "..."`
+    group: Option<String>,
+    /// Core to pin the benchmark to from `core = N`
+    core: Option<usize>,
+    /// Closure from `skip_if = ...`; the run is skipped when it returns true
+    skip_if: Option<Expr>,
+    /// Set by the bare `manual_timer` flag
+    manual_timer: bool,
+    /// Per-benchmark quality bar from `max_cv_pct = N`
+    max_cv_pct: Option<f64>,
+    /// Bytes that must be available before setup, from `min_free_memory = "..."`
+    min_free_memory: Option<u64>,
 }
 
-/// Generate code for a simple benchmark (no setup).
+/// Emit the benchmark function, its run wrapper and the inventory registration.
 ///
-/// The benchmark function is called directly in a closure passed to `measure_simple`.
-fn generate_simple(fn_name: &syn::Ident, fn_name_str: &str, input_fn: &ItemFn) -> TokenStream {
+/// `measure_call` is the expression producing `Result<BenchResult, BenchFailure>`;
+/// the wrapper checks `min_free_memory` and evaluates `skip_if` before it, then
+/// fills in any metadata declared on the attribute.
+fn emit_benchmark(
+    fn_name: &syn::Ident,
+    fn_name_str: &str,
+    input_fn: &ItemFn,
+    measure_call: proc_macro2::TokenStream,
+    attrs: &BenchAttrs,
+) -> TokenStream {
     let run_fn_name = format_ident!("__simplebench_run_{}", fn_name);
 
+    let throughput = attrs.throughput.as_ref().map(|(variant, amount)| {
+        quote! {
+            result.throughput = Some(
+                ::simplebench_runtime::Throughput::#variant((#amount) as u64)
+            );
+        }
+    });
+
+    let (group_field, group_result) = match attrs.group {
+        Some(ref group) => (
+            quote! { Some(#group) },
+            Some(quote! { result.group = Some(#group.to_string()); }),
+        ),
+        None => (quote! { None }, None),
+    };
+
+    let body_hash = body_hash(input_fn);
+
+    // Evaluated once at run time; a skipped run never reaches warmup
+    let measure = match attrs.skip_if {
+        Some(ref skip_if) => quote! {
+            if (#skip_if)() {
+                ::core::result::Result::<_, ::std::boxed::Box<::simplebench_runtime::BenchFailure>>::Ok(
+                    ::simplebench_runtime::BenchResult::skipped(
+                        #fn_name_str,
+                        module_path!(),
+                        "skip_if condition was true".to_string(),
+                    )
+                )
+            } else {
+                #measure_call
+            }
+        },
+        None => measure_call,
+    };
+    let measure = match attrs.min_free_memory {
+        Some(bytes) => quote! {
+            if let ::core::option::Option::Some(skipped) =
+                ::simplebench_runtime::preconditions::check_free_memory(
+                    #fn_name_str,
+                    module_path!(),
+                    #bytes,
+                    config,
+                )
+            {
+                ::core::result::Result::<_, ::std::boxed::Box<::simplebench_runtime::BenchFailure>>::Ok(skipped)
+            } else {
+                #measure
+            }
+        },
+        None => measure,
+    };
+
+    let quality_check = attrs
+        .max_cv_pct
+        .map(|pct| quote! { result.check_quality(::core::option::Option::Some(#pct)); });
+
+    let core_field = match attrs.core {
+        Some(core) => quote! { Some(#core) },
+        None => quote! { None },
+    };
+
     let expanded = quote! {
         #input_fn
 
         fn #run_fn_name(
             config: &::simplebench_runtime::config::BenchmarkConfig
-        ) -> ::simplebench_runtime::BenchResult {
-            ::simplebench_runtime::measure_simple(
-                config,
-                #fn_name_str,
-                module_path!(),
-                || #fn_name(),
-            )
+        ) -> ::core::result::Result<
+            ::simplebench_runtime::BenchResult,
+            ::std::boxed::Box<::simplebench_runtime::BenchFailure>,
+        > {
+            #[allow(unused_mut)]
+            let mut result = #measure?;
+            #throughput
+            #group_result
+            #quality_check
+            result.body_hash = ::core::option::Option::Some(#body_hash.to_string());
+            result.debug_assertions = cfg!(debug_assertions);
+            ::core::result::Result::Ok(result)
         }
 
         ::simplebench_runtime::inventory::submit! {
             ::simplebench_runtime::SimpleBench {
                 name: #fn_name_str,
                 module: module_path!(),
+                group: #group_field,
+                core: #core_field,
+                debug_assertions: cfg!(debug_assertions),
                 run: #run_fn_name,
             }
         }
@@ -176,6 +589,109 @@ fn generate_simple(fn_name: &syn::Ident, fn_name_str: &str, input_fn: &ItemFn) -
     TokenStream::from(expanded)
 }
 
+/// Stable hash of the benchmark body, used to recognize a renamed benchmark.
+///
+/// FNV-1a over the body's token stream, so formatting and comments don't affect it.
+fn body_hash(input_fn: &ItemFn) -> String {
+    let block = &input_fn.block;
+    let tokens = quote!(#block).to_string();
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in tokens.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Attribute keys accepted by `#[bench(...)]`, listed in unknown-key errors
+const ACCEPTED_KEYS: &[&str] = &[
+    "setup",
+    "setup_each",
+    "throughput_bytes",
+    "throughput_elems",
+    "group",
+    "core",
+    "skip_if",
+    "manual_timer",
+    "measure",
+    "max_cv_pct",
+    "min_free_memory",
+];
+
+/// Accepted keys formatted for diagnostics ("`setup`, `setup_each`, ...")
+fn accepted_keys_list() -> String {
+    ACCEPTED_KEYS
+        .iter()
+        .map(|key| format!("`{}`", key))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// How a benchmark function receives its setup data
+enum ParamKind {
+    /// `T` - takes ownership
+    Owned,
+    /// `&T` - shared borrow
+    Ref,
+    /// `&mut T` - mutable borrow
+    MutRef,
+}
+
+/// Classify the first parameter of the function as `T`, `&T` or `&mut T`
+fn param_kind(input_fn: &ItemFn) -> ParamKind {
+    if let Some(syn::FnArg::Typed(pat_type)) = input_fn.sig.inputs.first() {
+        if let syn::Type::Reference(reference) = &*pat_type.ty {
+            return if reference.mutability.is_some() {
+                ParamKind::MutRef
+            } else {
+                ParamKind::Ref
+            };
+        }
+    }
+    ParamKind::Owned
+}
+
+/// Generate code for a simple benchmark (no setup).
+///
+/// The benchmark function is called directly in a closure passed to `measure_simple`.
+fn generate_simple(
+    fn_name: &syn::Ident,
+    fn_name_str: &str,
+    input_fn: &ItemFn,
+    attrs: &BenchAttrs,
+) -> TokenStream {
+    let measure_call = quote! {
+        ::simplebench_runtime::measure_simple(
+            config,
+            #fn_name_str,
+            module_path!(),
+            || #fn_name(),
+        )
+    };
+
+    emit_benchmark(fn_name, fn_name_str, input_fn, measure_call, attrs)
+}
+
+/// Generate code for a benchmark that times itself with a `Timer`.
+fn generate_with_manual_timer(
+    fn_name: &syn::Ident,
+    fn_name_str: &str,
+    input_fn: &ItemFn,
+    attrs: &BenchAttrs,
+) -> TokenStream {
+    let measure_call = quote! {
+        ::simplebench_runtime::measure_with_manual_timer(
+            config,
+            #fn_name_str,
+            module_path!(),
+            |timer| #fn_name(timer),
+        )
+    };
+
+    emit_benchmark(fn_name, fn_name_str, input_fn, measure_call, attrs)
+}
+
 /// Generate code for a benchmark with setup.
 ///
 /// The setup expression runs once, then the benchmark function receives
@@ -189,96 +705,102 @@ fn generate_with_setup(
     fn_name_str: &str,
     input_fn: &ItemFn,
     setup_expr: Expr,
+    attrs: &BenchAttrs,
 ) -> TokenStream {
-    let run_fn_name = format_ident!("__simplebench_run_{}", fn_name);
-
     // The setup_expr could be:
     // - A function name: `create_data` -> call as `create_data()`
     // - A closure: `|| random_vectors(1000)` -> call as `(|| random_vectors(1000))()`
     // Both are handled by wrapping in parens and calling with ()
-    let expanded = quote! {
-        #input_fn
-
-        fn #run_fn_name(
-            config: &::simplebench_runtime::config::BenchmarkConfig
-        ) -> ::simplebench_runtime::BenchResult {
-            ::simplebench_runtime::measure_with_setup(
-                config,
-                #fn_name_str,
-                module_path!(),
-                || (#setup_expr)(),
-                |data| #fn_name(data),
-            )
-        }
-
-        ::simplebench_runtime::inventory::submit! {
-            ::simplebench_runtime::SimpleBench {
-                name: #fn_name_str,
-                module: module_path!(),
-                run: #run_fn_name,
-            }
-        }
+    let measure_call = quote! {
+        ::simplebench_runtime::measure_with_setup(
+            config,
+            #fn_name_str,
+            module_path!(),
+            || (#setup_expr)(),
+            |data| #fn_name(data),
+        )
     };
 
-    TokenStream::from(expanded)
+    emit_benchmark(fn_name, fn_name_str, input_fn, measure_call, attrs)
 }
 
 /// Generate code for a benchmark with setup_each (runs before every sample).
 ///
-/// Detects whether the benchmark takes `T` (ownership) or `&T` (reference)
-/// and generates the appropriate measurement function call.
+/// Detects whether the benchmark takes `T` (ownership), `&T` (reference) or
+/// `&mut T` (mutable reference) and generates the appropriate measurement
+/// function call.
 fn generate_with_setup_each(
     fn_name: &syn::Ident,
     fn_name_str: &str,
     input_fn: &ItemFn,
     setup_expr: Expr,
+    attrs: &BenchAttrs,
 ) -> TokenStream {
-    let run_fn_name = format_ident!("__simplebench_run_{}", fn_name);
-    let is_ref = is_reference_param(input_fn);
-
-    let measure_call = if is_ref {
+    let measure_fn = match param_kind(input_fn) {
         // Benchmark takes &T - use borrowing version
-        quote! {
-            ::simplebench_runtime::measure_with_setup_each_ref(
-                config,
-                #fn_name_str,
-                module_path!(),
-                || (#setup_expr)(),
-                |data| #fn_name(data),
+        ParamKind::Ref => format_ident!("measure_with_setup_each_ref"),
+        // Benchmark takes &mut T - mutate in place, drop outside timing
+        ParamKind::MutRef => format_ident!("measure_with_setup_each_mut"),
+        // Benchmark takes T - use owning version
+        ParamKind::Owned => format_ident!("measure_with_setup_each"),
+    };
+
+    let measure_call = quote! {
+        ::simplebench_runtime::#measure_fn(
+            config,
+            #fn_name_str,
+            module_path!(),
+            || (#setup_expr)(),
+            |data| #fn_name(data),
+        )
+    };
+
+    emit_benchmark(fn_name, fn_name_str, input_fn, measure_call, attrs)
+}
+
+/// Generate code for a benchmark timing only the drop of its setup value.
+///
+/// The parameter's type is passed to `measure_drop` explicitly, since the
+/// empty benchmark function is never called with the data.
+fn generate_measure_drop(
+    fn_name: &syn::Ident,
+    fn_name_str: &str,
+    input_fn: &ItemFn,
+    setup_expr: Expr,
+    attrs: &BenchAttrs,
+) -> TokenStream {
+    let value_ty = match input_fn.sig.inputs.first() {
+        Some(syn::FnArg::Typed(pat_type)) => &pat_type.ty,
+        _ => {
+            return syn::Error::new_spanned(
+                &input_fn.sig,
+                "#[bench(measure = drop)] requires a single owned parameter naming the dropped type",
             )
+            .to_compile_error()
+            .into();
         }
-    } else {
-        // Benchmark takes T - use owning version
-        quote! {
-            ::simplebench_runtime::measure_with_setup_each(
+    };
+
+    let measure_call = quote! {
+        {
+            // Ties the signature to the dropped type and keeps the function in use
+            let _: fn(#value_ty) = #fn_name;
+            ::simplebench_runtime::measure_drop::<#value_ty, _, _>(
                 config,
                 #fn_name_str,
                 module_path!(),
                 || (#setup_expr)(),
-                |data| #fn_name(data),
             )
         }
     };
 
-    let expanded = quote! {
-        #input_fn
-
-        fn #run_fn_name(
-            config: &::simplebench_runtime::config::BenchmarkConfig
-        ) -> ::simplebench_runtime::BenchResult {
-            #measure_call
-        }
+    // The parameter exists only to name the type, so it is never read
+    let mut input_fn = input_fn.clone();
+    input_fn
+        .attrs
+        .push(syn::parse_quote!(#[allow(unused_variables)]));
 
-        ::simplebench_runtime::inventory::submit! {
-            ::simplebench_runtime::SimpleBench {
-                name: #fn_name_str,
-                module: module_path!(),
-                run: #run_fn_name,
-            }
-        }
-    };
-
-    TokenStream::from(expanded)
+    emit_benchmark(fn_name, fn_name_str, &input_fn, measure_call, attrs)
 }
 
 #[cfg(test)]
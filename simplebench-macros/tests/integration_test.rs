@@ -40,12 +40,85 @@ fn bench_sort_owning(mut data: Vec<i32>) {
     data.sort();
 }
 
+// Test setup_each handing its data back, so the drop is untimed
+#[bench(setup_each = || vec![5, 3, 8, 1, 9, 4, 7, 2, 6])]
+fn bench_sort_returning(mut data: Vec<i32>) -> Vec<i32> {
+    data.sort();
+    data
+}
+
 // Test setup_each with reference (fresh data each sample, borrowed)
 #[bench(setup_each = || vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10])]
 fn bench_sum_ref(data: &[i32]) {
     let _sum: i32 = data.iter().sum();
 }
 
+// Test setup_each with mutable reference (mutated in place)
+#[bench(setup_each = || vec![0u8; 256])]
+fn bench_fill_mut(buf: &mut [u8]) {
+    buf.fill(0xAB);
+}
+
+// Test throughput annotation (value stored on the result)
+#[bench(throughput_bytes = 4 * 1024, setup = || vec![0u8; 4 * 1024])]
+fn bench_checksum_bytes(data: &[u8]) {
+    let _sum: u64 = data.iter().map(|&b| b as u64).sum();
+}
+
+// Test group annotation (independent of module path)
+#[bench(group = "arithmetic")]
+fn bench_grouped_division() {
+    let _ = std::hint::black_box(10) / 3;
+}
+
+// Test fallible setup (error fails the benchmark instead of panicking)
+struct Fixture(Vec<u8>);
+
+fn load_fixture() -> std::io::Result<Fixture> {
+    std::fs::read("/nonexistent/simplebench/fixture.bin").map(Fixture)
+}
+
+#[bench(setup = load_fixture)]
+fn bench_parse_fixture(fixture: &Fixture) {
+    let _ = std::hint::black_box(fixture.0.len());
+}
+
+// Test skip_if (condition evaluated at run time)
+#[bench(skip_if = || true, group = "conditional")]
+fn bench_always_skipped() {
+    panic!("skipped benchmarks must not run");
+}
+
+// Test min_free_memory (no machine has this much available)
+#[bench(min_free_memory = "1000TB", group = "conditional")]
+fn bench_needs_memory() {
+    let _ = std::hint::black_box(1) + 1;
+}
+
+// Test manual_timer (only the start/stop region is measured)
+#[bench(manual_timer)]
+fn bench_manual_region(timer: &mut simplebench_runtime::Timer) {
+    // Excluded from the measurement
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    timer.start();
+    let _ = std::hint::black_box(2) * 3;
+    timer.stop();
+}
+
+// Test measure = drop (only dropping the setup value is measured)
+#[bench(measure = drop, setup_each = || vec![vec![0u8; 64]; 256])]
+fn bench_drop_nested(data: Vec<Vec<u8>>) {}
+
+// Test a per-benchmark quality bar on a benchmark whose every other call sleeps
+static NOISY_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[bench(max_cv_pct = 10)]
+fn bench_noisy_sleep() {
+    if NOISY_CALLS.fetch_add(1, std::sync::atomic::Ordering::Relaxed) % 2 == 0 {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+}
+
 #[test]
 fn test_benchmarks_are_registered() {
     // Collect all registered benchmarks via inventory
@@ -89,6 +162,10 @@ fn test_benchmarks_are_registered() {
         bench_names.contains(&"bench_sum_ref"),
         "bench_sum_ref not found"
     );
+    assert!(
+        bench_names.contains(&"bench_fill_mut"),
+        "bench_fill_mut not found"
+    );
 }
 
 #[test]
@@ -116,15 +193,18 @@ fn test_json_serialization() {
         module: "test_module".to_string(),
         samples: 10,
         percentiles: Percentiles {
-            p50: Duration::from_millis(5),
-            p90: Duration::from_millis(10),
-            p99: Duration::from_millis(15),
+            values: vec![
+                (50.0, Duration::from_millis(5)),
+                (90.0, Duration::from_millis(10)),
+                (99.0, Duration::from_millis(15)),
+            ],
             mean: Duration::from_millis(8),
         },
         all_timings: vec![Duration::from_millis(5); 10],
         cpu_samples: vec![],
         warmup_ms: Some(100),
         warmup_iterations: Some(1000),
+        ..Default::default()
     };
 
     let temp_file = NamedTempFile::new().unwrap();
@@ -139,3 +219,166 @@ fn test_json_serialization() {
     assert_eq!(result.module, loaded.module);
     assert_eq!(result.samples, loaded.samples);
 }
+
+#[test]
+fn test_throughput_attribute_sets_result() {
+    use simplebench_runtime::config::BenchmarkConfig;
+    use simplebench_runtime::Throughput;
+
+    let bench = simplebench_runtime::inventory::iter::<SimpleBench>()
+        .find(|b| b.name == "bench_checksum_bytes")
+        .expect("bench_checksum_bytes not registered");
+
+    let mut config = BenchmarkConfig::default();
+    config.measurement.samples = 5;
+    config.measurement.warmup_duration = std::time::Duration::ZERO;
+
+    let result = (bench.run)(&config).unwrap();
+    assert_eq!(result.throughput, Some(Throughput::Bytes(4096)));
+}
+
+#[test]
+fn test_max_cv_attribute_overrides_config() {
+    use simplebench_runtime::config::BenchmarkConfig;
+
+    let bench = simplebench_runtime::inventory::iter::<SimpleBench>()
+        .find(|b| b.name == "bench_noisy_sleep")
+        .expect("bench_noisy_sleep not registered");
+
+    // The configured bar is loose enough for any run; the attribute's isn't
+    let mut config = BenchmarkConfig::default();
+    config.measurement.samples = 20;
+    config.measurement.warmup_duration = std::time::Duration::ZERO;
+    config.measurement.max_cv_pct = Some(100_000.0);
+
+    let result = (bench.run)(&config).unwrap();
+    let failure = result
+        .quality_failure
+        .expect("noisy run passed its quality bar");
+    assert_eq!(failure.max_cv_pct, 10.0);
+}
+
+#[test]
+fn test_build_profile_is_recorded() {
+    use simplebench_runtime::config::BenchmarkConfig;
+
+    let bench = simplebench_runtime::inventory::iter::<SimpleBench>()
+        .find(|b| b.name == "bench_addition")
+        .expect("bench_addition not registered");
+    // Evaluated in this crate, so it follows the test profile's setting
+    assert_eq!(bench.debug_assertions, cfg!(debug_assertions));
+
+    let mut config = BenchmarkConfig::default();
+    config.measurement.samples = 5;
+    config.measurement.warmup_duration = std::time::Duration::ZERO;
+    let result = (bench.run)(&config).unwrap();
+    assert_eq!(result.debug_assertions, cfg!(debug_assertions));
+}
+
+#[test]
+fn test_group_attribute_registers_group() {
+    let grouped = simplebench_runtime::inventory::iter::<SimpleBench>()
+        .find(|b| b.name == "bench_grouped_division")
+        .expect("bench_grouped_division not registered");
+    assert_eq!(grouped.group, Some("arithmetic"));
+
+    let ungrouped = simplebench_runtime::inventory::iter::<SimpleBench>()
+        .find(|b| b.name == "bench_addition")
+        .expect("bench_addition not registered");
+    assert_eq!(ungrouped.group, None);
+}
+
+#[test]
+fn test_fallible_setup_reports_failure() {
+    use simplebench_runtime::config::BenchmarkConfig;
+
+    let bench = simplebench_runtime::inventory::iter::<SimpleBench>()
+        .find(|b| b.name == "bench_parse_fixture")
+        .expect("bench_parse_fixture not registered");
+
+    let failure = (bench.run)(&BenchmarkConfig::default()).unwrap_err();
+    assert_eq!(failure.name, "bench_parse_fixture");
+    assert!(failure.message.starts_with("setup failed: "));
+}
+
+#[test]
+fn test_skip_if_skips_without_measuring() {
+    use simplebench_runtime::config::BenchmarkConfig;
+
+    let bench = simplebench_runtime::inventory::iter::<SimpleBench>()
+        .find(|b| b.name == "bench_always_skipped")
+        .expect("bench_always_skipped not registered");
+
+    let result = (bench.run)(&BenchmarkConfig::default()).unwrap();
+    assert!(result.skipped);
+    assert!(result.skip_reason.is_some());
+    assert!(result.all_timings.is_empty());
+    assert_eq!(result.warmup_iterations, None);
+    // Attribute metadata still applies, so the run is listed under its group
+    assert_eq!(result.group.as_deref(), Some("conditional"));
+}
+
+#[test]
+fn test_min_free_memory_skips_before_setup() {
+    use simplebench_runtime::config::BenchmarkConfig;
+
+    let bench = simplebench_runtime::inventory::iter::<SimpleBench>()
+        .find(|b| b.name == "bench_needs_memory")
+        .expect("bench_needs_memory not registered");
+
+    let mut config = BenchmarkConfig::default();
+    config.measurement.samples = 5;
+    config.measurement.warmup_duration = std::time::Duration::ZERO;
+
+    // Where available memory can't be read, the precondition doesn't apply
+    if simplebench_runtime::preconditions::available_memory_bytes().is_some() {
+        let result = (bench.run)(&config).unwrap();
+        assert!(result.skipped);
+        let reason = result.skip_reason.unwrap();
+        assert!(reason.starts_with("needs 1000TB free, "), "{}", reason);
+        assert!(reason.ends_with(" available"), "{}", reason);
+    }
+
+    config.measurement.ignore_preconditions = true;
+    let result = (bench.run)(&config).unwrap();
+    assert!(!result.skipped);
+    assert_eq!(result.all_timings.len(), 5);
+}
+
+#[test]
+fn test_manual_timer_excludes_unmeasured_work() {
+    use simplebench_runtime::config::BenchmarkConfig;
+
+    let bench = simplebench_runtime::inventory::iter::<SimpleBench>()
+        .find(|b| b.name == "bench_manual_region")
+        .expect("bench_manual_region not registered");
+
+    let mut config = BenchmarkConfig::default();
+    config.measurement.samples = 5;
+    config.measurement.warmup_duration = std::time::Duration::ZERO;
+
+    let result = (bench.run)(&config).unwrap();
+    assert_eq!(result.all_timings.len(), 5);
+    // The 2ms sleep outside start/stop is not part of any sample
+    assert!(result
+        .all_timings
+        .iter()
+        .all(|t| *t < std::time::Duration::from_millis(2)));
+}
+
+#[test]
+fn test_measure_drop_marks_result() {
+    use simplebench_runtime::config::BenchmarkConfig;
+
+    let bench = simplebench_runtime::inventory::iter::<SimpleBench>()
+        .find(|b| b.name == "bench_drop_nested")
+        .expect("bench_drop_nested not registered");
+
+    let mut config = BenchmarkConfig::default();
+    config.measurement.samples = 5;
+    config.measurement.warmup_duration = std::time::Duration::ZERO;
+
+    let result = (bench.run)(&config).unwrap();
+    assert_eq!(result.all_timings.len(), 5);
+    assert!(result.measures_drop);
+}
@@ -0,0 +1,6 @@
+use simplebench_macros::bench;
+
+#[bench(group("serialization"))]
+fn bench_noop() {}
+
+fn main() {}
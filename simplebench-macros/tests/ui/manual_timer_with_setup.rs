@@ -0,0 +1,9 @@
+use simplebench_macros::bench;
+
+#[bench(manual_timer, setup = || vec![1, 2, 3])]
+fn bench_timed(timer: &mut simplebench_runtime::Timer) {
+    timer.start();
+    timer.stop();
+}
+
+fn main() {}
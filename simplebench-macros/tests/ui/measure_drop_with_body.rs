@@ -0,0 +1,8 @@
+use simplebench_macros::bench;
+
+#[bench(measure = drop, setup_each = || vec![1, 2, 3])]
+fn bench_drop(data: Vec<i32>) {
+    std::hint::black_box(&data);
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use simplebench_macros::bench;
+
+#[bench(min_free_memory = "8XB")]
+fn bench_big() {}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use simplebench_macros::bench;
+
+#[bench(setup)]
+fn bench_noop() {}
+
+fn main() {}
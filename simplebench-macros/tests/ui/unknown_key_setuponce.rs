@@ -0,0 +1,12 @@
+use simplebench_macros::bench;
+
+fn create_data() -> Vec<i32> {
+    vec![1, 2, 3]
+}
+
+#[bench(setuponce = create_data)]
+fn bench_sum(data: &[i32]) {
+    let _sum: i32 = data.iter().sum();
+}
+
+fn main() {}
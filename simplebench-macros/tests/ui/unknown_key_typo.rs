@@ -0,0 +1,8 @@
+use simplebench_macros::bench;
+
+#[bench(setup_each_ = || vec![3, 1, 2])]
+fn bench_sort(mut data: Vec<i32>) {
+    data.sort();
+}
+
+fn main() {}
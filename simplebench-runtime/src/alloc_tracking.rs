@@ -0,0 +1,195 @@
+//! Heap allocation counting per timed region.
+//!
+//! With the `alloc-tracking` feature, this crate installs a global allocator that
+//! wraps [`std::alloc::System`] and counts every allocation, deallocation and
+//! allocated byte in process-wide counters. The measurement loop reads them
+//! around each timed call. Without the feature nothing is installed and no
+//! allocation statistics are recorded.
+//!
+//! The counters are process-wide, so allocations made by threads the benchmark
+//! spawns are included. A binary can only have one global allocator: a
+//! benchmark crate that installs its own can't enable this feature.
+
+use serde::{Deserialize, Serialize};
+
+/// Mean heap activity per sample over a benchmark's timed calls
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AllocStats {
+    /// Allocations (including reallocations) per sample
+    pub mean_allocs: f64,
+    /// Deallocations per sample
+    pub mean_deallocs: f64,
+    /// Bytes allocated per sample
+    pub mean_bytes: f64,
+}
+
+impl AllocStats {
+    /// Average the counts of each sample; None without samples
+    pub(crate) fn from_counts(counts: &[AllocCounts]) -> Option<Self> {
+        if counts.is_empty() {
+            return None;
+        }
+
+        let n = counts.len() as f64;
+        let sum = |f: fn(&AllocCounts) -> u64| counts.iter().map(f).sum::<u64>() as f64 / n;
+        Some(Self {
+            mean_allocs: sum(|c| c.allocs),
+            mean_deallocs: sum(|c| c.deallocs),
+            mean_bytes: sum(|c| c.bytes),
+        })
+    }
+
+    /// Format as "12 (1.3 KiB)": allocations per sample and the bytes they request
+    pub fn format(&self) -> String {
+        const KIB: f64 = 1024.0;
+        let bytes = if self.mean_bytes >= KIB * KIB {
+            format!("{:.1} MiB", self.mean_bytes / (KIB * KIB))
+        } else if self.mean_bytes >= KIB {
+            format!("{:.1} KiB", self.mean_bytes / KIB)
+        } else {
+            format!("{:.0} B", self.mean_bytes)
+        };
+
+        let allocs = if self.mean_allocs.fract() == 0.0 {
+            format!("{:.0}", self.mean_allocs)
+        } else {
+            format!("{:.1}", self.mean_allocs)
+        };
+
+        format!("{} ({})", allocs, bytes)
+    }
+}
+
+/// Counter values, or the difference between two readings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct AllocCounts {
+    pub allocs: u64,
+    pub deallocs: u64,
+    pub bytes: u64,
+}
+
+impl AllocCounts {
+    /// Activity between `start` and this reading
+    pub fn since(self, start: AllocCounts) -> AllocCounts {
+        AllocCounts {
+            allocs: self.allocs.wrapping_sub(start.allocs),
+            deallocs: self.deallocs.wrapping_sub(start.deallocs),
+            bytes: self.bytes.wrapping_sub(start.bytes),
+        }
+    }
+}
+
+/// Current counter values; None when allocation tracking isn't compiled in
+#[inline(always)]
+pub(crate) fn read_alloc_counts() -> Option<AllocCounts> {
+    #[cfg(feature = "alloc-tracking")]
+    {
+        Some(tracking::read())
+    }
+    #[cfg(not(feature = "alloc-tracking"))]
+    {
+        None
+    }
+}
+
+#[cfg(feature = "alloc-tracking")]
+mod tracking {
+    use super::AllocCounts;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static ALLOCS: AtomicU64 = AtomicU64::new(0);
+    static DEALLOCS: AtomicU64 = AtomicU64::new(0);
+    static BYTES: AtomicU64 = AtomicU64::new(0);
+
+    /// [`System`] allocator that counts what passes through it
+    pub struct TrackingAllocator;
+
+    #[global_allocator]
+    static GLOBAL: TrackingAllocator = TrackingAllocator;
+
+    #[inline(always)]
+    fn record_alloc(size: usize) {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(size as u64, Ordering::Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for TrackingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            record_alloc(layout.size());
+            System.alloc(layout)
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            record_alloc(layout.size());
+            System.alloc_zeroed(layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            record_alloc(new_size);
+            System.realloc(ptr, layout, new_size)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            DEALLOCS.fetch_add(1, Ordering::Relaxed);
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[inline(always)]
+    pub fn read() -> AllocCounts {
+        AllocCounts {
+            allocs: ALLOCS.load(Ordering::Relaxed),
+            deallocs: DEALLOCS.load(Ordering::Relaxed),
+            bytes: BYTES.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alloc_stats_from_counts() {
+        let counts = [
+            AllocCounts {
+                allocs: 2,
+                deallocs: 1,
+                bytes: 1024,
+            },
+            AllocCounts {
+                allocs: 1,
+                deallocs: 1,
+                bytes: 1536,
+            },
+        ];
+
+        let stats = AllocStats::from_counts(&counts).unwrap();
+        assert_eq!(stats.mean_allocs, 1.5);
+        assert_eq!(stats.mean_deallocs, 1.0);
+        assert_eq!(stats.mean_bytes, 1280.0);
+        assert_eq!(stats.format(), "1.5 (1.2 KiB)");
+        assert!(AllocStats::from_counts(&[]).is_none());
+
+        let end = AllocCounts {
+            allocs: 5,
+            deallocs: 4,
+            bytes: 100,
+        };
+        assert_eq!(end.since(counts[0]).allocs, 3);
+    }
+
+    #[cfg(feature = "alloc-tracking")]
+    #[test]
+    fn test_tracking_allocator_counts_allocations() {
+        let start = read_alloc_counts().unwrap();
+        let data = std::hint::black_box(vec![0u8; 4096]);
+        let delta = read_alloc_counts().unwrap().since(start);
+        drop(data);
+
+        // Other test threads may allocate concurrently, so only lower bounds hold
+        assert!(delta.allocs >= 1);
+        assert!(delta.bytes >= 4096);
+    }
+}
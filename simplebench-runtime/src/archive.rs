@@ -0,0 +1,314 @@
+//! Stored runs as portable entries, for moving a `.benches` tree between
+//! machines or CI jobs (`cargo simplebench export` / `import`).
+//!
+//! An entry is one file of a benchmark directory, named
+//! `<machine>/<crate>_<benchmark>/<file>` relative to the tree's root: a run,
+//! or the rename marker the benchmark's history is followed through. Imports
+//! validate every file before writing it and never replace a stored one.
+
+use crate::baseline::{
+    decode_run, encode_run, is_compressed, parse_baseline, run_name, validate_machine_id,
+    BaselineManager, COMPRESSED_RUN_EXTENSION, REDIRECT_FILE, RUN_EXTENSION,
+};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// One file of a stored benchmark directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    pub machine_id: String,
+    /// `<crate>_<benchmark>` directory name
+    pub benchmark: String,
+    /// A run (`<timestamp>.json` or `.json.gz`) or the rename marker
+    pub file_name: String,
+}
+
+/// What [`BaselineManager::import_entry`] did with an entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportOutcome {
+    Imported,
+    /// A file of that name was already stored, and was kept
+    AlreadyStored,
+}
+
+impl ArchiveEntry {
+    /// Whether the entry is a run rather than the rename marker
+    pub fn is_run(&self) -> bool {
+        self.file_name != REDIRECT_FILE
+    }
+
+    /// `<machine>/<benchmark>/<file>`
+    pub fn archive_path(&self) -> String {
+        format!("{}/{}/{}", self.machine_id, self.benchmark, self.file_name)
+    }
+
+    /// Parse an archive path, rejecting anything that isn't a stored run or
+    /// rename marker three plain components deep
+    pub fn parse(path: &str) -> io::Result<Self> {
+        let invalid = |why: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("archive entry {:?} {}", path, why),
+            )
+        };
+        let components: Vec<&str> = path.trim_start_matches("./").split('/').collect();
+        let [machine_id, benchmark, file_name] = components[..] else {
+            return Err(invalid("is not <machine>/<benchmark>/<file>"));
+        };
+        for component in [machine_id, benchmark, file_name] {
+            validate_machine_id(component).map_err(|_| invalid("has an invalid component"))?;
+        }
+        if run_name(file_name).is_none() && file_name != REDIRECT_FILE {
+            return Err(invalid("is neither a run nor a rename marker"));
+        }
+        Ok(Self {
+            machine_id: machine_id.to_string(),
+            benchmark: benchmark.to_string(),
+            file_name: file_name.to_string(),
+        })
+    }
+
+    /// Check that `contents` is a valid file for this entry, without storing it
+    pub fn validate(&self, contents: &[u8]) -> io::Result<()> {
+        self.prepare(contents).map(drop)
+    }
+
+    /// The file to store for `contents`: a run recorded on another machine
+    /// than the entry's is rewritten to record the entry's, compressed as
+    /// it was
+    fn prepare(&self, contents: &[u8]) -> io::Result<Vec<u8>> {
+        let invalid = |e: &dyn std::fmt::Display| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", self.archive_path(), e),
+            )
+        };
+        let compressed = is_compressed(Path::new(&self.file_name));
+        let text = decode_run(contents.to_vec(), compressed).map_err(|e| invalid(&e))?;
+        if !self.is_run() {
+            serde_json::from_str::<crate::baseline::RenameRedirect>(&text)
+                .map_err(|e| invalid(&e))?;
+            return Ok(contents.to_vec());
+        }
+
+        let mut run = parse_baseline(&text).map_err(|e| invalid(&e))?;
+        if run.machine_id == self.machine_id {
+            return Ok(contents.to_vec());
+        }
+        run.machine_id = self.machine_id.clone();
+        encode_run(&serde_json::to_string_pretty(&run)?, compressed)
+    }
+}
+
+impl BaselineManager {
+    /// Every stored run and rename marker of every machine, with its path
+    ///
+    /// With `bench_filter`, only benchmarks whose directory name contains it;
+    /// with `last`, only the newest `last` runs of each benchmark.
+    pub fn archive_entries(
+        &self,
+        bench_filter: Option<&str>,
+        last: Option<usize>,
+    ) -> io::Result<Vec<(ArchiveEntry, PathBuf)>> {
+        let mut entries = Vec::new();
+        for (key, bench_dir) in self.benchmark_dirs()? {
+            let Some((machine_id, benchmark)) = key.split_once('/') else {
+                continue;
+            };
+            if bench_filter.is_some_and(|filter| !benchmark.contains(filter)) {
+                continue;
+            }
+
+            let mut files = crate::disk_usage::run_files(&bench_dir)?;
+            files.sort();
+            if let Some(last) = last {
+                files.drain(..files.len().saturating_sub(last));
+            }
+            if bench_dir.join(REDIRECT_FILE).is_file() {
+                files.push(REDIRECT_FILE.to_string());
+            }
+            for file_name in files {
+                let path = bench_dir.join(&file_name);
+                entries.push((
+                    ArchiveEntry {
+                        machine_id: machine_id.to_string(),
+                        benchmark: benchmark.to_string(),
+                        file_name,
+                    },
+                    path,
+                ));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Whether a file of the entry's name, or the same run in the other
+    /// format, is stored
+    pub(crate) fn is_stored(&self, entry: &ArchiveEntry) -> bool {
+        let bench_dir = self
+            .root_dir()
+            .join(&entry.machine_id)
+            .join(&entry.benchmark);
+        match run_name(&entry.file_name) {
+            Some(run) => [RUN_EXTENSION, COMPRESSED_RUN_EXTENSION]
+                .iter()
+                .any(|extension| bench_dir.join(format!("{}{}", run, extension)).exists()),
+            None => bench_dir.join(&entry.file_name).exists(),
+        }
+    }
+
+    /// Store an archive entry unless a file of its name, or the same run in
+    /// the other format, is already stored
+    ///
+    /// Runs must parse as [`crate::baseline::BaselineData`] and markers as
+    /// rename markers, or nothing is written. A run imported under another
+    /// machine than it was recorded on (`--remap-machine`) gets that machine's
+    /// id recorded in it too.
+    pub fn import_entry(&self, entry: &ArchiveEntry, contents: &[u8]) -> io::Result<ImportOutcome> {
+        let contents = entry.prepare(contents)?;
+        let bench_dir = self
+            .root_dir()
+            .join(&entry.machine_id)
+            .join(&entry.benchmark);
+        if self.is_stored(entry) {
+            return Ok(ImportOutcome::AlreadyStored);
+        }
+        let path = bench_dir.join(&entry.file_name);
+        fs::create_dir_all(&bench_dir)?;
+        crate::lock::write_atomic(&path, &contents)?;
+        Ok(ImportOutcome::Imported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::baseline::BaselineData;
+    use crate::{BenchResult, Percentiles};
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn run_json(machine_id: &str, micros: u64) -> Vec<u8> {
+        let timing = Duration::from_micros(micros);
+        let result = BenchResult {
+            name: "b".to_string(),
+            module: "c".to_string(),
+            samples: 10,
+            percentiles: Percentiles {
+                values: vec![(50.0, timing)],
+                mean: timing,
+            },
+            all_timings: vec![timing; 10],
+            ..Default::default()
+        };
+        let baseline = BaselineData::from_bench_result(&result, machine_id.to_string(), false);
+        serde_json::to_vec(&baseline).unwrap()
+    }
+
+    fn entry(path: &str) -> ArchiveEntry {
+        ArchiveEntry::parse(path).unwrap()
+    }
+
+    #[test]
+    fn test_archive_paths_are_validated() {
+        let parsed = entry("m1/c_b/2025-01-01T00-00-00.json");
+        assert_eq!(parsed.benchmark, "c_b");
+        assert_eq!(parsed.archive_path(), "m1/c_b/2025-01-01T00-00-00.json");
+        assert!(ArchiveEntry::parse("m1/c_b/redirect.meta").is_ok());
+        assert!(entry("m1/c_b/2025-01-01T00-00-00.json.gz").is_run());
+
+        for path in [
+            "../c_b/run.json",
+            "m1/../run.json",
+            "m1/c_b/run.txt",
+            "m1/run.json",
+            "m1/c_b/x/run.json",
+        ] {
+            assert!(ArchiveEntry::parse(path).is_err(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_export_last_runs_and_import_without_overwriting() {
+        let source = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(source.path()).unwrap();
+        for (i, run) in [
+            "2025-01-01T00-00-00",
+            "2025-01-02T00-00-00",
+            "2025-01-03T00-00-00",
+        ]
+        .iter()
+        .enumerate()
+        {
+            let entry = entry(&format!("m1/c_b/{}.json", run));
+            let contents = run_json("m1", 10 + i as u64);
+            assert_eq!(
+                manager.import_entry(&entry, &contents).unwrap(),
+                ImportOutcome::Imported
+            );
+        }
+        manager
+            .import_entry(
+                &entry("m1/c_other/2025-01-01T00-00-00.json"),
+                &run_json("m1", 5),
+            )
+            .unwrap();
+
+        let exported = manager.archive_entries(Some("c_b"), Some(2)).unwrap();
+        let names: Vec<&str> = exported.iter().map(|(e, _)| e.file_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["2025-01-02T00-00-00.json", "2025-01-03T00-00-00.json"]
+        );
+
+        // A stored run of the same name is kept, and invalid files aren't written
+        let target = TempDir::new().unwrap();
+        let local = BaselineManager::with_root_dir(target.path()).unwrap();
+        let newest = entry("m1/c_b/2025-01-03T00-00-00.json");
+        local.import_entry(&newest, &run_json("m1", 99)).unwrap();
+        for (entry, path) in &exported {
+            local.import_entry(entry, &fs::read(path).unwrap()).unwrap();
+        }
+        let kept =
+            fs::read_to_string(target.path().join("m1/c_b/2025-01-03T00-00-00.json")).unwrap();
+        assert_eq!(parse_baseline(&kept).unwrap().statistics.mean, 99_000);
+        assert!(target
+            .path()
+            .join("m1/c_b/2025-01-02T00-00-00.json")
+            .exists());
+
+        let bad = entry("m1/c_b/2025-01-04T00-00-00.json");
+        assert!(local.import_entry(&bad, b"{ truncated").is_err());
+        assert!(!target.path().join(bad.archive_path()).exists());
+    }
+
+    #[test]
+    fn test_remapped_import_records_local_machine() {
+        let target = TempDir::new().unwrap();
+        let local = BaselineManager::with_root_dir(target.path()).unwrap();
+        let remapped = ArchiveEntry {
+            machine_id: local.machine_id().to_string(),
+            ..entry("ci-runner/c_b/2025-01-01T00-00-00.json")
+        };
+        local
+            .import_entry(&remapped, &run_json("ci-runner", 10))
+            .unwrap();
+        // A compressed run is rewritten compressed
+        let json = String::from_utf8(run_json("ci-runner", 20)).unwrap();
+        let compressed = ArchiveEntry {
+            file_name: "2025-01-02T00-00-00.json.gz".to_string(),
+            ..remapped.clone()
+        };
+        local
+            .import_entry(&compressed, &encode_run(&json, true).unwrap())
+            .unwrap();
+
+        let runs = local.list_runs("c", "b").unwrap();
+        assert_eq!(runs.len(), 2);
+        for run in &runs {
+            let run = local.load_run("c", "b", run).unwrap().unwrap();
+            assert_eq!(run.machine_id, local.machine_id());
+        }
+    }
+}
@@ -1,9 +1,14 @@
-use crate::config::ComparisonConfig;
-use crate::{BenchResult, CpuSnapshot, Percentiles};
+use crate::config::{ComparisonConfig, ComparisonMethod, ComparisonMetric, WindowScale};
+use crate::disk_usage::RetentionPolicy;
+use crate::filter::BenchFilter;
+use crate::{BenchResult, CpuSnapshot, EnvChange, Percentiles, Throughput};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fs;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 /// Get the MAC address of the primary network interface and hash it for privacy
@@ -33,6 +38,29 @@ fn get_primary_mac_address() -> Result<String, std::io::Error> {
     hash_mac_address(&mac_string)
 }
 
+/// Identify this machine: by its MAC address, else (in containers without a
+/// default interface) by `/etc/machine-id` on Linux, else by its hostname
+///
+/// Each source is hashed like the MAC address, so ids look alike whatever
+/// they came from.
+fn detect_machine_id() -> Result<String, std::io::Error> {
+    let mac_error = match get_primary_mac_address() {
+        Ok(machine_id) => return Ok(machine_id),
+        Err(e) => e,
+    };
+    let fallback = fs::read_to_string("/etc/machine-id")
+        .ok()
+        .or_else(|| fs::read_to_string("/proc/sys/kernel/hostname").ok())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty());
+    match fallback {
+        Some(id) => hash_mac_address(&id),
+        None => Err(mac_error),
+    }
+}
+
 /// Hash a MAC address using SHA256 for privacy protection
 ///
 /// Returns the first 16 characters of the hex digest as a stable machine identifier
@@ -45,16 +73,42 @@ fn hash_mac_address(mac: &str) -> Result<String, std::io::Error> {
     Ok(format!("{:x}", result)[..16].to_string())
 }
 
+/// A configured machine id names a directory, so it must be a single path
+/// component
+pub(crate) fn validate_machine_id(machine_id: &str) -> Result<(), std::io::Error> {
+    if machine_id.is_empty()
+        || machine_id == "."
+        || machine_id == ".."
+        || machine_id.contains(['/', '\\'])
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "machine id {:?} must be a non-empty name without path separators",
+                machine_id
+            ),
+        ));
+    }
+    Ok(())
+}
+
 /// Storage format for baseline benchmark results
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BaselineData {
     pub benchmark_name: String,
     pub module: String,
     pub timestamp: String,
-    /// All raw timing samples in nanoseconds
+    /// All raw timing samples in nanoseconds, one call each
     pub samples: Vec<u128>,
+    /// Start of each sample in milliseconds since measurement began,
+    /// index-aligned with `samples`; empty for older runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sample_offsets_ms: Vec<u64>,
     /// Comprehensive statistics calculated from samples
     pub statistics: crate::Statistics,
+    /// Log-scaled histogram of `samples`, for spotting multimodal runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<crate::statistics::Histogram>,
     #[serde(alias = "hostname")]
     pub machine_id: String,
 
@@ -62,6 +116,14 @@ pub struct BaselineData {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub cpu_samples: Vec<CpuSnapshot>,
 
+    /// Per-sample thread CPU time in nanoseconds (empty if unavailable)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cpu_time_samples: Vec<u128>,
+
+    /// Statistics calculated from the CPU time samples
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_time_statistics: Option<crate::Statistics>,
+
     // Legacy fields for backward compatibility (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub percentiles: Option<Percentiles>,
@@ -69,13 +131,232 @@ pub struct BaselineData {
     // Flag indicating this run was a detected regression
     #[serde(default, skip_serializing_if = "is_false")]
     pub was_regression: bool,
+
+    /// Flag indicating this run was a confirmed improvement
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub was_improvement: bool,
+
+    /// This run's regression was accepted with `cargo simplebench accept`: it
+    /// began a new performance level, and windows start here
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub accepted: bool,
+
+    /// Work processed per call, if the benchmark declared throughput
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throughput: Option<Throughput>,
+
+    /// Logical group the benchmark belongs to, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+
+    /// CPU core the run was pinned to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub core: Option<usize>,
+
+    /// Hash of the benchmark function body, used to detect renames
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_hash: Option<String>,
+
+    /// Timer overhead subtracted from each sample, in nanoseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timer_overhead_ns: Option<u64>,
+
+    /// The benchmark's crate was compiled with debug assertions (unoptimized)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub debug_assertions: bool,
+
+    /// Samples time only the drop of the setup value
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub measures_drop: bool,
+
+    /// Heap activity per sample, recorded with the `alloc-tracking` feature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alloc_stats: Option<crate::AllocStats>,
+
+    /// Clock(s) the samples come from; with `cpu`, `samples` is thread CPU time
+    #[serde(default, skip_serializing_if = "crate::ClockMode::is_default")]
+    pub clock: crate::ClockMode,
+
+    /// Hardware counters of each sample (`perf-counters` feature, Linux)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub perf_samples: Vec<crate::PerfSample>,
+
+    /// Involuntary context switches during each sample (Linux), index-aligned
+    /// with `samples`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub involuntary_switches: Vec<u64>,
+
+    /// Samples the watchdog flagged as pathologically slow
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pathological_samples: Vec<crate::PathologicalSample>,
+
+    /// Package energy over the measurement phase in microjoules (RAPL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub energy_uj: Option<u64>,
+
+    /// Mean package power over the measurement phase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mean_watts: Option<f64>,
+
+    /// Watched environment variables at run time, None where unset
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environment: BTreeMap<String, Option<String>>,
+
+    /// Commit, branch and dirty state of the working tree at run time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<crate::git::GitInfo>,
+
+    /// Compiler, build profile and CPU model at run time; None for older runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<crate::toolchain::Toolchain>,
+
+    /// Measurement settings the run was taken with; None for older runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<MeasurementSettings>,
+
+    /// `samples` and the other per-sample series are a uniform reservoir
+    /// (`store_raw_samples = false`); `statistics` covers all
+    /// `statistics.sample_count` samples
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub reservoir_sampled: bool,
+
+    /// Mean timed duration of one warmup iteration, in nanoseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warmup_ns_per_iter: Option<f64>,
+
+    /// Mean timed duration of one recorded sample, in nanoseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling_ns_per_iter: Option<f64>,
+
+    /// Leading samples dropped before `samples` was recorded
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub discarded_samples: usize,
+
+    /// How `statistics` and `percentiles` were computed; see [`STATS_VERSION`]
+    #[serde(default)]
+    pub stats_version: u32,
+
+    /// `statistics` without the samples outside the Tukey fences; None for
+    /// runs stored before they were recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filtered: Option<crate::FilteredStatistics>,
+}
+
+/// Version of the statistics written to baselines.
+///
+/// - 0: percentiles were the sample at index `len * p` (biased high: the
+///   median of 1..=10 was 6)
+/// - 1: linear interpolation between order statistics (type 7)
+/// - 2: adds median absolute deviation, interquartile range and robust CV
+///
+/// Files from older versions get their statistics recomputed from the raw
+/// samples when loaded, so old and new runs compare like for like. Reservoir
+/// runs can't be recomputed and keep their streaming estimates, with robust
+/// dispersion estimated from the stored reservoir.
+pub const STATS_VERSION: u32 = 2;
+
+/// Sample counts or warmups further apart than this factor make two runs
+/// incomparable
+const SETTINGS_TOLERANCE: f64 = 2.0;
+
+/// Baseline timing in nanoseconds below which a relative change is
+/// meaningless: the clock resolves no finer
+const MIN_RELATIVE_BASELINE_NS: f64 = 1.0;
+
+/// Percentage change of `current` from `baseline`, None when the baseline is
+/// below [`MIN_RELATIVE_BASELINE_NS`]
+fn percent_change(current: f64, baseline: f64) -> Option<f64> {
+    (baseline >= MIN_RELATIVE_BASELINE_NS)
+        .then(|| (current - baseline) / baseline * 100.0)
+        .filter(|change| change.is_finite())
+}
+
+/// The measurement settings a run was taken with, so a window only holds runs
+/// measured alike
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MeasurementSettings {
+    /// Samples requested: `samples`, or `max_samples` in adaptive mode
+    pub samples: usize,
+    /// Configured `warmup_duration` in milliseconds
+    pub warmup_ms: u64,
+    #[serde(default, skip_serializing_if = "crate::config::WarmupMode::is_default")]
+    pub warmup_mode: crate::config::WarmupMode,
+    /// Adaptive sampling (`adaptive = true`)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub adaptive: bool,
+}
+
+impl MeasurementSettings {
+    pub fn from_config(measurement: &crate::config::MeasurementConfig) -> Self {
+        Self {
+            samples: crate::measurement::sample_budget(measurement),
+            warmup_ms: u64::try_from(measurement.warmup_duration.as_millis()).unwrap_or(u64::MAX),
+            warmup_mode: measurement.warmup_mode,
+            adaptive: measurement.adaptive,
+        }
+    }
+
+    /// Runs taken with `self` and `other` compare like for like: the same
+    /// sampling and warmup modes, and sample counts and warmups within
+    /// [`SETTINGS_TOLERANCE`] of each other
+    pub fn comparable_with(&self, other: &Self) -> bool {
+        let within = |a: f64, b: f64| a.max(b) <= a.min(b) * SETTINGS_TOLERANCE;
+        self.adaptive == other.adaptive
+            && self.warmup_mode == other.warmup_mode
+            && within(self.samples as f64, other.samples as f64)
+            && within(self.warmup_ms as f64, other.warmup_ms as f64)
+    }
 }
 
-fn is_false(b: &bool) -> bool {
+/// Parse a stored run, bringing statistics of older versions up to date
+pub(crate) fn parse_baseline(contents: &str) -> serde_json::Result<BaselineData> {
+    let mut baseline: BaselineData = serde_json::from_str(contents)?;
+    baseline.upgrade_statistics();
+    Ok(baseline)
+}
+
+pub(crate) fn is_false(b: &bool) -> bool {
     !*b
 }
 
+pub(crate) fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
 impl BaselineData {
+    /// Recompute statistics written before [`STATS_VERSION`] from the raw samples
+    pub fn upgrade_statistics(&mut self) {
+        if self.stats_version >= STATS_VERSION {
+            return;
+        }
+        if !self.reservoir_sampled && !self.samples.is_empty() {
+            let levels = self
+                .percentiles
+                .as_ref()
+                .map_or_else(|| crate::DEFAULT_PERCENTILES.to_vec(), |p| p.levels());
+            self.statistics = crate::calculate_statistics_at(&self.samples, &levels);
+            if let Some(ref mut percentiles) = self.percentiles {
+                let timings: Vec<Duration> = self
+                    .samples
+                    .iter()
+                    .map(|&ns| crate::duration_from_nanos(ns))
+                    .collect();
+                *percentiles = crate::calculate_percentiles(&timings, &levels);
+            }
+            if !self.cpu_time_samples.is_empty() {
+                self.cpu_time_statistics = Some(crate::calculate_statistics_at(
+                    &self.cpu_time_samples,
+                    &levels,
+                ));
+            }
+        } else if !self.samples.is_empty() {
+            let mut reservoir = self.samples.clone();
+            reservoir.sort_unstable();
+            self.statistics.set_robust_dispersion(&reservoir);
+        }
+        self.stats_version = STATS_VERSION;
+    }
+
     pub fn from_bench_result(
         result: &BenchResult,
         machine_id: String,
@@ -84,216 +365,878 @@ impl BaselineData {
         // Convert Duration timings to u128 nanoseconds
         let samples: Vec<u128> = result.all_timings.iter().map(|d| d.as_nanos()).collect();
 
-        // Calculate comprehensive statistics
-        let statistics = crate::calculate_statistics(&samples);
+        // Calculate comprehensive statistics, unless they were computed while
+        // sampling and `samples` is only a reservoir
+        let levels = result.percentiles.levels();
+        let statistics = result
+            .summary
+            .clone()
+            .unwrap_or_else(|| crate::calculate_statistics_at(&samples, &levels));
+
+        let histogram =
+            crate::statistics::histogram(&samples, crate::statistics::DEFAULT_HISTOGRAM_BINS);
+
+        let filtered = result
+            .filtered
+            .clone()
+            .or_else(|| crate::filter_outliers(&samples, &levels));
+
+        let cpu_time_samples: Vec<u128> = result.cpu_timings.iter().map(|d| d.as_nanos()).collect();
+        let cpu_time_statistics = (!cpu_time_samples.is_empty())
+            .then(|| crate::calculate_statistics_at(&cpu_time_samples, &levels));
 
         Self {
             benchmark_name: result.name.clone(),
             module: result.module.clone(),
             timestamp: chrono::Utc::now().to_rfc3339(),
             samples,
+            sample_offsets_ms: result.sample_offsets_ms.clone(),
             statistics,
+            histogram,
             machine_id,
             cpu_samples: result.cpu_samples.clone(),
+            cpu_time_samples,
+            cpu_time_statistics,
             percentiles: Some(result.percentiles.clone()),
             was_regression,
+            was_improvement: false,
+            accepted: false,
+            throughput: result.throughput,
+            group: result.group.clone(),
+            core: result.core,
+            body_hash: result.body_hash.clone(),
+            timer_overhead_ns: result.timer_overhead_ns,
+            debug_assertions: result.debug_assertions,
+            measures_drop: result.measures_drop,
+            alloc_stats: result.alloc_stats,
+            clock: result.clock,
+            perf_samples: result.perf_samples.clone(),
+            involuntary_switches: result.involuntary_switches.clone(),
+            pathological_samples: result.pathological_samples.clone(),
+            energy_uj: result.energy_uj,
+            mean_watts: result.mean_watts,
+            environment: result.environment.clone(),
+            git: result.git.clone(),
+            toolchain: result.toolchain.clone(),
+            settings: result.settings,
+            reservoir_sampled: result.summary.is_some(),
+            warmup_ns_per_iter: result.warmup_ns_per_iter,
+            sampling_ns_per_iter: result.sampling_ns_per_iter,
+            discarded_samples: result.discarded_samples,
+            stats_version: STATS_VERSION,
+            filtered,
         }
     }
 
-    pub fn to_bench_result(&self) -> BenchResult {
-        // If we have percentiles (new format), use them
-        let percentiles = if let Some(ref p) = self.percentiles {
-            p.clone()
-        } else {
-            // Reconstruct from statistics (for forward compatibility)
-            Percentiles {
-                mean: Duration::from_nanos(self.statistics.mean as u64),
-                p50: Duration::from_nanos(self.statistics.median as u64),
-                p90: Duration::from_nanos(self.statistics.p90 as u64),
-                p99: Duration::from_nanos(self.statistics.p99 as u64),
+    /// This run with every timing multiplied by `factor`, as if measured on
+    /// a machine `factor` times slower
+    pub(crate) fn scaled(&self, factor: f64) -> BaselineData {
+        let scale = |ns: u128| (ns as f64 * factor) as u128;
+        let mut run = self.clone();
+        run.samples = self.samples.iter().map(|&ns| scale(ns)).collect();
+        run.statistics = self.statistics.scaled(factor);
+        run.cpu_time_samples = self.cpu_time_samples.iter().map(|&ns| scale(ns)).collect();
+        run.cpu_time_statistics = self.cpu_time_statistics.as_ref().map(|s| s.scaled(factor));
+        if let Some(ref mut percentiles) = run.percentiles {
+            percentiles.mean = percentiles.mean.mul_f64(factor);
+            for (_, timing) in &mut percentiles.values {
+                *timing = timing.mul_f64(factor);
             }
-        };
+        }
+        if let Some(ref mut histogram) = run.histogram {
+            for edge in &mut histogram.edges {
+                *edge *= factor;
+            }
+        }
+        if let Some(ref mut filtered) = run.filtered {
+            filtered.statistics = filtered.statistics.scaled(factor);
+        }
+        run.warmup_ns_per_iter = self.warmup_ns_per_iter.map(|ns| ns * factor);
+        run.sampling_ns_per_iter = self.sampling_ns_per_iter.map(|ns| ns * factor);
+        run
+    }
+
+    pub fn to_bench_result(&self) -> BenchResult {
+        // If we have percentiles (new format), use them, otherwise reconstruct
+        // from statistics (for forward compatibility)
+        let percentiles = self
+            .percentiles
+            .clone()
+            .unwrap_or_else(|| crate::percentiles_from_statistics(&self.statistics));
 
         // Convert samples back to Duration
         let all_timings: Vec<Duration> = self
             .samples
             .iter()
-            .map(|&ns| Duration::from_nanos(ns as u64))
+            .map(|&ns| crate::duration_from_nanos(ns))
+            .collect();
+
+        let cpu_timings: Vec<Duration> = self
+            .cpu_time_samples
+            .iter()
+            .map(|&ns| crate::duration_from_nanos(ns))
             .collect();
+        let cpu_time_percentiles = self
+            .cpu_time_statistics
+            .as_ref()
+            .map(crate::percentiles_from_statistics);
 
         BenchResult {
             name: self.benchmark_name.clone(),
             module: self.module.clone(),
             percentiles,
-            samples: self.samples.len(),
+            samples: if self.reservoir_sampled {
+                self.statistics.sample_count
+            } else {
+                self.samples.len()
+            },
             all_timings,
+            sample_offsets_ms: self.sample_offsets_ms.clone(),
             cpu_samples: self.cpu_samples.clone(),
+            cpu_timings,
+            cpu_time_percentiles,
             warmup_ms: None,
             warmup_iterations: None,
+            warmup_mode: Default::default(),
+            warmup_ns_per_iter: self.warmup_ns_per_iter,
+            sampling_ns_per_iter: self.sampling_ns_per_iter,
+            discarded_samples: self.discarded_samples,
+            throughput: self.throughput,
+            group: self.group.clone(),
+            core: self.core,
+            body_hash: self.body_hash.clone(),
+            skipped: false,
+            skip_reason: None,
+            timer_overhead_ns: self.timer_overhead_ns,
+            debug_assertions: self.debug_assertions,
+            truncated: false,
+            watchdog_stopped: false,
+            measures_drop: self.measures_drop,
+            early_verdict: None,
+            alloc_stats: self.alloc_stats,
+            clock: self.clock,
+            perf_samples: self.perf_samples.clone(),
+            involuntary_switches: self.involuntary_switches.clone(),
+            pathological_samples: self.pathological_samples.clone(),
+            energy_uj: self.energy_uj,
+            mean_watts: self.mean_watts,
+            environment: self.environment.clone(),
+            git: self.git.clone(),
+            toolchain: self.toolchain.clone(),
+            settings: self.settings,
+            interleaved_rounds: 0,
+            quality_failure: None,
+            summary: self.reservoir_sampled.then(|| self.statistics.clone()),
+            filtered: self.filtered.clone(),
         }
     }
 }
 
+/// Regression-flagged runs cleared by [`BaselineManager::accept_regression`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcceptedRegression {
+    /// Runs whose flag was cleared
+    pub runs: usize,
+    /// Timestamp of the first of them, where windows now start
+    pub since: String,
+}
+
+/// File in a benchmark directory recording the name it was renamed from
+pub(crate) const REDIRECT_FILE: &str = "redirect.meta";
+
+/// `chrono` format of run file names, e.g. `2025-01-15T10-30-00`
+pub(crate) const RUN_FILE_TIMESTAMP: &str = "%Y-%m-%dT%H-%M-%S";
+
+/// Extension of a stored run
+pub(crate) const RUN_EXTENSION: &str = ".json";
+
+/// Extension of a gzip-compressed stored run (`[baseline] compress`)
+pub(crate) const COMPRESSED_RUN_EXTENSION: &str = ".json.gz";
+
+/// Run name (`<timestamp>[_NN]`) of a stored run's file name; None for any
+/// other file
+pub(crate) fn run_name(file_name: &str) -> Option<&str> {
+    file_name
+        .strip_suffix(COMPRESSED_RUN_EXTENSION)
+        .or_else(|| file_name.strip_suffix(RUN_EXTENSION))
+}
+
+/// Whether the run file at `path` is gzip-compressed
+pub(crate) fn is_compressed(path: &Path) -> bool {
+    path.to_string_lossy().ends_with(COMPRESSED_RUN_EXTENSION)
+}
+
+/// JSON of a stored run from its file contents
+pub(crate) fn decode_run(bytes: Vec<u8>, compressed: bool) -> Result<String, std::io::Error> {
+    if !compressed {
+        return String::from_utf8(bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e));
+    }
+    let mut json = String::new();
+    flate2::read::GzDecoder::new(bytes.as_slice()).read_to_string(&mut json)?;
+    Ok(json)
+}
+
+/// File contents storing a run's `json`
+pub(crate) fn encode_run(json: &str, compressed: bool) -> Result<Vec<u8>, std::io::Error> {
+    if !compressed {
+        return Ok(json.as_bytes().to_vec());
+    }
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(json.as_bytes())?;
+    encoder.finish()
+}
+
+/// JSON of the stored run at `path`, compressed or not
+pub(crate) fn read_run_file(path: &Path) -> Result<String, std::io::Error> {
+    decode_run(fs::read(path)?, is_compressed(path))
+}
+
+/// Run file name for a stored `timestamp` (RFC 3339, or already a run file name)
+fn run_timestamp(timestamp: &str) -> Option<String> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(timestamp) {
+        return Some(
+            time.with_timezone(&chrono::Utc)
+                .format(RUN_FILE_TIMESTAMP)
+                .to_string(),
+        );
+    }
+    chrono::NaiveDateTime::parse_from_str(timestamp, RUN_FILE_TIMESTAMP)
+        .ok()
+        .map(|time| time.format(RUN_FILE_TIMESTAMP).to_string())
+}
+
+/// Crate and stored name of the benchmark `name` of `module`
+///
+/// A benchmark at its crate's root is stored under its name; one further down
+/// under its module path below the crate, joined by `-`, and its name
+/// (`game_physics::broad::bench_step` as `broad-bench_step`), so same-named
+/// benchmarks of different modules keep separate histories.
+pub fn storage_name<'a>(module: &'a str, name: &str) -> (&'a str, String) {
+    match module.split_once("::") {
+        Some((crate_name, path)) => (crate_name, format!("{}-{}", path.replace("::", "-"), name)),
+        None => (module, name.to_string()),
+    }
+}
+
+/// `<crate>_<stored name>` directory the runs of the benchmark `name` of
+/// `module` are stored in, see [`storage_name`]
+pub fn storage_key(module: &str, name: &str) -> String {
+    let (crate_name, benchmark_name) = storage_name(module, name);
+    format!("{}_{}", crate_name, benchmark_name)
+}
+
+/// Marker left in a renamed benchmark's directory pointing at its former history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenameRedirect {
+    /// Benchmark name the history is carried over from
+    pub redirect_from: String,
+}
+
+/// A benchmark directory with stored runs on this machine, as listed by
+/// [`BaselineManager::stored_benchmarks`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredBenchmark {
+    /// The `<crate>_<benchmark>` directory name
+    pub dir_name: String,
+    /// Crate and benchmark names addressing the directory; any split of the
+    /// directory name maps back to the same path
+    pub crate_name: String,
+    pub benchmark_name: String,
+}
+
 /// Manages baseline storage in .benches/ directory
 #[derive(Debug)]
 pub struct BaselineManager {
     root_dir: PathBuf,
     machine_id: String,
+    follow_renames: bool,
+    improvement_resets_window: bool,
+    /// Pool other machines' normalized runs into this machine's windows
+    compare_across_machines: bool,
+    /// Limits applied to a benchmark's runs after each save
+    retention: RetentionPolicy,
+    /// Write new runs as `.json.gz`
+    compress: bool,
+    /// Set once this manager has migrated legacy single-file baselines
+    legacy_migrated: AtomicBool,
 }
 
 impl BaselineManager {
     /// Create a new baseline manager
     ///
-    /// By default, uses .benches/ in the current directory
+    /// By default, uses .benches/ in the current directory and the machine id
+    /// derived from this machine; `SIMPLEBENCH_BASELINE_DIR` and
+    /// `SIMPLEBENCH_MACHINE_ID` override them.
     pub fn new() -> Result<Self, std::io::Error> {
-        let machine_id = get_primary_mac_address()?;
-
-        Ok(Self {
-            root_dir: PathBuf::from(".benches"),
-            machine_id,
-        })
+        let mut config = crate::config::BenchmarkConfig::default();
+        config.apply_env_overrides();
+        Self::for_workspace(Path::new("."), &config.baseline)
     }
 
     /// Create a baseline manager with a custom root directory
     pub fn with_root_dir<P: AsRef<Path>>(root_dir: P) -> Result<Self, std::io::Error> {
-        let machine_id = get_primary_mac_address()?;
+        Ok(Self::open(
+            root_dir.as_ref().to_path_buf(),
+            detect_machine_id()?,
+        ))
+    }
+
+    /// Create a baseline manager for the workspace at `workspace_root`, with
+    /// the directory and machine id of `[baseline] dir` and `machine_id`
+    pub fn for_workspace(
+        workspace_root: &Path,
+        config: &crate::config::BaselineConfig,
+    ) -> Result<Self, std::io::Error> {
+        let machine_id = match config.machine_id {
+            Some(ref machine_id) => {
+                validate_machine_id(machine_id)?;
+                machine_id.clone()
+            }
+            None => detect_machine_id()?,
+        };
+        Ok(Self::open(config.root_dir(workspace_root), machine_id)
+            .with_compression(config.compress))
+    }
 
-        Ok(Self {
-            root_dir: root_dir.as_ref().to_path_buf(),
+    fn open(root_dir: PathBuf, machine_id: String) -> Self {
+        Self {
+            root_dir,
             machine_id,
-        })
+            follow_renames: true,
+            improvement_resets_window: false,
+            compare_across_machines: false,
+            retention: RetentionPolicy::default(),
+            compress: false,
+            legacy_migrated: AtomicBool::new(false),
+        }
+    }
+
+    /// Enable or disable following benchmark renames (enabled by default)
+    ///
+    /// See [`BaselineManager::save_baseline`] for how renames are detected.
+    pub fn with_follow_renames(mut self, follow_renames: bool) -> Self {
+        self.follow_renames = follow_renames;
+        self
+    }
+
+    /// Write new runs gzip-compressed as `.json.gz` (disabled by default)
+    ///
+    /// Runs are read in either format, so a tree can hold both.
+    pub fn with_compression(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Start windows at the latest confirmed improvement (disabled by default)
+    ///
+    /// See [`BaselineManager::load_recent_baselines`].
+    pub fn with_improvement_resets_window(mut self, improvement_resets_window: bool) -> Self {
+        self.improvement_resets_window = improvement_resets_window;
+        self
+    }
+
+    /// Pool the windows of every machine into this machine's, normalized by
+    /// each machine's median (disabled by default)
+    ///
+    /// See [`BaselineManager::load_window`].
+    pub fn with_compare_across_machines(mut self, compare_across_machines: bool) -> Self {
+        self.compare_across_machines = compare_across_machines;
+        self
+    }
+
+    /// Prune each benchmark's runs to `retention` after saving one (unlimited
+    /// by default)
+    ///
+    /// See [`BaselineManager::prune`].
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Get the machine identifier used to partition baselines
+    pub fn machine_id(&self) -> &str {
+        &self.machine_id
+    }
+
+    /// The `.benches` directory this manager reads and writes
+    pub fn root_dir(&self) -> &Path {
+        &self.root_dir
     }
 
     /// Get the directory path for this machine's baselines
     fn machine_dir(&self) -> PathBuf {
-        self.root_dir.join(&self.machine_id)
+        self.machine_dir_for(&self.machine_id)
+    }
+
+    /// Get the directory path for another machine's baselines
+    fn machine_dir_for(&self, machine_id: &str) -> PathBuf {
+        self.root_dir.join(machine_id)
     }
 
     /// Get the directory path for a specific benchmark's runs
     fn benchmark_dir(&self, crate_name: &str, benchmark_name: &str) -> PathBuf {
+        self.benchmark_dir_for(&self.machine_id, crate_name, benchmark_name)
+    }
+
+    /// Get the directory path for a specific benchmark's runs on a given machine
+    ///
+    /// A benchmark stored under its module path (see [`storage_name`]) that
+    /// has no directory of its own there yet reads the `<crate>_<name>` one
+    /// its runs were kept in before; its next save moves them over.
+    pub(crate) fn benchmark_dir_for(
+        &self,
+        machine_id: &str,
+        crate_name: &str,
+        benchmark_name: &str,
+    ) -> PathBuf {
+        let dir = self.storage_dir_for(machine_id, crate_name, benchmark_name);
+        match self.legacy_dir_for(machine_id, crate_name, benchmark_name) {
+            Some((legacy_dir, _)) if !dir.exists() => legacy_dir,
+            _ => dir,
+        }
+    }
+
+    /// Module of the runs a benchmark reads when `bench_dir` is the
+    /// `<crate>_<name>` directory it shares with same-named benchmarks of
+    /// other modules (see [`BaselineManager::benchmark_dir_for`]); None when
+    /// every run there is its own
+    fn shared_dir_module(
+        &self,
+        machine_id: &str,
+        crate_name: &str,
+        benchmark_name: &str,
+        bench_dir: &Path,
+    ) -> Option<String> {
+        self.legacy_dir_for(machine_id, crate_name, benchmark_name)
+            .filter(|(legacy_dir, _)| legacy_dir == bench_dir)
+            .map(|(_, module)| module)
+    }
+
+    /// Directory a benchmark's runs are saved in on a given machine
+    fn storage_dir_for(&self, machine_id: &str, crate_name: &str, benchmark_name: &str) -> PathBuf {
         let dir_name = format!("{}_{}", crate_name, benchmark_name);
-        self.machine_dir().join(dir_name)
+        self.machine_dir_for(machine_id).join(dir_name)
     }
 
-    /// Get the file path for a specific benchmark baseline (legacy - single file)
-    fn legacy_baseline_path(&self, crate_name: &str, benchmark_name: &str) -> PathBuf {
-        let filename = format!("{}_{}.json", crate_name, benchmark_name);
-        self.machine_dir().join(filename)
+    /// The `<crate>_<name>` directory a benchmark stored under its module path
+    /// kept its runs in before, with that module; None when there is none
+    ///
+    /// The directory may hold same-named benchmarks of other modules too.
+    fn legacy_dir_for(
+        &self,
+        machine_id: &str,
+        crate_name: &str,
+        benchmark_name: &str,
+    ) -> Option<(PathBuf, String)> {
+        let (path, name) = benchmark_name.rsplit_once('-')?;
+        let dir = self
+            .machine_dir_for(machine_id)
+            .join(format!("{}_{}", crate_name, name));
+        let module = format!("{}::{}", crate_name, path.replace('-', "::"));
+        dir.is_dir().then_some((dir, module))
     }
 
-    /// Get a timestamped run path for a new baseline
-    fn get_run_path(&self, crate_name: &str, benchmark_name: &str) -> PathBuf {
-        let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H-%M-%S");
-        let filename = format!("{}.json", timestamp);
-        self.benchmark_dir(crate_name, benchmark_name)
-            .join(filename)
+    /// Get the file path for a specific benchmark baseline (legacy - single file)
+    fn legacy_baseline_path(&self, crate_name: &str, benchmark_name: &str) -> PathBuf {
+        self.legacy_baseline_path_for(&self.machine_id, crate_name, benchmark_name)
     }
 
-    /// Ensure the baseline directory exists
-    fn ensure_dir_exists(
+    /// Get the legacy single-file baseline path of a benchmark on a given machine
+    fn legacy_baseline_path_for(
         &self,
+        machine_id: &str,
         crate_name: &str,
         benchmark_name: &str,
-    ) -> Result<(), std::io::Error> {
-        fs::create_dir_all(self.benchmark_dir(crate_name, benchmark_name))
+    ) -> PathBuf {
+        let filename = format!("{}_{}.json", crate_name, benchmark_name);
+        self.machine_dir_for(machine_id).join(filename)
     }
 
     /// Save a benchmark result as a baseline (creates new timestamped file)
+    ///
+    /// When this creates a benchmark's directory and exactly one other benchmark
+    /// in the same module has a latest run with the identical body hash, the
+    /// benchmark is treated as renamed and a [`RenameRedirect`] is recorded so
+    /// its former history keeps feeding the comparison window.
     pub fn save_baseline(
         &self,
         crate_name: &str,
         result: &BenchResult,
         was_regression: bool,
     ) -> Result<(), std::io::Error> {
-        self.ensure_dir_exists(crate_name, &result.name)?;
-
-        let baseline =
-            BaselineData::from_bench_result(result, self.machine_id.clone(), was_regression);
-        let json = serde_json::to_string_pretty(&baseline)?;
-
-        let path = self.get_run_path(crate_name, &result.name);
-        fs::write(path, json)?;
+        self.save_run(crate_name, result, was_regression, false)
+    }
 
-        Ok(())
+    /// Save a benchmark result as a baseline flagged with the verdict of its
+    /// comparison, so later windows can skip a regression or start at an
+    /// improvement
+    pub fn save_judged_baseline(
+        &self,
+        crate_name: &str,
+        result: &BenchResult,
+        comparison: &ComparisonResult,
+    ) -> Result<(), std::io::Error> {
+        self.save_run(
+            crate_name,
+            result,
+            comparison.is_regression,
+            comparison.is_improvement,
+        )
     }
 
-    /// Load the most recent baseline for a specific benchmark
-    pub fn load_baseline(
+    fn save_run(
         &self,
         crate_name: &str,
-        benchmark_name: &str,
-    ) -> Result<Option<BaselineData>, std::io::Error> {
-        let bench_dir = self.benchmark_dir(crate_name, benchmark_name);
+        result: &BenchResult,
+        was_regression: bool,
+        was_improvement: bool,
+    ) -> Result<(), std::io::Error> {
+        self.migrate_legacy_once()?;
 
-        // Check if new directory structure exists
-        if bench_dir.exists() && bench_dir.is_dir() {
-            // Find most recent JSON file
-            let mut runs: Vec<_> = fs::read_dir(&bench_dir)?
-                .filter_map(|e| e.ok())
-                .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
-                .collect();
+        let (_, benchmark_name) = storage_name(&result.module, &result.name);
+        self.migrate_module_key(crate_name, &benchmark_name)?;
+        let bench_dir = self.storage_dir_for(&self.machine_id, crate_name, &benchmark_name);
+        let is_new = !bench_dir.exists();
+        fs::create_dir_all(&bench_dir)?;
 
-            if runs.is_empty() {
-                return Ok(None);
+        if is_new && self.follow_renames {
+            if let Some(former_name) = self.find_former_name(crate_name, result)? {
+                let redirect = RenameRedirect {
+                    redirect_from: former_name,
+                };
+                crate::lock::write_atomic(
+                    &bench_dir.join(REDIRECT_FILE),
+                    serde_json::to_string_pretty(&redirect)?.as_bytes(),
+                )?;
             }
+        }
 
-            // Sort by filename (timestamps are sortable)
-            runs.sort_by_key(|e| e.file_name());
-            let latest = runs.last().unwrap();
+        let mut baseline =
+            BaselineData::from_bench_result(result, self.machine_id.clone(), was_regression);
+        baseline.was_improvement = was_improvement;
+        let json = serde_json::to_string_pretty(&baseline)?;
+        let extension = if self.compress {
+            COMPRESSED_RUN_EXTENSION
+        } else {
+            RUN_EXTENSION
+        };
 
-            let contents = fs::read_to_string(latest.path())?;
-            let baseline: BaselineData = serde_json::from_str(&contents)?;
-            return Ok(Some(baseline));
-        }
+        // Named by timestamp; a second run saved within the same second gets a suffix
+        let timestamp = chrono::Utc::now().format(RUN_FILE_TIMESTAMP).to_string();
+        crate::lock::create_run_file(
+            &bench_dir,
+            &timestamp,
+            extension,
+            &encode_run(&json, self.compress)?,
+        )?;
 
-        // Fall back to legacy single-file format
-        let legacy_path = self.legacy_baseline_path(crate_name, benchmark_name);
-        if legacy_path.exists() {
-            let contents = fs::read_to_string(legacy_path)?;
-            let baseline: BaselineData = serde_json::from_str(&contents)?;
-            return Ok(Some(baseline));
+        // The run is saved either way; a failed cleanup is only reported
+        if !self.retention.is_unlimited() {
+            if let Err(e) = self.prune(crate_name, &benchmark_name, self.retention) {
+                eprintln!(
+                    "Warning: Failed to prune old runs of {}: {}",
+                    result.name, e
+                );
+            }
         }
 
-        Ok(None)
+        Ok(())
     }
 
-    /// Check if a baseline exists for a benchmark
-    pub fn has_baseline(&self, crate_name: &str, benchmark_name: &str) -> bool {
-        let bench_dir = self.benchmark_dir(crate_name, benchmark_name);
-        if bench_dir.exists() && bench_dir.is_dir() {
-            return true;
+    /// Migrate legacy baselines on the first save of this manager
+    fn migrate_legacy_once(&self) -> Result<(), std::io::Error> {
+        if !self.legacy_migrated.swap(true, Ordering::Relaxed) {
+            let migrated = self.migrate_legacy_baselines()?;
+            if migrated > 0 {
+                eprintln!(
+                    "migrated {} legacy baselines to the per-run directory format",
+                    migrated
+                );
+            }
         }
-        self.legacy_baseline_path(crate_name, benchmark_name)
-            .exists()
+        Ok(())
     }
 
-    /// List all run timestamps for a specific benchmark
-    pub fn list_runs(
+    /// Convert this machine's legacy `<crate>_<bench>.json` files into runs of
+    /// their benchmark directories, returning how many were migrated
+    ///
+    /// Each run is named after the timestamp stored in the file (its mtime if
+    /// that doesn't parse). The legacy file is removed once its run is written;
+    /// an existing run of the same name is kept and the legacy file dropped.
+    pub fn migrate_legacy_baselines(&self) -> Result<usize, std::io::Error> {
+        let machine_dir = self.machine_dir();
+        if !machine_dir.is_dir() {
+            return Ok(0);
+        }
+
+        let mut migrated = 0;
+        for entry in fs::read_dir(&machine_dir)? {
+            let path = entry?.path();
+            if !path.is_file() || !path.extension().is_some_and(|ext| ext == "json") {
+                continue;
+            }
+            let Some(dir_name) = path.file_stem() else {
+                continue;
+            };
+            let contents = fs::read_to_string(&path)?;
+            let Ok(baseline) = parse_baseline(&contents) else {
+                continue;
+            };
+
+            let timestamp = run_timestamp(&baseline.timestamp)
+                .or_else(|| {
+                    let modified = fs::metadata(&path).ok()?.modified().ok()?;
+                    Some(
+                        chrono::DateTime::<chrono::Utc>::from(modified)
+                            .format(RUN_FILE_TIMESTAMP)
+                            .to_string(),
+                    )
+                })
+                .unwrap_or_else(|| "0000-00-00T00-00-00".to_string());
+
+            let bench_dir = machine_dir.join(dir_name);
+            fs::create_dir_all(&bench_dir)?;
+            let run_path = bench_dir.join(format!("{}.json", timestamp));
+            if !run_path.exists() {
+                crate::lock::write_atomic(&run_path, contents.as_bytes())?;
+            }
+            fs::remove_file(&path)?;
+            migrated += 1;
+        }
+
+        Ok(migrated)
+    }
+
+    /// Move this machine's runs of a benchmark stored under its module path
+    /// (see [`storage_name`]) out of the `<crate>_<name>` directory they were
+    /// kept in before, returning how many were moved
+    ///
+    /// Only runs recorded by the benchmark's module move; those of same-named
+    /// benchmarks in other modules stay for their own benchmark's next save,
+    /// and the directory is removed once none is left. Does nothing once the
+    /// benchmark has a directory of its own.
+    fn migrate_module_key(
         &self,
         crate_name: &str,
         benchmark_name: &str,
-    ) -> Result<Vec<String>, std::io::Error> {
-        let bench_dir = self.benchmark_dir(crate_name, benchmark_name);
-
-        if !bench_dir.exists() || !bench_dir.is_dir() {
-            return Ok(vec![]);
+    ) -> Result<usize, std::io::Error> {
+        let bench_dir = self.storage_dir_for(&self.machine_id, crate_name, benchmark_name);
+        if bench_dir.exists() {
+            return Ok(0);
+        }
+        let Some((legacy_dir, module)) =
+            self.legacy_dir_for(&self.machine_id, crate_name, benchmark_name)
+        else {
+            return Ok(0);
+        };
+
+        let mut moved = 0;
+        let mut left = 0;
+        for entry in fs::read_dir(&legacy_dir)? {
+            let path = entry?.path();
+            let Some(file_name) = path.file_name().map(|name| name.to_os_string()) else {
+                continue;
+            };
+            if run_name(&file_name.to_string_lossy()).is_none() {
+                continue;
+            }
+            let own = read_run_file(&path)
+                .ok()
+                .and_then(|contents| parse_baseline(&contents).ok())
+                .is_some_and(|run| run.module == module);
+            if !own {
+                left += 1;
+                continue;
+            }
+            fs::create_dir_all(&bench_dir)?;
+            fs::rename(&path, bench_dir.join(file_name))?;
+            moved += 1;
+        }
+        if moved == 0 {
+            return Ok(0);
+        }
+
+        // A rename marker was left by whichever benchmark saved there first
+        let redirect = legacy_dir.join(REDIRECT_FILE);
+        if redirect.is_file() {
+            fs::copy(&redirect, bench_dir.join(REDIRECT_FILE))?;
+        }
+        if left == 0 {
+            fs::remove_dir_all(&legacy_dir)?;
+        }
+        let dir_name = |dir: &Path| {
+            dir.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        };
+        eprintln!(
+            "moved {} runs of {} from {} to {}",
+            moved,
+            module,
+            dir_name(&legacy_dir),
+            dir_name(&bench_dir)
+        );
+        Ok(moved)
+    }
+
+    /// Find the benchmark a newly seen result was most likely renamed from
+    ///
+    /// Conservative: requires an exact body hash and module match, and gives up
+    /// if more than one former benchmark matches.
+    fn find_former_name(
+        &self,
+        crate_name: &str,
+        result: &BenchResult,
+    ) -> Result<Option<String>, std::io::Error> {
+        let Some(ref body_hash) = result.body_hash else {
+            return Ok(None);
+        };
+
+        let mut candidates = Vec::new();
+        let (_, own_name) = storage_name(&result.module, &result.name);
+        for name in self.list_baselines(crate_name)? {
+            if name == own_name {
+                continue;
+            }
+            if let Ok(Some(latest)) = self.load_baseline(crate_name, &name) {
+                if latest.module == result.module
+                    && latest.body_hash.as_deref() == Some(body_hash.as_str())
+                {
+                    candidates.push(name);
+                }
+            }
+        }
+
+        if candidates.len() == 1 {
+            Ok(candidates.pop())
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Read the rename marker of a benchmark on a given machine, if any
+    pub(crate) fn load_redirect(
+        &self,
+        machine_id: &str,
+        crate_name: &str,
+        benchmark_name: &str,
+    ) -> Option<RenameRedirect> {
+        let path = self
+            .benchmark_dir_for(machine_id, crate_name, benchmark_name)
+            .join(REDIRECT_FILE);
+        let contents = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// Load the most recent baseline for a specific benchmark
+    pub fn load_baseline(
+        &self,
+        crate_name: &str,
+        benchmark_name: &str,
+    ) -> Result<Option<BaselineData>, std::io::Error> {
+        let bench_dir = self.benchmark_dir(crate_name, benchmark_name);
+        let module =
+            self.shared_dir_module(&self.machine_id, crate_name, benchmark_name, &bench_dir);
+
+        // Check if new directory structure exists
+        if bench_dir.exists() && bench_dir.is_dir() {
+            // Find most recent run, compressed or not
+            let mut runs: Vec<_> = fs::read_dir(&bench_dir)?
+                .filter_map(|e| e.ok())
+                .filter(|e| run_name(&e.file_name().to_string_lossy()).is_some())
+                .collect();
+
+            if runs.is_empty() {
+                return Ok(None);
+            }
+
+            // Sort by filename (timestamps are sortable); a run pruned since
+            // the listing falls back to the one before
+            runs.sort_by_key(|e| e.file_name());
+            for run in runs.iter().rev() {
+                let baseline = match read_run_file(&run.path()) {
+                    Ok(contents) => parse_baseline(&contents)?,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                    Err(e) => return Err(e),
+                };
+                if module
+                    .as_ref()
+                    .is_some_and(|module| baseline.module != *module)
+                {
+                    continue;
+                }
+                return Ok(Some(baseline));
+            }
+            return Ok(None);
+        }
+
+        // Fall back to legacy single-file format
+        let legacy_path = self.legacy_baseline_path(crate_name, benchmark_name);
+        if legacy_path.exists() {
+            let contents = fs::read_to_string(legacy_path)?;
+            let baseline = parse_baseline(&contents)?;
+            return Ok(Some(baseline));
+        }
+
+        Ok(None)
+    }
+
+    /// Check if a baseline exists for a benchmark
+    pub fn has_baseline(&self, crate_name: &str, benchmark_name: &str) -> bool {
+        let bench_dir = self.benchmark_dir(crate_name, benchmark_name);
+        if self
+            .shared_dir_module(&self.machine_id, crate_name, benchmark_name, &bench_dir)
+            .is_some()
+        {
+            return matches!(self.load_baseline(crate_name, benchmark_name), Ok(Some(_)));
+        }
+        if bench_dir.exists() && bench_dir.is_dir() {
+            return true;
+        }
+        self.legacy_baseline_path(crate_name, benchmark_name)
+            .exists()
+    }
+
+    /// List all run timestamps for a specific benchmark
+    pub fn list_runs(
+        &self,
+        crate_name: &str,
+        benchmark_name: &str,
+    ) -> Result<Vec<String>, std::io::Error> {
+        let bench_dir = self.benchmark_dir(crate_name, benchmark_name);
+
+        if !bench_dir.exists() || !bench_dir.is_dir() {
+            return Ok(vec![]);
         }
 
         let mut runs: Vec<String> = fs::read_dir(&bench_dir)?
             .filter_map(|e| e.ok())
-            .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
-            .filter_map(|e| {
-                e.file_name()
-                    .to_string_lossy()
-                    .strip_suffix(".json")
-                    .map(|s| s.to_string())
-            })
+            .filter_map(|e| run_name(&e.file_name().to_string_lossy()).map(str::to_string))
             .collect();
 
+        // A run stored both compressed and not is listed once
         runs.sort();
+        runs.dedup();
         Ok(runs)
     }
 
+    /// File of the run stored under `timestamp` (as returned by [`BaselineManager::list_runs`])
+    ///
+    /// The uncompressed file when both exist, and the `.json` name when
+    /// neither does.
+    pub fn run_path(&self, crate_name: &str, benchmark_name: &str, timestamp: &str) -> PathBuf {
+        let bench_dir = self.benchmark_dir(crate_name, benchmark_name);
+        let plain = bench_dir.join(format!("{}{}", timestamp, RUN_EXTENSION));
+        let compressed = bench_dir.join(format!("{}{}", timestamp, COMPRESSED_RUN_EXTENSION));
+        if !plain.exists() && compressed.exists() {
+            compressed
+        } else {
+            plain
+        }
+    }
+
     /// Load a specific run by timestamp
     pub fn load_run(
         &self,
@@ -301,17 +1244,95 @@ impl BaselineManager {
         benchmark_name: &str,
         timestamp: &str,
     ) -> Result<Option<BaselineData>, std::io::Error> {
+        let path = self.run_path(crate_name, benchmark_name, timestamp);
+
+        // Missing, or pruned since it was listed
+        let contents = match read_run_file(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let baseline = parse_baseline(&contents)?;
+        Ok(Some(baseline))
+    }
+
+    /// Delete the run stored under `timestamp`, or with None every run of the
+    /// benchmark on this machine, a legacy single-file baseline included
+    ///
+    /// Returns the number of runs deleted. Doesn't take the writer lock; callers
+    /// outside a benchmark session should hold it ([`BaselineManager::lock_writer`]).
+    pub fn remove_runs(
+        &self,
+        crate_name: &str,
+        benchmark_name: &str,
+        timestamp: Option<&str>,
+    ) -> Result<usize, std::io::Error> {
+        let remove = |path: &Path| match fs::remove_file(path) {
+            Ok(()) => Ok(1),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e),
+        };
+        if let Some(timestamp) = timestamp {
+            let bench_dir = self.benchmark_dir(crate_name, benchmark_name);
+            let mut removed = 0;
+            for extension in [RUN_EXTENSION, COMPRESSED_RUN_EXTENSION] {
+                removed += remove(&bench_dir.join(format!("{}{}", timestamp, extension)))?;
+            }
+            return Ok(removed.min(1));
+        }
+
+        let mut removed = self.list_runs(crate_name, benchmark_name)?.len();
         let bench_dir = self.benchmark_dir(crate_name, benchmark_name);
-        let filename = format!("{}.json", timestamp);
-        let path = bench_dir.join(filename);
+        match fs::remove_dir_all(&bench_dir) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        removed += remove(&self.legacy_baseline_path(crate_name, benchmark_name))?;
+        Ok(removed)
+    }
 
-        if !path.exists() {
-            return Ok(None);
+    /// Accept the latest regression of a benchmark on this machine as its new
+    /// performance level
+    ///
+    /// The most recent regression-flagged run and the flagged runs right before
+    /// it lose their flag, and the first of them is marked
+    /// [`BaselineData::accepted`]: windows start there, so the runs measured at
+    /// the old level no longer count. Returns None when no run is flagged.
+    pub fn accept_regression(
+        &self,
+        crate_name: &str,
+        benchmark_name: &str,
+    ) -> Result<Option<AcceptedRegression>, std::io::Error> {
+        let mut streak = Vec::new();
+        for timestamp in self
+            .list_runs(crate_name, benchmark_name)?
+            .into_iter()
+            .rev()
+        {
+            // Unreadable runs never feed a window either way
+            let Ok(Some(run)) = self.load_run(crate_name, benchmark_name, &timestamp) else {
+                continue;
+            };
+            if run.was_regression {
+                streak.push((timestamp, run));
+            } else if !streak.is_empty() {
+                break;
+            }
         }
+        let Some(since) = streak.last().map(|(timestamp, _)| timestamp.clone()) else {
+            return Ok(None);
+        };
 
-        let contents = fs::read_to_string(path)?;
-        let baseline: BaselineData = serde_json::from_str(&contents)?;
-        Ok(Some(baseline))
+        let runs = streak.len();
+        for (timestamp, mut run) in streak {
+            run.was_regression = false;
+            run.accepted = timestamp == since;
+            let path = self.run_path(crate_name, benchmark_name, &timestamp);
+            let json = serde_json::to_string_pretty(&run)?;
+            crate::lock::write_atomic(&path, &encode_run(&json, is_compressed(&path))?)?;
+        }
+        Ok(Some(AcceptedRegression { runs, since }))
     }
 
     /// List all baselines for a crate
@@ -349,57 +1370,345 @@ impl BaselineManager {
         Ok(baselines)
     }
 
+    /// List the `<crate>_<benchmark>` directory names with stored runs on this machine
+    pub fn list_benchmarks(&self) -> Result<Vec<String>, std::io::Error> {
+        let machine_dir = self.machine_dir();
+
+        if !machine_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut benchmarks: Vec<String> = fs::read_dir(machine_dir)?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+
+        benchmarks.sort();
+        Ok(benchmarks)
+    }
+
+    /// This machine's benchmark directories whose name `filter` matches, or
+    /// all of them without one, sorted
+    pub fn stored_benchmarks(
+        &self,
+        filter: Option<&BenchFilter>,
+    ) -> Result<Vec<StoredBenchmark>, std::io::Error> {
+        Ok(self
+            .list_benchmarks()?
+            .into_iter()
+            .filter(|dir_name| filter.map_or(true, |filter| filter.matches(dir_name)))
+            .filter_map(|dir_name| {
+                let (crate_name, benchmark_name) = dir_name.split_once('_')?;
+                Some(StoredBenchmark {
+                    crate_name: crate_name.to_string(),
+                    benchmark_name: benchmark_name.to_string(),
+                    dir_name,
+                })
+            })
+            .collect())
+    }
+
+    /// List all machine IDs that have stored baselines under the root directory
+    ///
+    /// When `.benches` is shared (e.g. committed to the repository), this includes
+    /// CI and other developers' machines as well as the local one.
+    pub fn list_machines(&self) -> Result<Vec<String>, std::io::Error> {
+        if !self.root_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut machines: Vec<String> = fs::read_dir(&self.root_dir)?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+
+        machines.sort();
+        Ok(machines)
+    }
+
     /// Load last N baseline runs for a benchmark
     ///
     /// Returns the most recent baseline runs in chronological order (oldest first).
     /// **Excludes runs that were flagged as regressions** to keep the baseline clean.
     /// This is used for statistical window comparison.
+    ///
+    /// Only this machine's history is considered, so runs from other machines sharing
+    /// the same `.benches` tree never mix into the window.
+    ///
+    /// If the benchmark was renamed (see [`BaselineManager::save_baseline`]) and its
+    /// own history doesn't fill the window yet, runs under the former name are
+    /// included ahead of it.
+    ///
+    /// With [`BaselineManager::with_improvement_resets_window`], the window starts
+    /// at the latest run flagged as a confirmed improvement: that run began a new
+    /// performance level, and the runs before it would only drag the window back.
     pub fn load_recent_baselines(
         &self,
         crate_name: &str,
         benchmark_name: &str,
         count: usize,
     ) -> Result<Vec<BaselineData>, std::io::Error> {
-        let bench_dir = self.benchmark_dir(crate_name, benchmark_name);
+        Ok(self.load_window(crate_name, benchmark_name, count)?.runs)
+    }
 
-        if !bench_dir.exists() || !bench_dir.is_dir() {
-            return Ok(vec![]);
+    /// [`BaselineManager::load_recent_baselines`], telling an empty window of a
+    /// benchmark with no history apart from one whose every run was excluded
+    ///
+    /// With [`BaselineManager::with_compare_across_machines`], the windows of
+    /// the other machines sharing the tree are pooled in, each run scaled by
+    /// the ratio of this machine's median run mean to its own machine's. A
+    /// run is then judged by its ratio to its machine's typical time rather
+    /// than by nanoseconds, so developers on different hardware share one
+    /// history. This machine needs a run of its own to be normalized against;
+    /// until then the window is empty as usual.
+    pub fn load_window(
+        &self,
+        crate_name: &str,
+        benchmark_name: &str,
+        count: usize,
+    ) -> Result<Window, std::io::Error> {
+        let mut window =
+            self.load_window_for_machine(&self.machine_id, crate_name, benchmark_name, count)?;
+        if !self.compare_across_machines || window.runs.is_empty() {
+            return Ok(window);
         }
 
-        // List all run timestamps
-        let mut runs: Vec<_> = fs::read_dir(&bench_dir)?
-            .filter_map(|e| e.ok())
-            .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
-            .collect();
+        let local_median = median_run_mean(&window.runs);
+        for machine_id in self.list_machines()? {
+            if machine_id == self.machine_id {
+                continue;
+            }
+            let runs = self.load_recent_baselines_for_machine(
+                &machine_id,
+                crate_name,
+                benchmark_name,
+                count,
+            )?;
+            let machine_median = median_run_mean(&runs);
+            if machine_median <= 0.0 {
+                continue;
+            }
+            let factor = local_median / machine_median;
+            window
+                .runs
+                .extend(runs.iter().map(|run| run.scaled(factor)));
+        }
+        // Newest `count` runs of any machine, oldest first
+        window.runs.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        window.runs.drain(..window.runs.len().saturating_sub(count));
+        Ok(window)
+    }
 
-        if runs.is_empty() {
-            return Ok(vec![]);
+    /// [`BaselineManager::load_window`] without the runs recorded with
+    /// settings not comparable to `settings` (when known)
+    ///
+    /// A window emptied this way is [`WindowOutcome::EmptyFiltered`], so the
+    /// run is reported as starting a new window instead of judged against
+    /// runs measured differently.
+    pub fn load_comparable_window(
+        &self,
+        crate_name: &str,
+        benchmark_name: &str,
+        count: usize,
+        settings: Option<&MeasurementSettings>,
+    ) -> Result<Window, std::io::Error> {
+        let mut window = self.load_window(crate_name, benchmark_name, count)?;
+        if let Some(settings) = settings {
+            window.retain_comparable(settings);
+        }
+        Ok(window)
+    }
+
+    /// Load last N baseline runs for a benchmark recorded on a specific machine
+    ///
+    /// Same semantics as [`BaselineManager::load_recent_baselines`], but reads the
+    /// history partition of `machine_id` instead of the local machine.
+    pub fn load_recent_baselines_for_machine(
+        &self,
+        machine_id: &str,
+        crate_name: &str,
+        benchmark_name: &str,
+        count: usize,
+    ) -> Result<Vec<BaselineData>, std::io::Error> {
+        Ok(self
+            .load_window_for_machine(machine_id, crate_name, benchmark_name, count)?
+            .runs)
+    }
+
+    fn load_window_for_machine(
+        &self,
+        machine_id: &str,
+        crate_name: &str,
+        benchmark_name: &str,
+        count: usize,
+    ) -> Result<Window, std::io::Error> {
+        let (mut baselines, mut excluded) =
+            self.load_own_baselines(machine_id, crate_name, benchmark_name, count)?;
+
+        // Top up a young window with history from before a rename, unless it
+        // starts at an improvement
+        let era_start = baselines
+            .first()
+            .is_some_and(|b| b.accepted || self.improvement_resets_window && b.was_improvement);
+        if baselines.len() < count && self.follow_renames && !era_start {
+            if let Some(redirect) = self.load_redirect(machine_id, crate_name, benchmark_name) {
+                let (former, former_excluded) = self.load_own_baselines(
+                    machine_id,
+                    crate_name,
+                    &redirect.redirect_from,
+                    count - baselines.len(),
+                )?;
+                for (total, former) in excluded.iter_mut().zip(former_excluded) {
+                    total.runs += former.runs;
+                }
+                if !former.is_empty() {
+                    eprintln!(
+                        "including history from former name {} (renamed)",
+                        redirect.redirect_from
+                    );
+                    baselines.splice(0..0, former);
+                }
+            }
         }
 
+        Ok(Window {
+            outcome: WindowOutcome::from_exclusions(baselines.len(), &excluded),
+            runs: baselines,
+        })
+    }
+
+    /// Best window of another machine for a benchmark this machine has no history of
+    ///
+    /// Considers every other machine sharing the `.benches` tree and picks the one
+    /// with the fullest window of the last `count` runs, breaking ties by the most
+    /// recent run. Returns the machine ID with its window, oldest first.
+    pub fn load_reference_baselines(
+        &self,
+        crate_name: &str,
+        benchmark_name: &str,
+        count: usize,
+    ) -> Result<Option<(String, Vec<BaselineData>)>, std::io::Error> {
+        let mut best: Option<(String, Vec<BaselineData>)> = None;
+
+        for machine_id in self.list_machines()? {
+            if machine_id == self.machine_id {
+                continue;
+            }
+            let window = self.load_recent_baselines_for_machine(
+                &machine_id,
+                crate_name,
+                benchmark_name,
+                count,
+            )?;
+            let Some(latest) = window.last() else {
+                continue;
+            };
+
+            let better = match best {
+                None => true,
+                Some((_, ref current)) => {
+                    (window.len(), &latest.timestamp)
+                        > (current.len(), &current[current.len() - 1].timestamp)
+                }
+            };
+            if better {
+                best = Some((machine_id, window));
+            }
+        }
+
+        Ok(best)
+    }
+
+    /// Load last N non-regression runs stored under exactly `benchmark_name`,
+    /// with the runs passed over on the way (complete only when fewer than N
+    /// runs were found)
+    fn load_own_baselines(
+        &self,
+        machine_id: &str,
+        crate_name: &str,
+        benchmark_name: &str,
+        count: usize,
+    ) -> Result<(Vec<BaselineData>, [Exclusion; 2]), std::io::Error> {
+        let bench_dir = self.benchmark_dir_for(machine_id, crate_name, benchmark_name);
+        let module = self.shared_dir_module(machine_id, crate_name, benchmark_name, &bench_dir);
+
+        // List all run timestamps
+        let mut runs: Vec<PathBuf> = if bench_dir.is_dir() {
+            fs::read_dir(&bench_dir)?
+                .filter_map(|e| e.ok())
+                .filter(|e| run_name(&e.file_name().to_string_lossy()).is_some())
+                .map(|e| e.path())
+                .collect()
+        } else {
+            vec![]
+        };
+
         // Sort chronologically by filename (timestamps are sortable)
-        runs.sort_by_key(|e| e.file_name());
+        runs.sort();
+
+        // A not yet migrated legacy file predates every run in the directory
+        let legacy_path = self.legacy_baseline_path_for(machine_id, crate_name, benchmark_name);
+        if legacy_path.is_file() {
+            runs.insert(0, legacy_path);
+        }
 
         // Load baseline data, filtering out regressions
         let mut baselines = Vec::new();
-        for entry in runs.iter().rev() {
+        let mut regressions = 0;
+        let mut unreadable = 0;
+        for path in runs.iter().rev() {
             // Stop once we have enough non-regression baselines
             if baselines.len() >= count {
                 break;
             }
 
-            let contents = fs::read_to_string(entry.path())?;
-            if let Ok(baseline) = serde_json::from_str::<BaselineData>(&contents) {
-                // Skip runs that were detected as regressions
-                if !baseline.was_regression {
-                    baselines.push(baseline);
-                }
+            // Pruned since the listing
+            let contents = match read_run_file(path) {
+                Ok(contents) => contents,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            let Ok(baseline) = parse_baseline(&contents) else {
+                unreadable += 1;
+                continue;
+            };
+            if module
+                .as_ref()
+                .is_some_and(|module| baseline.module != *module)
+            {
+                continue;
+            }
+            // Skip runs that were detected as regressions
+            if baseline.was_regression {
+                regressions += 1;
+                continue;
+            }
+            // Nothing before a confirmed improvement or an accepted regression
+            // belongs to the window
+            let era_start =
+                baseline.accepted || self.improvement_resets_window && baseline.was_improvement;
+            baselines.push(baseline);
+            if era_start {
+                break;
             }
         }
 
         // Reverse to get chronological order (oldest first)
         baselines.reverse();
 
-        Ok(baselines)
+        let excluded = [
+            Exclusion {
+                reason: ExclusionReason::FlaggedRegression,
+                runs: regressions,
+            },
+            Exclusion {
+                reason: ExclusionReason::Unreadable,
+                runs: unreadable,
+            },
+        ];
+        Ok((baselines, excluded))
     }
 }
 
@@ -409,296 +1718,3361 @@ impl Default for BaselineManager {
     }
 }
 
-/// Result of baseline comparison for a single benchmark
-#[derive(Debug, Clone)]
-pub struct ComparisonResult {
+/// Saves queued on a [`BaselineWriter`] before it blocks the caller
+const WRITER_QUEUE_LEN: usize = 16;
+
+struct PendingSave {
+    crate_name: String,
+    result: BenchResult,
+    was_regression: bool,
+    was_improvement: bool,
+}
+
+/// A baseline the background writer couldn't save
+#[derive(Debug)]
+pub struct SaveFailure {
     pub benchmark_name: String,
-    pub comparison: Option<crate::Comparison>,
-    pub is_regression: bool,
+    pub error: std::io::Error,
 }
 
-/// Detect regression using statistical window + Bayesian Change Point Detection
+/// Saves baselines on a background thread, off the measurement path
 ///
-/// This function combines three criteria for robust regression detection:
-/// 1. Statistical significance (outside confidence interval)
-/// 2. Practical significance (exceeds threshold percentage)
-/// 3. Change point probability (likely distribution shift)
+/// Serializing and writing a run with many samples takes long enough to warm
+/// the core and delay the next benchmark, so a session only queues its saves
+/// here. The queue is bounded; [`BaselineWriter::finish`] waits for it to drain
+/// and returns the saves that failed. Without a thread the saves happen inline.
 ///
-/// All three conditions must be met for a regression to be flagged.
-pub fn detect_regression_with_cpd(
-    current: &crate::BenchResult,
-    historical: &[BaselineData],
-    threshold: f64,
-    confidence_level: f64,
-    cp_threshold: f64,
-    hazard_rate: f64,
-) -> ComparisonResult {
-    if historical.is_empty() {
-        return ComparisonResult {
-            benchmark_name: current.name.clone(),
+/// The writer holds the machine directory's writer lock ([`crate::lock`])
+/// until it finishes. When another process holds it, the session only
+/// compares: saves are dropped.
+pub struct BaselineWriter {
+    manager: std::sync::Arc<BaselineManager>,
+    sender: Option<std::sync::mpsc::SyncSender<PendingSave>>,
+    handle: Option<std::thread::JoinHandle<Vec<SaveFailure>>>,
+    /// Failures of inline saves
+    failures: Vec<SaveFailure>,
+    /// False when another process holds the writer lock
+    saving: bool,
+    lock: Option<crate::lock::WriterLock>,
+}
+
+impl BaselineWriter {
+    /// Start the writer thread, kept off `avoid_core` (the core benchmarks are
+    /// measured on) when there is another core to run on
+    pub fn spawn(manager: std::sync::Arc<BaselineManager>, avoid_core: Option<usize>) -> Self {
+        // Without a usable directory the saves fail and are reported themselves
+        let lock = match manager.lock_writer() {
+            Ok(lock) => Some(lock),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                eprintln!(
+                    "Warning: {}; comparing only, this session's baselines won't be saved",
+                    e
+                );
+                return Self {
+                    manager,
+                    sender: None,
+                    handle: None,
+                    failures: Vec::new(),
+                    saving: false,
+                    lock: None,
+                };
+            }
+            Err(_) => None,
+        };
+
+        // Legacy files would otherwise be moved while windows are being read
+        if let Err(e) = manager.migrate_legacy_once() {
+            eprintln!("Warning: Failed to migrate legacy baselines: {}", e);
+        }
+
+        let (sender, receiver) = std::sync::mpsc::sync_channel::<PendingSave>(WRITER_QUEUE_LEN);
+        let thread_manager = std::sync::Arc::clone(&manager);
+        let handle = std::thread::Builder::new()
+            .name("simplebench-baseline-writer".to_string())
+            .spawn(move || {
+                if let Some(avoid_core) = avoid_core {
+                    let others: Vec<usize> = (0..affinity::get_core_num())
+                        .filter(|&core| core != avoid_core)
+                        .collect();
+                    if !others.is_empty() {
+                        let _ = affinity::set_thread_affinity(&others);
+                    }
+                }
+
+                let mut failures = Vec::new();
+                for save in receiver {
+                    if let Err(error) = thread_manager.save_run(
+                        &save.crate_name,
+                        &save.result,
+                        save.was_regression,
+                        save.was_improvement,
+                    ) {
+                        failures.push(SaveFailure {
+                            benchmark_name: save.result.name,
+                            error,
+                        });
+                    }
+                }
+                failures
+            })
+            .ok();
+
+        Self {
+            manager,
+            sender: handle.is_some().then_some(sender),
+            handle,
+            failures: Vec::new(),
+            saving: true,
+            lock,
+        }
+    }
+
+    /// False when another process holds the writer lock and saves are dropped
+    pub fn is_saving(&self) -> bool {
+        self.saving
+    }
+
+    /// Queue `result` to be saved, flagged with the verdict of `comparison`
+    /// (unflagged for a benchmark without a window)
+    pub fn save(
+        &mut self,
+        crate_name: &str,
+        result: &BenchResult,
+        comparison: Option<&ComparisonResult>,
+    ) {
+        if !self.is_saving() {
+            return;
+        }
+        let save = PendingSave {
+            crate_name: crate_name.to_string(),
+            result: result.clone(),
+            was_regression: comparison.is_some_and(|c| c.is_regression),
+            was_improvement: comparison.is_some_and(|c| c.is_improvement),
+        };
+        // A writer that stopped hands its saves back; do them inline
+        let save = match self.sender {
+            Some(ref sender) => match sender.send(save) {
+                Ok(()) => return,
+                Err(std::sync::mpsc::SendError(save)) => save,
+            },
+            None => save,
+        };
+        if let Err(error) = self.manager.save_run(
+            &save.crate_name,
+            &save.result,
+            save.was_regression,
+            save.was_improvement,
+        ) {
+            self.failures.push(SaveFailure {
+                benchmark_name: save.result.name,
+                error,
+            });
+        }
+    }
+
+    /// Wait for every queued save and return the ones that failed
+    pub fn finish(mut self) -> Vec<SaveFailure> {
+        self.drain()
+    }
+
+    fn drain(&mut self) -> Vec<SaveFailure> {
+        // Closing the channel ends the writer's loop once the queue is empty
+        self.sender = None;
+        let mut failures = std::mem::take(&mut self.failures);
+        if let Some(handle) = self.handle.take() {
+            failures.extend(handle.join().unwrap_or_default());
+        }
+        // Every save is on disk; the next writer may start
+        self.lock = None;
+        failures
+    }
+}
+
+impl Drop for BaselineWriter {
+    fn drop(&mut self) {
+        for failure in self.drain() {
+            eprintln!(
+                "Warning: Failed to save baseline for {}: {}",
+                failure.benchmark_name, failure.error
+            );
+        }
+    }
+}
+
+/// Why stored runs were left out of a comparison window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExclusionReason {
+    /// Flagged as a regression when it was saved
+    FlaggedRegression,
+    /// The run file couldn't be parsed
+    Unreadable,
+    /// Recorded with measurement settings not comparable to the current run's
+    DifferentSettings,
+}
+
+/// Stored runs left out of a window for one reason
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exclusion {
+    pub reason: ExclusionReason,
+    pub runs: usize,
+}
+
+impl Exclusion {
+    /// "2 flagged regressions", "1 unreadable run"
+    pub fn format(&self) -> String {
+        let (one, many) = match self.reason {
+            ExclusionReason::FlaggedRegression => ("flagged regression", "flagged regressions"),
+            ExclusionReason::Unreadable => ("unreadable run", "unreadable runs"),
+            ExclusionReason::DifferentSettings => (
+                "run recorded with different settings",
+                "runs recorded with different settings",
+            ),
+        };
+        format!("{} {}", self.runs, if self.runs == 1 { one } else { many })
+    }
+}
+
+/// How a benchmark's comparison window came out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowOutcome {
+    /// The window holds at least one run
+    Populated,
+    /// No run of the benchmark is stored on this machine
+    EmptyNoHistory,
+    /// Runs are stored, but every one of them was excluded
+    EmptyFiltered { reasons: Vec<Exclusion> },
+}
+
+impl WindowOutcome {
+    /// Outcome of a window of `window_len` runs, given the runs left out while
+    /// building it
+    pub fn from_exclusions(window_len: usize, exclusions: &[Exclusion]) -> Self {
+        let reasons: Vec<Exclusion> = exclusions.iter().copied().filter(|e| e.runs > 0).collect();
+        if window_len > 0 {
+            WindowOutcome::Populated
+        } else if reasons.is_empty() {
+            WindowOutcome::EmptyNoHistory
+        } else {
+            WindowOutcome::EmptyFiltered { reasons }
+        }
+    }
+
+    /// "baseline history exists but was excluded: 6 flagged regressions, 1
+    /// unreadable run"; None unless history was excluded
+    pub fn exclusion_note(&self) -> Option<String> {
+        let WindowOutcome::EmptyFiltered { ref reasons } = *self else {
+            return None;
+        };
+        let reasons: Vec<String> = reasons.iter().map(Exclusion::format).collect();
+        Some(format!(
+            "baseline history exists but was excluded: {}",
+            reasons.join(", ")
+        ))
+    }
+}
+
+/// Median of the runs' means in nanoseconds; 0 without runs
+fn median_run_mean(runs: &[BaselineData]) -> f64 {
+    let means: Vec<f64> = runs.iter().map(|b| b.statistics.mean as f64).collect();
+    crate::statistics::median(&means)
+}
+
+/// A benchmark's comparison window, oldest run first, with how it came out
+#[derive(Debug, Clone)]
+pub struct Window {
+    pub runs: Vec<BaselineData>,
+    pub outcome: WindowOutcome,
+}
+
+impl Window {
+    /// Drop the runs whose recorded settings aren't comparable to `settings`,
+    /// counting them as excluded; runs from before settings were recorded stay
+    pub fn retain_comparable(&mut self, settings: &MeasurementSettings) {
+        let before = self.runs.len();
+        self.runs.retain(|run| {
+            run.settings
+                .as_ref()
+                .map_or(true, |recorded| recorded.comparable_with(settings))
+        });
+        let removed = before - self.runs.len();
+        if removed > 0 && self.runs.is_empty() {
+            self.outcome = WindowOutcome::EmptyFiltered {
+                reasons: vec![Exclusion {
+                    reason: ExclusionReason::DifferentSettings,
+                    runs: removed,
+                }],
+            };
+        }
+    }
+}
+
+/// Result of baseline comparison for a single benchmark
+#[derive(Debug, Clone)]
+pub struct ComparisonResult {
+    pub benchmark_name: String,
+    pub group: Option<String>,
+    pub comparison: Option<crate::Comparison>,
+    pub is_regression: bool,
+    /// Informational comparison against another machine's history, for a
+    /// benchmark with no history on this machine yet; never a regression
+    pub reference: Option<crate::Comparison>,
+    /// The current run failed the CV quality bar; never a regression
+    pub quality_failure: Option<crate::QualityFailure>,
+    /// The current run is significantly faster than the window, by the same
+    /// evidence a regression needs
+    pub is_improvement: bool,
+    /// How the window came out; an empty window over excluded history makes
+    /// the verdict PROVISIONAL instead of NEW
+    pub window: WindowOutcome,
+}
+
+impl ComparisonResult {
+    /// The compiler, build profile or CPU model differs from the window's, so
+    /// a regression may be the toolchain's rather than the code's
+    pub fn toolchain_changed(&self) -> bool {
+        self.comparison
+            .as_ref()
+            .is_some_and(|comparison| !comparison.toolchain_changes.is_empty())
+    }
+
+    /// No window to compare against because the benchmark has no history here
+    pub fn is_new(&self) -> bool {
+        self.comparison.is_none() && !self.history_excluded()
+    }
+
+    /// No window to compare against although history exists: every stored run
+    /// was excluded
+    pub fn history_excluded(&self) -> bool {
+        matches!(self.window, WindowOutcome::EmptyFiltered { .. })
+    }
+
+    /// Record how the window came out
+    pub fn with_window(mut self, window: WindowOutcome) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Allocations per sample grew past `alloc_threshold`
+    pub fn is_alloc_regression(&self) -> bool {
+        self.comparison
+            .as_ref()
+            .and_then(|c| c.allocs.as_ref())
+            .is_some_and(|a| a.is_regression)
+    }
+
+    /// The p99 grew past `p99_threshold` with the evidence a regression needs
+    pub fn is_tail_regression(&self) -> bool {
+        self.comparison
+            .as_ref()
+            .and_then(|c| c.p99.as_ref())
+            .is_some_and(|t| t.is_regression)
+    }
+
+    /// Trend whose drift over the window exceeded `drift_threshold`, if any
+    pub fn drift(&self) -> Option<&crate::statistics::Trend> {
+        self.comparison.as_ref().and_then(|c| c.drift.as_ref())
+    }
+
+    /// Fit a line through the means of `historical` (oldest first) and
+    /// `current`, keeping it when it climbs more than `drift_threshold` percent
+    /// over them.
+    ///
+    /// Only a warning: each step of a slow drift is within the threshold, so
+    /// the verdict stays as judged.
+    pub fn with_drift_check(
+        mut self,
+        current: &crate::BenchResult,
+        historical: &[BaselineData],
+        drift_threshold: Option<f64>,
+    ) -> Self {
+        let (Some(threshold), Some(comparison)) = (drift_threshold, self.comparison.as_mut())
+        else {
+            return self;
+        };
+        let means: Vec<f64> = historical
+            .iter()
+            .map(|b| b.statistics.mean as f64)
+            .chain([current.percentiles.mean.as_nanos() as f64])
+            .collect();
+        comparison.drift =
+            crate::statistics::linear_trend(&means).filter(|trend| trend.drift_pct() > threshold);
+        self
+    }
+
+    /// Attach the allocation comparison of `current` against `historical`.
+    ///
+    /// Kept apart from [`detect_regression_with_cpd`]: allocation counts are
+    /// close to deterministic, so a plain threshold on the window mean is enough.
+    pub fn with_alloc_comparison(
+        mut self,
+        current: &crate::BenchResult,
+        historical: &[BaselineData],
+        alloc_threshold: Option<f64>,
+    ) -> Self {
+        if let Some(ref mut comparison) = self.comparison {
+            comparison.allocs = compare_allocations(current, historical, alloc_threshold);
+        }
+        self
+    }
+
+    /// Attach a comparison against another machine's window when this
+    /// machine has no history to compare with.
+    ///
+    /// The local run still starts this machine's own window; the reference
+    /// only gives orientation and never affects the verdict.
+    pub fn with_reference(
+        mut self,
+        current: &crate::BenchResult,
+        reference: Option<(String, Vec<BaselineData>)>,
+        config: &ComparisonConfig,
+    ) -> Self {
+        if self.comparison.is_some() || self.history_excluded() {
+            return self;
+        }
+        if let Some((machine_id, window)) = reference {
+            self.reference =
+                detect_regression(current, &window, config)
+                    .comparison
+                    .map(|comparison| crate::Comparison {
+                        reference_machine: Some(machine_id),
+                        ..comparison
+                    });
+        }
+        self
+    }
+
+    /// Mark the comparison SUSPECT when `current`'s warmup and sampling
+    /// iteration times differ by more than `factor`; its verdict still stands
+    pub fn with_consistency_check(mut self, current: &crate::BenchResult, factor: f64) -> Self {
+        if let Some(ref mut comparison) = self.comparison {
+            comparison.suspect = current.phase_inconsistency(factor);
+        }
+        self
+    }
+
+    /// Exclude `current` from regression gating when it failed the CV quality
+    /// bar, marking its comparison SUSPECT
+    pub fn with_quality_check(mut self, current: &crate::BenchResult) -> Self {
+        self.quality_failure = current.quality_failure;
+        if self.quality_failure.is_some() {
+            self.is_regression = false;
+            self.is_improvement = false;
+            if let Some(ref mut comparison) = self.comparison {
+                comparison.quality_failure = current.quality_failure;
+            }
+        }
+        self
+    }
+}
+
+/// Compare mean instructions per sample; None unless both sides have counters
+fn compare_instructions(
+    current: &crate::BenchResult,
+    historical: &[BaselineData],
+) -> Option<crate::InstructionComparison> {
+    let current = crate::PerfSummary::from_samples(&current.perf_samples)?;
+    let historical: Vec<f64> = historical
+        .iter()
+        .filter_map(|b| crate::PerfSummary::from_samples(&b.perf_samples))
+        .map(|s| s.mean_instructions)
+        .collect();
+    if historical.is_empty() {
+        return None;
+    }
+
+    Some(crate::InstructionComparison {
+        current_instructions: current.mean_instructions,
+        baseline_instructions: crate::statistics::mean(&historical),
+    })
+}
+
+/// Compare mean allocations per sample; None unless both sides were tracked
+fn compare_allocations(
+    current: &crate::BenchResult,
+    historical: &[BaselineData],
+    alloc_threshold: Option<f64>,
+) -> Option<crate::AllocComparison> {
+    let current_allocs = current.alloc_stats?.mean_allocs;
+    let historical_allocs: Vec<f64> = historical
+        .iter()
+        .filter_map(|b| b.alloc_stats)
+        .map(|s| s.mean_allocs)
+        .collect();
+    if historical_allocs.is_empty() {
+        return None;
+    }
+
+    let mut allocs = crate::AllocComparison {
+        current_allocs,
+        baseline_allocs: crate::statistics::mean(&historical_allocs),
+        is_regression: false,
+    };
+    allocs.is_regression = alloc_threshold.is_some_and(|threshold| {
+        allocs
+            .percentage_change()
+            .map_or(current_allocs > 0.0, |change| change > threshold)
+    });
+    Some(allocs)
+}
+
+/// Detect regression using statistical window + Bayesian Change Point Detection
+///
+/// This function combines three criteria for robust regression detection:
+/// 1. Statistical significance (outside confidence interval)
+/// 2. Practical significance (exceeds threshold percentage)
+/// 3. Change point probability (likely distribution shift)
+///
+/// All three conditions must be met for a regression to be flagged.
+///
+/// `metric` selects the timing compared. CPU time is used only when the current
+/// run and at least one historical run recorded it; otherwise wall time is used.
+/// `window_scale` picks how the window's center and spread, which the z-score
+/// and bounds are measured against, are estimated; a MAD of zero (too few or
+/// identical runs) falls back to mean and standard deviation.
+///
+/// With `min_effect_size`, practical significance also needs the current
+/// samples to differ from the window's pooled samples by that Cohen's d, or
+/// only that with `effect_size_primary`; without per-sample data on both sides
+/// the percentage threshold decides alone.
+///
+/// With `method = "bootstrap"` a bootstrap interval of the difference between
+/// the current and the window mean replaces the z-score tiers: the lower bound
+/// must be above zero and the change practically significant; the change point
+/// probability is still reported but not required. `method = "mannwhitney"`
+/// does the same with a one-sided Mann-Whitney U test of the current samples
+/// against the most recent baseline run's, falling back to the z-score tiers
+/// when either side has no samples, and `method = "welch"` with a one-sided
+/// Welch's t-test against that run's stored mean, variance and sample count.
+/// `bootstrap_samples` resamples the raw samples when both sides have them
+/// instead of the window's per-run means.
+///
+/// `filter_outliers` compares the wall-time means of the samples inside the
+/// Tukey fences, and sizes the MDE by their spread, where a run recorded them;
+/// the per-sample tests still see every sample.
+pub fn detect_regression(
+    current: &crate::BenchResult,
+    historical: &[BaselineData],
+    config: &ComparisonConfig,
+) -> ComparisonResult {
+    let &ComparisonConfig {
+        threshold,
+        confidence_level,
+        cp_threshold,
+        hazard_rate,
+        metric,
+        window_scale,
+        min_effect_size,
+        effect_size_primary,
+        method,
+        bootstrap_iterations,
+        bootstrap_samples,
+        p99_threshold,
+        filter_outliers,
+        ..
+    } = config;
+    if historical.is_empty() {
+        return ComparisonResult {
+            benchmark_name: current.name.clone(),
+            group: current.group.clone(),
+            comparison: None,
+            is_regression: false,
+            reference: None,
+            quality_failure: None,
+            is_improvement: false,
+            window: WindowOutcome::EmptyNoHistory,
+        };
+    }
+
+    // Extract means from historical runs (in nanoseconds)
+    let cpu_time_means = match metric {
+        ComparisonMetric::CpuTime => current.cpu_time_percentiles.as_ref().and_then(|p| {
+            let historical_means: Vec<f64> = historical
+                .iter()
+                .filter_map(|b| b.cpu_time_statistics.as_ref())
+                .map(|s| s.mean as f64)
+                .collect();
+            (!historical_means.is_empty()).then_some((p.mean.as_nanos() as f64, historical_means))
+        }),
+        ComparisonMetric::WallTime => None,
+    };
+    let use_cpu_time = cpu_time_means.is_some();
+    let (current_timings, current_summary) = if use_cpu_time {
+        (&current.cpu_timings, None)
+    } else {
+        (&current.all_timings, current.summary.as_ref())
+    };
+
+    let trimmed = |filtered: &Option<crate::FilteredStatistics>| {
+        filtered
+            .as_ref()
+            .filter(|_| filter_outliers && !use_cpu_time)
+            .map(|f| f.statistics.clone())
+    };
+    let current_trimmed = trimmed(&current.filtered);
+    let (current_mean, historical_means) = cpu_time_means.unwrap_or_else(|| {
+        (
+            current_trimmed
+                .as_ref()
+                .map_or(current.percentiles.mean.as_nanos() as f64, |s| {
+                    s.mean as f64
+                }),
+            historical
+                .iter()
+                .map(|b| trimmed(&b.filtered).map_or(b.statistics.mean as f64, |s| s.mean as f64))
+                .collect(),
+        )
+    });
+
+    // --- Statistical Window Analysis ---
+    let hist_mean = crate::statistics::mean(&historical_means);
+    let (hist_center, hist_stddev) = window_center_and_scale(&historical_means, window_scale);
+
+    // Z-score: how many standard deviations away?
+    let z_score_value = crate::statistics::z_score(current_mean, hist_center, hist_stddev);
+
+    // Confidence interval (one-tailed for regression detection)
+    let z_critical = crate::statistics::z_critical(confidence_level);
+
+    let upper_bound = hist_center + (z_critical * hist_stddev);
+    let lower_bound = hist_center - (z_critical * hist_stddev);
+
+    // A regression is slower than the upper bound, an improvement faster than
+    // the lower one
+    let statistically_significant = current_mean > upper_bound;
+    let statistically_improved = current_mean < lower_bound;
+
+    // --- Practical Significance ---
+    // A baseline below the clock's resolution has no relative change; the
+    // comparison is provisional and shows the absolute delta instead
+    let relative_change = percent_change(current_mean, hist_mean);
+    let percentage_change = relative_change.unwrap_or(0.0);
+
+    // --- Bayesian Change Point Detection ---
+    let change_probability = if relative_change.is_some() {
+        crate::changepoint::bayesian_change_point_probability(
+            current_mean,
+            &historical_means,
+            hazard_rate,
+        )
+    } else {
+        0.0
+    };
+    let practically_significant = percentage_change > threshold;
+    let practically_improved = percentage_change < -threshold;
+
+    // --- Sample-count compatibility ---
+    // With few samples (or a noisy window) a change the size of the threshold
+    // can't be told apart from noise; only changes beyond the MDE give a verdict
+    let current_samples: Vec<f64> = current_timings
+        .iter()
+        .map(|d| d.as_nanos() as f64)
+        .collect();
+    let current_std_error = if let Some(trimmed) = &current_trimmed {
+        trimmed.std_dev / (trimmed.sample_count.max(1) as f64).sqrt()
+    } else if let Some(summary) = current_summary {
+        // Exact over all samples, not just the reservoir
+        summary.std_dev / (summary.sample_count.max(1) as f64).sqrt()
+    } else if current_samples.is_empty() {
+        0.0
+    } else {
+        crate::statistics::standard_deviation(&current_samples)
+            / (current_samples.len() as f64).sqrt()
+    };
+    let min_detectable_effect = crate::statistics::minimum_detectable_effect(
+        hist_mean,
+        hist_stddev,
+        current_std_error,
+        confidence_level,
+    );
+    let provisional = relative_change.is_none()
+        || min_detectable_effect
+            .is_some_and(|mde| threshold < mde && percentage_change.abs() < mde);
+
+    // --- Effect size ---
+    // The change in units of the samples' own spread, against the pooled window
+    let pooled_baseline: Vec<f64> = historical
+        .iter()
+        .flat_map(|b| {
+            if use_cpu_time {
+                &b.cpu_time_samples
+            } else {
+                &b.samples
+            }
+        })
+        .map(|&ns| ns as f64)
+        .collect();
+    let effect_size = crate::statistics::cohens_d(&current_samples, &pooled_baseline);
+    let practically_significant = match (min_effect_size, effect_size) {
+        (Some(min_d), Some(d)) if effect_size_primary => d >= min_d,
+        (Some(min_d), Some(d)) => practically_significant && d >= min_d,
+        _ => practically_significant,
+    };
+    let practically_improved = match (min_effect_size, effect_size) {
+        (Some(min_d), Some(d)) if effect_size_primary => d <= -min_d,
+        (Some(min_d), Some(d)) => practically_improved && d <= -min_d,
+        _ => practically_improved,
+    };
+
+    // --- Bootstrap ---
+    let bootstrap_ci = match method {
+        ComparisonMethod::Bootstrap => {
+            if bootstrap_samples && !current_samples.is_empty() && !pooled_baseline.is_empty() {
+                crate::statistics::bootstrap_difference_ci(
+                    &current_samples,
+                    &pooled_baseline,
+                    bootstrap_iterations,
+                    confidence_level,
+                )
+            } else {
+                crate::statistics::bootstrap_difference_ci(
+                    &[current_mean],
+                    &historical_means,
+                    bootstrap_iterations,
+                    confidence_level,
+                )
+            }
+        }
+        ComparisonMethod::ZScore | ComparisonMethod::MannWhitney | ComparisonMethod::Welch => None,
+    };
+
+    // --- Rank test ---
+    // Against the latest run alone: pooling the window would mix in older
+    // runs' drift
+    let latest_run = historical.iter().rev().find(|b| !b.was_regression);
+    let latest_samples: Vec<f64> = latest_run
+        .map(|b| {
+            if use_cpu_time {
+                &b.cpu_time_samples
+            } else {
+                &b.samples
+            }
+        })
+        .into_iter()
+        .flatten()
+        .map(|&ns| ns as f64)
+        .collect();
+    let mann_whitney = crate::statistics::mann_whitney_u(&current_samples, &latest_samples);
+
+    // --- Welch's t-test ---
+    // The latest run's stored moments, so it needs no stored samples
+    let welch = match method {
+        ComparisonMethod::Welch => {
+            let current_moments = match current_summary {
+                Some(summary) => crate::statistics::SampleMoments::from_population(
+                    current_mean,
+                    summary.variance,
+                    summary.sample_count,
+                ),
+                None => crate::statistics::SampleMoments::of(&current_samples),
+            };
+            latest_run
+                .and_then(|b| {
+                    if use_cpu_time {
+                        b.cpu_time_statistics.as_ref()
+                    } else {
+                        Some(&b.statistics)
+                    }
+                })
+                .and_then(|stats| {
+                    crate::statistics::welch_t_test(
+                        current_moments,
+                        crate::statistics::SampleMoments::from_population(
+                            stats.mean as f64,
+                            stats.variance,
+                            stats.sample_count,
+                        ),
+                    )
+                })
+        }
+        _ => None,
+    };
+
+    // Evidence standing in for the z-score with the other methods
+    let method_significant = match method {
+        ComparisonMethod::ZScore => None,
+        ComparisonMethod::Bootstrap => bootstrap_ci.map(|(lower, _)| lower > 0.0),
+        ComparisonMethod::MannWhitney => mann_whitney
+            .as_ref()
+            .map(|test| test.z > 0.0 && test.p_value / 2.0 < 1.0 - confidence_level),
+        ComparisonMethod::Welch => welch
+            .as_ref()
+            .map(|test| test.t > 0.0 && test.p_value / 2.0 < 1.0 - confidence_level),
+    };
+    let method_improved = match method {
+        ComparisonMethod::ZScore => None,
+        ComparisonMethod::Bootstrap => bootstrap_ci.map(|(_, upper)| upper < 0.0),
+        ComparisonMethod::MannWhitney => mann_whitney
+            .as_ref()
+            .map(|test| test.z < 0.0 && test.p_value / 2.0 < 1.0 - confidence_level),
+        ComparisonMethod::Welch => welch
+            .as_ref()
+            .map(|test| test.t < 0.0 && test.p_value / 2.0 < 1.0 - confidence_level),
+    };
+
+    // --- Combined Decision ---
+    // Use tiered logic based on strength of statistical evidence:
+    //
+    // 1. EXTREME evidence (z-score > 5): Statistical + practical significance = regression
+    //    This catches acute performance disasters that are clearly not noise
+    //
+    // 2. STRONG evidence (z-score > 2): Require all three conditions
+    //    Statistical + practical + change point = regression
+    //    This is the normal case for real regressions
+    //
+    // 3. WEAK evidence (z-score <= 2): Not a regression
+    //    Likely just noise or natural variance, even if percentage is high
+    //
+    // Improvements are judged by the same tiers in the other direction.
+    let decide = |statistical: bool, practical: bool, method: Option<bool>| {
+        if provisional {
+            // Neither REGRESS nor STABLE is reliable at this sample count
+            false
+        } else if let Some(significant) = method {
+            // The interval or rank test stands in for the z-score
+            significant && practical
+        } else {
+            tiered_verdict(
+                z_score_value,
+                statistical && practical,
+                change_probability > cp_threshold,
+            )
+        }
+    };
+    let tail = p99_threshold.and_then(|p99_threshold| {
+        compare_p99(current, historical, use_cpu_time, p99_threshold, config)
+    });
+    let tail_regression = tail.is_some_and(|t| t.is_regression);
+    let is_regression = decide(
+        statistically_significant,
+        practically_significant,
+        method_significant,
+    ) || tail_regression;
+    let is_improvement = decide(
+        statistically_improved,
+        practically_improved,
+        method_improved,
+    ) && !tail_regression;
+
+    ComparisonResult {
+        benchmark_name: current.name.clone(),
+        group: current.group.clone(),
+        comparison: Some(crate::Comparison {
+            current_mean: Duration::from_nanos(current_mean as u64),
+            baseline_mean: Duration::from_nanos(hist_mean as u64),
+            percentage_change,
+            baseline_count: historical_means.len(),
+            z_score: Some(z_score_value),
+            confidence_interval: bootstrap_ci.or(Some((lower_bound, upper_bound))),
+            change_probability: relative_change.map(|_| change_probability),
+            throughput: current.throughput,
+            mixed_build_profiles: historical
+                .iter()
+                .any(|b| b.debug_assertions != current.debug_assertions),
+            allocs: None,
+            min_detectable_effect,
+            effect_size,
+            mann_whitney_p: mann_whitney.as_ref().map(|test| test.p_value),
+            rank_biserial: mann_whitney.as_ref().map(|test| test.rank_biserial),
+            t_statistic: welch.as_ref().map(|test| test.t),
+            p_value: welch.as_ref().map(|test| test.p_value),
+            provisional,
+            threshold,
+            global_threshold: config.global_threshold,
+            instructions: compare_instructions(current, historical),
+            suspect: None,
+            quality_failure: None,
+            reference_machine: None,
+            normalized_machines: normalized_machines(historical, config),
+            env_changes: environment_changes(current, historical),
+            commit_change: commit_change(current, historical),
+            toolchain_changes: toolchain_changes(current, historical),
+            accepted_since: historical
+                .first()
+                .filter(|b| b.accepted)
+                .map(|b| b.timestamp.clone()),
+            drift: None,
+            p99: tail,
+        }),
+        is_regression,
+        reference: None,
+        quality_failure: None,
+        is_improvement,
+        window: WindowOutcome::Populated,
+    }
+}
+
+/// Center and spread of a window's per-run values
+///
+/// With MAD scaling the window is centered on its median as well, so an
+/// outlying run moves neither the center nor the spread; a window with no
+/// median deviation falls back to the mean and standard deviation.
+fn window_center_and_scale(values: &[f64], window_scale: WindowScale) -> (f64, f64) {
+    match window_scale {
+        WindowScale::Mad => Some((
+            crate::statistics::median(values),
+            crate::statistics::MAD_SCALE * crate::statistics::median_absolute_deviation(values),
+        ))
+        .filter(|&(_, scale)| scale > 0.0),
+        WindowScale::StdDev => None,
+    }
+    .unwrap_or_else(|| {
+        (
+            crate::statistics::mean(values),
+            crate::statistics::standard_deviation(values),
+        )
+    })
+}
+
+/// The z-score tiers of [`detect_regression`]: `significant` (statistically
+/// and practically) is enough past |z| > 5, needs the change point as well
+/// past |z| > 2, and below that nothing is a change
+fn tiered_verdict(z_score: f64, significant: bool, change_point: bool) -> bool {
+    if z_score.abs() > 5.0 {
+        significant
+    } else if z_score.abs() > 2.0 {
+        significant && change_point
+    } else {
+        false
+    }
+}
+
+/// The current run's p99 against the window's p99s, with `p99_threshold` in
+/// place of the mean's threshold
+///
+/// Always judged by the z-score tiers, whatever `method` the mean uses: the
+/// bootstrap and rank tests compare whole distributions, not one percentile.
+/// None when the current run or no window run recorded a p99.
+fn compare_p99(
+    current: &crate::BenchResult,
+    historical: &[BaselineData],
+    use_cpu_time: bool,
+    p99_threshold: f64,
+    config: &ComparisonConfig,
+) -> Option<crate::TailComparison> {
+    let (current_p99, historical_p99s): (Option<Duration>, Vec<f64>) = if use_cpu_time {
+        (
+            current
+                .cpu_time_percentiles
+                .as_ref()
+                .and_then(|p| p.get(99.0)),
+            historical
+                .iter()
+                .filter_map(|b| b.cpu_time_statistics.as_ref())
+                .map(|s| s.p99 as f64)
+                .collect(),
+        )
+    } else {
+        (
+            current.percentiles.get(99.0).or_else(|| {
+                let mut sorted: Vec<u128> =
+                    current.all_timings.iter().map(|t| t.as_nanos()).collect();
+                sorted.sort_unstable();
+                (!sorted.is_empty()).then(|| {
+                    crate::duration_from_nanos(crate::statistics::percentile_sorted(&sorted, 0.99))
+                })
+            }),
+            historical.iter().map(|b| b.statistics.p99 as f64).collect(),
+        )
+    };
+    let current_p99 = current_p99?.as_nanos() as f64;
+    let historical_p99s: Vec<f64> = historical_p99s.into_iter().filter(|&p| p > 0.0).collect();
+    if historical_p99s.is_empty() {
+        return None;
+    }
+
+    let baseline_p99 = crate::statistics::mean(&historical_p99s);
+    let percentage_change = percent_change(current_p99, baseline_p99)?;
+    let (center, scale) = window_center_and_scale(&historical_p99s, config.window_scale);
+    let z_score = crate::statistics::z_score(current_p99, center, scale);
+    let upper_bound = center + crate::statistics::z_critical(config.confidence_level) * scale;
+    let change_probability = crate::changepoint::bayesian_change_point_probability(
+        current_p99,
+        &historical_p99s,
+        config.hazard_rate,
+    );
+
+    Some(crate::TailComparison {
+        current_p99: Duration::from_nanos(current_p99 as u64),
+        baseline_p99: Duration::from_nanos(baseline_p99 as u64),
+        percentage_change,
+        z_score,
+        change_probability,
+        threshold: p99_threshold,
+        is_regression: tiered_verdict(
+            z_score,
+            current_p99 > upper_bound && percentage_change > p99_threshold,
+            change_probability > config.cp_threshold,
+        ),
+    })
+}
+
+/// [`detect_regression`] followed by the allocation, warmup/sampling
+/// consistency and quality checks every session applies to a fresh result
+pub fn judge_against_history(
+    current: &crate::BenchResult,
+    historical: &[BaselineData],
+    config: &ComparisonConfig,
+) -> ComparisonResult {
+    detect_regression(current, historical, config)
+        .with_alloc_comparison(current, historical, config.alloc_threshold)
+        .with_consistency_check(current, config.consistency_factor)
+        .with_quality_check(current)
+        .with_drift_check(current, historical, config.drift_threshold)
+}
+
+/// [`detect_regression`] with its main settings spelled out and the others,
+/// such as the effect-size gate, at their defaults
+#[allow(clippy::too_many_arguments)]
+pub fn detect_regression_with_cpd(
+    current: &crate::BenchResult,
+    historical: &[BaselineData],
+    threshold: f64,
+    confidence_level: f64,
+    cp_threshold: f64,
+    hazard_rate: f64,
+    metric: ComparisonMetric,
+    window_scale: WindowScale,
+) -> ComparisonResult {
+    detect_regression(
+        current,
+        historical,
+        &ComparisonConfig {
+            threshold,
+            confidence_level,
+            cp_threshold,
+            hazard_rate,
+            metric,
+            window_scale,
+            ..Default::default()
+        },
+    )
+}
+
+/// Watched environment variables of `current` set differently in the window.
+///
+/// Only runs that recorded a variable count toward it, so variables added to
+/// `[environment] watch` (and baselines from before they were recorded) aren't
+/// reported as changes.
+pub fn environment_changes(current: &BenchResult, historical: &[BaselineData]) -> Vec<EnvChange> {
+    current
+        .environment
+        .iter()
+        .filter_map(|(name, value)| {
+            let recorded: Vec<&Option<String>> = historical
+                .iter()
+                .filter_map(|b| b.environment.get(name))
+                .collect();
+            let differing: Vec<&Option<String>> =
+                recorded.iter().copied().filter(|v| *v != value).collect();
+            let latest = *differing.last()?;
+            Some(EnvChange {
+                name: name.clone(),
+                baseline: latest.clone(),
+                current: value.clone(),
+                differing_runs: differing.len(),
+                recorded_runs: recorded.len(),
+            })
+        })
+        .collect()
+}
+
+/// The commit of the window's latest run, when the current run was measured
+/// at another; None when either side recorded no commit
+pub fn commit_change(
+    current: &BenchResult,
+    historical: &[BaselineData],
+) -> Option<crate::git::CommitChange> {
+    let current = current.git.as_ref()?;
+    let baseline = historical.last()?.git.as_ref()?;
+    (baseline.commit != current.commit).then(|| crate::git::CommitChange {
+        baseline: baseline.clone(),
+        current: current.clone(),
+    })
+}
+
+/// Number of machines a window pooled with `compare_across_machines` drew
+/// its runs from; None when they're all one machine's
+fn normalized_machines(historical: &[BaselineData], config: &ComparisonConfig) -> Option<usize> {
+    if !config.compare_across_machines {
+        return None;
+    }
+    let machines: std::collections::BTreeSet<&str> =
+        historical.iter().map(|b| b.machine_id.as_str()).collect();
+    (machines.len() > 1).then_some(machines.len())
+}
+
+/// Toolchain properties of `current` recorded differently in the window
+///
+/// As with [`environment_changes`], only window runs that recorded a property
+/// count toward it; the change names the latest differing value.
+pub fn toolchain_changes(
+    current: &BenchResult,
+    historical: &[BaselineData],
+) -> Vec<crate::toolchain::ToolchainChange> {
+    let Some(ref toolchain) = current.toolchain else {
+        return Vec::new();
+    };
+    crate::toolchain::ToolchainProperty::ALL
+        .into_iter()
+        .filter_map(|property| {
+            let value = toolchain.get(property)?;
+            let baseline = historical
+                .iter()
+                .rev()
+                .filter_map(|b| b.toolchain.as_ref()?.get(property))
+                .find(|recorded| *recorded != value)?;
+            Some(crate::toolchain::ToolchainChange {
+                property,
+                baseline: baseline.to_string(),
+                current: value.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Latest-run verdict for one machine sharing the `.benches` tree
+#[derive(Debug, Clone)]
+pub struct MachineSummary {
+    pub machine_id: String,
+    /// Total number of stored runs for the benchmark on this machine
+    pub run_count: usize,
+    /// The most recent run recorded on this machine
+    pub latest: BaselineData,
+    /// Latest run compared against the same machine's preceding window
+    pub comparison: ComparisonResult,
+}
+
+/// Summarize the latest run of a benchmark on every machine that has history for it
+///
+/// Each machine's latest run is compared only against that machine's own preceding
+/// window, so the verdicts are independent and can be shown side by side.
+pub fn summarize_machines(
+    baseline_manager: &BaselineManager,
+    crate_name: &str,
+    benchmark_name: &str,
+    config: &ComparisonConfig,
+) -> Result<Vec<MachineSummary>, std::io::Error> {
+    let mut summaries = Vec::new();
+
+    for machine_id in baseline_manager.list_machines()? {
+        let bench_dir = baseline_manager.benchmark_dir_for(&machine_id, crate_name, benchmark_name);
+        if !bench_dir.is_dir() {
+            continue;
+        }
+
+        let mut runs: Vec<_> = fs::read_dir(&bench_dir)?
+            .filter_map(|e| e.ok())
+            .filter(|e| run_name(&e.file_name().to_string_lossy()).is_some())
+            .collect();
+        runs.sort_by_key(|e| e.file_name());
+
+        let Some(latest_entry) = runs.last() else {
+            continue;
+        };
+        let contents = read_run_file(&latest_entry.path())?;
+        let Ok(latest) = parse_baseline(&contents) else {
+            continue;
+        };
+
+        // Window the latest run would have been compared against
+        let historical: Vec<BaselineData> = baseline_manager
+            .load_recent_baselines_for_machine(
+                &machine_id,
+                crate_name,
+                benchmark_name,
+                config.window_size + 1,
+            )?
+            .into_iter()
+            .filter(|b| b.timestamp != latest.timestamp)
+            .collect();
+        let historical = &historical[historical.len().saturating_sub(config.window_size)..];
+
+        let comparison = detect_regression(&latest.to_bench_result(), historical, config);
+
+        summaries.push(MachineSummary {
+            machine_id,
+            run_count: runs.len(),
+            latest,
+            comparison,
+        });
+    }
+
+    Ok(summaries)
+}
+
+/// Check whether machines disagree about a regression
+///
+/// Returns true when at least one machine flags its latest run as a regression while
+/// another machine with enough history to compare considers it not a regression. This
+/// usually points at a microarchitecture-specific change.
+pub fn machines_disagree(summaries: &[MachineSummary]) -> bool {
+    let compared: Vec<&MachineSummary> = summaries
+        .iter()
+        .filter(|s| s.comparison.comparison.is_some())
+        .collect();
+
+    compared.iter().any(|s| s.comparison.is_regression)
+        && compared.iter().any(|s| !s.comparison.is_regression)
+}
+
+/// Verdict a stored run would receive under a given comparison configuration
+#[derive(Debug, Clone)]
+pub struct ReplayedRun {
+    pub timestamp: String,
+    /// Verdict recorded when the run was originally saved
+    pub recorded_regression: bool,
+    /// Verdict under the replayed configuration
+    pub comparison: ComparisonResult,
+}
+
+/// Outcome of replaying one benchmark's history against a comparison configuration
+#[derive(Debug, Clone)]
+pub struct ReplayReport {
+    pub benchmark_name: String,
+    pub runs: Vec<ReplayedRun>,
+}
+
+/// Number of flagged runs whose next run was not flagged again, in a series of
+/// regression flags oldest first; see [`ReplayReport::flaps`]
+pub fn count_flaps(flagged: &[bool]) -> usize {
+    flagged.windows(2).filter(|w| w[0] && !w[1]).count()
+}
+
+impl ReplayReport {
+    /// Number of runs flagged as regressions
+    pub fn regressions(&self) -> usize {
+        self.runs
+            .iter()
+            .filter(|r| r.comparison.is_regression)
+            .count()
+    }
+
+    /// Number of flagged runs whose next run was not flagged again
+    ///
+    /// Flagged runs are excluded from later windows, so a genuine shift keeps being
+    /// flagged until it is accepted. A flag that clears on the very next run was
+    /// most likely noise.
+    pub fn flaps(&self) -> usize {
+        let flagged: Vec<bool> = self
+            .runs
+            .iter()
+            .map(|r| r.comparison.is_regression)
+            .collect();
+        count_flaps(&flagged)
+    }
+
+    /// Number of runs whose verdict differs from the one originally recorded
+    pub fn changed_verdicts(&self) -> usize {
+        self.runs
+            .iter()
+            .filter(|r| r.comparison.is_regression != r.recorded_regression)
+            .count()
+    }
+}
+
+/// Re-judge a benchmark's stored history with a different comparison configuration
+///
+/// `history` must be in chronological order (oldest first). Each run is compared
+/// against the window it would have seen: the preceding `window_size` runs that
+/// the replay itself did not flag, mirroring how flagged runs are kept out of the
+/// baseline during a real session. Only the last `last` runs are reported, but the
+/// whole history is replayed so their windows are reconstructed faithfully.
+pub fn replay_history(
+    history: &[BaselineData],
+    config: &ComparisonConfig,
+    last: Option<usize>,
+) -> ReplayReport {
+    let mut accepted: Vec<&BaselineData> = Vec::new();
+    let mut runs = Vec::with_capacity(history.len());
+
+    for run in history {
+        let window: Vec<BaselineData> = accepted
+            [accepted.len().saturating_sub(config.window_size)..]
+            .iter()
+            .map(|b| (*b).clone())
+            .collect();
+
+        let comparison = detect_regression(&run.to_bench_result(), &window, config);
+
+        if config.improvement_resets_window && comparison.is_improvement {
+            accepted.clear();
+        }
+        if !comparison.is_regression {
+            accepted.push(run);
+        }
+        runs.push(ReplayedRun {
+            timestamp: run.timestamp.clone(),
+            recorded_regression: run.was_regression,
+            comparison,
+        });
+    }
+
+    let keep = last.unwrap_or(runs.len());
+    ReplayReport {
+        benchmark_name: history
+            .last()
+            .map(|b| b.benchmark_name.clone())
+            .unwrap_or_default(),
+        runs: runs.split_off(runs.len().saturating_sub(keep)),
+    }
+}
+
+/// Process benchmarks with baseline comparison using CPD
+///
+/// This function:
+/// 1. Loads recent baseline runs (window-based)
+/// 2. Compares current results with historical data using statistical + Bayesian CPD
+/// 3. Saves new baselines, unless another process holds the writer lock
+/// 4. Returns comparison results
+pub fn process_with_baselines(
+    results: &[crate::BenchResult],
+    config: &ComparisonConfig,
+) -> Result<Vec<ComparisonResult>, std::io::Error> {
+    let baseline_manager = BaselineManager::new()?
+        .with_follow_renames(config.follow_renames)
+        .with_improvement_resets_window(config.improvement_resets_window)
+        .with_compare_across_machines(config.compare_across_machines);
+    let lock = baseline_manager.lock_writer();
+    let saving = match lock {
+        Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+            eprintln!("Warning: {}; comparing only, baselines won't be saved", e);
+            false
+        }
+        _ => true,
+    };
+    let mut comparisons = Vec::new();
+
+    for result in results.iter().filter(|r| !r.skipped) {
+        let (crate_name, benchmark_name) = storage_name(&result.module, &result.name);
+        let config = &config.for_benchmark(&result.name);
+
+        // Load recent baselines (window-based comparison)
+        let window = baseline_manager.load_comparable_window(
+            crate_name,
+            &benchmark_name,
+            config.window_size,
+            result.settings.as_ref(),
+        )?;
+
+        let comparison_result = if !window.runs.is_empty() {
+            // Use CPD-based comparison
+            detect_regression(result, &window.runs, config)
+        } else {
+            // No baseline exists on this machine - first run, oriented by other machines
+            let reference = baseline_manager.load_reference_baselines(
+                crate_name,
+                &benchmark_name,
+                config.window_size,
+            )?;
+            ComparisonResult {
+                benchmark_name: result.name.clone(),
+                group: result.group.clone(),
+                comparison: None,
+                is_regression: false,
+                reference: None,
+                quality_failure: None,
+                is_improvement: false,
+                window: window.outcome,
+            }
+            .with_reference(result, reference, config)
+        };
+
+        // Save current result as baseline with the verdict's flags
+        if saving {
+            baseline_manager.save_judged_baseline(crate_name, result, &comparison_result)?;
+        }
+        comparisons.push(comparison_result);
+    }
+
+    Ok(comparisons)
+}
+
+/// Check if any regressions were detected and exit in CI mode
+pub fn check_regressions_and_exit(comparisons: &[ComparisonResult], config: &ComparisonConfig) {
+    if !config.ci_mode {
+        return;
+    }
+
+    let has_regression = comparisons.iter().any(|c| c.is_regression);
+    let has_alloc_regression = comparisons.iter().any(|c| c.is_alloc_regression());
+
+    use colored::Colorize;
+    if has_regression {
+        eprintln!();
+        eprintln!(
+            "{}",
+            format!(
+                "FAILED: Performance regression detected (threshold: {}%)",
+                config.threshold
+            )
+            .red()
+            .bold()
+        );
+    }
+    if has_alloc_regression {
+        eprintln!();
+        eprintln!(
+            "{}",
+            format!(
+                "FAILED: Allocation regression detected (threshold: {}%)",
+                config.alloc_threshold.unwrap_or_default()
+            )
+            .red()
+            .bold()
+        );
+    }
+
+    if has_regression || has_alloc_regression {
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn create_test_result(name: &str) -> BenchResult {
+        BenchResult {
+            name: name.to_string(),
+            module: "test_module".to_string(),
+            samples: 10,
+            percentiles: Percentiles {
+                values: vec![
+                    (50.0, Duration::from_millis(5)),
+                    (90.0, Duration::from_millis(10)),
+                    (99.0, Duration::from_millis(15)),
+                ],
+                mean: Duration::from_millis(8),
+            },
+            all_timings: vec![Duration::from_millis(5); 10],
+            cpu_samples: vec![],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_baseline_data_conversion() {
+        let result = create_test_result("test_bench");
+        let machine_id = "0123456789abcdef".to_string(); // 16-char hex hash
+
+        let baseline = BaselineData::from_bench_result(&result, machine_id.clone(), false);
+
+        assert_eq!(baseline.benchmark_name, "test_bench");
+        assert_eq!(baseline.module, "test_module");
+        assert_eq!(baseline.machine_id, machine_id);
+        assert_eq!(baseline.statistics.sample_count, 10);
+        assert_eq!(baseline.samples.len(), 10);
+
+        let converted = baseline.to_bench_result();
+        assert_eq!(converted.name, result.name);
+        assert_eq!(converted.module, result.module);
+        assert_eq!(converted.percentiles.p90(), result.percentiles.p90());
+    }
+
+    #[test]
+    fn test_save_and_load_baseline() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+
+        let result = create_test_result("test_bench");
+
+        // Save baseline
+        manager.save_baseline("my_crate", &result, false).unwrap();
+
+        // Load baseline
+        let loaded = manager.load_baseline("my_crate", "test_bench").unwrap();
+        assert!(loaded.is_some());
+
+        let baseline = loaded.unwrap();
+        assert_eq!(baseline.benchmark_name, "test_bench");
+        assert_eq!(baseline.module, "test_module");
+        assert!(baseline.percentiles.is_some());
+        assert_eq!(
+            baseline.percentiles.unwrap().p90(),
+            Duration::from_millis(10)
+        );
+    }
+
+    #[test]
+    fn test_writer_saves_in_background() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = Arc::new(BaselineManager::with_root_dir(temp_dir.path()).unwrap());
+
+        let mut writer = BaselineWriter::spawn(Arc::clone(&manager), Some(0));
+        for name in ["bench_a", "bench_b", "bench_c"] {
+            writer.save("my_crate", &create_test_result(name), None);
+        }
+        assert!(writer.finish().is_empty());
+        for name in ["bench_a", "bench_b", "bench_c"] {
+            assert!(manager.has_baseline("my_crate", name));
+        }
+
+        // Failures surface when the writer finishes instead of being lost
+        let blocked = temp_dir.path().join("not-a-dir");
+        fs::write(&blocked, "").unwrap();
+        let manager = Arc::new(BaselineManager::with_root_dir(&blocked).unwrap());
+        let mut writer = BaselineWriter::spawn(manager, None);
+        writer.save("my_crate", &create_test_result("bench_a"), None);
+        let failures = writer.finish();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].benchmark_name, "bench_a");
+    }
+
+    #[test]
+    fn test_load_nonexistent_baseline() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+
+        let loaded = manager.load_baseline("my_crate", "nonexistent").unwrap();
+        assert!(loaded.is_none());
+    }
+
+    #[test]
+    fn test_has_baseline() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+
+        let result = create_test_result("test_bench");
+
+        assert!(!manager.has_baseline("my_crate", "test_bench"));
+
+        manager.save_baseline("my_crate", &result, false).unwrap();
+
+        assert!(manager.has_baseline("my_crate", "test_bench"));
+    }
+
+    #[test]
+    fn test_list_baselines() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+
+        let result1 = create_test_result("bench1");
+        let result2 = create_test_result("bench2");
+
+        manager.save_baseline("my_crate", &result1, false).unwrap();
+        manager.save_baseline("my_crate", &result2, false).unwrap();
+
+        let mut baselines = manager.list_baselines("my_crate").unwrap();
+        baselines.sort();
+
+        assert_eq!(baselines, vec!["bench1", "bench2"]);
+    }
+
+    #[test]
+    fn test_remove_runs() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        let machine = manager.machine_id().to_string();
+        for timestamp in [
+            "2025-01-01T00-00-00",
+            "2025-01-02T00-00-00",
+            "2025-01-03T00-00-00",
+        ] {
+            write_machine_run(temp_dir.path(), &machine, timestamp, 10, false);
+        }
+
+        assert_eq!(
+            manager
+                .remove_runs("my_crate", "test_bench", Some("2025-01-02T00-00-00"))
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            manager
+                .remove_runs("my_crate", "test_bench", Some("2025-01-02T00-00-00"))
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            manager.list_runs("my_crate", "test_bench").unwrap(),
+            vec!["2025-01-01T00-00-00", "2025-01-03T00-00-00"]
+        );
+
+        assert_eq!(
+            manager.remove_runs("my_crate", "test_bench", None).unwrap(),
+            2
+        );
+        assert!(!manager.has_baseline("my_crate", "test_bench"));
+        assert_eq!(
+            manager.remove_runs("my_crate", "test_bench", None).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_accept_regression_starts_window_at_flagged_streak() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        let machine = manager.machine_id().to_string();
+        for (day, mean_ms, flagged) in
+            [(1, 10, false), (2, 10, false), (3, 20, true), (4, 20, true)]
+        {
+            let timestamp = format!("2025-01-0{}T00-00-00", day);
+            write_machine_run(temp_dir.path(), &machine, &timestamp, mean_ms, flagged);
+        }
+        assert_eq!(
+            manager
+                .load_recent_baselines("my_crate", "test_bench", 10)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        assert_eq!(
+            manager.accept_regression("my_crate", "test_bench").unwrap(),
+            Some(AcceptedRegression {
+                runs: 2,
+                since: "2025-01-03T00-00-00".to_string(),
+            })
+        );
+        let window = manager
+            .load_recent_baselines("my_crate", "test_bench", 10)
+            .unwrap();
+        assert_eq!(window.len(), 2);
+        assert!(window[0].accepted && !window[1].accepted);
+        assert!(window.iter().all(|run| run.statistics.mean == 20_000_000));
+
+        let mut current = create_test_result("test_bench");
+        current.all_timings = vec![Duration::from_millis(20); 10];
+        current.percentiles.mean = Duration::from_millis(20);
+        let comparison = detect_regression(&current, &window, &ComparisonConfig::default());
+        assert!(!comparison.is_regression);
+        assert!(comparison
+            .comparison
+            .unwrap()
+            .accepted_note()
+            .unwrap()
+            .starts_with("window starts at the accepted regression"));
+
+        // Nothing left to accept
+        assert_eq!(
+            manager.accept_regression("my_crate", "test_bench").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_for_workspace_uses_configured_dir_and_machine_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = crate::config::BaselineConfig {
+            dir: Some(PathBuf::from("shared/benches")),
+            machine_id: Some("ci-runner".to_string()),
+            ..Default::default()
+        };
+        let manager = BaselineManager::for_workspace(temp_dir.path(), &config).unwrap();
+        assert_eq!(manager.machine_id(), "ci-runner");
+        manager
+            .save_baseline("my_crate", &create_test_result("bench"), false)
+            .unwrap();
+        assert!(temp_dir
+            .path()
+            .join("shared/benches/ci-runner/my_crate_bench")
+            .is_dir());
+
+        // An absolute directory isn't joined to the workspace root
+        let elsewhere = TempDir::new().unwrap();
+        let config = crate::config::BaselineConfig {
+            dir: Some(elsewhere.path().to_path_buf()),
+            ..config
+        };
+        let manager = BaselineManager::for_workspace(temp_dir.path(), &config).unwrap();
+        assert_eq!(manager.root_dir(), elsewhere.path());
+
+        for machine_id in ["", "..", "a/b"] {
+            let config = crate::config::BaselineConfig {
+                machine_id: Some(machine_id.to_string()),
+                ..Default::default()
+            };
+            let err = BaselineManager::for_workspace(temp_dir.path(), &config).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn test_save_prunes_to_retention() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path())
+            .unwrap()
+            .with_retention(RetentionPolicy {
+                max_runs: Some(2),
+                max_age_days: None,
+            });
+        let result = create_test_result("test_bench");
+        for _ in 0..4 {
+            manager.save_baseline("my_crate", &result, false).unwrap();
+        }
+
+        let dir = manager.benchmark_dir("my_crate", "test_bench");
+        assert_eq!(fs::read_dir(dir).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_compressed_and_plain_runs_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let plain = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        let compressed = BaselineManager::with_root_dir(temp_dir.path())
+            .unwrap()
+            .with_compression(true);
+        for (manager, mean_ms) in [(&plain, 5), (&compressed, 6), (&plain, 7)] {
+            let mut result = create_test_result("test_bench");
+            result.all_timings = vec![Duration::from_millis(mean_ms); 10];
+            manager.save_baseline("my_crate", &result, false).unwrap();
+        }
+
+        let dir = plain.benchmark_dir("my_crate", "test_bench");
+        let gz: Vec<PathBuf> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|path| is_compressed(path))
+            .collect();
+        assert_eq!(gz.len(), 1);
+        assert_eq!(fs::read(&gz[0]).unwrap()[..2], [0x1f, 0x8b]);
+
+        // Both formats are listed and read, in order
+        let runs = plain.list_runs("my_crate", "test_bench").unwrap();
+        assert_eq!(runs.len(), 3);
+        let means: Vec<u128> = runs
+            .iter()
+            .map(|run| {
+                let data = compressed.load_run("my_crate", "test_bench", run);
+                data.unwrap().unwrap().statistics.mean
+            })
+            .collect();
+        assert_eq!(means, vec![5_000_000, 6_000_000, 7_000_000]);
+        let latest = compressed.load_baseline("my_crate", "test_bench").unwrap();
+        assert_eq!(latest.unwrap().statistics.mean, 7_000_000);
+        let window = plain
+            .load_recent_baselines("my_crate", "test_bench", 10)
+            .unwrap();
+        assert_eq!(window.len(), 3);
+
+        let report = plain
+            .prune(
+                "my_crate",
+                "test_bench",
+                RetentionPolicy {
+                    max_runs: Some(1),
+                    max_age_days: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(report.removed_runs, 2);
+        assert!(!gz[0].exists());
+        assert_eq!(plain.list_runs("my_crate", "test_bench").unwrap().len(), 1);
+    }
+
+    fn write_machine_run(
+        root: &Path,
+        machine_id: &str,
+        timestamp: &str,
+        mean_ms: u64,
+        was_regression: bool,
+    ) {
+        let mut result = create_test_result("test_bench");
+        result.all_timings = vec![Duration::from_millis(mean_ms); 10];
+        result.percentiles.mean = Duration::from_millis(mean_ms);
+
+        let mut baseline =
+            BaselineData::from_bench_result(&result, machine_id.to_string(), was_regression);
+        baseline.timestamp = timestamp.to_string();
+
+        let dir = root.join(machine_id).join("my_crate_test_bench");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(format!("{}.json", timestamp)),
+            serde_json::to_string(&baseline).unwrap(),
+        )
+        .unwrap();
+    }
+
+    /// Write a legacy `<crate>_<bench>.json` baseline for this machine
+    fn write_legacy_baseline(
+        manager: &BaselineManager,
+        bench: &str,
+        timestamp: &str,
+        mean_ms: u64,
+    ) {
+        let mut result = create_test_result(bench);
+        result.percentiles.mean = Duration::from_millis(mean_ms);
+        let mut baseline =
+            BaselineData::from_bench_result(&result, manager.machine_id().to_string(), false);
+        baseline.timestamp = timestamp.to_string();
+
+        fs::create_dir_all(manager.machine_dir()).unwrap();
+        fs::write(
+            manager.legacy_baseline_path("my_crate", bench),
+            serde_json::to_string(&baseline).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_old_statistics_are_recomputed_on_load() {
+        let mut result = create_test_result("test_bench");
+        result.all_timings = (1..=10).map(Duration::from_millis).collect();
+        let mut old = BaselineData::from_bench_result(&result, "ci-machine".to_string(), false);
+        // As written before interpolated percentiles: the sample at index len / 2
+        old.stats_version = 0;
+        old.statistics.median = 6_000_000;
+        old.statistics.mad = 0.0;
+
+        let loaded = parse_baseline(&serde_json::to_string(&old).unwrap()).unwrap();
+        assert_eq!(loaded.stats_version, STATS_VERSION);
+        assert_eq!(loaded.statistics.median, 5_500_000);
+        assert_eq!(
+            loaded.percentiles.unwrap().p50(),
+            Duration::from_micros(5_500)
+        );
+        assert_eq!(loaded.statistics.mad, 2_500_000.0);
+        assert_eq!(loaded.statistics.iqr, 4_500_000.0);
+
+        // Reservoir runs keep what they recorded, only gaining robust dispersion
+        old.reservoir_sampled = true;
+        let loaded = parse_baseline(&serde_json::to_string(&old).unwrap()).unwrap();
+        assert_eq!(loaded.statistics.median, 6_000_000);
+        assert_eq!(loaded.statistics.mad, 2_500_000.0);
+    }
+
+    #[test]
+    fn test_multi_minute_samples_round_trip_exactly() {
+        let mut result = create_test_result("test_bench");
+        // 3 to 7 minutes, with sub-second remainders
+        result.all_timings = (3..=7)
+            .map(|minutes| Duration::new(minutes * 60, 123_456_789))
+            .collect();
+        result.samples = result.all_timings.len();
+        result.percentiles = crate::calculate_percentiles(&result.all_timings, &[50.0, 99.0]);
+        assert_eq!(result.percentiles.mean, Duration::new(300, 123_456_789));
+
+        let baseline = BaselineData::from_bench_result(&result, "ci-machine".to_string(), false);
+        assert_eq!(baseline.statistics.max, 420_123_456_789);
+        let loaded = parse_baseline(&serde_json::to_string(&baseline).unwrap()).unwrap();
+        let restored = loaded.to_bench_result();
+        assert_eq!(restored.all_timings, result.all_timings);
+        assert_eq!(restored.percentiles.mean, result.percentiles.mean);
+        assert_eq!(restored.percentiles.p50(), Duration::new(300, 123_456_789));
+
+        assert_eq!(
+            crate::duration_from_nanos(u128::from(u64::MAX) + 1),
+            Duration::new(18_446_744_073, 709_551_616)
+        );
+        assert_eq!(crate::duration_from_nanos(u128::MAX), Duration::MAX);
+        assert_eq!(crate::nanos_u64(Duration::MAX), u64::MAX);
+        assert_eq!(crate::nanos_u64(Duration::new(420, 1)), 420_000_000_001);
+    }
+
+    #[test]
+    fn test_batched_iterations_field_is_ignored() {
+        let result = create_test_result("test_bench");
+        let baseline = BaselineData::from_bench_result(&result, "ci-machine".to_string(), false);
+        let mut json = serde_json::to_value(&baseline).unwrap();
+        // As written when each sample timed a batch of calls
+        json["iterations"] = serde_json::json!(100);
+
+        let loaded = parse_baseline(&json.to_string()).unwrap();
+        assert_eq!(loaded.samples, baseline.samples);
+        assert_eq!(loaded.to_bench_result().samples, result.samples);
+        assert!(!serde_json::to_string(&loaded)
+            .unwrap()
+            .contains("\"iterations\""));
+    }
+
+    #[test]
+    fn test_window_outcome_tells_excluded_history_from_none() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        let machine = manager.machine_id().to_string();
+        let outcome = || {
+            manager
+                .load_window("my_crate", "test_bench", 10)
+                .unwrap()
+                .outcome
+        };
+
+        assert_eq!(outcome(), WindowOutcome::EmptyNoHistory);
+        assert_eq!(outcome().exclusion_note(), None);
+
+        // Every stored run was flagged as a regression
+        write_machine_run(temp_dir.path(), &machine, "2025-01-01T00-00-00", 10, true);
+        write_machine_run(temp_dir.path(), &machine, "2025-01-02T00-00-00", 10, true);
+        let flagged = Exclusion {
+            reason: ExclusionReason::FlaggedRegression,
+            runs: 2,
+        };
+        assert_eq!(
+            outcome(),
+            WindowOutcome::EmptyFiltered {
+                reasons: vec![flagged]
+            }
+        );
+        assert_eq!(
+            outcome().exclusion_note().unwrap(),
+            "baseline history exists but was excluded: 2 flagged regressions"
+        );
+
+        // A run that doesn't parse is excluded too
+        let bench_dir = manager.benchmark_dir("my_crate", "test_bench");
+        fs::write(bench_dir.join("2025-01-03T00-00-00.json"), "{ truncated").unwrap();
+        assert_eq!(
+            outcome().exclusion_note().unwrap(),
+            "baseline history exists but was excluded: 2 flagged regressions, 1 unreadable run"
+        );
+
+        let excluded = ComparisonResult {
+            benchmark_name: "test_bench".to_string(),
+            group: None,
             comparison: None,
             is_regression: false,
+            reference: None,
+            quality_failure: None,
+            is_improvement: false,
+            window: outcome(),
+        };
+        assert!(excluded.history_excluded());
+        assert!(!excluded.is_new());
+
+        // One usable run is a window
+        write_machine_run(temp_dir.path(), &machine, "2025-01-04T00-00-00", 10, false);
+        assert_eq!(outcome(), WindowOutcome::Populated);
+    }
+
+    #[test]
+    fn test_settings_comparable_within_tolerance() {
+        let base = MeasurementSettings {
+            samples: 1000,
+            warmup_ms: 3000,
+            warmup_mode: Default::default(),
+            adaptive: false,
+        };
+        let with = |f: fn(&mut MeasurementSettings)| {
+            let mut other = base;
+            f(&mut other);
+            base.comparable_with(&other)
+        };
+
+        assert!(with(|s| s.samples = 2000));
+        assert!(with(|s| s.warmup_ms = 1500));
+        assert!(!with(|s| s.samples = 100));
+        assert!(!with(|s| s.warmup_ms = 10_000));
+        assert!(!with(|s| s.adaptive = true));
+    }
+
+    #[test]
+    fn test_window_drops_runs_with_different_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        let machine = manager.machine_id().to_string();
+        let settings = MeasurementSettings {
+            samples: 1000,
+            warmup_ms: 3000,
+            warmup_mode: Default::default(),
+            adaptive: false,
+        };
+        let bench_dir = temp_dir.path().join(&machine).join("my_crate_test_bench");
+        let write_run = |timestamp: &str, samples: Option<usize>| {
+            write_machine_run(temp_dir.path(), &machine, timestamp, 10, false);
+            let path = bench_dir.join(format!("{}.json", timestamp));
+            let mut run = parse_baseline(&fs::read_to_string(&path).unwrap()).unwrap();
+            run.settings = samples.map(|samples| MeasurementSettings {
+                samples,
+                ..settings
+            });
+            fs::write(&path, serde_json::to_string(&run).unwrap()).unwrap();
+        };
+        let window = || {
+            manager
+                .load_comparable_window("my_crate", "test_bench", 10, Some(&settings))
+                .unwrap()
+        };
+
+        write_run("2025-01-01T00-00-00", Some(100));
+        write_run("2025-01-02T00-00-00", Some(100));
+        let window_now = window();
+        assert!(window_now.runs.is_empty());
+        assert_eq!(
+            window_now.outcome.exclusion_note().unwrap(),
+            "baseline history exists but was excluded: 2 runs recorded with different settings"
+        );
+
+        // Runs from before settings were recorded still compare
+        write_run("2025-01-03T00-00-00", None);
+        write_run("2025-01-04T00-00-00", Some(1000));
+        let window_now = window();
+        assert_eq!(window_now.runs.len(), 2);
+        assert_eq!(window_now.outcome, WindowOutcome::Populated);
+    }
+
+    #[test]
+    fn test_window_outcome_counts_runs_excluded_under_a_former_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        let machine = manager.machine_id().to_string();
+        write_machine_run(temp_dir.path(), &machine, "2025-01-01T00-00-00", 10, true);
+        fs::rename(
+            temp_dir.path().join(&machine).join("my_crate_test_bench"),
+            temp_dir.path().join(&machine).join("my_crate_bench_old"),
+        )
+        .unwrap();
+        let new_dir = manager.benchmark_dir("my_crate", "bench_new");
+        fs::create_dir_all(&new_dir).unwrap();
+        fs::write(
+            new_dir.join(REDIRECT_FILE),
+            r#"{"redirect_from": "bench_old"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manager
+                .load_window("my_crate", "bench_new", 10)
+                .unwrap()
+                .outcome
+                .exclusion_note()
+                .unwrap(),
+            "baseline history exists but was excluded: 1 flagged regression"
+        );
+    }
+
+    #[test]
+    fn test_legacy_baseline_feeds_window_until_migrated() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        let machine = manager.machine_id().to_string();
+
+        // bench_old has only a legacy file; bench_mixed has one next to a newer run
+        write_legacy_baseline(&manager, "bench_old", "2024-03-01T09:15:00+00:00", 5);
+        write_legacy_baseline(&manager, "bench_mixed", "2024-03-02T10:00:00+00:00", 6);
+        write_machine_run(temp_dir.path(), &machine, "2025-01-01T00-00-00", 7, false);
+        fs::rename(
+            temp_dir.path().join(&machine).join("my_crate_test_bench"),
+            temp_dir.path().join(&machine).join("my_crate_bench_mixed"),
+        )
+        .unwrap();
+
+        let old = manager
+            .load_recent_baselines("my_crate", "bench_old", 10)
+            .unwrap();
+        assert_eq!(old.len(), 1);
+        assert_eq!(old[0].timestamp, "2024-03-01T09:15:00+00:00");
+
+        // The legacy file is the oldest entry of a mixed window
+        let mean_ms = |window: &[BaselineData]| -> Vec<u128> {
+            window
+                .iter()
+                .map(|b| b.percentiles.as_ref().unwrap().mean.as_millis())
+                .collect()
+        };
+        let mixed = manager
+            .load_recent_baselines("my_crate", "bench_mixed", 10)
+            .unwrap();
+        assert_eq!(mean_ms(&mixed), vec![6, 7]);
+        assert_eq!(
+            mean_ms(
+                &manager
+                    .load_recent_baselines("my_crate", "bench_mixed", 1)
+                    .unwrap()
+            ),
+            vec![7]
+        );
+
+        // The first write migrates every legacy file, keeping its timestamp
+        manager
+            .save_baseline("my_crate", &create_test_result("bench_new"), false)
+            .unwrap();
+        assert!(!manager
+            .legacy_baseline_path("my_crate", "bench_old")
+            .exists());
+        assert!(!manager
+            .legacy_baseline_path("my_crate", "bench_mixed")
+            .exists());
+        assert_eq!(
+            manager.list_runs("my_crate", "bench_old").unwrap(),
+            vec!["2024-03-01T09-15-00"]
+        );
+        assert_eq!(
+            manager.list_runs("my_crate", "bench_mixed").unwrap(),
+            vec!["2024-03-02T10-00-00", "2025-01-01T00-00-00"]
+        );
+
+        // Windows are unchanged by the migration
+        let old_after = manager
+            .load_recent_baselines("my_crate", "bench_old", 10)
+            .unwrap();
+        assert_eq!(old_after.len(), 1);
+        assert_eq!(old_after[0].timestamp, old[0].timestamp);
+        assert_eq!(
+            mean_ms(
+                &manager
+                    .load_recent_baselines("my_crate", "bench_mixed", 10)
+                    .unwrap()
+            ),
+            vec![6, 7]
+        );
+        assert_eq!(manager.migrate_legacy_baselines().unwrap(), 0);
+    }
+
+    /// Write a run of `module::bench_step` into this machine's
+    /// `my_crate_bench_step` directory, as kept before benchmarks were
+    /// stored under their module path
+    fn write_shared_run(manager: &BaselineManager, module: &str, timestamp: &str) {
+        let result = BenchResult {
+            module: module.to_string(),
+            ..create_test_result("bench_step")
+        };
+        let baseline =
+            BaselineData::from_bench_result(&result, manager.machine_id().to_string(), false);
+        let dir = manager.machine_dir().join("my_crate_bench_step");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(format!("{}.json", timestamp)),
+            serde_json::to_string(&baseline).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_stored_benchmarks_filtered_by_directory_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        for name in ["bench_sort", "bench_sort_large", "bench_hash"] {
+            manager
+                .save_baseline("my_crate", &create_test_result(name), false)
+                .unwrap();
+        }
+
+        let stored = |filter: Option<BenchFilter>| -> Vec<String> {
+            manager
+                .stored_benchmarks(filter.as_ref())
+                .unwrap()
+                .into_iter()
+                .map(|stored| stored.dir_name)
+                .collect()
+        };
+        assert_eq!(
+            stored(None),
+            vec![
+                "my_crate_bench_hash",
+                "my_crate_bench_sort",
+                "my_crate_bench_sort_large"
+            ]
+        );
+        assert_eq!(
+            stored(Some(BenchFilter::new("sort", false))),
+            vec!["my_crate_bench_sort", "my_crate_bench_sort_large"]
+        );
+        assert_eq!(
+            stored(Some(BenchFilter::new("my_crate_bench_sort", true))),
+            vec!["my_crate_bench_sort"]
+        );
+        assert_eq!(
+            stored(Some(BenchFilter::new("*_sort", false))),
+            vec!["my_crate_bench_sort"]
+        );
+        assert!(stored(Some(BenchFilter::new("bench_sort", true))).is_empty());
+
+        // Split back into names addressing the same directory
+        let hash = &manager.stored_benchmarks(None).unwrap()[0];
+        assert_eq!(
+            manager
+                .list_runs(&hash.crate_name, &hash.benchmark_name)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_storage_name_qualifies_module_benchmarks() {
+        assert_eq!(
+            storage_name("game_physics", "bench_step"),
+            ("game_physics", "bench_step".to_string())
+        );
+        assert_eq!(
+            storage_name("game_physics::broad::grid", "bench_step"),
+            ("game_physics", "broad-grid-bench_step".to_string())
+        );
+        assert_eq!(
+            storage_key("game_physics::broad", "bench_step"),
+            "game_physics_broad-bench_step"
+        );
+    }
+
+    #[test]
+    fn test_module_benchmark_reads_shared_directory_until_saved() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        write_shared_run(&manager, "my_crate::broad", "2025-01-01T00-00-00");
+        write_shared_run(&manager, "my_crate::narrow", "2025-01-02T00-00-00");
+        write_shared_run(&manager, "my_crate::broad", "2025-01-03T00-00-00");
+
+        // Each reads its own module's runs of the shared directory
+        let modules = |benchmark_name: &str| -> Vec<String> {
+            manager
+                .load_recent_baselines("my_crate", benchmark_name, 10)
+                .unwrap()
+                .into_iter()
+                .map(|b| b.module)
+                .collect()
+        };
+        assert_eq!(modules("broad-bench_step"), vec!["my_crate::broad"; 2]);
+        assert_eq!(modules("narrow-bench_step"), vec!["my_crate::narrow"]);
+        assert!(modules("grid-bench_step").is_empty());
+        assert!(manager.has_baseline("my_crate", "broad-bench_step"));
+        assert!(!manager.has_baseline("my_crate", "grid-bench_step"));
+
+        // Nothing moved by reading
+        assert_eq!(
+            manager.list_benchmarks().unwrap(),
+            vec!["my_crate_bench_step"]
+        );
+    }
+
+    #[test]
+    fn test_module_benchmark_save_moves_its_shared_runs() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        write_shared_run(&manager, "my_crate::broad", "2025-01-01T00-00-00");
+        write_shared_run(&manager, "my_crate::narrow", "2025-01-02T00-00-00");
+        write_shared_run(&manager, "my_crate::broad", "2025-01-03T00-00-00");
+
+        let saved = |module: &str| {
+            let result = BenchResult {
+                module: module.to_string(),
+                ..create_test_result("bench_step")
+            };
+            manager.save_baseline("my_crate", &result, false).unwrap();
+        };
+
+        // The broad runs move; narrow's stay behind for its own save
+        saved("my_crate::broad");
+        let broad = manager.list_runs("my_crate", "broad-bench_step").unwrap();
+        assert_eq!(broad.len(), 3);
+        assert_eq!(broad[..2], ["2025-01-01T00-00-00", "2025-01-03T00-00-00"]);
+        assert_eq!(
+            manager.list_runs("my_crate", "bench_step").unwrap(),
+            vec!["2025-01-02T00-00-00"]
+        );
+        assert_eq!(
+            manager
+                .load_recent_baselines("my_crate", "narrow-bench_step", 10)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // The last move removes the shared directory
+        saved("my_crate::narrow");
+        assert_eq!(
+            manager.list_benchmarks().unwrap(),
+            vec!["my_crate_broad-bench_step", "my_crate_narrow-bench_step"]
+        );
+        let narrow = manager
+            .load_recent_baselines("my_crate", "narrow-bench_step", 10)
+            .unwrap();
+        assert_eq!(narrow.len(), 2);
+        assert!(narrow.iter().all(|b| b.module == "my_crate::narrow"));
+    }
+
+    #[test]
+    fn test_load_recent_baselines_partitions_by_machine() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+
+        write_machine_run(
+            temp_dir.path(),
+            "ci-machine",
+            "2025-01-01T00-00-00",
+            5,
+            false,
+        );
+        write_machine_run(
+            temp_dir.path(),
+            "ci-machine",
+            "2025-01-02T00-00-00",
+            5,
+            false,
+        );
+        write_machine_run(temp_dir.path(), "laptop", "2025-01-01T12-00-00", 9, false);
+
+        let ci = manager
+            .load_recent_baselines_for_machine("ci-machine", "my_crate", "test_bench", 10)
+            .unwrap();
+        let laptop = manager
+            .load_recent_baselines_for_machine("laptop", "my_crate", "test_bench", 10)
+            .unwrap();
+
+        assert_eq!(ci.len(), 2);
+        assert!(ci.iter().all(|b| b.machine_id == "ci-machine"));
+        assert_eq!(laptop.len(), 1);
+        assert_eq!(laptop[0].machine_id, "laptop");
+
+        // The local machine has no history in this tree
+        let local = manager
+            .load_recent_baselines("my_crate", "test_bench", 10)
+            .unwrap();
+        assert!(local.is_empty());
+
+        assert_eq!(
+            manager.list_machines().unwrap(),
+            vec!["ci-machine", "laptop"]
+        );
+    }
+
+    #[test]
+    fn test_window_across_machines_is_normalized() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path())
+            .unwrap()
+            .with_compare_across_machines(true);
+        let local = manager.machine_id().to_string();
+
+        // Nothing to normalize against before this machine's first run
+        write_machine_run(
+            temp_dir.path(),
+            "ci-machine",
+            "2025-01-01T00-00-00",
+            20,
+            false,
+        );
+        let window = manager.load_window("my_crate", "test_bench", 10).unwrap();
+        assert!(window.runs.is_empty());
+
+        write_machine_run(temp_dir.path(), &local, "2025-01-02T00-00-00", 10, false);
+        write_machine_run(
+            temp_dir.path(),
+            "ci-machine",
+            "2025-01-03T00-00-00",
+            20,
+            false,
+        );
+        write_machine_run(temp_dir.path(), "laptop", "2025-01-04T00-00-00", 40, false);
+        let window = manager.load_window("my_crate", "test_bench", 10).unwrap();
+        assert_eq!(window.runs.len(), 4);
+        // Every machine's runs at this machine's scale, oldest first
+        assert!(window.runs.iter().all(|b| b.statistics.mean == 10_000_000));
+        assert!(window.runs.iter().all(|b| b.samples[0] == 10_000_000));
+        assert_eq!(window.runs[0].machine_id, "ci-machine");
+
+        // The newest runs of any machine fill the window
+        let window = manager.load_window("my_crate", "test_bench", 2).unwrap();
+        assert_eq!(window.runs[1].machine_id, "laptop");
+
+        let mut current = create_test_result("test_bench");
+        current.all_timings = vec![Duration::from_millis(10); 10];
+        current.percentiles.mean = Duration::from_millis(10);
+        let config = ComparisonConfig {
+            compare_across_machines: true,
+            ..Default::default()
+        };
+        let window = manager.load_window("my_crate", "test_bench", 10).unwrap();
+        let result = detect_regression(&current, &window.runs, &config);
+        let comparison = result.comparison.unwrap();
+        assert_eq!(comparison.normalized_machines, Some(3));
+        assert!(comparison.percentage_change.abs() < 0.01);
+        assert!(!result.is_regression);
+
+        // Other machines' runs stay out without the mode
+        let own = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        assert_eq!(
+            own.load_window("my_crate", "test_bench", 10)
+                .unwrap()
+                .runs
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_reference_from_other_machine_is_informational() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+
+        // CI has the fuller window, the laptop only one run
+        for (i, mean) in [10, 11, 10].iter().enumerate() {
+            let ts = format!("2025-01-0{}T00-00-00", i + 1);
+            write_machine_run(temp_dir.path(), "ci-machine", &ts, *mean, false);
+        }
+        write_machine_run(temp_dir.path(), "laptop", "2025-01-09T00-00-00", 10, false);
+
+        let reference = manager
+            .load_reference_baselines("my_crate", "test_bench", 10)
+            .unwrap();
+        assert_eq!(reference.as_ref().unwrap().0, "ci-machine");
+        assert_eq!(reference.as_ref().unwrap().1.len(), 3);
+
+        // Twice as slow as CI: still a NEW benchmark here, not a regression
+        let mut current = create_test_result("test_bench");
+        current.all_timings = vec![Duration::from_millis(20); 10];
+        current.percentiles.mean = Duration::from_millis(20);
+        let result = detect_regression_with_cpd(
+            &current,
+            &[],
+            5.0,
+            0.95,
+            0.8,
+            0.01,
+            Default::default(),
+            Default::default(),
+        )
+        .with_reference(&current, reference, &ComparisonConfig::default());
+
+        assert!(result.comparison.is_none());
+        assert!(!result.is_regression);
+        let reference = result.reference.unwrap();
+        assert_eq!(reference.reference_machine.as_deref(), Some("ci-machine"));
+        assert!(reference.percentage_change > 50.0);
+
+        // Nothing to orient by when only this machine could have history
+        let empty = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(empty.path()).unwrap();
+        assert!(manager
+            .load_reference_baselines("my_crate", "test_bench", 10)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_summarize_machines_flags_disagreement() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+
+        // CI: stable history around 10ms, then a jump to 20ms
+        for (i, mean) in [10, 11, 10, 11, 10, 20].iter().enumerate() {
+            let ts = format!("2025-01-0{}T00-00-00", i + 1);
+            write_machine_run(temp_dir.path(), "ci-machine", &ts, *mean, false);
+        }
+        // Laptop: stable throughout
+        for (i, mean) in [10, 11, 10, 11, 10, 10].iter().enumerate() {
+            let ts = format!("2025-01-0{}T12-00-00", i + 1);
+            write_machine_run(temp_dir.path(), "laptop", &ts, *mean, false);
+        }
+
+        let config = ComparisonConfig::default();
+        let summaries = summarize_machines(&manager, "my_crate", "test_bench", &config).unwrap();
+
+        assert_eq!(summaries.len(), 2);
+        let ci = summaries
+            .iter()
+            .find(|s| s.machine_id == "ci-machine")
+            .unwrap();
+        let laptop = summaries.iter().find(|s| s.machine_id == "laptop").unwrap();
+        assert_eq!(ci.run_count, 6);
+        assert!(ci.comparison.is_regression);
+        assert!(!laptop.comparison.is_regression);
+        assert!(machines_disagree(&summaries));
+        assert!(!machines_disagree(std::slice::from_ref(laptop)));
+    }
+
+    fn history_from_means(means: &[u64]) -> Vec<BaselineData> {
+        means
+            .iter()
+            .enumerate()
+            .map(|(i, &mean_ms)| {
+                let mut result = create_test_result("test_bench");
+                result.all_timings = vec![Duration::from_millis(mean_ms); 10];
+                result.percentiles.mean = Duration::from_millis(mean_ms);
+                let mut baseline =
+                    BaselineData::from_bench_result(&result, "ci-machine".to_string(), false);
+                baseline.timestamp = format!("2025-01-{:02}T00-00-00", i + 1);
+                baseline
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_drift_check_warns_on_slow_climb() {
+        // 1ms per run: every step is within the 5% threshold
+        let history = history_from_means(&[100, 101, 102, 103, 104, 105, 106, 107]);
+        let mut current = history[0].to_bench_result();
+        current.all_timings = vec![Duration::from_millis(108); 10];
+        current.percentiles.mean = Duration::from_millis(108);
+
+        let judge = |drift_threshold: Option<f64>| {
+            let config = ComparisonConfig {
+                drift_threshold,
+                ..ComparisonConfig::default()
+            };
+            judge_against_history(&current, &history, &config)
+        };
+
+        let drifting = judge(Some(5.0));
+        assert!(!drifting.is_regression);
+        let trend = drifting.drift().unwrap();
+        assert_eq!(trend.runs, 9);
+        assert!(trend.r_squared > 0.99);
+        assert!((trend.drift_pct() - 7.7).abs() < 0.1);
+
+        assert!(judge(Some(10.0)).drift().is_none());
+        assert!(judge(None).drift().is_none());
+    }
+
+    /// 100 samples: 98 at 1µs and a tail of two at `tail_ns`
+    fn tail_run(tail_ns: u64) -> BenchResult {
+        let mut result = create_test_result("test_bench");
+        result.all_timings = (0..100)
+            .map(|i| Duration::from_nanos(if i >= 98 { tail_ns } else { 1000 }))
+            .collect();
+        result.percentiles =
+            crate::calculate_percentiles(&result.all_timings, &crate::DEFAULT_PERCENTILES);
+        result
+    }
+
+    #[test]
+    fn test_p99_regression_with_stable_mean() {
+        let history: Vec<BaselineData> = [2000, 2050, 1950, 2000, 2100, 1900, 2000, 2050]
+            .iter()
+            .map(|&tail_ns| {
+                BaselineData::from_bench_result(&tail_run(tail_ns), "ci-machine".to_string(), false)
+            })
+            .collect();
+        let judge = |current: &BenchResult, p99_threshold| {
+            let config = ComparisonConfig {
+                p99_threshold,
+                ..ComparisonConfig::default()
+            };
+            detect_regression(current, &history, &config)
+        };
+
+        // The tail doubles; the mean moves under 4%
+        let doubled = tail_run(4000);
+        let flagged = judge(&doubled, Some(20.0));
+        assert!(flagged.is_regression);
+        assert!(flagged.is_tail_regression());
+        assert!(!flagged.is_improvement);
+        let comparison = flagged.comparison.unwrap();
+        assert!(comparison.percentage_change < comparison.threshold);
+        let tail = comparison.p99.unwrap();
+        assert!(tail.percentage_change > 90.0);
+        assert!(tail.z_score > 5.0);
+
+        // Off by default, and mean-only judging misses it
+        let unjudged = judge(&doubled, None);
+        assert!(!unjudged.is_regression);
+        assert!(unjudged.comparison.unwrap().p99.is_none());
+
+        // A tail within the window's spread isn't flagged
+        let steady = judge(&tail_run(2020), Some(20.0));
+        assert!(!steady.is_regression);
+        assert!(!steady.is_tail_regression());
+        assert!(steady.comparison.unwrap().p99.is_some());
+    }
+
+    #[test]
+    fn test_replay_counts_regressions_and_flaps() {
+        let config = ComparisonConfig::default();
+
+        // A one-off spike that clears on the next run is a flap
+        let spike = replay_history(
+            &history_from_means(&[10, 11, 10, 11, 10, 20, 10, 11]),
+            &config,
+            None,
+        );
+        assert_eq!(spike.benchmark_name, "test_bench");
+        assert_eq!(spike.runs.len(), 8);
+        assert_eq!(spike.regressions(), 1);
+        assert_eq!(spike.flaps(), 1);
+        assert_eq!(spike.changed_verdicts(), 1);
+
+        // A persistent shift stays out of the window, so every later run is flagged
+        let shift = replay_history(
+            &history_from_means(&[10, 11, 10, 11, 10, 20, 20, 20]),
+            &config,
+            None,
+        );
+        assert_eq!(shift.regressions(), 3);
+        assert_eq!(shift.flaps(), 0);
+
+        // A looser threshold would have flagged nothing
+        let loose = ComparisonConfig {
+            threshold: 200.0,
+            ..ComparisonConfig::default()
+        };
+        let replayed = replay_history(
+            &history_from_means(&[10, 11, 10, 11, 10, 20, 20, 20]),
+            &loose,
+            None,
+        );
+        assert_eq!(replayed.regressions(), 0);
+    }
+
+    #[test]
+    fn test_replay_last_keeps_full_window() {
+        let config = ComparisonConfig::default();
+        let report = replay_history(
+            &history_from_means(&[10, 11, 10, 11, 10, 20]),
+            &config,
+            Some(1),
+        );
+
+        assert_eq!(report.runs.len(), 1);
+        assert_eq!(report.runs[0].timestamp, "2025-01-06T00-00-00");
+        let comparison = report.runs[0].comparison.comparison.as_ref().unwrap();
+        assert_eq!(comparison.baseline_count, 5);
+        assert!(report.runs[0].comparison.is_regression);
+    }
+
+    #[test]
+    fn test_comparison_flags_mixed_build_profiles() {
+        let history = history_from_means(&[10, 11, 10]);
+        let config = ComparisonConfig::default();
+        let compare = |current: &BenchResult| {
+            detect_regression_with_cpd(
+                current,
+                &history,
+                config.threshold,
+                config.confidence_level,
+                config.cp_threshold,
+                config.hazard_rate,
+                config.metric,
+                config.window_scale,
+            )
+            .comparison
+            .unwrap()
+            .mixed_build_profiles
+        };
+
+        let mut current = history[0].to_bench_result();
+        assert!(!compare(&current));
+        current.debug_assertions = true;
+        assert!(compare(&current));
+    }
+
+    #[test]
+    fn test_environment_changes_against_window() {
+        let env = |pairs: &[(&str, Option<&str>)]| -> BTreeMap<String, Option<String>> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.map(str::to_string)))
+                .collect()
+        };
+        let mut history = history_from_means(&[10, 11, 10]);
+        for (i, baseline) in history.iter_mut().enumerate() {
+            let threads = if i == 0 { Some("4") } else { Some("8") };
+            baseline.environment = env(&[("RUST_LOG", None), ("RAYON_NUM_THREADS", threads)]);
+        }
+        // Recorded before MALLOC_CONF was watched
+        history[0]
+            .environment
+            .insert("MALLOC_CONF".to_string(), None);
+
+        let mut current = history[2].to_bench_result();
+        current.environment = env(&[("RUST_LOG", None), ("OMP_NUM_THREADS", None)]);
+        assert!(environment_changes(&current, &history).is_empty());
+
+        current.environment = env(&[
+            ("RUST_LOG", Some("debug")),
+            ("MALLOC_CONF", None),
+            ("OMP_NUM_THREADS", Some("2")),
+        ]);
+        let changes = environment_changes(&current, &history);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes[0].format(),
+            "RUST_LOG was unset in baselines but =debug now"
+        );
+
+        current.environment = env(&[("RAYON_NUM_THREADS", None)]);
+        assert_eq!(
+            environment_changes(&current, &history)[0].format(),
+            "RAYON_NUM_THREADS was =8 in baselines but unset now"
+        );
+        current.environment = env(&[("RAYON_NUM_THREADS", Some("8"))]);
+        assert_eq!(
+            environment_changes(&current, &history)[0].format(),
+            "RAYON_NUM_THREADS was =4 in 1 of 3 baseline runs but =8 now"
+        );
+    }
+
+    #[test]
+    fn test_commit_change_against_latest_run() {
+        let at = |commit: &str, dirty: bool| crate::git::GitInfo {
+            commit: commit.to_string(),
+            branch: Some("main".to_string()),
+            dirty,
+        };
+        let mut history = history_from_means(&[10, 11, 10]);
+        let mut current = history[2].to_bench_result();
+        assert_eq!(commit_change(&current, &history), None);
+
+        history[0].git = Some(at("1111111111", false));
+        history[2].git = Some(at("2222222222", false));
+        current.git = Some(at("2222222222", true));
+        assert_eq!(commit_change(&current, &history), None);
+
+        current.git = Some(at("3333333333", true));
+        assert_eq!(
+            commit_change(&current, &history).unwrap().format(),
+            "baseline measured at 2222222, now at 3333333*"
+        );
+    }
+
+    #[test]
+    fn test_toolchain_changes_against_recorded_runs() {
+        use crate::toolchain::{Toolchain, ToolchainProperty};
+        let built = |rustc: &str, cpu: &str| Toolchain {
+            rustc_version: Some(format!("rustc {} (0000000 2024-01-01)", rustc)),
+            target: Some("x86_64-unknown-linux-gnu".to_string()),
+            opt_level: Some("3".to_string()),
+            cpu_model: Some(cpu.to_string()),
+        };
+        let mut history = history_from_means(&[10, 11, 10]);
+        history[1].toolchain = Some(built("1.76.0", "Xeon"));
+        let mut current = history[2].to_bench_result();
+        current.toolchain = Some(built("1.76.0", "Xeon"));
+        assert!(toolchain_changes(&current, &history).is_empty());
+
+        // Runs without a record don't count
+        current.toolchain = Some(built("1.78.0", "EPYC"));
+        let changes = toolchain_changes(&current, &history);
+        let properties: Vec<ToolchainProperty> = changes.iter().map(|c| c.property).collect();
+        assert_eq!(
+            properties,
+            [ToolchainProperty::Rustc, ToolchainProperty::CpuModel]
+        );
+        assert_eq!(
+            changes[0].format(),
+            "baseline recorded with rustc 1.76.0, current 1.78.0"
+        );
+
+        let result = detect_regression(&current, &history, &ComparisonConfig::default());
+        assert!(result.toolchain_changed());
+    }
+
+    #[test]
+    fn test_mad_window_scale_ignores_outlying_run() {
+        let history = history_from_means(&[100, 101, 100, 101, 100, 101, 100, 180]);
+        let mut current = history[0].to_bench_result();
+        current.all_timings = vec![Duration::from_millis(125); 10];
+        current.percentiles.mean = Duration::from_millis(125);
+        let config = ComparisonConfig::default();
+        let detect = |window_scale| {
+            detect_regression_with_cpd(
+                &current,
+                &history,
+                config.threshold,
+                config.confidence_level,
+                config.cp_threshold,
+                config.hazard_rate,
+                config.metric,
+                window_scale,
+            )
         };
+
+        // The 180ms run inflates the standard deviation enough to hide +25%
+        let std_dev = detect(WindowScale::StdDev);
+        assert!(!std_dev.is_regression);
+        assert!(std_dev.comparison.unwrap().z_score.unwrap() < 1.0);
+
+        let mad = detect(WindowScale::Mad);
+        assert!(mad.is_regression);
+        assert!(mad.comparison.unwrap().z_score.unwrap() > 5.0);
+
+        // Identical runs have no MAD; the standard deviation takes over
+        let flat = history_from_means(&[100, 100, 100, 100]);
+        let result = detect_regression_with_cpd(
+            &current,
+            &flat,
+            config.threshold,
+            config.confidence_level,
+            config.cp_threshold,
+            config.hazard_rate,
+            config.metric,
+            WindowScale::Mad,
+        );
+        assert_eq!(result.comparison.unwrap().z_score, Some(0.0));
     }
 
-    // Extract means from historical runs (in nanoseconds)
-    let historical_means: Vec<f64> = historical
-        .iter()
-        .map(|b| b.statistics.mean as f64)
-        .collect();
+    #[test]
+    fn test_effect_size_gate() {
+        // Samples alternating `spread_us` either side of `mean_us`
+        let run = |mean_us: u64, spread_us: u64, n: usize| {
+            let mut result = create_test_result("test_bench");
+            result.all_timings = (0..n)
+                .map(|i| {
+                    let us = if i % 2 == 0 {
+                        mean_us - spread_us
+                    } else {
+                        mean_us + spread_us
+                    };
+                    Duration::from_micros(us)
+                })
+                .collect();
+            result.percentiles.mean = Duration::from_micros(mean_us);
+            result
+        };
+        let history = |means_us: &[u64], spread_us: u64, n: usize| -> Vec<BaselineData> {
+            means_us
+                .iter()
+                .map(|&mean| {
+                    BaselineData::from_bench_result(&run(mean, spread_us, n), "m".into(), false)
+                })
+                .collect()
+        };
+        let gated = ComparisonConfig {
+            min_effect_size: Some(0.5),
+            ..ComparisonConfig::default()
+        };
 
-    let current_mean = current.percentiles.mean.as_nanos() as f64;
+        // +30% on a benchmark whose samples spread ±90%: d is only ~0.33
+        let noisy = history(&[100_000, 101_000, 100_000, 101_000], 90_000, 10_000);
+        let current = run(130_000, 90_000, 10_000);
+        assert!(detect_regression(&current, &noisy, &ComparisonConfig::default()).is_regression);
+        let result = detect_regression(&current, &noisy, &gated);
+        assert!(!result.is_regression);
+        let d = result.comparison.unwrap().effect_size.unwrap();
+        assert!((0.3..0.4).contains(&d), "d = {}", d);
 
-    // --- Statistical Window Analysis ---
-    let hist_mean = crate::statistics::mean(&historical_means);
-    let hist_stddev = crate::statistics::standard_deviation(&historical_means);
+        // +0.3% on a benchmark spreading ±0.1%: d ~ 3, flagged only once the
+        // effect size is the primary gate
+        let stable = history(&[100_000, 100_010, 100_000, 100_010], 100, 1_000);
+        let current = run(100_300, 100, 1_000);
+        let result = detect_regression(&current, &stable, &gated);
+        assert!(!result.is_regression);
+        assert!(result.comparison.unwrap().effect_size.unwrap() > 2.5);
+        let primary = ComparisonConfig {
+            effect_size_primary: true,
+            ..gated.clone()
+        };
+        assert!(detect_regression(&current, &stable, &primary).is_regression);
+    }
 
-    // Z-score: how many standard deviations away?
-    let z_score_value = crate::statistics::z_score(current_mean, hist_mean, hist_stddev);
+    #[test]
+    fn test_bootstrap_method() {
+        let history = history_from_means(&[100, 102, 98, 101, 99, 100, 102, 98]);
+        let bootstrap = ComparisonConfig {
+            method: ComparisonMethod::Bootstrap,
+            ..ComparisonConfig::default()
+        };
+        let compare = |mean_ms: u64, config: &ComparisonConfig| {
+            let mut current = history[0].to_bench_result();
+            current.all_timings = vec![Duration::from_millis(mean_ms); 10];
+            current.percentiles.mean = Duration::from_millis(mean_ms);
+            detect_regression(&current, &history, config)
+        };
 
-    // Confidence interval (one-tailed for regression detection)
-    let z_critical = if (confidence_level - 0.90).abs() < 0.01 {
-        1.282 // 90% one-tailed
-    } else if (confidence_level - 0.95).abs() < 0.01 {
-        1.645 // 95% one-tailed
-    } else if (confidence_level - 0.99).abs() < 0.01 {
-        2.326 // 99% one-tailed
-    } else {
-        1.96 // Default two-tailed 95%
-    };
+        // The interval is of the difference, so it brackets the +20ms change
+        let result = compare(120, &bootstrap);
+        assert!(result.is_regression);
+        let (lower, upper) = result.comparison.unwrap().confidence_interval.unwrap();
+        assert!(lower > 0.0 && lower < 20e6 && upper > 20e6);
 
-    let upper_bound = hist_mean + (z_critical * hist_stddev);
-    let lower_bound = hist_mean - (z_critical * hist_stddev);
+        assert!(!compare(101, &bootstrap).is_regression);
 
-    // For regression, we only care if it's slower (above upper bound)
-    let statistically_significant = current_mean > upper_bound;
+        let with_samples = ComparisonConfig {
+            bootstrap_samples: true,
+            ..bootstrap.clone()
+        };
+        assert!(compare(120, &with_samples).is_regression);
+        assert!(!compare(101, &with_samples).is_regression);
+    }
 
-    // --- Bayesian Change Point Detection ---
-    let change_probability = crate::changepoint::bayesian_change_point_probability(
-        current_mean,
-        &historical_means,
-        hazard_rate,
-    );
+    #[test]
+    fn test_mann_whitney_method() {
+        // Samples alternating 10µs either side of `mean_us`
+        let run = |mean_us: u64| {
+            let mut result = create_test_result("test_bench");
+            result.all_timings = (0..200)
+                .map(|i| {
+                    Duration::from_micros(if i % 2 == 0 {
+                        mean_us - 10
+                    } else {
+                        mean_us + 10
+                    })
+                })
+                .collect();
+            result.percentiles.mean = Duration::from_micros(mean_us);
+            result
+        };
+        // Identical run means leave the z-score nothing to measure against
+        let history: Vec<BaselineData> = (0..4)
+            .map(|_| BaselineData::from_bench_result(&run(100), "m".into(), false))
+            .collect();
+        let mann_whitney = ComparisonConfig {
+            method: ComparisonMethod::MannWhitney,
+            ..ComparisonConfig::default()
+        };
 
-    // --- Practical Significance ---
-    let percentage_change = ((current_mean - hist_mean) / hist_mean) * 100.0;
-    let practically_significant = percentage_change > threshold;
+        let slower = run(110);
+        assert!(!detect_regression(&slower, &history, &ComparisonConfig::default()).is_regression);
+        let result = detect_regression(&slower, &history, &mann_whitney);
+        assert!(result.is_regression);
+        let comparison = result.comparison.unwrap();
+        assert!(comparison.mann_whitney_p.unwrap() < 1e-6);
+        assert!(comparison.rank_biserial.unwrap() > 0.4);
 
-    // --- Combined Decision ---
-    // Use tiered logic based on strength of statistical evidence:
-    //
-    // 1. EXTREME evidence (z-score > 5): Statistical + practical significance = regression
-    //    This catches acute performance disasters that are clearly not noise
-    //
-    // 2. STRONG evidence (z-score > 2): Require all three conditions
-    //    Statistical + practical + change point = regression
-    //    This is the normal case for real regressions
-    //
-    // 3. WEAK evidence (z-score <= 2): Not a regression
-    //    Likely just noise or natural variance, even if percentage is high
+        assert!(!detect_regression(&run(100), &history, &mann_whitney).is_regression);
+        // Faster is never a regression, however significant
+        assert!(!detect_regression(&run(90), &history, &mann_whitney).is_regression);
+    }
 
-    let is_regression = if z_score_value.abs() > 5.0 {
-        // Extreme statistical evidence: trust the statistics
-        statistically_significant && practically_significant
-    } else if z_score_value.abs() > 2.0 {
-        // Strong statistical evidence: require change point confirmation
-        statistically_significant && practically_significant && change_probability > cp_threshold
-    } else {
-        // Weak evidence: not a regression
-        false
-    };
+    #[test]
+    fn test_welch_method() {
+        // Samples alternating 10µs either side of `mean_us`
+        let run = |mean_us: u64| {
+            let mut result = create_test_result("test_bench");
+            result.all_timings = (0..200)
+                .map(|i| {
+                    Duration::from_micros(if i % 2 == 0 {
+                        mean_us - 10
+                    } else {
+                        mean_us + 10
+                    })
+                })
+                .collect();
+            result.percentiles.mean = Duration::from_micros(mean_us);
+            result
+        };
+        // The test reads the stored statistics, so the history keeps no samples
+        let history: Vec<BaselineData> = (0..4)
+            .map(|_| {
+                let mut baseline = BaselineData::from_bench_result(&run(100), "m".into(), false);
+                baseline.samples.clear();
+                baseline
+            })
+            .collect();
+        let welch = ComparisonConfig {
+            method: ComparisonMethod::Welch,
+            ..ComparisonConfig::default()
+        };
 
-    ComparisonResult {
-        benchmark_name: current.name.clone(),
-        comparison: Some(crate::Comparison {
-            current_mean: current.percentiles.mean,
-            baseline_mean: Duration::from_nanos(hist_mean as u64),
-            percentage_change,
-            baseline_count: historical.len(),
-            z_score: Some(z_score_value),
-            confidence_interval: Some((lower_bound, upper_bound)),
-            change_probability: Some(change_probability),
-        }),
-        is_regression,
+        let slower = run(110);
+        assert!(!detect_regression(&slower, &history, &ComparisonConfig::default()).is_regression);
+        let result = detect_regression(&slower, &history, &welch);
+        assert!(result.is_regression);
+        let comparison = result.comparison.unwrap();
+        // 10µs apart with a 10µs spread over 200 samples each
+        assert!((comparison.t_statistic.unwrap() - 10.0).abs() < 0.1);
+        assert!(comparison.p_value.unwrap() < 1e-6);
+
+        let unchanged = detect_regression(&run(100), &history, &welch);
+        assert!(!unchanged.is_regression);
+        assert!(unchanged.comparison.unwrap().p_value.unwrap() > 0.5);
+        assert!(detect_regression(&run(90), &history, &welch).is_improvement);
+
+        // Only computed when selected
+        let default = detect_regression(&slower, &history, &ComparisonConfig::default());
+        assert!(default.comparison.unwrap().t_statistic.is_none());
     }
-}
 
-/// Process benchmarks with baseline comparison using CPD
-///
-/// This function:
-/// 1. Loads recent baseline runs (window-based)
-/// 2. Compares current results with historical data using statistical + Bayesian CPD
-/// 3. Saves new baselines
-/// 4. Returns comparison results
-pub fn process_with_baselines(
-    results: &[crate::BenchResult],
-    config: &ComparisonConfig,
-) -> Result<Vec<ComparisonResult>, std::io::Error> {
-    let baseline_manager = BaselineManager::new()?;
-    let mut comparisons = Vec::new();
+    #[test]
+    fn test_filter_outliers_compares_trimmed_means() {
+        // 200 samples 1µs either side of `mean_us`, the first `stalls` of
+        // them 2ms stalls
+        let run = |mean_us: u64, stalls: usize| {
+            let mut result = create_test_result("test_bench");
+            result.all_timings = (0..200)
+                .map(|i| match i {
+                    i if i < stalls => Duration::from_millis(2),
+                    i if i % 2 == 0 => Duration::from_micros(mean_us - 1),
+                    _ => Duration::from_micros(mean_us + 1),
+                })
+                .collect();
+            let nanos: Vec<u128> = result.all_timings.iter().map(|d| d.as_nanos()).collect();
+            result.percentiles.mean =
+                Duration::from_nanos((nanos.iter().sum::<u128>() / nanos.len() as u128) as u64);
+            result.filtered = crate::filter_outliers(&nanos, &crate::DEFAULT_PERCENTILES);
+            result
+        };
+        let history: Vec<BaselineData> = [99, 100, 101, 100]
+            .iter()
+            .map(|&mean| BaselineData::from_bench_result(&run(mean, 0), "m".into(), false))
+            .collect();
+        let trimmed = ComparisonConfig {
+            filter_outliers: true,
+            ..ComparisonConfig::default()
+        };
 
-    for result in results {
-        // Extract crate name from module path (first component)
-        let crate_name = result.module.split("::").next().unwrap_or("unknown");
+        // Five stalls in unchanged code: a 47% slower raw mean, too noisy
+        // for a verdict, but no change once trimmed
+        let stalled = run(100, 5);
+        assert_eq!(stalled.filtered.as_ref().unwrap().outliers_removed, 5);
+        let raw = detect_regression(&stalled, &history, &ComparisonConfig::default());
+        assert!(!raw.is_regression);
+        assert!(raw.comparison.unwrap().percentage_change > 40.0);
+        let result = detect_regression(&stalled, &history, &trimmed);
+        assert!(!result.is_regression);
+        assert!(result.comparison.unwrap().percentage_change.abs() < 1.0);
 
-        // Load recent baselines (window-based comparison)
-        let historical =
-            baseline_manager.load_recent_baselines(crate_name, &result.name, config.window_size)?;
+        // A shift of the whole distribution is still caught
+        let slower = run(110, 5);
+        assert!(detect_regression(&slower, &history, &trimmed).is_regression);
+    }
 
-        let comparison_result = if !historical.is_empty() {
-            // Use CPD-based comparison
+    #[test]
+    fn test_small_sample_count_gives_provisional_verdict() {
+        let history = history_from_means(&[100, 100, 100, 100]);
+        let config = ComparisonConfig::default();
+        let compare = |low_ms: u64, high_ms: u64, samples: usize| {
+            let mut current = history[0].to_bench_result();
+            current.all_timings = (0..samples)
+                .map(|i| Duration::from_millis(if i % 2 == 0 { low_ms } else { high_ms }))
+                .collect();
+            current.percentiles.mean = Duration::from_millis((low_ms + high_ms) / 2);
             detect_regression_with_cpd(
-                result,
-                &historical,
+                &current,
+                &history,
                 config.threshold,
                 config.confidence_level,
                 config.cp_threshold,
                 config.hazard_rate,
+                config.metric,
+                config.window_scale,
             )
-        } else {
-            // No baseline exists - first run
-            ComparisonResult {
-                benchmark_name: result.name.clone(),
-                comparison: None,
-                is_regression: false,
-            }
         };
 
-        let is_regression = comparison_result.is_regression;
-        comparisons.push(comparison_result);
-
-        // Save current result as baseline with regression flag
-        baseline_manager.save_baseline(crate_name, result, is_regression)?;
-    }
+        // +10% with a 30ms spread over 20 samples: the MDE is ~16.7%
+        let few = compare(80, 140, 20);
+        let comparison = few.comparison.unwrap();
+        assert!(comparison.provisional);
+        assert!(!few.is_regression);
+        let mde = comparison.min_detectable_effect.unwrap();
+        assert!((mde - (1.645 + 0.8416) * 30.0 / 20f64.sqrt()).abs() < 1e-6);
+        assert!(comparison
+            .provisional_note()
+            .unwrap()
+            .contains("threshold 5% is below the minimum detectable change (~16.7%)"));
 
-    Ok(comparisons)
-}
+        // The same spread over 2000 samples resolves a 5% change
+        let many = compare(80, 140, 2000).comparison.unwrap();
+        assert!(!many.provisional);
+        assert!(many.min_detectable_effect.unwrap() < config.threshold);
 
-/// Check if any regressions were detected and exit in CI mode
-pub fn check_regressions_and_exit(comparisons: &[ComparisonResult], config: &ComparisonConfig) {
-    if !config.ci_mode {
-        return;
+        // A change well beyond the MDE still gets a verdict
+        let large = compare(170, 230, 20).comparison.unwrap();
+        assert!(!large.provisional);
     }
 
-    let has_regression = comparisons.iter().any(|c| c.is_regression);
+    #[test]
+    fn test_zero_baseline_has_no_relative_change() {
+        // Timings below the clock's resolution round to a 0ns baseline
+        let history = history_from_means(&[0, 0, 0, 0]);
+        let mut current = history[0].to_bench_result();
+        current.all_timings = vec![Duration::from_nanos(500); 10];
+        current.percentiles.mean = Duration::from_nanos(500);
+        current.percentiles.values = vec![(99.0, Duration::from_nanos(500))];
+        let config = ComparisonConfig {
+            p99_threshold: Some(10.0),
+            ..Default::default()
+        };
 
-    if has_regression {
-        use colored::Colorize;
-        eprintln!();
-        eprintln!(
-            "{}",
+        let judged = judge_against_history(&current, &history, &config);
+        assert!(!judged.is_regression && !judged.is_improvement);
+        let comparison = judged.comparison.unwrap();
+        assert_eq!(comparison.percentage_change, 0.0);
+        assert_eq!(comparison.change_probability, None);
+        assert!(comparison.provisional && comparison.below_resolution());
+        assert!(comparison.p99.is_none());
+        assert_eq!(
+            comparison.format_change(),
             format!(
-                "FAILED: Performance regression detected (threshold: {}%)",
-                config.threshold
+                "+{}",
+                crate::output::format_duration_human_readable(Duration::from_nanos(500))
             )
-            .red()
-            .bold()
         );
-        std::process::exit(1);
+        let json = serde_json::to_value(&comparison).unwrap();
+        assert_eq!(json["percentage_change"].as_f64(), Some(0.0));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::time::Duration;
-    use tempfile::TempDir;
+    #[test]
+    fn test_phase_inconsistency_marks_comparison_suspect() {
+        let history = history_from_means(&[100, 101, 100, 101]);
+        let config = ComparisonConfig::default();
+        let judge = |warmup_ns: f64, sampling_ns: f64| {
+            let mut current = history[0].to_bench_result();
+            current.all_timings = vec![Duration::from_millis(150); 100];
+            current.percentiles.mean = Duration::from_millis(150);
+            current.warmup_ns_per_iter = Some(warmup_ns);
+            current.sampling_ns_per_iter = Some(sampling_ns);
+            detect_regression_with_cpd(
+                &current,
+                &history,
+                config.threshold,
+                config.confidence_level,
+                config.cp_threshold,
+                config.hazard_rate,
+                config.metric,
+                config.window_scale,
+            )
+            .with_consistency_check(&current, config.consistency_factor)
+        };
 
-    fn create_test_result(name: &str) -> BenchResult {
-        BenchResult {
-            name: name.to_string(),
-            module: "test_module".to_string(),
-            samples: 10,
-            percentiles: Percentiles {
-                p50: Duration::from_millis(5),
-                p90: Duration::from_millis(10),
-                p99: Duration::from_millis(15),
-                mean: Duration::from_millis(8),
-            },
-            all_timings: vec![Duration::from_millis(5); 10],
-            cpu_samples: vec![],
-            ..Default::default()
-        }
+        // Phases agree: the +50% regression stands
+        let consistent = judge(200.0, 240.0);
+        assert!(consistent.is_regression);
+        assert!(consistent.comparison.unwrap().suspect.is_none());
+
+        // Sampling iterations took 2.3x as long as warmup ones: flagged, but
+        // the regression still gates CI
+        let suspect = judge(210.0, 480.0);
+        assert!(suspect.is_regression);
+        let inconsistency = suspect.comparison.unwrap().suspect.unwrap();
+        assert_eq!(
+            inconsistency.format(),
+            "warmup suggests 210ns/iter but sampling measured 480ns/iter — possible interference during sampling"
+        );
+
+        // Runs without warmup figures are never suspect
+        let mut current = history[0].to_bench_result();
+        current.warmup_ns_per_iter = None;
+        assert!(current.phase_inconsistency(1.5).is_none());
     }
 
     #[test]
-    fn test_baseline_data_conversion() {
-        let result = create_test_result("test_bench");
-        let machine_id = "0123456789abcdef".to_string(); // 16-char hex hash
+    fn test_improvement_starts_new_window() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        let mut history = history_from_means(&[100, 101, 100, 101]);
 
-        let baseline = BaselineData::from_bench_result(&result, machine_id.clone(), false);
+        // A 40% speedup is confirmed by the same evidence as a regression
+        let mut faster = history[0].to_bench_result();
+        faster.all_timings = vec![Duration::from_millis(60); 10];
+        faster.percentiles.mean = Duration::from_millis(60);
+        let judged = detect_regression(&faster, &history, &ComparisonConfig::default());
+        assert!(judged.is_improvement && !judged.is_regression);
+        let noise = detect_regression(
+            &history[1].to_bench_result(),
+            &history,
+            &ComparisonConfig::default(),
+        );
+        assert!(!noise.is_improvement);
 
-        assert_eq!(baseline.benchmark_name, "test_bench");
-        assert_eq!(baseline.module, "test_module");
-        assert_eq!(baseline.machine_id, machine_id);
-        assert_eq!(baseline.statistics.sample_count, 10);
-        assert_eq!(baseline.samples.len(), 10);
+        // Stored after the window, followed by a run at the new level
+        for (day, was_improvement) in [(5, true), (6, false)] {
+            let mut run = BaselineData::from_bench_result(&faster, "ci-machine".into(), false);
+            run.was_improvement = was_improvement;
+            run.timestamp = format!("2025-01-{:02}T00-00-00", day);
+            history.push(run);
+        }
+        let dir = manager.benchmark_dir("my_crate", "test_bench");
+        fs::create_dir_all(&dir).unwrap();
+        for run in &history {
+            fs::write(
+                dir.join(format!("{}.json", run.timestamp)),
+                serde_json::to_string(run).unwrap(),
+            )
+            .unwrap();
+        }
 
-        let converted = baseline.to_bench_result();
-        assert_eq!(converted.name, result.name);
-        assert_eq!(converted.module, result.module);
-        assert_eq!(converted.percentiles.p90, result.percentiles.p90);
+        let mixed = manager
+            .load_recent_baselines("my_crate", "test_bench", 10)
+            .unwrap();
+        assert_eq!(mixed.len(), 6);
+
+        let manager = manager.with_improvement_resets_window(true);
+        let window = manager
+            .load_recent_baselines("my_crate", "test_bench", 10)
+            .unwrap();
+        assert_eq!(window.len(), 2);
+        assert!(window[0].was_improvement && !window[1].was_improvement);
     }
 
     #[test]
-    fn test_save_and_load_baseline() {
-        let temp_dir = TempDir::new().unwrap();
-        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+    fn test_quality_failure_is_excluded_from_gating() {
+        let history = history_from_means(&[100, 101, 100, 101]);
+        let mut current = history[0].to_bench_result();
+        // Alternating 50ms and 250ms: +50% on average, at a CV near 100%
+        current.all_timings = (0..100)
+            .map(|i| Duration::from_millis(if i % 2 == 0 { 50 } else { 250 }))
+            .collect();
+        current.percentiles.mean = Duration::from_millis(150);
+        let config = ComparisonConfig::default();
 
-        let result = create_test_result("test_bench");
+        current.check_quality(Some(200.0));
+        let judged = detect_regression(&current, &history, &config).with_quality_check(&current);
+        assert!(judged.is_regression && judged.quality_failure.is_none());
 
-        // Save baseline
-        manager.save_baseline("my_crate", &result, false).unwrap();
+        current.check_quality(Some(10.0));
+        let failed = detect_regression(&current, &history, &config).with_quality_check(&current);
+        assert!(!failed.is_regression);
+        assert_eq!(failed.quality_failure, current.quality_failure);
+        assert!(failed.comparison.unwrap().quality_failure.is_some());
+    }
 
-        // Load baseline
-        let loaded = manager.load_baseline("my_crate", "test_bench").unwrap();
-        assert!(loaded.is_some());
+    #[test]
+    fn test_alloc_regression_is_flagged_separately() {
+        let allocs = |mean_allocs: f64| {
+            Some(crate::AllocStats {
+                mean_allocs,
+                mean_deallocs: mean_allocs,
+                mean_bytes: mean_allocs * 64.0,
+            })
+        };
+        let mut history = history_from_means(&[10, 10, 10]);
+        for run in &mut history {
+            run.alloc_stats = allocs(10.0);
+        }
+        let config = ComparisonConfig::default();
+        let compare = |current: &BenchResult, alloc_threshold: Option<f64>| {
+            detect_regression_with_cpd(
+                current,
+                &history,
+                config.threshold,
+                config.confidence_level,
+                config.cp_threshold,
+                config.hazard_rate,
+                config.metric,
+                config.window_scale,
+            )
+            .with_alloc_comparison(current, &history, alloc_threshold)
+        };
 
-        let baseline = loaded.unwrap();
-        assert_eq!(baseline.benchmark_name, "test_bench");
-        assert_eq!(baseline.module, "test_module");
-        assert!(baseline.percentiles.is_some());
-        assert_eq!(baseline.percentiles.unwrap().p90, Duration::from_millis(10));
+        // Same timings, 50% more allocations
+        let mut current = history[0].to_bench_result();
+        current.alloc_stats = allocs(15.0);
+
+        let flagged = compare(&current, Some(20.0));
+        assert!(!flagged.is_regression);
+        assert!(flagged.is_alloc_regression());
+        let alloc_comparison = flagged.comparison.unwrap().allocs.unwrap();
+        assert_eq!(alloc_comparison.baseline_allocs, 10.0);
+        assert_eq!(alloc_comparison.percentage_change(), Some(50.0));
+
+        assert!(!compare(&current, Some(60.0)).is_alloc_regression());
+        assert!(!compare(&current, None).is_alloc_regression());
+
+        // Without tracking on the current run, nothing is compared
+        current.alloc_stats = None;
+        assert!(compare(&current, Some(20.0))
+            .comparison
+            .unwrap()
+            .allocs
+            .is_none());
+    }
+
+    fn create_hashed_result(name: &str, body_hash: &str) -> BenchResult {
+        BenchResult {
+            body_hash: Some(body_hash.to_string()),
+            ..create_test_result(name)
+        }
     }
 
     #[test]
-    fn test_load_nonexistent_baseline() {
+    fn test_rename_carries_history_across_sessions() {
         let temp_dir = TempDir::new().unwrap();
+
+        // Session 1: benchmark runs under its original name
+        {
+            let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+            let result = create_hashed_result("bench_update_entities", "a1b2c3d4e5f60718");
+            manager.save_baseline("my_crate", &result, false).unwrap();
+        }
+
+        // Session 2: same body, new name
         let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        let renamed = create_hashed_result("bench_update_world", "a1b2c3d4e5f60718");
+        manager.save_baseline("my_crate", &renamed, false).unwrap();
 
-        let loaded = manager.load_baseline("my_crate", "nonexistent").unwrap();
-        assert!(loaded.is_none());
+        let history = manager
+            .load_recent_baselines("my_crate", "bench_update_world", 10)
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        // Former history comes first (chronological order)
+        assert_eq!(history[0].benchmark_name, "bench_update_entities");
+        assert_eq!(history[1].benchmark_name, "bench_update_world");
+
+        // Once the window is full of its own runs, the former name drops out
+        let own_only = manager
+            .load_recent_baselines("my_crate", "bench_update_world", 1)
+            .unwrap();
+        assert_eq!(own_only.len(), 1);
+        assert_eq!(own_only[0].benchmark_name, "bench_update_world");
     }
 
     #[test]
-    fn test_has_baseline() {
+    fn test_rename_requires_exact_body_hash_match() {
         let temp_dir = TempDir::new().unwrap();
         let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
 
-        let result = create_test_result("test_bench");
-
-        assert!(!manager.has_baseline("my_crate", "test_bench"));
+        let old = create_hashed_result("bench_update_entities", "a1b2c3d4e5f60718");
+        manager.save_baseline("my_crate", &old, false).unwrap();
 
-        manager.save_baseline("my_crate", &result, false).unwrap();
+        // Body changed along with the name: not treated as a rename
+        let changed = create_hashed_result("bench_update_world", "ffffffffffffffff");
+        manager.save_baseline("my_crate", &changed, false).unwrap();
+        let history = manager
+            .load_recent_baselines("my_crate", "bench_update_world", 10)
+            .unwrap();
+        assert_eq!(history.len(), 1);
 
-        assert!(manager.has_baseline("my_crate", "test_bench"));
+        // Two former benchmarks with the same body are ambiguous: no redirect
+        let twin = create_hashed_result("bench_update_twin", "a1b2c3d4e5f60718");
+        manager.save_baseline("my_crate", &twin, false).unwrap();
+        let newcomer = create_hashed_result("bench_update_all", "a1b2c3d4e5f60718");
+        manager.save_baseline("my_crate", &newcomer, false).unwrap();
+        let history = manager
+            .load_recent_baselines("my_crate", "bench_update_all", 10)
+            .unwrap();
+        assert_eq!(history.len(), 1);
     }
 
     #[test]
-    fn test_list_baselines() {
+    fn test_follow_renames_can_be_disabled() {
         let temp_dir = TempDir::new().unwrap();
-        let manager = BaselineManager::with_root_dir(temp_dir.path()).unwrap();
+        let manager = BaselineManager::with_root_dir(temp_dir.path())
+            .unwrap()
+            .with_follow_renames(false);
 
-        let result1 = create_test_result("bench1");
-        let result2 = create_test_result("bench2");
+        let old = create_hashed_result("bench_update_entities", "a1b2c3d4e5f60718");
+        manager.save_baseline("my_crate", &old, false).unwrap();
+        let renamed = create_hashed_result("bench_update_world", "a1b2c3d4e5f60718");
+        manager.save_baseline("my_crate", &renamed, false).unwrap();
 
-        manager.save_baseline("my_crate", &result1, false).unwrap();
-        manager.save_baseline("my_crate", &result2, false).unwrap();
+        let history = manager
+            .load_recent_baselines("my_crate", "bench_update_world", 10)
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(!temp_dir
+            .path()
+            .join(manager.machine_id())
+            .join("my_crate_bench_update_world")
+            .join(REDIRECT_FILE)
+            .exists());
+    }
 
-        let mut baselines = manager.list_baselines("my_crate").unwrap();
-        baselines.sort();
+    #[test]
+    fn test_cpu_time_metric_compares_cpu_time() {
+        let run = |wall_ms: u64, cpu_us: u64| {
+            let mut result = create_test_result("test_bench");
+            result.all_timings = vec![Duration::from_millis(wall_ms); 10];
+            result.percentiles.mean = Duration::from_millis(wall_ms);
+            result.cpu_timings = vec![Duration::from_micros(cpu_us); 10];
+            result.cpu_time_percentiles = Some(crate::calculate_percentiles(
+                &result.cpu_timings,
+                &crate::DEFAULT_PERCENTILES,
+            ));
+            result
+        };
 
-        assert_eq!(baselines, vec!["bench1", "bench2"]);
+        let historical: Vec<BaselineData> = [(10, 1_000), (11, 1_100), (10, 1_000), (11, 1_100)]
+            .iter()
+            .map(|&(wall, cpu)| BaselineData::from_bench_result(&run(wall, cpu), "m".into(), false))
+            .collect();
+
+        // Same wall time, but the benchmark now burns 5x the CPU
+        let current = run(10, 5_000);
+        let config = ComparisonConfig::default();
+        let detect = |metric| {
+            detect_regression_with_cpd(
+                &current,
+                &historical,
+                config.threshold,
+                config.confidence_level,
+                config.cp_threshold,
+                config.hazard_rate,
+                metric,
+                config.window_scale,
+            )
+        };
+
+        assert!(!detect(ComparisonMetric::WallTime).is_regression);
+        let cpu = detect(ComparisonMetric::CpuTime);
+        assert!(cpu.is_regression);
+        assert_eq!(
+            cpu.comparison.unwrap().current_mean,
+            Duration::from_micros(5_000)
+        );
     }
 
     #[test]
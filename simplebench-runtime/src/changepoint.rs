@@ -1,9 +1,132 @@
 //! Bayesian Online Change Point Detection
 //!
-//! Based on Adams & MacKay (2007) "Bayesian Online Changepoint Detection"
-//! Simplified implementation for detecting performance regressions in benchmark data.
+//! Based on Adams & MacKay (2007) "Bayesian Online Changepoint Detection":
+//! a posterior over the run length (observations since the last change point)
+//! is updated one observation at a time, with a Normal-Inverse-Gamma prior on
+//! each run's mean and variance and a constant hazard rate.
 
-use crate::statistics::mean;
+use crate::statistics::median;
+
+/// Prior pseudo-observations behind a new run's mean; small, so the mean of a
+/// run after a change point can land far from the window's
+const PRIOR_KAPPA: f64 = 0.01;
+
+/// Prior shape of a run's variance: the weakest prior with a finite predictive
+const PRIOR_ALPHA: f64 = 1.0;
+
+/// Noise scale floor as a fraction of the window's median; benchmark means
+/// are never reproducible between runs to better than about this
+const MIN_RELATIVE_NOISE: f64 = 0.01;
+
+/// Posterior over the mean and variance of one run
+#[derive(Debug, Clone, Copy)]
+struct NormalInverseGamma {
+    mu: f64,
+    kappa: f64,
+    alpha: f64,
+    beta: f64,
+}
+
+impl NormalInverseGamma {
+    /// Prior centered on the window's median, its noise scale estimated from
+    /// successive differences, which level shifts barely affect
+    fn prior(historical: &[f64]) -> Self {
+        Self::prior_with_floor(historical, 0.0)
+    }
+
+    /// [`Self::prior`] with the noise scale at least `noise_floor`
+    fn prior_with_floor(historical: &[f64], noise_floor: f64) -> Self {
+        let center = median(historical);
+        let differences: Vec<f64> = historical
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .collect();
+        // Median |x_i - x_(i-1)| is 0.6745 * sqrt(2) sigma for normal noise
+        let sigma = (median(&differences) / (0.6745 * std::f64::consts::SQRT_2))
+            .max(MIN_RELATIVE_NOISE * center.abs())
+            .max(noise_floor)
+            // Squared into `beta` below, which must stay nonzero
+            .max(f64::MIN_POSITIVE.sqrt());
+        Self {
+            mu: center,
+            kappa: PRIOR_KAPPA,
+            alpha: PRIOR_ALPHA,
+            beta: PRIOR_ALPHA * sigma * sigma,
+        }
+    }
+
+    /// Posterior after observing `x`
+    fn observe(&self, x: f64) -> Self {
+        let kappa = self.kappa + 1.0;
+        Self {
+            mu: (self.kappa * self.mu + x) / kappa,
+            kappa,
+            alpha: self.alpha + 0.5,
+            beta: self.beta + self.kappa * (x - self.mu).powi(2) / (2.0 * kappa),
+        }
+    }
+
+    /// Log density of `x` under the posterior predictive, a Student's t with
+    /// `2 * alpha` degrees of freedom
+    fn log_predictive(&self, x: f64) -> f64 {
+        let df = 2.0 * self.alpha;
+        let scale_sq = self.beta * (self.kappa + 1.0) / (self.alpha * self.kappa);
+        ln_gamma((df + 1.0) / 2.0)
+            - ln_gamma(df / 2.0)
+            - 0.5 * (df * std::f64::consts::PI * scale_sq).ln()
+            - (df + 1.0) / 2.0 * (1.0 + (x - self.mu).powi(2) / (df * scale_sq)).ln()
+    }
+}
+
+/// Log marginal likelihood of `values` as one run under `prior`: the product
+/// of each observation's predictive given the ones before it
+fn log_marginal(prior: &NormalInverseGamma, values: &[f64]) -> f64 {
+    let mut run = *prior;
+    let mut log_likelihood = 0.0;
+    for &x in values {
+        log_likelihood += run.log_predictive(x);
+        run = run.observe(x);
+    }
+    log_likelihood
+}
+
+/// Natural log of the gamma function for positive `x` (Lanczos, g = 7)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection formula
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| {
+            sum + c / (x + i as f64 + 1.0)
+        });
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// log(sum(exp(values))), without overflow; -inf for no values
+fn log_sum_exp(values: impl Iterator<Item = f64> + Clone) -> f64 {
+    let max = values.clone().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + values.map(|v| (v - max).exp()).sum::<f64>().ln()
+}
 
 /// Bayesian Online Change Point Detection core algorithm
 pub struct BayesianCPD {
@@ -22,6 +145,11 @@ impl BayesianCPD {
 
     /// Update with a new observation and return change point probability
     ///
+    /// Runs the run-length recursion over `historical` and then `value`, and
+    /// returns the posterior probability that a new run starts at `value`:
+    /// that it comes from the prior predictive of a fresh run rather than
+    /// from the predictive of any run in progress.
+    ///
     /// # Arguments
     /// * `value` - New observation (e.g., current benchmark mean)
     /// * `historical` - Historical observations (e.g., previous benchmark means)
@@ -29,72 +157,39 @@ impl BayesianCPD {
     /// # Returns
     /// Probability that a change point occurred (0.0 to 1.0)
     pub fn update(&mut self, value: f64, historical: &[f64]) -> f64 {
-        if historical.is_empty() {
+        let Some((&first, rest)) = historical.split_first() else {
             return 0.0;
-        }
-
-        // Calculate predictive probability using all historical data
-        let likelihood = self.student_t_likelihood(value, historical);
-
-        // For simplified implementation: compare new value against historical distribution
-        // High likelihood = fits the pattern (low change probability)
-        // Low likelihood = doesn't fit (high change probability)
-
-        // Convert likelihood to change probability (invert the likelihood)
-        // If value is very different from historical mean, likelihood will be low
-        let unlikelihood = 1.0 - likelihood.min(1.0);
-
-        // Combine data evidence with prior (hazard rate)
-        // Bayesian update: P(CP|data) ∝ P(data|CP) * P(CP)
-        // Where P(CP) = hazard_rate
-        // And P(data|CP) = 1 - likelihood (assuming uniform prior under change)
-
-        // Weight the evidence by the hazard rate as a prior
-        let prior_weight = self.hazard_rate;
-        let evidence_weight = unlikelihood;
-
-        // Combine: higher hazard rate gives more weight to the prior expectation of change
-        let change_prob =
-            (evidence_weight * 0.7) + (prior_weight * 0.3) + (evidence_weight * prior_weight * 0.5);
-
-        change_prob.min(1.0)
-    }
+        };
 
-    /// Calculate Student's t-distribution likelihood
-    ///
-    /// This is used as the predictive distribution for new observations.
-    /// Student's t is more robust to outliers than normal distribution.
-    fn student_t_likelihood(&self, value: f64, historical: &[f64]) -> f64 {
-        if historical.is_empty() {
-            return 0.5;
-        }
+        let hazard = self
+            .hazard_rate
+            .clamp(f64::MIN_POSITIVE, 1.0 - f64::EPSILON);
+        let prior = NormalInverseGamma::prior(historical);
 
-        let n = historical.len() as f64;
-        let hist_mean = mean(historical);
-        let variance = if n > 1.0 {
-            historical
-                .iter()
-                .map(|&x| (x - hist_mean).powi(2))
-                .sum::<f64>()
-                / n
-        } else {
-            1.0 // Default variance for single observation
-        };
+        // (log posterior, parameters) of each run length; the first
+        // observation starts the first run
+        let mut runs = vec![(0.0, prior.observe(first))];
+        let mut change_probability = 0.0;
+        for &x in rest.iter().chain(std::iter::once(&value)) {
+            let log_change = hazard.ln() + prior.log_predictive(x);
+            let mut next = Vec::with_capacity(runs.len() + 1);
+            next.push((log_change, prior.observe(x)));
+            next.extend(runs.iter().map(|(log_p, run)| {
+                (
+                    log_p + (1.0 - hazard).ln() + run.log_predictive(x),
+                    run.observe(x),
+                )
+            }));
 
-        if variance < 1e-10 {
-            // Very low variance, use normal approximation
-            let stddev = variance.sqrt().max(1e-5);
-            let z = ((value - hist_mean) / stddev).abs();
-            return (-0.5 * z * z).exp();
+            let log_evidence = log_sum_exp(next.iter().map(|(log_p, _)| *log_p));
+            for (log_p, _) in &mut next {
+                *log_p -= log_evidence;
+            }
+            change_probability = next[0].0.exp();
+            runs = next;
         }
 
-        // Student's t-distribution with n-1 degrees of freedom
-        let df = (n - 1.0).max(1.0);
-        let t = (value - hist_mean) / variance.sqrt();
-        let t_squared = t * t;
-
-        // Simplified Student's t PDF (good enough for our purposes)
-        (1.0 + t_squared / df).powf(-(df + 1.0) / 2.0)
+        change_probability
     }
 }
 
@@ -119,10 +214,111 @@ pub fn bayesian_change_point_probability(
     cpd.update(new_value, historical)
 }
 
+/// Fewest observations on either side of an offline change point
+const MIN_SEGMENT_LEN: usize = 2;
+
+/// A shift in the level of a series, located after the fact
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangePoint {
+    /// Index of the first observation after the shift
+    pub index: usize,
+    /// Posterior probability that the segment the search split shifts here
+    /// rather than anywhere else or nowhere
+    pub probability: f64,
+    /// Mean of the segment's observations before the shift
+    pub mean_before: f64,
+    /// Mean of the segment's observations from the shift on
+    pub mean_after: f64,
+}
+
+/// Locate the level shifts in `values` (e.g. per-run means, oldest first)
+/// by binary segmentation.
+///
+/// Each segment is split at the index with the highest posterior under a
+/// single-change model: no change, or one change at any index leaving
+/// [`MIN_SEGMENT_LEN`] observations on both sides, each side a run with its
+/// own mean and variance, the prior odds set by `hazard_rate`. A split with a
+/// posterior of at least `threshold` is kept and both halves are searched
+/// again. `noise_floor` bounds the noise scale from below, e.g. by the runs'
+/// standard errors, so runs that agree to within their own noise aren't split.
+///
+/// Returned in order of index.
+pub fn find_change_points(
+    values: &[f64],
+    noise_floor: f64,
+    hazard_rate: f64,
+    threshold: f64,
+) -> Vec<ChangePoint> {
+    let hazard = hazard_rate.clamp(f64::MIN_POSITIVE, 1.0 - f64::EPSILON);
+    let mut change_points = Vec::new();
+    let mut pending = vec![(0, values.len())];
+    while let Some((start, end)) = pending.pop() {
+        let Some(found) = split_segment(&values[start..end], noise_floor, hazard) else {
+            continue;
+        };
+        if found.probability < threshold {
+            continue;
+        }
+        let index = start + found.index;
+        pending.push((start, index));
+        pending.push((index, end));
+        change_points.push(ChangePoint { index, ..found });
+    }
+    change_points.sort_by_key(|c| c.index);
+    change_points
+}
+
+/// The most probable single change point of `segment`, if it's long enough to have one
+fn split_segment(segment: &[f64], noise_floor: f64, hazard: f64) -> Option<ChangePoint> {
+    let len = segment.len();
+    if len < 2 * MIN_SEGMENT_LEN {
+        return None;
+    }
+    let prior = NormalInverseGamma::prior_with_floor(segment, noise_floor);
+    let stays = (1.0 - hazard).ln();
+
+    let log_no_change = (len - 1) as f64 * stays + log_marginal(&prior, segment);
+    let log_changes: Vec<(usize, f64)> = (MIN_SEGMENT_LEN..=len - MIN_SEGMENT_LEN)
+        .map(|index| {
+            let (before, after) = segment.split_at(index);
+            let log_p = hazard.ln()
+                + (len - 2) as f64 * stays
+                + log_marginal(&prior, before)
+                + log_marginal(&prior, after);
+            (index, log_p)
+        })
+        .collect();
+
+    let log_evidence = log_sum_exp(
+        log_changes
+            .iter()
+            .map(|(_, log_p)| *log_p)
+            .chain(std::iter::once(log_no_change)),
+    );
+    let &(index, log_p) = log_changes.iter().max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let (before, after) = segment.split_at(index);
+    Some(ChangePoint {
+        index,
+        probability: (log_p - log_evidence).exp(),
+        mean_before: crate::statistics::mean(before),
+        mean_after: crate::statistics::mean(after),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_all_zero_history_gives_finite_probability() {
+        // A window of zero timings has no spread at all
+        let historical = vec![0.0; 8];
+        for new_value in [0.0, 5.0] {
+            let prob = bayesian_change_point_probability(new_value, &historical, 0.1);
+            assert!(prob.is_finite(), "got {} for {}", prob, new_value);
+        }
+    }
+
     #[test]
     fn test_stable_data_low_change_probability() {
         // Stable data should have low change probability
@@ -176,6 +372,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_change_probability_is_a_posterior() {
+        let historical = vec![1.0, 1.01, 0.99, 1.0, 1.02, 0.98, 1.0, 1.01];
+
+        // A value that fits lowers the prior hazard; a clear jump is certain
+        assert!(bayesian_change_point_probability(1.0, &historical, 0.1) < 0.1);
+        assert!(bayesian_change_point_probability(2.0, &historical, 0.1) > 0.99);
+
+        // Scale-free: nanosecond means give the same answer
+        let scaled: Vec<f64> = historical.iter().map(|x| x * 1e6).collect();
+        let a = bayesian_change_point_probability(1.05, &historical, 0.1);
+        let b = bayesian_change_point_probability(1.05e6, &scaled, 0.1);
+        assert!((a - b).abs() < 1e-9, "{} vs {}", a, b);
+
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-12);
+        assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-12);
+    }
+
     #[test]
     fn test_higher_hazard_rate_increases_change_probability() {
         let historical = vec![1.0, 1.0, 1.0, 1.0, 1.0];
@@ -189,4 +403,49 @@ mod tests {
             "Higher hazard rate ({}) should produce higher change probability than lower hazard rate ({})",
             prob_high_hazard, prob_low_hazard);
     }
+
+    #[test]
+    fn test_find_change_points_locates_a_shift() {
+        // Ten runs around 100, then six around 120
+        let mut values = vec![
+            100.0, 101.0, 99.5, 100.5, 100.0, 99.0, 101.0, 100.0, 100.5, 99.5,
+        ];
+        values.extend([120.0, 121.0, 119.5, 120.5, 120.0, 119.0]);
+
+        let found = find_change_points(&values, 0.0, 0.1, 0.5);
+        assert_eq!(found.len(), 1, "{:?}", found);
+        assert_eq!(found[0].index, 10);
+        assert!(found[0].probability > 0.9, "{:?}", found[0]);
+        assert!((found[0].mean_before - 100.1).abs() < 1e-9);
+        assert!((found[0].mean_after - 120.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_find_change_points_finds_several_shifts() {
+        let values = [
+            10.0, 10.1, 9.9, 10.0, 10.1, 15.0, 15.1, 14.9, 15.0, 15.1, 10.0, 9.9, 10.1, 10.0,
+        ];
+
+        let indices: Vec<usize> = find_change_points(&values, 0.0, 0.1, 0.5)
+            .iter()
+            .map(|c| c.index)
+            .collect();
+        assert_eq!(indices, vec![5, 10]);
+    }
+
+    #[test]
+    fn test_find_change_points_ignores_noise() {
+        let stable = [
+            1.0, 1.01, 0.99, 1.0, 1.02, 0.98, 1.0, 1.01, 0.99, 1.0, 1.02, 0.98,
+        ];
+        assert!(find_change_points(&stable, 0.0, 0.1, 0.5).is_empty());
+
+        // A step smaller than the runs' own noise isn't a change
+        let step = [1.0, 1.0, 1.0, 1.0, 1.0, 1.05, 1.05, 1.05, 1.05, 1.05];
+        assert!(!find_change_points(&step, 0.0, 0.1, 0.5).is_empty());
+        assert!(find_change_points(&step, 0.1, 0.1, 0.5).is_empty());
+
+        assert!(find_change_points(&[1.0, 2.0, 3.0], 0.0, 0.1, 0.5).is_empty());
+        assert!(find_change_points(&[], 0.0, 0.1, 0.5).is_empty());
+    }
 }
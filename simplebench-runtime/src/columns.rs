@@ -0,0 +1,241 @@
+//! Selectable fields of the per-benchmark output.
+//!
+//! `[output] columns = ["mean", "p99", "cv", "delta"]` (or `--columns`) picks
+//! which fields the terminal result and comparison lines show, and the same
+//! list lays out the markdown report table. Each [`Column`] yields a
+//! [`ColumnValue`]; renderers label and format it their own way.
+//! `percentiles` stands for whichever set `[measurement] percentiles` records.
+
+use crate::{BenchResult, Comparison};
+use std::time::Duration;
+
+/// A field of a benchmark result or of its comparison against the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Mean,
+    /// Robust coefficient of variation (MAD / median), which a few preempted
+    /// samples don't inflate
+    Cv,
+    Throughput,
+    /// Every percentile the run recorded (`[measurement] percentiles`)
+    Percentiles,
+    P50,
+    P90,
+    P99,
+    Min,
+    Max,
+    Samples,
+    Allocs,
+    Warmup,
+    /// Percentage change of the mean against the baseline
+    Delta,
+    Z,
+    Cp,
+    Mde,
+    /// Effect size of the change in pooled standard deviations (Cohen's d)
+    EffectSize,
+}
+
+/// Value of one column for one benchmark
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValue {
+    /// A timing, formatted by the renderer
+    Time(Duration),
+    /// Already formatted text (percentages, counts, rates)
+    Text(String),
+    /// Labelled timings, one per recorded percentile
+    Times(Vec<(String, Duration)>),
+}
+
+impl Column {
+    /// Every column, in the order they are listed in errors and docs
+    pub const ALL: [Column; 17] = [
+        Column::Mean,
+        Column::Cv,
+        Column::Throughput,
+        Column::Percentiles,
+        Column::P50,
+        Column::P90,
+        Column::P99,
+        Column::Min,
+        Column::Max,
+        Column::Samples,
+        Column::Allocs,
+        Column::Warmup,
+        Column::Delta,
+        Column::Z,
+        Column::Cp,
+        Column::Mde,
+        Column::EffectSize,
+    ];
+
+    /// Terminal layout when no columns are configured
+    pub const TERMINAL_DEFAULT: &'static [Column] = &[
+        Column::Mean,
+        Column::Cv,
+        Column::Throughput,
+        Column::Percentiles,
+        Column::Allocs,
+        Column::Warmup,
+        Column::Delta,
+        Column::Z,
+        Column::Cp,
+        Column::Mde,
+        Column::EffectSize,
+    ];
+
+    /// Report table layout when no columns are configured
+    pub const REPORT_DEFAULT: &'static [Column] = &[Column::Mean, Column::P99, Column::Delta];
+
+    /// Name used in `[output] columns` and `--columns`
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Mean => "mean",
+            Column::Cv => "cv",
+            Column::Throughput => "throughput",
+            Column::Percentiles => "percentiles",
+            Column::P50 => "p50",
+            Column::P90 => "p90",
+            Column::P99 => "p99",
+            Column::Min => "min",
+            Column::Max => "max",
+            Column::Samples => "samples",
+            Column::Allocs => "allocs",
+            Column::Warmup => "warmup",
+            Column::Delta => "delta",
+            Column::Z => "z",
+            Column::Cp => "cp",
+            Column::Mde => "mde",
+            Column::EffectSize => "d",
+        }
+    }
+
+    /// Table header of the column
+    pub fn header(self) -> &'static str {
+        match self {
+            Column::Mean => "Mean",
+            Column::Cv => "CV",
+            Column::Throughput => "Throughput",
+            Column::Percentiles => "Percentiles",
+            Column::P50 => "p50",
+            Column::P90 => "p90",
+            Column::P99 => "p99",
+            Column::Min => "Min",
+            Column::Max => "Max",
+            Column::Samples => "Samples",
+            Column::Allocs => "Allocs",
+            Column::Warmup => "Warmup",
+            Column::Delta => "Change",
+            Column::Z => "z",
+            Column::Cp => "cp",
+            Column::Mde => "MDE",
+            Column::EffectSize => "d",
+        }
+    }
+
+    /// Parse a column name, listing the valid names if it is unknown
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|c| c.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                format!(
+                    "unknown column '{}' (valid: {})",
+                    name,
+                    Self::ALL.map(Column::name).join(", ")
+                )
+            })
+    }
+
+    /// Parse a list of column names, failing on the first unknown one
+    pub fn parse_list<S: AsRef<str>>(names: &[S]) -> Result<Vec<Self>, String> {
+        names
+            .iter()
+            .map(|name| Self::parse(name.as_ref()))
+            .collect()
+    }
+
+    /// Value of the column for `result`, or None if it wasn't recorded
+    ///
+    /// Comparison columns ([`Column::Delta`], [`Column::Z`], [`Column::Cp`],
+    /// [`Column::Mde`], [`Column::EffectSize`]) read `comparison` and are None
+    /// without one.
+    pub fn value(
+        self,
+        result: &BenchResult,
+        comparison: Option<&Comparison>,
+    ) -> Option<ColumnValue> {
+        let time = |d: Duration| Some(ColumnValue::Time(d));
+        let text = |s: String| Some(ColumnValue::Text(s));
+        match self {
+            Column::Mean => time(result.percentiles.mean),
+            Column::Percentiles => (!result.percentiles.values.is_empty()).then(|| {
+                ColumnValue::Times(
+                    result
+                        .percentiles
+                        .values
+                        .iter()
+                        .map(|&(p, timing)| (crate::percentile_label(p), timing))
+                        .collect(),
+                )
+            }),
+            Column::P50 => time(result.percentiles.get(50.0)?),
+            Column::P90 => time(result.percentiles.get(90.0)?),
+            Column::P99 => time(result.percentiles.get(99.0)?),
+            Column::Min => time(crate::duration_from_nanos(result.statistics()?.min)),
+            Column::Max => time(crate::duration_from_nanos(result.statistics()?.max)),
+            Column::Cv => text(format!("{:.1}%", result.statistics()?.robust_cv * 100.0)),
+            Column::Throughput => text(result.throughput?.format_rate(result.percentiles.mean)?),
+            Column::Samples => text(result.samples.to_string()),
+            Column::Allocs => text(result.alloc_stats?.format()),
+            Column::Warmup => {
+                let mode = if result.warmup_mode == crate::WarmupMode::Adaptive {
+                    ", until frequency settled"
+                } else {
+                    ""
+                };
+                text(format!(
+                    "{}ms ({} iterations{})",
+                    result.warmup_ms?, result.warmup_iterations?, mode
+                ))
+            }
+            Column::Delta | Column::Z | Column::Cp | Column::Mde | Column::EffectSize => {
+                self.comparison_value(comparison?)
+            }
+        }
+    }
+
+    /// Value of a comparison column, or None for result columns
+    pub fn comparison_value(self, comparison: &Comparison) -> Option<ColumnValue> {
+        let text = match self {
+            Column::Delta => comparison.format_change(),
+            Column::Z => format!("{:.2}", comparison.z_score?),
+            Column::Cp => format!("{:.0}%", comparison.change_probability? * 100.0),
+            Column::Mde => format!("{:.1}%", comparison.min_detectable_effect?),
+            Column::EffectSize => format!("{:.2}", comparison.effect_size?),
+            _ => return None,
+        };
+        Some(ColumnValue::Text(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_columns_lists_valid_names() {
+        assert_eq!(
+            Column::parse_list(&["mean", " P99", "cv", "delta"]).unwrap(),
+            vec![Column::Mean, Column::P99, Column::Cv, Column::Delta]
+        );
+
+        let err = Column::parse_list(&["mean", "median"]).unwrap_err();
+        assert_eq!(
+            err,
+            "unknown column 'median' (valid: mean, cv, throughput, percentiles, p50, p90, p99, \
+             min, max, samples, allocs, warmup, delta, z, cp, mde, d)"
+        );
+    }
+}
@@ -1,6 +1,232 @@
+use crate::columns::Column;
+use crate::output::TimeFormat;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Suffixes accepted by [`parse_duration`], smallest unit first
+const DURATION_SUFFIXES: &[(&str, u64)] = &[
+    ("ns", 1),
+    ("us", 1_000),
+    ("µs", 1_000),
+    ("ms", 1_000_000),
+    ("s", 1_000_000_000),
+    ("m", 60 * 1_000_000_000),
+    ("h", 3_600 * 1_000_000_000),
+];
+
+/// Parse a human-friendly duration such as "500ms", "2m", "1.5s" or "1h30m"
+///
+/// Each component is a number followed by one of `ns`, `us`/`µs`, `ms`, `s`, `m`
+/// or `h`; components can be chained without separators. A bare number is
+/// interpreted as seconds, for backwards compatibility with seconds-only options.
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let valid = "valid suffixes: ns, us, ms, s, m, h (e.g. \"500ms\", \"2m\", \"1h30m\")";
+    let trimmed = input.trim();
+
+    if trimmed.is_empty() {
+        return Err(format!("invalid duration \"{}\": empty; {}", input, valid));
+    }
+
+    // Bare number: seconds
+    if let Ok(secs) = trimmed.parse::<f64>() {
+        return if secs.is_finite() && secs >= 0.0 {
+            Ok(Duration::from_secs_f64(secs))
+        } else {
+            Err(format!(
+                "invalid duration \"{}\": must be a non-negative number; {}",
+                input, valid
+            ))
+        };
+    }
+
+    let mut total_nanos: u128 = 0;
+    let mut rest = trimmed;
+
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        if number_len == 0 {
+            return Err(format!(
+                "invalid duration \"{}\": expected a number before \"{}\"; {}",
+                input, rest, valid
+            ));
+        }
+        let (number, after_number) = rest.split_at(number_len);
+        let value: f64 = number.parse().map_err(|_| {
+            format!(
+                "invalid duration \"{}\": \"{}\" is not a number; {}",
+                input, number, valid
+            )
+        })?;
+
+        let suffix_len = after_number
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(after_number.len());
+        let (suffix, remaining) = after_number.split_at(suffix_len);
+        if suffix.is_empty() {
+            return Err(format!(
+                "invalid duration \"{}\": missing unit after \"{}\"; {}",
+                input, number, valid
+            ));
+        }
+
+        let unit_nanos = DURATION_SUFFIXES
+            .iter()
+            .find(|(name, _)| *name == suffix)
+            .map(|(_, nanos)| *nanos)
+            .ok_or_else(|| {
+                format!(
+                    "invalid duration \"{}\": unknown unit \"{}\"; {}",
+                    input, suffix, valid
+                )
+            })?;
+
+        total_nanos += (value * unit_nanos as f64).round() as u128;
+        rest = remaining;
+    }
+
+    u64::try_from(total_nanos)
+        .map(Duration::from_nanos)
+        .map_err(|_| format!("invalid duration \"{}\": too large", input))
+}
+
+/// Suffixes accepted by [`parse_size`], largest unit first
+const SIZE_SUFFIXES: &[(&str, u64)] = &[
+    ("TB", 1 << 40),
+    ("GB", 1 << 30),
+    ("MB", 1 << 20),
+    ("KB", 1 << 10),
+    ("B", 1),
+];
+
+/// Parse a human-friendly byte size such as "8GB", "512MiB", "1.5G" or "4096"
+///
+/// Units are binary (1KB = 1024 bytes) and case-insensitive; `K`, `KiB` and
+/// `KB` all mean the same. A bare number is a count of bytes.
+pub fn parse_size(input: &str) -> Result<u64, String> {
+    let valid = "valid suffixes: B, KB, MB, GB, TB (e.g. \"8GB\", \"512MiB\")";
+    let trimmed = input.trim();
+    let number_len = trimmed
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(number_len);
+    if number.is_empty() {
+        return Err(format!(
+            "invalid size \"{}\": expected a number; {}",
+            input, valid
+        ));
+    }
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid size \"{}\": \"{}\" is not a number", input, number))?;
+
+    // "MiB" and "M" are spelled-out and short forms of "MB"
+    let unit = unit.trim().to_ascii_uppercase().replace("IB", "B");
+    let unit = if unit.len() == 1 && unit != "B" {
+        format!("{}B", unit)
+    } else {
+        unit
+    };
+    let scale = if unit.is_empty() {
+        1
+    } else {
+        SIZE_SUFFIXES
+            .iter()
+            .find(|(suffix, _)| *suffix == unit)
+            .map(|&(_, scale)| scale)
+            .ok_or_else(|| format!("invalid size \"{}\": unknown unit; {}", input, valid))?
+    };
+
+    let bytes = value * scale as f64;
+    if bytes >= u64::MAX as f64 {
+        return Err(format!("invalid size \"{}\": too large", input));
+    }
+    Ok(bytes.round() as u64)
+}
+
+/// Format a byte count with the largest unit of [`parse_size`] it reaches and
+/// at most one decimal ("8GB", "3.1GB", "512B")
+pub fn format_size(bytes: u64) -> String {
+    let &(suffix, scale) = SIZE_SUFFIXES
+        .iter()
+        .find(|&&(_, scale)| bytes >= scale)
+        .unwrap_or(&("B", 1));
+    let value = format!("{:.1}", bytes as f64 / scale as f64);
+    format!("{}{}", value.trim_end_matches(".0"), suffix)
+}
+
+/// Format a duration in the compact form accepted by [`parse_duration`] ("1h30m", "500ms")
+pub fn format_duration_compact(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+
+    // Whole seconds and above: compose h/m/s components
+    if nanos % 1_000_000_000 == 0 {
+        let secs = duration.as_secs();
+        let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+        let mut out = String::new();
+        if hours > 0 {
+            out.push_str(&format!("{}h", hours));
+        }
+        if minutes > 0 {
+            out.push_str(&format!("{}m", minutes));
+        }
+        if seconds > 0 {
+            out.push_str(&format!("{}s", seconds));
+        }
+        return out;
+    }
+
+    if nanos % 1_000_000 == 0 {
+        format!("{}ms", nanos / 1_000_000)
+    } else if nanos % 1_000 == 0 {
+        format!("{}us", nanos / 1_000)
+    } else {
+        format!("{}ns", nanos)
+    }
+}
+
+/// Serde support for durations in config files
+///
+/// Deserializes either a number (seconds) or a string parsed by [`parse_duration`];
+/// serializes to the compact string form.
+pub mod duration_format {
+    use super::{format_duration_compact, parse_duration};
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum DurationRepr {
+        Secs(u64),
+        FractionalSecs(f64),
+        Text(String),
+    }
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_duration_compact(*duration))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        match DurationRepr::deserialize(deserializer)? {
+            DurationRepr::Secs(secs) => Ok(Duration::from_secs(secs)),
+            DurationRepr::FractionalSecs(secs) if secs.is_finite() && secs >= 0.0 => {
+                Ok(Duration::from_secs_f64(secs))
+            }
+            DurationRepr::FractionalSecs(secs) => Err(serde::de::Error::custom(format!(
+                "invalid duration {}: must be a non-negative number of seconds",
+                secs
+            ))),
+            DurationRepr::Text(text) => parse_duration(&text).map_err(serde::de::Error::custom),
+        }
+    }
+}
 
 /// Configuration for benchmark measurement parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,23 +235,308 @@ pub struct MeasurementConfig {
     #[serde(default = "default_samples")]
     pub samples: usize,
 
-    /// Warmup duration in seconds (default: 3 seconds, matching Criterion)
-    #[serde(default = "default_warmup_duration")]
-    pub warmup_duration_secs: u64,
+    /// Warmup duration (default: 3 seconds, matching Criterion)
+    ///
+    /// Accepts a number of seconds or a duration string like "500ms"; the old
+    /// `warmup_duration_secs` key is still read.
+    #[serde(
+        default = "default_warmup_duration",
+        alias = "warmup_duration_secs",
+        with = "duration_format"
+    )]
+    pub warmup_duration: Duration,
+
+    /// How warmup decides it's done (default: fixed `warmup_duration`)
+    #[serde(default)]
+    pub warmup_mode: WarmupMode,
+
+    /// Cap on adaptive warmup, in seconds (default: 10)
+    #[serde(default = "default_max_warmup_secs")]
+    pub max_warmup_secs: f64,
+
+    /// Consecutive 100ms frequency readings within 2% of the peak that end
+    /// adaptive warmup (default: 5)
+    #[serde(default = "default_warmup_stable_readings")]
+    pub warmup_stable_readings: usize,
+
+    /// Busy-wait this many microseconds after dropping per-sample data
+    /// (`setup_each`), so the allocator finishes freeing it before the next
+    /// sample's setup and timing begin (default: 0 = disabled)
+    #[serde(default)]
+    pub settle_after_drop_us: u64,
+
+    /// Extra samples taken first and dropped before statistics, to absorb the
+    /// slow first iterations after warmup (default: 0)
+    #[serde(default)]
+    pub discard_samples: usize,
+
+    /// Stop sampling early once timings stabilize instead of always taking
+    /// `samples` (default: false)
+    #[serde(default)]
+    pub adaptive: bool,
+
+    /// Adaptive mode stops once the coefficient of variation (in percent) of the
+    /// last `min_samples` timings drops below this (default: 2.0)
+    #[serde(default = "default_target_cv")]
+    pub target_cv: f64,
+
+    /// Fewest samples adaptive mode takes, and the window its CV is computed over
+    /// (default: 100)
+    #[serde(default = "default_min_samples")]
+    pub min_samples: usize,
+
+    /// Most samples adaptive mode takes if timings never stabilize (default: 10000)
+    #[serde(default = "default_max_samples")]
+    pub max_samples: usize,
+
+    /// Total time budget for warmup plus measurement, in seconds. Sampling stops
+    /// once it's exceeded, provided a minimum number of samples was collected
+    /// (default: unlimited)
+    #[serde(default)]
+    pub max_time_secs: Option<f64>,
+
+    /// Clock(s) read around each call (default: both)
+    #[serde(default)]
+    pub clock: ClockMode,
+
+    /// Longest wait between benchmarks for the CPU to cool below
+    /// `cooldown_max_temp_c`, in seconds (default: 0 = disabled)
+    #[serde(default)]
+    pub cooldown_secs: f64,
+
+    /// Temperature in °C below which the next benchmark starts without waiting
+    /// (default: 60)
+    #[serde(default = "default_cooldown_max_temp_c")]
+    pub cooldown_max_temp_c: f64,
+
+    /// Stop sampling once the running mean is certainly a regression, or
+    /// certainly not one, against the baseline window (default: false)
+    #[serde(default)]
+    pub early_verdict: bool,
+
+    /// Samples between early-verdict checks (default: 100)
+    #[serde(default = "default_early_verdict_interval")]
+    pub early_verdict_interval: usize,
+
+    /// Confidence the running mean must reach before sampling stops early
+    /// (default: 0.999)
+    #[serde(default = "default_early_verdict_confidence")]
+    pub early_verdict_confidence: f64,
+
+    /// A sample slower than this multiple of the running median is recorded
+    /// as pathological (default: 1000; 0 disables the check)
+    #[serde(default = "default_watchdog_factor")]
+    pub watchdog_factor: f64,
+
+    /// A sample slower than this many milliseconds is recorded as
+    /// pathological, whatever the median (default: no ceiling)
+    #[serde(default)]
+    pub watchdog_max_sample_ms: Option<f64>,
+
+    /// Stop sampling at the first pathological sample, keeping the samples
+    /// collected so far, and mark the run truncated (default: false)
+    #[serde(default)]
+    pub watchdog_stop: bool,
+
+    /// Keep every timing in results and baselines (default: true). When false,
+    /// statistics are computed while sampling and only a uniform reservoir of
+    /// 10,000 samples is kept, so a million samples don't need a million
+    /// stored timings; percentiles are then P² estimates
+    #[serde(default = "default_store_raw_samples")]
+    pub store_raw_samples: bool,
+
+    /// Most samples a benchmark may request (default: 1,000,000); larger
+    /// `samples` (or adaptive `max_samples`) are rejected before warmup
+    #[serde(default = "default_sample_limit")]
+    pub sample_limit: usize,
+
+    /// Percentiles (0-100) each run records and prints, e.g.
+    /// `[50, 90, 95, 99, 99.9]` (default: `[50, 90, 99]`)
+    #[serde(default = "default_percentiles")]
+    pub percentiles: Vec<f64>,
+
+    /// Fail a benchmark's quality check when its robust CV, in percent,
+    /// exceeds this; its verdict becomes SUSPECT (default: no bar). The
+    /// `max_cv_pct` attribute of `#[bench]` overrides it per benchmark
+    #[serde(default)]
+    pub max_cv_pct: Option<f64>,
+
+    /// Run benchmarks whose preconditions (such as `min_free_memory`) aren't
+    /// met instead of skipping them
+    #[serde(default)]
+    pub ignore_preconditions: bool,
+
+    /// Fewest CPU snapshots a run needs before frequency and temperature
+    /// warnings are raised for it (default: 10)
+    #[serde(default = "default_min_cpu_snapshots")]
+    pub min_cpu_snapshots: usize,
+
+    /// Fraction of the measurement the CPU snapshots must span before those
+    /// warnings are raised (default: 0.5)
+    #[serde(default = "default_min_cpu_coverage")]
+    pub min_cpu_coverage: f64,
+}
+
+impl MeasurementConfig {
+    /// Coverage CPU snapshots need for [`crate::CpuAnalysis`] to warn
+    pub fn cpu_coverage_rule(&self) -> crate::CoverageRule {
+        crate::CoverageRule {
+            min_snapshots: self.min_cpu_snapshots,
+            min_span_fraction: self.min_cpu_coverage,
+        }
+    }
+}
+
+/// Which clocks [`crate::measurement`] reads around each timed call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClockMode {
+    /// Wall-clock time only
+    Wall,
+    /// Thread CPU time only (`CLOCK_THREAD_CPUTIME_ID`), excluding time the
+    /// thread is preempted or blocked; falls back to wall time when unavailable
+    Cpu,
+    /// Wall-clock timings, plus thread CPU time of each sample as a second series
+    #[default]
+    Both,
+}
+
+impl ClockMode {
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::str::FromStr for ClockMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wall" => Ok(Self::Wall),
+            "cpu" => Ok(Self::Cpu),
+            "both" => Ok(Self::Both),
+            other => Err(format!(
+                "unknown clock '{}' (expected 'wall', 'cpu' or 'both')",
+                other
+            )),
+        }
+    }
+}
+
+/// How long [`crate::measurement`] warms up before taking samples
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmupMode {
+    /// Run for `warmup_duration`
+    #[default]
+    Fixed,
+    /// Run until the CPU frequency has settled near its peak, up to
+    /// `max_warmup_secs`; falls back to `warmup_duration` when the frequency
+    /// can't be read
+    Adaptive,
+}
+
+impl WarmupMode {
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::str::FromStr for WarmupMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fixed" => Ok(Self::Fixed),
+            "adaptive" => Ok(Self::Adaptive),
+            other => Err(format!(
+                "unknown warmup mode '{}' (expected 'fixed' or 'adaptive')",
+                other
+            )),
+        }
+    }
 }
 
 fn default_samples() -> usize {
     1000
 }
-fn default_warmup_duration() -> u64 {
-    3 // 3 seconds, matching Criterion's default
+fn default_target_cv() -> f64 {
+    2.0
+}
+fn default_min_samples() -> usize {
+    100
+}
+fn default_max_samples() -> usize {
+    10_000
+}
+fn default_max_warmup_secs() -> f64 {
+    10.0
+}
+fn default_warmup_stable_readings() -> usize {
+    5
+}
+fn default_cooldown_max_temp_c() -> f64 {
+    60.0
+}
+fn default_early_verdict_interval() -> usize {
+    100
+}
+fn default_early_verdict_confidence() -> f64 {
+    0.999
+}
+fn default_watchdog_factor() -> f64 {
+    1000.0
+}
+fn default_store_raw_samples() -> bool {
+    true
+}
+fn default_sample_limit() -> usize {
+    1_000_000
+}
+fn default_percentiles() -> Vec<f64> {
+    crate::DEFAULT_PERCENTILES.to_vec()
+}
+fn default_min_cpu_snapshots() -> usize {
+    crate::CoverageRule::default().min_snapshots
+}
+fn default_min_cpu_coverage() -> f64 {
+    crate::CoverageRule::default().min_span_fraction
+}
+fn default_warmup_duration() -> Duration {
+    Duration::from_secs(3) // 3 seconds, matching Criterion's default
 }
 
 impl Default for MeasurementConfig {
     fn default() -> Self {
         Self {
             samples: default_samples(),
-            warmup_duration_secs: default_warmup_duration(),
+            warmup_duration: default_warmup_duration(),
+            warmup_mode: WarmupMode::default(),
+            max_warmup_secs: default_max_warmup_secs(),
+            warmup_stable_readings: default_warmup_stable_readings(),
+            settle_after_drop_us: 0,
+            discard_samples: 0,
+            adaptive: false,
+            target_cv: default_target_cv(),
+            min_samples: default_min_samples(),
+            max_samples: default_max_samples(),
+            max_time_secs: None,
+            clock: ClockMode::default(),
+            cooldown_secs: 0.0,
+            cooldown_max_temp_c: default_cooldown_max_temp_c(),
+            early_verdict: false,
+            early_verdict_interval: default_early_verdict_interval(),
+            early_verdict_confidence: default_early_verdict_confidence(),
+            watchdog_factor: default_watchdog_factor(),
+            watchdog_max_sample_ms: None,
+            watchdog_stop: false,
+            store_raw_samples: default_store_raw_samples(),
+            sample_limit: default_sample_limit(),
+            percentiles: default_percentiles(),
+            max_cv_pct: None,
+            ignore_preconditions: false,
+            min_cpu_snapshots: default_min_cpu_snapshots(),
+            min_cpu_coverage: default_min_cpu_coverage(),
         }
     }
 }
@@ -56,11 +567,283 @@ pub struct ComparisonConfig {
     /// Bayesian hazard rate (default: 0.1 = change every 10 runs)
     #[serde(default = "default_hazard_rate")]
     pub hazard_rate: f64,
+
+    /// Carry history over when a benchmark is renamed without changing its body
+    #[serde(default = "default_follow_renames")]
+    pub follow_renames: bool,
+
+    /// Start a new baseline window at a confirmed improvement, dropping the
+    /// runs before it instead of mixing the old and new performance levels
+    #[serde(default)]
+    pub improvement_resets_window: bool,
+
+    /// Pool the windows of every machine sharing the `.benches` tree, each
+    /// run normalized by its machine's median, and compare ratios instead of
+    /// nanoseconds
+    #[serde(default)]
+    pub compare_across_machines: bool,
+
+    /// Timing compared against the baseline window (default: wall time)
+    #[serde(default)]
+    pub metric: ComparisonMetric,
+
+    /// Spread of the baseline window the z-score and bounds are scaled by
+    /// (default: standard deviation of the run means)
+    #[serde(default)]
+    pub window_scale: WindowScale,
+
+    /// How a change is judged statistically significant (default: z-score of
+    /// the current mean against the window)
+    #[serde(default)]
+    pub method: ComparisonMethod,
+
+    /// Resamples drawn by the bootstrap method
+    #[serde(default = "default_bootstrap_iterations")]
+    pub bootstrap_iterations: usize,
+
+    /// Bootstrap the raw samples of the current run and the window instead of
+    /// the window's run means
+    #[serde(default)]
+    pub bootstrap_samples: bool,
+
+    /// Also require a change of at least this many pooled sample standard
+    /// deviations (Cohen's d) before flagging a regression
+    #[serde(default)]
+    pub min_effect_size: Option<f64>,
+
+    /// With `min_effect_size`, judge practical significance by the effect size
+    /// alone: small percentage changes of very stable benchmarks are flagged,
+    /// and the percentage threshold no longer applies
+    #[serde(default)]
+    pub effect_size_primary: bool,
+
+    /// Flag an allocation regression when allocations per sample grow by more than
+    /// this percentage over the window mean (needs the `alloc-tracking` feature;
+    /// default: disabled)
+    #[serde(default)]
+    pub alloc_threshold: Option<f64>,
+
+    /// Warn in the summary when a least-squares line through the window's
+    /// means and the current run's climbs more than this percentage from the
+    /// first run to the last, catching drifts too slow for any single
+    /// comparison to flag (default: disabled)
+    #[serde(default)]
+    pub drift_threshold: Option<f64>,
+
+    /// Also judge the p99 against the window's p99s, flagging a regression when
+    /// it grows by more than this percentage with the same statistical evidence
+    /// the mean needs; tail regressions leave the mean untouched
+    /// (default: disabled)
+    #[serde(default)]
+    pub p99_threshold: Option<f64>,
+
+    /// Compare means computed without the samples outside the Tukey fences,
+    /// so a few stalls don't move the verdict; runs stored without filtered
+    /// statistics use their raw mean (default: false, wall time only)
+    #[serde(default)]
+    pub filter_outliers: bool,
+
+    /// Mark a comparison SUSPECT when the mean iteration time of warmup and of
+    /// sampling differ by more than this factor (default: 1.5)
+    #[serde(default = "default_consistency_factor")]
+    pub consistency_factor: f64,
+
+    /// Print a warning under comparisons with less than 80% power to detect a
+    /// change of `threshold` (default: false)
+    #[serde(default)]
+    pub warn_underpowered: bool,
+
+    /// Settings replaced for the benchmarks whose name matches a key, e.g.
+    /// `{ "bench_fs_*" = { threshold = 15.0, window_size = 20 } }`; `*` and
+    /// `?` are wildcards. See [`ComparisonConfig::for_benchmark`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, ComparisonOverride>,
+
+    /// The global threshold, when an override replaced it in this resolved
+    /// config
+    #[serde(skip)]
+    pub global_threshold: Option<f64>,
+}
+
+/// Comparison settings replaced for the benchmarks of a `[comparison.overrides]`
+/// key; unset fields keep the global value
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence_level: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cp_threshold: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_effect_size: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p99_threshold: Option<f64>,
+}
+
+impl ComparisonConfig {
+    /// The settings a benchmark is compared with: these, with the fields of
+    /// every override whose key matches `benchmark_name` replaced
+    ///
+    /// Globs apply before an exact name, and shorter patterns before longer
+    /// ones, so the most specific key wins a field several of them set.
+    pub fn for_benchmark(&self, benchmark_name: &str) -> ComparisonConfig {
+        let mut config = self.clone();
+        config.overrides.clear();
+
+        let mut matching: Vec<(&String, &ComparisonOverride)> = self
+            .overrides
+            .iter()
+            .filter(|(pattern, _)| glob_match(pattern, benchmark_name))
+            .collect();
+        matching.sort_by_key(|(pattern, _)| (!pattern.contains(['*', '?']), pattern.len()));
+        for (_, overrides) in matching {
+            config.threshold = overrides.threshold.unwrap_or(config.threshold);
+            config.window_size = overrides.window_size.unwrap_or(config.window_size);
+            config.confidence_level = overrides
+                .confidence_level
+                .unwrap_or(config.confidence_level);
+            config.cp_threshold = overrides.cp_threshold.unwrap_or(config.cp_threshold);
+            config.min_effect_size = overrides.min_effect_size.or(config.min_effect_size);
+            config.p99_threshold = overrides.p99_threshold.or(config.p99_threshold);
+        }
+
+        if config.threshold != self.threshold {
+            config.global_threshold = Some(self.threshold);
+        }
+        config
+    }
+}
+
+/// Whether `text` matches `pattern` as a whole, `*` matching any run of
+/// characters and `?` any one
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it resumes at
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, t));
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((after_star, resume)) => {
+                    p = after_star;
+                    t = resume + 1;
+                    backtrack = Some((after_star, resume + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Which per-sample timing regression detection compares
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonMetric {
+    /// Elapsed wall-clock time
+    #[default]
+    WallTime,
+    /// Thread CPU time; falls back to wall time when unavailable
+    CpuTime,
+}
+
+impl std::str::FromStr for ComparisonMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wall_time" => Ok(Self::WallTime),
+            "cpu_time" => Ok(Self::CpuTime),
+            other => Err(format!(
+                "unknown metric '{}' (expected 'wall_time' or 'cpu_time')",
+                other
+            )),
+        }
+    }
+}
+
+/// How the spread of the baseline window is measured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowScale {
+    /// Standard deviation of the window's run means
+    #[default]
+    StdDev,
+    /// Median absolute deviation of the run means, scaled to match the
+    /// standard deviation of normal data; one outlying run doesn't widen the
+    /// bounds for the rest of the window
+    Mad,
+}
+
+impl std::str::FromStr for WindowScale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "std_dev" => Ok(Self::StdDev),
+            "mad" => Ok(Self::Mad),
+            other => Err(format!(
+                "unknown window scale '{}' (expected 'std_dev' or 'mad')",
+                other
+            )),
+        }
+    }
+}
+
+/// How the statistical significance of a change against the window is judged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonMethod {
+    /// z-score of the current mean against the window, assuming normality
+    #[default]
+    ZScore,
+    /// Percentile bootstrap interval of the difference between the current
+    /// and baseline means; significant when it excludes zero
+    Bootstrap,
+    /// Mann-Whitney U test of the current samples against those of the most
+    /// recent baseline run
+    #[serde(rename = "mannwhitney")]
+    MannWhitney,
+    /// Welch's t-test of the current samples against the stored mean, variance
+    /// and sample count of the most recent baseline run
+    Welch,
+}
+
+impl std::str::FromStr for ComparisonMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "z_score" => Ok(Self::ZScore),
+            "bootstrap" => Ok(Self::Bootstrap),
+            "mannwhitney" => Ok(Self::MannWhitney),
+            "welch" => Ok(Self::Welch),
+            other => Err(format!(
+                "unknown comparison method '{}' (expected 'z_score', 'bootstrap', 'mannwhitney' or 'welch')",
+                other
+            )),
+        }
+    }
 }
 
 fn default_threshold() -> f64 {
     5.0
 }
+fn default_consistency_factor() -> f64 {
+    1.5
+}
 
 fn default_window_size() -> usize {
     10
@@ -78,6 +861,14 @@ fn default_hazard_rate() -> f64 {
     0.1
 }
 
+fn default_bootstrap_iterations() -> usize {
+    1000
+}
+
+fn default_follow_renames() -> bool {
+    true
+}
+
 impl Default for ComparisonConfig {
     fn default() -> Self {
         Self {
@@ -87,6 +878,312 @@ impl Default for ComparisonConfig {
             confidence_level: default_confidence_level(),
             cp_threshold: default_cp_threshold(),
             hazard_rate: default_hazard_rate(),
+            follow_renames: default_follow_renames(),
+            improvement_resets_window: false,
+            compare_across_machines: false,
+            metric: ComparisonMetric::default(),
+            window_scale: WindowScale::default(),
+            method: ComparisonMethod::default(),
+            bootstrap_iterations: default_bootstrap_iterations(),
+            bootstrap_samples: false,
+            min_effect_size: None,
+            effect_size_primary: false,
+            alloc_threshold: None,
+            drift_threshold: None,
+            p99_threshold: None,
+            filter_outliers: false,
+            consistency_factor: default_consistency_factor(),
+            warn_underpowered: false,
+            overrides: BTreeMap::new(),
+            global_threshold: None,
+        }
+    }
+}
+
+/// Configuration for CPU core assignment
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoresConfig {
+    /// Benchmarks pinned to a specific core, e.g. `{ "bench_entity_update_loop" = 7 }`
+    ///
+    /// Overridden cores are reserved out of the general pool. Takes precedence
+    /// over `#[bench(core = N)]`.
+    #[serde(default)]
+    pub overrides: BTreeMap<String, usize>,
+
+    /// Allow pinning benchmarks to core 0 (normally reserved for the system)
+    #[serde(default)]
+    pub allow_core_zero: bool,
+}
+
+/// Configuration for baseline storage in `.benches`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineConfig {
+    /// Prune the oldest runs across all benchmarks once `.benches` grows past
+    /// this many megabytes (default: unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_disk_mb: Option<f64>,
+
+    /// Newest runs of each benchmark that pruning never removes (default: 10,
+    /// the default comparison window)
+    #[serde(default = "default_min_runs")]
+    pub min_runs: usize,
+
+    /// Runs kept per benchmark on this machine; older ones are deleted after
+    /// each save, except regression-flagged runs (default: unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_runs_per_benchmark: Option<usize>,
+
+    /// Delete a benchmark's runs older than this many days after each save,
+    /// except regression-flagged runs (default: unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<f64>,
+
+    /// Directory runs are stored in, relative to the workspace root unless
+    /// absolute (default: `.benches`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+
+    /// Machine id runs are stored and compared under, instead of the one
+    /// derived from this machine (default: derived)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine_id: Option<String>,
+
+    /// Write new runs gzip-compressed as `.json.gz`; runs are read in either
+    /// format (default: false)
+    #[serde(default)]
+    pub compress: bool,
+
+    /// Central service this machine's runs are fetched from before a run and
+    /// uploaded to after it, e.g. `https://bench.example.com/baselines`
+    /// (needs the `remote-baselines` feature of cargo-simplebench)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_url: Option<String>,
+}
+
+/// Baseline directory without `[baseline] dir`, relative to the workspace root
+pub const DEFAULT_BASELINE_DIR: &str = ".benches";
+
+impl BaselineConfig {
+    /// Directory the runs of the workspace at `workspace_root` are stored in
+    pub fn root_dir(&self, workspace_root: &Path) -> PathBuf {
+        match self.dir {
+            Some(ref dir) => workspace_root.join(dir),
+            None => workspace_root.join(DEFAULT_BASELINE_DIR),
+        }
+    }
+
+    /// Limits applied to a benchmark's runs after each save
+    pub fn retention(&self) -> crate::disk_usage::RetentionPolicy {
+        crate::disk_usage::RetentionPolicy {
+            max_runs: self.max_runs_per_benchmark,
+            max_age_days: self.max_age_days,
+        }
+    }
+}
+
+fn default_min_runs() -> usize {
+    default_window_size()
+}
+
+impl Default for BaselineConfig {
+    fn default() -> Self {
+        Self {
+            max_disk_mb: None,
+            min_runs: default_min_runs(),
+            max_runs_per_benchmark: None,
+            max_age_days: None,
+            dir: None,
+            machine_id: None,
+            compress: false,
+            remote_url: None,
+        }
+    }
+}
+
+/// Configuration for what the per-benchmark output shows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputConfig {
+    /// Fields of the result and comparison lines and of the report table, in
+    /// order, e.g. `["mean", "p99", "cv", "delta"]` (default: the full layout)
+    ///
+    /// See [`Column::ALL`] for the valid names.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<String>>,
+
+    /// Unit every duration is printed in; `auto` picks one per value
+    #[serde(default)]
+    pub time_unit: TimeUnit,
+
+    /// Decimal places of durations above a nanosecond
+    #[serde(default = "default_precision")]
+    pub precision: usize,
+
+    /// Group the digits of durations by thousands with commas, whatever the locale
+    #[serde(default)]
+    pub thousands_separator: bool,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            columns: None,
+            time_unit: TimeUnit::default(),
+            precision: default_precision(),
+            thousands_separator: false,
+        }
+    }
+}
+
+fn default_precision() -> usize {
+    2
+}
+
+/// Unit of `[output] time_unit`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeUnit {
+    /// The largest unit the value is at least one of
+    #[default]
+    Auto,
+    Ns,
+    Us,
+    Ms,
+    S,
+}
+
+impl std::str::FromStr for TimeUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "ns" => Ok(Self::Ns),
+            "us" => Ok(Self::Us),
+            "ms" => Ok(Self::Ms),
+            "s" => Ok(Self::S),
+            other => Err(format!(
+                "unknown time unit '{}' (expected 'auto', 'ns', 'us', 'ms' or 's')",
+                other
+            )),
+        }
+    }
+}
+
+impl OutputConfig {
+    /// Duration formatting of the configured unit, precision and separator
+    pub fn time_format(&self) -> TimeFormat {
+        TimeFormat {
+            unit: self.time_unit,
+            precision: self.precision,
+            thousands_separator: self.thousands_separator,
+        }
+    }
+
+    /// Configured columns, or `default` if none are configured
+    ///
+    /// Fails naming the first unknown column and listing the valid ones.
+    pub fn columns_or(&self, default: &[Column]) -> Result<Vec<Column>, String> {
+        match self.columns {
+            Some(ref names) => Column::parse_list(names),
+            None => Ok(default.to_vec()),
+        }
+    }
+}
+
+/// Environment variables known to change how fast benchmarks run, watched
+/// in every run and recorded with each baseline
+pub const WATCHED_ENV_VARS: &[&str] = &[
+    "RAYON_NUM_THREADS",
+    "TOKIO_WORKER_THREADS",
+    "OMP_NUM_THREADS",
+    "MKL_NUM_THREADS",
+    "OPENBLAS_NUM_THREADS",
+    "GOMAXPROCS",
+    "MALLOC_CONF",
+    "MALLOC_ARENA_MAX",
+    "LD_PRELOAD",
+    "RUST_LOG",
+    "RUST_BACKTRACE",
+    "RUST_MIN_STACK",
+];
+
+/// Configuration for the environment variables recorded with each run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnvironmentConfig {
+    /// Variables watched in addition to [`WATCHED_ENV_VARS`]
+    #[serde(default)]
+    pub watch: Vec<String>,
+}
+
+impl EnvironmentConfig {
+    /// Every watched variable: the built-in list, then the configured ones
+    pub fn watched_vars(&self) -> Vec<String> {
+        let mut vars: Vec<String> = WATCHED_ENV_VARS.iter().map(|v| v.to_string()).collect();
+        for var in &self.watch {
+            if !vars.contains(var) {
+                vars.push(var.clone());
+            }
+        }
+        vars
+    }
+
+    /// Value of each watched variable in this process, None where unset
+    pub fn snapshot(&self) -> BTreeMap<String, Option<String>> {
+        self.watched_vars()
+            .into_iter()
+            .map(|var| {
+                let value = std::env::var(&var).ok();
+                (var, value)
+            })
+            .collect()
+    }
+}
+
+/// Commands `cargo simplebench` runs on a verdict, such as capturing a profile
+///
+/// Each runs through the shell in the workspace root, with a JSON payload on
+/// stdin and its key fields in `SIMPLEBENCH_HOOK_*` variables.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Run for each benchmark confirmed as a regression
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_regression: Option<String>,
+
+    /// Run for each benchmark confirmed as an improvement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_improvement: Option<String>,
+
+    /// Run once after the session, with its summary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_complete: Option<String>,
+
+    /// Seconds a hook may run before it's killed
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: f64,
+
+    /// Fail the run when a hook fails or times out (default: only reported)
+    #[serde(default)]
+    pub strict: bool,
+}
+
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        self.on_regression.is_none() && self.on_improvement.is_none() && self.on_complete.is_none()
+    }
+}
+
+fn default_hook_timeout_secs() -> f64 {
+    60.0
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            on_regression: None,
+            on_improvement: None,
+            on_complete: None,
+            timeout_secs: default_hook_timeout_secs(),
+            strict: false,
         }
     }
 }
@@ -99,6 +1196,21 @@ pub struct BenchmarkConfig {
 
     #[serde(default)]
     pub comparison: ComparisonConfig,
+
+    #[serde(default)]
+    pub cores: CoresConfig,
+
+    #[serde(default)]
+    pub baseline: BaselineConfig,
+
+    #[serde(default)]
+    pub output: OutputConfig,
+
+    #[serde(default)]
+    pub environment: EnvironmentConfig,
+
+    #[serde(default)]
+    pub hooks: HooksConfig,
 }
 
 impl BenchmarkConfig {
@@ -120,6 +1232,11 @@ impl BenchmarkConfig {
         config
     }
 
+    /// Check the settings a run can't proceed with, such as a zero sample count
+    pub fn validate(&self) -> Result<(), String> {
+        crate::measurement::validate_measurement_params(&self.measurement)
+    }
+
     /// Load configuration from a TOML file
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
         let contents = fs::read_to_string(path)?;
@@ -139,8 +1256,158 @@ impl BenchmarkConfig {
         }
 
         if let Ok(warmup) = std::env::var("SIMPLEBENCH_WARMUP_DURATION") {
-            if let Ok(val) = warmup.parse() {
-                self.measurement.warmup_duration_secs = val;
+            if let Ok(val) = parse_duration(&warmup) {
+                self.measurement.warmup_duration = val;
+            }
+        }
+
+        if let Ok(settle) = std::env::var("SIMPLEBENCH_SETTLE_AFTER_DROP_US") {
+            if let Ok(val) = settle.parse() {
+                self.measurement.settle_after_drop_us = val;
+            }
+        }
+
+        if let Ok(mode) = std::env::var("SIMPLEBENCH_WARMUP_MODE") {
+            if let Ok(val) = mode.parse() {
+                self.measurement.warmup_mode = val;
+            }
+        }
+
+        if let Ok(max_warmup) = std::env::var("SIMPLEBENCH_MAX_WARMUP_SECS") {
+            if let Ok(val) = max_warmup.parse() {
+                self.measurement.max_warmup_secs = val;
+            }
+        }
+
+        if let Ok(clock) = std::env::var("SIMPLEBENCH_CLOCK") {
+            if let Ok(val) = clock.parse() {
+                self.measurement.clock = val;
+            }
+        }
+
+        if std::env::var("SIMPLEBENCH_ADAPTIVE").is_ok() {
+            self.measurement.adaptive = true;
+        }
+
+        if let Ok(target_cv) = std::env::var("SIMPLEBENCH_TARGET_CV") {
+            if let Ok(val) = target_cv.parse() {
+                self.measurement.target_cv = val;
+            }
+        }
+
+        if let Ok(min_samples) = std::env::var("SIMPLEBENCH_MIN_SAMPLES") {
+            if let Ok(val) = min_samples.parse() {
+                self.measurement.min_samples = val;
+            }
+        }
+
+        if let Ok(max_samples) = std::env::var("SIMPLEBENCH_MAX_SAMPLES") {
+            if let Ok(val) = max_samples.parse() {
+                self.measurement.max_samples = val;
+            }
+        }
+
+        if let Ok(discard) = std::env::var("SIMPLEBENCH_DISCARD_SAMPLES") {
+            if let Ok(val) = discard.parse() {
+                self.measurement.discard_samples = val;
+            }
+        }
+
+        if let Ok(max_time) = std::env::var("SIMPLEBENCH_MAX_TIME_SECS") {
+            if let Ok(val) = max_time.parse() {
+                self.measurement.max_time_secs = Some(val);
+            }
+        }
+
+        if let Ok(cooldown) = std::env::var("SIMPLEBENCH_COOLDOWN_SECS") {
+            if let Ok(val) = cooldown.parse() {
+                self.measurement.cooldown_secs = val;
+            }
+        }
+
+        if let Ok(max_temp) = std::env::var("SIMPLEBENCH_COOLDOWN_MAX_TEMP_C") {
+            if let Ok(val) = max_temp.parse() {
+                self.measurement.cooldown_max_temp_c = val;
+            }
+        }
+
+        if std::env::var("SIMPLEBENCH_EARLY_VERDICT").is_ok() {
+            self.measurement.early_verdict = true;
+        }
+
+        if let Ok(percentiles) = std::env::var("SIMPLEBENCH_PERCENTILES") {
+            let parsed: Result<Vec<f64>, _> =
+                percentiles.split(',').map(|p| p.trim().parse()).collect();
+            if let Ok(val) = parsed {
+                self.measurement.percentiles = val;
+            }
+        }
+
+        if let Ok(max_cv) = std::env::var("SIMPLEBENCH_MAX_CV_PCT") {
+            if let Ok(val) = max_cv.parse() {
+                self.measurement.max_cv_pct = Some(val);
+            }
+        }
+
+        if std::env::var("SIMPLEBENCH_IGNORE_PRECONDITIONS").is_ok() {
+            self.measurement.ignore_preconditions = true;
+        }
+
+        // Baseline storage overrides
+        if let Ok(max_disk) = std::env::var("SIMPLEBENCH_MAX_DISK_MB") {
+            if let Ok(val) = max_disk.parse() {
+                self.baseline.max_disk_mb = Some(val);
+            }
+        }
+
+        if let Ok(max_runs) = std::env::var("SIMPLEBENCH_MAX_RUNS_PER_BENCHMARK") {
+            if let Ok(val) = max_runs.parse() {
+                self.baseline.max_runs_per_benchmark = Some(val);
+            }
+        }
+
+        if let Ok(max_age) = std::env::var("SIMPLEBENCH_MAX_AGE_DAYS") {
+            if let Ok(val) = max_age.parse() {
+                self.baseline.max_age_days = Some(val);
+            }
+        }
+
+        if let Ok(dir) = std::env::var("SIMPLEBENCH_BASELINE_DIR") {
+            if !dir.is_empty() {
+                self.baseline.dir = Some(PathBuf::from(dir));
+            }
+        }
+
+        if let Ok(machine_id) = std::env::var("SIMPLEBENCH_MACHINE_ID") {
+            if !machine_id.is_empty() {
+                self.baseline.machine_id = Some(machine_id);
+            }
+        }
+
+        if let Ok(remote_url) = std::env::var("SIMPLEBENCH_REMOTE_URL") {
+            if !remote_url.is_empty() {
+                self.baseline.remote_url = Some(remote_url);
+            }
+        }
+
+        if let Ok(compress) = std::env::var("SIMPLEBENCH_COMPRESS_BASELINES") {
+            if let Ok(val) = compress.parse() {
+                self.baseline.compress = val;
+            }
+        }
+
+        // Output overrides
+        if let Ok(columns) = std::env::var("SIMPLEBENCH_COLUMNS") {
+            self.output.columns = Some(columns.split(',').map(|c| c.trim().to_string()).collect());
+        }
+        if let Ok(unit) = std::env::var("SIMPLEBENCH_TIME_UNIT") {
+            if let Ok(val) = unit.parse() {
+                self.output.time_unit = val;
+            }
+        }
+        if let Ok(precision) = std::env::var("SIMPLEBENCH_PRECISION") {
+            if let Ok(val) = precision.parse() {
+                self.output.precision = val;
             }
         }
 
@@ -179,6 +1446,92 @@ impl BenchmarkConfig {
                 self.comparison.hazard_rate = val;
             }
         }
+
+        if let Ok(metric) = std::env::var("SIMPLEBENCH_METRIC") {
+            if let Ok(val) = metric.parse() {
+                self.comparison.metric = val;
+            }
+        }
+
+        if let Ok(window_scale) = std::env::var("SIMPLEBENCH_WINDOW_SCALE") {
+            if let Ok(val) = window_scale.parse() {
+                self.comparison.window_scale = val;
+            }
+        }
+
+        if let Ok(method) = std::env::var("SIMPLEBENCH_COMPARISON_METHOD") {
+            if let Ok(val) = method.parse() {
+                self.comparison.method = val;
+            }
+        }
+
+        if let Ok(iterations) = std::env::var("SIMPLEBENCH_BOOTSTRAP_ITERATIONS") {
+            if let Ok(val) = iterations.parse() {
+                self.comparison.bootstrap_iterations = val;
+            }
+        }
+
+        if let Ok(min_effect_size) = std::env::var("SIMPLEBENCH_MIN_EFFECT_SIZE") {
+            if let Ok(val) = min_effect_size.parse() {
+                self.comparison.min_effect_size = Some(val);
+            }
+        }
+
+        if let Ok(effect_size_primary) = std::env::var("SIMPLEBENCH_EFFECT_SIZE_PRIMARY") {
+            if let Ok(val) = effect_size_primary.parse() {
+                self.comparison.effect_size_primary = val;
+            }
+        }
+
+        if let Ok(follow_renames) = std::env::var("SIMPLEBENCH_FOLLOW_RENAMES") {
+            if let Ok(val) = follow_renames.parse() {
+                self.comparison.follow_renames = val;
+            }
+        }
+
+        if let Ok(resets) = std::env::var("SIMPLEBENCH_IMPROVEMENT_RESETS_WINDOW") {
+            if let Ok(val) = resets.parse() {
+                self.comparison.improvement_resets_window = val;
+            }
+        }
+
+        if let Ok(across) = std::env::var("SIMPLEBENCH_COMPARE_ACROSS_MACHINES") {
+            if let Ok(val) = across.parse() {
+                self.comparison.compare_across_machines = val;
+            }
+        }
+
+        if let Ok(alloc_threshold) = std::env::var("SIMPLEBENCH_ALLOC_THRESHOLD") {
+            if let Ok(val) = alloc_threshold.parse() {
+                self.comparison.alloc_threshold = Some(val);
+            }
+        }
+
+        if let Ok(drift_threshold) = std::env::var("SIMPLEBENCH_DRIFT_THRESHOLD") {
+            if let Ok(val) = drift_threshold.parse() {
+                self.comparison.drift_threshold = Some(val);
+            }
+        }
+
+        if let Ok(p99_threshold) = std::env::var("SIMPLEBENCH_P99_THRESHOLD") {
+            if let Ok(val) = p99_threshold.parse() {
+                self.comparison.p99_threshold = Some(val);
+            }
+        }
+
+        if std::env::var("SIMPLEBENCH_FILTER_OUTLIERS").is_ok() {
+            self.comparison.filter_outliers = true;
+        }
+
+        if let Ok(factor) = std::env::var("SIMPLEBENCH_CONSISTENCY_FACTOR") {
+            if let Ok(val) = factor.parse() {
+                self.comparison.consistency_factor = val;
+            }
+        }
+
+        if std::env::var("SIMPLEBENCH_WARN_UNDERPOWERED").is_ok() {
+            self.comparison.warn_underpowered = true;
+        }
     }
 
     /// Save configuration to a TOML file
@@ -199,7 +1552,7 @@ mod tests {
     fn test_default_config() {
         let config = BenchmarkConfig::default();
         assert_eq!(config.measurement.samples, 1000);
-        assert_eq!(config.measurement.warmup_duration_secs, 3);
+        assert_eq!(config.measurement.warmup_duration, Duration::from_secs(3));
         assert_eq!(config.comparison.threshold, 5.0);
         assert!(!config.comparison.ci_mode);
     }
@@ -213,7 +1566,7 @@ mod tests {
         let loaded = BenchmarkConfig::from_file(temp_file.path()).unwrap();
 
         assert_eq!(loaded.measurement.samples, 1000);
-        assert_eq!(loaded.measurement.warmup_duration_secs, 3);
+        assert_eq!(loaded.measurement.warmup_duration, Duration::from_secs(3));
     }
 
     #[test]
@@ -227,7 +1580,7 @@ mod tests {
         config.apply_env_overrides();
 
         assert_eq!(config.measurement.samples, 300);
-        assert_eq!(config.measurement.warmup_duration_secs, 5);
+        assert_eq!(config.measurement.warmup_duration, Duration::from_secs(5));
         assert!(config.comparison.ci_mode);
         assert_eq!(config.comparison.threshold, 10.0);
 
@@ -238,6 +1591,62 @@ mod tests {
         env::remove_var("SIMPLEBENCH_THRESHOLD");
     }
 
+    #[test]
+    fn test_core_overrides_config() {
+        let toml_content = r#"
+            [cores]
+            overrides = { "bench_entity_update_loop" = 7 }
+        "#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), toml_content).unwrap();
+
+        let config = BenchmarkConfig::from_file(temp_file.path()).unwrap();
+
+        assert_eq!(
+            config.cores.overrides.get("bench_entity_update_loop"),
+            Some(&7)
+        );
+        assert!(!config.cores.allow_core_zero);
+        assert_eq!(config.measurement.samples, 1000);
+    }
+
+    #[test]
+    fn test_comparison_overrides_config() {
+        let toml_content = r#"
+            [comparison]
+            threshold = 5.0
+
+            [comparison.overrides]
+            "bench_fs_*" = { threshold = 15.0, window_size = 20 }
+            "bench_fs_sync" = { threshold = 25.0 }
+        "#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), toml_content).unwrap();
+        let config = BenchmarkConfig::from_file(temp_file.path()).unwrap();
+
+        let fs_read = config.comparison.for_benchmark("bench_fs_read");
+        assert_eq!(fs_read.threshold, 15.0);
+        assert_eq!(fs_read.window_size, 20);
+        assert_eq!(fs_read.global_threshold, Some(5.0));
+
+        // The exact name wins the threshold; the glob still sets the window
+        let fs_sync = config.comparison.for_benchmark("bench_fs_sync");
+        assert_eq!(fs_sync.threshold, 25.0);
+        assert_eq!(fs_sync.window_size, 20);
+
+        let other = config.comparison.for_benchmark("bench_vec_push");
+        assert_eq!(other.threshold, 5.0);
+        assert_eq!(other.window_size, config.comparison.window_size);
+        assert_eq!(other.global_threshold, None);
+
+        assert!(glob_match("bench_?s_*", "bench_fs_read"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("bench_fs_*", "my_bench_fs_read"));
+        assert!(!glob_match("bench_fs", "bench_fs_read"));
+    }
+
     #[test]
     fn test_partial_config_file() {
         let toml_content = r#"
@@ -258,7 +1667,280 @@ mod tests {
         assert_eq!(config.comparison.threshold, 7.5);
 
         // Default values for unspecified fields
-        assert_eq!(config.measurement.warmup_duration_secs, 3);
+        assert_eq!(config.measurement.warmup_duration, Duration::from_secs(3));
         assert!(!config.comparison.ci_mode);
+        assert_eq!(config.baseline.max_disk_mb, None);
+        assert_eq!(config.baseline.min_runs, 10);
+        assert!(config.baseline.retention().is_unlimited());
+        assert!(config.hooks.is_empty());
+        assert!(!config.hooks.strict);
+        assert_eq!(config.output.columns, None);
+    }
+
+    #[test]
+    fn test_output_columns_validated_on_use() {
+        let config: BenchmarkConfig = toml::from_str(
+            r#"
+            [output]
+            columns = ["mean", "p99", "cv", "delta"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.output.columns_or(Column::TERMINAL_DEFAULT).unwrap(),
+            vec![Column::Mean, Column::P99, Column::Cv, Column::Delta]
+        );
+        assert_eq!(
+            OutputConfig::default()
+                .columns_or(Column::REPORT_DEFAULT)
+                .unwrap(),
+            Column::REPORT_DEFAULT
+        );
+
+        let typo = OutputConfig {
+            columns: Some(vec!["mena".to_string()]),
+            ..Default::default()
+        };
+        assert!(typo
+            .columns_or(Column::TERMINAL_DEFAULT)
+            .unwrap_err()
+            .starts_with("unknown column 'mena' (valid: mean, cv,"));
+    }
+
+    #[test]
+    fn test_output_time_format() {
+        assert_eq!(OutputConfig::default().time_format(), TimeFormat::DEFAULT);
+
+        let config: BenchmarkConfig = toml::from_str(
+            r#"
+            [output]
+            time_unit = "us"
+            precision = 3
+            thousands_separator = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.output.time_format(),
+            TimeFormat {
+                unit: TimeUnit::Us,
+                precision: 3,
+                thousands_separator: true,
+            }
+        );
+
+        assert!(toml::from_str::<BenchmarkConfig>("[output]\ntime_unit = \"min\"").is_err());
+        assert_eq!("ms".parse(), Ok(TimeUnit::Ms));
+        assert!("µs".parse::<TimeUnit>().is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_accepted_forms() {
+        let cases = [
+            ("3", Duration::from_secs(3)),
+            ("0", Duration::ZERO),
+            ("2.5", Duration::from_millis(2500)),
+            ("250ns", Duration::from_nanos(250)),
+            ("10us", Duration::from_micros(10)),
+            ("10µs", Duration::from_micros(10)),
+            ("500ms", Duration::from_millis(500)),
+            ("1.5s", Duration::from_millis(1500)),
+            ("2m", Duration::from_secs(120)),
+            ("1h", Duration::from_secs(3600)),
+            ("1h30m", Duration::from_secs(5400)),
+            ("1m30s500ms", Duration::from_millis(90_500)),
+            ("  45s  ", Duration::from_secs(45)),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(parse_duration(input), Ok(expected), "input {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_size() {
+        let cases = [
+            ("4096", 4096),
+            ("8GB", 8 << 30),
+            ("8gb", 8 << 30),
+            ("8G", 8 << 30),
+            ("512MiB", 512 << 20),
+            ("1.5KB", 1536),
+            (" 2 TB ", 2 << 40),
+            ("100B", 100),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_size(input), Ok(expected), "input {:?}", input);
+        }
+
+        assert!(parse_size("").unwrap_err().contains("expected a number"));
+        assert!(parse_size("8XB").unwrap_err().contains("unknown unit"));
+        assert!(parse_size("1..5GB").unwrap_err().contains("not a number"));
+
+        assert_eq!(format_size(8 << 30), "8GB");
+        assert_eq!(format_size(3_328_599_654), "3.1GB");
+        assert_eq!(format_size(512), "512B");
+        assert_eq!(format_size(0), "0B");
+    }
+
+    #[test]
+    fn test_parse_duration_rejected_forms() {
+        let cases = [
+            ("", "empty"),
+            ("   ", "empty"),
+            ("-5s", "expected a number"),
+            ("-5", "non-negative"),
+            ("5x", "unknown unit \"x\""),
+            ("5 s", "unknown unit \" s\""),
+            ("1h30", "missing unit after \"30\""),
+            ("1..5s", "\"1..5\" is not a number"),
+            ("ms", "expected a number"),
+            ("99999999999h", "too large"),
+        ];
+
+        for (input, expected) in cases {
+            let err = parse_duration(input).unwrap_err();
+            assert!(
+                err.contains(expected),
+                "input {:?}: error {:?} should mention {:?}",
+                input,
+                err,
+                expected
+            );
+            // Errors echo the offending input and list the valid suffixes
+            assert!(err.contains(&format!("\"{}\"", input)), "error {:?}", err);
+            if expected != "too large" {
+                assert!(err.contains("ns, us, ms, s, m, h"), "error {:?}", err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_duration_compact_round_trips() {
+        for duration in [
+            Duration::ZERO,
+            Duration::from_nanos(250),
+            Duration::from_micros(10),
+            Duration::from_millis(500),
+            Duration::from_secs(3),
+            Duration::from_secs(5400),
+            Duration::from_secs(3661),
+        ] {
+            let formatted = format_duration_compact(duration);
+            assert_eq!(parse_duration(&formatted), Ok(duration), "{}", formatted);
+        }
+        assert_eq!(format_duration_compact(Duration::from_secs(5400)), "1h30m");
+    }
+
+    #[test]
+    fn test_warmup_duration_config_forms() {
+        let parse = |toml_content: &str| -> MeasurementConfig {
+            let temp_file = NamedTempFile::new().unwrap();
+            fs::write(temp_file.path(), toml_content).unwrap();
+            BenchmarkConfig::from_file(temp_file.path())
+                .unwrap()
+                .measurement
+        };
+
+        // Legacy key with integer seconds
+        let legacy = parse("[measurement]\nwarmup_duration_secs = 5\n");
+        assert_eq!(legacy.warmup_duration, Duration::from_secs(5));
+
+        // Number of seconds
+        let secs = parse("[measurement]\nwarmup_duration = 2\n");
+        assert_eq!(secs.warmup_duration, Duration::from_secs(2));
+
+        // Duration string
+        let text = parse("[measurement]\nwarmup_duration = \"500ms\"\n");
+        assert_eq!(text.warmup_duration, Duration::from_millis(500));
+
+        // Invalid string surfaces the parse error
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(
+            temp_file.path(),
+            "[measurement]\nwarmup_duration = \"5 parsecs\"\n",
+        )
+        .unwrap();
+        let err = BenchmarkConfig::from_file(temp_file.path())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("5 parsecs"), "{}", err);
+    }
+
+    #[test]
+    fn test_adaptive_sampling_config() {
+        let defaults = MeasurementConfig::default();
+        assert!(!defaults.adaptive);
+        assert_eq!(defaults.target_cv, 2.0);
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(
+            temp_file.path(),
+            "[measurement]\nadaptive = true\ntarget_cv = 1.5\nmin_samples = 50\nmax_samples = 5000\n",
+        )
+        .unwrap();
+        let measurement = BenchmarkConfig::from_file(temp_file.path())
+            .unwrap()
+            .measurement;
+        assert!(measurement.adaptive);
+        assert_eq!(measurement.target_cv, 1.5);
+        assert_eq!(measurement.min_samples, 50);
+        assert_eq!(measurement.max_samples, 5000);
+        // Fixed sample count keeps its default alongside the adaptive keys
+        assert_eq!(measurement.samples, 1000);
+    }
+
+    #[test]
+    fn test_clock_mode_config() {
+        assert_eq!(MeasurementConfig::default().clock, ClockMode::Both);
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), "[measurement]\nclock = \"cpu\"\n").unwrap();
+        let config = BenchmarkConfig::from_file(temp_file.path()).unwrap();
+        assert_eq!(config.measurement.clock, ClockMode::Cpu);
+
+        assert_eq!("wall".parse::<ClockMode>(), Ok(ClockMode::Wall));
+        assert!("monotonic".parse::<ClockMode>().is_err());
+    }
+
+    #[test]
+    fn test_cpu_coverage_rule_config() {
+        assert_eq!(
+            MeasurementConfig::default().cpu_coverage_rule(),
+            crate::CoverageRule::default()
+        );
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(
+            temp_file.path(),
+            "[measurement]\nmin_cpu_snapshots = 4\nmin_cpu_coverage = 0.25\n",
+        )
+        .unwrap();
+        let config = BenchmarkConfig::from_file(temp_file.path()).unwrap();
+        assert_eq!(
+            config.measurement.cpu_coverage_rule(),
+            crate::CoverageRule {
+                min_snapshots: 4,
+                min_span_fraction: 0.25
+            }
+        );
+    }
+
+    #[test]
+    fn test_warmup_mode_config() {
+        let defaults = MeasurementConfig::default();
+        assert_eq!(defaults.warmup_mode, WarmupMode::Fixed);
+        assert_eq!(defaults.max_warmup_secs, 10.0);
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(
+            temp_file.path(),
+            "[measurement]\nwarmup_mode = \"adaptive\"\nmax_warmup_secs = 4.0\n",
+        )
+        .unwrap();
+        let config = BenchmarkConfig::from_file(temp_file.path()).unwrap();
+        assert_eq!(config.measurement.warmup_mode, WarmupMode::Adaptive);
+        assert_eq!(config.measurement.max_warmup_secs, 4.0);
+        assert!("forever".parse::<WarmupMode>().is_err());
     }
 }
@@ -1,6 +1,17 @@
-//! CPU analysis for detecting thermal throttling, frequency variance, and cold starts
+//! CPU analysis for detecting thermal throttling, frequency variance, cold starts
+//! and preemption
 
-use crate::CpuSnapshot;
+use crate::config::format_duration_compact;
+use crate::{CpuSnapshot, CPU_SAMPLE_INTERVAL};
+use std::time::Duration;
+
+/// Percentage by which mean wall time may exceed mean CPU time before the gap is
+/// attributed to preemption (or blocking) rather than measurement noise
+pub const SCHEDULING_INTERFERENCE_PERCENT: f64 = 10.0;
+
+/// Sampler intervals a measurement must last before its snapshot coverage
+/// means anything; a shorter one only ever gets the start and end readings
+const COVERABLE_INTERVALS: u32 = 4;
 
 #[derive(Debug, Clone)]
 pub struct FrequencyStats {
@@ -36,6 +47,16 @@ pub enum CpuWarning {
         max_available_mhz: f64,
         percent_of_max: f64,
     },
+    SchedulingInterference {
+        wall_mean_ns: f64,
+        cpu_mean_ns: f64,
+        divergence_percent: f64,
+    },
+    /// The scheduler switched the benchmark thread out during timed calls
+    Preemption {
+        samples_affected: u64,
+        total_switches: u64,
+    },
 }
 
 impl CpuWarning {
@@ -74,7 +95,106 @@ impl CpuWarning {
                     mean_mhz, percent_of_max, max_available_mhz
                 )
             }
+            CpuWarning::SchedulingInterference {
+                divergence_percent, ..
+            } => {
+                format!(
+                    "⚠ Wall time {:.0}% above CPU time (preempted or blocked; try clock = \"cpu\")",
+                    divergence_percent
+                )
+            }
+            CpuWarning::Preemption {
+                samples_affected,
+                total_switches,
+            } => {
+                format!(
+                    "⚠ Preempted during {} samples ({} involuntary context switches)",
+                    samples_affected, total_switches
+                )
+            }
+        }
+    }
+}
+
+/// Snapshots a run needs before its frequency and temperature readings are
+/// trusted to raise warnings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoverageRule {
+    pub min_snapshots: usize,
+    /// Fraction of the measurement the snapshots must span, first to last
+    pub min_span_fraction: f64,
+}
+
+impl Default for CoverageRule {
+    fn default() -> Self {
+        Self {
+            min_snapshots: 10,
+            min_span_fraction: 0.5,
+        }
+    }
+}
+
+/// How much of a measurement its CPU snapshots cover
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuCoverage {
+    pub snapshots: usize,
+    /// Time from the first snapshot to the last
+    pub span_ms: u64,
+    /// Length of the measurement, when known; 0 for one under a millisecond
+    pub duration_ms: Option<u64>,
+    /// Whether the snapshots meet the [`CoverageRule`]; when they don't, the
+    /// frequency and temperature warnings are suppressed
+    pub sufficient: bool,
+}
+
+impl CpuCoverage {
+    pub fn new(snapshots: &[CpuSnapshot], duration_ms: Option<u64>, rule: CoverageRule) -> Self {
+        let span_ms = match (snapshots.first(), snapshots.last()) {
+            (Some(first), Some(last)) => last
+                .elapsed_ms_from_start
+                .saturating_sub(first.elapsed_ms_from_start),
+            _ => 0,
+        };
+        let mut coverage = Self {
+            snapshots: snapshots.len(),
+            span_ms,
+            duration_ms,
+            sufficient: false,
+        };
+        coverage.sufficient = coverage.snapshots >= rule.min_snapshots
+            && coverage
+                .span_fraction()
+                .map_or(true, |fraction| fraction >= rule.min_span_fraction);
+        coverage
+    }
+
+    /// Fraction of the measurement spanned, None when its length is unknown
+    /// or under a millisecond
+    pub fn span_fraction(&self) -> Option<f64> {
+        self.duration_ms
+            .filter(|&duration| duration > 0)
+            .map(|duration| (self.span_ms as f64 / duration as f64).min(1.0))
+    }
+
+    /// The measurement lasted at least [`COVERABLE_INTERVALS`] intervals of
+    /// the [`CpuSampler`](crate::CpuSampler), or its length is unknown
+    pub fn coverable(&self) -> bool {
+        self.duration_ms.map_or(true, |duration| {
+            u128::from(duration) >= (CPU_SAMPLE_INTERVAL * COVERABLE_INTERVALS).as_millis()
+        })
+    }
+
+    /// e.g. "12 snapshots over 1.2s (96% of the measurement)"
+    pub fn format(&self) -> String {
+        let mut line = format!(
+            "{} snapshots over {}",
+            self.snapshots,
+            format_duration_compact(Duration::from_millis(self.span_ms))
+        );
+        if let Some(fraction) = self.span_fraction() {
+            line.push_str(&format!(" ({:.0}% of the measurement)", fraction * 100.0));
         }
+        line
     }
 }
 
@@ -82,13 +202,25 @@ impl CpuWarning {
 pub struct CpuAnalysis {
     pub frequency_stats: Option<FrequencyStats>,
     pub temperature_stats: Option<TemperatureStats>,
+    pub coverage: CpuCoverage,
     pub warnings: Vec<CpuWarning>,
 }
 
 impl CpuAnalysis {
-    /// Analyze CPU snapshots and detect anomalies
-    pub fn from_snapshots(snapshots: &[CpuSnapshot], max_freq_khz: Option<u64>) -> Self {
+    /// Analyze CPU snapshots of a measurement lasting `duration_ms` and detect
+    /// anomalies
+    ///
+    /// Frequency and temperature warnings compare readings against each other
+    /// and need the coverage `rule` asks for; preemption comes from the
+    /// thread's counters over every sample and is reported regardless.
+    pub fn from_snapshots(
+        snapshots: &[CpuSnapshot],
+        max_freq_khz: Option<u64>,
+        duration_ms: Option<u64>,
+        rule: CoverageRule,
+    ) -> Self {
         let mut warnings = Vec::new();
+        let coverage = CpuCoverage::new(snapshots, duration_ms, rule);
 
         // Collect frequency data
         let frequencies: Vec<f64> = snapshots.iter().filter_map(|s| s.frequency_mhz()).collect();
@@ -193,13 +325,52 @@ impl CpuAnalysis {
             None
         };
 
+        if !coverage.sufficient {
+            warnings.clear();
+        }
+
+        // Detect preemption (any involuntary switch inside a timed call)
+        let preempted: Vec<(u64, u64)> = snapshots
+            .iter()
+            .filter_map(|s| Some((s.preempted_samples?, s.thread_usage?.involuntary_switches)))
+            .collect();
+        let total_switches: u64 = preempted.iter().map(|(_, switches)| switches).sum();
+        if total_switches > 0 {
+            warnings.push(CpuWarning::Preemption {
+                samples_affected: preempted.iter().map(|(samples, _)| samples).sum(),
+                total_switches,
+            });
+        }
+
         CpuAnalysis {
             frequency_stats,
             temperature_stats,
+            coverage,
             warnings,
         }
     }
 
+    /// Add a [`CpuWarning::SchedulingInterference`] if mean wall time exceeds the
+    /// mean thread CPU time by more than [`SCHEDULING_INTERFERENCE_PERCENT`]
+    pub fn with_cpu_time(mut self, wall_mean_ns: f64, cpu_mean_ns: f64) -> Self {
+        self.warnings
+            .extend(scheduling_interference(wall_mean_ns, cpu_mean_ns));
+        self
+    }
+
+    /// Note printed instead of the frequency and temperature warnings when
+    /// the snapshots don't cover enough of the measurement; None for one too
+    /// short for the sampler to cover at all
+    pub fn insufficient_samples_note(&self) -> Option<String> {
+        (self.coverage.snapshots > 0 && !self.coverage.sufficient && self.coverage.coverable())
+            .then(|| {
+                format!(
+                    "insufficient CPU samples for analysis ({})",
+                    self.coverage.format()
+                )
+            })
+    }
+
     /// Format stats as a single-line string
     pub fn format_stats_line(&self) -> Option<String> {
         let mut parts = Vec::new();
@@ -226,6 +397,25 @@ impl CpuAnalysis {
     }
 }
 
+/// Warn when the wall-clock mean is well above the CPU-time mean of the same samples.
+///
+/// Time the thread spends off-CPU (preempted on a shared machine, or blocked)
+/// counts towards wall time only.
+pub fn scheduling_interference(wall_mean_ns: f64, cpu_mean_ns: f64) -> Option<CpuWarning> {
+    if cpu_mean_ns <= 0.0 {
+        return None;
+    }
+
+    let divergence_percent = (wall_mean_ns - cpu_mean_ns) / cpu_mean_ns * 100.0;
+    (divergence_percent > SCHEDULING_INTERFERENCE_PERCENT).then_some(
+        CpuWarning::SchedulingInterference {
+            wall_mean_ns,
+            cpu_mean_ns,
+            divergence_percent,
+        },
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -236,22 +426,35 @@ mod tests {
         let snapshots = vec![
             CpuSnapshot {
                 timestamp: Instant::now(),
+                elapsed_ms_from_start: 0,
                 frequency_khz: Some(4_000_000), // 4000 MHz
                 temperature_millic: None,
+                thread_usage: None,
+                preempted_samples: None,
+                energy_uj: None,
             },
             CpuSnapshot {
                 timestamp: Instant::now(),
+                elapsed_ms_from_start: 0,
                 frequency_khz: Some(4_500_000), // 4500 MHz
                 temperature_millic: None,
+                thread_usage: None,
+                preempted_samples: None,
+                energy_uj: None,
             },
             CpuSnapshot {
                 timestamp: Instant::now(),
+                elapsed_ms_from_start: 0,
                 frequency_khz: Some(4_600_000), // 4600 MHz
                 temperature_millic: None,
+                thread_usage: None,
+                preempted_samples: None,
+                energy_uj: None,
             },
         ];
 
-        let analysis = CpuAnalysis::from_snapshots(&snapshots, Some(5_000_000));
+        let analysis =
+            CpuAnalysis::from_snapshots(&snapshots, Some(5_000_000), None, CoverageRule::default());
 
         assert!(analysis.frequency_stats.is_some());
         let freq_stats = analysis.frequency_stats.unwrap();
@@ -260,43 +463,41 @@ mod tests {
         assert!((freq_stats.mean_mhz - 4366.67).abs() < 1.0);
     }
 
+    /// One snapshot every 100ms: the readings of a one-second measurement
+    fn every_100ms(frequency_khz: &[u64], temperature_millic: &[i32]) -> Vec<CpuSnapshot> {
+        let count = frequency_khz.len().max(temperature_millic.len());
+        (0..count)
+            .map(|i| CpuSnapshot {
+                elapsed_ms_from_start: i as u64 * 100,
+                frequency_khz: frequency_khz.get(i).copied(),
+                temperature_millic: temperature_millic.get(i).copied(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn analyze(snapshots: &[CpuSnapshot]) -> CpuAnalysis {
+        CpuAnalysis::from_snapshots(snapshots, None, Some(1000), CoverageRule::default())
+    }
+
     #[test]
     fn test_cold_start_detection() {
-        let snapshots = vec![
-            CpuSnapshot {
-                timestamp: Instant::now(),
-                frequency_khz: None,
-                temperature_millic: Some(45_000), // 45°C - cold start
-            },
-            CpuSnapshot {
-                timestamp: Instant::now(),
-                frequency_khz: None,
-                temperature_millic: Some(55_000), // 55°C
-            },
-        ];
-
-        let analysis = CpuAnalysis::from_snapshots(&snapshots, None);
+        // 45°C, then 55°C
+        let mut temps = vec![55_000; 10];
+        temps[0] = 45_000;
+        let analysis = analyze(&every_100ms(&[], &temps));
 
+        assert!(analysis.coverage.sufficient);
         assert!(!analysis.warnings.is_empty());
         assert!(matches!(analysis.warnings[0], CpuWarning::ColdStart { .. }));
     }
 
     #[test]
     fn test_frequency_variance_detection() {
-        let snapshots = vec![
-            CpuSnapshot {
-                timestamp: Instant::now(),
-                frequency_khz: Some(2_000_000), // 2000 MHz
-                temperature_millic: None,
-            },
-            CpuSnapshot {
-                timestamp: Instant::now(),
-                frequency_khz: Some(4_500_000), // 4500 MHz - large variance
-                temperature_millic: None,
-            },
-        ];
-
-        let analysis = CpuAnalysis::from_snapshots(&snapshots, None);
+        // 2000 MHz, then 4500 MHz - large variance
+        let mut frequencies = vec![4_500_000; 10];
+        frequencies[0] = 2_000_000;
+        let analysis = analyze(&every_100ms(&frequencies, &[]));
 
         assert!(!analysis.warnings.is_empty());
         let has_variance_warning = analysis
@@ -308,20 +509,10 @@ mod tests {
 
     #[test]
     fn test_thermal_throttling_detection() {
-        let snapshots = vec![
-            CpuSnapshot {
-                timestamp: Instant::now(),
-                frequency_khz: None,
-                temperature_millic: Some(60_000), // 60°C
-            },
-            CpuSnapshot {
-                timestamp: Instant::now(),
-                frequency_khz: None,
-                temperature_millic: Some(90_000), // 90°C - throttling
-            },
-        ];
-
-        let analysis = CpuAnalysis::from_snapshots(&snapshots, None);
+        // 60°C, then 90°C - throttling
+        let mut temps = vec![90_000; 10];
+        temps[0] = 60_000;
+        let analysis = analyze(&every_100ms(&[], &temps));
 
         assert!(!analysis.warnings.is_empty());
         let has_throttling_warning = analysis
@@ -330,4 +521,123 @@ mod tests {
             .any(|w| matches!(w, CpuWarning::ThermalThrottling { .. }));
         assert!(has_throttling_warning);
     }
+
+    #[test]
+    fn test_insufficient_coverage_suppresses_warnings() {
+        // Two readings: a 45°C cold start and 90°C throttling, but nothing between
+        let analysis = analyze(&every_100ms(&[2_000_000, 4_500_000], &[45_000, 90_000]));
+        assert!(!analysis.coverage.sufficient);
+        assert!(analysis.warnings.is_empty());
+        assert!(analysis.temperature_stats.is_some());
+        assert_eq!(
+            analysis.insufficient_samples_note().unwrap(),
+            "insufficient CPU samples for analysis (2 snapshots over 100ms (10% of the measurement))"
+        );
+
+        // Enough snapshots, all within the first fifth of the measurement
+        let mut temps = vec![90_000; 20];
+        temps[0] = 60_000;
+        let snapshots: Vec<CpuSnapshot> = every_100ms(&[], &temps)
+            .into_iter()
+            .map(|s| CpuSnapshot {
+                elapsed_ms_from_start: s.elapsed_ms_from_start / 10,
+                ..s
+            })
+            .collect();
+        let analysis = analyze(&snapshots);
+        assert_eq!(analysis.coverage.span_ms, 190);
+        assert!(!analysis.coverage.sufficient);
+        assert!(analysis.warnings.is_empty());
+
+        // The same snapshots over a measurement of unknown length only need the count
+        let analysis = CpuAnalysis::from_snapshots(&snapshots, None, None, CoverageRule::default());
+        assert!(analysis.coverage.sufficient);
+        assert!(analysis.insufficient_samples_note().is_none());
+        assert!(!analysis.warnings.is_empty());
+
+        // No snapshots at all isn't worth a note
+        assert!(analyze(&[]).insufficient_samples_note().is_none());
+
+        // Nor is a run too short for the sampler: it only gets its start and end
+        let start_and_end = [
+            CpuSnapshot::default(),
+            CpuSnapshot {
+                elapsed_ms_from_start: 1,
+                ..Default::default()
+            },
+        ];
+        for duration_ms in [0, 1] {
+            let analysis = CpuAnalysis::from_snapshots(
+                &start_and_end,
+                None,
+                Some(duration_ms),
+                CoverageRule::default(),
+            );
+            assert!(!analysis.coverage.sufficient && !analysis.coverage.coverable());
+            assert!(analysis.insufficient_samples_note().is_none());
+        }
+    }
+
+    #[test]
+    fn test_scheduling_interference_detection() {
+        // 12% more wall than CPU time
+        let analysis = analyze(&[]).with_cpu_time(1_120.0, 1_000.0);
+        assert!(matches!(
+            analysis.warnings.as_slice(),
+            [CpuWarning::SchedulingInterference { divergence_percent, .. }]
+                if (*divergence_percent - 12.0).abs() < 1e-9
+        ));
+
+        assert!(scheduling_interference(1_050.0, 1_000.0).is_none());
+        assert!(scheduling_interference(1_000.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_preemption_detection() {
+        let mut snapshots: Vec<CpuSnapshot> = [0, 50, 100]
+            .iter()
+            .map(|&ms| CpuSnapshot {
+                elapsed_ms_from_start: ms,
+                ..Default::default()
+            })
+            .collect();
+        let switched = |involuntary_switches| crate::ThreadUsage {
+            involuntary_switches,
+            minor_faults: 1,
+            ..Default::default()
+        };
+
+        // Samples at 10ms and 60ms were switched out, the rest weren't
+        crate::cpu_monitor::attribute_thread_usage(
+            &mut snapshots,
+            &[0, 10, 40, 60, 120],
+            &[
+                switched(0),
+                switched(2),
+                switched(0),
+                switched(1),
+                switched(0),
+            ],
+        );
+        assert_eq!(snapshots[0].preempted_samples, Some(0));
+        assert_eq!(snapshots[1].preempted_samples, Some(1));
+        assert_eq!(snapshots[1].thread_usage.unwrap().minor_faults, 2);
+        assert_eq!(snapshots[2].preempted_samples, Some(1));
+        assert_eq!(snapshots[2].thread_usage.unwrap().minor_faults, 2);
+
+        let analysis = analyze(&snapshots);
+        assert!(matches!(
+            analysis.warnings.as_slice(),
+            [CpuWarning::Preemption {
+                samples_affected: 2,
+                total_switches: 3
+            }]
+        ));
+
+        // Counters without switches, or no counters at all, don't warn
+        let mut quiet = vec![CpuSnapshot::default()];
+        crate::cpu_monitor::attribute_thread_usage(&mut quiet, &[0], &[switched(0)]);
+        assert!(analyze(&quiet).warnings.is_empty());
+        assert!(analyze(&[CpuSnapshot::default()]).warnings.is_empty());
+    }
 }
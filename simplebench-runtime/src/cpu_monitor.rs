@@ -3,8 +3,142 @@
 //! Provides CPU frequency and thermal monitoring on Linux via sysfs.
 //! Gracefully degrades on non-Linux platforms.
 
+use crate::energy::RaplDomain;
 use std::fs;
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Interval between readings of a [`CpuSampler`]
+pub const CPU_SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Interval between temperature readings while cooling down between benchmarks
+const COOLDOWN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// CPU time consumed by the calling thread so far (returns None if unavailable)
+///
+/// Uses `CLOCK_THREAD_CPUTIME_ID`, so time spent blocked or sleeping doesn't count.
+#[inline(always)]
+pub fn thread_cpu_time() -> Option<Duration> {
+    #[cfg(unix)]
+    {
+        let mut ts = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        // SAFETY: `ts` is a valid, writable timespec for the duration of the call
+        let ret = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) };
+        if ret != 0 {
+            return None;
+        }
+        Some(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+    }
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+/// Scheduler and page-fault counters of a thread, from `getrusage(RUSAGE_THREAD)`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ThreadUsage {
+    /// The thread gave up the CPU (blocked or yielded)
+    pub voluntary_switches: u64,
+    /// The scheduler took the CPU away (preemption)
+    pub involuntary_switches: u64,
+    /// Page faults served without I/O
+    pub minor_faults: u64,
+    /// Page faults that needed I/O
+    pub major_faults: u64,
+}
+
+impl ThreadUsage {
+    /// Counts between `start` and this reading
+    pub(crate) fn since(self, start: ThreadUsage) -> ThreadUsage {
+        ThreadUsage {
+            voluntary_switches: self
+                .voluntary_switches
+                .saturating_sub(start.voluntary_switches),
+            involuntary_switches: self
+                .involuntary_switches
+                .saturating_sub(start.involuntary_switches),
+            minor_faults: self.minor_faults.saturating_sub(start.minor_faults),
+            major_faults: self.major_faults.saturating_sub(start.major_faults),
+        }
+    }
+
+    fn add(&mut self, other: ThreadUsage) {
+        self.voluntary_switches += other.voluntary_switches;
+        self.involuntary_switches += other.involuntary_switches;
+        self.minor_faults += other.minor_faults;
+        self.major_faults += other.major_faults;
+    }
+}
+
+/// Counters of the calling thread so far (returns None if unavailable)
+///
+/// `RUSAGE_THREAD` is Linux-only; other platforms always return None.
+#[inline(always)]
+pub fn thread_usage() -> Option<ThreadUsage> {
+    #[cfg(target_os = "linux")]
+    {
+        // SAFETY: an all-zero rusage is a valid value of the plain C struct
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        // SAFETY: `usage` is a valid, writable rusage for the duration of the call
+        let ret = unsafe { libc::getrusage(libc::RUSAGE_THREAD, &mut usage) };
+        if ret != 0 {
+            return None;
+        }
+        Some(ThreadUsage {
+            voluntary_switches: usage.ru_nvcsw as u64,
+            involuntary_switches: usage.ru_nivcsw as u64,
+            minor_faults: usage.ru_minflt as u64,
+            major_faults: usage.ru_majflt as u64,
+        })
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Add each sample's counters to the snapshot that closes its interval.
+///
+/// A sample starting at `offsets_ms[i]` belongs to the first snapshot taken at
+/// or after that offset (the last snapshot if none is), so each snapshot holds
+/// the counters of the samples since the previous one. Does nothing unless
+/// every sample has counters.
+pub(crate) fn attribute_thread_usage(
+    snapshots: &mut [CpuSnapshot],
+    offsets_ms: &[u64],
+    usage: &[ThreadUsage],
+) {
+    if snapshots.is_empty() || usage.is_empty() || usage.len() != offsets_ms.len() {
+        return;
+    }
+
+    for snapshot in snapshots.iter_mut() {
+        snapshot.thread_usage = Some(ThreadUsage::default());
+        snapshot.preempted_samples = Some(0);
+    }
+    let last = snapshots.len() - 1;
+    for (&offset, &sample) in offsets_ms.iter().zip(usage) {
+        let idx = snapshots
+            .iter()
+            .position(|s| s.elapsed_ms_from_start >= offset)
+            .unwrap_or(last);
+        let snapshot = &mut snapshots[idx];
+        if let Some(total) = snapshot.thread_usage.as_mut() {
+            total.add(sample);
+        }
+        if sample.involuntary_switches > 0 {
+            if let Some(count) = snapshot.preempted_samples.as_mut() {
+                *count += 1;
+            }
+        }
+    }
+}
 
 /// Monitor for a specific CPU core
 pub struct CpuMonitor {
@@ -123,8 +257,21 @@ impl CpuMonitor {
 pub struct CpuSnapshot {
     #[serde(skip, default = "Instant::now")]
     pub timestamp: Instant,
+    /// Milliseconds since measurement started; unlike `timestamp`, this
+    /// survives a round trip through stored baselines
+    #[serde(default)]
+    pub elapsed_ms_from_start: u64,
     pub frequency_khz: Option<u64>,
     pub temperature_millic: Option<i32>,
+    /// Counters of the timed calls since the previous snapshot (Linux)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_usage: Option<ThreadUsage>,
+    /// Timed calls since the previous snapshot that were involuntarily switched out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preempted_samples: Option<u64>,
+    /// Package energy since the previous snapshot in microjoules (RAPL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub energy_uj: Option<u64>,
 }
 
 impl CpuSnapshot {
@@ -143,12 +290,197 @@ impl Default for CpuSnapshot {
     fn default() -> Self {
         Self {
             timestamp: Instant::now(),
+            elapsed_ms_from_start: 0,
             frequency_khz: None,
             temperature_millic: None,
+            thread_usage: None,
+            preempted_samples: None,
+            energy_uj: None,
         }
     }
 }
 
+/// The snapshot taken closest to `elapsed_ms` into the measurement
+pub fn snapshot_at(snapshots: &[CpuSnapshot], elapsed_ms: u64) -> Option<&CpuSnapshot> {
+    snapshots
+        .iter()
+        .min_by_key(|s| s.elapsed_ms_from_start.abs_diff(elapsed_ms))
+}
+
+/// Background thread reading frequency, temperature and package energy of a core at a fixed interval.
+///
+/// Keeps sysfs reads out of the measurement loop: the benchmark thread only
+/// starts the sampler before measuring and collects the time-ordered snapshots
+/// afterwards. The thread moves itself off the observed core where possible,
+/// so its wakeups don't land on the benchmark.
+pub struct CpuSampler {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<Vec<CpuSnapshot>>>,
+}
+
+impl CpuSampler {
+    /// Start sampling `cpu_core` every `interval`, taking the first reading
+    /// immediately; offsets are counted from `origin`
+    pub fn start(cpu_core: usize, interval: Duration, origin: Instant) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = std::thread::Builder::new()
+            .name("simplebench-cpu-sampler".to_string())
+            .spawn(move || {
+                let others: Vec<usize> = (0..affinity::get_core_num())
+                    .filter(|&core| core != cpu_core)
+                    .collect();
+                if !others.is_empty() {
+                    let _ = affinity::set_thread_affinity(&others);
+                }
+
+                let monitor = CpuMonitor::new(cpu_core);
+                let rapl = RaplDomain::for_core(cpu_core);
+                let mut last_energy = rapl.as_ref().and_then(RaplDomain::read_uj);
+                let mut read = || {
+                    let timestamp = Instant::now();
+                    let energy = rapl.as_ref().and_then(RaplDomain::read_uj);
+                    let energy_uj = match (&rapl, last_energy, energy) {
+                        (Some(rapl), Some(start), Some(end)) => Some(rapl.delta_uj(start, end)),
+                        _ => None,
+                    };
+                    if energy.is_some() {
+                        last_energy = energy;
+                    }
+                    CpuSnapshot {
+                        timestamp,
+                        elapsed_ms_from_start: u64::try_from(
+                            timestamp.saturating_duration_since(origin).as_millis(),
+                        )
+                        .unwrap_or(u64::MAX),
+                        frequency_khz: monitor.read_frequency(),
+                        temperature_millic: monitor.read_temperature(),
+                        thread_usage: None,
+                        preempted_samples: None,
+                        energy_uj,
+                    }
+                };
+
+                // The opening reading is taken even if sampling is already
+                // stopped, so every run has both ends
+                let mut snapshots = vec![read()];
+                let mut next = snapshots[0].timestamp + interval;
+                while !thread_stop.load(Ordering::Acquire) {
+                    let now = Instant::now();
+                    if now >= next {
+                        snapshots.push(read());
+                        next += interval;
+                    } else {
+                        std::thread::park_timeout(next - now);
+                    }
+                }
+                // A closing reading, so short runs still see the end state
+                snapshots.push(read());
+                snapshots
+            })
+            .ok();
+
+        Self { stop, handle }
+    }
+
+    /// Stop sampling and return the snapshots, oldest first
+    pub fn finish(mut self) -> Vec<CpuSnapshot> {
+        self.stop_thread().unwrap_or_default()
+    }
+
+    fn stop_thread(&mut self) -> Option<Vec<CpuSnapshot>> {
+        let handle = self.handle.take()?;
+        self.stop.store(true, Ordering::Release);
+        handle.thread().unpark();
+        handle.join().ok()
+    }
+}
+
+impl Drop for CpuSampler {
+    fn drop(&mut self) {
+        self.stop_thread();
+    }
+}
+
+/// How a wait for the CPU to cool down between benchmarks went
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cooldown {
+    pub start_celsius: f64,
+    pub end_celsius: f64,
+    pub waited: Duration,
+    /// The temperature dropped below the ceiling before the timeout ran out
+    pub reached: bool,
+}
+
+impl Cooldown {
+    /// One-line description ("cooled down from 84°C to 58°C in 6.2s")
+    pub fn format(&self) -> String {
+        if self.reached {
+            format!(
+                "cooled down from {:.0}°C to {:.0}°C in {:.1}s",
+                self.start_celsius,
+                self.end_celsius,
+                self.waited.as_secs_f64()
+            )
+        } else {
+            format!(
+                "still {:.0}°C after {:.1}s cooldown (from {:.0}°C), continuing",
+                self.end_celsius,
+                self.waited.as_secs_f64(),
+                self.start_celsius
+            )
+        }
+    }
+}
+
+/// Wait until the CPU temperature drops below `max_celsius`, for at most `timeout`.
+///
+/// Returns None without waiting when `timeout` is zero, when the temperature
+/// can't be read, or when the CPU is already cool enough.
+pub fn cool_down(monitor: &CpuMonitor, max_celsius: f64, timeout: Duration) -> Option<Cooldown> {
+    wait_until_cool(
+        || monitor.read_temperature(),
+        max_celsius,
+        timeout,
+        COOLDOWN_POLL_INTERVAL,
+    )
+}
+
+fn wait_until_cool(
+    mut read_millic: impl FnMut() -> Option<i32>,
+    max_celsius: f64,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> Option<Cooldown> {
+    if timeout.is_zero() {
+        return None;
+    }
+    let start_celsius = read_millic()? as f64 / 1000.0;
+    if start_celsius < max_celsius {
+        return None;
+    }
+
+    let start = Instant::now();
+    let mut end_celsius = start_celsius;
+    while start.elapsed() < timeout {
+        std::thread::sleep(poll_interval.min(timeout.saturating_sub(start.elapsed())));
+        match read_millic() {
+            Some(millic) => end_celsius = millic as f64 / 1000.0,
+            None => break,
+        }
+        if end_celsius < max_celsius {
+            break;
+        }
+    }
+
+    Some(Cooldown {
+        start_celsius,
+        end_celsius,
+        waited: start.elapsed(),
+        reached: end_celsius < max_celsius,
+    })
+}
+
 /// Verify and report benchmark environment
 pub fn verify_benchmark_environment(cpu_core: usize) {
     use colored::*;
@@ -289,14 +621,83 @@ mod tests {
     fn test_cpu_snapshot() {
         let snapshot = CpuSnapshot {
             timestamp: Instant::now(),
+            elapsed_ms_from_start: 0,
             frequency_khz: Some(4500000),
             temperature_millic: Some(55000),
+            thread_usage: None,
+            preempted_samples: None,
+            energy_uj: None,
         };
 
         assert_eq!(snapshot.frequency_mhz(), Some(4500.0));
         assert_eq!(snapshot.temperature_celsius(), Some(55.0));
     }
 
+    #[test]
+    fn test_cpu_sampler_collects_time_ordered_snapshots() {
+        let sampler = CpuSampler::start(0, Duration::from_millis(5), Instant::now());
+        std::thread::sleep(Duration::from_millis(30));
+        let snapshots = sampler.finish();
+
+        assert!(snapshots.len() >= 2);
+        assert!(snapshots.windows(2).all(|pair| {
+            pair[0].timestamp <= pair[1].timestamp
+                && pair[0].elapsed_ms_from_start <= pair[1].elapsed_ms_from_start
+        }));
+        assert!(snapshots.last().unwrap().elapsed_ms_from_start >= 30);
+    }
+
+    #[test]
+    fn test_snapshot_at_picks_nearest_offset() {
+        let snapshots: Vec<CpuSnapshot> = [0, 50, 100]
+            .iter()
+            .map(|&ms| CpuSnapshot {
+                elapsed_ms_from_start: ms,
+                temperature_millic: Some(ms as i32 * 1000),
+                ..Default::default()
+            })
+            .collect();
+
+        assert_eq!(
+            snapshot_at(&snapshots, 60).unwrap().elapsed_ms_from_start,
+            50
+        );
+        assert_eq!(
+            snapshot_at(&snapshots, 90).unwrap().elapsed_ms_from_start,
+            100
+        );
+        assert!(snapshot_at(&[], 10).is_none());
+    }
+
+    #[test]
+    fn test_cooldown_waits_for_ceiling_or_timeout() {
+        let never = Duration::from_secs(60);
+
+        // Cooling by 10°C per reading: the third reading is below 60°C
+        let mut temps = [80_000, 70_000, 55_000].into_iter();
+        let cooldown = wait_until_cool(|| temps.next(), 60.0, never, Duration::ZERO).unwrap();
+        assert!(cooldown.reached);
+        assert_eq!(cooldown.start_celsius, 80.0);
+        assert_eq!(cooldown.end_celsius, 55.0);
+
+        // Already cool, or unreadable: no wait at all
+        assert!(wait_until_cool(|| Some(50_000), 60.0, never, Duration::ZERO).is_none());
+        assert!(wait_until_cool(|| None, 60.0, never, Duration::ZERO).is_none());
+        assert!(wait_until_cool(|| Some(90_000), 60.0, Duration::ZERO, Duration::ZERO).is_none());
+
+        // Never cools: gives up after the timeout
+        let hot = wait_until_cool(
+            || Some(90_000),
+            60.0,
+            Duration::from_millis(20),
+            Duration::from_millis(5),
+        )
+        .unwrap();
+        assert!(!hot.reached);
+        assert!(hot.waited >= Duration::from_millis(20));
+        assert!(hot.format().starts_with("still 90°C"));
+    }
+
     #[test]
     fn test_verify_environment() {
         // Should not panic on any platform
@@ -0,0 +1,773 @@
+//! Disk usage accounting and size-based pruning of `.benches`.
+//!
+//! Sizes are cached per benchmark directory in `.benches/usage.json`, keyed by
+//! the directory's modification time and run count, so only benchmarks whose
+//! runs changed since the last measurement are rescanned. The index also keeps
+//! the total recorded after each run, from which the growth rate is derived.
+//!
+//! Retention limits prune each benchmark's runs by count and age instead,
+//! after every save or from `cargo simplebench clean`.
+//!
+//! Benchmark directories no benchmark of the current build stores its runs
+//! in are found and removed whole (`cargo simplebench clean --stale`).
+//!
+//! Legacy single-file baselines directly under a machine directory are not
+//! counted or pruned.
+
+use crate::baseline::BaselineManager;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Cache file at the root of the `.benches` tree
+const USAGE_INDEX_FILE: &str = "usage.json";
+
+/// Totals kept for the growth rate, one per recorded run
+const USAGE_HISTORY_LEN: usize = 30;
+
+/// Bytes per megabyte of `max_disk_mb`
+pub const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Cached sizes of the tree, stored in [`USAGE_INDEX_FILE`]
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UsageIndex {
+    /// Keyed by `<machine>/<crate>_<benchmark>`
+    #[serde(default)]
+    benchmarks: BTreeMap<String, CachedUsage>,
+    /// Totals after recent runs, oldest first
+    #[serde(default)]
+    history: Vec<UsageSample>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedUsage {
+    bytes: u64,
+    runs: usize,
+    modified_ns: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UsageSample {
+    timestamp: DateTime<Utc>,
+    bytes: u64,
+}
+
+/// Disk used by one benchmark's runs on one machine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchmarkUsage {
+    pub machine_id: String,
+    /// `<crate>_<benchmark>` directory name
+    pub benchmark: String,
+    pub bytes: u64,
+    pub runs: usize,
+}
+
+/// Disk used by a `.benches` tree
+#[derive(Debug, Clone, Default)]
+pub struct DiskUsage {
+    /// Largest first
+    pub benchmarks: Vec<BenchmarkUsage>,
+    /// Total recorded after the previous run, if any
+    pub previous_bytes: Option<u64>,
+    /// Growth in bytes per day over the recorded history; None with under an
+    /// hour of history
+    pub growth_per_day: Option<f64>,
+}
+
+impl DiskUsage {
+    pub fn total_bytes(&self) -> u64 {
+        self.benchmarks.iter().map(|b| b.bytes).sum()
+    }
+
+    pub fn total_runs(&self) -> usize {
+        self.benchmarks.iter().map(|b| b.runs).sum()
+    }
+}
+
+/// Runs removed by [`BaselineManager::prune_to_size`] or
+/// [`BaselineManager::prune`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    pub removed_runs: usize,
+    pub freed_bytes: u64,
+}
+
+/// A benchmark directory of this machine whose benchmark is no longer built,
+/// left behind by a rename or deletion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleBaseline {
+    /// `<crate>_<benchmark>` directory name
+    pub benchmark: String,
+    pub runs: usize,
+    pub bytes: u64,
+}
+
+/// Count and age limits on the runs kept per benchmark
+///
+/// Runs beyond either limit are deleted oldest first, except regression-flagged
+/// ones: they never feed a comparison window, and they are the evidence.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RetentionPolicy {
+    /// Newest runs kept
+    pub max_runs: Option<usize>,
+    /// Runs older than this many days are deleted
+    pub max_age_days: Option<f64>,
+}
+
+impl RetentionPolicy {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_runs.is_none() && self.max_age_days.is_none()
+    }
+}
+
+/// Format a byte count with a binary suffix ("12.3 MiB")
+pub fn format_bytes(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    let value = bytes as f64;
+    if value >= KIB * KIB * KIB {
+        format!("{:.1} GiB", value / (KIB * KIB * KIB))
+    } else if value >= KIB * KIB {
+        format!("{:.1} MiB", value / (KIB * KIB))
+    } else if value >= KIB {
+        format!("{:.1} KiB", value / KIB)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+impl BaselineManager {
+    /// Measure the `.benches` tree across all machines
+    ///
+    /// Benchmark directories unchanged since the cached index aren't rescanned.
+    pub fn disk_usage(&self) -> io::Result<DiskUsage> {
+        let mut index = self.load_usage_index();
+        let usage = self.refresh_usage(&mut index)?;
+        self.save_usage_index(&index)?;
+        Ok(usage)
+    }
+
+    /// Measure the tree and record its total for the growth rate
+    ///
+    /// Call once per benchmark run.
+    pub fn record_disk_usage(&self) -> io::Result<DiskUsage> {
+        let mut index = self.load_usage_index();
+        let mut usage = self.refresh_usage(&mut index)?;
+
+        let now = Utc::now();
+        let total = usage.total_bytes();
+        usage.previous_bytes = index.history.last().map(|s| s.bytes);
+        usage.growth_per_day = index.history.first().and_then(|oldest| {
+            let days = (now - oldest.timestamp).num_seconds() as f64 / 86_400.0;
+            (days >= 1.0 / 24.0).then(|| (total as f64 - oldest.bytes as f64) / days)
+        });
+
+        index.history.push(UsageSample {
+            timestamp: now,
+            bytes: total,
+        });
+        let excess = index.history.len().saturating_sub(USAGE_HISTORY_LEN);
+        index.history.drain(..excess);
+
+        self.save_usage_index(&index)?;
+        Ok(usage)
+    }
+
+    /// Remove the oldest runs until the tree fits in `max_bytes`
+    ///
+    /// Runs are removed oldest first across all benchmarks and machines. The
+    /// newest `min_runs` runs of each benchmark are protected, so the limit may
+    /// stay exceeded when protected runs alone are larger.
+    ///
+    /// Machines whose directory another process holds the writer lock of
+    /// ([`crate::lock`]) are left alone.
+    pub fn prune_to_size(&self, max_bytes: u64, min_runs: usize) -> io::Result<PruneReport> {
+        let mut total = self.disk_usage()?.total_bytes();
+        let mut report = PruneReport::default();
+        if total <= max_bytes {
+            return Ok(report);
+        }
+
+        // (run file name, path, size) of every unprotected run
+        let mut candidates = Vec::new();
+        for (key, bench_dir) in self.benchmark_dirs()? {
+            let machine_id = key
+                .split_once('/')
+                .map_or(key.as_str(), |(machine, _)| machine);
+            if self.locked_by_other_process(machine_id) {
+                continue;
+            }
+            let mut runs = run_files(&bench_dir)?;
+            runs.sort();
+            runs.truncate(runs.len().saturating_sub(min_runs));
+            for name in runs {
+                let path = bench_dir.join(&name);
+                // Another pruner may have been first
+                let Some(size) = file_size(&path)? else {
+                    continue;
+                };
+                candidates.push((name, path, size));
+            }
+        }
+
+        // Run files are named by timestamp, so names order runs across benchmarks
+        candidates.sort();
+        for (_, path, size) in candidates {
+            if total <= max_bytes {
+                break;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+            total = total.saturating_sub(size);
+            report.removed_runs += 1;
+            report.freed_bytes += size;
+        }
+
+        self.disk_usage()?;
+        Ok(report)
+    }
+
+    /// Delete this machine's runs of one benchmark beyond `policy`
+    pub fn prune(
+        &self,
+        crate_name: &str,
+        benchmark_name: &str,
+        policy: RetentionPolicy,
+    ) -> io::Result<PruneReport> {
+        let bench_dir = self
+            .root_dir()
+            .join(self.machine_id())
+            .join(format!("{}_{}", crate_name, benchmark_name));
+        if !bench_dir.is_dir() {
+            return Ok(PruneReport::default());
+        }
+        prune_runs(&bench_dir, policy, Utc::now())
+    }
+
+    /// Delete the runs beyond `policy` of every benchmark and machine
+    ///
+    /// Machines whose directory another process holds the writer lock of are
+    /// left alone, as in [`BaselineManager::prune_to_size`].
+    pub fn prune_all(&self, policy: RetentionPolicy) -> io::Result<PruneReport> {
+        let now = Utc::now();
+        let mut report = PruneReport::default();
+        for (key, bench_dir) in self.benchmark_dirs()? {
+            let machine_id = key
+                .split_once('/')
+                .map_or(key.as_str(), |(machine, _)| machine);
+            if self.locked_by_other_process(machine_id) {
+                continue;
+            }
+            let pruned = prune_runs(&bench_dir, policy, now)?;
+            report.removed_runs += pruned.removed_runs;
+            report.freed_bytes += pruned.freed_bytes;
+        }
+
+        self.disk_usage()?;
+        Ok(report)
+    }
+
+    /// This machine's benchmark directories that none of `registered`, the
+    /// `(crate, stored name)` pairs of the current build
+    /// ([`crate::baseline::storage_name`]), stores its runs in
+    ///
+    /// A directory a registered benchmark was renamed from is still its
+    /// history ([`crate::config::ComparisonConfig::follow_renames`]) and
+    /// isn't stale, nor is the one it kept its runs in before it was stored
+    /// under its module path, until its next save moves them. Other
+    /// machines' directories are never reported.
+    pub fn stale_baselines<'a>(
+        &self,
+        registered: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> io::Result<Vec<StaleBaseline>> {
+        let machine_dir = self.root_dir().join(self.machine_id());
+        if !machine_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut live = BTreeSet::new();
+        for (crate_name, benchmark_name) in registered {
+            let mut name = benchmark_name.to_string();
+            // A name already seen had its markers followed, or closes a cycle
+            while live.insert(
+                self.benchmark_dir_for(self.machine_id(), crate_name, &name)
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+            ) {
+                match self.load_redirect(self.machine_id(), crate_name, &name) {
+                    Some(redirect) => name = redirect.redirect_from,
+                    None => break,
+                }
+            }
+        }
+
+        let mut stale = Vec::new();
+        for entry in fs::read_dir(&machine_dir)?.filter_map(|e| e.ok()) {
+            let benchmark = entry.file_name().to_string_lossy().to_string();
+            if !entry.path().is_dir() || live.contains(&benchmark) {
+                continue;
+            }
+            stale.push(StaleBaseline {
+                runs: run_files(&entry.path())?.len(),
+                bytes: dir_size(&entry.path())?,
+                benchmark,
+            });
+        }
+        stale.sort_by(|a, b| a.benchmark.cmp(&b.benchmark));
+        Ok(stale)
+    }
+
+    /// Delete the directories of `stale`, as found by
+    /// [`BaselineManager::stale_baselines`], with all their runs
+    pub fn remove_stale_baselines(&self, stale: &[StaleBaseline]) -> io::Result<PruneReport> {
+        let machine_dir = self.root_dir().join(self.machine_id());
+        let mut report = PruneReport::default();
+        for baseline in stale {
+            match fs::remove_dir_all(machine_dir.join(&baseline.benchmark)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+            report.removed_runs += baseline.runs;
+            report.freed_bytes += baseline.bytes;
+        }
+
+        self.disk_usage()?;
+        Ok(report)
+    }
+
+    /// Every benchmark directory of every machine, keyed `<machine>/<benchmark>`
+    pub(crate) fn benchmark_dirs(&self) -> io::Result<Vec<(String, std::path::PathBuf)>> {
+        let mut dirs = Vec::new();
+        for machine_id in self.list_machines()? {
+            let machine_dir = self.root_dir().join(&machine_id);
+            for entry in fs::read_dir(&machine_dir)?.filter_map(|e| e.ok()) {
+                if entry.path().is_dir() {
+                    let key = format!("{}/{}", machine_id, entry.file_name().to_string_lossy());
+                    dirs.push((key, entry.path()));
+                }
+            }
+        }
+        dirs.sort();
+        Ok(dirs)
+    }
+
+    /// Bring the index up to date with the tree and return the current usage
+    fn refresh_usage(&self, index: &mut UsageIndex) -> io::Result<DiskUsage> {
+        let mut benchmarks = BTreeMap::new();
+        for (key, bench_dir) in self.benchmark_dirs()? {
+            let modified_ns = fs::metadata(&bench_dir)?
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, crate::nanos_u64);
+            let runs = run_files(&bench_dir)?.len();
+
+            let cached = index
+                .benchmarks
+                .get(&key)
+                .filter(|c| c.modified_ns == modified_ns && c.runs == runs)
+                .cloned();
+            let usage = match cached {
+                Some(cached) => cached,
+                None => CachedUsage {
+                    bytes: dir_size(&bench_dir)?,
+                    runs,
+                    modified_ns,
+                },
+            };
+            benchmarks.insert(key, usage);
+        }
+        index.benchmarks = benchmarks;
+
+        let mut usage = DiskUsage {
+            benchmarks: index
+                .benchmarks
+                .iter()
+                .map(|(key, cached)| {
+                    let (machine_id, benchmark) = key.split_once('/').unwrap_or(("", key));
+                    BenchmarkUsage {
+                        machine_id: machine_id.to_string(),
+                        benchmark: benchmark.to_string(),
+                        bytes: cached.bytes,
+                        runs: cached.runs,
+                    }
+                })
+                .collect(),
+            ..Default::default()
+        };
+        usage.benchmarks.sort_by_key(|b| std::cmp::Reverse(b.bytes));
+        Ok(usage)
+    }
+
+    fn load_usage_index(&self) -> UsageIndex {
+        fs::read_to_string(self.root_dir().join(USAGE_INDEX_FILE))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_usage_index(&self, index: &UsageIndex) -> io::Result<()> {
+        if !self.root_dir().exists() {
+            return Ok(());
+        }
+        crate::lock::write_atomic(
+            &self.root_dir().join(USAGE_INDEX_FILE),
+            serde_json::to_string_pretty(index)?.as_bytes(),
+        )
+    }
+}
+
+/// File names of the stored runs in a benchmark directory, compressed or not
+pub(crate) fn run_files(bench_dir: &Path) -> io::Result<Vec<String>> {
+    Ok(fs::read_dir(bench_dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| crate::baseline::run_name(name).is_some())
+        .collect())
+}
+
+/// Delete the runs in `bench_dir` beyond `policy` as of `now`
+fn prune_runs(
+    bench_dir: &Path,
+    policy: RetentionPolicy,
+    now: DateTime<Utc>,
+) -> io::Result<PruneReport> {
+    let mut runs = run_files(bench_dir)?;
+    runs.sort();
+    let over_count = policy
+        .max_runs
+        .map_or(0, |max_runs| runs.len().saturating_sub(max_runs));
+    let cutoff = policy
+        .max_age_days
+        .map(|days| now - chrono::Duration::seconds((days * 86_400.0) as i64));
+
+    let mut report = PruneReport::default();
+    for (position, name) in runs.iter().enumerate() {
+        let path = bench_dir.join(name);
+        let too_old = cutoff.is_some_and(|cutoff| run_time(&path).is_some_and(|t| t < cutoff));
+        if position >= over_count && !too_old {
+            continue;
+        }
+        // Another pruner may have been first
+        let contents = match fs::read(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        let freed_bytes = contents.len() as u64;
+        let was_regression =
+            crate::baseline::decode_run(contents, crate::baseline::is_compressed(&path))
+                .ok()
+                .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+                .and_then(|run| run.get("was_regression")?.as_bool())
+                .unwrap_or(false);
+        if was_regression {
+            continue;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        }
+        report.removed_runs += 1;
+        report.freed_bytes += freed_bytes;
+    }
+    Ok(report)
+}
+
+/// When a run was saved, from its file name (`<timestamp>[_NN].json`, or
+/// `.json.gz`), or its modification time for a file not named by `save_baseline`
+pub(crate) fn run_time(path: &Path) -> Option<DateTime<Utc>> {
+    let file_name = path.file_name()?.to_string_lossy();
+    let stem = crate::baseline::run_name(&file_name).unwrap_or(&file_name);
+    let timestamp = stem.split('_').next()?;
+    match chrono::NaiveDateTime::parse_from_str(timestamp, crate::baseline::RUN_FILE_TIMESTAMP) {
+        Ok(time) => Some(time.and_utc()),
+        Err(_) => Some(fs::metadata(path).ok()?.modified().ok()?.into()),
+    }
+}
+
+/// Size of a file; None when it was removed meanwhile
+fn file_size(path: &Path) -> io::Result<Option<u64>> {
+    match fs::metadata(path) {
+        Ok(metadata) => Ok(Some(metadata.len())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Total size of the files in a directory, skipping any removed while it's read
+fn dir_size(dir: &Path) -> io::Result<u64> {
+    let mut bytes = 0;
+    for entry in fs::read_dir(dir)?.filter_map(|e| e.ok()) {
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        if metadata.is_file() {
+            bytes += metadata.len();
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Write a run of `bytes` bytes under `<machine>/<benchmark>/<timestamp>.json`
+    fn write_run(root: &Path, machine: &str, benchmark: &str, timestamp: &str, bytes: usize) {
+        let dir = root.join(machine).join(benchmark);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(format!("{}.json", timestamp)), vec![b' '; bytes]).unwrap();
+    }
+
+    fn remaining_runs(root: &Path, machine: &str, benchmark: &str) -> Vec<String> {
+        let mut runs = run_files(&root.join(machine).join(benchmark)).unwrap();
+        runs.sort();
+        runs
+    }
+
+    #[test]
+    fn test_disk_usage_per_benchmark() {
+        let temp = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp.path()).unwrap();
+        write_run(temp.path(), "m1", "c_small", "2025-01-01T00-00-00", 100);
+        write_run(temp.path(), "m1", "c_big", "2025-01-01T00-00-00", 300);
+        write_run(temp.path(), "m2", "c_big", "2025-01-02T00-00-00", 200);
+
+        let usage = manager.disk_usage().unwrap();
+        assert_eq!(usage.total_bytes(), 600);
+        assert_eq!(usage.total_runs(), 3);
+        assert_eq!(usage.benchmarks[0].benchmark, "c_big");
+        assert_eq!(usage.benchmarks[0].machine_id, "m1");
+        assert_eq!(usage.benchmarks[2].benchmark, "c_small");
+
+        // A new run is picked up despite the cached index
+        write_run(temp.path(), "m1", "c_small", "2025-01-03T00-00-00", 50);
+        assert_eq!(manager.disk_usage().unwrap().total_bytes(), 650);
+
+        // The first recorded total has nothing to compare against
+        let recorded = manager.record_disk_usage().unwrap();
+        assert_eq!(recorded.previous_bytes, None);
+        assert_eq!(recorded.growth_per_day, None);
+        let recorded = manager.record_disk_usage().unwrap();
+        assert_eq!(recorded.previous_bytes, Some(650));
+    }
+
+    #[test]
+    fn test_prune_removes_oldest_unprotected_runs_first() {
+        let temp = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp.path()).unwrap();
+        for day in 1..=4 {
+            let ts = format!("2025-01-0{}T00-00-00", day);
+            write_run(temp.path(), "m1", "c_a", &ts, 100);
+        }
+        write_run(temp.path(), "m1", "c_b", "2025-01-01T12-00-00", 100);
+        write_run(temp.path(), "m1", "c_b", "2025-01-05T00-00-00", 100);
+        write_run(temp.path(), "m2", "c_a", "2025-01-02T12-00-00", 100);
+
+        // 700 bytes: removing the three oldest unprotected runs gets to 400
+        let report = manager.prune_to_size(400, 1).unwrap();
+        assert_eq!(
+            report,
+            PruneReport {
+                removed_runs: 3,
+                freed_bytes: 300
+            }
+        );
+        assert_eq!(
+            remaining_runs(temp.path(), "m1", "c_a"),
+            ["2025-01-03T00-00-00.json", "2025-01-04T00-00-00.json"]
+        );
+        assert_eq!(
+            remaining_runs(temp.path(), "m1", "c_b"),
+            ["2025-01-05T00-00-00.json"]
+        );
+        assert_eq!(remaining_runs(temp.path(), "m2", "c_a").len(), 1);
+        assert_eq!(manager.disk_usage().unwrap().total_bytes(), 400);
+
+        // Under the limit: nothing to do
+        assert_eq!(
+            manager.prune_to_size(400, 1).unwrap(),
+            PruneReport::default()
+        );
+    }
+
+    #[test]
+    fn test_prune_never_removes_protected_runs() {
+        let temp = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp.path()).unwrap();
+        for day in 1..=3 {
+            let ts = format!("2025-01-0{}T00-00-00", day);
+            write_run(temp.path(), "m1", "c_a", &ts, 100);
+            write_run(temp.path(), "m1", "c_b", &ts, 100);
+        }
+
+        // Two protected runs per benchmark leave 400 bytes, above the limit
+        let report = manager.prune_to_size(0, 2).unwrap();
+        assert_eq!(report.removed_runs, 2);
+        assert_eq!(remaining_runs(temp.path(), "m1", "c_a").len(), 2);
+        assert_eq!(remaining_runs(temp.path(), "m1", "c_b").len(), 2);
+        assert_eq!(manager.disk_usage().unwrap().total_bytes(), 400);
+    }
+
+    #[test]
+    fn test_retention_keeps_newest_runs() {
+        let temp = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp.path()).unwrap();
+        let machine = manager.machine_id().to_string();
+        for day in 1..=5 {
+            let ts = format!("2025-01-0{}T00-00-00", day);
+            write_run(temp.path(), &machine, "c_a", &ts, 100);
+        }
+        write_run(temp.path(), "m2", "c_a", "2025-01-01T00-00-00", 100);
+
+        let policy = RetentionPolicy {
+            max_runs: Some(2),
+            max_age_days: None,
+        };
+        let report = manager.prune("c", "a", policy).unwrap();
+        assert_eq!(
+            report,
+            PruneReport {
+                removed_runs: 3,
+                freed_bytes: 300
+            }
+        );
+        assert_eq!(
+            remaining_runs(temp.path(), &machine, "c_a"),
+            ["2025-01-04T00-00-00.json", "2025-01-05T00-00-00.json"]
+        );
+        // Only this machine's runs of the benchmark
+        assert_eq!(remaining_runs(temp.path(), "m2", "c_a").len(), 1);
+
+        assert_eq!(manager.prune_all(policy).unwrap().removed_runs, 0);
+        assert_eq!(
+            manager.prune("c", "missing", policy).unwrap().removed_runs,
+            0
+        );
+    }
+
+    #[test]
+    fn test_retention_removes_old_runs_across_machines() {
+        let temp = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp.path()).unwrap();
+        let now = Utc::now();
+        for (machine, days_ago) in [("m1", 40), ("m1", 10), ("m2", 31), ("m2", 1)] {
+            let ts = (now - chrono::Duration::days(days_ago))
+                .format(crate::baseline::RUN_FILE_TIMESTAMP)
+                .to_string();
+            write_run(temp.path(), machine, "c_a", &ts, 100);
+        }
+
+        let policy = RetentionPolicy {
+            max_runs: None,
+            max_age_days: Some(30.0),
+        };
+        let report = manager.prune_all(policy).unwrap();
+        assert_eq!(report.removed_runs, 2);
+        assert_eq!(remaining_runs(temp.path(), "m1", "c_a").len(), 1);
+        assert_eq!(remaining_runs(temp.path(), "m2", "c_a").len(), 1);
+        assert_eq!(manager.disk_usage().unwrap().total_bytes(), 200);
+    }
+
+    #[test]
+    fn test_retention_keeps_regression_flagged_runs() {
+        let temp = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp.path()).unwrap();
+        let dir = temp.path().join("m1").join("c_a");
+        fs::create_dir_all(&dir).unwrap();
+        for (day, was_regression) in [(1, true), (2, false), (3, false)] {
+            fs::write(
+                dir.join(format!("2025-01-0{}T00-00-00.json", day)),
+                format!(r#"{{"was_regression": {}}}"#, was_regression),
+            )
+            .unwrap();
+        }
+
+        let policy = RetentionPolicy {
+            max_runs: Some(1),
+            max_age_days: None,
+        };
+        assert_eq!(manager.prune_all(policy).unwrap().removed_runs, 1);
+        assert_eq!(
+            remaining_runs(temp.path(), "m1", "c_a"),
+            ["2025-01-01T00-00-00.json", "2025-01-03T00-00-00.json"]
+        );
+    }
+
+    #[test]
+    fn test_stale_baselines_of_unbuilt_benchmarks() {
+        let temp = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp.path()).unwrap();
+        let machine = manager.machine_id().to_string();
+        write_run(temp.path(), &machine, "c_kept", "2025-01-01T00-00-00", 100);
+        write_run(
+            temp.path(),
+            &machine,
+            "c_deleted",
+            "2025-01-01T00-00-00",
+            100,
+        );
+        write_run(
+            temp.path(),
+            &machine,
+            "c_deleted",
+            "2025-01-02T00-00-00",
+            100,
+        );
+        // Renamed from c_old, whose runs are still its history
+        write_run(temp.path(), &machine, "c_new", "2025-01-02T00-00-00", 100);
+        write_run(temp.path(), &machine, "c_old", "2025-01-01T00-00-00", 100);
+        fs::write(
+            temp.path()
+                .join(&machine)
+                .join("c_new")
+                .join(crate::baseline::REDIRECT_FILE),
+            r#"{"redirect_from": "old"}"#,
+        )
+        .unwrap();
+        // Another machine's directories are never stale here
+        write_run(temp.path(), "other", "c_gone", "2025-01-01T00-00-00", 100);
+
+        let stale = manager
+            .stale_baselines([("c", "kept"), ("c", "new")])
+            .unwrap();
+        assert_eq!(
+            stale,
+            vec![StaleBaseline {
+                benchmark: "c_deleted".to_string(),
+                runs: 2,
+                bytes: 200,
+            }]
+        );
+
+        let report = manager.remove_stale_baselines(&stale).unwrap();
+        assert_eq!(report.removed_runs, 2);
+        assert!(!temp.path().join(&machine).join("c_deleted").exists());
+        assert!(temp.path().join(&machine).join("c_old").exists());
+        assert!(temp.path().join("other").join("c_gone").exists());
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(
+            format_bytes(12 * BYTES_PER_MB + BYTES_PER_MB / 2),
+            "12.5 MiB"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Package energy from RAPL counters (Linux powercap).
+//!
+//! The measurement loop reads the `energy_uj` counter of the pinned core's
+//! package domain (`/sys/class/powercap/intel-rapl:<n>`, also exposed on AMD)
+//! before and after the measurement phase, and the background CPU sampler
+//! records the energy of each interval between its snapshots. The counter
+//! covers the whole package, so other load on the same socket is included.
+//!
+//! On other platforms, without RAPL, or when the counter isn't readable (it is
+//! root-only on most current kernels), no energy is recorded.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const POWERCAP_ROOT: &str = "/sys/class/powercap";
+
+/// Energy counter of one RAPL package domain
+#[derive(Debug, Clone)]
+pub struct RaplDomain {
+    energy_path: PathBuf,
+    /// Value at which the counter wraps back to zero
+    max_range_uj: Option<u64>,
+}
+
+impl RaplDomain {
+    /// Package domain of the package `cpu_core` belongs to; None if unavailable
+    pub fn for_core(cpu_core: usize) -> Option<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            let package = std::fs::read_to_string(format!(
+                "/sys/devices/system/cpu/cpu{}/topology/physical_package_id",
+                cpu_core
+            ))
+            .ok()
+            .and_then(|id| id.trim().parse().ok())
+            .unwrap_or(0);
+            Self::discover(Path::new(POWERCAP_ROOT), package)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = cpu_core;
+            None
+        }
+    }
+
+    /// Find the readable `package-<package>` domain under `root`, falling back
+    /// to `intel-rapl:0` on single-package machines that name it differently
+    pub fn discover(root: &Path, package: u32) -> Option<Self> {
+        let wanted = format!("package-{}", package);
+        let mut domains: Vec<PathBuf> = std::fs::read_dir(root)
+            .ok()?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                // Top-level domains only: `intel-rapl:0`, not `intel-rapl:0:1`
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_prefix("intel-rapl:"))
+                    .is_some_and(|rest| !rest.contains(':'))
+            })
+            .collect();
+        domains.sort();
+
+        let named = domains.iter().find(|dir| {
+            std::fs::read_to_string(dir.join("name")).is_ok_and(|name| name.trim() == wanted)
+        });
+        let dir = match named {
+            Some(dir) => dir.clone(),
+            None if package == 0 => root.join("intel-rapl:0"),
+            None => return None,
+        };
+
+        let domain = Self {
+            energy_path: dir.join("energy_uj"),
+            max_range_uj: read_u64(&dir.join("max_energy_range_uj")),
+        };
+        domain.read_uj().map(|_| domain)
+    }
+
+    /// Current counter value in microjoules
+    pub fn read_uj(&self) -> Option<u64> {
+        read_u64(&self.energy_path)
+    }
+
+    /// Energy between two readings, accounting for one wrap of the counter
+    pub fn delta_uj(&self, start: u64, end: u64) -> u64 {
+        if end >= start {
+            end - start
+        } else {
+            match self.max_range_uj {
+                Some(max) => max.saturating_sub(start) + end,
+                None => 0,
+            }
+        }
+    }
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Mean power over `elapsed`; None for an empty interval
+pub fn mean_watts(energy_uj: u64, elapsed: Duration) -> Option<f64> {
+    let secs = elapsed.as_secs_f64();
+    (secs > 0.0).then(|| energy_uj as f64 / 1e6 / secs)
+}
+
+/// "3.2 J (1.1 W avg)"
+pub fn format_energy(energy_uj: u64, mean_watts: Option<f64>) -> String {
+    let joules = energy_uj as f64 / 1e6;
+    let energy = if joules >= 1.0 {
+        format!("{:.1} J", joules)
+    } else if joules >= 1e-3 {
+        format!("{:.1} mJ", joules * 1e3)
+    } else {
+        format!("{} µJ", energy_uj)
+    };
+    match mean_watts {
+        Some(watts) => format!("{} ({:.1} W avg)", energy, watts),
+        None => energy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_domain(root: &Path, dir: &str, name: &str, energy_uj: u64) {
+        let dir = root.join(dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("name"), format!("{}\n", name)).unwrap();
+        std::fs::write(dir.join("energy_uj"), format!("{}\n", energy_uj)).unwrap();
+        std::fs::write(dir.join("max_energy_range_uj"), "1000000\n").unwrap();
+    }
+
+    #[test]
+    fn test_rapl_discovery_and_wraparound() {
+        let root = tempfile::tempdir().unwrap();
+        fake_domain(root.path(), "intel-rapl:0", "package-0", 10);
+        fake_domain(root.path(), "intel-rapl:0:0", "core", 20);
+        fake_domain(root.path(), "intel-rapl:1", "package-1", 30);
+
+        let package_1 = RaplDomain::discover(root.path(), 1).unwrap();
+        assert_eq!(package_1.read_uj(), Some(30));
+        assert_eq!(package_1.delta_uj(100, 250), 150);
+        assert_eq!(package_1.delta_uj(999_900, 50), 150);
+        assert!(RaplDomain::discover(root.path(), 2).is_none());
+        assert!(RaplDomain::discover(&root.path().join("missing"), 0).is_none());
+
+        assert_eq!(
+            format_energy(3_200_000, mean_watts(3_200_000, Duration::from_secs(2))),
+            "3.2 J (1.6 W avg)"
+        );
+        assert_eq!(format_energy(4_500, None), "4.5 mJ");
+    }
+}
@@ -0,0 +1,134 @@
+//! Which benchmarks a `--bench` pattern selects.
+//!
+//! A plain pattern matches every benchmark whose name contains it, a pattern
+//! with `*` or `?` must match the whole name as a glob, and an exact filter
+//! matches one name only. The orchestrator hands a runner the one benchmark
+//! it should run in `SIMPLEBENCH_BENCH_EXACT`, with its module in
+//! `SIMPLEBENCH_BENCH_MODULE`; `SIMPLEBENCH_BENCH_FILTER` keeps its substring
+//! (or glob) meaning everywhere else.
+
+use crate::config::glob_match;
+use std::fmt;
+
+/// Environment variable with a substring or glob filter
+pub const FILTER_ENV: &str = "SIMPLEBENCH_BENCH_FILTER";
+
+/// Environment variable with the exact name of the benchmark to run
+pub const EXACT_ENV: &str = "SIMPLEBENCH_BENCH_EXACT";
+
+/// Environment variable with the module of the benchmark to run, telling
+/// same-named benchmarks of different modules apart
+pub const MODULE_ENV: &str = "SIMPLEBENCH_BENCH_MODULE";
+
+/// A benchmark name filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BenchFilter {
+    /// Names containing the pattern
+    Substring(String),
+    /// Names matching the pattern as a whole, `*` matching any run of
+    /// characters and `?` any one
+    Glob(String),
+    /// The name itself
+    Exact(String),
+}
+
+impl BenchFilter {
+    /// The filter of a `--bench` pattern; a glob when it has `*` or `?`,
+    /// unless `exact`
+    pub fn new(pattern: &str, exact: bool) -> Self {
+        if exact {
+            BenchFilter::Exact(pattern.to_string())
+        } else if pattern.contains(['*', '?']) {
+            BenchFilter::Glob(pattern.to_string())
+        } else {
+            BenchFilter::Substring(pattern.to_string())
+        }
+    }
+
+    /// The filter of [`EXACT_ENV`], else of [`FILTER_ENV`]; None when
+    /// neither is set
+    pub fn from_env() -> Option<Self> {
+        if let Ok(name) = std::env::var(EXACT_ENV) {
+            return Some(BenchFilter::Exact(name));
+        }
+        std::env::var(FILTER_ENV)
+            .ok()
+            .map(|pattern| BenchFilter::new(&pattern, false))
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            BenchFilter::Substring(pattern) => name.contains(pattern.as_str()),
+            BenchFilter::Glob(pattern) => glob_match(pattern, name),
+            BenchFilter::Exact(pattern) => name == pattern,
+        }
+    }
+
+    /// The pattern as given
+    pub fn pattern(&self) -> &str {
+        match self {
+            BenchFilter::Substring(pattern)
+            | BenchFilter::Glob(pattern)
+            | BenchFilter::Exact(pattern) => pattern,
+        }
+    }
+
+    /// The environment variable and value that hand this filter to a runner
+    pub fn env(&self) -> (&'static str, &str) {
+        match self {
+            BenchFilter::Exact(name) => (EXACT_ENV, name),
+            _ => (FILTER_ENV, self.pattern()),
+        }
+    }
+}
+
+impl fmt::Display for BenchFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.pattern())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_kinds_select_names() {
+        let names = ["bench_sort", "bench_sort_large", "my_bench_sort"];
+        let selected = |filter: BenchFilter| -> Vec<&str> {
+            names
+                .iter()
+                .copied()
+                .filter(|name| filter.matches(name))
+                .collect()
+        };
+
+        assert_eq!(
+            selected(BenchFilter::new("bench_sort", false)),
+            vec!["bench_sort", "bench_sort_large", "my_bench_sort"]
+        );
+        assert_eq!(
+            selected(BenchFilter::new("bench_sort", true)),
+            vec!["bench_sort"]
+        );
+        assert_eq!(
+            selected(BenchFilter::new("bench_sort*", false)),
+            vec!["bench_sort", "bench_sort_large"]
+        );
+        assert_eq!(
+            selected(BenchFilter::new("*_sort", false)),
+            vec!["bench_sort", "my_bench_sort"]
+        );
+        // An exact filter takes the pattern literally
+        assert!(selected(BenchFilter::new("bench_sort*", true)).is_empty());
+
+        assert_eq!(
+            BenchFilter::new("bench_sort", true).env(),
+            (EXACT_ENV, "bench_sort")
+        );
+        assert_eq!(
+            BenchFilter::new("bench_*", false).env(),
+            (FILTER_ENV, "bench_*")
+        );
+    }
+}
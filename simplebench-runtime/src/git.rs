@@ -0,0 +1,158 @@
+//! The commit a run was measured at, recorded with every stored run.
+//!
+//! Read with the `git` command line once per process; outside a repository,
+//! or without `git` on the PATH, runs simply record no commit.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// Short commit ids shown in tables and comparison notes
+const SHORT_COMMIT_LEN: usize = 7;
+
+/// State of the working tree's repository when a run was measured
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitInfo {
+    /// Full id of `HEAD`
+    pub commit: String,
+    /// Checked out branch; None on a detached `HEAD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// The tree had changes not in `commit`, outside `.benches`
+    #[serde(default, skip_serializing_if = "crate::baseline::is_false")]
+    pub dirty: bool,
+}
+
+impl GitInfo {
+    /// e.g. "3f2a9c1"
+    pub fn short_commit(&self) -> &str {
+        let end = self
+            .commit
+            .char_indices()
+            .nth(SHORT_COMMIT_LEN)
+            .map_or(self.commit.len(), |(at, _)| at);
+        &self.commit[..end]
+    }
+
+    /// e.g. "3f2a9c1", or "3f2a9c1*" for a dirty tree
+    pub fn label(&self) -> String {
+        if self.dirty {
+            format!("{}*", self.short_commit())
+        } else {
+            self.short_commit().to_string()
+        }
+    }
+}
+
+/// The window's latest run was measured at another commit than the current run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitChange {
+    pub baseline: GitInfo,
+    pub current: GitInfo,
+}
+
+impl CommitChange {
+    /// "baseline measured at 3f2a9c1, now at 8e1b2d4*"
+    pub fn format(&self) -> String {
+        format!(
+            "baseline measured at {}, now at {}",
+            self.baseline.label(),
+            self.current.label()
+        )
+    }
+}
+
+/// The repository state of the current directory, read on first use
+pub fn current() -> Option<GitInfo> {
+    static CURRENT: OnceLock<Option<GitInfo>> = OnceLock::new();
+    CURRENT.get_or_init(|| capture(Path::new("."))).clone()
+}
+
+/// Read the repository state of `dir`; None outside a repository
+pub fn capture(dir: &Path) -> Option<GitInfo> {
+    let commit = git(dir, &["rev-parse", "--verify", "HEAD"])?;
+    let branch = git(dir, &["symbolic-ref", "--quiet", "--short", "HEAD"]);
+    let dirty = git(dir, &["status", "--porcelain"])
+        .is_some_and(|status| status.lines().any(|line| !is_benches_entry(line)));
+    Some(GitInfo {
+        commit,
+        branch,
+        dirty,
+    })
+}
+
+/// A `status --porcelain` line for the stored runs themselves, which change on
+/// every save
+fn is_benches_entry(line: &str) -> bool {
+    line.get(3..)
+        .is_some_and(|path| path.split('/').any(|component| component == ".benches"))
+}
+
+/// Stdout of a successful git command, without the trailing newline
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn run(dir: &Path, args: &[&str]) -> bool {
+        Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    #[test]
+    fn test_capture_commit_branch_and_dirty_state() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(capture(temp.path()), None);
+        if !run(temp.path(), &["init", "-q", "-b", "main"]) {
+            // No git on this machine
+            return;
+        }
+        fs::write(temp.path().join("lib.rs"), "fn a() {}").unwrap();
+        assert!(run(temp.path(), &["add", "lib.rs"]));
+        assert!(run(temp.path(), &["commit", "-q", "-m", "init"]));
+
+        let info = capture(temp.path()).unwrap();
+        assert_eq!(info.commit.len(), 40);
+        assert_eq!(info.short_commit().len(), SHORT_COMMIT_LEN);
+        assert_eq!(info.branch.as_deref(), Some("main"));
+        assert!(!info.dirty);
+
+        // Stored runs don't make the tree dirty; source changes do
+        fs::create_dir_all(temp.path().join(".benches/m/c_b")).unwrap();
+        fs::write(temp.path().join(".benches/m/c_b/run.json"), "{}").unwrap();
+        assert!(!capture(temp.path()).unwrap().dirty);
+        fs::write(temp.path().join("lib.rs"), "fn b() {}").unwrap();
+        let info = capture(temp.path()).unwrap();
+        assert!(info.dirty);
+        assert_eq!(info.label(), format!("{}*", info.short_commit()));
+
+        assert!(run(temp.path(), &["checkout", "-q", "--detach"]));
+        assert_eq!(capture(temp.path()).unwrap().branch, None);
+    }
+}
@@ -0,0 +1,301 @@
+//! Merging rounds of interleaved runs.
+//!
+//! With `cargo simplebench --interleave <rounds>`, the orchestrator splits each
+//! benchmark's sample budget into rounds and runs every benchmark once per
+//! round, round-robin, so slow drift (thermals, background load) spreads over
+//! all benchmarks instead of penalizing the ones that run last. Each round is
+//! a complete runner invocation with its own warmup; [`merge_rounds`] pools
+//! the rounds of one benchmark into a single result.
+
+use crate::{
+    alloc_tracking::AllocStats, calculate_percentiles, percentile_sorted,
+    percentiles_from_statistics, BenchResult, Statistics,
+};
+use std::time::Duration;
+
+/// Split `samples` into `rounds` shares that differ by at most one, larger
+/// shares first; fewer rounds when there are fewer samples than rounds
+pub fn split_samples(samples: usize, rounds: usize) -> Vec<usize> {
+    let rounds = rounds.clamp(1, samples.max(1));
+    (0..rounds)
+        .map(|round| samples / rounds + usize::from(round < samples % rounds))
+        .collect()
+}
+
+/// Pool the rounds of one benchmark, in the order they ran; None without rounds.
+///
+/// Timings and the other per-sample series are concatenated, with sample
+/// offsets and CPU snapshots shifted so each round starts where the previous
+/// one's sampling ended. Percentiles are recomputed over the pooled timings;
+/// rounds summarized without raw samples are combined from their moments, with
+/// percentiles taken from the pooled reservoirs.
+pub fn merge_rounds(rounds: Vec<BenchResult>) -> Option<BenchResult> {
+    let mut rounds = rounds.into_iter();
+    let mut merged = rounds.next()?;
+    let mut count = 1;
+    let mut summaries: Vec<Statistics> = merged.summary.iter().cloned().collect();
+    let weighted = |value: Option<f64>, samples: usize| value.map(|v| v * samples as f64);
+    let mut alloc_sum = merged
+        .alloc_stats
+        .map(|a| scale_allocs(a, merged.samples as f64));
+    let mut sampling_ns = weighted(merged.sampling_ns_per_iter, merged.samples);
+    let mut energy_secs = energy_duration(&merged);
+    let mut max_cv_pct = merged.quality_failure.map(|f| f.max_cv_pct);
+
+    for round in rounds {
+        count += 1;
+        max_cv_pct = max_cv_pct.or(round.quality_failure.map(|f| f.max_cv_pct));
+        let offset_ms = merged
+            .cpu_samples
+            .last()
+            .map(|s| s.elapsed_ms_from_start)
+            .into_iter()
+            .chain(merged.sample_offsets_ms.last().copied())
+            .max()
+            .unwrap_or(0);
+        let recorded = merged.all_timings.len();
+
+        alloc_sum = alloc_sum
+            .zip(round.alloc_stats)
+            .map(|(sum, a)| add_allocs(sum, scale_allocs(a, round.samples as f64)));
+        sampling_ns = sampling_ns
+            .zip(weighted(round.sampling_ns_per_iter, round.samples))
+            .map(|(a, b)| a + b);
+        energy_secs = energy_secs.zip(energy_duration(&round)).map(|(a, b)| a + b);
+
+        // Per-sample series are kept only while every round has them
+        let aligned = |merged: usize, round: usize| merged == recorded && round > 0;
+        if aligned(
+            merged.sample_offsets_ms.len(),
+            round.sample_offsets_ms.len(),
+        ) {
+            merged
+                .sample_offsets_ms
+                .extend(round.sample_offsets_ms.iter().map(|ms| ms + offset_ms));
+        } else {
+            merged.sample_offsets_ms.clear();
+        }
+        if aligned(merged.cpu_timings.len(), round.cpu_timings.len()) {
+            merged.cpu_timings.extend(round.cpu_timings);
+        } else {
+            merged.cpu_timings.clear();
+        }
+        if aligned(merged.perf_samples.len(), round.perf_samples.len()) {
+            merged.perf_samples.extend(round.perf_samples);
+        } else {
+            merged.perf_samples.clear();
+        }
+        if aligned(
+            merged.involuntary_switches.len(),
+            round.involuntary_switches.len(),
+        ) {
+            merged
+                .involuntary_switches
+                .extend(round.involuntary_switches);
+        } else {
+            merged.involuntary_switches.clear();
+        }
+        merged.all_timings.extend(round.all_timings);
+        merged
+            .cpu_samples
+            .extend(round.cpu_samples.into_iter().map(|mut snapshot| {
+                snapshot.elapsed_ms_from_start += offset_ms;
+                snapshot
+            }));
+        merged
+            .pathological_samples
+            .extend(round.pathological_samples.into_iter().map(|mut sample| {
+                sample.index += merged.samples;
+                sample
+            }));
+
+        merged.energy_uj = merged.energy_uj.zip(round.energy_uj).map(|(a, b)| a + b);
+        if let Some(summary) = round.summary {
+            summaries.push(summary);
+        }
+
+        // Each round requested its share; the pooled run requested them all
+        merged.settings = merged.settings.zip(round.settings).map(|(mut a, b)| {
+            a.samples += b.samples;
+            a
+        });
+        merged.samples += round.samples;
+        merged.discarded_samples += round.discarded_samples;
+        merged.warmup_ms = merged.warmup_ms.zip(round.warmup_ms).map(|(a, b)| a + b);
+        merged.warmup_iterations = merged
+            .warmup_iterations
+            .zip(round.warmup_iterations)
+            .map(|(a, b)| a + b);
+        merged.truncated |= round.truncated;
+        merged.watchdog_stopped |= round.watchdog_stopped;
+        merged.early_verdict = round.early_verdict;
+    }
+
+    if merged.samples > 0 {
+        merged.alloc_stats = alloc_sum.map(|sum| scale_allocs(sum, 1.0 / merged.samples as f64));
+        merged.sampling_ns_per_iter = sampling_ns.map(|ns| ns / merged.samples as f64);
+    }
+    merged.warmup_ns_per_iter = merged
+        .warmup_ms
+        .zip(merged.warmup_iterations)
+        .filter(|&(_, iterations)| iterations > 0)
+        .map(|(ms, iterations)| ms as f64 * 1_000_000.0 / iterations as f64);
+    merged.mean_watts = merged
+        .energy_uj
+        .zip(energy_secs)
+        .and_then(|(uj, secs)| crate::energy::mean_watts(uj, Duration::from_secs_f64(secs)));
+
+    let levels = merged.percentiles.levels();
+    let reservoir = calculate_percentiles(&merged.all_timings, &levels);
+    merged.summary = (!summaries.is_empty()).then(|| {
+        let sorted = sorted_nanos(&merged.all_timings);
+        let mut pooled = pool_statistics(&summaries);
+        pooled.median = percentile_sorted(&sorted, 0.50);
+        pooled.p90 = percentile_sorted(&sorted, 0.90);
+        pooled.p99 = percentile_sorted(&sorted, 0.99);
+        pooled.percentiles = reservoir
+            .values
+            .iter()
+            .map(|&(p, timing)| (p, timing.as_nanos()))
+            .collect();
+        pooled.set_robust_dispersion(&sorted);
+        pooled
+    });
+    merged.percentiles = match merged.summary {
+        Some(ref summary) => percentiles_from_statistics(summary),
+        None => reservoir,
+    };
+    merged.cpu_time_percentiles = (!merged.cpu_timings.is_empty())
+        .then(|| calculate_percentiles(&merged.cpu_timings, &levels));
+    merged.filtered = crate::filter_outliers(&sorted_nanos(&merged.all_timings), &levels);
+    merged.interleaved_rounds = count;
+    // Only a failing round tells the bar; the pooled rounds are judged against it
+    merged.check_quality(max_cv_pct);
+    Some(merged)
+}
+
+fn sorted_nanos(timings: &[Duration]) -> Vec<u128> {
+    let mut nanos: Vec<u128> = timings.iter().map(|d| d.as_nanos()).collect();
+    nanos.sort_unstable();
+    nanos
+}
+
+/// Sampling time a round's energy was measured over, from its mean power
+fn energy_duration(result: &BenchResult) -> Option<f64> {
+    let watts = result.mean_watts.filter(|w| *w > 0.0)?;
+    Some(result.energy_uj? as f64 / 1e6 / watts)
+}
+
+fn scale_allocs(stats: AllocStats, n: f64) -> AllocStats {
+    AllocStats {
+        mean_allocs: stats.mean_allocs * n,
+        mean_deallocs: stats.mean_deallocs * n,
+        mean_bytes: stats.mean_bytes * n,
+    }
+}
+
+fn add_allocs(a: AllocStats, b: AllocStats) -> AllocStats {
+    AllocStats {
+        mean_allocs: a.mean_allocs + b.mean_allocs,
+        mean_deallocs: a.mean_deallocs + b.mean_deallocs,
+        mean_bytes: a.mean_bytes + b.mean_bytes,
+    }
+}
+
+/// Exact mean, population variance, min and max over the union of `parts`;
+/// percentiles and robust dispersion are left at those of the first part
+fn pool_statistics(parts: &[Statistics]) -> Statistics {
+    let total: usize = parts.iter().map(|s| s.sample_count).sum();
+    let n = total.max(1) as f64;
+    let mean = parts
+        .iter()
+        .map(|s| s.mean as f64 * s.sample_count as f64)
+        .sum::<f64>()
+        / n;
+    let variance = parts
+        .iter()
+        .map(|s| s.sample_count as f64 * (s.variance + (s.mean as f64 - mean).powi(2)))
+        .sum::<f64>()
+        / n;
+    Statistics {
+        mean: mean.round() as u128,
+        std_dev: variance.sqrt(),
+        variance,
+        min: parts.iter().map(|s| s.min).min().unwrap_or(0),
+        max: parts.iter().map(|s| s.max).max().unwrap_or(0),
+        sample_count: total,
+        ..parts[0].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::baseline::MeasurementSettings;
+
+    fn round(timings_ms: &[u64]) -> BenchResult {
+        let all_timings: Vec<Duration> = timings_ms
+            .iter()
+            .map(|&ms| Duration::from_millis(ms))
+            .collect();
+        BenchResult {
+            name: "bench".to_string(),
+            samples: all_timings.len(),
+            percentiles: calculate_percentiles(&all_timings, &crate::DEFAULT_PERCENTILES),
+            sample_offsets_ms: (0..timings_ms.len() as u64).map(|i| i * 10).collect(),
+            all_timings,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_split_samples_and_merge_rounds() {
+        assert_eq!(split_samples(10, 3), vec![4, 3, 3]);
+        assert_eq!(split_samples(2, 4), vec![1, 1]);
+        assert_eq!(split_samples(0, 4), vec![0]);
+
+        let merged = merge_rounds(vec![round(&[1, 2]), round(&[3, 4]), round(&[5, 6])]).unwrap();
+        assert_eq!(merged.interleaved_rounds, 3);
+        assert_eq!(merged.samples, 6);
+        assert_eq!(merged.all_timings.len(), 6);
+        assert_eq!(merged.percentiles.mean, Duration::from_micros(3_500));
+        // Each round starts after the previous one's last sample
+        assert_eq!(merged.sample_offsets_ms, vec![0, 10, 10, 20, 20, 30]);
+
+        let parts = [
+            crate::calculate_statistics(&[1, 2, 3]),
+            crate::calculate_statistics(&[10, 14]),
+        ];
+        let pooled = pool_statistics(&parts);
+        let exact = crate::calculate_statistics(&[1, 2, 3, 10, 14]);
+        assert_eq!(pooled.sample_count, 5);
+        assert_eq!((pooled.min, pooled.max), (1, 14));
+        assert!((pooled.variance - exact.variance).abs() < 1e-9);
+        assert!(merge_rounds(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_merged_settings_compare_with_uninterleaved_runs() {
+        let configured = MeasurementSettings {
+            samples: 1000,
+            warmup_ms: 3000,
+            warmup_mode: Default::default(),
+            adaptive: false,
+        };
+        let rounds: Vec<BenchResult> = split_samples(configured.samples, 4)
+            .into_iter()
+            .map(|share| BenchResult {
+                settings: Some(MeasurementSettings {
+                    samples: share,
+                    ..configured
+                }),
+                ..round(&[1, 2])
+            })
+            .collect();
+        assert!(!rounds[0].settings.unwrap().comparable_with(&configured));
+
+        let merged = merge_rounds(rounds).unwrap().settings.unwrap();
+        assert_eq!(merged.samples, 1000);
+        assert!(merged.comparable_with(&configured));
+    }
+}
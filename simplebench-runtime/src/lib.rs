@@ -36,18 +36,36 @@
 //! The `cargo simplebench` CLI tool handles compilation and execution of benchmarks.
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Duration;
 
+pub mod alloc_tracking;
+pub mod archive;
 pub mod baseline;
 pub mod changepoint;
+pub mod columns;
 pub mod config;
 pub mod cpu_analysis;
 pub mod cpu_monitor;
+pub mod disk_usage;
+pub mod energy;
+pub mod filter;
+pub mod git;
+pub mod interleave;
+pub mod lock;
 pub mod measurement;
+pub mod numa;
 pub mod output;
+pub mod perf_counters;
+pub mod preconditions;
 pub mod progress;
+pub mod remote;
+pub mod run_index;
 pub mod statistics;
+pub mod streaming;
+pub mod toolchain;
 
+pub use alloc_tracking::AllocStats;
 pub use baseline::*;
 pub use changepoint::*;
 pub use config::*;
@@ -55,27 +73,108 @@ pub use cpu_analysis::*;
 pub use cpu_monitor::*;
 pub use measurement::*;
 pub use output::*;
+pub use perf_counters::{PerfSample, PerfSummary};
 pub use progress::*;
 pub use statistics::*;
 
 // Re-export inventory for use by the macro
 pub use inventory;
 
+/// Percentiles a run reports unless `[measurement] percentiles` lists others
+pub const DEFAULT_PERCENTILES: [f64; 3] = [50.0, 90.0, 99.0];
+
 /// Percentile statistics for a benchmark run.
 ///
-/// Contains the 50th, 90th, and 99th percentile timings along with the mean.
+/// Contains the timing at each configured percentile (by default the 50th,
+/// 90th and 99th) along with the mean.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(from = "PercentilesRepr", into = "PercentilesRepr")]
 pub struct Percentiles {
-    /// 50th percentile (median) timing
-    pub p50: Duration,
-    /// 90th percentile timing
-    pub p90: Duration,
-    /// 99th percentile timing
-    pub p99: Duration,
+    /// Timing at each percentile (0-100), in configured order
+    pub values: Vec<(f64, Duration)>,
     /// Arithmetic mean of all timings
     pub mean: Duration,
 }
 
+impl Percentiles {
+    /// Timing at `percentile` (0-100), if it was recorded
+    pub fn get(&self, percentile: f64) -> Option<Duration> {
+        self.values
+            .iter()
+            .find(|&&(p, _)| p == percentile)
+            .map(|&(_, timing)| timing)
+    }
+
+    /// The recorded percentiles, in order
+    pub fn levels(&self) -> Vec<f64> {
+        self.values.iter().map(|&(p, _)| p).collect()
+    }
+
+    /// 50th percentile (median) timing; zero unless 50 was recorded
+    pub fn p50(&self) -> Duration {
+        self.get(50.0).unwrap_or_default()
+    }
+
+    /// 90th percentile timing; zero unless 90 was recorded
+    pub fn p90(&self) -> Duration {
+        self.get(90.0).unwrap_or_default()
+    }
+
+    /// 99th percentile timing; zero unless 99 was recorded
+    pub fn p99(&self) -> Duration {
+        self.get(99.0).unwrap_or_default()
+    }
+}
+
+/// Serialized form of [`Percentiles`]: the list, plus the fixed fields results
+/// and baselines had before the set was configurable
+#[derive(Serialize, Deserialize)]
+struct PercentilesRepr {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    values: Vec<(f64, Duration)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    p50: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    p90: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    p99: Option<Duration>,
+    mean: Duration,
+}
+
+impl From<PercentilesRepr> for Percentiles {
+    fn from(repr: PercentilesRepr) -> Self {
+        let values = if repr.values.is_empty() {
+            [(50.0, repr.p50), (90.0, repr.p90), (99.0, repr.p99)]
+                .into_iter()
+                .filter_map(|(p, timing)| Some((p, timing?)))
+                .collect()
+        } else {
+            repr.values
+        };
+        Self {
+            values,
+            mean: repr.mean,
+        }
+    }
+}
+
+impl From<Percentiles> for PercentilesRepr {
+    fn from(percentiles: Percentiles) -> Self {
+        Self {
+            p50: percentiles.get(50.0),
+            p90: percentiles.get(90.0),
+            p99: percentiles.get(99.0),
+            values: percentiles.values,
+            mean: percentiles.mean,
+        }
+    }
+}
+
+/// Name of a percentile in output and columns: "p50", "p99.9"
+pub fn percentile_label(percentile: f64) -> String {
+    format!("p{}", percentile)
+}
+
 /// Comprehensive statistics for a benchmark run.
 ///
 /// All timing values are in nanoseconds for precision.
@@ -99,6 +198,69 @@ pub struct Statistics {
     pub max: u128,
     /// Number of samples collected
     pub sample_count: usize,
+    /// Median absolute deviation from the median in nanoseconds (unscaled);
+    /// taken from the reservoir for streamed runs
+    #[serde(default)]
+    pub mad: f64,
+    /// Interquartile range (p75 − p25) in nanoseconds
+    #[serde(default)]
+    pub iqr: f64,
+    /// Robust coefficient of variation, `mad / median`; unlike std-dev / mean
+    /// it ignores the few preempted samples every run has
+    #[serde(default)]
+    pub robust_cv: f64,
+    /// Timing in nanoseconds at each configured percentile (0-100); empty for
+    /// runs recorded before the set was configurable
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub percentiles: Vec<(f64, u128)>,
+}
+
+impl Statistics {
+    /// The configured percentiles, or median, p90 and p99 for older runs
+    pub fn percentile_values(&self) -> Vec<(f64, u128)> {
+        if self.percentiles.is_empty() {
+            vec![(50.0, self.median), (90.0, self.p90), (99.0, self.p99)]
+        } else {
+            self.percentiles.clone()
+        }
+    }
+
+    /// These statistics with every timing multiplied by `factor`
+    pub(crate) fn scaled(&self, factor: f64) -> Statistics {
+        let scale = |ns: u128| (ns as f64 * factor) as u128;
+        Statistics {
+            mean: scale(self.mean),
+            median: scale(self.median),
+            p90: scale(self.p90),
+            p99: scale(self.p99),
+            std_dev: self.std_dev * factor,
+            variance: self.variance * factor * factor,
+            min: scale(self.min),
+            max: scale(self.max),
+            sample_count: self.sample_count,
+            mad: self.mad * factor,
+            iqr: self.iqr * factor,
+            robust_cv: self.robust_cv,
+            percentiles: self
+                .percentiles
+                .iter()
+                .map(|&(level, ns)| (level, scale(ns)))
+                .collect(),
+        }
+    }
+
+    /// Set `mad`, `iqr` and `robust_cv` from timings in ascending order
+    pub fn set_robust_dispersion(&mut self, sorted: &[u128]) {
+        let median = percentile_sorted(sorted, 0.50);
+        let deviations: Vec<f64> = sorted.iter().map(|&s| s.abs_diff(median) as f64).collect();
+        self.mad = statistics::median(&deviations);
+        self.iqr = (percentile_sorted(sorted, 0.75) - percentile_sorted(sorted, 0.25)) as f64;
+        self.robust_cv = if median > 0 {
+            self.mad / median as f64
+        } else {
+            0.0
+        };
+    }
 }
 
 /// Complete result of a benchmark run.
@@ -110,21 +272,407 @@ pub struct BenchResult {
     pub name: String,
     /// Module path where the benchmark is defined
     pub module: String,
-    /// Number of samples collected
+    /// Number of samples collected; every sample times a single call, so
+    /// there is no separate per-sample iteration count (files written when
+    /// samples were batched carry an `iterations` field, which is ignored)
     pub samples: usize,
     /// Percentile statistics computed from all timings
     pub percentiles: Percentiles,
-    /// Raw timing data for each sample
+    /// Timing of each sample, net of `timer_overhead_ns`
     pub all_timings: Vec<Duration>,
+    /// Start of each sample in milliseconds since measurement began,
+    /// index-aligned with `all_timings`; empty for older results
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sample_offsets_ms: Vec<u64>,
     /// CPU state samples collected during the run
     #[serde(default)]
     pub cpu_samples: Vec<CpuSnapshot>,
+    /// Thread CPU time of each sample (empty if unavailable on this platform)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cpu_timings: Vec<Duration>,
+    /// Percentile statistics computed from the per-sample CPU times
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_time_percentiles: Option<Percentiles>,
     /// Total warmup duration in milliseconds
     #[serde(default)]
     pub warmup_ms: Option<u128>,
     /// Number of iterations performed during warmup
     #[serde(default)]
     pub warmup_iterations: Option<u64>,
+    /// How warmup length was decided; `fixed` when adaptive warmup couldn't
+    /// read the CPU frequency
+    #[serde(default, skip_serializing_if = "WarmupMode::is_default")]
+    pub warmup_mode: WarmupMode,
+    /// Mean timed duration of one warmup iteration, in nanoseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warmup_ns_per_iter: Option<f64>,
+    /// Mean timed duration of one recorded sample, in nanoseconds, measured
+    /// the same way as warmup's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling_ns_per_iter: Option<f64>,
+    /// Leading samples taken after warmup and dropped (`discard_samples`)
+    #[serde(default, skip_serializing_if = "crate::baseline::is_zero")]
+    pub discarded_samples: usize,
+    /// Amount of work processed per call, from `#[bench(throughput_* = ...)]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throughput: Option<Throughput>,
+    /// Logical group from `#[bench(group = "...")]`, independent of module path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// CPU core the benchmark was pinned to while running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub core: Option<usize>,
+    /// Hash of the benchmark function body, used to follow renames in history
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_hash: Option<String>,
+    /// Set when `#[bench(skip_if = ...)]` skipped the run; no timings were taken
+    #[serde(default, skip_serializing_if = "crate::baseline::is_false")]
+    pub skipped: bool,
+    /// Why the run was skipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<String>,
+    /// Calibrated cost of an empty timed region (of the CPU clock when `clock`
+    /// is `cpu`), already subtracted from each timing (floored at zero); add it
+    /// back to recover the raw timings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timer_overhead_ns: Option<u64>,
+    /// Set when the benchmark's crate was compiled with debug assertions
+    #[serde(default, skip_serializing_if = "crate::baseline::is_false")]
+    pub debug_assertions: bool,
+    /// Sampling stopped early because `max_time_secs` ran out, or at a
+    /// pathological sample with `watchdog_stop`
+    #[serde(default, skip_serializing_if = "crate::baseline::is_false")]
+    pub truncated: bool,
+    /// Sampling was stopped by the watchdog (`watchdog_stop = true`)
+    #[serde(default, skip_serializing_if = "crate::baseline::is_false")]
+    pub watchdog_stopped: bool,
+    /// Each sample times only the drop of the setup value (`#[bench(measure = drop)]`)
+    #[serde(default, skip_serializing_if = "crate::baseline::is_false")]
+    pub measures_drop: bool,
+    /// Sampling stopped early because the verdict against the baseline window
+    /// was already certain (`early_verdict = true`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub early_verdict: Option<EarlyVerdict>,
+    /// Heap activity per sample, recorded with the `alloc-tracking` feature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alloc_stats: Option<AllocStats>,
+    /// Clock(s) the timings come from; with `cpu`, `all_timings` is thread CPU time
+    #[serde(default, skip_serializing_if = "ClockMode::is_default")]
+    pub clock: ClockMode,
+    /// Hardware counters of each sample (`perf-counters` feature, Linux); empty
+    /// when unavailable
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub perf_samples: Vec<PerfSample>,
+    /// Involuntary context switches during each sample (Linux); empty when
+    /// unavailable
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub involuntary_switches: Vec<u64>,
+    /// Samples the watchdog flagged as pathologically slow
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pathological_samples: Vec<PathologicalSample>,
+    /// Statistics over all `samples` when raw samples weren't stored
+    /// (`store_raw_samples = false`); `all_timings` and the other per-sample
+    /// series then hold a uniform reservoir of them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Statistics>,
+    /// Package energy over the measurement phase in microjoules (RAPL, Linux);
+    /// None when unsupported or unreadable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub energy_uj: Option<u64>,
+    /// Mean package power over the measurement phase, from `energy_uj`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mean_watts: Option<f64>,
+    /// Watched environment variables at run time (`[environment]`), None
+    /// where unset
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environment: BTreeMap<String, Option<String>>,
+    /// Commit of the working tree at run time; None outside a git repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<git::GitInfo>,
+    /// Compiler, build profile and CPU model the run was measured with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<toolchain::Toolchain>,
+    /// Measurement settings the run was taken with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<baseline::MeasurementSettings>,
+    /// Rounds an interleaved run (`--interleave`) was pooled from; 0 for a
+    /// single run
+    #[serde(default, skip_serializing_if = "crate::baseline::is_zero")]
+    pub interleaved_rounds: usize,
+    /// The run's CV exceeded `[measurement] max_cv_pct` (or the benchmark's
+    /// `max_cv_pct` attribute)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_failure: Option<QualityFailure>,
+    /// Statistics without the samples outside the Tukey fences; over the
+    /// reservoir when raw samples weren't stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filtered: Option<FilteredStatistics>,
+}
+
+impl BenchResult {
+    /// Record a quality failure when the robust CV of the run, in percent,
+    /// exceeds `max_cv_pct`; clears it otherwise
+    pub fn check_quality(&mut self, max_cv_pct: Option<f64>) {
+        self.quality_failure = max_cv_pct.and_then(|max_cv_pct| {
+            let cv_pct = self.statistics()?.robust_cv * 100.0;
+            (cv_pct > max_cv_pct).then_some(QualityFailure { cv_pct, max_cv_pct })
+        });
+    }
+
+    /// Statistics over all samples: the summary recorded while sampling, or
+    /// computed from `all_timings`; None without samples
+    pub fn statistics(&self) -> Option<Statistics> {
+        if let Some(ref summary) = self.summary {
+            return Some(summary.clone());
+        }
+        if self.all_timings.is_empty() {
+            return None;
+        }
+        let samples_ns: Vec<u128> = self.all_timings.iter().map(|d| d.as_nanos()).collect();
+        Some(calculate_statistics_at(
+            &samples_ns,
+            &self.percentiles.levels(),
+        ))
+    }
+
+    /// Warmup and sampling iteration times that differ by more than `factor`.
+    ///
+    /// Both phases run identical iterations, so their mean iteration times
+    /// should agree; a gap points at interference in one of them.
+    pub fn phase_inconsistency(&self, factor: f64) -> Option<PhaseInconsistency> {
+        let inconsistency = PhaseInconsistency {
+            warmup_ns_per_iter: self.warmup_ns_per_iter.filter(|ns| *ns > 0.0)?,
+            sampling_ns_per_iter: self.sampling_ns_per_iter.filter(|ns| *ns > 0.0)?,
+        };
+        let ratio = inconsistency.ratio();
+        (ratio > factor || ratio < 1.0 / factor).then_some(inconsistency)
+    }
+
+    /// Result for a benchmark that was skipped instead of measured
+    pub fn skipped(name: &str, module: &str, reason: String) -> Self {
+        Self {
+            name: name.to_string(),
+            module: module.to_string(),
+            skipped: true,
+            skip_reason: Some(reason),
+            ..Default::default()
+        }
+    }
+}
+
+/// Amount of work a single benchmark call processes.
+///
+/// Used to report a rate (e.g. GiB/s) derived from the mean timing in addition
+/// to the timing itself.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Throughput {
+    /// Bytes processed per call
+    Bytes(u64),
+    /// Elements processed per call
+    Elements(u64),
+}
+
+impl Throughput {
+    /// Format the processing rate for a given per-call duration (e.g. "1.25 GiB/s")
+    ///
+    /// Returns None for a zero duration, where no meaningful rate exists.
+    pub fn format_rate(&self, per_call: Duration) -> Option<String> {
+        let secs = per_call.as_secs_f64();
+        if secs <= 0.0 {
+            return None;
+        }
+
+        let formatted = match *self {
+            Throughput::Bytes(bytes) => {
+                let rate = bytes as f64 / secs;
+                const KIB: f64 = 1024.0;
+                if rate >= KIB * KIB * KIB {
+                    format!("{:.2} GiB/s", rate / (KIB * KIB * KIB))
+                } else if rate >= KIB * KIB {
+                    format!("{:.2} MiB/s", rate / (KIB * KIB))
+                } else if rate >= KIB {
+                    format!("{:.2} KiB/s", rate / KIB)
+                } else {
+                    format!("{:.2} B/s", rate)
+                }
+            }
+            Throughput::Elements(elems) => {
+                let rate = elems as f64 / secs;
+                if rate >= 1e9 {
+                    format!("{:.2} Gelem/s", rate / 1e9)
+                } else if rate >= 1e6 {
+                    format!("{:.2} Melem/s", rate / 1e6)
+                } else if rate >= 1e3 {
+                    format!("{:.2} Kelem/s", rate / 1e3)
+                } else {
+                    format!("{:.2} elem/s", rate)
+                }
+            }
+        };
+
+        Some(formatted)
+    }
+}
+
+/// A sample the measurement watchdog flagged as pathologically slow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathologicalSample {
+    /// Index into the recorded samples
+    pub index: usize,
+    pub duration_ns: u64,
+    /// Running median when the sample was flagged; None while too few samples
+    /// existed for one, when only `watchdog_max_sample_ms` applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub median_ns: Option<u64>,
+}
+
+impl PathologicalSample {
+    /// How many times slower than the running median the sample was
+    pub fn median_multiple(&self) -> Option<f64> {
+        self.median_ns
+            .filter(|&median| median > 0)
+            .map(|median| self.duration_ns as f64 / median as f64)
+    }
+}
+
+/// Verdict that ended sampling early (`early_verdict = true`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EarlyVerdict {
+    /// Even the low end of the running mean's interval is past the regression bar
+    Regression,
+    /// Even the high end of the running mean's interval stays under it
+    NoRegression,
+}
+
+impl EarlyVerdict {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EarlyVerdict::Regression => "certain regression",
+            EarlyVerdict::NoRegression => "certainly no regression",
+        }
+    }
+}
+
+/// Maximum number of stdout/stderr lines kept in a [`BenchFailure`].
+pub const MAX_FAILURE_OUTPUT_LINES: usize = 50;
+
+/// Maximum length of a single captured output line in a [`BenchFailure`].
+pub const MAX_FAILURE_LINE_CHARS: usize = 1000;
+
+/// Details of a benchmark whose runner process failed.
+///
+/// Captured by the orchestrator so machine-readable consumers get the same
+/// information that is printed to the terminal.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchFailure {
+    /// Benchmark function name
+    pub name: String,
+    /// Human-readable description of the failure
+    pub message: String,
+    /// Process exit code, if the runner exited normally
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// Panic message extracted from stderr, if the benchmark panicked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub panic_message: Option<String>,
+    /// Backtrace lines following the panic, if one was printed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub backtrace: Vec<String>,
+    /// Last lines of the runner's stdout (capped)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stdout_tail: Vec<String>,
+    /// Last lines of the runner's stderr (capped, progress messages excluded)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stderr_tail: Vec<String>,
+}
+
+impl BenchFailure {
+    /// Build a failure record from a runner's captured output.
+    ///
+    /// Extracts the panic message and backtrace from stderr and keeps only the
+    /// last [`MAX_FAILURE_OUTPUT_LINES`] lines of each stream.
+    pub fn from_output(
+        name: &str,
+        message: String,
+        exit_code: Option<i32>,
+        stdout: &str,
+        stderr_lines: &[String],
+    ) -> Self {
+        let (panic_message, backtrace) = extract_panic(stderr_lines);
+        let stdout_lines: Vec<String> = stdout.lines().map(|l| l.to_string()).collect();
+
+        Self {
+            name: name.to_string(),
+            message,
+            exit_code,
+            panic_message,
+            backtrace: cap_lines(&backtrace),
+            stdout_tail: cap_lines(&stdout_lines),
+            stderr_tail: cap_lines(stderr_lines),
+        }
+    }
+}
+
+/// Keep the last [`MAX_FAILURE_OUTPUT_LINES`] lines, truncating overlong ones
+fn cap_lines(lines: &[String]) -> Vec<String> {
+    lines[lines.len().saturating_sub(MAX_FAILURE_OUTPUT_LINES)..]
+        .iter()
+        .map(|line| {
+            if line.chars().count() > MAX_FAILURE_LINE_CHARS {
+                let truncated: String = line.chars().take(MAX_FAILURE_LINE_CHARS).collect();
+                format!("{}...", truncated)
+            } else {
+                line.clone()
+            }
+        })
+        .collect()
+}
+
+/// Extract the panic message and backtrace from stderr lines
+///
+/// Handles both the current `panicked at src/lib.rs:1:1:` + message-on-next-line
+/// format and the older `panicked at 'message', src/lib.rs:1:1` format.
+fn extract_panic(stderr_lines: &[String]) -> (Option<String>, Vec<String>) {
+    let Some(panic_idx) = stderr_lines
+        .iter()
+        .position(|l| l.starts_with("thread '") && l.contains("panicked at"))
+    else {
+        return (None, Vec::new());
+    };
+
+    let panic_line = &stderr_lines[panic_idx];
+    let mut message_lines = Vec::new();
+
+    if let Some(rest) = panic_line.split("panicked at '").nth(1) {
+        // Old format: message is quoted on the same line
+        let message = rest.rsplit_once("', ").map(|(m, _)| m).unwrap_or(rest);
+        message_lines.push(message.to_string());
+    } else {
+        // New format: message follows on subsequent lines
+        for line in &stderr_lines[panic_idx + 1..] {
+            if line.starts_with("note:") || line.starts_with("stack backtrace:") {
+                break;
+            }
+            message_lines.push(line.clone());
+        }
+    }
+
+    let backtrace = stderr_lines
+        .iter()
+        .position(|l| l.starts_with("stack backtrace:"))
+        .map(|idx| stderr_lines[idx + 1..].to_vec())
+        .unwrap_or_default();
+
+    let message = message_lines.join("\n");
+    let panic_message = if message.is_empty() {
+        Some(panic_line.clone())
+    } else {
+        Some(message)
+    };
+
+    (panic_message, backtrace)
 }
 
 /// Comparison between current benchmark run and baseline.
@@ -144,12 +692,289 @@ pub struct Comparison {
     /// Z-score for statistical significance
     #[serde(skip_serializing_if = "Option::is_none")]
     pub z_score: Option<f64>,
-    /// 95% confidence interval for the change
+    /// Confidence interval, in nanoseconds: the window's bounds with the
+    /// z-score method, or the bootstrap interval of current minus baseline mean
     #[serde(skip_serializing_if = "Option::is_none")]
     pub confidence_interval: Option<(f64, f64)>,
     /// Probability that a real change occurred
     #[serde(skip_serializing_if = "Option::is_none")]
     pub change_probability: Option<f64>,
+    /// Throughput of the benchmark, used to show rates before/after
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throughput: Option<Throughput>,
+    /// The window mixes runs built with and without debug assertions
+    #[serde(default, skip_serializing_if = "crate::baseline::is_false")]
+    pub mixed_build_profiles: bool,
+    /// Allocations per sample against the window, when both were tracked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocs: Option<AllocComparison>,
+    /// Smallest change in percent the comparison can detect at this sample count
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_detectable_effect: Option<f64>,
+    /// Cohen's d of the current run's samples against the window's pooled
+    /// samples; None without per-sample data on both sides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effect_size: Option<f64>,
+    /// Two-sided p-value of a Mann-Whitney U test of the current samples
+    /// against the most recent baseline run's; None without samples on both
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mann_whitney_p: Option<f64>,
+    /// Rank-biserial correlation of that test; positive when the current
+    /// samples tend to be slower
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rank_biserial: Option<f64>,
+    /// Welch's t of the current samples against the most recent baseline run,
+    /// positive when slower; only with `method = "welch"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub t_statistic: Option<f64>,
+    /// Two-sided p-value of that t-test
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p_value: Option<f64>,
+    /// The threshold is below `min_detectable_effect` and the change is within it,
+    /// so no REGRESS/STABLE verdict was given
+    #[serde(default, skip_serializing_if = "crate::baseline::is_false")]
+    pub provisional: bool,
+    /// Regression threshold percentage the verdict was judged against
+    #[serde(default)]
+    pub threshold: f64,
+    /// The global threshold, when `[comparison.overrides]` replaced it with
+    /// `threshold` for this benchmark
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub global_threshold: Option<f64>,
+    /// Mean retired instructions per sample against the window, when both sides
+    /// recorded hardware counters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<InstructionComparison>,
+    /// Warmup and sampling disagreed; the comparison is labeled SUSPECT but
+    /// its verdict stands
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suspect: Option<PhaseInconsistency>,
+    /// The current run was too noisy to judge; the verdict is SUSPECT
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_failure: Option<QualityFailure>,
+    /// Machine whose history the baseline came from, when it isn't this one;
+    /// such a comparison is for orientation only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference_machine: Option<String>,
+    /// Number of machines whose runs made up the window, each normalized by
+    /// its machine's median (`compare_across_machines`); None for a window of
+    /// this machine alone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalized_machines: Option<usize>,
+    /// Watched environment variables set differently than in the window
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_changes: Vec<EnvChange>,
+    /// The window's latest run was measured at another commit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_change: Option<git::CommitChange>,
+    /// Compiler, build profile or CPU model differing from the window's; a
+    /// regression alongside one may come from the toolchain, not the code
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub toolchain_changes: Vec<toolchain::ToolchainChange>,
+    /// Timestamp of the window's first run when a regression accepted there
+    /// with `cargo simplebench accept` started it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accepted_since: Option<String>,
+    /// Trend of the window's means and the current run's, when its drift
+    /// exceeded `drift_threshold`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drift: Option<crate::statistics::Trend>,
+    /// The current run's p99 against the window's, when `p99_threshold` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p99: Option<TailComparison>,
+}
+
+/// The current run's p99 against the p99s of its baseline window, judged by
+/// the same z-score and change point tiers as the mean
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TailComparison {
+    pub current_p99: Duration,
+    /// Mean of the window's p99s
+    pub baseline_p99: Duration,
+    /// Percentage change from the window (positive = slower)
+    pub percentage_change: f64,
+    pub z_score: f64,
+    pub change_probability: f64,
+    /// `p99_threshold` the verdict was judged against
+    pub threshold: f64,
+    /// The p99 regressed, whatever the mean did
+    #[serde(default)]
+    pub is_regression: bool,
+}
+
+/// Mean instructions per sample of the current run against its baseline window.
+///
+/// Unlike timings, instruction counts don't move with CPU frequency, so a change
+/// here points at the code rather than the machine.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InstructionComparison {
+    pub current_instructions: f64,
+    pub baseline_instructions: f64,
+}
+
+impl InstructionComparison {
+    /// Percentage change from the baseline, None when the baseline is zero
+    pub fn percentage_change(&self) -> Option<f64> {
+        (self.baseline_instructions > 0.0).then(|| {
+            (self.current_instructions - self.baseline_instructions) / self.baseline_instructions
+                * 100.0
+        })
+    }
+}
+
+impl Comparison {
+    /// The baseline mean is below the clock's resolution, so the change has
+    /// no percentage (`percentage_change` is 0) and is shown as a duration
+    pub fn below_resolution(&self) -> bool {
+        self.baseline_mean.is_zero()
+    }
+
+    /// Change from the baseline: "+7.2%", or the absolute delta ("+480ns")
+    /// when [`Comparison::below_resolution`]
+    pub fn format_change(&self) -> String {
+        if self.below_resolution() {
+            format!(
+                "+{}",
+                crate::output::format_duration_human_readable(self.current_mean)
+            )
+        } else {
+            format!("{:+.1}%", self.percentage_change)
+        }
+    }
+
+    /// Explanation of a provisional verdict, None for a regular one
+    pub fn provisional_note(&self) -> Option<String> {
+        let mde = self.min_detectable_effect.filter(|_| self.provisional)?;
+        Some(format!(
+            "threshold {}% is below the minimum detectable change (~{:.1}%) for this sample count — verdict downgraded to PROVISIONAL",
+            self.threshold, mde
+        ))
+    }
+
+    /// "window starts at the accepted regression measured at 2025-01-15 10:30 UTC"
+    pub fn accepted_note(&self) -> Option<String> {
+        let since = self.accepted_since.as_ref()?;
+        let since = chrono::DateTime::parse_from_rfc3339(since).map_or(since.clone(), |t| {
+            t.with_timezone(&chrono::Utc)
+                .format("%Y-%m-%d %H:%M UTC")
+                .to_string()
+        });
+        Some(format!(
+            "window starts at the accepted regression measured at {}",
+            since
+        ))
+    }
+}
+
+/// Warmup and sampling of one run disagreeing about the cost of an iteration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PhaseInconsistency {
+    pub warmup_ns_per_iter: f64,
+    pub sampling_ns_per_iter: f64,
+}
+
+impl PhaseInconsistency {
+    /// Sampling iteration time over warmup iteration time
+    pub fn ratio(&self) -> f64 {
+        self.sampling_ns_per_iter / self.warmup_ns_per_iter
+    }
+
+    pub fn format(&self) -> String {
+        let phase = if self.ratio() > 1.0 {
+            "sampling"
+        } else {
+            "warmup"
+        };
+        format!(
+            "warmup suggests {} but sampling measured {} — possible interference during {}",
+            format_ns_per_iter(self.warmup_ns_per_iter),
+            format_ns_per_iter(self.sampling_ns_per_iter),
+            phase
+        )
+    }
+}
+
+/// A run whose coefficient of variation exceeded the quality bar, so its
+/// timings can't support a verdict
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QualityFailure {
+    /// Robust CV of the run, in percent
+    pub cv_pct: f64,
+    /// The bar it exceeded, in percent
+    pub max_cv_pct: f64,
+}
+
+impl QualityFailure {
+    pub fn format(&self) -> String {
+        format!(
+            "QUALITY FAIL (CV {:.0}% > {}%)",
+            self.cv_pct, self.max_cv_pct
+        )
+    }
+}
+
+fn format_ns_per_iter(ns: f64) -> String {
+    format!(
+        "{}/iter",
+        output::format_duration_human_readable(Duration::from_nanos(ns.round() as u64))
+    )
+}
+
+/// Mean allocations per sample of the current run against its baseline window
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AllocComparison {
+    pub current_allocs: f64,
+    pub baseline_allocs: f64,
+    /// Growth exceeds `alloc_threshold`; independent of the timing verdict
+    #[serde(default)]
+    pub is_regression: bool,
+}
+
+impl AllocComparison {
+    /// Percentage change from the baseline, None when the baseline didn't allocate
+    pub fn percentage_change(&self) -> Option<f64> {
+        (self.baseline_allocs > 0.0)
+            .then(|| (self.current_allocs - self.baseline_allocs) / self.baseline_allocs * 100.0)
+    }
+}
+
+/// A watched environment variable whose value differs between the current run
+/// and runs of the baseline window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvChange {
+    pub name: String,
+    /// Value in the latest differing window run, None if it was unset
+    pub baseline: Option<String>,
+    pub current: Option<String>,
+    /// Window runs with a different value
+    pub differing_runs: usize,
+    /// Window runs that recorded the variable
+    pub recorded_runs: usize,
+}
+
+impl EnvChange {
+    /// "RUST_LOG was unset in baselines but =debug now"
+    pub fn format(&self) -> String {
+        let value = |v: &Option<String>| match v {
+            Some(v) => format!("={}", v),
+            None => "unset".to_string(),
+        };
+        let runs = if self.differing_runs == self.recorded_runs {
+            "baselines".to_string()
+        } else {
+            format!(
+                "{} of {} baseline runs",
+                self.differing_runs, self.recorded_runs
+            )
+        };
+        format!(
+            "{} was {} in {} but {} now",
+            self.name,
+            value(&self.baseline),
+            runs,
+            value(&self.current)
+        )
+    }
 }
 
 /// A registered benchmark function.
@@ -160,13 +985,21 @@ pub struct Comparison {
 /// The `run` function encapsulates the entire measurement process: it receives config,
 /// performs warmup, runs measurement iterations, and returns a complete `BenchResult`.
 /// This design allows benchmarks with setup to run setup once before measurement begins.
+/// A fallible setup that returns an error yields a [`BenchFailure`] instead.
 pub struct SimpleBench {
     /// Name of the benchmark function
     pub name: &'static str,
     /// Module path where the benchmark is defined
     pub module: &'static str,
+    /// Optional logical group, used for grouped output and `--bench-group` filtering
+    pub group: Option<&'static str>,
+    /// Core requested via `#[bench(core = N)]`; `[cores] overrides` in config wins
+    pub core: Option<usize>,
+    /// Whether the benchmark's crate was compiled with debug assertions, which
+    /// almost always means it is unoptimized and its timings are meaningless
+    pub debug_assertions: bool,
     /// The benchmark runner function that performs measurement and returns results
-    pub run: fn(&crate::config::BenchmarkConfig) -> BenchResult,
+    pub run: fn(&crate::config::BenchmarkConfig) -> Result<BenchResult, Box<BenchFailure>>,
 }
 
 inventory::collect!(SimpleBench);
@@ -180,6 +1013,135 @@ pub struct BenchmarkInfo {
     pub name: String,
     /// Module path where the benchmark is defined
     pub module: String,
+    /// Logical group the benchmark belongs to, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Core requested by the benchmark attribute, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub core: Option<usize>,
+    /// Whether the benchmark's crate was compiled with debug assertions
+    #[serde(default, skip_serializing_if = "crate::baseline::is_false")]
+    pub debug_assertions: bool,
+}
+
+/// Version of this crate, embedded in generated runners
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Build metadata of a generated runner, printed by `runner --info`
+///
+/// The orchestrator embeds it in the runner source, so the binary can tell
+/// what it was built from when it looks stale or mismatched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunnerInfo {
+    /// When the runner was generated (RFC 3339)
+    pub built_at: String,
+    /// Version of simplebench-runtime the runner was generated for
+    pub runtime_version: String,
+    /// Output of `rustc --version` for the compiler that built it
+    pub rustc_version: String,
+    /// Target triple the benchmark crates were compiled for
+    #[serde(default)]
+    pub target: String,
+    /// `-C opt-level` the benchmark crates were compiled with
+    #[serde(default)]
+    pub opt_level: String,
+    /// Benchmark crates linked into the runner
+    pub crates: Vec<RunnerCrate>,
+}
+
+/// A benchmark crate linked into a runner
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunnerCrate {
+    /// Crate name, as in `extern crate`
+    pub name: String,
+    /// SHA-256 of the rlib the runner was linked against, in hex
+    pub rlib_hash: String,
+}
+
+/// Print a runner's embedded build metadata as JSON to stdout
+///
+/// `crates` holds `(name, rlib_hash)` pairs. Used by the `--info` flag of
+/// generated runners.
+pub fn print_runner_info_json(
+    built_at: &str,
+    runtime_version: &str,
+    rustc_version: &str,
+    target: &str,
+    opt_level: &str,
+    crates: &[(&str, &str)],
+) {
+    let info = RunnerInfo {
+        built_at: built_at.to_string(),
+        runtime_version: runtime_version.to_string(),
+        rustc_version: rustc_version.to_string(),
+        target: target.to_string(),
+        opt_level: opt_level.to_string(),
+        crates: crates
+            .iter()
+            .map(|&(name, rlib_hash)| RunnerCrate {
+                name: name.to_string(),
+                rlib_hash: rlib_hash.to_string(),
+            })
+            .collect(),
+    };
+    println!("{}", serde_json::to_string(&info).unwrap());
+}
+
+/// Crates (from module paths) whose benchmarks were compiled with debug assertions
+///
+/// Takes `(module, debug_assertions)` pairs and returns the sorted, deduplicated
+/// crate names of the flagged ones.
+pub fn debug_assertion_crates<'a>(
+    benchmarks: impl IntoIterator<Item = (&'a str, bool)>,
+) -> Vec<String> {
+    let mut crates: Vec<String> = benchmarks
+        .into_iter()
+        .filter(|(_, debug_assertions)| *debug_assertions)
+        .map(|(module, _)| module.split("::").next().unwrap_or(module).to_string())
+        .collect();
+    crates.sort();
+    crates.dedup();
+    crates
+}
+
+/// Benchmarks whose runs would be stored in the same directory (see
+/// [`storage_key`]), interleaving their histories
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageCollision {
+    /// The shared directory name
+    pub key: String,
+    /// `module::name` of each benchmark, sorted
+    pub benchmarks: Vec<String>,
+}
+
+impl StorageCollision {
+    /// "game_physics_ext_bench_raycast: game::physics_ext_bench_raycast, game_physics_ext::bench_raycast"
+    pub fn format(&self) -> String {
+        format!("{}: {}", self.key, self.benchmarks.join(", "))
+    }
+}
+
+/// Benchmarks that would share a baseline directory, from `(module, name)`
+/// pairs: names that meet across crates (`a_b::c` and `a::b_c`)
+pub fn storage_collisions<'a>(
+    benchmarks: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<StorageCollision> {
+    let mut by_key: std::collections::BTreeMap<String, Vec<String>> =
+        std::collections::BTreeMap::new();
+    for (module, name) in benchmarks {
+        by_key
+            .entry(storage_key(module, name))
+            .or_default()
+            .push(format!("{}::{}", module, name));
+    }
+    by_key
+        .into_iter()
+        .filter(|(_, benchmarks)| benchmarks.len() > 1)
+        .map(|(key, mut benchmarks)| {
+            benchmarks.sort();
+            StorageCollision { key, benchmarks }
+        })
+        .collect()
 }
 
 /// List all registered benchmarks as JSON to stdout
@@ -190,18 +1152,129 @@ pub fn list_benchmarks_json() {
         .map(|b| BenchmarkInfo {
             name: b.name.to_string(),
             module: b.module.to_string(),
+            group: b.group.map(str::to_string),
+            core: b.core,
+            debug_assertions: b.debug_assertions,
         })
         .collect();
     println!("{}", serde_json::to_string(&benchmarks).unwrap());
 }
 
+/// Structured error printed to stdout by a runner whose benchmark failed.
+///
+/// Lets the orchestrator report the failure instead of a JSON parse error.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunnerError {
+    /// The failure, as seen from inside the runner
+    pub error: BenchFailure,
+    /// Registered benchmarks closest to a requested name the runner doesn't
+    /// have, nearest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+}
+
+impl RunnerError {
+    /// The failure message, with any suggestions appended
+    pub fn message(&self) -> String {
+        if self.suggestions.is_empty() {
+            return self.error.message.clone();
+        }
+        format!(
+            "{} (did you mean {}?)",
+            self.error.message,
+            self.suggestions.join(", ")
+        )
+    }
+}
+
+/// Error for a requested benchmark the runner doesn't have, given its
+/// `registered` (module, name) pairs: the modules holding a benchmark of that
+/// name when only `module` differs, the closest names otherwise
+fn missing_benchmark_error(
+    name: &str,
+    module: Option<&str>,
+    registered: &[(&str, &str)],
+) -> RunnerError {
+    let modules: Vec<&str> = registered
+        .iter()
+        .filter(|(_, registered_name)| *registered_name == name)
+        .map(|(registered_module, _)| *registered_module)
+        .collect();
+    let (message, suggestions) = match module {
+        Some(module) if !modules.is_empty() => (
+            format!(
+                "benchmark '{}' not found in module '{}' of the runner, only in {}",
+                name,
+                module,
+                modules.join(", ")
+            ),
+            Vec::new(),
+        ),
+        _ => (
+            format!("benchmark '{}' not found in the runner", name),
+            closest_names(name, registered.iter().map(|(_, name)| *name)),
+        ),
+    };
+    RunnerError {
+        error: BenchFailure {
+            name: name.to_string(),
+            message,
+            ..Default::default()
+        },
+        suggestions,
+    }
+}
+
+/// Most suggestions a [`RunnerError`] lists
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Up to [`MAX_SUGGESTIONS`] of `candidates` within a third of `name`'s length
+/// in edit distance (at least 2), nearest first
+fn closest_names<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let max_distance = (name.chars().count() / 3).max(2);
+    let mut close: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect();
+    close.sort();
+    close.dedup();
+    close
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+/// Levenshtein distance between `a` and `b`, in chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 /// Run a single benchmark and output JSON result to stdout
 ///
-/// The benchmark to run is specified via SIMPLEBENCH_BENCH_FILTER env var (exact match).
+/// The benchmark to run is specified via SIMPLEBENCH_BENCH_EXACT env var, or
+/// via SIMPLEBENCH_BENCH_FILTER as set by older orchestrators (exact match
+/// either way), in the module of SIMPLEBENCH_BENCH_MODULE when that is set.
 /// The core to pin to is specified via SIMPLEBENCH_PIN_CORE env var.
+/// If the benchmark fails (e.g. its setup returns an error), a [`RunnerError`] is
+/// printed instead and the process exits non-zero.
 pub fn run_single_benchmark_json(config: &crate::config::BenchmarkConfig) {
-    let bench_name = std::env::var("SIMPLEBENCH_BENCH_FILTER")
-        .expect("SIMPLEBENCH_BENCH_FILTER must be set for single benchmark execution");
+    let bench_name = std::env::var(filter::EXACT_ENV)
+        .or_else(|_| std::env::var(filter::FILTER_ENV))
+        .expect("SIMPLEBENCH_BENCH_EXACT must be set for single benchmark execution");
+    // Older orchestrators don't set it
+    let module = std::env::var(filter::MODULE_ENV).ok();
 
     let pin_core: usize = std::env::var("SIMPLEBENCH_PIN_CORE")
         .ok()
@@ -216,44 +1289,141 @@ pub fn run_single_benchmark_json(config: &crate::config::BenchmarkConfig) {
         );
     }
 
+    // Keep allocations on the pinned core's node, before warmup touches any memory
+    if let Some(node) = numa::node_of_cpu(pin_core) {
+        if let Err(e) = numa::bind_memory_to_node(node) {
+            if e.kind() != std::io::ErrorKind::Unsupported {
+                eprintln!(
+                    "Warning: Failed to bind memory to NUMA node {}: {}",
+                    node, e
+                );
+            }
+        }
+    }
+
     // Find and run the benchmark
     for bench in inventory::iter::<SimpleBench>() {
-        if bench.name == bench_name {
+        if bench.name == bench_name && module.as_deref().map_or(true, |m| m == bench.module) {
             // The benchmark's run function handles warmup, measurement, and returns results
-            let result = (bench.run)(config);
-            println!("{}", serde_json::to_string(&result).unwrap());
-            return;
+            match (bench.run)(config) {
+                Ok(mut result) => {
+                    result.core = Some(pin_core);
+                    println!("{}", serde_json::to_string(&result).unwrap());
+                    return;
+                }
+                Err(error) => {
+                    let error = *error;
+                    let error = RunnerError {
+                        error,
+                        suggestions: Vec::new(),
+                    };
+                    println!("{}", serde_json::to_string(&error).unwrap());
+                    std::process::exit(1);
+                }
+            }
         }
     }
 
-    eprintln!("ERROR: Benchmark '{}' not found", bench_name);
+    // A stale runner binary, or a benchmark renamed or moved since discovery
+    let registered: Vec<(&str, &str)> = inventory::iter::<SimpleBench>()
+        .map(|bench| (bench.module, bench.name))
+        .collect();
+    let error = missing_benchmark_error(&bench_name, module.as_deref(), &registered);
+    eprintln!("ERROR: {}", error.message());
+    println!("{}", serde_json::to_string(&error).unwrap());
     std::process::exit(1);
 }
 
-pub(crate) fn calculate_percentiles(timings: &[Duration]) -> Percentiles {
-    let mut sorted_timings = timings.to_vec();
-    sorted_timings.sort();
+/// `nanos` as a [`Duration`], exactly; saturating at [`Duration::MAX`] for
+/// counts beyond it rather than wrapping like `from_nanos(nanos as u64)`
+pub fn duration_from_nanos(nanos: u128) -> Duration {
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+    match u64::try_from(nanos / NANOS_PER_SEC) {
+        Ok(secs) => Duration::new(secs, (nanos % NANOS_PER_SEC) as u32),
+        Err(_) => Duration::MAX,
+    }
+}
+
+/// Nanoseconds of `duration`, saturating at `u64::MAX` rather than wrapping
+/// like `as_nanos() as u64`
+pub(crate) fn nanos_u64(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Percentiles recorded in `statistics`, for results summarized without raw samples
+pub(crate) fn percentiles_from_statistics(statistics: &Statistics) -> Percentiles {
+    Percentiles {
+        mean: duration_from_nanos(statistics.mean),
+        values: statistics
+            .percentile_values()
+            .into_iter()
+            .map(|(p, ns)| (p, duration_from_nanos(ns)))
+            .collect(),
+    }
+}
+
+/// Mean of `timings` and their timing at each of `levels` (0-100); zero
+/// throughout without timings, like [`calculate_statistics_at`]
+pub(crate) fn calculate_percentiles(timings: &[Duration], levels: &[f64]) -> Percentiles {
+    let mut sorted_nanos: Vec<u128> = timings.iter().map(|d| d.as_nanos()).collect();
+    sorted_nanos.sort();
 
-    let len = sorted_timings.len();
-    let p50_idx = (len * 50) / 100;
-    let p90_idx = (len * 90) / 100;
-    let p99_idx = (len * 99) / 100;
+    let len = sorted_nanos.len();
+    if len == 0 {
+        return Percentiles {
+            values: levels.iter().map(|&p| (p, Duration::ZERO)).collect(),
+            mean: Duration::ZERO,
+        };
+    }
+    let at = |p: f64| duration_from_nanos(percentile_sorted(&sorted_nanos, p));
 
     // Calculate mean
-    let sum_nanos: u128 = timings.iter().map(|d| d.as_nanos()).sum();
-    let mean_nanos = sum_nanos / (len as u128);
-    let mean = Duration::from_nanos(mean_nanos as u64);
+    let sum_nanos: u128 = sorted_nanos.iter().sum();
+    let mean = duration_from_nanos(sum_nanos / (len as u128));
 
     Percentiles {
-        p50: sorted_timings[p50_idx.min(len - 1)],
-        p90: sorted_timings[p90_idx.min(len - 1)],
-        p99: sorted_timings[p99_idx.min(len - 1)],
+        values: levels.iter().map(|&p| (p, at(p / 100.0))).collect(),
         mean,
     }
 }
 
-/// Calculate comprehensive statistics from raw timing samples
+/// Calculate comprehensive statistics from raw timing samples, at the
+/// [`DEFAULT_PERCENTILES`]
 pub fn calculate_statistics(samples: &[u128]) -> Statistics {
+    calculate_statistics_at(samples, &DEFAULT_PERCENTILES)
+}
+
+/// Statistics of the samples inside the Tukey fences, next to a run's raw ones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilteredStatistics {
+    pub statistics: Statistics,
+    /// Samples outside the fences, left out of `statistics`
+    pub outliers_removed: usize,
+}
+
+/// Statistics of `samples` without those outside the Tukey fences
+/// ([`statistics::tukey_fences`]); None without samples
+pub fn filter_outliers(samples: &[u128], levels: &[f64]) -> Option<FilteredStatistics> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable();
+    let (lower, upper) = statistics::tukey_fences(&sorted);
+    let kept: Vec<u128> = samples
+        .iter()
+        .copied()
+        .filter(|&s| (lower..=upper).contains(&s))
+        .collect();
+    Some(FilteredStatistics {
+        statistics: calculate_statistics_at(&kept, levels),
+        outliers_removed: samples.len() - kept.len(),
+    })
+}
+
+/// Calculate comprehensive statistics from raw timing samples, recording
+/// each of `levels` (0-100) in [`Statistics::percentiles`]
+pub fn calculate_statistics_at(samples: &[u128], levels: &[f64]) -> Statistics {
     let sample_count = samples.len();
 
     if sample_count == 0 {
@@ -267,6 +1437,10 @@ pub fn calculate_statistics(samples: &[u128]) -> Statistics {
             min: 0,
             max: 0,
             sample_count: 0,
+            mad: 0.0,
+            iqr: 0.0,
+            robust_cv: 0.0,
+            percentiles: Vec::new(),
         };
     }
 
@@ -275,13 +1449,9 @@ pub fn calculate_statistics(samples: &[u128]) -> Statistics {
     sorted.sort();
 
     // Calculate percentiles
-    let p50_idx = (sample_count * 50) / 100;
-    let p90_idx = (sample_count * 90) / 100;
-    let p99_idx = (sample_count * 99) / 100;
-
-    let median = sorted[p50_idx.min(sample_count - 1)];
-    let p90 = sorted[p90_idx.min(sample_count - 1)];
-    let p99 = sorted[p99_idx.min(sample_count - 1)];
+    let median = percentile_sorted(&sorted, 0.50);
+    let p90 = percentile_sorted(&sorted, 0.90);
+    let p99 = percentile_sorted(&sorted, 0.99);
 
     // Calculate mean
     let sum: u128 = samples.iter().sum();
@@ -304,7 +1474,7 @@ pub fn calculate_statistics(samples: &[u128]) -> Statistics {
     let min = *sorted.first().unwrap();
     let max = *sorted.last().unwrap();
 
-    Statistics {
+    let mut stats = Statistics {
         mean,
         median,
         p90,
@@ -314,7 +1484,16 @@ pub fn calculate_statistics(samples: &[u128]) -> Statistics {
         min,
         max,
         sample_count,
-    }
+        mad: 0.0,
+        iqr: 0.0,
+        robust_cv: 0.0,
+        percentiles: levels
+            .iter()
+            .map(|&p| (p, percentile_sorted(&sorted, p / 100.0)))
+            .collect(),
+    };
+    stats.set_robust_dispersion(&sorted);
+    stats
 }
 
 /// Run all benchmarks with configuration and stream results
@@ -322,10 +1501,10 @@ pub fn calculate_statistics(samples: &[u128]) -> Statistics {
 /// This is the primary entry point for the generated runner.
 /// Prints each benchmark result immediately as it completes.
 pub fn run_and_stream_benchmarks(config: &crate::config::BenchmarkConfig) -> Vec<BenchResult> {
-    use crate::baseline::{BaselineManager, ComparisonResult};
+    use crate::baseline::{BaselineManager, BaselineWriter, ComparisonResult};
     use crate::output::{
-        print_benchmark_result_line, print_comparison_line, print_new_baseline_line,
-        print_streaming_summary,
+        print_benchmark_result_line, print_comparison_line, print_excluded_history_line,
+        print_group_header, print_new_baseline_line, print_power_warning, print_streaming_summary,
     };
     use colored::*;
 
@@ -344,35 +1523,110 @@ pub fn run_and_stream_benchmarks(config: &crate::config::BenchmarkConfig) -> Vec
     let mut results = Vec::new();
     let mut comparisons = Vec::new();
 
+    let columns = config
+        .output
+        .columns_or(crate::columns::Column::TERMINAL_DEFAULT)
+        .unwrap_or_else(|e| {
+            eprintln!(
+                "Warning: {} in [output] columns; using the default columns",
+                e
+            );
+            crate::columns::Column::TERMINAL_DEFAULT.to_vec()
+        });
+
     // Initialize baseline manager
-    let baseline_manager = match BaselineManager::new() {
-        Ok(bm) => Some(bm),
-        Err(e) => {
-            eprintln!("Warning: Could not initialize baseline manager: {}", e);
-            eprintln!("Running without baseline comparison.");
-            None
-        }
-    };
+    let baseline_manager =
+        match BaselineManager::for_workspace(std::path::Path::new("."), &config.baseline) {
+            Ok(bm) => Some(std::sync::Arc::new(
+                bm.with_follow_renames(config.comparison.follow_renames)
+                    .with_improvement_resets_window(config.comparison.improvement_resets_window)
+                    .with_compare_across_machines(config.comparison.compare_across_machines)
+                    .with_retention(config.baseline.retention()),
+            )),
+            Err(e) => {
+                eprintln!("Warning: Could not initialize baseline manager: {}", e);
+                eprintln!("Running without baseline comparison.");
+                None
+            }
+        };
+    // Baselines are written off the pinned core, between benchmarks
+    let mut baseline_writer = baseline_manager
+        .clone()
+        .map(|bm| BaselineWriter::spawn(bm, Some(0)));
 
-    // Get benchmark filter if specified
-    let bench_filter = std::env::var("SIMPLEBENCH_BENCH_FILTER").ok();
+    // Get benchmark filters if specified
+    let bench_filter = filter::BenchFilter::from_env();
+    let group_filter = std::env::var("SIMPLEBENCH_BENCH_GROUP").ok();
 
-    // Count how many benchmarks match the filter
+    // Select matching benchmarks, ordered so each group runs contiguously
     let total_benchmarks: usize = inventory::iter::<SimpleBench>().count();
-    let filtered_count = if let Some(ref filter) = bench_filter {
-        inventory::iter::<SimpleBench>()
-            .filter(|b| b.name.contains(filter))
-            .count()
-    } else {
-        total_benchmarks
-    };
+    let mut selected: Vec<&SimpleBench> = inventory::iter::<SimpleBench>()
+        .filter(|b| {
+            bench_filter
+                .as_ref()
+                .map_or(true, |filter| filter.matches(b.name))
+        })
+        .filter(|b| {
+            group_filter
+                .as_ref()
+                .map_or(true, |group| b.group == Some(group.as_str()))
+        })
+        .collect();
+    selected.sort_by_key(|b| b.group);
+    let filtered_count = selected.len();
 
-    println!(
-        "{} {} {}",
-        "Running benchmarks with".green().bold(),
-        config.measurement.samples,
-        "samples".green().bold()
-    );
+    // Runs are stored by crate, module and name; sharing a directory would mix histories
+    let collisions =
+        storage_collisions(inventory::iter::<SimpleBench>().map(|b| (b.module, b.name)));
+    if !collisions.is_empty() {
+        eprintln!(
+            "{} benchmarks would share a baseline directory; rename one of each:",
+            "error:".red().bold()
+        );
+        for collision in &collisions {
+            eprintln!("       {}", collision.format());
+        }
+        std::process::exit(1);
+    }
+
+    // Timings of unoptimized code say nothing about the optimized build
+    let debug_crates =
+        debug_assertion_crates(selected.iter().map(|b| (b.module, b.debug_assertions)));
+    if !debug_crates.is_empty() {
+        let crate_list = debug_crates.join(", ");
+        if std::env::var("SIMPLEBENCH_ALLOW_DEBUG").is_err() {
+            eprintln!(
+                "{} benchmark crates compiled with debug assertions: {}",
+                "error:".red().bold(),
+                crate_list
+            );
+            eprintln!("       Set SIMPLEBENCH_ALLOW_DEBUG=1 to run them anyway");
+            std::process::exit(1);
+        }
+        eprintln!(
+            "{} running benchmarks compiled with debug assertions ({}); timings are not representative",
+            "WARNING:".yellow().bold(),
+            crate_list
+        );
+    }
+
+    if config.measurement.adaptive {
+        println!(
+            "{} {}-{} {} {}%)",
+            "Running benchmarks with".green().bold(),
+            config.measurement.min_samples,
+            config.measurement.max_samples,
+            "samples (adaptive, target CV".green().bold(),
+            config.measurement.target_cv
+        );
+    } else {
+        println!(
+            "{} {} {}",
+            "Running benchmarks with".green().bold(),
+            config.measurement.samples,
+            "samples".green().bold()
+        );
+    }
 
     if let Some(ref filter) = bench_filter {
         println!(
@@ -386,86 +1640,159 @@ pub fn run_and_stream_benchmarks(config: &crate::config::BenchmarkConfig) -> Vec
             },
             filter
         );
+    } else if let Some(ref group) = group_filter {
+        println!(
+            "{} {} ({} in group \"{}\")\n",
+            "Filtering to".dimmed(),
+            filtered_count,
+            if filtered_count == 1 {
+                "benchmark"
+            } else {
+                "benchmarks"
+            },
+            group
+        );
     } else {
         println!();
     }
 
     // Run each benchmark and print immediately
-    for bench in inventory::iter::<SimpleBench> {
-        // Apply filter if specified
-        if let Some(ref filter) = bench_filter {
-            if !bench.name.contains(filter) {
-                continue; // Skip this benchmark
+    let mut current_group: Option<&str> = None;
+    let cooldown_monitor = crate::cpu_monitor::CpuMonitor::new(0);
+    for (index, bench) in selected.into_iter().enumerate() {
+        // Let the CPU shed the previous benchmark's heat before timing the next
+        if index > 0 {
+            if let Some(cooldown) = crate::cpu_monitor::cool_down(
+                &cooldown_monitor,
+                config.measurement.cooldown_max_temp_c,
+                Duration::try_from_secs_f64(config.measurement.cooldown_secs).unwrap_or_default(),
+            ) {
+                println!("{}", format!("  Cooldown: {}", cooldown.format()).dimmed());
+            }
+        }
+
+        if let Some(group) = bench.group {
+            if current_group != Some(group) {
+                print_group_header(group);
+                current_group = Some(group);
             }
         }
+
         // Run benchmark - the run function handles warmup, measurement, and returns results
-        let result = (bench.run)(config);
+        // A failed setup has already been reported by the measurement loop
+        let result = match (bench.run)(config) {
+            Ok(result) => result,
+            Err(_) => continue,
+        };
 
         // Print benchmark result immediately
-        print_benchmark_result_line(&result);
+        print_benchmark_result_line(&result, &columns);
+
+        // Skipped runs have no timings to compare or keep as a baseline
+        if result.skipped {
+            results.push(result);
+            println!();
+            continue;
+        }
 
         // Compare with baseline using CPD and print comparison
-        if let Some(ref bm) = baseline_manager {
-            let crate_name = result.module.split("::").next().unwrap_or("unknown");
+        if let (Some(bm), Some(writer)) = (&baseline_manager, &mut baseline_writer) {
+            let (crate_name, benchmark_name) = storage_name(&result.module, &result.name);
+            let comparison_config = config.comparison.for_benchmark(&result.name);
 
             // Load recent baselines for window-based comparison
-            let mut is_regression = false;
-            if let Ok(historical) =
-                bm.load_recent_baselines(crate_name, &result.name, config.comparison.window_size)
-            {
-                if !historical.is_empty() {
+            let mut judged = false;
+            if let Ok(window) = bm.load_comparable_window(
+                crate_name,
+                &benchmark_name,
+                comparison_config.window_size,
+                result.settings.as_ref(),
+            ) {
+                if !window.runs.is_empty() {
                     // Use CPD-based comparison
-                    let comparison_result = crate::baseline::detect_regression_with_cpd(
+                    let comparison_result = crate::baseline::judge_against_history(
                         &result,
-                        &historical,
-                        config.comparison.threshold,
-                        config.comparison.confidence_level,
-                        config.comparison.cp_threshold,
-                        config.comparison.hazard_rate,
+                        &window.runs,
+                        &comparison_config,
                     );
 
-                    is_regression = comparison_result.is_regression;
+                    judged = true;
 
                     if let Some(ref comparison) = comparison_result.comparison {
                         print_comparison_line(
                             comparison,
                             &result.name,
                             comparison_result.is_regression,
+                            &columns,
                         );
+                        if comparison_config.warn_underpowered {
+                            print_power_warning(comparison, comparison_config.confidence_level);
+                        }
                     }
 
                     comparisons.push(comparison_result);
                 } else {
-                    // First run - no baseline
-                    print_new_baseline_line(&result.name);
-
-                    comparisons.push(ComparisonResult {
+                    // First run on this machine - other machines may still have history
+                    let reference = bm
+                        .load_reference_baselines(
+                            crate_name,
+                            &benchmark_name,
+                            comparison_config.window_size,
+                        )
+                        .unwrap_or(None);
+                    let comparison_result = ComparisonResult {
                         benchmark_name: result.name.clone(),
+                        group: result.group.clone(),
                         comparison: None,
                         is_regression: false,
-                    });
+                        reference: None,
+                        quality_failure: None,
+                        is_improvement: false,
+                        window: window.outcome,
+                    }
+                    .with_reference(&result, reference, &comparison_config)
+                    .with_quality_check(&result);
+
+                    match comparison_result.window.exclusion_note() {
+                        Some(note) => print_excluded_history_line(&result.name, &note),
+                        None => print_new_baseline_line(
+                            &result.name,
+                            comparison_result.reference.as_ref(),
+                        ),
+                    }
+                    comparisons.push(comparison_result);
                 }
             }
 
-            // Save new baseline with regression flag
-            if let Err(e) = bm.save_baseline(crate_name, &result, is_regression) {
-                eprintln!(
-                    "Warning: Failed to save baseline for {}: {}",
-                    result.name, e
-                );
-            }
+            // Queue the new baseline, flagged with its verdict
+            writer.save(crate_name, &result, comparisons.last().filter(|_| judged));
         }
 
         results.push(result);
         println!(); // Blank line between benchmarks
     }
 
+    // Every baseline is on disk before the session ends
+    if let Some(writer) = baseline_writer {
+        for failure in writer.finish() {
+            eprintln!(
+                "Warning: Failed to save baseline for {}: {}",
+                failure.benchmark_name, failure.error
+            );
+        }
+    }
+
     // Print summary footer
-    if !comparisons.is_empty() {
-        print_streaming_summary(&comparisons, &config.comparison);
+    let skipped: Vec<&BenchResult> = results.iter().filter(|r| r.skipped).collect();
+    if !comparisons.is_empty() || !skipped.is_empty() {
+        print_streaming_summary(&comparisons, &skipped, &config.comparison);
 
         // Show filter stats if filtering was applied
-        if let Some(ref filter) = bench_filter {
+        if let Some(filter) = bench_filter
+            .as_ref()
+            .map(|filter| filter.pattern())
+            .or(group_filter.as_deref())
+        {
             println!(
                 "\n{} {} of {} total benchmarks (filter: \"{}\")",
                 "Ran".dimmed(),
@@ -498,24 +1825,275 @@ mod tests {
             Duration::from_millis(10),
         ];
 
-        let percentiles = calculate_percentiles(&timings);
+        let percentiles = calculate_percentiles(&timings, &DEFAULT_PERCENTILES);
 
-        // For 10 samples: p50 at index 5 (6ms), p90 at index 9 (10ms), p99 at index 9 (10ms)
+        // For 10 samples: p50 halfway between 5ms and 6ms, p90 at rank 8.1
+        // (9.1ms), p99 at rank 8.91 (9.91ms)
         // Mean: (1+2+3+4+5+6+7+8+9+10)/10 = 55/10 = 5.5ms
-        assert_eq!(percentiles.p50, Duration::from_millis(6));
-        assert_eq!(percentiles.p90, Duration::from_millis(10));
-        assert_eq!(percentiles.p99, Duration::from_millis(10));
+        assert_eq!(percentiles.p50(), Duration::from_micros(5500));
+        assert_eq!(percentiles.p90(), Duration::from_micros(9100));
+        assert_eq!(percentiles.p99(), Duration::from_micros(9910));
         assert_eq!(percentiles.mean, Duration::from_micros(5500));
     }
 
+    #[test]
+    fn test_configured_percentiles() {
+        let timings: Vec<Duration> = (1..=1000).map(Duration::from_micros).collect();
+        let percentiles = calculate_percentiles(&timings, &[50.0, 95.0, 99.9]);
+
+        assert_eq!(percentiles.levels(), vec![50.0, 95.0, 99.9]);
+        assert!(percentiles.get(95.0).is_some());
+        assert_eq!(percentiles.get(90.0), None);
+        assert_eq!(percentiles.p90(), Duration::ZERO);
+        assert_eq!(percentile_label(99.9), "p99.9");
+        assert_eq!(percentile_label(50.0), "p50");
+
+        let json = serde_json::to_string(&percentiles).unwrap();
+        let back: Percentiles = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.values, percentiles.values);
+    }
+
+    #[test]
+    fn test_filter_outliers() {
+        // 100 samples around 200µs, one 5ms stall and one 3µs fluke
+        let mut samples: Vec<u128> = (0..100).map(|i| 199_000 + (i % 5) * 500).collect();
+        samples.push(5_000_000);
+        samples.push(3_000);
+
+        let filtered = filter_outliers(&samples, &DEFAULT_PERCENTILES).unwrap();
+        assert_eq!(filtered.outliers_removed, 2);
+        assert_eq!(filtered.statistics.sample_count, 100);
+        assert_eq!(filtered.statistics.max, 201_000);
+        assert_eq!(filtered.statistics.min, 199_000);
+        assert_eq!(filtered.statistics.mean, 200_000);
+        assert!(calculate_statistics_at(&samples, &DEFAULT_PERCENTILES).mean > 240_000);
+
+        let quiet = filter_outliers(&[100, 101, 102, 103], &DEFAULT_PERCENTILES).unwrap();
+        assert_eq!(quiet.outliers_removed, 0);
+        assert!(filter_outliers(&[], &DEFAULT_PERCENTILES).is_none());
+    }
+
+    #[test]
+    fn test_fixed_field_percentiles_deserialize() {
+        let json = r#"{"p50":{"secs":0,"nanos":5000},"p90":{"secs":0,"nanos":9000},"p99":{"secs":0,"nanos":9900},"mean":{"secs":0,"nanos":5500}}"#;
+        let percentiles: Percentiles = serde_json::from_str(json).unwrap();
+
+        assert_eq!(percentiles.levels(), vec![50.0, 90.0, 99.0]);
+        assert_eq!(percentiles.p50(), Duration::from_nanos(5000));
+        assert_eq!(percentiles.p99(), Duration::from_nanos(9900));
+        assert_eq!(percentiles.mean, Duration::from_nanos(5500));
+    }
+
+    #[test]
+    fn test_bench_failure_extracts_panic() {
+        let stderr: Vec<String> = [
+            "Warning: something unrelated",
+            "thread 'main' panicked at src/lib.rs:10:5:",
+            "fixture file missing",
+            "stack backtrace:",
+            "   0: my_crate::setup",
+            "   1: main",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let failure = BenchFailure::from_output(
+            "bench_load",
+            "Benchmark failed with status: exit status: 101".to_string(),
+            Some(101),
+            "partial stdout\n",
+            &stderr,
+        );
+
+        assert_eq!(failure.name, "bench_load");
+        assert_eq!(failure.exit_code, Some(101));
+        assert_eq!(
+            failure.panic_message.as_deref(),
+            Some("fixture file missing")
+        );
+        assert_eq!(
+            failure.backtrace,
+            vec!["   0: my_crate::setup", "   1: main"]
+        );
+        assert_eq!(failure.stdout_tail, vec!["partial stdout"]);
+        assert_eq!(failure.stderr_tail.len(), 6);
+    }
+
+    #[test]
+    fn test_bench_failure_old_panic_format() {
+        let stderr = vec!["thread 'main' panicked at 'boom', src/lib.rs:3:9".to_string()];
+        let failure = BenchFailure::from_output("b", String::new(), Some(101), "", &stderr);
+        assert_eq!(failure.panic_message.as_deref(), Some("boom"));
+        assert!(failure.backtrace.is_empty());
+    }
+
+    #[test]
+    fn test_bench_failure_caps_output() {
+        let stderr: Vec<String> = (0..200).map(|i| format!("line {}", i)).collect();
+        let stdout = "x".repeat(MAX_FAILURE_LINE_CHARS * 2);
+        let failure = BenchFailure::from_output("b", String::new(), None, &stdout, &stderr);
+
+        assert_eq!(failure.stderr_tail.len(), MAX_FAILURE_OUTPUT_LINES);
+        assert_eq!(failure.stderr_tail.last().unwrap(), "line 199");
+        assert_eq!(failure.stdout_tail[0].len(), MAX_FAILURE_LINE_CHARS + 3);
+        assert!(failure.panic_message.is_none());
+    }
+
+    #[test]
+    fn test_throughput_format_rate() {
+        let bytes = Throughput::Bytes(1024 * 1024 * 1024);
+        assert_eq!(
+            bytes.format_rate(Duration::from_millis(500)).unwrap(),
+            "2.00 GiB/s"
+        );
+        assert_eq!(
+            Throughput::Bytes(1024)
+                .format_rate(Duration::from_millis(1))
+                .unwrap(),
+            "1000.00 KiB/s"
+        );
+
+        let elems = Throughput::Elements(1_000);
+        assert_eq!(
+            elems.format_rate(Duration::from_micros(1)).unwrap(),
+            "1.00 Gelem/s"
+        );
+        assert_eq!(
+            elems.format_rate(Duration::from_millis(1)).unwrap(),
+            "1.00 Melem/s"
+        );
+
+        assert!(bytes.format_rate(Duration::ZERO).is_none());
+    }
+
     #[test]
     fn test_calculate_percentiles_single_element() {
         let timings = vec![Duration::from_millis(5)];
-        let percentiles = calculate_percentiles(&timings);
+        let percentiles = calculate_percentiles(&timings, &DEFAULT_PERCENTILES);
 
-        assert_eq!(percentiles.p50, Duration::from_millis(5));
-        assert_eq!(percentiles.p90, Duration::from_millis(5));
-        assert_eq!(percentiles.p99, Duration::from_millis(5));
+        assert_eq!(percentiles.p50(), Duration::from_millis(5));
+        assert_eq!(percentiles.p90(), Duration::from_millis(5));
+        assert_eq!(percentiles.p99(), Duration::from_millis(5));
         assert_eq!(percentiles.mean, Duration::from_millis(5));
     }
+
+    #[test]
+    fn test_storage_collisions_across_crates() {
+        let collisions = storage_collisions([
+            ("game_physics::broad", "bench_step"),
+            ("game_physics::narrow", "bench_step"),
+            ("game_physics", "bench_step"),
+            ("game_physics", "bench_raycast"),
+            ("game_physics_ext", "bench_raycast"),
+            ("game", "physics_ext_bench_raycast"),
+        ]);
+        assert_eq!(
+            collisions,
+            vec![StorageCollision {
+                key: "game_physics_ext_bench_raycast".to_string(),
+                benchmarks: vec![
+                    "game::physics_ext_bench_raycast".to_string(),
+                    "game_physics_ext::bench_raycast".to_string(),
+                ],
+            }]
+        );
+        assert!(storage_collisions([("a::x", "bench_a"), ("a::y", "bench_b")]).is_empty());
+    }
+
+    #[test]
+    fn test_missing_benchmark_reports_module_mismatch() {
+        let registered = [
+            ("game_math::benchmarks", "bench_step"),
+            ("game_physics::benchmarks", "bench_step"),
+            ("game_math::benchmarks", "bench_vec3_normalize"),
+        ];
+
+        // The name exists, just not in the requested module
+        let moved = missing_benchmark_error("bench_step", Some("game_ai::benchmarks"), &registered);
+        assert_eq!(
+            moved.message(),
+            "benchmark 'bench_step' not found in module 'game_ai::benchmarks' of the runner, \
+             only in game_math::benchmarks, game_physics::benchmarks"
+        );
+
+        let missing = missing_benchmark_error(
+            "bench_vec3_normalized",
+            Some("game_math::benchmarks"),
+            &registered,
+        );
+        assert_eq!(
+            missing.message(),
+            "benchmark 'bench_vec3_normalized' not found in the runner (did you mean bench_vec3_normalize?)"
+        );
+    }
+
+    #[test]
+    fn test_closest_names_suggests_near_misses() {
+        let registered = [
+            "bench_vec3_normalize",
+            "bench_vec3_normalise",
+            "bench_matrix_multiply",
+            "bench_vec3_cross",
+        ];
+        assert_eq!(
+            closest_names("bench_vec3_normalized", registered),
+            vec!["bench_vec3_normalize", "bench_vec3_normalise"]
+        );
+        assert!(closest_names("bench_parse_json", registered).is_empty());
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+
+        let error = RunnerError {
+            error: BenchFailure {
+                message: "benchmark 'x' not found in the runner".to_string(),
+                ..Default::default()
+            },
+            suggestions: vec!["a".to_string(), "b".to_string()],
+        };
+        assert_eq!(
+            error.message(),
+            "benchmark 'x' not found in the runner (did you mean a, b?)"
+        );
+        let json = serde_json::to_string(&error).unwrap();
+        let parsed: RunnerError = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.suggestions, error.suggestions);
+    }
+
+    #[test]
+    fn test_calculate_percentiles_empty() {
+        let percentiles = calculate_percentiles(&[], &DEFAULT_PERCENTILES);
+
+        assert_eq!(percentiles.values.len(), DEFAULT_PERCENTILES.len());
+        assert_eq!(percentiles.p50(), Duration::ZERO);
+        assert_eq!(percentiles.p99(), Duration::ZERO);
+        assert_eq!(percentiles.mean, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_robust_dispersion_ignores_preempted_sample() {
+        let samples: Vec<u128> = (100..109).chain([10_000]).collect();
+        let stats = calculate_statistics(&samples);
+
+        assert_eq!(stats.median, 105);
+        assert_eq!(stats.mad, 2.5);
+        assert_eq!(stats.iqr, 5.0);
+        assert!((stats.robust_cv - 2.5 / 105.0).abs() < 1e-12);
+        // The one preempted sample makes std-dev / mean useless
+        assert!(stats.std_dev / stats.mean as f64 > 2.0);
+
+        assert_eq!(calculate_statistics(&[]).robust_cv, 0.0);
+    }
+
+    #[test]
+    fn test_debug_assertion_crates() {
+        let crates = debug_assertion_crates([
+            ("game_math::vectors", true),
+            ("game_math::matrices", true),
+            ("game_physics::collide", false),
+            ("engine", true),
+        ]);
+        assert_eq!(crates, vec!["engine", "game_math"]);
+        assert!(debug_assertion_crates([("game_physics", false)]).is_empty());
+    }
 }
@@ -0,0 +1,440 @@
+//! Single-writer discipline for a machine's baseline directory.
+//!
+//! The orchestrator saves a session's baselines itself, and a standalone
+//! runner (`run_and_stream_benchmarks`) saves its own. Only one process at a
+//! time may save runs and prune under a machine directory: the writer holds
+//! `<machine>/writer.lock`, created exclusively and removed when it's done.
+//! A process that finds the lock held compares against the stored baselines
+//! without saving. A lock left behind by a process that died is taken over.
+//!
+//! Files are written to a temporary name and renamed into place, so readers
+//! never see a partial run or index.
+
+use crate::baseline::{BaselineManager, COMPRESSED_RUN_EXTENSION, RUN_EXTENSION};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Lock file in each machine directory
+pub const WRITER_LOCK_FILE: &str = "writer.lock";
+
+/// Suffixes tried after a run file name is taken (`<timestamp>_01.json`, ...)
+const MAX_RUN_NAME_SUFFIX: usize = 99;
+
+/// Process holding a writer lock, stored in the lock file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    pub since: DateTime<Utc>,
+}
+
+impl LockHolder {
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            since: Utc::now(),
+        }
+    }
+
+    /// Whether the holding process still runs; assumed so where that can't be told
+    pub fn is_alive(&self) -> bool {
+        if self.pid == std::process::id() {
+            return true;
+        }
+        process_exists(self.pid)
+    }
+}
+
+#[cfg(unix)]
+fn process_exists(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    if pid <= 0 {
+        return false;
+    }
+    // Signal 0 only checks that the process exists and may be signalled
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_exists(_pid: u32) -> bool {
+    true
+}
+
+/// Held writer lock of a machine directory, released on drop
+#[derive(Debug)]
+pub struct WriterLock {
+    path: PathBuf,
+    holder: LockHolder,
+}
+
+impl WriterLock {
+    pub fn holder(&self) -> &LockHolder {
+        &self.holder
+    }
+}
+
+impl Drop for WriterLock {
+    fn drop(&mut self) {
+        // A lock taken over after this one was presumed dead isn't ours to remove
+        if read_holder(&self.path).is_some_and(|holder| holder == self.holder) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn read_holder(path: &Path) -> Option<LockHolder> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+impl BaselineManager {
+    /// Take the writer lock of this machine's directory, creating the directory
+    ///
+    /// Fails with [`io::ErrorKind::WouldBlock`] while another live writer, in
+    /// this process or another, holds it.
+    pub fn lock_writer(&self) -> io::Result<WriterLock> {
+        let machine_dir = self.root_dir().join(self.machine_id());
+        fs::create_dir_all(&machine_dir)?;
+        let path = machine_dir.join(WRITER_LOCK_FILE);
+        let holder = LockHolder::current();
+        // Published complete, so a reader never takes a lock being written for stale
+        let temp = temp_path(&path);
+        fs::write(&temp, serde_json::to_string(&holder)?)?;
+        let locked = acquire_with_temp(&temp, &path, &machine_dir);
+        let _ = fs::remove_file(&temp);
+        locked?;
+        Ok(WriterLock { path, holder })
+    }
+
+    /// Whether another live process holds the writer lock of `machine_id`'s directory
+    pub(crate) fn locked_by_other_process(&self, machine_id: &str) -> bool {
+        read_holder(&self.root_dir().join(machine_id).join(WRITER_LOCK_FILE))
+            .is_some_and(|holder| holder.pid != std::process::id() && holder.is_alive())
+    }
+}
+
+/// Publish the lock written to `temp` as `path`: at once when it's free,
+/// again after taking over a dead writer's
+fn acquire_with_temp(temp: &Path, path: &Path, machine_dir: &Path) -> io::Result<()> {
+    for _ in 0..2 {
+        if publish(temp, path)? {
+            return Ok(());
+        }
+        match read_holder(path) {
+            Some(current) if current.is_alive() => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    format!(
+                        "baselines in {} are being written by process {} (since {})",
+                        machine_dir.display(),
+                        current.pid,
+                        current.since.format("%Y-%m-%d %H:%M:%S UTC")
+                    ),
+                ));
+            }
+            stale => take_over(path, stale.as_ref())?,
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::WouldBlock,
+        format!("could not take over {}", path.display()),
+    ))
+}
+
+/// Give the file at `temp` the name `path` unless that's taken; false when it is
+///
+/// Without hard links on the filesystem a rename can't refuse to replace, so
+/// only a name that's free right now is taken.
+fn publish(temp: &Path, path: &Path) -> io::Result<bool> {
+    match fs::hard_link(temp, path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Ok(false),
+        Err(_) if !path.exists() => fs::rename(temp, path).map(|()| true),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Remove the lock file of a dead writer (`stale`, None when unreadable)
+///
+/// The file is renamed aside first and put back when it turns out to be
+/// another process's fresh lock, so two processes taking over at once can't
+/// remove each other's.
+fn take_over(path: &Path, stale: Option<&LockHolder>) -> io::Result<()> {
+    let aside = temp_path(path);
+    match fs::rename(path, &aside) {
+        Ok(()) => {}
+        // Released or taken over by someone else meanwhile
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    }
+    let moved = read_holder(&aside);
+    if moved.is_some() && moved.as_ref() != stale {
+        // Best effort: a fresh lock created meanwhile wins
+        let _ = fs::hard_link(&aside, path);
+    }
+    fs::remove_file(&aside)
+}
+
+/// Unique hidden name next to `path`, without the `.json` extension so run
+/// listings skip it
+fn temp_path(path: &Path) -> PathBuf {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let name = path
+        .file_name()
+        .map_or_else(String::new, |n| n.to_string_lossy().to_string());
+    path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        name,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Replace `path` with `contents` in one step: readers see the old file or
+/// the new one, never a partial write
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp = temp_path(path);
+    fs::write(&temp, contents)?;
+    let renamed = fs::rename(&temp, path);
+    if renamed.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    renamed
+}
+
+/// Write a new `<stem><extension>` in `dir` without replacing an existing
+/// run: a run saved in the same second gets the next free `<stem>_NN`, free
+/// meaning no run of that name is stored compressed or not
+///
+/// Returns the path written.
+pub(crate) fn create_run_file(
+    dir: &Path,
+    stem: &str,
+    extension: &str,
+    contents: &[u8],
+) -> io::Result<PathBuf> {
+    let temp = temp_path(&dir.join(format!("{}{}", stem, extension)));
+    fs::write(&temp, contents)?;
+
+    let mut result = Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("no free run file name for {} in {}", stem, dir.display()),
+    ));
+    for suffix in 0..=MAX_RUN_NAME_SUFFIX {
+        let name = match suffix {
+            0 => stem.to_string(),
+            n => format!("{}_{:02}", stem, n),
+        };
+        let taken = [RUN_EXTENSION, COMPRESSED_RUN_EXTENSION]
+            .iter()
+            .any(|other| *other != extension && dir.join(format!("{}{}", name, other)).exists());
+        if taken {
+            continue;
+        }
+        let path = dir.join(format!("{}{}", name, extension));
+        match publish(&temp, &path) {
+            Ok(true) => {
+                result = Ok(path);
+                break;
+            }
+            Ok(false) => continue,
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+    let _ = fs::remove_file(&temp);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BenchResult;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn result(name: &str, mean_us: u64) -> BenchResult {
+        BenchResult {
+            name: name.to_string(),
+            module: "my_crate".to_string(),
+            samples: 50,
+            all_timings: vec![Duration::from_micros(mean_us); 50],
+            ..Default::default()
+        }
+    }
+
+    /// No temporary files left anywhere under `dir`
+    fn assert_no_temp_files(dir: &Path) {
+        for entry in fs::read_dir(dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                assert_no_temp_files(&path);
+            } else {
+                assert!(
+                    !path.extension().is_some_and(|ext| ext == "tmp"),
+                    "left behind: {}",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_writer_lock_is_exclusive() {
+        let temp = TempDir::new().unwrap();
+        let first = BaselineManager::with_root_dir(temp.path()).unwrap();
+        let second = BaselineManager::with_root_dir(temp.path()).unwrap();
+
+        let lock = first.lock_writer().unwrap();
+        assert_eq!(lock.holder().pid, std::process::id());
+        let error = second.lock_writer().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::WouldBlock);
+        assert!(error.to_string().contains(&std::process::id().to_string()));
+
+        drop(lock);
+        assert!(!temp
+            .path()
+            .join(first.machine_id())
+            .join(WRITER_LOCK_FILE)
+            .exists());
+        assert!(second.lock_writer().is_ok());
+    }
+
+    #[test]
+    fn test_writer_without_lock_only_compares() {
+        let temp = TempDir::new().unwrap();
+        let manager = Arc::new(BaselineManager::with_root_dir(temp.path()).unwrap());
+        let held = manager.lock_writer().unwrap();
+
+        let mut writer = crate::baseline::BaselineWriter::spawn(Arc::clone(&manager), None);
+        assert!(!writer.is_saving());
+        writer.save("my_crate", &result("bench_a", 100), None);
+        assert!(writer.finish().is_empty());
+        assert!(manager.list_runs("my_crate", "bench_a").unwrap().is_empty());
+
+        // Released at finish, so the next session saves
+        drop(held);
+        let mut writer = crate::baseline::BaselineWriter::spawn(Arc::clone(&manager), None);
+        assert!(writer.is_saving());
+        writer.save("my_crate", &result("bench_a", 100), None);
+        writer.finish();
+        assert_eq!(manager.list_runs("my_crate", "bench_a").unwrap().len(), 1);
+        assert!(manager.lock_writer().is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dead_writers_lock_is_taken_over() {
+        let temp = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp.path()).unwrap();
+        let machine_dir = temp.path().join(manager.machine_id());
+        fs::create_dir_all(&machine_dir).unwrap();
+
+        // Above any pid Linux or macOS hands out
+        let dead = LockHolder {
+            pid: 99_999_999,
+            since: Utc::now(),
+        };
+        assert!(!dead.is_alive());
+        fs::write(
+            machine_dir.join(WRITER_LOCK_FILE),
+            serde_json::to_string(&dead).unwrap(),
+        )
+        .unwrap();
+        assert!(!manager.locked_by_other_process(manager.machine_id()));
+
+        let lock = manager.lock_writer().unwrap();
+        assert_eq!(lock.holder().pid, std::process::id());
+
+        // An unreadable lock file counts as stale too
+        drop(lock);
+        fs::write(machine_dir.join(WRITER_LOCK_FILE), "garbage").unwrap();
+        assert!(manager.lock_writer().is_ok());
+        assert_no_temp_files(temp.path());
+    }
+
+    #[test]
+    fn test_create_run_file_never_replaces() {
+        let temp = TempDir::new().unwrap();
+        let stem = "2025-01-01T00-00-00";
+
+        let first = create_run_file(temp.path(), stem, RUN_EXTENSION, b"1").unwrap();
+        let second = create_run_file(temp.path(), stem, RUN_EXTENSION, b"2").unwrap();
+        let third = create_run_file(temp.path(), stem, RUN_EXTENSION, b"3").unwrap();
+        assert_eq!(first, temp.path().join(format!("{}.json", stem)));
+        assert_eq!(second, temp.path().join(format!("{}_01.json", stem)));
+        assert_eq!(third, temp.path().join(format!("{}_02.json", stem)));
+        assert_eq!(fs::read_to_string(first).unwrap(), "1");
+        assert_eq!(fs::read_to_string(third).unwrap(), "3");
+
+        // A compressed run doesn't take the name of an uncompressed one
+        let fourth = create_run_file(temp.path(), stem, COMPRESSED_RUN_EXTENSION, b"4").unwrap();
+        assert_eq!(fourth, temp.path().join(format!("{}_03.json.gz", stem)));
+
+        // Same-second runs still sort after the first when listed
+        let mut names: Vec<_> = fs::read_dir(temp.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        names.sort();
+        assert_eq!(names[0].to_string_lossy(), format!("{}.json", stem));
+        assert_no_temp_files(temp.path());
+    }
+
+    #[test]
+    fn test_concurrent_save_load_prune() {
+        const SAVES_PER_THREAD: usize = 40;
+        let temp = TempDir::new().unwrap();
+        let manager = Arc::new(BaselineManager::with_root_dir(temp.path()).unwrap());
+
+        let workers: Vec<_> = ["bench_a", "bench_b"]
+            .into_iter()
+            .map(|own| {
+                let manager = Arc::clone(&manager);
+                std::thread::spawn(move || {
+                    for i in 0..SAVES_PER_THREAD {
+                        // Both threads save both benchmarks, mostly within one second
+                        let name = if i % 2 == 0 { own } else { "bench_shared" };
+                        manager
+                            .save_baseline("my_crate", &result(name, 100 + i as u64), false)
+                            .unwrap();
+
+                        let window = manager
+                            .load_recent_baselines("my_crate", "bench_shared", 5)
+                            .unwrap();
+                        assert!(window.len() <= 5);
+                        manager.load_baseline("my_crate", own).unwrap();
+                        manager.disk_usage().unwrap();
+                        // Over any limit, but every run is protected
+                        manager.prune_to_size(0, usize::MAX).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        let runs = |name: &str| manager.list_runs("my_crate", name).unwrap().len();
+        assert_eq!(runs("bench_a"), SAVES_PER_THREAD / 2);
+        assert_eq!(runs("bench_b"), SAVES_PER_THREAD / 2);
+        assert_eq!(runs("bench_shared"), SAVES_PER_THREAD);
+
+        // The cached index agrees with a fresh count
+        let usage = manager.disk_usage().unwrap();
+        assert_eq!(usage.total_runs(), 2 * SAVES_PER_THREAD);
+        fs::remove_file(temp.path().join("usage.json")).unwrap();
+        let rescanned = manager.disk_usage().unwrap();
+        assert_eq!(rescanned.total_bytes(), usage.total_bytes());
+        assert_no_temp_files(temp.path());
+    }
+}
@@ -1,5 +1,21 @@
+use crate::alloc_tracking::{read_alloc_counts, AllocCounts};
+use crate::baseline::{BaselineData, BaselineManager, MeasurementSettings};
+use crate::cpu_monitor::attribute_thread_usage;
+use crate::energy::{mean_watts, RaplDomain};
+use crate::perf_counters::{PerfCounters, PerfSample};
 use crate::progress::{emit_progress, ProgressMessage, ProgressPhase};
-use crate::{calculate_percentiles, config::BenchmarkConfig, BenchResult, CpuMonitor, CpuSnapshot};
+use crate::statistics::{mean, normal_quantile, standard_deviation, z_critical};
+use crate::streaming::{store_in_slot, Moments, Reservoir, StreamingStats, RESERVOIR_SIZE};
+use crate::{
+    calculate_percentiles, calculate_statistics,
+    config::{BenchmarkConfig, ClockMode, ComparisonMetric, MeasurementConfig, WarmupMode},
+    filter_outliers, percentiles_from_statistics, thread_cpu_time, thread_usage, AllocStats,
+    BenchFailure, BenchResult, CpuMonitor, CpuSampler, CpuSnapshot, EarlyVerdict,
+    PathologicalSample, RollingStats, Statistics, ThreadUsage, CPU_SAMPLE_INTERVAL,
+};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::hint::black_box;
 use std::time::{Duration, Instant};
 
 /// Get the CPU core this thread is pinned to (if any)
@@ -11,489 +27,1411 @@ fn get_pinned_core() -> usize {
         .unwrap_or(0)
 }
 
-/// Warmup using a closure (generic version for new measurement functions)
-fn warmup_closure<F>(func: &mut F, duration: Duration, bench_name: &str) -> (u128, u64)
-where
-    F: FnMut(),
-{
-    let start = Instant::now();
-    let mut total_iterations = 0u64;
-    let mut last_report = Instant::now();
-    let target_ms = duration.as_millis() as u64;
-
-    while start.elapsed() < duration {
-        func();
-        total_iterations += 1;
-
-        // Emit progress every 100ms
-        if last_report.elapsed() >= Duration::from_millis(100) {
-            emit_progress(&ProgressMessage {
-                bench: bench_name,
-                phase: ProgressPhase::Warmup {
-                    elapsed_ms: start.elapsed().as_millis() as u64,
-                    target_ms,
-                },
-            });
-            last_report = Instant::now();
-        }
+/// Output of a benchmark setup: either the data itself, or a `Result` whose
+/// error fails the benchmark cleanly instead of panicking.
+///
+/// Implemented for every `T` (infallible setup) and for `Result<T, E>` with
+/// `E: Display`. For a fallible setup, `T` must match the benchmark parameter
+/// exactly (e.g. `Result<Vec<u8>, _>` with `data: &Vec<u8>`), since deref
+/// coercion can't be inferred through the `Result`.
+pub trait IntoSetupResult<T> {
+    /// Convert into the setup data, or a displayable error message
+    fn into_setup_result(self) -> Result<T, String>;
+}
+
+impl<T> IntoSetupResult<T> for T {
+    fn into_setup_result(self) -> Result<T, String> {
+        Ok(self)
     }
+}
 
-    (start.elapsed().as_millis(), total_iterations)
+impl<T, E: Display> IntoSetupResult<T> for Result<T, E> {
+    fn into_setup_result(self) -> Result<T, String> {
+        self.map_err(|e| e.to_string())
+    }
 }
 
-/// Measure a closure, collecting timing samples with CPU monitoring
-fn measure_closure<F>(
-    func: &mut F,
-    samples: usize,
-    bench_name: &str,
-) -> (Vec<Duration>, Vec<CpuSnapshot>)
-where
-    F: FnMut(),
-{
-    let mut all_timings = Vec::with_capacity(samples);
-    let mut cpu_samples = Vec::with_capacity(samples);
-
-    // Initialize CPU monitor for the pinned core
-    let cpu_core = get_pinned_core();
-    let monitor = CpuMonitor::new(cpu_core);
-
-    // Report progress every ~1% of samples (minimum every sample for small counts)
-    let report_interval = (samples / 100).max(1);
-
-    for sample_idx in 0..samples {
-        // Emit progress BEFORE timing (so we don't affect measurements)
-        if sample_idx % report_interval == 0 {
-            emit_progress(&ProgressMessage {
-                bench: bench_name,
-                phase: ProgressPhase::Samples {
-                    current: sample_idx as u32,
-                    total: samples as u32,
-                },
-            });
-        }
+/// Manual timing handle for `#[bench(manual_timer)]` benchmarks.
+///
+/// Only the time between `start()` and `stop()` counts towards the sample, so
+/// per-iteration work that depends on loop state can be left out of the
+/// measurement. Several start/stop pairs in one call accumulate; a region still
+/// running when the benchmark returns is stopped at that point.
+#[derive(Debug, Default)]
+pub struct Timer {
+    started: Option<Instant>,
+    accumulated: Duration,
+    used: bool,
+}
 
-        // Read CPU frequency BEFORE measurement (while CPU is active)
-        let freq_before = monitor.read_frequency();
+impl Timer {
+    /// Start (or restart) the measured region
+    #[inline(always)]
+    pub fn start(&mut self) {
+        self.used = true;
+        self.started = Some(Instant::now());
+    }
 
-        let start = Instant::now();
-        func();
-        let elapsed = start.elapsed();
-        all_timings.push(elapsed);
+    /// Stop the measured region, adding it to this sample's time
+    #[inline(always)]
+    pub fn stop(&mut self) {
+        let now = Instant::now();
+        self.used = true;
+        if let Some(started) = self.started.take() {
+            self.accumulated += now - started;
+        }
+    }
 
-        // Read frequency after as well, use the higher of the two
-        let freq_after = monitor.read_frequency();
-        let frequency_khz = match (freq_before, freq_after) {
-            (Some(before), Some(after)) => Some(before.max(after)),
-            (Some(f), None) | (None, Some(f)) => Some(f),
-            (None, None) => None,
-        };
+    /// Time recorded so far in this sample
+    pub fn elapsed(&self) -> Duration {
+        self.accumulated
+    }
 
-        let snapshot = CpuSnapshot {
-            timestamp: Instant::now(),
-            frequency_khz,
-            temperature_millic: monitor.read_temperature(),
-        };
-        cpu_samples.push(snapshot);
+    /// Clear the timer before a new sample
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// The sample's recorded time, or `None` if neither start nor stop was called
+    fn recorded(&self) -> Option<Duration> {
+        self.used.then_some(self.accumulated)
     }
+}
+
+/// Number of empty timed regions measured by [`calibrate_timer_overhead`]
+const TIMER_CALIBRATION_ROUNDS: usize = 1000;
+
+/// Measure the median cost of an empty timed region.
+///
+/// For nanosecond-scale benchmarks a large share of every sample is the cost
+/// of reading the clock itself. The region timed here matches the one around
+/// `run` in [`SampleLoop::iteration`], the CPU clock reads nested in it
+/// included with `read_cpu`, so it can be subtracted from each sample.
+fn calibrate_timer_overhead(read_cpu: bool) -> Duration {
+    let mut timings: Vec<Duration> = (0..TIMER_CALIBRATION_ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            if read_cpu {
+                black_box(thread_cpu_time());
+                black_box(thread_cpu_time());
+            } else {
+                black_box(());
+            }
+            start.elapsed()
+        })
+        .collect();
+    timings.sort_unstable();
+    timings[timings.len() / 2]
+}
+
+/// Measure the median thread CPU time of an empty region between two CPU
+/// clock reads, as [`calibrate_timer_overhead`] does for the wall clock; None
+/// without a thread CPU clock
+fn calibrate_cpu_clock_overhead() -> Option<Duration> {
+    let mut timings = (0..TIMER_CALIBRATION_ROUNDS)
+        .map(|_| {
+            let start = thread_cpu_time()?;
+            black_box(());
+            Some(thread_cpu_time()?.saturating_sub(start))
+        })
+        .collect::<Option<Vec<Duration>>>()?;
+    timings.sort_unstable();
+    Some(timings[timings.len() / 2])
+}
 
-    // Emit completion message
+/// Report a failed run: print an error naming the benchmark, close out its
+/// progress, and build the failure record.
+fn bench_failure(bench_name: &str, message: String) -> Box<BenchFailure> {
+    eprintln!("ERROR: benchmark '{}' failed: {}", bench_name, message);
     emit_progress(&ProgressMessage {
-        bench: bench_name,
+        bench: bench_name.into(),
         phase: ProgressPhase::Complete,
     });
 
-    (all_timings, cpu_samples)
+    Box::new(BenchFailure {
+        name: bench_name.to_string(),
+        message,
+        ..Default::default()
+    })
 }
 
-/// Measure a simple benchmark (no setup) using the new architecture.
-///
-/// This function is called by the generated benchmark wrapper for benchmarks
-/// without setup code. The config is passed in, and a complete BenchResult is returned.
-pub fn measure_simple<F>(
-    config: &BenchmarkConfig,
-    name: &str,
-    module: &str,
-    mut func: F,
-) -> BenchResult
-where
-    F: FnMut(),
-{
-    // Warmup
-    let (warmup_ms, warmup_iters) = warmup_closure(
-        &mut func,
-        Duration::from_secs(config.measurement.warmup_duration_secs),
-        name,
-    );
-
-    // Measurement
-    let (all_timings, cpu_samples) = measure_closure(&mut func, config.measurement.samples, name);
-
-    let percentiles = calculate_percentiles(&all_timings);
+/// Early-stop rule for adaptive sampling (`adaptive = true`)
+#[derive(Debug, Clone, Copy)]
+struct AdaptiveStop {
+    /// Stop once the CV of the recent window drops below this percentage
+    target_cv: f64,
+    /// Fewest samples to take; also the size of the CV window
+    min_samples: usize,
+}
 
-    BenchResult {
-        name: name.to_string(),
-        module: module.to_string(),
-        samples: config.measurement.samples,
-        percentiles,
-        all_timings,
-        cpu_samples,
-        warmup_ms: Some(warmup_ms),
-        warmup_iterations: Some(warmup_iters),
+impl AdaptiveStop {
+    fn from_config(measurement: &MeasurementConfig) -> Option<Self> {
+        measurement.adaptive.then_some(Self {
+            target_cv: measurement.target_cv,
+            min_samples: measurement.min_samples.max(2),
+        })
     }
 }
 
-/// Measure a benchmark with setup code that runs once before measurement.
+/// Fewest samples between early-verdict checks, so the running mean's interval
+/// never rests on a handful of samples
+const MIN_EARLY_VERDICT_INTERVAL: usize = 30;
+
+/// Early-termination rule (`early_verdict = true`): stop once the running mean
+/// is certainly past the regression bar of the baseline window, or certainly
+/// under it, for two checks in a row
 ///
-/// This function is called by the generated benchmark wrapper for benchmarks
-/// with the `setup` attribute. Setup runs exactly once, then the benchmark
-/// function receives a reference to the setup data for each iteration.
-pub fn measure_with_setup<T, S, B>(
-    config: &BenchmarkConfig,
-    name: &str,
-    module: &str,
-    setup: S,
-    mut bench: B,
-) -> BenchResult
-where
-    S: FnOnce() -> T,
-    B: FnMut(&T),
-{
-    // Run setup ONCE before any measurement
-    let data = setup();
+/// The bar is the one [`crate::baseline::detect_regression_with_cpd`] applies:
+/// above the window's confidence interval and beyond the threshold. The final
+/// verdict is still computed from all collected samples.
+#[derive(Debug, Clone, Copy)]
+struct EarlyVerdictRule {
+    /// Lowest mean, in nanoseconds, that is both statistically and practically
+    /// significant against the window
+    regression_bar: f64,
+    /// One-tailed z of `early_verdict_confidence` for the running mean's interval
+    z_running: f64,
+    /// Samples between checks
+    interval: usize,
+}
 
-    // Create closure that borrows the setup data
-    let mut func = || bench(&data);
+impl EarlyVerdictRule {
+    /// Load this benchmark's baseline window; None if disabled or the window
+    /// has fewer than two runs
+    ///
+    /// Only wall-time comparisons are judged early.
+    fn load(config: &BenchmarkConfig, name: &str, module: &str) -> Option<Self> {
+        if !config.measurement.early_verdict
+            || config.comparison.metric != ComparisonMetric::WallTime
+        {
+            return None;
+        }
 
-    // Warmup
-    let (warmup_ms, warmup_iters) = warmup_closure(
-        &mut func,
-        Duration::from_secs(config.measurement.warmup_duration_secs),
-        name,
-    );
+        let config = &BenchmarkConfig {
+            comparison: config.comparison.for_benchmark(name),
+            ..config.clone()
+        };
+        let (crate_name, benchmark_name) = crate::baseline::storage_name(module, name);
+        let window = BaselineManager::for_workspace(std::path::Path::new("."), &config.baseline)
+            .ok()?
+            .with_follow_renames(config.comparison.follow_renames)
+            .with_improvement_resets_window(config.comparison.improvement_resets_window)
+            .with_compare_across_machines(config.comparison.compare_across_machines)
+            .load_comparable_window(
+                crate_name,
+                &benchmark_name,
+                config.comparison.window_size,
+                Some(&MeasurementSettings::from_config(&config.measurement)),
+            )
+            .ok()?
+            .runs;
+        Self::from_window(&window, config)
+    }
 
-    // Measurement
-    let (all_timings, cpu_samples) = measure_closure(&mut func, config.measurement.samples, name);
+    fn from_window(window: &[BaselineData], config: &BenchmarkConfig) -> Option<Self> {
+        if window.len() < 2 {
+            return None;
+        }
 
-    let percentiles = calculate_percentiles(&all_timings);
+        let means: Vec<f64> = window.iter().map(|b| b.statistics.mean as f64).collect();
+        let window_mean = mean(&means);
+        let upper_bound = window_mean
+            + z_critical(config.comparison.confidence_level) * standard_deviation(&means);
+        let practical = window_mean * (1.0 + config.comparison.threshold / 100.0);
+
+        Some(Self {
+            regression_bar: upper_bound.max(practical),
+            z_running: normal_quantile(config.measurement.early_verdict_confidence),
+            interval: config
+                .measurement
+                .early_verdict_interval
+                .max(MIN_EARLY_VERDICT_INTERVAL),
+        })
+    }
 
-    BenchResult {
-        name: name.to_string(),
-        module: module.to_string(),
-        samples: config.measurement.samples,
-        percentiles,
-        all_timings,
-        cpu_samples,
-        warmup_ms: Some(warmup_ms),
-        warmup_iterations: Some(warmup_iters),
+    /// The verdict the samples so far already settle, checked every `interval` samples
+    fn check(&self, samples: &Moments) -> Option<EarlyVerdict> {
+        let count = samples.count() as usize;
+        if count == 0 || count % self.interval != 0 {
+            return None;
+        }
+
+        let running_mean = samples.mean();
+        let margin = self.z_running * samples.std_dev() / (count as f64).sqrt();
+
+        if running_mean - margin > self.regression_bar {
+            Some(EarlyVerdict::Regression)
+        } else if running_mean + margin < self.regression_bar {
+            Some(EarlyVerdict::NoRegression)
+        } else {
+            None
+        }
     }
 }
 
-/// Warmup with setup running before each call (for setup_each benchmarks)
-fn warmup_with_setup<T, S, B>(
-    setup: &mut S,
-    bench: &mut B,
-    duration: Duration,
-    bench_name: &str,
-) -> (u128, u64)
-where
-    S: FnMut() -> T,
-    B: FnMut(T),
-{
-    let start = Instant::now();
-    let mut total_iterations = 0u64;
-    let mut last_report = Instant::now();
-    let target_ms = duration.as_millis() as u64;
-
-    while start.elapsed() < duration {
-        let data = setup();
-        bench(data);
-        total_iterations += 1;
-
-        // Emit progress every 100ms
-        if last_report.elapsed() >= Duration::from_millis(100) {
-            emit_progress(&ProgressMessage {
-                bench: bench_name,
-                phase: ProgressPhase::Warmup {
-                    elapsed_ms: start.elapsed().as_millis() as u64,
-                    target_ms,
-                },
-            });
-            last_report = Instant::now();
+/// How often adaptive warmup reads the CPU frequency
+const FREQUENCY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Adaptive warmup counts a reading as settled within this percentage of the peak
+const FREQUENCY_SETTLED_PERCENT: f64 = 2.0;
+
+/// Stop rule for adaptive warmup: the frequency stayed within
+/// [`FREQUENCY_SETTLED_PERCENT`] of the highest reading so far for
+/// `required` consecutive readings
+struct FrequencySettle {
+    peak_khz: u64,
+    stable: usize,
+    required: usize,
+}
+
+impl FrequencySettle {
+    fn new(required: usize) -> Self {
+        Self {
+            peak_khz: 0,
+            stable: 0,
+            required: required.max(1),
         }
     }
 
-    (start.elapsed().as_millis(), total_iterations)
+    /// Record a reading; true once the frequency has settled
+    fn observe(&mut self, khz: u64) -> bool {
+        let band = FREQUENCY_SETTLED_PERCENT / 100.0;
+        let khz_f = khz as f64;
+        let peak = self.peak_khz as f64;
+
+        if self.peak_khz == 0 || khz_f > peak * (1.0 + band) {
+            // Still ramping: earlier readings weren't near this new peak
+            self.peak_khz = khz;
+            self.stable = 1;
+        } else if khz_f >= peak * (1.0 - band) {
+            self.peak_khz = self.peak_khz.max(khz);
+            self.stable += 1;
+        } else {
+            self.stable = 0;
+        }
+        self.stable >= self.required
+    }
 }
 
-/// Warmup with setup running before each call, borrowing version
-fn warmup_with_setup_ref<T, S, B>(
-    setup: &mut S,
-    bench: &mut B,
-    duration: Duration,
-    bench_name: &str,
-) -> (u128, u64)
-where
-    S: FnMut() -> T,
-    B: FnMut(&T),
-{
-    let start = Instant::now();
-    let mut total_iterations = 0u64;
-    let mut last_report = Instant::now();
-    let target_ms = duration.as_millis() as u64;
-
-    while start.elapsed() < duration {
-        let data = setup();
-        bench(&data);
-        total_iterations += 1;
-
-        // Emit progress every 100ms
-        if last_report.elapsed() >= Duration::from_millis(100) {
-            emit_progress(&ProgressMessage {
-                bench: bench_name,
-                phase: ProgressPhase::Warmup {
-                    elapsed_ms: start.elapsed().as_millis() as u64,
-                    target_ms,
-                },
+/// Recent samples the watchdog's running median is taken over
+const WATCHDOG_WINDOW: usize = 128;
+
+/// Samples between recomputations of the watchdog's running median
+const WATCHDOG_MEDIAN_REFRESH: usize = 32;
+
+/// Fewest samples before the watchdog compares against the running median
+const WATCHDOG_MIN_SAMPLES: usize = 10;
+
+/// Running median below which the factor check is skipped, in nanoseconds:
+/// samples that short are timer noise, and any multiple of them is too
+const WATCHDOG_MIN_MEDIAN_NS: u64 = 5;
+
+/// Per-sample check for pathologically slow samples (`watchdog_factor`,
+/// `watchdog_max_sample_ms`), run after each sample completes.
+///
+/// The median is recomputed every [`WATCHDOG_MEDIAN_REFRESH`] samples over
+/// the last [`WATCHDOG_WINDOW`], so the check costs a comparison per sample.
+/// Flagged samples stay out of the window.
+#[derive(Debug, Clone)]
+struct Watchdog {
+    /// Multiple of the running median that flags a sample; 0 disables
+    factor: f64,
+    /// Duration that flags a sample regardless of the median
+    ceiling: Option<Duration>,
+    /// Stop sampling at the first flagged sample
+    stop: bool,
+    recent: std::collections::VecDeque<u64>,
+    since_refresh: usize,
+    median_ns: Option<u64>,
+    /// Median the factor check needs, at least [`WATCHDOG_MIN_MEDIAN_NS`]
+    min_median_ns: u64,
+}
+
+impl Watchdog {
+    /// None if neither the factor nor the ceiling is set
+    fn from_config(measurement: &MeasurementConfig) -> Option<Self> {
+        let factor = if measurement.watchdog_factor.is_finite() {
+            measurement.watchdog_factor.max(0.0)
+        } else {
+            0.0
+        };
+        let ceiling = measurement
+            .watchdog_max_sample_ms
+            .and_then(|ms| Duration::try_from_secs_f64(ms / 1000.0).ok());
+        (factor > 0.0 || ceiling.is_some()).then(|| Self {
+            factor,
+            ceiling,
+            stop: measurement.watchdog_stop,
+            recent: std::collections::VecDeque::with_capacity(WATCHDOG_WINDOW),
+            since_refresh: 0,
+            median_ns: None,
+            min_median_ns: WATCHDOG_MIN_MEDIAN_NS,
+        })
+    }
+
+    /// Skip the factor check while the median is within the timer's own overhead
+    fn with_timer_overhead(mut self, overhead: Duration) -> Self {
+        self.min_median_ns = self.min_median_ns.max(crate::nanos_u64(overhead));
+        self
+    }
+
+    /// Check the sample recorded at `index`; Some if it is pathological
+    fn check(&mut self, index: usize, elapsed: Duration) -> Option<PathologicalSample> {
+        let duration_ns = crate::nanos_u64(elapsed);
+        let over_median = self.factor > 0.0
+            && self.median_ns.is_some_and(|median| {
+                median >= self.min_median_ns && duration_ns as f64 > median as f64 * self.factor
+            });
+        let over_ceiling = self.ceiling.is_some_and(|ceiling| elapsed > ceiling);
+        if over_median || over_ceiling {
+            return Some(PathologicalSample {
+                index,
+                duration_ns,
+                median_ns: self.median_ns,
             });
-            last_report = Instant::now();
         }
+
+        if self.recent.len() == WATCHDOG_WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(duration_ns);
+        self.since_refresh += 1;
+        if self.recent.len() >= WATCHDOG_MIN_SAMPLES
+            && (self.median_ns.is_none() || self.since_refresh >= WATCHDOG_MEDIAN_REFRESH)
+        {
+            let mut sorted: Vec<u64> = self.recent.iter().copied().collect();
+            sorted.sort_unstable();
+            self.median_ns = Some(sorted[sorted.len() / 2]);
+            self.since_refresh = 0;
+        }
+        None
+    }
+}
+
+/// Fewest samples taken before `max_time_secs` may cut measurement short
+const MIN_SAMPLES_WITHIN_BUDGET: usize = 30;
+
+/// Number of samples to collect: `samples`, or `max_samples` as the adaptive cap
+pub(crate) fn sample_budget(measurement: &MeasurementConfig) -> usize {
+    if measurement.adaptive {
+        measurement.max_samples.max(measurement.min_samples)
+    } else {
+        measurement.samples
     }
+}
 
-    (start.elapsed().as_millis(), total_iterations)
+/// Per-sample data recorded by [`SampleLoop::measure`]
+struct Measurements {
+    /// Wall-clock time of each sample, or a uniform reservoir of them without
+    /// raw samples; the other per-sample series hold the same samples
+    timings: Vec<Duration>,
+    /// Statistics over all samples, when `timings` is a reservoir
+    summary: Option<Statistics>,
+    /// Start of each sample in milliseconds since measurement began, index-aligned with `timings`
+    offsets_ms: Vec<u64>,
+    /// Thread CPU time of each sample; empty if unavailable on this platform
+    cpu_timings: Vec<Duration>,
+    /// Background CPU readings over the measurement, oldest first, each with
+    /// the thread counters of the samples since the previous one
+    cpu_samples: Vec<CpuSnapshot>,
+    /// Involuntary context switches of each sample; empty if unavailable
+    involuntary_switches: Vec<u64>,
+    /// Heap activity of each sample; empty without allocation tracking
+    alloc_counts: Vec<AllocCounts>,
+    /// Hardware counters of each sample; empty if unavailable
+    perf_samples: Vec<PerfSample>,
+    /// The time budget ran out, or the watchdog stopped sampling, before the
+    /// sample count was reached
+    truncated: bool,
+    /// Sampling stopped at a pathological sample (`watchdog_stop`)
+    watchdog_stopped: bool,
+    /// Samples the watchdog flagged
+    pathological_samples: Vec<PathologicalSample>,
+    /// Sampling ended on a settled early verdict
+    early_verdict: Option<EarlyVerdict>,
+    /// Mean timed duration of a recorded iteration
+    ns_per_iter: Option<f64>,
+    /// Package energy over the loop in microjoules, and its mean power
+    energy_uj: Option<u64>,
+    mean_watts: Option<f64>,
 }
 
-/// Measure a benchmark where setup runs before every sample (owning version).
+/// What the warmup phase of [`SampleLoop`] ran
+struct Warmup {
+    elapsed_ms: u128,
+    iterations: u64,
+    /// Mean timed duration of a warmup iteration, measured like a sample
+    ns_per_iter: Option<f64>,
+    mode: WarmupMode,
+}
+
+/// What a single iteration of [`SampleLoop`] observed
+struct Sample {
+    /// When the timed call began
+    started: Instant,
+    elapsed: Duration,
+    /// Thread CPU time, if available on this platform
+    cpu_time: Option<Duration>,
+    /// Heap activity during the timed call, with allocation tracking
+    allocs: Option<AllocCounts>,
+    /// Hardware counters over the call, if they could be opened
+    perf: Option<PerfSample>,
+    /// Context switches and page faults during the call (Linux)
+    usage: Option<ThreadUsage>,
+}
+
+/// The inner loop shared by warmup and measurement.
 ///
-/// The benchmark function takes ownership of the data produced by setup.
-/// This allows benchmarking operations that consume or mutate their input.
-pub fn measure_with_setup_each<T, S, B>(
-    config: &BenchmarkConfig,
-    name: &str,
-    module: &str,
-    mut setup: S,
-    mut bench: B,
-) -> BenchResult
-where
-    S: FnMut() -> T,
-    B: FnMut(T),
-{
-    // Warmup: run setup + bench together
-    let (warmup_ms, warmup_iters) = warmup_with_setup(
-        &mut setup,
-        &mut bench,
-        Duration::from_secs(config.measurement.warmup_duration_secs),
-        name,
-    );
+/// Every iteration runs exactly the same code in both phases: an untimed
+/// `prepare` step, the timed `run` step and an untimed drop of whatever `run`
+/// hands back (plus optional settle time). Warmup and measurement differ only
+/// in whether the timing is kept, so warmup trains caches and the branch
+/// predictor on the measured code path. CPU frequency and temperature are
+/// read by a [`CpuSampler`] thread during measurement, never per iteration.
+struct SampleLoop<'a> {
+    bench_name: &'a str,
+    /// Core the benchmark is pinned to
+    cpu_core: usize,
+    /// Polled by adaptive warmup only
+    monitor: CpuMonitor,
+    settle_after_drop: Duration,
+    /// Timer driven by the benchmark itself; replaces the wall-clock timing of `run`
+    manual_timer: Option<&'a RefCell<Timer>>,
+    /// Cost of an empty timed region, subtracted from each wall-clock timing
+    timer_overhead: Duration,
+    /// Cost of an empty CPU-clock region, subtracted from each CPU timing;
+    /// None when the CPU clock isn't read
+    cpu_clock_overhead: Option<Duration>,
+    /// Stop measuring before the sample budget once timings stabilize
+    adaptive: Option<AdaptiveStop>,
+    /// End of the `max_time_secs` budget, counted from the start of warmup
+    deadline: Option<Instant>,
+    clock: ClockMode,
+    /// Hardware counters of this thread (`perf-counters` feature, Linux)
+    perf: Option<PerfCounters>,
+    warmup_mode: WarmupMode,
+    /// Longest adaptive warmup
+    max_warmup: Duration,
+    /// Settled frequency readings that end adaptive warmup
+    warmup_stable_readings: usize,
+    /// Leading samples measured but not recorded
+    discard_samples: usize,
+    /// Stop once the verdict against the baseline window is certain
+    early_verdict: Option<EarlyVerdictRule>,
+    /// Flags pathologically slow samples
+    watchdog: Option<Watchdog>,
+    /// Keep every sample; otherwise a reservoir plus streaming statistics
+    store_raw_samples: bool,
+    /// Percentiles (0-100) the streaming statistics estimate
+    percentiles: Vec<f64>,
+    /// Package energy counter, read around the measurement phase
+    rapl: Option<RaplDomain>,
+}
 
-    // Measurement
-    let samples = config.measurement.samples;
-    let mut all_timings = Vec::with_capacity(samples);
-    let mut cpu_samples = Vec::with_capacity(samples);
-
-    // Initialize CPU monitor for the pinned core
-    let cpu_core = get_pinned_core();
-    let monitor = CpuMonitor::new(cpu_core);
-
-    // Report progress every ~1% of samples
-    let report_interval = (samples / 100).max(1);
-
-    for sample_idx in 0..samples {
-        // Emit progress BEFORE timing
-        if sample_idx % report_interval == 0 {
-            emit_progress(&ProgressMessage {
-                bench: name,
-                phase: ProgressPhase::Samples {
-                    current: sample_idx as u32,
-                    total: samples as u32,
-                },
-            });
-        }
+impl<'a> SampleLoop<'a> {
+    fn new(bench_name: &'a str, config: &BenchmarkConfig) -> Self {
+        let cpu_clock_overhead = (config.measurement.clock != ClockMode::Wall)
+            .then(calibrate_cpu_clock_overhead)
+            .flatten();
+        let mut sample_loop = Self {
+            bench_name,
+            cpu_core: get_pinned_core(),
+            monitor: CpuMonitor::new(get_pinned_core()),
+            settle_after_drop: Duration::from_micros(config.measurement.settle_after_drop_us),
+            manual_timer: None,
+            timer_overhead: calibrate_timer_overhead(cpu_clock_overhead.is_some()),
+            cpu_clock_overhead,
+            adaptive: AdaptiveStop::from_config(&config.measurement),
+            deadline: config
+                .measurement
+                .max_time_secs
+                .filter(|secs| secs.is_finite() && *secs >= 0.0)
+                .map(|secs| Instant::now() + Duration::from_secs_f64(secs)),
+            clock: config.measurement.clock,
+            perf: PerfCounters::open(),
+            warmup_mode: config.measurement.warmup_mode,
+            max_warmup: Duration::try_from_secs_f64(config.measurement.max_warmup_secs)
+                .unwrap_or(config.measurement.warmup_duration),
+            warmup_stable_readings: config.measurement.warmup_stable_readings,
+            discard_samples: config.measurement.discard_samples,
+            early_verdict: None,
+            watchdog: Watchdog::from_config(&config.measurement),
+            store_raw_samples: config.measurement.store_raw_samples,
+            percentiles: config.measurement.percentiles.clone(),
+            rapl: RaplDomain::for_core(get_pinned_core()),
+        };
+        let overhead = sample_loop.primary_overhead();
+        sample_loop.watchdog = sample_loop
+            .watchdog
+            .map(|watchdog| watchdog.with_timer_overhead(overhead));
+        sample_loop
+    }
 
-        // Setup runs before each sample
-        let data = setup();
+    /// Overhead subtracted from the recorded series: the CPU clock's when CPU
+    /// time replaces wall time, the wall clock's otherwise
+    fn primary_overhead(&self) -> Duration {
+        match (self.clock, self.cpu_clock_overhead) {
+            (ClockMode::Cpu, Some(overhead)) => overhead,
+            _ => self.timer_overhead,
+        }
+    }
 
-        // Read CPU frequency BEFORE measurement
-        let freq_before = monitor.read_frequency();
+    /// Record each sample from `timer` instead of timing the whole `run` step
+    fn with_manual_timer(mut self, timer: &'a RefCell<Timer>) -> Self {
+        self.manual_timer = Some(timer);
+        self
+    }
 
+    /// Run a single iteration, returning its wall time, thread CPU time, heap
+    /// activity, hardware counters and context switches.
+    ///
+    /// `prepare` produces the input (e.g. `setup_each` data) outside the timed
+    /// section, and its error aborts the loop. The value returned by `run` is
+    /// dropped after timing stops, and that drop (plus any settle time)
+    /// completes before this iteration returns, so the deferred free never
+    /// overlaps the next sample's setup or timing.
+    #[inline(always)]
+    fn iteration<I, O, P, R>(&self, prepare: &mut P, run: &mut R) -> Result<Sample, String>
+    where
+        P: FnMut() -> Result<I, String>,
+        R: FnMut(I) -> O,
+    {
+        let input = prepare().map_err(|e| format!("setup failed: {}", e))?;
+
+        // The CPU clock is read innermost, so only `run` falls between its reads
+        let read_cpu = self.cpu_clock_overhead.is_some();
+        let usage_start = thread_usage();
+        let allocs_start = read_alloc_counts();
+        let perf_start = self.perf.as_ref().and_then(PerfCounters::read);
         let start = Instant::now();
-        bench(data); // Consumes data
+        let cpu_start = if read_cpu { thread_cpu_time() } else { None };
+        let output = black_box(run(black_box(input)));
+        let cpu_end = if read_cpu { thread_cpu_time() } else { None };
         let elapsed = start.elapsed();
-        all_timings.push(elapsed);
-
-        // Read frequency after as well
-        let freq_after = monitor.read_frequency();
-        let frequency_khz = match (freq_before, freq_after) {
-            (Some(before), Some(after)) => Some(before.max(after)),
-            (Some(f), None) | (None, Some(f)) => Some(f),
-            (None, None) => None,
+        let perf_end = self.perf.as_ref().and_then(PerfCounters::read);
+        let allocs_end = read_alloc_counts();
+        let usage_end = thread_usage();
+
+        // A manual timer covers only part of the call, so the CPU time of the
+        // whole call isn't comparable to it
+        let (elapsed, cpu_time) = match self.manual_timer {
+            Some(timer) => {
+                let recorded = timer.borrow().recorded().ok_or_else(|| {
+                    "manual_timer benchmark never called `Timer::start()` or `Timer::stop()`"
+                        .to_string()
+                })?;
+                (recorded, None)
+            }
+            None => {
+                let elapsed = elapsed.saturating_sub(self.timer_overhead);
+                let cpu_time = cpu_start.zip(cpu_end).map(|(start, end)| {
+                    end.saturating_sub(start)
+                        .saturating_sub(self.cpu_clock_overhead.unwrap_or_default())
+                });
+                match cpu_time {
+                    // CPU time replaces the wall-clock timing as the only series
+                    Some(cpu_time) if self.clock == ClockMode::Cpu => (cpu_time, None),
+                    cpu_time => (elapsed, cpu_time),
+                }
+            }
         };
 
-        let snapshot = CpuSnapshot {
-            timestamp: Instant::now(),
-            frequency_khz,
-            temperature_millic: monitor.read_temperature(),
+        drop(output);
+        self.settle();
+
+        Ok(Sample {
+            started: start,
+            elapsed,
+            cpu_time,
+            allocs: allocs_start
+                .zip(allocs_end)
+                .map(|(start, end)| end.since(start)),
+            perf: perf_start
+                .zip(perf_end)
+                .map(|(start, end)| end.since(start)),
+            usage: usage_start
+                .zip(usage_end)
+                .map(|(start, end)| end.since(start)),
+        })
+    }
+
+    /// Give the allocator time to finish freeing the previous sample's data.
+    ///
+    /// Busy-waits rather than sleeping so the core doesn't drop into an idle
+    /// state (and lower frequency) between samples.
+    #[inline(always)]
+    fn settle(&self) {
+        if self.settle_after_drop.is_zero() {
+            return;
+        }
+        let start = Instant::now();
+        while start.elapsed() < self.settle_after_drop {
+            std::hint::spin_loop();
+        }
+    }
+
+    /// Run iterations for `duration`, keeping only their mean timing.
+    ///
+    /// In adaptive mode, warmup instead ends once the CPU frequency has
+    /// settled, or after `max_warmup`; if the frequency can't be read it runs
+    /// for `duration` after all.
+    fn warmup<I, O, P, R>(
+        &self,
+        prepare: &mut P,
+        run: &mut R,
+        duration: Duration,
+    ) -> Result<Warmup, String>
+    where
+        P: FnMut() -> Result<I, String>,
+        R: FnMut(I) -> O,
+    {
+        let start = Instant::now();
+        let mut total_iterations = 0u64;
+        let mut moments = Moments::default();
+        let mut last_report = Instant::now();
+        let mut mode = self.warmup_mode;
+        let mut limit = match mode {
+            WarmupMode::Fixed => duration,
+            WarmupMode::Adaptive => self.max_warmup,
         };
-        cpu_samples.push(snapshot);
+        let mut settle = FrequencySettle::new(self.warmup_stable_readings);
+        let mut last_poll = Instant::now();
+
+        while start.elapsed() < limit {
+            let sample = self.iteration(prepare, run)?;
+            moments.push(crate::nanos_u64(sample.elapsed));
+            total_iterations += 1;
+
+            if mode == WarmupMode::Adaptive && last_poll.elapsed() >= FREQUENCY_POLL_INTERVAL {
+                last_poll = Instant::now();
+                match self.monitor.read_frequency() {
+                    Some(khz) if settle.observe(khz) => break,
+                    Some(_) => {}
+                    None => {
+                        mode = WarmupMode::Fixed;
+                        limit = duration;
+                    }
+                }
+            }
+
+            // Emit progress every 100ms
+            if last_report.elapsed() >= Duration::from_millis(100) {
+                emit_progress(&ProgressMessage {
+                    bench: self.bench_name.into(),
+                    phase: ProgressPhase::Warmup {
+                        elapsed_ms: u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX),
+                        target_ms: u64::try_from(limit.as_millis()).unwrap_or(u64::MAX),
+                    },
+                });
+                last_report = Instant::now();
+            }
+        }
+
+        Ok(Warmup {
+            elapsed_ms: start.elapsed().as_millis(),
+            iterations: total_iterations,
+            ns_per_iter: (moments.count() > 0).then(|| moments.mean()),
+            mode,
+        })
     }
 
-    // Emit completion message
-    emit_progress(&ProgressMessage {
-        bench: name,
-        phase: ProgressPhase::Complete,
-    });
+    /// Run `samples` iterations, recording timings while a [`CpuSampler`]
+    /// records CPU snapshots in the background.
+    ///
+    /// The first `discard_samples` iterations run beforehand and are thrown
+    /// away, so they count toward neither the sample budget nor adaptive stopping.
+    ///
+    /// With adaptive sampling, `samples` is the cap and the loop ends early
+    /// once the timings of the last `min_samples` iterations are stable. A
+    /// `max_time_secs` budget also ends it early, once enough samples exist,
+    /// and so does an early verdict that held for two checks in a row. The
+    /// watchdog reports each pathological sample as it happens and, with
+    /// `watchdog_stop`, ends sampling at the first one.
+    fn measure<I, O, P, R>(
+        &self,
+        prepare: &mut P,
+        run: &mut R,
+        samples: usize,
+    ) -> Result<Measurements, String>
+    where
+        P: FnMut() -> Result<I, String>,
+        R: FnMut(I) -> O,
+    {
+        // Without raw samples, every per-sample series holds the same reservoir slots
+        let mut summary = (!self.store_raw_samples).then(|| StreamingStats::new(&self.percentiles));
+        let mut reservoir = summary.is_some().then(|| Reservoir::new(RESERVOIR_SIZE));
+        let capacity = if reservoir.is_some() {
+            samples.min(RESERVOIR_SIZE)
+        } else {
+            samples
+        };
+        let mut all_timings = Vec::with_capacity(capacity);
+        let mut offsets_ms = Vec::with_capacity(capacity);
+        let mut cpu_timings = Vec::with_capacity(capacity);
+        let mut alloc_counts = Vec::new();
+        let mut perf_samples = Vec::new();
+        let mut usage_samples = Vec::new();
+
+        // Report progress every ~1% of samples (minimum every sample for small counts)
+        let total = samples + self.discard_samples;
+        let report_interval = (total / 100).max(1);
+        let mut recent = self.adaptive.map(|a| RollingStats::new(a.min_samples));
+        let mut truncated = false;
+        let mut watchdog = self.watchdog.clone();
+        let mut watchdog_stopped = false;
+        let mut pathological_samples = Vec::new();
+        let mut pending_verdict = None;
+        let mut early_verdict = None;
+        let mut recorded = 0usize;
+        let mut moments = Moments::default();
+
+        let measurement_start = Instant::now();
+        let sampler = CpuSampler::start(self.cpu_core, CPU_SAMPLE_INTERVAL, measurement_start);
+        let energy_start = self.rapl.as_ref().and_then(RaplDomain::read_uj);
+        let loop_start = Instant::now();
+
+        for sample_idx in 0..total {
+            // Emit progress BEFORE timing (so we don't affect measurements)
+            if sample_idx % report_interval == 0 {
+                emit_progress(&ProgressMessage {
+                    bench: self.bench_name.into(),
+                    phase: ProgressPhase::Samples {
+                        current: sample_idx as u32,
+                        total: total as u32,
+                    },
+                });
+            }
+
+            if sample_idx < self.discard_samples {
+                self.iteration(prepare, run)?;
+                continue;
+            }
+
+            let Sample {
+                started,
+                elapsed,
+                cpu_time,
+                allocs,
+                perf,
+                usage,
+            } = self.iteration(prepare, run)?;
+            recorded += 1;
+            let nanos = crate::nanos_u64(elapsed);
+            moments.push(nanos);
+            if let Some(summary) = summary.as_mut() {
+                summary.push(nanos);
+            }
+
+            let slot = match reservoir.as_mut() {
+                Some(reservoir) => reservoir.offer(),
+                None => Some(recorded - 1),
+            };
+            if let Some(slot) = slot {
+                let offset_ms =
+                    u64::try_from(started.duration_since(measurement_start).as_millis())
+                        .unwrap_or(u64::MAX);
+                store_in_slot(&mut all_timings, slot, elapsed);
+                store_in_slot(&mut offsets_ms, slot, offset_ms);
+                if let Some(cpu_time) = cpu_time {
+                    store_in_slot(&mut cpu_timings, slot, cpu_time);
+                }
+                if let Some(allocs) = allocs {
+                    store_in_slot(&mut alloc_counts, slot, allocs);
+                }
+                if let Some(perf) = perf {
+                    store_in_slot(&mut perf_samples, slot, perf);
+                }
+                if let Some(usage) = usage {
+                    store_in_slot(&mut usage_samples, slot, usage);
+                }
+            }
+
+            if let Some(flagged) = watchdog
+                .as_mut()
+                .and_then(|w| w.check(recorded - 1, elapsed))
+            {
+                emit_progress(&ProgressMessage {
+                    bench: self.bench_name.into(),
+                    phase: ProgressPhase::PathologicalSample {
+                        index: flagged.index as u32,
+                        duration_ns: flagged.duration_ns,
+                        median_ns: flagged.median_ns,
+                    },
+                });
+                pathological_samples.push(flagged);
+                if watchdog.as_ref().is_some_and(|w| w.stop) {
+                    truncated = true;
+                    watchdog_stopped = true;
+                    break;
+                }
+            }
+
+            if let (Some(adaptive), Some(recent)) = (self.adaptive, recent.as_mut()) {
+                recent.push(elapsed.as_nanos() as f64);
+                if recent.is_full() && recent.cv_percent() < adaptive.target_cv {
+                    break;
+                }
+            }
+
+            if let Some(rule) = self.early_verdict {
+                if recorded % rule.interval == 0 {
+                    let verdict = rule.check(&moments);
+                    if verdict.is_some() && verdict == pending_verdict {
+                        early_verdict = verdict;
+                        break;
+                    }
+                    pending_verdict = verdict;
+                }
+            }
+
+            if let Some(deadline) = self.deadline {
+                let remaining = samples - recorded;
+                if remaining > 0
+                    && recorded >= MIN_SAMPLES_WITHIN_BUDGET
+                    && Instant::now() >= deadline
+                {
+                    truncated = true;
+                    break;
+                }
+            }
+        }
 
-    let percentiles = calculate_percentiles(&all_timings);
+        let loop_elapsed = loop_start.elapsed();
+        let energy_uj = match (&self.rapl, energy_start) {
+            (Some(rapl), Some(start)) => rapl.read_uj().map(|end| rapl.delta_uj(start, end)),
+            _ => None,
+        };
+        // Timed durations only, so progress and bookkeeping between samples
+        // don't make sampling look slower than warmup
+        let ns_per_iter = (moments.count() > 0).then(|| moments.mean());
+        let mut cpu_samples = sampler.finish();
+
+        // Emit completion message
+        emit_progress(&ProgressMessage {
+            bench: self.bench_name.into(),
+            phase: ProgressPhase::Complete,
+        });
 
-    BenchResult {
-        name: name.to_string(),
-        module: module.to_string(),
-        samples,
-        percentiles,
-        all_timings,
-        cpu_samples,
-        warmup_ms: Some(warmup_ms),
-        warmup_iterations: Some(warmup_iters),
+        // Only keep CPU times and counters if every sample has them
+        if alloc_counts.len() != all_timings.len() {
+            alloc_counts.clear();
+        }
+        if cpu_timings.len() != all_timings.len() {
+            cpu_timings.clear();
+        }
+        if perf_samples.len() != all_timings.len() {
+            perf_samples.clear();
+        }
+        if usage_samples.len() != all_timings.len() {
+            usage_samples.clear();
+        }
+        attribute_thread_usage(&mut cpu_samples, &offsets_ms, &usage_samples);
+        let involuntary_switches = usage_samples
+            .iter()
+            .map(|usage| usage.involuntary_switches)
+            .collect();
+
+        // Robust dispersion can't be streamed; estimate it from the reservoir
+        let summary = summary.map(|s| {
+            let mut statistics = s.statistics();
+            let mut reservoir: Vec<u128> = all_timings.iter().map(|d| d.as_nanos()).collect();
+            reservoir.sort_unstable();
+            statistics.set_robust_dispersion(&reservoir);
+            statistics
+        });
+
+        Ok(Measurements {
+            timings: all_timings,
+            summary,
+            offsets_ms,
+            cpu_timings,
+            cpu_samples,
+            involuntary_switches,
+            alloc_counts,
+            perf_samples,
+            truncated,
+            watchdog_stopped,
+            pathological_samples,
+            early_verdict,
+            ns_per_iter,
+            energy_uj,
+            mean_watts: energy_uj.and_then(|uj| mean_watts(uj, loop_elapsed)),
+        })
     }
 }
 
-/// Measure a benchmark where setup runs before every sample (borrowing version).
+/// Warm up and measure a benchmark through a shared [`SampleLoop`], then build its result.
 ///
-/// The benchmark function borrows the data produced by setup.
-/// Use this when you need fresh data each sample but don't consume it.
-pub fn measure_with_setup_each_ref<T, S, B>(
+/// A failing `prepare` (fallible `setup_each`) aborts the run with a [`BenchFailure`].
+fn run_sample_loop<I, O, P, R>(
     config: &BenchmarkConfig,
     name: &str,
     module: &str,
-    mut setup: S,
-    mut bench: B,
-) -> BenchResult
+    prepare: P,
+    run: R,
+) -> Result<BenchResult, Box<BenchFailure>>
 where
-    S: FnMut() -> T,
-    B: FnMut(&T),
+    P: FnMut() -> Result<I, String>,
+    R: FnMut(I) -> O,
 {
-    // Warmup: run setup + bench together
-    let (warmup_ms, warmup_iters) = warmup_with_setup_ref(
-        &mut setup,
-        &mut bench,
-        Duration::from_secs(config.measurement.warmup_duration_secs),
-        name,
-    );
-
-    // Measurement
-    let samples = config.measurement.samples;
-    let mut all_timings = Vec::with_capacity(samples);
-    let mut cpu_samples = Vec::with_capacity(samples);
-
-    // Initialize CPU monitor for the pinned core
-    let cpu_core = get_pinned_core();
-    let monitor = CpuMonitor::new(cpu_core);
-
-    // Report progress every ~1% of samples
-    let report_interval = (samples / 100).max(1);
-
-    for sample_idx in 0..samples {
-        // Emit progress BEFORE timing
-        if sample_idx % report_interval == 0 {
-            emit_progress(&ProgressMessage {
-                bench: name,
-                phase: ProgressPhase::Samples {
-                    current: sample_idx as u32,
-                    total: samples as u32,
-                },
-            });
-        }
-
-        // Setup runs before each sample
-        let data = setup();
-
-        // Read CPU frequency BEFORE measurement
-        let freq_before = monitor.read_frequency();
+    let sample_loop = SampleLoop::new(name, config);
+    collect_result(sample_loop, config, name, module, prepare, run)
+}
 
-        let start = Instant::now();
-        bench(&data); // Borrows data
-        let elapsed = start.elapsed();
-        all_timings.push(elapsed);
+/// Drive warmup and measurement on `sample_loop` and assemble the [`BenchResult`]
+fn collect_result<I, O, P, R>(
+    mut sample_loop: SampleLoop,
+    config: &BenchmarkConfig,
+    name: &str,
+    module: &str,
+    mut prepare: P,
+    mut run: R,
+) -> Result<BenchResult, Box<BenchFailure>>
+where
+    P: FnMut() -> Result<I, String>,
+    R: FnMut(I) -> O,
+{
+    validate_measurement_params(&config.measurement).map_err(|e| bench_failure(name, e))?;
 
-        // Read frequency after as well
-        let freq_after = monitor.read_frequency();
-        let frequency_khz = match (freq_before, freq_after) {
-            (Some(before), Some(after)) => Some(before.max(after)),
-            (Some(f), None) | (None, Some(f)) => Some(f),
-            (None, None) => None,
-        };
+    // The window is loaded before warmup, so reading it never overlaps sampling
+    sample_loop.early_verdict = EarlyVerdictRule::load(config, name, module);
 
-        let snapshot = CpuSnapshot {
-            timestamp: Instant::now(),
-            frequency_khz,
-            temperature_millic: monitor.read_temperature(),
-        };
-        cpu_samples.push(snapshot);
+    // Warmup
+    let warmup = sample_loop
+        .warmup(&mut prepare, &mut run, config.measurement.warmup_duration)
+        .map_err(|e| bench_failure(name, e))?;
 
-        drop(data); // Explicit drop (happens anyway)
+    // Measurement
+    let samples = sample_budget(&config.measurement);
+    let Measurements {
+        timings: all_timings,
+        summary,
+        offsets_ms: sample_offsets_ms,
+        cpu_timings,
+        cpu_samples,
+        involuntary_switches,
+        alloc_counts,
+        perf_samples,
+        truncated,
+        watchdog_stopped,
+        pathological_samples,
+        early_verdict,
+        ns_per_iter: sampling_ns_per_iter,
+        energy_uj,
+        mean_watts,
+    } = sample_loop
+        .measure(&mut prepare, &mut run, samples)
+        .map_err(|e| bench_failure(name, e))?;
+    if all_timings.is_empty() {
+        return Err(bench_failure(name, "no samples were recorded".to_string()));
     }
 
-    // Emit completion message
-    emit_progress(&ProgressMessage {
-        bench: name,
-        phase: ProgressPhase::Complete,
-    });
-
-    let percentiles = calculate_percentiles(&all_timings);
-
-    BenchResult {
+    let (samples, percentiles) = match summary {
+        Some(ref summary) => (summary.sample_count, percentiles_from_statistics(summary)),
+        None => (
+            all_timings.len(),
+            calculate_percentiles(&all_timings, &config.measurement.percentiles),
+        ),
+    };
+    let cpu_time_percentiles = (!cpu_timings.is_empty())
+        .then(|| calculate_percentiles(&cpu_timings, &config.measurement.percentiles));
+
+    let mut result = BenchResult {
         name: name.to_string(),
         module: module.to_string(),
         samples,
         percentiles,
         all_timings,
+        sample_offsets_ms,
         cpu_samples,
-        warmup_ms: Some(warmup_ms),
-        warmup_iterations: Some(warmup_iters),
-    }
+        cpu_timings,
+        cpu_time_percentiles,
+        warmup_ms: Some(warmup.elapsed_ms),
+        warmup_iterations: Some(warmup.iterations),
+        warmup_mode: warmup.mode,
+        warmup_ns_per_iter: warmup.ns_per_iter,
+        sampling_ns_per_iter,
+        discarded_samples: sample_loop.discard_samples,
+        throughput: None,
+        group: None,
+        core: None,
+        body_hash: None,
+        skipped: false,
+        skip_reason: None,
+        timer_overhead_ns: sample_loop
+            .manual_timer
+            .is_none()
+            .then(|| crate::nanos_u64(sample_loop.primary_overhead())),
+        // Filled in by the generated wrapper, which is compiled with the benchmark's crate
+        debug_assertions: false,
+        truncated,
+        watchdog_stopped,
+        measures_drop: false,
+        early_verdict,
+        alloc_stats: AllocStats::from_counts(&alloc_counts),
+        clock: config.measurement.clock,
+        perf_samples,
+        involuntary_switches,
+        pathological_samples,
+        summary,
+        energy_uj,
+        mean_watts,
+        environment: config.environment.snapshot(),
+        git: crate::git::current(),
+        toolchain: crate::toolchain::current(),
+        settings: Some(MeasurementSettings::from_config(&config.measurement)),
+        interleaved_rounds: 0,
+        quality_failure: None,
+        filtered: None,
+    };
+    result.filtered = filter_outliers(
+        &result
+            .all_timings
+            .iter()
+            .map(|d| d.as_nanos())
+            .collect::<Vec<_>>(),
+        &config.measurement.percentiles,
+    );
+    result.check_quality(config.measurement.max_cv_pct);
+    Ok(result)
 }
 
-pub fn measure_single_iteration<F>(func: F) -> Duration
+/// Measure a simple benchmark (no setup) using the new architecture.
+///
+/// This function is called by the generated benchmark wrapper for benchmarks
+/// without setup code. The config is passed in, and a complete BenchResult is
+/// returned; it only fails if the config is invalid (e.g. zero samples).
+pub fn measure_simple<F>(
+    config: &BenchmarkConfig,
+    name: &str,
+    module: &str,
+    mut func: F,
+) -> Result<BenchResult, Box<BenchFailure>>
 where
-    F: FnOnce(),
+    F: FnMut(),
 {
-    let start = Instant::now();
-    func();
-    start.elapsed()
-}
-
-pub fn validate_measurement_params(samples: usize) -> Result<(), String> {
-    if samples == 0 {
-        return Err("Samples must be greater than 0".to_string());
-    }
-    if samples > 1_000_000 {
-        return Err(
-            "Samples should not exceed 1,000,000 for reasonable execution time".to_string(),
-        );
-    }
-    Ok(())
+    run_sample_loop(config, name, module, || Ok(()), |()| func())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::thread;
+/// Measure a benchmark that times itself through a [`Timer`].
+///
+/// This function is called by the generated benchmark wrapper for benchmarks
+/// with the `manual_timer` attribute. Each sample records only the time between
+/// the benchmark's `start()`/`stop()` calls; a benchmark that calls neither
+/// fails with an error naming it.
+pub fn measure_with_manual_timer<F>(
+    config: &BenchmarkConfig,
+    name: &str,
+    module: &str,
+    mut func: F,
+) -> Result<BenchResult, Box<BenchFailure>>
+where
+    F: FnMut(&mut Timer),
+{
+    let timer = RefCell::new(Timer::default());
+    let sample_loop = SampleLoop::new(name, config).with_manual_timer(&timer);
 
-    #[test]
-    fn test_measure_single_iteration() {
-        let duration = measure_single_iteration(|| {
-            thread::sleep(Duration::from_millis(1));
-        });
+    collect_result(
+        sample_loop,
+        config,
+        name,
+        module,
+        || Ok(()),
+        |()| {
+            let mut timer = timer.borrow_mut();
+            timer.reset();
+            func(&mut timer);
+            // A region left running counts up to the benchmark's return
+            if timer.started.is_some() {
+                timer.stop();
+            }
+        },
+    )
+}
 
-        assert!(duration >= Duration::from_millis(1));
+/// Measure a benchmark with setup code that runs once before measurement.
+///
+/// This function is called by the generated benchmark wrapper for benchmarks
+/// with the `setup` attribute. Setup runs exactly once, then the benchmark
+/// function receives a reference to the setup data for each iteration.
+///
+/// Setup may return `T` or `Result<T, E: Display>`; an error fails the benchmark.
+pub fn measure_with_setup<T, R, S, B>(
+    config: &BenchmarkConfig,
+    name: &str,
+    module: &str,
+    setup: S,
+    mut bench: B,
+) -> Result<BenchResult, Box<BenchFailure>>
+where
+    S: FnOnce() -> R,
+    R: IntoSetupResult<T>,
+    B: FnMut(&T),
+{
+    // Run setup ONCE before any measurement
+    let data = setup()
+        .into_setup_result()
+        .map_err(|e| bench_failure(name, format!("setup failed: {}", e)))?;
+
+    run_sample_loop(config, name, module, || Ok(()), |()| bench(&data))
+}
+
+/// Measure a benchmark where setup runs before every sample (owning version).
+///
+/// The benchmark function takes ownership of the data produced by setup.
+/// This allows benchmarking operations that consume or mutate their input.
+/// Whatever it returns is dropped after the clock is read, so a benchmark that
+/// hands its data (or what is left of it) back keeps deallocation untimed.
+pub fn measure_with_setup_each<T, R, S, B, L>(
+    config: &BenchmarkConfig,
+    name: &str,
+    module: &str,
+    mut setup: S,
+    bench: B,
+) -> Result<BenchResult, Box<BenchFailure>>
+where
+    S: FnMut() -> R,
+    R: IntoSetupResult<T>,
+    B: FnMut(T) -> L,
+{
+    // Setup runs before each sample (untimed); bench consumes the data and
+    // its leftovers are dropped untimed
+    run_sample_loop(config, name, module, || setup().into_setup_result(), bench)
+}
+
+/// Measure a benchmark where setup runs before every sample (borrowing version).
+///
+/// The benchmark function borrows the data produced by setup.
+/// Use this when you need fresh data each sample but don't consume it.
+pub fn measure_with_setup_each_ref<T, R, S, B>(
+    config: &BenchmarkConfig,
+    name: &str,
+    module: &str,
+    mut setup: S,
+    mut bench: B,
+) -> Result<BenchResult, Box<BenchFailure>>
+where
+    S: FnMut() -> R,
+    R: IntoSetupResult<T>,
+    B: FnMut(&T),
+{
+    // Hand the data back out of the timed section so it is dropped untimed
+    run_sample_loop(
+        config,
+        name,
+        module,
+        || setup().into_setup_result(),
+        |data| {
+            bench(&data);
+            data
+        },
+    )
+}
+
+/// Measure a benchmark where setup runs before every sample (mutable borrow version).
+///
+/// The benchmark function mutates the data produced by setup in place, avoiding
+/// a reallocation per sample when only `&mut T` access is needed. The data is
+/// dropped outside the timed section.
+pub fn measure_with_setup_each_mut<T, R, S, B>(
+    config: &BenchmarkConfig,
+    name: &str,
+    module: &str,
+    mut setup: S,
+    mut bench: B,
+) -> Result<BenchResult, Box<BenchFailure>>
+where
+    S: FnMut() -> R,
+    R: IntoSetupResult<T>,
+    B: FnMut(&mut T),
+{
+    // Hand the data back out of the timed section so it is dropped untimed
+    run_sample_loop(
+        config,
+        name,
+        module,
+        || setup().into_setup_result(),
+        |mut data| {
+            bench(&mut data);
+            data
+        },
+    )
+}
+
+/// Measure how long dropping a value takes (`#[bench(measure = drop)]`).
+///
+/// Setup builds a fresh value before every sample, outside timing, and the
+/// timed region is exactly `drop(data)`: the loop hands nothing back, so no
+/// other work lands between the clock reads. The result is marked
+/// `measures_drop`.
+pub fn measure_drop<T, R, S>(
+    config: &BenchmarkConfig,
+    name: &str,
+    module: &str,
+    mut setup: S,
+) -> Result<BenchResult, Box<BenchFailure>>
+where
+    S: FnMut() -> R,
+    R: IntoSetupResult<T>,
+{
+    let mut result = run_sample_loop(
+        config,
+        name,
+        module,
+        || setup().into_setup_result(),
+        |data: T| drop(data),
+    )?;
+    result.measures_drop = true;
+    Ok(result)
+}
+
+/// Wall time of a single call of `func`
+pub fn measure_single_iteration<F>(func: F) -> Duration
+where
+    F: FnOnce(),
+{
+    let start = Instant::now();
+    func();
+    start.elapsed()
+}
+
+/// Time `iterations` calls of `func` and summarize them, for ad-hoc use in
+/// examples and unit tests.
+///
+/// Unlike `#[bench]`, there is no warmup phase, core pinning, CPU sampling or
+/// baseline: one untimed call primes caches, then each call is timed on
+/// its own and its return value passed through [`black_box`].
+///
+/// # Panics
+///
+/// If `iterations` is 0; `name` identifies the call site in the message.
+///
+/// ```
+/// let stats = simplebench_runtime::quick_bench("sum", 100, || (0..1_000u64).sum::<u64>());
+/// assert_eq!(stats.sample_count, 100);
+/// ```
+pub fn quick_bench<F, R>(name: &str, iterations: usize, mut func: F) -> Statistics
+where
+    F: FnMut() -> R,
+{
+    assert!(
+        iterations > 0,
+        "quick_bench '{}': iterations must be greater than 0",
+        name
+    );
+    black_box(func());
+    let samples_ns: Vec<u128> = (0..iterations)
+        .map(|_| {
+            measure_single_iteration(|| {
+                black_box(func());
+            })
+            .as_nanos()
+        })
+        .collect();
+    calculate_statistics(&samples_ns)
+}
+
+/// Check that the sample budget of `measurement` is at least 1 and at most
+/// its `sample_limit`
+pub fn validate_measurement_params(measurement: &MeasurementConfig) -> Result<(), String> {
+    let (key, samples) = if measurement.adaptive {
+        ("max_samples", sample_budget(measurement))
+    } else {
+        ("samples", measurement.samples)
+    };
+    if samples == 0 {
+        return Err(format!("{} must be greater than 0", key));
+    }
+    if samples > measurement.sample_limit {
+        return Err(format!(
+            "{} = {} exceeds the sample_limit of {}",
+            key, samples, measurement.sample_limit
+        ));
+    }
+    if let Some(p) = measurement
+        .percentiles
+        .iter()
+        .find(|p| !(0.0..=100.0).contains(*p))
+    {
+        return Err(format!("percentile {} is outside 0-100", p));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_measure_single_iteration() {
+        let duration = measure_single_iteration(|| {
+            thread::sleep(Duration::from_millis(1));
+        });
+
+        assert!(duration >= Duration::from_millis(1));
         assert!(duration < Duration::from_millis(10)); // Should be close to 1ms
     }
 
     #[test]
     fn test_validate_measurement_params() {
-        assert!(validate_measurement_params(100).is_ok());
-        assert!(validate_measurement_params(0).is_err());
-        assert!(validate_measurement_params(1_000_001).is_err());
-        assert!(validate_measurement_params(100_000).is_ok());
+        let with_samples = |samples| MeasurementConfig {
+            samples,
+            ..Default::default()
+        };
+        assert!(validate_measurement_params(&with_samples(100)).is_ok());
+        assert!(validate_measurement_params(&with_samples(0)).is_err());
+        assert!(validate_measurement_params(&with_samples(1_000_001)).is_err());
+        assert!(validate_measurement_params(&with_samples(100_000)).is_ok());
+
+        let raised = MeasurementConfig {
+            sample_limit: 2_000_000,
+            ..with_samples(1_500_000)
+        };
+        assert!(validate_measurement_params(&raised).is_ok());
+
+        let adaptive = MeasurementConfig {
+            adaptive: true,
+            min_samples: 0,
+            max_samples: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_measurement_params(&adaptive).unwrap_err(),
+            "max_samples must be greater than 0"
+        );
+
+        let percentiles = MeasurementConfig {
+            percentiles: vec![50.0, 99.9, 150.0],
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_measurement_params(&percentiles).unwrap_err(),
+            "percentile 150 is outside 0-100"
+        );
+    }
+
+    #[test]
+    fn test_zero_samples_fail_cleanly() {
+        let config = BenchmarkConfig {
+            measurement: MeasurementConfig {
+                samples: 0,
+                warmup_duration: Duration::ZERO,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let failure = measure_simple(&config, "zero", "tests", || {}).unwrap_err();
+        assert_eq!(failure.message, "samples must be greater than 0");
+    }
+
+    #[test]
+    fn test_quick_bench() {
+        let stats = quick_bench("sleep", 5, || thread::sleep(Duration::from_millis(1)));
+        assert_eq!(stats.sample_count, 5);
+        assert!(stats.min >= Duration::from_millis(1).as_nanos());
     }
 
     #[test]
@@ -501,7 +1439,8 @@ mod tests {
         let config = BenchmarkConfig {
             measurement: crate::config::MeasurementConfig {
                 samples: 10,
-                warmup_duration_secs: 0, // Skip warmup for test speed
+                warmup_duration: Duration::ZERO, // Skip warmup for test speed
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -509,7 +1448,8 @@ mod tests {
         let result = measure_simple(&config, "test_bench", "test_module", || {
             // Simple work
             let _ = (0..100).sum::<i32>();
-        });
+        })
+        .unwrap();
 
         assert_eq!(result.name, "test_bench");
         assert_eq!(result.module, "test_module");
@@ -522,4 +1462,817 @@ mod tests {
             assert!(*timing < Duration::from_secs(1));
         }
     }
+
+    #[test]
+    fn test_timer_overhead_is_recorded_and_subtracted() {
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 50,
+                warmup_duration: Duration::ZERO,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // An empty benchmark costs little more than the clock reads themselves
+        let result = measure_simple(&config, "empty", "test_module", || {}).unwrap();
+        let overhead = Duration::from_nanos(result.timer_overhead_ns.unwrap());
+        assert!(overhead < Duration::from_millis(1));
+        assert!(result
+            .all_timings
+            .iter()
+            .all(|t| *t < Duration::from_millis(10)));
+
+        // The overhead is stored with the baseline so raw timings stay recoverable
+        let stored =
+            crate::baseline::BaselineData::from_bench_result(&result, "machine".to_string(), false);
+        assert_eq!(stored.timer_overhead_ns, result.timer_overhead_ns);
+        assert_eq!(
+            stored.to_bench_result().timer_overhead_ns,
+            result.timer_overhead_ns
+        );
+
+        // Manual timers measure their own region, so nothing is subtracted
+        let manual = measure_with_manual_timer(&config, "manual", "test_module", |timer| {
+            timer.start();
+            timer.stop();
+        })
+        .unwrap();
+        assert_eq!(manual.timer_overhead_ns, None);
+    }
+
+    #[test]
+    fn test_measure_with_setup_each_mut_drops_untimed() {
+        use std::cell::Cell;
+
+        struct DropCounter<'a>(Vec<u8>, &'a Cell<u32>);
+        impl Drop for DropCounter<'_> {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0u32);
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 10,
+                warmup_duration: Duration::ZERO,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = measure_with_setup_each_mut(
+            &config,
+            "test_fill",
+            "test_module",
+            || DropCounter(vec![0u8; 64], &drops),
+            |buf| buf.0.fill(7),
+        )
+        .unwrap();
+
+        assert_eq!(result.all_timings.len(), 10);
+        // One fresh buffer per sample, each dropped exactly once
+        assert_eq!(drops.get(), 10);
+    }
+
+    #[test]
+    fn test_measure_with_setup_each_drops_leftovers_untimed() {
+        // Dropping sleeps 5ms, far longer than the benchmark itself
+        struct SlowDrop(Vec<u8>);
+        impl Drop for SlowDrop {
+            fn drop(&mut self) {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        }
+
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 10,
+                warmup_duration: Duration::ZERO,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = measure_with_setup_each(
+            &config,
+            "test_consume",
+            "test_module",
+            || SlowDrop(vec![0u8; 64]),
+            |mut data| {
+                data.0.push(1);
+                data
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.all_timings.len(), 10);
+        assert!(result
+            .all_timings
+            .iter()
+            .all(|t| *t < Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn test_measure_drop_times_only_the_drop() {
+        use std::cell::Cell;
+
+        // Dropping takes ~200µs; building takes far longer and must not be timed
+        struct SlowDrop<'a>(&'a Cell<u32>);
+        impl Drop for SlowDrop<'_> {
+            fn drop(&mut self) {
+                let start = Instant::now();
+                while start.elapsed() < Duration::from_micros(200) {
+                    std::hint::spin_loop();
+                }
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0u32);
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 10,
+                warmup_duration: Duration::ZERO,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = measure_drop(&config, "drop_graph", "test_module", || {
+            std::thread::sleep(Duration::from_millis(2));
+            SlowDrop(&drops)
+        })
+        .unwrap();
+
+        assert!(result.measures_drop);
+        assert_eq!(drops.get(), 10);
+        assert!(result
+            .all_timings
+            .iter()
+            .all(|t| *t >= Duration::from_micros(190)));
+        assert!(result.percentiles.mean < Duration::from_millis(2));
+    }
+
+    #[test]
+    fn test_fallible_setup_error_fails_benchmark() {
+        use std::cell::Cell;
+
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 10,
+                warmup_duration: Duration::ZERO,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Setup once: the error surfaces before any measurement
+        let failure = measure_with_setup(
+            &config,
+            "test_missing_fixture",
+            "test_module",
+            || std::fs::read("/nonexistent/simplebench/fixture.bin"),
+            |data: &Vec<u8>| {
+                black_box(data.len());
+            },
+        )
+        .unwrap_err();
+        assert_eq!(failure.name, "test_missing_fixture");
+        assert!(failure.message.starts_with("setup failed: "));
+
+        // Setup each: a later sample's setup failing aborts the run
+        let calls = Cell::new(0u32);
+        let failure = measure_with_setup_each(
+            &config,
+            "test_flaky_setup",
+            "test_module",
+            || {
+                calls.set(calls.get() + 1);
+                if calls.get() > 3 {
+                    Err("out of fixtures")
+                } else {
+                    Ok(vec![3u8, 1, 2])
+                }
+            },
+            |mut data: Vec<u8>| data.sort(),
+        )
+        .unwrap_err();
+        assert_eq!(failure.message, "setup failed: out of fixtures");
+        assert_eq!(calls.get(), 4);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cpu_time_recorded_per_sample() {
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 5,
+                warmup_duration: Duration::ZERO,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sleeping uses wall time but almost no CPU time
+        let result = measure_simple(&config, "test_sleep", "test_module", || {
+            thread::sleep(Duration::from_millis(2));
+        })
+        .unwrap();
+
+        assert_eq!(result.cpu_timings.len(), 5);
+        let cpu_mean = result.cpu_time_percentiles.unwrap().mean;
+        assert!(cpu_mean * 2 < result.percentiles.mean);
+    }
+
+    #[test]
+    fn test_manual_timer_records_only_timed_region() {
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 5,
+                warmup_duration: Duration::ZERO,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = measure_with_manual_timer(&config, "test_manual", "test_module", |timer| {
+            thread::sleep(Duration::from_millis(2));
+            timer.start();
+            thread::sleep(Duration::from_millis(1));
+            timer.stop();
+            // Left running: counted up to the return
+            timer.start();
+        })
+        .unwrap();
+
+        for timing in &result.all_timings {
+            assert!(*timing >= Duration::from_millis(1));
+            assert!(*timing < Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_adaptive_sampling_stops_when_stable() {
+        let adaptive_config = |target_cv: f64| BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                warmup_duration: Duration::ZERO,
+                adaptive: true,
+                target_cv,
+                min_samples: 20,
+                max_samples: 200,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let work = || {
+            black_box((0..1000u64).sum::<u64>());
+        };
+
+        // A loose target is met as soon as the first window fills
+        let stable =
+            measure_simple(&adaptive_config(1000.0), "stable", "test_module", work).unwrap();
+        assert_eq!(stable.samples, 20);
+        assert_eq!(stable.all_timings.len(), 20);
+
+        // An unreachable target runs to the cap
+        let capped = measure_simple(&adaptive_config(0.0), "capped", "test_module", work).unwrap();
+        assert_eq!(capped.samples, 200);
+        assert_eq!(capped.all_timings.len(), 200);
+    }
+
+    #[cfg(feature = "alloc-tracking")]
+    #[test]
+    fn test_alloc_stats_cover_the_timed_call() {
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 50,
+                warmup_duration: Duration::ZERO,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = measure_simple(&config, "test_alloc", "test_module", || {
+            black_box(vec![0u8; 1024]);
+        })
+        .unwrap();
+        let stats = result.alloc_stats.unwrap();
+        assert!(stats.mean_allocs >= 1.0);
+        assert!(stats.mean_bytes >= 1024.0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_clock_mode_selects_series() {
+        let clock_config = |clock: ClockMode| BenchmarkConfig {
+            measurement: MeasurementConfig {
+                samples: 10,
+                warmup_duration: Duration::ZERO,
+                clock,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let sleep = || std::thread::sleep(Duration::from_millis(2));
+
+        let wall =
+            measure_simple(&clock_config(ClockMode::Wall), "wall", "test_module", sleep).unwrap();
+        assert!(wall.percentiles.mean >= Duration::from_millis(2));
+        assert!(wall.cpu_timings.is_empty());
+
+        // Sleeping barely uses the CPU, so CPU time stays far below wall time
+        let cpu =
+            measure_simple(&clock_config(ClockMode::Cpu), "cpu", "test_module", sleep).unwrap();
+        assert_eq!(cpu.clock, ClockMode::Cpu);
+        assert!(cpu.percentiles.mean < Duration::from_millis(1));
+        assert!(cpu.cpu_timings.is_empty());
+        // The CPU clock's own overhead is calibrated, subtracted and recorded
+        let cpu_overhead = Duration::from_nanos(cpu.timer_overhead_ns.unwrap());
+        assert!(cpu_overhead < Duration::from_millis(1));
+
+        let both =
+            measure_simple(&clock_config(ClockMode::Both), "both", "test_module", sleep).unwrap();
+        assert_eq!(both.cpu_timings.len(), both.all_timings.len());
+        assert!(crate::output::format_scheduling_warning(&both).is_some());
+    }
+
+    #[test]
+    fn test_frequency_settle_waits_for_consecutive_readings() {
+        let mut settle = FrequencySettle::new(3);
+        // Ramping up: each jump past 2% of the old peak breaks the streak
+        assert!(!settle.observe(1_000_000));
+        assert!(!settle.observe(2_000_000));
+        assert!(!settle.observe(1_900_000));
+        assert!(!settle.observe(2_000_000));
+        assert!(!settle.observe(1_990_000));
+        assert!(settle.observe(1_980_000));
+    }
+
+    #[test]
+    fn test_adaptive_warmup_is_bounded() {
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 10,
+                warmup_duration: Duration::from_millis(50),
+                warmup_mode: WarmupMode::Adaptive,
+                max_warmup_secs: 0.3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = measure_simple(&config, "adaptive", "test_module", || {
+            black_box(1 + 1);
+        })
+        .unwrap();
+        assert!(result.warmup_iterations.unwrap() > 0);
+        // Without frequency readings warmup falls back to the fixed duration
+        match result.warmup_mode {
+            WarmupMode::Adaptive => assert!(result.warmup_ms.unwrap() <= 400),
+            WarmupMode::Fixed => assert!(result.warmup_ms.unwrap() <= 150),
+        }
+    }
+
+    #[test]
+    fn test_discard_samples_are_not_recorded() {
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 20,
+                warmup_duration: Duration::ZERO,
+                discard_samples: 5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // The first five calls sleep; none of them may show up in the timings
+        let mut calls = 0;
+        let result = measure_simple(&config, "discard", "test_module", || {
+            calls += 1;
+            if calls <= 5 {
+                thread::sleep(Duration::from_millis(5));
+            }
+        })
+        .unwrap();
+
+        assert_eq!(calls, 25);
+        assert_eq!(result.samples, 20);
+        assert_eq!(result.all_timings.len(), 20);
+        assert_eq!(result.discarded_samples, 5);
+        assert!(result
+            .all_timings
+            .iter()
+            .all(|t| *t < Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn test_max_time_truncates_after_minimum_samples() {
+        let budget_config = |max_time_secs: f64| BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 1000,
+                warmup_duration: Duration::ZERO,
+                max_time_secs: Some(max_time_secs),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let work = || std::thread::sleep(Duration::from_millis(1));
+
+        // 1000 one-millisecond samples can't fit in 50ms
+        let truncated = measure_simple(&budget_config(0.05), "slow", "test_module", work).unwrap();
+        assert!(truncated.truncated);
+        assert!(truncated.samples >= MIN_SAMPLES_WITHIN_BUDGET);
+        assert!(truncated.samples < 1000);
+        assert_eq!(truncated.all_timings.len(), truncated.samples);
+
+        // An exhausted budget still yields the minimum sample count
+        let minimum = measure_simple(&budget_config(0.0), "minimum", "test_module", work).unwrap();
+        assert!(minimum.truncated);
+        assert_eq!(minimum.samples, MIN_SAMPLES_WITHIN_BUDGET);
+
+        let fast = measure_simple(&budget_config(60.0), "fast", "test_module", || {
+            black_box(1 + 1);
+        })
+        .unwrap();
+        assert!(!fast.truncated);
+        assert_eq!(fast.samples, 1000);
+    }
+
+    #[test]
+    fn test_summarized_run_keeps_a_reservoir() {
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 2 * RESERVOIR_SIZE,
+                warmup_duration: Duration::ZERO,
+                store_raw_samples: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = measure_simple(&config, "summarized", "test_module", || {
+            black_box(1 + 1);
+        })
+        .unwrap();
+
+        assert_eq!(result.samples, 2 * RESERVOIR_SIZE);
+        assert_eq!(result.all_timings.len(), RESERVOIR_SIZE);
+        assert_eq!(result.sample_offsets_ms.len(), RESERVOIR_SIZE);
+        let summary = result.summary.clone().unwrap();
+        assert_eq!(summary.sample_count, 2 * RESERVOIR_SIZE);
+        assert_eq!(result.percentiles.mean.as_nanos(), summary.mean);
+
+        let baseline = BaselineData::from_bench_result(&result, "test-machine".into(), false);
+        assert!(baseline.reservoir_sampled);
+        assert_eq!(baseline.samples.len(), RESERVOIR_SIZE);
+        assert_eq!(baseline.statistics.sample_count, 2 * RESERVOIR_SIZE);
+        let restored = baseline.to_bench_result();
+        assert_eq!(restored.samples, 2 * RESERVOIR_SIZE);
+        assert_eq!(restored.summary.unwrap().mean, summary.mean);
+    }
+
+    #[test]
+    fn test_watchdog_flags_pathological_samples() {
+        use crate::config::MeasurementConfig;
+        use std::cell::Cell;
+
+        let mut watchdog = Watchdog::from_config(&MeasurementConfig {
+            watchdog_factor: 100.0,
+            ..Default::default()
+        })
+        .unwrap();
+        for index in 0..20 {
+            assert!(watchdog.check(index, Duration::from_micros(2)).is_none());
+        }
+        assert_eq!(
+            watchdog.check(20, Duration::from_millis(1)),
+            Some(PathologicalSample {
+                index: 20,
+                duration_ns: 1_000_000,
+                median_ns: Some(2_000),
+            })
+        );
+        assert!(watchdog.check(21, Duration::from_micros(150)).is_none());
+
+        // A median of 0ns (or within the timer overhead) flags nothing by factor
+        let mut watchdog = Watchdog::from_config(&MeasurementConfig {
+            watchdog_factor: 100.0,
+            ..Default::default()
+        })
+        .unwrap()
+        .with_timer_overhead(Duration::from_nanos(20));
+        for index in 0..20 {
+            assert!(watchdog.check(index, Duration::ZERO).is_none());
+        }
+        assert_eq!(watchdog.median_ns, Some(0));
+        assert!(watchdog.check(20, Duration::from_nanos(40)).is_none());
+        for index in 21..60 {
+            watchdog.check(index, Duration::from_nanos(15));
+        }
+        assert_eq!(watchdog.median_ns, Some(15));
+        assert!(watchdog.check(60, Duration::from_micros(10)).is_none());
+        assert!(Watchdog::from_config(&MeasurementConfig {
+            watchdog_factor: 0.0,
+            ..Default::default()
+        })
+        .is_none());
+
+        // The ceiling applies before any median exists; watchdog_stop keeps
+        // the samples so far
+        let config = BenchmarkConfig {
+            measurement: MeasurementConfig {
+                watchdog_max_sample_ms: Some(30.0),
+                watchdog_stop: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let calls = Cell::new(0);
+        let mut prepare = || Ok(());
+        let mut run = |()| {
+            calls.set(calls.get() + 1);
+            if calls.get() == 3 {
+                thread::sleep(Duration::from_millis(60));
+            }
+        };
+        let measurements = SampleLoop::new("stalls", &config)
+            .measure(&mut prepare, &mut run, 100)
+            .unwrap();
+        assert_eq!(measurements.timings.len(), 3);
+        assert!(measurements.truncated && measurements.watchdog_stopped);
+        assert!(matches!(
+            measurements.pathological_samples.as_slice(),
+            [PathologicalSample {
+                index: 2,
+                median_ns: None,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_max_cv_fails_noisy_benchmarks() {
+        use std::cell::Cell;
+
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 20,
+                warmup_duration: Duration::ZERO,
+                max_cv_pct: Some(10.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Every other call sleeps: a CV far beyond any quality bar
+        let calls = Cell::new(0);
+        let noisy = measure_simple(&config, "noisy", "test_module", || {
+            calls.set(calls.get() + 1);
+            if calls.get() % 2 == 0 {
+                thread::sleep(Duration::from_millis(2));
+            }
+        })
+        .unwrap();
+        let failure = noisy.quality_failure.unwrap();
+        assert!(failure.cv_pct > 50.0);
+        assert_eq!(failure.max_cv_pct, 10.0);
+        assert!(failure.format().starts_with("QUALITY FAIL (CV "));
+        assert!(failure.format().ends_with("% > 10%)"));
+
+        let steady = measure_simple(&config, "steady", "test_module", || {
+            thread::sleep(Duration::from_millis(2));
+        })
+        .unwrap();
+        assert!(steady.quality_failure.is_none());
+
+        // Without a bar nothing is checked
+        let mut unchecked = noisy.clone();
+        unchecked.check_quality(None);
+        assert!(unchecked.quality_failure.is_none());
+    }
+
+    #[test]
+    fn test_early_verdict_stops_once_settled() {
+        // A window of runs averaging ~1µs
+        let window: Vec<BaselineData> = [990u64, 1000, 1010]
+            .iter()
+            .map(|&nanos| {
+                let result = BenchResult {
+                    all_timings: vec![Duration::from_nanos(nanos); 50],
+                    ..Default::default()
+                };
+                BaselineData::from_bench_result(&result, "test-machine".into(), false)
+            })
+            .collect();
+        let config = BenchmarkConfig {
+            measurement: MeasurementConfig {
+                early_verdict: true,
+                early_verdict_interval: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let rule = EarlyVerdictRule::from_window(&window, &config).unwrap();
+        assert_eq!(rule.interval, MIN_EARLY_VERDICT_INTERVAL);
+        assert!(EarlyVerdictRule::from_window(&window[..1], &config).is_none());
+
+        let moments = |timings: &[Duration]| {
+            timings
+                .iter()
+                .map(|d| d.as_nanos() as u64)
+                .collect::<Moments>()
+        };
+        let faster = vec![Duration::from_nanos(500); 30];
+        assert_eq!(
+            rule.check(&moments(&faster)),
+            Some(EarlyVerdict::NoRegression)
+        );
+        assert_eq!(rule.check(&moments(&faster[..29])), None);
+        let close = [Duration::from_nanos(1000), Duration::from_nanos(1100)].repeat(15);
+        assert_eq!(rule.check(&moments(&close)), None);
+
+        // 20µs per call against a ~1µs window: settled long before the cap,
+        // though a preempted sample may postpone it by a check or two
+        let mut sample_loop = SampleLoop::new("slow", &config);
+        sample_loop.early_verdict = Some(rule);
+        let mut prepare = || Ok(());
+        let mut run = |()| {
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_micros(20) {
+                std::hint::spin_loop();
+            }
+        };
+        let measurements = sample_loop.measure(&mut prepare, &mut run, 1000).unwrap();
+        assert_eq!(measurements.early_verdict, Some(EarlyVerdict::Regression));
+        assert!(measurements.timings.len() < 1000);
+        assert_eq!(measurements.timings.len() % MIN_EARLY_VERDICT_INTERVAL, 0);
+    }
+
+    #[test]
+    fn test_manual_timer_unused_is_an_error() {
+        let config = BenchmarkConfig::default();
+
+        let failure =
+            measure_with_manual_timer(&config, "test_untimed", "test_module", |_| {}).unwrap_err();
+        assert_eq!(failure.name, "test_untimed");
+        assert!(failure.message.contains("Timer::start()"));
+    }
+
+    #[test]
+    fn test_sample_loop_warmup_and_measure_share_iteration() {
+        use std::cell::Cell;
+
+        let prepared = Cell::new(0u32);
+        let ran = Cell::new(0u32);
+        let mut prepare = || {
+            prepared.set(prepared.get() + 1);
+            Ok(())
+        };
+        let mut run = |()| ran.set(ran.get() + 1);
+
+        let sample_loop = SampleLoop::new("test_bench", &BenchmarkConfig::default());
+        let warmup = sample_loop
+            .warmup(&mut prepare, &mut run, Duration::from_millis(20))
+            .unwrap();
+        let warmup_iters = warmup.iterations;
+        let measurements = sample_loop.measure(&mut prepare, &mut run, 25).unwrap();
+
+        // Every warmup and measurement iteration goes through prepare + run once
+        assert_eq!(prepared.get() as u64, warmup_iters + 25);
+        assert_eq!(ran.get() as u64, warmup_iters + 25);
+        assert_eq!(measurements.timings.len(), 25);
+        assert_eq!(measurements.offsets_ms.len(), 25);
+        assert!(measurements.offsets_ms.windows(2).all(|w| w[0] <= w[1]));
+        // CPU readings come from the background sampler: one at the start, one at the end
+        assert!(measurements.cpu_samples.len() >= 2);
+        // Both phases report the mean of their timed durations, not loop wall time
+        assert!(warmup.ns_per_iter.is_some());
+        let timed_mean = measurements
+            .timings
+            .iter()
+            .map(|d| d.as_nanos() as f64)
+            .sum::<f64>()
+            / 25.0;
+        assert!((measurements.ns_per_iter.unwrap() - timed_mean).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_unified_warmup_on_cache_sensitive_workload() {
+        use std::cell::Cell;
+
+        // A workload that is slow until its state is warm: the first
+        // `COLD_CALLS` calls each pay `COLD_COST`, as a cold cache would
+        const COLD_CALLS: u64 = 20;
+        const COLD_COST: Duration = Duration::from_millis(1);
+        let calls = Cell::new(0u64);
+        let workload = || {
+            let call = calls.get();
+            calls.set(call + 1);
+            if call < COLD_CALLS {
+                let start = Instant::now();
+                while start.elapsed() < COLD_COST {
+                    std::hint::spin_loop();
+                }
+            }
+            black_box(call);
+        };
+        let first_samples_median = |timings: &[Duration]| {
+            let mut first = timings[..10].to_vec();
+            first.sort();
+            first[5]
+        };
+
+        // Without warmup the first samples pay the cold cost
+        let sample_loop = SampleLoop::new("cold", &BenchmarkConfig::default());
+        let cold = sample_loop
+            .measure(&mut || Ok(()), &mut |()| workload(), 50)
+            .unwrap();
+        assert!(cold.timings[0] >= COLD_COST);
+
+        // Warmup runs the measured iteration itself, so the state it warms is
+        // the one the first measured samples see
+        calls.set(0);
+        let fixed_warmup = BenchmarkConfig {
+            measurement: MeasurementConfig {
+                warmup_mode: WarmupMode::Fixed,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let sample_loop = SampleLoop::new("unified", &fixed_warmup);
+        let warmup_iterations = sample_loop
+            .warmup(
+                &mut || Ok(()),
+                &mut |()| workload(),
+                Duration::from_millis(50),
+            )
+            .unwrap()
+            .iterations;
+        assert!(warmup_iterations > COLD_CALLS);
+        let warm = sample_loop
+            .measure(&mut || Ok(()), &mut |()| workload(), 50)
+            .unwrap();
+        assert!(
+            first_samples_median(&warm.timings) < COLD_COST / 10,
+            "first measured samples {:?} still pay the cold cost",
+            &warm.timings[..10]
+        );
+    }
+
+    #[test]
+    fn test_drop_and_settle_complete_before_next_setup() {
+        use std::cell::RefCell;
+
+        // Each payload logs when it's dropped; each setup logs when it starts
+        #[derive(Debug, PartialEq)]
+        enum Event {
+            Setup(Instant),
+            Drop(Instant),
+        }
+        struct Payload<'a>(Vec<u8>, &'a RefCell<Vec<Event>>);
+        impl Drop for Payload<'_> {
+            fn drop(&mut self) {
+                self.1.borrow_mut().push(Event::Drop(Instant::now()));
+            }
+        }
+
+        let events = RefCell::new(Vec::new());
+        let config = BenchmarkConfig {
+            measurement: crate::config::MeasurementConfig {
+                samples: 20,
+                warmup_duration: Duration::ZERO,
+                settle_after_drop_us: 200,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        measure_with_setup_each_ref(
+            &config,
+            "test_large_drop",
+            "test_module",
+            || {
+                events.borrow_mut().push(Event::Setup(Instant::now()));
+                Payload(vec![1u8; 64 * 1024], &events)
+            },
+            |data| {
+                black_box(data.0.iter().step_by(4096).map(|&b| b as u64).sum::<u64>());
+            },
+        )
+        .unwrap();
+
+        let events = events.into_inner();
+        assert_eq!(events.len(), 40);
+        for pair in events.chunks(2).collect::<Vec<_>>().windows(2) {
+            // Setup, drop, then the next setup only after the settle time
+            let (Event::Setup(_), Event::Drop(dropped)) = (&pair[0][0], &pair[0][1]) else {
+                panic!("expected setup followed by drop, got {:?}", pair[0]);
+            };
+            let Event::Setup(next_setup) = pair[1][0] else {
+                panic!("expected next setup, got {:?}", pair[1][0]);
+            };
+            assert!(next_setup.duration_since(*dropped) >= Duration::from_micros(200));
+        }
+    }
 }
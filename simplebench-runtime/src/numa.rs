@@ -0,0 +1,104 @@
+//! NUMA node lookup and memory binding for pinned runners.
+//!
+//! On multi-socket machines a benchmark pinned to one node can still allocate
+//! memory on another, and every access then crosses the interconnect. With the
+//! `numa` feature on Linux, the runner binds its allocations to the node of its
+//! pinned core (`set_mempolicy(MPOL_BIND)`) before warmup begins.
+//!
+//! Without the feature or on other platforms, binding reports
+//! [`std::io::ErrorKind::Unsupported`] and memory placement is left to the kernel.
+
+use std::fs;
+use std::path::Path;
+
+/// NUMA node of a logical CPU, from `/sys/devices/system/cpu/cpuN/nodeK`
+///
+/// None if sysfs has no node link for the CPU (non-NUMA kernels, other platforms).
+pub fn node_of_cpu(cpu: usize) -> Option<usize> {
+    node_in_cpu_dir(&Path::new("/sys/devices/system/cpu").join(format!("cpu{}", cpu)))
+}
+
+/// NUMA node named by the `nodeK` entry of a sysfs CPU directory
+pub fn node_in_cpu_dir(cpu_dir: &Path) -> Option<usize> {
+    fs::read_dir(cpu_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .find_map(|entry| {
+            let name = entry.file_name();
+            let node = name.to_str()?.strip_prefix("node")?;
+            node.parse().ok()
+        })
+}
+
+/// Restrict the calling thread's future allocations to `node`
+///
+/// Threads spawned afterwards inherit the policy.
+pub fn bind_memory_to_node(node: usize) -> std::io::Result<()> {
+    #[cfg(all(feature = "numa", target_os = "linux"))]
+    {
+        linux::set_mempolicy_bind(node)
+    }
+    #[cfg(not(all(feature = "numa", target_os = "linux")))]
+    {
+        let _ = node;
+        Err(std::io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[cfg(all(feature = "numa", target_os = "linux"))]
+mod linux {
+    const MPOL_BIND: libc::c_int = 2;
+    const BITS_PER_WORD: usize = libc::c_ulong::BITS as usize;
+
+    pub fn set_mempolicy_bind(node: usize) -> std::io::Result<()> {
+        let mut nodemask = vec![0 as libc::c_ulong; node / BITS_PER_WORD + 1];
+        nodemask[node / BITS_PER_WORD] |= 1 << (node % BITS_PER_WORD);
+
+        // The kernel reads `maxnode - 1` bits of the mask
+        let maxnode = (nodemask.len() * BITS_PER_WORD + 1) as libc::c_ulong;
+
+        // SAFETY: `nodemask` is valid for reads of the `maxnode - 1` bits passed
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_set_mempolicy,
+                MPOL_BIND,
+                nodemask.as_ptr(),
+                maxnode,
+            )
+        };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_in_cpu_dir_reads_node_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("topology")).unwrap();
+        assert_eq!(node_in_cpu_dir(dir.path()), None);
+
+        fs::create_dir(dir.path().join("node1")).unwrap();
+        assert_eq!(node_in_cpu_dir(dir.path()), Some(1));
+        assert_eq!(node_in_cpu_dir(&dir.path().join("missing")), None);
+    }
+
+    #[test]
+    fn test_bind_memory_degrades_gracefully() {
+        // Either node 0 is bindable, or binding reports why it isn't
+        let node = node_of_cpu(0).unwrap_or(0);
+        if let Err(e) = bind_memory_to_node(node) {
+            // Without the feature, binding is never attempted
+            assert!(
+                e.kind() == std::io::ErrorKind::Unsupported
+                    || cfg!(all(feature = "numa", target_os = "linux"))
+            );
+        }
+    }
+}
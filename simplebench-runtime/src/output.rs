@@ -1,9 +1,12 @@
 use crate::baseline::ComparisonResult;
-use crate::{BenchResult, Comparison};
+use crate::columns::{Column, ColumnValue};
+use crate::config::TimeUnit;
+use crate::{AllocComparison, BenchResult, Comparison};
 use colored::*;
 use serde_json;
 use std::fs;
 use std::path::Path;
+use std::sync::RwLock;
 
 pub fn save_result_to_file<P: AsRef<Path>>(
     result: &BenchResult,
@@ -39,57 +42,360 @@ pub fn load_results_from_file<P: AsRef<Path>>(
     Ok(results)
 }
 
-pub fn format_duration_human_readable(duration: std::time::Duration) -> String {
-    let nanos = duration.as_nanos();
-
-    if nanos < 1_000 {
-        format!("{}ns", nanos)
-    } else if nanos < 1_000_000 {
-        format!("{:.2}μs", nanos as f64 / 1_000.0)
-    } else if nanos < 1_000_000_000 {
-        format!("{:.2}ms", nanos as f64 / 1_000_000.0)
-    } else {
-        format!("{:.2}s", nanos as f64 / 1_000_000_000.0)
+/// How durations are printed, from `[output] time_unit`, `precision` and
+/// `thousands_separator`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeFormat {
+    pub unit: TimeUnit,
+    pub precision: usize,
+    pub thousands_separator: bool,
+}
+
+impl TimeFormat {
+    /// Automatic units with two decimals and no separators
+    pub const DEFAULT: TimeFormat = TimeFormat {
+        unit: TimeUnit::Auto,
+        precision: 2,
+        thousands_separator: false,
+    };
+
+    /// Number and unit of `nanos` ("1.50", "μs")
+    ///
+    /// Nanoseconds are whole numbers; the other units carry `precision` decimals.
+    pub fn parts(&self, nanos: u128) -> (String, &'static str) {
+        let unit = match self.unit {
+            TimeUnit::Auto if nanos < 1_000 => TimeUnit::Ns,
+            TimeUnit::Auto if nanos < 1_000_000 => TimeUnit::Us,
+            TimeUnit::Auto if nanos < 1_000_000_000 => TimeUnit::Ms,
+            TimeUnit::Auto => TimeUnit::S,
+            fixed => fixed,
+        };
+        let (scale, label) = match unit {
+            TimeUnit::Ns | TimeUnit::Auto => (1.0, "ns"),
+            TimeUnit::Us => (1_000.0, "μs"),
+            TimeUnit::Ms => (1_000_000.0, "ms"),
+            TimeUnit::S => (1_000_000_000.0, "s"),
+        };
+        let number = if label == "ns" {
+            nanos.to_string()
+        } else {
+            format!("{:.*}", self.precision, nanos as f64 / scale)
+        };
+        if self.thousands_separator {
+            (group_thousands(&number), label)
+        } else {
+            (number, label)
+        }
+    }
+
+    /// "1.50μs"
+    pub fn format(&self, duration: std::time::Duration) -> String {
+        let (number, unit) = self.parts(duration.as_nanos());
+        format!("{}{}", number, unit)
+    }
+}
+
+impl Default for TimeFormat {
+    fn default() -> Self {
+        Self::DEFAULT
     }
 }
 
+/// Commas between each three digits of the integer part, "1234567.5" to
+/// "1,234,567.5"
+fn group_thousands(number: &str) -> String {
+    let (integer, fraction) = number.split_at(number.find('.').unwrap_or(number.len()));
+    let mut grouped = String::with_capacity(number.len() + integer.len() / 3);
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped.push_str(fraction);
+    grouped
+}
+
+static TIME_FORMAT: RwLock<TimeFormat> = RwLock::new(TimeFormat::DEFAULT);
+
+/// Format every later duration of this process with `format`
+///
+/// Set once from the loaded configuration by each command and runner.
+pub fn set_time_format(format: TimeFormat) {
+    *TIME_FORMAT.write().unwrap_or_else(|e| e.into_inner()) = format;
+}
+
+/// Duration formatting set by [`set_time_format`]
+pub fn time_format() -> TimeFormat {
+    *TIME_FORMAT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Format a duration in the process's [`time_format`]
+pub fn format_duration_human_readable(duration: std::time::Duration) -> String {
+    time_format().format(duration)
+}
+
 pub fn format_benchmark_result(result: &BenchResult) -> String {
+    format_benchmark_result_columns(result, Column::TERMINAL_DEFAULT)
+}
+
+/// Format the `BENCH` line of a result with the given columns
+pub fn format_benchmark_result_columns(result: &BenchResult, columns: &[Column]) -> String {
+    if result.skipped {
+        return format_skipped_result(result);
+    }
+
     let bench_name = format!("{}::{}", result.module, result.name);
-    let mean_str = format_duration_human_readable(result.percentiles.mean);
-    let p50_str = format_duration_human_readable(result.percentiles.p50);
-    let p90_str = format_duration_human_readable(result.percentiles.p90);
-    let p99_str = format_duration_human_readable(result.percentiles.p99);
-
-    // Calculate coefficient of variation (CV) from raw timings if available
-    let cv_str = if !result.all_timings.is_empty() {
-        let samples_ns: Vec<u128> = result.all_timings.iter().map(|d| d.as_nanos()).collect();
-        let stats = crate::calculate_statistics(&samples_ns);
-        let cv_pct = if stats.mean > 0 {
-            (stats.std_dev / stats.mean as f64) * 100.0
-        } else {
-            0.0
-        };
-        format!(", CV: {:.1}%", cv_pct)
+    let fields = format_result_columns(result, columns);
+    if fields.is_empty() {
+        format!("{} {}", "BENCH".green().bold(), bench_name.cyan())
     } else {
-        String::new()
-    };
+        format!(
+            "{} {} {}",
+            "BENCH".green().bold(),
+            bench_name.cyan(),
+            fields
+        )
+    }
+}
 
-    format!(
-        "{} {} mean: {}{}, p50: {}, p90: {}, p99: {}",
-        "BENCH".green().bold(),
-        bench_name.cyan(),
-        mean_str.cyan().bold(),
-        if !cv_str.is_empty() {
-            format!(" ({})", cv_str.trim_start_matches(", "))
-                .dimmed()
-                .to_string()
+/// Result fields of the `BENCH` line ("mean: 1.20μs (CV: 2.1%), p50: ...")
+///
+/// CV attaches in parentheses to the field before it; warmup and comparison
+/// columns are printed on lines of their own.
+pub fn format_result_columns(result: &BenchResult, columns: &[Column]) -> String {
+    format_result_columns_with(result, columns, &time_format())
+}
+
+/// [`format_result_columns`] with durations in `time_format`
+pub fn format_result_columns_with(
+    result: &BenchResult,
+    columns: &[Column],
+    time_format: &TimeFormat,
+) -> String {
+    let mut line = String::new();
+    for &column in columns {
+        let text = match column.value(result, None) {
+            Some(ColumnValue::Time(duration)) => time_format.format(duration),
+            Some(ColumnValue::Text(text)) => text,
+            Some(ColumnValue::Times(times)) => {
+                for (label, timing) in times {
+                    if !line.is_empty() {
+                        line.push_str(", ");
+                    }
+                    let value = time_format.format(timing);
+                    line.push_str(&format!("{}: {}", label, value.dimmed()));
+                }
+                continue;
+            }
+            None => continue,
+        };
+        // The trimmed mean follows the mean, after its CV when that's shown
+        let trimmed = || format_trimmed_mean(result, time_format).unwrap_or_default();
+        let field = match column {
+            Column::Cv => {
+                let cv = format!("(CV: {})", text);
+                if line.is_empty() {
+                    line = cv.dimmed().to_string();
+                } else {
+                    line.push_str(&format!(" {}", cv).dimmed().to_string());
+                }
+                if columns.contains(&Column::Mean) {
+                    line.push_str(&trimmed());
+                }
+                continue;
+            }
+            Column::Mean if columns.contains(&Column::Cv) => {
+                format!("mean: {}", text.cyan().bold())
+            }
+            Column::Mean => format!("mean: {}{}", text.cyan().bold(), trimmed()),
+            Column::Throughput => text.bold().to_string(),
+            Column::Allocs => format!("allocs: {}", text),
+            Column::Warmup
+            | Column::Delta
+            | Column::Z
+            | Column::Cp
+            | Column::Mde
+            | Column::EffectSize => continue,
+            _ => format!("{}: {}", column.name(), text.dimmed()),
+        };
+        if !line.is_empty() {
+            line.push_str(", ");
+        }
+        line.push_str(&field);
+    }
+    line
+}
+
+/// ", trimmed: 199.00μs, 3 outliers" when the Tukey fences removed samples
+fn format_trimmed_mean(result: &BenchResult, time_format: &TimeFormat) -> Option<String> {
+    let filtered = result
+        .filtered
+        .as_ref()
+        .filter(|f| f.outliers_removed > 0)?;
+    Some(format!(
+        ", trimmed: {}, {} outlier{}",
+        time_format
+            .format(std::time::Duration::from_nanos(
+                filtered.statistics.mean as u64
+            ))
+            .cyan(),
+        filtered.outliers_removed,
+        if filtered.outliers_removed == 1 {
+            ""
         } else {
-            String::new()
-        },
-        p50_str.dimmed(),
-        p90_str.dimmed(),
-        p99_str.dimmed()
+            "s"
+        }
+    ))
+}
+
+/// The "Warmup: 3000ms (1200 iterations)" detail line, if the column is selected
+pub fn format_warmup_line(result: &BenchResult, columns: &[Column]) -> Option<String> {
+    if !columns.contains(&Column::Warmup) {
+        return None;
+    }
+    match Column::Warmup.value(result, None)? {
+        ColumnValue::Text(text) => Some(format!("        {} {}", "Warmup:".dimmed(), text)),
+        ColumnValue::Time(_) | ColumnValue::Times(_) => None,
+    }
+}
+
+/// "⚠ 2 pathological samples (slowest #17: 4.00s)", if the watchdog flagged any
+pub fn format_pathological_line(result: &BenchResult) -> Option<String> {
+    let slowest = result
+        .pathological_samples
+        .iter()
+        .max_by_key(|sample| sample.duration_ns)?;
+    let count = result.pathological_samples.len();
+    Some(format!(
+        "⚠ {} pathological sample{} (slowest #{}: {})",
+        count,
+        if count == 1 { "" } else { "s" },
+        slowest.index,
+        format_duration_human_readable(std::time::Duration::from_nanos(slowest.duration_ns))
+    ))
+}
+
+/// "periodic spikes every ~200 samples, amplitude 12.0×" when the samples
+/// repeat a pattern, so a wide distribution reads as structured rather than noisy
+pub fn format_periodicity_line(result: &BenchResult) -> Option<String> {
+    let samples: Vec<u128> = result.all_timings.iter().map(|t| t.as_nanos()).collect();
+    crate::statistics::detect_periodicity(&samples).map(|periodicity| periodicity.format())
+}
+
+/// Relative difference between wall and CPU time above which both are shown
+const WALL_CPU_DIVERGENCE: f64 = 0.05;
+
+/// Format mean wall vs CPU time ("wall 4.10ms, cpu 900.00µs") when they diverge
+///
+/// Returns None when CPU time wasn't recorded or is within a few percent of the
+/// wall time, as for CPU-bound benchmarks.
+pub fn format_wall_vs_cpu(result: &BenchResult) -> Option<String> {
+    let cpu = result.cpu_time_percentiles.as_ref()?.mean;
+    let wall = result.percentiles.mean;
+    if wall.is_zero() {
+        return None;
+    }
+
+    let divergence =
+        (wall.as_nanos() as f64 - cpu.as_nanos() as f64).abs() / wall.as_nanos() as f64;
+    if divergence <= WALL_CPU_DIVERGENCE {
+        return None;
+    }
+
+    Some(format!(
+        "wall {}, cpu {}",
+        format_duration_human_readable(wall),
+        format_duration_human_readable(cpu)
+    ))
+}
+
+/// Scheduling-interference warning when mean wall time is well above mean CPU time
+pub fn format_scheduling_warning(result: &BenchResult) -> Option<String> {
+    let cpu = result.cpu_time_percentiles.as_ref()?.mean;
+    crate::cpu_analysis::scheduling_interference(
+        result.percentiles.mean.as_nanos() as f64,
+        cpu.as_nanos() as f64,
     )
+    .map(|warning| warning.format())
+}
+
+/// Format the line for a benchmark skipped by `skip_if` or an unmet precondition
+pub fn format_skipped_result(result: &BenchResult) -> String {
+    let bench_name = format!("{}::{}", result.module, result.name);
+    match result.skip_reason {
+        Some(ref reason) => format!(
+            "{} {} ({})",
+            "SKIPPED".yellow().bold(),
+            bench_name.cyan(),
+            reason.dimmed()
+        ),
+        None => format!("{} {}", "SKIPPED".yellow().bold(), bench_name.cyan()),
+    }
+}
+
+/// Summary lines giving the reason each of `skipped` was skipped
+pub fn format_skip_reasons<'a>(skipped: impl IntoIterator<Item = &'a BenchResult>) -> Vec<String> {
+    skipped
+        .into_iter()
+        .filter_map(|result| {
+            let reason = result.skip_reason.as_ref()?;
+            Some(format!(
+                "  {} {}: {}",
+                "Skipped".yellow(),
+                result.name,
+                reason.dimmed()
+            ))
+        })
+        .collect()
+}
+
+/// Summary warnings for comparisons whose window drifted past `drift_threshold`
+pub fn format_drift_warnings(
+    comparisons: &[ComparisonResult],
+    config: &crate::config::ComparisonConfig,
+) -> Vec<String> {
+    comparisons
+        .iter()
+        .filter_map(|c| {
+            let trend = c.drift()?;
+            Some(format!(
+                "{} {} drifted {:+.1}% over the last {} runs, {} (drift threshold: {}%)",
+                "Warning:".yellow().bold(),
+                c.benchmark_name,
+                trend.drift_pct(),
+                trend.runs,
+                trend.format(),
+                config.drift_threshold.unwrap_or_default()
+            ))
+        })
+        .collect()
+}
+
+/// Summary warning counting comparisons whose warmup and sampling phases
+/// disagreed; their verdicts stand but deserve a re-run
+pub fn format_suspect_warning(comparisons: &[ComparisonResult]) -> Option<String> {
+    let suspects = comparisons
+        .iter()
+        .filter(|c| c.comparison.as_ref().is_some_and(|c| c.suspect.is_some()))
+        .count();
+    (suspects > 0).then(|| {
+        format!(
+            "{} {} suspect result(s): warmup and sampling disagree, re-run to confirm",
+            "Warning:".yellow().bold(),
+            suspects
+        )
+    })
+}
+
+/// Format the throughput rate derived from the mean (", 1.25 GiB/s"), or an
+/// empty string when the benchmark declared no throughput
+pub fn format_throughput(result: &BenchResult) -> String {
+    result
+        .throughput
+        .and_then(|t| t.format_rate(result.percentiles.mean))
+        .map(|rate| format!(", {}", rate.bold()))
+        .unwrap_or_default()
 }
 
 /// Format CPU statistics from samples
@@ -146,16 +452,102 @@ pub fn format_cpu_stats(cpu_samples: &[crate::CpuSnapshot]) -> Option<String> {
 }
 
 pub fn format_comparison_result(
+    comparison: &Comparison,
+    benchmark_name: &str,
+    is_regression: bool,
+) -> String {
+    format_comparison_result_columns(
+        comparison,
+        benchmark_name,
+        is_regression,
+        Column::TERMINAL_DEFAULT,
+    )
+}
+
+/// Format a comparison with the given columns, followed by its warnings
+pub fn format_comparison_result_columns(
     comparison: &Comparison,
     _benchmark_name: &str,
     is_regression: bool,
+    columns: &[Column],
 ) -> String {
-    let change_symbol = if comparison.percentage_change > 0.0 {
+    let mut output = format_comparison_columns(comparison, is_regression, columns);
+
+    if let Some(note) = comparison.provisional_note() {
+        output.push_str(&format!("\n        {} {}", "⚠".yellow(), note.yellow()));
+    }
+
+    if let Some(ref suspect) = comparison.suspect {
+        output.push_str(&format!(
+            "\n        {} {}",
+            "⚠".yellow(),
+            suspect.format().yellow()
+        ));
+    }
+
+    if let Some(ref allocs) = comparison.allocs {
+        output.push_str(&format!("\n        {}", format_alloc_comparison(allocs)));
+    }
+
+    if comparison.mixed_build_profiles {
+        output.push_str(&format!(
+            "\n        {} {}",
+            "⚠".yellow(),
+            "baseline mixes builds with and without debug assertions; comparison is unreliable"
+                .yellow()
+        ));
+    }
+
+    for change in &comparison.env_changes {
+        output.push_str(&format!(
+            "\n        {} {}",
+            "⚠".yellow(),
+            change.format().yellow()
+        ));
+    }
+
+    if let Some(ref change) = comparison.commit_change {
+        output.push_str(&format!("\n        {}", change.format().dimmed()));
+    }
+
+    for change in &comparison.toolchain_changes {
+        let note = format!("note: {}", change.format());
+        output.push_str(&format!("\n        {}", note.dimmed()));
+    }
+
+    if let Some(note) = comparison.accepted_note() {
+        output.push_str(&format!("\n        {}", note.dimmed()));
+    }
+
+    output
+}
+
+/// Verdict line of a comparison ("REGRESS ↗ 7.2% (mean: ... -> ...)") and,
+/// when any of its statistics are selected, a second line listing them
+pub fn format_comparison_columns(
+    comparison: &Comparison,
+    is_regression: bool,
+    columns: &[Column],
+) -> String {
+    // A regression of the p99 alone replaces the mean's verdict line
+    let tail_regression = comparison.p99.filter(|t| t.is_regression);
+    let tail_only = is_regression
+        && tail_regression.is_some()
+        && comparison.percentage_change <= comparison.threshold;
+    let is_regression = is_regression && !tail_only;
+
+    let change_symbol = if comparison.percentage_change > 0.0
+        || (comparison.below_resolution() && !comparison.current_mean.is_zero())
+    {
         "↗"
     } else {
         "↘"
     };
-    let percentage_str = format!("{:.1}%", comparison.percentage_change.abs());
+    let percentage_str = if comparison.below_resolution() {
+        format_duration_human_readable(comparison.current_mean)
+    } else {
+        format!("{:.1}%", comparison.percentage_change.abs())
+    };
     let baseline_str = format_duration_human_readable(comparison.baseline_mean);
     let current_str = format_duration_human_readable(comparison.current_mean);
 
@@ -166,49 +558,115 @@ pub fn format_comparison_result(
         String::new()
     };
 
-    let base_line = if is_regression {
-        format!(
-            "        {} {} {} (mean: {} -> {}{})",
+    let (label, percentage, current) = if is_regression {
+        (
             "REGRESS".red().bold(),
-            change_symbol,
             percentage_str.red().bold(),
-            baseline_str.dimmed(),
             current_str.red(),
-            baseline_suffix.dimmed()
+        )
+    } else if comparison.suspect.is_some() || comparison.quality_failure.is_some() {
+        (
+            "SUSPECT".yellow().bold(),
+            percentage_str.yellow(),
+            current_str.yellow(),
+        )
+    } else if comparison.provisional {
+        (
+            "PROVISIONAL".yellow().bold(),
+            percentage_str.yellow(),
+            current_str.yellow(),
         )
     } else if comparison.percentage_change < -5.0 {
         // Show improvements of >5% in green
-        format!(
-            "        {} {} {} (mean: {} -> {}{})",
+        (
             "IMPROVE".green().bold(),
-            change_symbol,
             percentage_str.green(),
-            baseline_str.dimmed(),
             current_str.green(),
-            baseline_suffix.dimmed()
         )
     } else {
-        // Minor changes in yellow
-        format!(
-            "        {} {} {} (mean: {} -> {}{})",
+        // Minor changes dimmed
+        (
             "STABLE".cyan(),
-            change_symbol,
             percentage_str.dimmed(),
-            baseline_str.dimmed(),
             current_str.dimmed(),
-            baseline_suffix.dimmed()
         )
     };
 
+    let mut base_line = format!("        {}", label);
+    if columns.contains(&Column::Delta) {
+        base_line.push_str(&format!(" {} {}", change_symbol, percentage));
+    }
+    if columns.contains(&Column::Mean) {
+        base_line.push_str(&format!(
+            " (mean: {} -> {}{})",
+            baseline_str.dimmed(),
+            current,
+            baseline_suffix.dimmed()
+        ));
+    }
+    // A per-benchmark threshold from `[comparison.overrides]`
+    if comparison.global_threshold.is_some() {
+        base_line.push_str(&format!(
+            " {}",
+            format!("[threshold {}%]", comparison.threshold).dimmed()
+        ));
+    }
+    // A window pooled across machines compares ratios, not nanoseconds
+    if let Some(machines) = comparison.normalized_machines {
+        base_line.push_str(&format!(
+            " {}",
+            format!("[normalized across {} machines]", machines).dimmed()
+        ));
+    }
+
     // Add statistical info if available
     let mut stats_parts = Vec::new();
 
-    if let Some(z_score) = comparison.z_score {
-        stats_parts.push(format!("z={:.2}", z_score));
+    for &column in columns {
+        let label = match column {
+            Column::Z => "z=",
+            Column::Cp => "cp=",
+            Column::Mde => "mde=",
+            Column::EffectSize => "d=",
+            _ => continue,
+        };
+        if let Some(ColumnValue::Text(value)) = column.comparison_value(comparison) {
+            stats_parts.push(format!("{}{}", label, value));
+        }
+    }
+
+    if let (Some(t), Some(p)) = (comparison.t_statistic, comparison.p_value) {
+        stats_parts.push(format!("t={:.2}, p={:.4}", t, p));
     }
 
-    if let Some(cp_prob) = comparison.change_probability {
-        stats_parts.push(format!("cp={:.0}%", cp_prob * 100.0));
+    if let Some(tail) = comparison.p99.filter(|t| !t.is_regression) {
+        stats_parts.push(format!("p99 {:+.1}%", tail.percentage_change));
+    }
+
+    if let Some(ref instructions) = comparison.instructions {
+        stats_parts.push(format_instruction_change(instructions));
+    }
+
+    // Throughput before/after; the comparison itself stays time-based
+    if let Some(throughput) = comparison
+        .throughput
+        .filter(|_| columns.contains(&Column::Throughput))
+    {
+        if let (Some(before), Some(after)) = (
+            throughput.format_rate(comparison.baseline_mean),
+            throughput.format_rate(comparison.current_mean),
+        ) {
+            stats_parts.push(format!("throughput: {} -> {}", before, after));
+        }
+    }
+
+    if let Some(ref tail) = tail_regression {
+        let tail_line = format_tail_regression(tail, tail_only);
+        base_line = if tail_only {
+            tail_line
+        } else {
+            format!("{}\n{}", base_line, tail_line)
+        };
     }
 
     if !stats_parts.is_empty() {
@@ -218,6 +676,125 @@ pub fn format_comparison_result(
     }
 }
 
+/// "REGRESS p99 ↗ 45.0% (p99: 10.00μs -> 14.50μs, mean stable)"
+fn format_tail_regression(tail: &crate::TailComparison, mean_stable: bool) -> String {
+    let change_symbol = if tail.percentage_change > 0.0 {
+        "↗"
+    } else {
+        "↘"
+    };
+    format!(
+        "        {} {} {} ({} -> {}{})",
+        "REGRESS p99".red().bold(),
+        change_symbol,
+        format!("{:.1}%", tail.percentage_change.abs()).red().bold(),
+        format!("p99: {}", format_duration_human_readable(tail.baseline_p99)).dimmed(),
+        format_duration_human_readable(tail.current_p99).red(),
+        if mean_stable { ", mean stable" } else { "" }
+    )
+}
+
+/// Warning for a comparison with less than 80% power to detect a change of its
+/// threshold; None when adequately powered or already marked provisional
+pub fn format_power_warning(comparison: &Comparison, confidence_level: f64) -> Option<String> {
+    let mde = comparison.min_detectable_effect?;
+    if comparison.provisional {
+        return None;
+    }
+    let power = crate::statistics::power_at_effect(comparison.threshold, mde, confidence_level);
+    (power < 0.8).then(|| {
+        format!(
+            "underpowered: {:.0}% chance to detect a {:.1}% change at this sample count (see `cargo simplebench plan`)",
+            power * 100.0,
+            comparison.threshold
+        )
+    })
+}
+
+/// Format instructions per sample before/after ("instructions: 1.20M -> 1.25M (+4.2%)")
+pub fn format_instruction_change(instructions: &crate::InstructionComparison) -> String {
+    use crate::perf_counters::format_count;
+    let change = match instructions.percentage_change() {
+        Some(change) => format!(" ({:+.1}%)", change),
+        None => String::new(),
+    };
+    format!(
+        "instructions: {} -> {}{}",
+        format_count(instructions.baseline_instructions),
+        format_count(instructions.current_instructions),
+        change
+    )
+}
+
+/// Format allocations per sample before/after ("allocs: 12 -> 15 (+25.0%)")
+pub fn format_alloc_comparison(allocs: &AllocComparison) -> String {
+    let change = match allocs.percentage_change() {
+        Some(change) => format!(" ({:+.1}%)", change),
+        None => String::new(),
+    };
+    let line = format!(
+        "allocs: {:.1} -> {:.1}{}",
+        allocs.baseline_allocs, allocs.current_allocs, change
+    );
+
+    if allocs.is_regression {
+        format!("{} {}", "ALLOC REGRESS".red().bold(), line.red())
+    } else {
+        line.dimmed().to_string()
+    }
+}
+
+/// Narrowest a name column gets, however little room the terminal leaves
+pub const MIN_NAME_WIDTH: usize = 16;
+
+/// Width of a column of `names` sharing a line of `terminal_width` with
+/// `reserved` columns of other content
+///
+/// The longest name when it fits, otherwise whatever the other columns leave
+/// (at least [`MIN_NAME_WIDTH`]). Without a terminal width (`--wide`) names
+/// are never squeezed.
+pub fn name_column_width<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+    reserved: usize,
+    terminal_width: Option<usize>,
+) -> usize {
+    let longest = names
+        .into_iter()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    match terminal_width {
+        Some(width) => longest.min(width.saturating_sub(reserved).max(MIN_NAME_WIDTH)),
+        None => longest,
+    }
+}
+
+/// Shorten `name` to at most `width` characters by eliding its middle
+///
+/// The last path segment (after the final `/` or `::`) is what tells
+/// `parse/10` from `parse/1000`, so it is kept whole whenever it fits; the
+/// rest of the room is split between the start of the name and the end.
+pub fn truncate_middle(name: &str, width: usize) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    if chars.len() <= width {
+        return name.to_string();
+    }
+    let Some(budget) = width.checked_sub(1) else {
+        return String::new();
+    };
+    let suffix = name
+        .rfind('/')
+        .or_else(|| name.rfind("::"))
+        .map_or(0, |at| name[at..].chars().count());
+    let tail = suffix.max(budget / 2).min(budget);
+    let head = budget - tail;
+    chars[..head]
+        .iter()
+        .chain(std::iter::once(&'…'))
+        .chain(&chars[chars.len() - tail..])
+        .collect()
+}
+
 pub fn print_benchmark_start(bench_name: &str, module: &str) {
     println!(
         "   {} {}::{}",
@@ -228,16 +805,59 @@ pub fn print_benchmark_start(bench_name: &str, module: &str) {
 }
 
 /// Print a single benchmark result line (for streaming output)
-pub fn print_benchmark_result_line(result: &BenchResult) {
-    println!("{}", format_benchmark_result(result));
+pub fn print_benchmark_result_line(result: &BenchResult, columns: &[Column]) {
+    println!("{}", format_benchmark_result_columns(result, columns));
+
+    // Print wall vs CPU time if they diverge (blocking or sleeping benchmarks)
+    if let Some(times) = format_wall_vs_cpu(result) {
+        println!("        {} {}", "Time:".dimmed(), times);
+    }
+    if let Some(warning) = format_scheduling_warning(result) {
+        println!("        {}", warning.yellow());
+    }
+    if result.clock == crate::ClockMode::Cpu {
+        println!("        {} thread CPU time", "Clock:".dimmed());
+    }
+    if result.measures_drop {
+        println!("        {} drop of the setup value", "Measures:".dimmed());
+    }
 
     // Print warmup stats if available
-    if let (Some(warmup_ms), Some(warmup_iters)) = (result.warmup_ms, result.warmup_iterations) {
+    if let Some(warmup) = format_warmup_line(result, columns) {
+        println!("{}", warmup);
+    }
+
+    if result.watchdog_stopped {
         println!(
-            "        {} {}ms ({} iterations)",
-            "Warmup:".dimmed(),
-            warmup_ms,
-            warmup_iters
+            "        {}",
+            format!("(stopped by the watchdog after {} samples)", result.samples).dimmed()
+        );
+    } else if result.truncated {
+        println!(
+            "        {}",
+            format!("(time budget reached after {} samples)", result.samples).dimmed()
+        );
+    }
+
+    if let Some(line) = format_pathological_line(result) {
+        println!("        {}", line.yellow());
+    }
+    if let Some(pattern) = format_periodicity_line(result) {
+        println!("        {} {}", "Pattern:".dimmed(), pattern);
+    }
+    if let Some(failure) = result.quality_failure {
+        println!("        {}", failure.format().red().bold());
+    }
+
+    if let Some(verdict) = result.early_verdict {
+        println!(
+            "        {}",
+            format!(
+                "(stopped early after {} samples: {})",
+                result.samples,
+                verdict.label()
+            )
+            .dimmed()
         );
     }
 
@@ -245,51 +865,167 @@ pub fn print_benchmark_result_line(result: &BenchResult) {
     if let Some(cpu_stats) = format_cpu_stats(&result.cpu_samples) {
         println!("        {}", cpu_stats.dimmed());
     }
+    if let Some(energy_uj) = result.energy_uj {
+        println!(
+            "        {} {}",
+            "Energy:".dimmed(),
+            crate::energy::format_energy(energy_uj, result.mean_watts)
+        );
+    }
 }
 
 /// Print a single comparison line (for streaming output)
-pub fn print_comparison_line(comparison: &Comparison, benchmark_name: &str, is_regression: bool) {
+pub fn print_comparison_line(
+    comparison: &Comparison,
+    benchmark_name: &str,
+    is_regression: bool,
+    columns: &[Column],
+) {
     println!(
         "{}",
-        format_comparison_result(comparison, benchmark_name, is_regression)
+        format_comparison_result_columns(comparison, benchmark_name, is_regression, columns)
     );
 }
 
-/// Print "NEW" message for first baseline
-pub fn print_new_baseline_line(benchmark_name: &str) {
+/// Print the underpowered warning of a comparison, if it has one
+pub fn print_power_warning(comparison: &Comparison, confidence_level: f64) {
+    if let Some(warning) = format_power_warning(comparison, confidence_level) {
+        println!("        {} {}", "⚠".yellow(), warning.dimmed());
+    }
+}
+
+/// Print a header introducing the benchmarks of a group
+pub fn print_group_header(group: &str) {
+    println!("{} {}\n", "GROUP".magenta().bold(), group.bold());
+}
+
+/// Format per-group summary lines ("  serialization: 4 stable, 1 regressed")
+///
+/// Returns an empty list when no benchmark belongs to a group, so callers can
+/// skip the breakdown entirely for ungrouped workspaces.
+pub fn format_group_breakdown(comparisons: &[ComparisonResult]) -> Vec<String> {
+    let mut groups: Vec<&str> = comparisons
+        .iter()
+        .filter_map(|c| c.group.as_deref())
+        .collect();
+    groups.sort_unstable();
+    groups.dedup();
+
+    groups
+        .into_iter()
+        .map(|group| {
+            let members: Vec<&ComparisonResult> = comparisons
+                .iter()
+                .filter(|c| c.group.as_deref() == Some(group))
+                .collect();
+            let regressions = members.iter().filter(|c| c.is_regression).count();
+            let improvements = members
+                .iter()
+                .filter(|c| {
+                    !c.is_regression
+                        && c.comparison
+                            .as_ref()
+                            .map(|comp| comp.percentage_change < -5.0)
+                            .unwrap_or(false)
+                })
+                .count();
+            let new_benchmarks = members.iter().filter(|c| c.is_new()).count();
+            let excluded = members.iter().filter(|c| c.history_excluded()).count();
+            let stable = members.len() - regressions - improvements - new_benchmarks - excluded;
+
+            format!(
+                "  {}: {} total, {} stable, {} improved, {} regressed{}{}",
+                group.bold(),
+                members.len(),
+                stable,
+                improvements,
+                if regressions > 0 {
+                    regressions.to_string().red().bold().to_string()
+                } else {
+                    regressions.to_string()
+                },
+                if new_benchmarks > 0 {
+                    format!(", {} new", new_benchmarks)
+                } else {
+                    String::new()
+                },
+                if excluded > 0 {
+                    format!(", {} provisional", excluded)
+                } else {
+                    String::new()
+                }
+            )
+        })
+        .collect()
+}
+
+/// Orientation for a benchmark new on this machine, from another machine's history
+/// ("new on this machine; ci-runner history shows ~4.20μs, this run +3.1%")
+pub fn format_reference_note(reference: &Comparison) -> Option<String> {
+    let machine = reference.reference_machine.as_ref()?;
+    Some(format!(
+        "new on this machine; {} history shows ~{}, this run {:+.1}%",
+        machine,
+        format_duration_human_readable(reference.baseline_mean),
+        reference.percentage_change
+    ))
+}
+
+/// Print "NEW" message for first baseline, oriented by another machine's
+/// history when there is one
+pub fn print_new_baseline_line(benchmark_name: &str, reference: Option<&Comparison>) {
+    match reference.and_then(format_reference_note) {
+        Some(note) => println!(
+            "        {} {} ({})",
+            "NEW".blue().bold(),
+            benchmark_name.bright_white(),
+            note
+        ),
+        None => println!(
+            "        {} {} (establishing baseline)",
+            "NEW".blue().bold(),
+            benchmark_name.bright_white()
+        ),
+    }
+}
+
+/// Print "PROVISIONAL" for a benchmark whose every stored run was excluded
+/// from the window, so it isn't mistaken for a new one
+pub fn print_excluded_history_line(benchmark_name: &str, note: &str) {
     println!(
-        "        {} {} (establishing baseline)",
-        "NEW".blue().bold(),
-        benchmark_name.bright_white()
+        "        {} {} ({}; this run starts a new window)",
+        "PROVISIONAL".yellow().bold(),
+        benchmark_name.bright_white(),
+        note
     );
 }
 
 /// Print summary footer for streaming mode
 pub fn print_streaming_summary(
     comparisons: &[ComparisonResult],
+    skipped: &[&BenchResult],
     config: &crate::config::ComparisonConfig,
 ) {
     let regressions = comparisons.iter().filter(|c| c.is_regression).count();
     let improvements = comparisons
         .iter()
         .filter(|c| {
-            c.comparison
-                .as_ref()
-                .map(|comp| comp.percentage_change < -5.0)
-                .unwrap_or(false)
+            !c.is_regression
+                && c.comparison
+                    .as_ref()
+                    .map(|comp| comp.percentage_change < -5.0)
+                    .unwrap_or(false)
         })
         .count();
-    let new_benchmarks = comparisons
-        .iter()
-        .filter(|c| c.comparison.is_none())
-        .count();
-    let stable = comparisons.len() - regressions - improvements - new_benchmarks;
+    let new_benchmarks = comparisons.iter().filter(|c| c.is_new()).count();
+    let excluded = comparisons.iter().filter(|c| c.history_excluded()).count();
+    let stable = comparisons.len() - regressions - improvements - new_benchmarks - excluded;
 
     println!("{}", "─".repeat(80).dimmed());
     println!(
-        "{} {} total: {} {}, {} {}, {} {}{}",
+        "{} {} total: {} {}, {} {}, {} {}{}{}{}",
         "Summary:".cyan().bold(),
-        comparisons.len(),
+        comparisons.len() + skipped.len(),
         stable,
         "stable".dimmed(),
         improvements,
@@ -304,17 +1040,56 @@ pub fn print_streaming_summary(
             format!(", {} {}", new_benchmarks, "new".blue())
         } else {
             String::new()
+        },
+        if excluded > 0 {
+            format!(", {} {}", excluded, "provisional".yellow())
+        } else {
+            String::new()
+        },
+        if !skipped.is_empty() {
+            format!(", {} {}", skipped.len(), "skipped".yellow())
+        } else {
+            String::new()
         }
     );
 
+    for line in format_group_breakdown(comparisons) {
+        println!("{}", line);
+    }
+    for line in format_skip_reasons(skipped.iter().copied()) {
+        println!("{}", line);
+    }
+
     if regressions > 0 {
         println!(
-            "{} {} regression(s) detected (threshold: {}%)",
+            "{} {} regression(s) detected (threshold: {}%{})",
             "Warning:".yellow().bold(),
             regressions,
-            config.threshold
+            config.threshold,
+            config
+                .p99_threshold
+                .map_or(String::new(), |t| format!(", p99 threshold: {}%", t))
         );
     }
+
+    let alloc_regressions = comparisons
+        .iter()
+        .filter(|c| c.is_alloc_regression())
+        .count();
+    if alloc_regressions > 0 {
+        println!(
+            "{} {} allocation regression(s) detected (threshold: {}%)",
+            "Warning:".yellow().bold(),
+            alloc_regressions,
+            config.alloc_threshold.unwrap_or_default()
+        );
+    }
+    if let Some(line) = format_suspect_warning(comparisons) {
+        println!("{}", line);
+    }
+    for line in format_drift_warnings(comparisons, config) {
+        println!("{}", line);
+    }
 }
 
 pub fn print_summary(results: &[BenchResult], comparisons: Option<&[ComparisonResult]>) {
@@ -344,11 +1119,7 @@ pub fn print_summary(results: &[BenchResult], comparisons: Option<&[ComparisonRe
                     );
                 } else {
                     // First run - no baseline to compare against
-                    println!(
-                        "        {} {} (establishing baseline)",
-                        "NEW".blue().bold(),
-                        result.name.bright_white()
-                    );
+                    print_new_baseline_line(&result.name, comparisons[i].reference.as_ref());
                 }
             }
         }
@@ -358,24 +1129,40 @@ pub fn print_summary(results: &[BenchResult], comparisons: Option<&[ComparisonRe
 
     // Print summary footer
     if let Some(comparisons) = comparisons {
+        // Too noisy to judge, so counted apart from every verdict
+        let quality_failures = comparisons
+            .iter()
+            .filter(|c| c.quality_failure.is_some())
+            .count();
         let regressions = comparisons.iter().filter(|c| c.is_regression).count();
         let improvements = comparisons
             .iter()
             .filter(|c| {
-                c.comparison
-                    .as_ref()
-                    .map(|comp| comp.percentage_change < -5.0)
-                    .unwrap_or(false)
+                c.quality_failure.is_none()
+                    && !c.is_regression
+                    && c.comparison
+                        .as_ref()
+                        .map(|comp| comp.percentage_change < -5.0)
+                        .unwrap_or(false)
             })
             .count();
         let new_benchmarks = comparisons
             .iter()
-            .filter(|c| c.comparison.is_none())
+            .filter(|c| c.quality_failure.is_none() && c.is_new())
             .count();
-        let stable = comparisons.len() - regressions - improvements - new_benchmarks;
+        let excluded = comparisons
+            .iter()
+            .filter(|c| c.quality_failure.is_none() && c.history_excluded())
+            .count();
+        let stable = comparisons.len()
+            - regressions
+            - improvements
+            - new_benchmarks
+            - excluded
+            - quality_failures;
 
         println!(
-            "{} {} total: {} {}, {} {}, {} {}{}",
+            "{} {} total: {} {}, {} {}, {} {}{}{}{}",
             "Summary:".cyan().bold(),
             results.len(),
             stable,
@@ -392,6 +1179,16 @@ pub fn print_summary(results: &[BenchResult], comparisons: Option<&[ComparisonRe
                 format!(", {} {}", new_benchmarks, "new".blue())
             } else {
                 String::new()
+            },
+            if excluded > 0 {
+                format!(", {} {}", excluded, "provisional".yellow())
+            } else {
+                String::new()
+            },
+            if quality_failures > 0 {
+                format!(", {} {}", quality_failures, "quality-failed".red())
+            } else {
+                String::new()
             }
         );
 
@@ -402,6 +1199,9 @@ pub fn print_summary(results: &[BenchResult], comparisons: Option<&[ComparisonRe
                 regressions
             );
         }
+        if let Some(line) = format_suspect_warning(comparisons) {
+            println!("{}", line);
+        }
     } else {
         println!(
             "{} running {} benchmarks",
@@ -424,9 +1224,11 @@ mod tests {
             module: "test_module".to_string(),
             samples: 10,
             percentiles: Percentiles {
-                p50: Duration::from_millis(5),
-                p90: Duration::from_millis(10),
-                p99: Duration::from_millis(15),
+                values: vec![
+                    (50.0, Duration::from_millis(5)),
+                    (90.0, Duration::from_millis(10)),
+                    (99.0, Duration::from_millis(15)),
+                ],
                 mean: Duration::from_millis(8),
             },
             all_timings: vec![Duration::from_millis(5); 10],
@@ -480,6 +1282,98 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_time_format_configurations() {
+        let format = |unit, precision, thousands_separator, nanos| {
+            TimeFormat {
+                unit,
+                precision,
+                thousands_separator,
+            }
+            .format(Duration::from_nanos(nanos))
+        };
+
+        // Automatic units, three decimals
+        assert_eq!(format(TimeUnit::Auto, 3, false, 999), "999ns");
+        assert_eq!(format(TimeUnit::Auto, 3, false, 1_500), "1.500μs");
+        assert_eq!(format(TimeUnit::Auto, 3, false, 2_345_678), "2.346ms");
+
+        // One unit for every value, so a table's column doesn't switch
+        assert_eq!(format(TimeUnit::Us, 2, false, 800), "0.80μs");
+        assert_eq!(format(TimeUnit::Us, 2, false, 1_234_567), "1234.57μs");
+        assert_eq!(format(TimeUnit::Ms, 1, false, 50_000), "0.1ms");
+        assert_eq!(format(TimeUnit::S, 0, false, 61_000_000_000), "61s");
+
+        // Separators group the integer part only, whatever the locale
+        assert_eq!(format(TimeUnit::Ns, 2, true, 12_345_678), "12,345,678ns");
+        assert_eq!(format(TimeUnit::Us, 2, true, 1_234_567), "1,234.57μs");
+        assert_eq!(format(TimeUnit::Us, 2, true, 999_000), "999.00μs");
+        assert_eq!(format(TimeUnit::Auto, 0, true, 1_000), "1μs");
+
+        assert_eq!(
+            TimeFormat::default().parts(1_500),
+            ("1.50".to_string(), "μs")
+        );
+    }
+
+    #[test]
+    fn test_result_columns_show_trimmed_mean() {
+        let mut result = create_test_result();
+        let layout = |result: &BenchResult, columns: &[Column]| {
+            plain(&format_result_columns_with(
+                result,
+                columns,
+                &TimeFormat::DEFAULT,
+            ))
+        };
+
+        // Nothing was removed: no trimmed mean
+        result.filtered = Some(crate::FilteredStatistics {
+            statistics: crate::calculate_statistics_at(&[7_500_000], &[]),
+            outliers_removed: 0,
+        });
+        assert_eq!(layout(&result, &[Column::Mean]), "mean: 8.00ms");
+
+        result.filtered.as_mut().unwrap().outliers_removed = 3;
+        assert_eq!(
+            layout(&result, &[Column::Mean, Column::P99]),
+            "mean: 8.00ms, trimmed: 7.50ms, 3 outliers, p99: 15.00ms"
+        );
+        assert!(layout(&result, &[Column::Mean, Column::Cv])
+            .ends_with("), trimmed: 7.50ms, 3 outliers"));
+        assert_eq!(layout(&result, &[Column::P99]), "p99: 15.00ms");
+    }
+
+    #[test]
+    fn test_result_columns_in_fixed_unit() {
+        let result = create_test_result();
+        let columns = [Column::Mean, Column::P50, Column::P99];
+        let layout = |time_format: TimeFormat| {
+            plain(&format_result_columns_with(&result, &columns, &time_format))
+        };
+
+        assert_eq!(
+            layout(TimeFormat {
+                unit: TimeUnit::Us,
+                precision: 1,
+                thousands_separator: true,
+            }),
+            "mean: 8,000.0μs, p50: 5,000.0μs, p99: 15,000.0μs"
+        );
+        assert_eq!(
+            layout(TimeFormat {
+                unit: TimeUnit::Ms,
+                precision: 3,
+                thousands_separator: false,
+            }),
+            "mean: 8.000ms, p50: 5.000ms, p99: 15.000ms"
+        );
+        assert_eq!(
+            layout(TimeFormat::DEFAULT),
+            "mean: 8.00ms, p50: 5.00ms, p99: 15.00ms"
+        );
+    }
+
     #[test]
     fn test_format_benchmark_result() {
         let result = create_test_result();
@@ -491,4 +1385,318 @@ mod tests {
         assert!(formatted.contains("p90:"));
         assert!(formatted.contains("p99:"));
     }
+
+    /// Strip ANSI color codes so layouts can be compared as plain text
+    fn plain(s: &str) -> String {
+        let mut out = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|&c| c == 'm');
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_result_columns_layouts() {
+        let result = create_test_result();
+        let layout = |columns: &[Column]| plain(&format_benchmark_result_columns(&result, columns));
+
+        assert_eq!(
+            layout(Column::TERMINAL_DEFAULT),
+            "BENCH test_module::test_bench mean: 8.00ms (CV: 0.0%), p50: 5.00ms, p90: 10.00ms, p99: 15.00ms"
+        );
+        assert_eq!(
+            layout(&[Column::Mean, Column::P99, Column::Cv, Column::Delta]),
+            "BENCH test_module::test_bench mean: 8.00ms, p99: 15.00ms (CV: 0.0%)"
+        );
+        assert_eq!(
+            layout(&[Column::Min, Column::Max, Column::Samples]),
+            "BENCH test_module::test_bench min: 5.00ms, max: 5.00ms, samples: 10"
+        );
+        assert_eq!(layout(&[Column::Z]), "BENCH test_module::test_bench");
+    }
+
+    #[test]
+    fn test_comparison_columns_layouts() {
+        let comparison = Comparison {
+            current_mean: Duration::from_millis(12),
+            baseline_mean: Duration::from_millis(10),
+            percentage_change: 20.0,
+            baseline_count: 5,
+            z_score: Some(4.2),
+            confidence_interval: None,
+            change_probability: Some(0.93),
+            throughput: None,
+            mixed_build_profiles: false,
+            allocs: None,
+            min_detectable_effect: Some(1.5),
+            effect_size: Some(2.4),
+            mann_whitney_p: None,
+            rank_biserial: None,
+            t_statistic: None,
+            p_value: None,
+            provisional: false,
+            threshold: 5.0,
+            global_threshold: None,
+            instructions: None,
+            suspect: None,
+            quality_failure: None,
+            reference_machine: None,
+            normalized_machines: None,
+            env_changes: Vec::new(),
+            commit_change: None,
+            toolchain_changes: Vec::new(),
+            accepted_since: None,
+            drift: None,
+            p99: None,
+        };
+        let layout =
+            |columns: &[Column]| plain(&format_comparison_columns(&comparison, true, columns));
+
+        assert_eq!(
+            layout(Column::TERMINAL_DEFAULT),
+            "        REGRESS ↗ 20.0% (mean: 10.00ms -> 12.00ms (n=5))\n        z=4.20, cp=93%, mde=1.5%, d=2.40"
+        );
+        assert_eq!(
+            layout(&[Column::Mean, Column::P99, Column::Cv, Column::Delta]),
+            "        REGRESS ↗ 20.0% (mean: 10.00ms -> 12.00ms (n=5))"
+        );
+        assert_eq!(
+            layout(&[Column::Cp, Column::Delta]),
+            "        REGRESS ↗ 20.0%\n        cp=93%"
+        );
+
+        let overridden = Comparison {
+            threshold: 15.0,
+            global_threshold: Some(5.0),
+            ..comparison.clone()
+        };
+        assert_eq!(
+            plain(&format_comparison_columns(
+                &overridden,
+                true,
+                &[Column::Mean, Column::Delta]
+            )),
+            "        REGRESS ↗ 20.0% (mean: 10.00ms -> 12.00ms (n=5)) [threshold 15%]"
+        );
+
+        let pooled = Comparison {
+            normalized_machines: Some(3),
+            ..comparison.clone()
+        };
+        assert_eq!(
+            plain(&format_comparison_columns(
+                &pooled,
+                true,
+                &[Column::Mean, Column::Delta]
+            )),
+            "        REGRESS ↗ 20.0% (mean: 10.00ms -> 12.00ms (n=5)) [normalized across 3 machines]"
+        );
+
+        let tail = |percentage_change, is_regression| crate::TailComparison {
+            current_p99: Duration::from_millis(29),
+            baseline_p99: Duration::from_millis(20),
+            percentage_change,
+            z_score: 8.0,
+            change_probability: 0.99,
+            threshold: 20.0,
+            is_regression,
+        };
+        let tail_only = Comparison {
+            current_mean: Duration::from_micros(10_100),
+            percentage_change: 1.0,
+            p99: Some(tail(45.0, true)),
+            ..comparison.clone()
+        };
+        assert_eq!(
+            plain(&format_comparison_columns(
+                &tail_only,
+                true,
+                &[Column::Mean, Column::Delta]
+            )),
+            "        REGRESS p99 ↗ 45.0% (p99: 20.00ms -> 29.00ms, mean stable)"
+        );
+
+        let both = Comparison {
+            p99: Some(tail(45.0, true)),
+            ..comparison.clone()
+        };
+        assert_eq!(
+            plain(&format_comparison_columns(&both, true, &[Column::Mean, Column::Delta])),
+            "        REGRESS ↗ 20.0% (mean: 10.00ms -> 12.00ms (n=5))\n        REGRESS p99 ↗ 45.0% (p99: 20.00ms -> 29.00ms)"
+        );
+
+        let stable_tail = Comparison {
+            percentage_change: 1.0,
+            p99: Some(tail(3.0, false)),
+            ..comparison.clone()
+        };
+        assert_eq!(
+            plain(&format_comparison_columns(
+                &stable_tail,
+                false,
+                &[Column::Delta]
+            )),
+            "        STABLE ↗ 1.0%\n        p99 +3.0%"
+        );
+
+        let welch = Comparison {
+            t_statistic: Some(6.512),
+            p_value: Some(0.00003),
+            ..comparison.clone()
+        };
+        assert_eq!(
+            plain(&format_comparison_columns(
+                &welch,
+                true,
+                &[Column::Z, Column::Delta]
+            )),
+            "        REGRESS ↗ 20.0%\n        z=4.20, t=6.51, p=0.0000"
+        );
+    }
+
+    #[test]
+    fn test_format_wall_vs_cpu() {
+        let mut result = create_test_result();
+
+        // Not recorded
+        assert_eq!(format_wall_vs_cpu(&result), None);
+
+        // CPU-bound: close to wall time, not shown
+        result.cpu_time_percentiles = Some(Percentiles {
+            mean: Duration::from_micros(7_900),
+            ..result.percentiles.clone()
+        });
+        assert_eq!(format_wall_vs_cpu(&result), None);
+
+        // Sleeping: diverges, both shown
+        result.cpu_time_percentiles = Some(Percentiles {
+            mean: Duration::from_micros(900),
+            ..result.percentiles.clone()
+        });
+        let formatted = format_wall_vs_cpu(&result).unwrap();
+        assert!(formatted.starts_with("wall "));
+        assert!(formatted.contains(", cpu "));
+    }
+
+    #[test]
+    fn test_format_periodicity_line() {
+        let mut result = create_test_result();
+        result.all_timings = (0..1000)
+            .map(|i| Duration::from_micros(if i % 50 == 0 { 40 } else { 4 + i % 3 }))
+            .collect();
+        assert!(format_periodicity_line(&result)
+            .unwrap()
+            .starts_with("periodic spikes every ~50 samples"));
+
+        result.all_timings = vec![Duration::from_micros(4); 1000];
+        assert_eq!(format_periodicity_line(&result), None);
+    }
+
+    #[test]
+    fn test_format_skipped_result() {
+        let result = BenchResult::skipped(
+            "test_bench",
+            "test_module",
+            "skip_if condition was true".to_string(),
+        );
+        let formatted = format_benchmark_result(&result);
+
+        assert!(formatted.contains("SKIPPED"));
+        assert!(formatted.contains("test_module::test_bench"));
+        assert!(formatted.contains("skip_if condition was true"));
+        assert!(!formatted.contains("mean:"));
+    }
+
+    #[test]
+    fn test_format_group_breakdown() {
+        let comparison = |name: &str, group: Option<&str>, is_regression: bool| ComparisonResult {
+            benchmark_name: name.to_string(),
+            group: group.map(str::to_string),
+            comparison: Some(Comparison {
+                current_mean: Duration::from_millis(if is_regression { 12 } else { 10 }),
+                baseline_mean: Duration::from_millis(10),
+                percentage_change: if is_regression { 20.0 } else { 0.0 },
+                baseline_count: 5,
+                z_score: None,
+                confidence_interval: None,
+                change_probability: None,
+                throughput: None,
+                mixed_build_profiles: false,
+                allocs: None,
+                min_detectable_effect: None,
+                effect_size: None,
+                mann_whitney_p: None,
+                rank_biserial: None,
+                t_statistic: None,
+                p_value: None,
+                provisional: false,
+                threshold: 5.0,
+                global_threshold: None,
+                instructions: None,
+                suspect: None,
+                quality_failure: None,
+                reference_machine: None,
+                normalized_machines: None,
+                env_changes: Vec::new(),
+                commit_change: None,
+                toolchain_changes: Vec::new(),
+                accepted_since: None,
+                drift: None,
+                p99: None,
+            }),
+            is_regression,
+            reference: None,
+            quality_failure: None,
+            is_improvement: false,
+            window: crate::baseline::WindowOutcome::Populated,
+        };
+
+        // No groups: no breakdown
+        assert!(format_group_breakdown(&[comparison("a", None, false)]).is_empty());
+
+        let lines = format_group_breakdown(&[
+            comparison("a", Some("serialization"), false),
+            comparison("b", Some("collision"), true),
+            comparison("c", Some("serialization"), false),
+            comparison("d", None, false),
+        ]);
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("collision"));
+        assert!(lines[0].contains("1 total"));
+        assert!(lines[0].contains("0 stable"));
+        assert!(lines[1].contains("serialization"));
+        assert!(lines[1].contains("2 total"));
+    }
+
+    #[test]
+    fn test_name_truncation_and_column_width() {
+        let name = "serialization::parse_json_document/1000";
+        assert_eq!(truncate_middle(name, 40), name);
+        assert_eq!(truncate_middle(name, 39), name);
+        assert_eq!(truncate_middle(name, 20), "serializat…ment/1000");
+        // The distinguishing suffix outlasts the head
+        assert_eq!(truncate_middle(name, 8), "se…/1000");
+        assert_eq!(truncate_middle(name, 4), "…000");
+        assert_eq!(truncate_middle(name, 0), "");
+        assert_eq!(
+            truncate_middle("module::very_long_function_name", 16),
+            "…g_function_name"
+        );
+        assert_eq!(truncate_middle("abcdefghij", 5), "ab…ij");
+        assert_eq!(truncate_middle("päckchen_größe", 6), "päc…ße");
+
+        let names = ["short", name];
+        assert_eq!(name_column_width(names, 40, Some(80)), 39);
+        assert_eq!(name_column_width(names, 40, Some(60)), 20);
+        assert_eq!(name_column_width(names, 40, Some(30)), MIN_NAME_WIDTH);
+        assert_eq!(name_column_width(["short"], 40, Some(30)), 5);
+        assert_eq!(name_column_width(names, 40, None), 39);
+        assert_eq!(name_column_width([], 40, Some(80)), 0);
+    }
 }
@@ -0,0 +1,261 @@
+//! Hardware performance counters per sample.
+//!
+//! With the `perf-counters` feature on Linux, the measurement loop opens a
+//! `perf_event_open` group counting retired instructions, CPU cycles and
+//! last-level cache misses of the benchmark thread (user space only), and reads
+//! it around each timed call. Instruction counts don't depend on CPU frequency,
+//! so they stay stable where nanoseconds drift.
+//!
+//! Without the feature, on other platforms, or when the kernel refuses the
+//! counters (e.g. `perf_event_paranoid`, containers without PMU access), no
+//! counters are opened and `perf_samples` stays empty.
+
+use serde::{Deserialize, Serialize};
+
+/// Counter deltas over one timed call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerfSample {
+    pub instructions: u64,
+    pub cycles: u64,
+    /// Last-level cache misses
+    pub cache_misses: u64,
+}
+
+/// Means of a run's [`PerfSample`]s
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerfSummary {
+    pub mean_instructions: f64,
+    pub mean_cycles: f64,
+    pub mean_cache_misses: f64,
+}
+
+impl PerfSummary {
+    /// Average the samples; None without samples
+    pub fn from_samples(samples: &[PerfSample]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let n = samples.len() as f64;
+        let mean = |f: fn(&PerfSample) -> u64| samples.iter().map(f).sum::<u64>() as f64 / n;
+        Some(Self {
+            mean_instructions: mean(|s| s.instructions),
+            mean_cycles: mean(|s| s.cycles),
+            mean_cache_misses: mean(|s| s.cache_misses),
+        })
+    }
+
+    /// Instructions per cycle
+    pub fn ipc(&self) -> Option<f64> {
+        (self.mean_cycles > 0.0).then(|| self.mean_instructions / self.mean_cycles)
+    }
+
+    /// Last-level cache misses per thousand instructions
+    pub fn misses_per_kilo_instruction(&self) -> Option<f64> {
+        (self.mean_instructions > 0.0)
+            .then(|| self.mean_cache_misses / self.mean_instructions * 1000.0)
+    }
+}
+
+/// Format a counter value with a metric suffix ("1.23M")
+pub fn format_count(value: f64) -> String {
+    if value >= 1e9 {
+        format!("{:.2}G", value / 1e9)
+    } else if value >= 1e6 {
+        format!("{:.2}M", value / 1e6)
+    } else if value >= 1e3 {
+        format!("{:.2}K", value / 1e3)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+/// Open counter group of the calling thread
+pub(crate) struct PerfCounters {
+    #[cfg(all(feature = "perf-counters", target_os = "linux"))]
+    group: linux::CounterGroup,
+}
+
+impl PerfCounters {
+    /// Open counters for the calling thread; None if unavailable
+    pub fn open() -> Option<Self> {
+        #[cfg(all(feature = "perf-counters", target_os = "linux"))]
+        {
+            linux::CounterGroup::open().map(|group| Self { group })
+        }
+        #[cfg(not(all(feature = "perf-counters", target_os = "linux")))]
+        {
+            None
+        }
+    }
+
+    /// Current counter values
+    #[inline(always)]
+    pub fn read(&self) -> Option<PerfSample> {
+        #[cfg(all(feature = "perf-counters", target_os = "linux"))]
+        {
+            self.group.read()
+        }
+        #[cfg(not(all(feature = "perf-counters", target_os = "linux")))]
+        {
+            None
+        }
+    }
+}
+
+impl PerfSample {
+    /// Counts between `start` and this reading
+    pub(crate) fn since(self, start: PerfSample) -> PerfSample {
+        PerfSample {
+            instructions: self.instructions.wrapping_sub(start.instructions),
+            cycles: self.cycles.wrapping_sub(start.cycles),
+            cache_misses: self.cache_misses.wrapping_sub(start.cache_misses),
+        }
+    }
+}
+
+#[cfg(all(feature = "perf-counters", target_os = "linux"))]
+mod linux {
+    use super::PerfSample;
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_FORMAT_GROUP: u64 = 1 << 3;
+    const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+    const EXCLUDE_KERNEL: u64 = 1 << 5;
+    const EXCLUDE_HV: u64 = 1 << 6;
+
+    /// `struct perf_event_attr` up to `config2` (`PERF_ATTR_SIZE_VER1`)
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        type_: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+        config2: u64,
+    }
+
+    /// Cycles (group leader), instructions and cache misses, read in one syscall
+    pub struct CounterGroup {
+        fds: [libc::c_int; 3],
+    }
+
+    fn open_counter(config: u64, group_fd: libc::c_int) -> Option<libc::c_int> {
+        let attr = PerfEventAttr {
+            type_: PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            read_format: PERF_FORMAT_GROUP,
+            // Counting user space only works without elevated privileges
+            flags: EXCLUDE_KERNEL | EXCLUDE_HV,
+            ..Default::default()
+        };
+
+        // SAFETY: `attr` is a valid perf_event_attr of the size it declares;
+        // pid 0 / cpu -1 counts the calling thread on any CPU
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_perf_event_open,
+                &attr as *const PerfEventAttr,
+                0 as libc::pid_t,
+                -1 as libc::c_int,
+                group_fd,
+                PERF_FLAG_FD_CLOEXEC,
+            )
+        };
+        (fd >= 0).then_some(fd as libc::c_int)
+    }
+
+    impl CounterGroup {
+        pub fn open() -> Option<Self> {
+            let leader = open_counter(PERF_COUNT_HW_CPU_CYCLES, -1)?;
+            let mut group = CounterGroup {
+                fds: [leader, -1, -1],
+            };
+            group.fds[1] = open_counter(PERF_COUNT_HW_INSTRUCTIONS, leader)?;
+            group.fds[2] = open_counter(PERF_COUNT_HW_CACHE_MISSES, leader)?;
+            // Counters that never run (e.g. no PMU in a VM) read as zero
+            group
+                .read()
+                .filter(|s| s.cycles > 0 || s.instructions > 0)?;
+            Some(group)
+        }
+
+        #[inline(always)]
+        pub fn read(&self) -> Option<PerfSample> {
+            // PERF_FORMAT_GROUP: the counter count, then each value in open order
+            let mut buf = [0u64; 4];
+            // SAFETY: `buf` is valid for writes of its full size
+            let n = unsafe {
+                libc::read(
+                    self.fds[0],
+                    buf.as_mut_ptr() as *mut libc::c_void,
+                    std::mem::size_of_val(&buf),
+                )
+            };
+            (n == std::mem::size_of_val(&buf) as isize && buf[0] == 3).then_some(PerfSample {
+                cycles: buf[1],
+                instructions: buf[2],
+                cache_misses: buf[3],
+            })
+        }
+    }
+
+    impl Drop for CounterGroup {
+        fn drop(&mut self) {
+            for &fd in self.fds.iter().filter(|&&fd| fd >= 0) {
+                // SAFETY: `fd` was returned by perf_event_open and is closed once
+                unsafe { libc::close(fd) };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perf_summary_ratios() {
+        let samples = [
+            PerfSample {
+                instructions: 3000,
+                cycles: 1000,
+                cache_misses: 3,
+            },
+            PerfSample {
+                instructions: 1000,
+                cycles: 1000,
+                cache_misses: 1,
+            },
+        ];
+
+        let summary = PerfSummary::from_samples(&samples).unwrap();
+        assert_eq!(summary.mean_instructions, 2000.0);
+        assert_eq!(summary.ipc(), Some(2.0));
+        assert_eq!(summary.misses_per_kilo_instruction(), Some(1.0));
+        assert!(PerfSummary::from_samples(&[]).is_none());
+        assert_eq!(format_count(1_234_000.0), "1.23M");
+        assert_eq!(format_count(512.0), "512");
+    }
+
+    #[test]
+    fn test_counters_degrade_gracefully() {
+        // Either the counters work and count the loop, or they're absent entirely
+        if let Some(counters) = PerfCounters::open() {
+            let start = counters.read().unwrap();
+            std::hint::black_box((0..10_000u64).sum::<u64>());
+            let delta = counters.read().unwrap().since(start);
+            assert!(delta.instructions > 0);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Checks a benchmark declares on the machine before it runs
+//!
+//! A benchmark whose precondition isn't met is skipped with the reason instead
+//! of measuring a machine it wasn't written for (and saving that as a baseline).
+
+use crate::config::{format_size, BenchmarkConfig};
+use crate::BenchResult;
+
+/// Memory available to new allocations without swapping, in bytes
+///
+/// `MemAvailable` of `/proc/meminfo` on Linux, free plus purgeable pages on
+/// macOS; None where it can't be read.
+pub fn available_memory_bytes() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+        parse_mem_available(&meminfo)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        fn sysctl_u64(name: &[u8]) -> Option<u64> {
+            // Page counts are 32-bit, the page size is 64-bit; both fit a
+            // zeroed u64 on the little-endian targets macOS runs on
+            let mut value: u64 = 0;
+            let mut len = std::mem::size_of::<u64>();
+            // SAFETY: `value` is writable for `len` bytes and `name` is NUL-terminated
+            let ret = unsafe {
+                libc::sysctlbyname(
+                    name.as_ptr() as *const libc::c_char,
+                    &mut value as *mut u64 as *mut libc::c_void,
+                    &mut len,
+                    std::ptr::null_mut(),
+                    0,
+                )
+            };
+            (ret == 0).then_some(value)
+        }
+        let page_size = sysctl_u64(b"hw.pagesize\0")?;
+        let free = sysctl_u64(b"vm.page_free_count\0")?;
+        let purgeable = sysctl_u64(b"vm.page_purgeable_count\0").unwrap_or(0);
+        Some((free + purgeable) * page_size)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// `MemAvailable` of a `/proc/meminfo` listing, in bytes
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_mem_available(meminfo: &str) -> Option<u64> {
+    let line = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))?;
+    let kib: u64 = line.trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Skipped result for a benchmark declaring `min_free_memory = min_bytes`
+/// when less than that is available, None when it may run
+///
+/// Memory that can't be read doesn't block the run, and
+/// `ignore_preconditions` runs it regardless.
+pub fn check_free_memory(
+    name: &str,
+    module: &str,
+    min_bytes: u64,
+    config: &BenchmarkConfig,
+) -> Option<BenchResult> {
+    if config.measurement.ignore_preconditions {
+        return None;
+    }
+    let available = available_memory_bytes()?;
+    (available < min_bytes).then(|| {
+        BenchResult::skipped(
+            name,
+            module,
+            format!(
+                "needs {} free, {} available",
+                format_size(min_bytes),
+                format_size(available)
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mem_available() {
+        let meminfo = "MemTotal:       32594660 kB\nMemFree:         1203444 kB\nMemAvailable:    3250524 kB\nBuffers:          402312 kB\n";
+        assert_eq!(parse_mem_available(meminfo), Some(3_250_524 * 1024));
+        assert_eq!(parse_mem_available("MemTotal: 1 kB\n"), None);
+    }
+
+    #[test]
+    fn test_check_free_memory_skips_with_reason() {
+        let mut config = BenchmarkConfig::default();
+        let Some(available) = available_memory_bytes() else {
+            return;
+        };
+
+        let skipped = check_free_memory("bench_big", "tests", available * 4, &config).unwrap();
+        assert!(skipped.skipped);
+        assert!(skipped
+            .skip_reason
+            .unwrap()
+            .starts_with(&format!("needs {} free, ", format_size(available * 4))));
+
+        assert!(check_free_memory("bench_small", "tests", 1, &config).is_none());
+
+        config.measurement.ignore_preconditions = true;
+        assert!(check_free_memory("bench_big", "tests", available * 4, &config).is_none());
+    }
+}
@@ -1,12 +1,21 @@
 //! Progress reporting for benchmark execution.
 //!
 //! Emits JSON progress messages to stderr during warmup and sample collection.
-//! The CLI tool parses these to display progress bars.
+//! The CLI tool parses these to display progress bars. Both sides use the types
+//! in this module, so the wire format can't drift between them.
+//!
+//! Each line is an envelope: `{"version":1,"progress":{"bench":...,"phase":...}}`.
+//! Runners built before the version field existed omit it; their messages are
+//! identical to version 1.
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
-use serde::Serialize;
+/// Version of the progress wire format, bumped on incompatible changes.
+pub const PROGRESS_PROTOCOL_VERSION: u32 = 1;
 
 /// Progress phase during benchmark execution.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "phase")]
 pub enum ProgressPhase {
     /// Warmup phase - running iterations to stabilize CPU/cache state.
@@ -25,21 +34,67 @@ pub enum ProgressPhase {
         /// Total number of samples to collect.
         total: u32,
     },
+    /// The watchdog flagged a pathologically slow sample.
+    #[serde(rename = "pathological_sample")]
+    PathologicalSample {
+        /// Index of the sample among the recorded samples.
+        index: u32,
+        /// Duration of the sample in nanoseconds.
+        duration_ns: u64,
+        /// Running median in nanoseconds, if one existed yet.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        median_ns: Option<u64>,
+    },
     /// Benchmark complete.
     #[serde(rename = "complete")]
     Complete,
 }
 
 /// Progress message emitted to stderr during benchmark execution.
-#[derive(Debug, Clone, Serialize)]
+///
+/// The runner borrows the benchmark name; parsed messages own it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProgressMessage<'a> {
     /// Benchmark name.
-    pub bench: &'a str,
+    pub bench: Cow<'a, str>,
     /// Current phase and its data.
     #[serde(flatten)]
     pub phase: ProgressPhase,
 }
 
+/// A progress line as written to stderr.
+#[derive(Debug, Serialize)]
+struct ProgressEnvelope<'a> {
+    version: u32,
+    progress: &'a ProgressMessage<'a>,
+}
+
+/// Result of parsing a stderr line that carries a progress envelope.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressLine {
+    Message(ProgressMessage<'static>),
+    /// The runner speaks a newer protocol than this side understands.
+    UnsupportedVersion(u32),
+}
+
+/// Parse a stderr line; None if it isn't a progress envelope.
+pub fn parse_progress_line(line: &str) -> Option<ProgressLine> {
+    #[derive(Deserialize)]
+    struct RawEnvelope {
+        #[serde(default)]
+        version: u32,
+        progress: serde_json::Value,
+    }
+
+    let raw: RawEnvelope = serde_json::from_str(line).ok()?;
+    if raw.version > PROGRESS_PROTOCOL_VERSION {
+        return Some(ProgressLine::UnsupportedVersion(raw.version));
+    }
+    serde_json::from_value(raw.progress)
+        .ok()
+        .map(ProgressLine::Message)
+}
+
 /// Check if progress output is enabled (not suppressed via env var).
 fn progress_enabled() -> bool {
     std::env::var("SIMPLEBENCH_QUIET").is_err()
@@ -54,8 +109,92 @@ pub fn emit_progress(msg: &ProgressMessage) {
         return;
     }
 
-    // Wrap in {"progress": ...} envelope
-    if let Ok(json) = serde_json::to_string(&serde_json::json!({"progress": msg})) {
+    if let Ok(json) = serde_json::to_string(&ProgressEnvelope {
+        version: PROGRESS_PROTOCOL_VERSION,
+        progress: msg,
+    }) {
         eprintln!("{}", json);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Frozen wire format: changing these means bumping PROGRESS_PROTOCOL_VERSION
+    const WARMUP_FIXTURE: &str = r#"{"version":1,"progress":{"bench":"my_bench","phase":"warmup","elapsed_ms":500,"target_ms":3000}}"#;
+    const SAMPLES_FIXTURE: &str = r#"{"version":1,"progress":{"bench":"my_bench","phase":"samples","current":10,"total":100}}"#;
+    const PATHOLOGICAL_FIXTURE: &str = r#"{"version":1,"progress":{"bench":"my_bench","phase":"pathological_sample","index":17,"duration_ns":4000000000,"median_ns":2000}}"#;
+    const COMPLETE_FIXTURE: &str =
+        r#"{"version":1,"progress":{"bench":"my_bench","phase":"complete"}}"#;
+
+    fn message(phase: ProgressPhase) -> ProgressMessage<'static> {
+        ProgressMessage {
+            bench: Cow::Borrowed("my_bench"),
+            phase,
+        }
+    }
+
+    fn encode(msg: &ProgressMessage) -> String {
+        serde_json::to_string(&ProgressEnvelope {
+            version: PROGRESS_PROTOCOL_VERSION,
+            progress: msg,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_progress_wire_format_is_frozen() {
+        let cases = [
+            (
+                WARMUP_FIXTURE,
+                ProgressPhase::Warmup {
+                    elapsed_ms: 500,
+                    target_ms: 3000,
+                },
+            ),
+            (
+                SAMPLES_FIXTURE,
+                ProgressPhase::Samples {
+                    current: 10,
+                    total: 100,
+                },
+            ),
+            (
+                PATHOLOGICAL_FIXTURE,
+                ProgressPhase::PathologicalSample {
+                    index: 17,
+                    duration_ns: 4_000_000_000,
+                    median_ns: Some(2000),
+                },
+            ),
+            (COMPLETE_FIXTURE, ProgressPhase::Complete),
+        ];
+
+        for (fixture, phase) in cases {
+            let msg = message(phase);
+            assert_eq!(encode(&msg), fixture);
+            assert_eq!(
+                parse_progress_line(fixture),
+                Some(ProgressLine::Message(msg))
+            );
+        }
+    }
+
+    #[test]
+    fn test_progress_envelope_versions() {
+        // Runners predating the version field
+        assert_eq!(
+            parse_progress_line(r#"{"progress":{"bench":"my_bench","phase":"complete"}}"#),
+            Some(ProgressLine::Message(message(ProgressPhase::Complete)))
+        );
+        assert_eq!(
+            parse_progress_line(
+                r#"{"version":2,"progress":{"bench":"my_bench","phase":"paused"}}"#
+            ),
+            Some(ProgressLine::UnsupportedVersion(2))
+        );
+        assert_eq!(parse_progress_line("thread 'main' panicked"), None);
+        assert_eq!(parse_progress_line(r#"{"name":"my_bench"}"#), None);
+    }
+}
@@ -0,0 +1,223 @@
+//! Keeping this machine's stored runs in sync with another store, such as a
+//! central service (`[baseline] remote_url`), with `.benches` as its cache.
+//!
+//! A store holds the files of a `.benches` tree under their archive paths
+//! (`<machine>/<crate>_<benchmark>/<file>`, see [`crate::archive`]). Before a
+//! session the runs the local tree lacks are fetched into it, so comparisons
+//! only ever read `.benches`; after it the local runs the store lacks are
+//! uploaded, including any saved while the store was unreachable.
+
+use crate::archive::{ArchiveEntry, ImportOutcome};
+use crate::baseline::{decode_run, is_compressed, run_name, BaselineManager, RUN_EXTENSION};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+
+/// Stored runs addressed like the files of a `.benches` tree
+pub trait BaselineStore: Send + Sync {
+    /// File names stored for a benchmark (`<crate>_<benchmark>`) of a
+    /// machine; empty when it has none
+    fn list(&self, machine_id: &str, benchmark: &str) -> io::Result<Vec<String>>;
+
+    /// Contents of one stored file
+    fn get(&self, entry: &ArchiveEntry) -> io::Result<Vec<u8>>;
+
+    /// Store one file, replacing any of the same name
+    fn put(&self, entry: &ArchiveEntry, contents: &[u8]) -> io::Result<()>;
+}
+
+impl BaselineManager {
+    /// Fetch the files of `benchmarks` (`<crate>_<benchmark>` names) that
+    /// `store` holds for this machine and `.benches` doesn't
+    ///
+    /// Files are validated as by [`BaselineManager::import_entry`] and never
+    /// replace a stored one. Returns how many were fetched.
+    pub fn pull_runs(&self, store: &dyn BaselineStore, benchmarks: &[String]) -> io::Result<usize> {
+        let mut fetched = 0;
+        for benchmark in benchmarks {
+            for file_name in store.list(self.machine_id(), benchmark)? {
+                let entry = ArchiveEntry::parse(&format!(
+                    "{}/{}/{}",
+                    self.machine_id(),
+                    benchmark,
+                    file_name
+                ))?;
+                if self.is_stored(&entry) {
+                    continue;
+                }
+                let contents = store.get(&entry)?;
+                if self.import_entry(&entry, &contents)? == ImportOutcome::Imported {
+                    fetched += 1;
+                }
+            }
+        }
+        Ok(fetched)
+    }
+
+    /// Upload the files of `benchmarks` stored for this machine that `store`
+    /// lacks; returns how many were uploaded
+    ///
+    /// Compressed runs are uploaded as plain `<timestamp>.json`, and a run the
+    /// store holds in either name isn't uploaded again.
+    pub fn push_runs(&self, store: &dyn BaselineStore, benchmarks: &[String]) -> io::Result<usize> {
+        let mut uploaded = 0;
+        for benchmark in benchmarks {
+            let bench_dir = self.root_dir().join(self.machine_id()).join(benchmark);
+            if !bench_dir.is_dir() {
+                continue;
+            }
+            let mut local = crate::disk_usage::run_files(&bench_dir)?;
+            local.sort();
+            if bench_dir.join(crate::baseline::REDIRECT_FILE).is_file() {
+                local.push(crate::baseline::REDIRECT_FILE.to_string());
+            }
+
+            let remote: BTreeSet<String> = store
+                .list(self.machine_id(), benchmark)?
+                .iter()
+                .map(|name| run_name(name).unwrap_or(name).to_string())
+                .collect();
+            for file_name in local {
+                let run = run_name(&file_name).unwrap_or(&file_name);
+                if remote.contains(run) {
+                    continue;
+                }
+                let path = bench_dir.join(&file_name);
+                let contents = match fs::read(&path) {
+                    Ok(contents) => contents,
+                    // Pruned since the listing
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                    Err(e) => return Err(e),
+                };
+                let (file_name, contents) = if is_compressed(&path) {
+                    let json = decode_run(contents, true)?;
+                    (format!("{}{}", run, RUN_EXTENSION), json.into_bytes())
+                } else {
+                    (file_name.clone(), contents)
+                };
+                let entry = ArchiveEntry {
+                    machine_id: self.machine_id().to_string(),
+                    benchmark: benchmark.clone(),
+                    file_name,
+                };
+                store.put(&entry, &contents)?;
+                uploaded += 1;
+            }
+        }
+        Ok(uploaded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::baseline::{encode_run, BaselineData};
+    use crate::{BenchResult, Percentiles};
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// Files keyed by archive path
+    #[derive(Default)]
+    struct MemoryStore {
+        files: Mutex<BTreeMap<String, Vec<u8>>>,
+    }
+
+    impl BaselineStore for MemoryStore {
+        fn list(&self, machine_id: &str, benchmark: &str) -> io::Result<Vec<String>> {
+            let prefix = format!("{}/{}/", machine_id, benchmark);
+            Ok(self
+                .files
+                .lock()
+                .unwrap()
+                .keys()
+                .filter_map(|path| path.strip_prefix(&prefix).map(str::to_string))
+                .collect())
+        }
+
+        fn get(&self, entry: &ArchiveEntry) -> io::Result<Vec<u8>> {
+            self.files
+                .lock()
+                .unwrap()
+                .get(&entry.archive_path())
+                .cloned()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        }
+
+        fn put(&self, entry: &ArchiveEntry, contents: &[u8]) -> io::Result<()> {
+            self.files
+                .lock()
+                .unwrap()
+                .insert(entry.archive_path(), contents.to_vec());
+            Ok(())
+        }
+    }
+
+    fn run_json(machine_id: &str, micros: u64) -> String {
+        let timing = Duration::from_micros(micros);
+        let result = BenchResult {
+            name: "b".to_string(),
+            module: "c".to_string(),
+            samples: 10,
+            percentiles: Percentiles {
+                values: vec![(50.0, timing)],
+                mean: timing,
+            },
+            all_timings: vec![timing; 10],
+            ..Default::default()
+        };
+        let baseline = BaselineData::from_bench_result(&result, machine_id.to_string(), false);
+        serde_json::to_string(&baseline).unwrap()
+    }
+
+    #[test]
+    fn test_pull_and_push_sync_missing_runs() {
+        let temp = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp.path()).unwrap();
+        let machine = manager.machine_id().to_string();
+        let benchmarks = vec!["c_b".to_string()];
+        let store = MemoryStore::default();
+
+        // Recorded by another CI job
+        let remote_run =
+            ArchiveEntry::parse(&format!("{}/c_b/2025-01-01T00-00-00.json", machine)).unwrap();
+        store
+            .put(&remote_run, run_json(&machine, 10).as_bytes())
+            .unwrap();
+        assert_eq!(manager.pull_runs(&store, &benchmarks).unwrap(), 1);
+        assert_eq!(manager.pull_runs(&store, &benchmarks).unwrap(), 0);
+        assert_eq!(manager.list_runs("c", "b").unwrap().len(), 1);
+
+        // Saved locally, one of them compressed
+        let dir = temp.path().join(&machine).join("c_b");
+        fs::write(dir.join("2025-01-02T00-00-00.json"), run_json(&machine, 11)).unwrap();
+        fs::write(
+            dir.join("2025-01-03T00-00-00.json.gz"),
+            encode_run(&run_json(&machine, 12), true).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manager.push_runs(&store, &benchmarks).unwrap(), 2);
+        assert_eq!(manager.push_runs(&store, &benchmarks).unwrap(), 0);
+
+        let stored = store.list(&machine, "c_b").unwrap();
+        assert_eq!(
+            stored,
+            vec![
+                "2025-01-01T00-00-00.json",
+                "2025-01-02T00-00-00.json",
+                "2025-01-03T00-00-00.json",
+            ]
+        );
+        let uploaded =
+            ArchiveEntry::parse(&format!("{}/c_b/2025-01-03T00-00-00.json", machine)).unwrap();
+        let json = String::from_utf8(store.get(&uploaded).unwrap()).unwrap();
+        assert_eq!(
+            crate::baseline::parse_baseline(&json)
+                .unwrap()
+                .statistics
+                .mean,
+            12_000
+        );
+    }
+}
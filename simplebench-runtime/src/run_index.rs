@@ -0,0 +1,221 @@
+//! Per-run summaries of this machine's stored runs, for suite-wide reports.
+//!
+//! The summaries are cached in `.benches/runs.json`, keyed like
+//! [`crate::disk_usage`]'s index, so a report over thousands of runs reads one
+//! small file. Runs are matched by file name: only runs saved since the last
+//! refresh are parsed, and deleted ones are dropped.
+
+use crate::baseline::BaselineManager;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+/// Cache file at the root of the `.benches` tree
+const RUN_INDEX_FILE: &str = "runs.json";
+
+/// Cached summaries, stored in [`RUN_INDEX_FILE`]
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RunIndex {
+    /// Keyed by `<machine>/<crate>_<benchmark>`
+    #[serde(default)]
+    benchmarks: BTreeMap<String, Vec<RunSummary>>,
+}
+
+/// One stored run, without its samples
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    /// Run file stem, e.g. "2025-01-15T10-30-00"
+    pub run: String,
+    pub timestamp: DateTime<Utc>,
+    /// Mean in nanoseconds
+    pub mean: u128,
+    pub robust_cv: f64,
+    #[serde(default, skip_serializing_if = "crate::baseline::is_false")]
+    pub was_regression: bool,
+    #[serde(default, skip_serializing_if = "crate::baseline::is_false")]
+    pub was_improvement: bool,
+}
+
+/// One benchmark's runs on this machine
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkHistory {
+    /// `<crate>_<benchmark>` directory name
+    pub benchmark: String,
+    /// Oldest first
+    pub runs: Vec<RunSummary>,
+}
+
+impl BaselineManager {
+    /// Summaries of this machine's runs of every benchmark, by benchmark name
+    ///
+    /// Benchmarks without a readable run are left out; unreadable runs are
+    /// skipped, and retried on the next call.
+    pub fn run_summaries(&self) -> io::Result<Vec<BenchmarkHistory>> {
+        let mut index = self.load_run_index();
+        let prefix = format!("{}/", self.machine_id());
+
+        let mut benchmarks = BTreeMap::new();
+        let mut histories = Vec::new();
+        for (key, bench_dir) in self.benchmark_dirs()? {
+            let Some(benchmark) = key.strip_prefix(&prefix) else {
+                continue;
+            };
+            let mut cached: BTreeMap<String, RunSummary> = index
+                .benchmarks
+                .remove(&key)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|summary| (summary.run.clone(), summary))
+                .collect();
+
+            let mut names = crate::disk_usage::run_files(&bench_dir)?;
+            names.sort();
+            let mut runs = Vec::with_capacity(names.len());
+            for name in names {
+                let run = crate::baseline::run_name(&name)
+                    .unwrap_or(&name)
+                    .to_string();
+                if let Some(summary) = cached.remove(&run) {
+                    runs.push(summary);
+                } else if let Some(summary) = summarize_run(&bench_dir.join(&name), run) {
+                    runs.push(summary);
+                }
+            }
+
+            if !runs.is_empty() {
+                histories.push(BenchmarkHistory {
+                    benchmark: benchmark.to_string(),
+                    runs: runs.clone(),
+                });
+                benchmarks.insert(key, runs);
+            }
+        }
+
+        // Other machines' entries aren't refreshed here, only carried over
+        index.benchmarks.retain(|key, _| !key.starts_with(&prefix));
+        index.benchmarks.extend(benchmarks);
+        self.save_run_index(&index)?;
+        Ok(histories)
+    }
+
+    fn load_run_index(&self) -> RunIndex {
+        fs::read_to_string(self.root_dir().join(RUN_INDEX_FILE))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_run_index(&self, index: &RunIndex) -> io::Result<()> {
+        if !self.root_dir().exists() {
+            return Ok(());
+        }
+        crate::lock::write_atomic(
+            &self.root_dir().join(RUN_INDEX_FILE),
+            serde_json::to_string(index)?.as_bytes(),
+        )
+    }
+}
+
+/// Summarize a stored run; None when it can't be read, or was removed meanwhile
+fn summarize_run(path: &std::path::Path, run: String) -> Option<RunSummary> {
+    let contents = crate::baseline::read_run_file(path).ok()?;
+    let baseline = crate::baseline::parse_baseline(&contents).ok()?;
+    let timestamp = DateTime::parse_from_rfc3339(&baseline.timestamp)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| crate::disk_usage::run_time(path))?;
+    Some(RunSummary {
+        run,
+        timestamp,
+        mean: baseline.statistics.mean,
+        robust_cv: baseline.statistics.robust_cv,
+        was_regression: baseline.was_regression,
+        was_improvement: baseline.was_improvement,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::baseline::BaselineData;
+    use crate::{BenchResult, Percentiles};
+    use std::path::Path;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// Write a run of `micros`-µs samples as `<benchmark>/<run>.json` of `manager`'s machine
+    fn write_run(manager: &BaselineManager, benchmark: &str, run: &str, micros: u64) {
+        let timing = Duration::from_micros(micros);
+        let result = BenchResult {
+            name: benchmark.to_string(),
+            module: "test_module".to_string(),
+            samples: 10,
+            percentiles: Percentiles {
+                values: vec![(50.0, timing)],
+                mean: timing,
+            },
+            all_timings: vec![timing; 10],
+            ..Default::default()
+        };
+        let baseline =
+            BaselineData::from_bench_result(&result, manager.machine_id().to_string(), false);
+        let dir = manager
+            .root_dir()
+            .join(manager.machine_id())
+            .join(benchmark);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(format!("{}.json", run)),
+            serde_json::to_string(&baseline).unwrap(),
+        )
+        .unwrap();
+    }
+
+    fn means(history: &BenchmarkHistory) -> Vec<u128> {
+        history.runs.iter().map(|run| run.mean).collect()
+    }
+
+    #[test]
+    fn test_run_summaries_follow_the_tree() {
+        let temp = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp.path()).unwrap();
+        write_run(&manager, "c_b", "2025-01-02T00-00-00", 20);
+        write_run(&manager, "c_b", "2025-01-01T00-00-00", 10);
+        write_run(&manager, "c_a", "2025-01-01T00-00-00", 5);
+        // Another machine's runs aren't summarized
+        fs::create_dir_all(temp.path().join("other").join("c_x")).unwrap();
+        fs::write(temp.path().join("other/c_x/2025-01-01T00-00-00.json"), "{}").unwrap();
+
+        let histories = manager.run_summaries().unwrap();
+        assert_eq!(histories.len(), 2);
+        assert_eq!(histories[0].benchmark, "c_a");
+        assert_eq!(means(&histories[1]), vec![10_000, 20_000]);
+        assert!(temp.path().join(RUN_INDEX_FILE).exists());
+
+        // New runs are parsed, removed ones dropped, cached ones kept as indexed
+        let b_dir = temp.path().join(manager.machine_id()).join("c_b");
+        fs::remove_file(b_dir.join("2025-01-01T00-00-00.json")).unwrap();
+        write_run(&manager, "c_b", "2025-01-03T00-00-00", 30);
+        fs::write(b_dir.join("2025-01-02T00-00-00.json"), "not a run").unwrap();
+
+        let histories = manager.run_summaries().unwrap();
+        assert_eq!(means(&histories[1]), vec![20_000, 30_000]);
+        assert_eq!(histories[1].runs[1].run, "2025-01-03T00-00-00");
+    }
+
+    #[test]
+    fn test_unreadable_runs_are_skipped() {
+        let temp = TempDir::new().unwrap();
+        let manager = BaselineManager::with_root_dir(temp.path()).unwrap();
+        write_run(&manager, "c_b", "2025-01-01T00-00-00", 10);
+        let b_dir: &Path = &temp.path().join(manager.machine_id()).join("c_b");
+        fs::write(b_dir.join("2025-01-02T00-00-00.json"), "not a run").unwrap();
+        fs::create_dir_all(temp.path().join(manager.machine_id()).join("c_empty")).unwrap();
+
+        let histories = manager.run_summaries().unwrap();
+        assert_eq!(histories.len(), 1);
+        assert_eq!(means(&histories[0]), vec![10_000]);
+    }
+}
@@ -3,6 +3,12 @@
 //! This module provides core statistical operations used by both the statistical
 //! window approach and Bayesian change point detection.
 
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Bins stored with every baseline by [`histogram`]
+pub const DEFAULT_HISTOGRAM_BINS: usize = 20;
+
 /// Calculate the arithmetic mean of a slice of values
 pub fn mean(values: &[f64]) -> f64 {
     if values.is_empty() {
@@ -21,11 +27,86 @@ pub fn variance(values: &[f64]) -> f64 {
     values.iter().map(|&x| (x - m).powi(2)).sum::<f64>() / values.len() as f64
 }
 
+/// Quantile `p` of ascending `sorted`, interpolated linearly between the two
+/// surrounding order statistics (Hyndman & Fan type 7, the default of R and
+/// NumPy); 0 for an empty slice
+pub fn percentile_sorted(sorted: &[u128], p: f64) -> u128 {
+    let Some(last) = sorted.len().checked_sub(1) else {
+        return 0;
+    };
+    let rank = last as f64 * p.clamp(0.0, 1.0);
+    let below = rank.floor() as usize;
+    let above = (below + 1).min(last);
+    let fraction = rank - below as f64;
+    sorted[below] + ((sorted[above] - sorted[below]) as f64 * fraction).round() as u128
+}
+
 /// Calculate the standard deviation of a slice of values
 pub fn standard_deviation(values: &[f64]) -> f64 {
     variance(values).sqrt()
 }
 
+/// Multiple of the interquartile range beyond the quartiles at which a
+/// sample counts as an outlier
+pub const TUKEY_K: f64 = 1.5;
+
+/// Tukey fences of ascending `sorted`: samples below the first or above the
+/// second are outliers; (0, 0) for an empty slice
+pub fn tukey_fences(sorted: &[u128]) -> (u128, u128) {
+    let q1 = percentile_sorted(sorted, 0.25) as f64;
+    let q3 = percentile_sorted(sorted, 0.75) as f64;
+    let iqr = q3 - q1;
+    (
+        (q1 - TUKEY_K * iqr).max(0.0) as u128,
+        (q3 + TUKEY_K * iqr) as u128,
+    )
+}
+
+/// Median of `values`, averaging the middle two of an even count; 0 for an
+/// empty slice
+pub fn median(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Factor turning a median absolute deviation into an estimate of the
+/// standard deviation of normally distributed data
+pub const MAD_SCALE: f64 = 1.4826;
+
+/// Median absolute deviation from the median (unscaled); 0 for an empty slice
+///
+/// Unlike the standard deviation, a few extreme values barely move it.
+pub fn median_absolute_deviation(values: &[f64]) -> f64 {
+    let center = median(values);
+    let deviations: Vec<f64> = values.iter().map(|v| (v - center).abs()).collect();
+    median(&deviations)
+}
+
+/// Cohen's d: difference of the means of `current` and `baseline` in units of
+/// their pooled sample standard deviation
+///
+/// Positive when `current` is larger (slower). None with fewer than two values
+/// on either side or no spread at all.
+pub fn cohens_d(current: &[f64], baseline: &[f64]) -> Option<f64> {
+    let (n1, n2) = (current.len(), baseline.len());
+    if n1 < 2 || n2 < 2 {
+        return None;
+    }
+    // `variance` is the population variance; undo it for the sample variances
+    let sum_sq = |values: &[f64]| variance(values) * values.len() as f64;
+    let pooled_sd = ((sum_sq(current) + sum_sq(baseline)) / (n1 + n2 - 2) as f64).sqrt();
+    (pooled_sd > 0.0).then(|| (mean(current) - mean(baseline)) / pooled_sd)
+}
+
 /// Calculate the z-score: how many standard deviations a value is from the mean
 pub fn z_score(value: f64, mean: f64, stddev: f64) -> f64 {
     if stddev < 1e-10 {
@@ -43,8 +124,13 @@ pub fn z_score(value: f64, mean: f64, stddev: f64) -> f64 {
 /// - 0.95 = 95% confidence (z = 1.645 one-tailed, 1.96 two-tailed)
 /// - 0.99 = 99% confidence (z = 2.326 one-tailed, 2.576 two-tailed)
 pub fn confidence_interval(mean: f64, stddev: f64, confidence_level: f64) -> (f64, f64) {
-    // Map confidence level to z-critical value (one-tailed for regression detection)
-    let z_critical = if (confidence_level - 0.90).abs() < 0.01 {
+    let margin = z_critical(confidence_level) * stddev;
+    (mean - margin, mean + margin)
+}
+
+/// Map a confidence level to its z-critical value (one-tailed for regression detection)
+pub fn z_critical(confidence_level: f64) -> f64 {
+    if (confidence_level - 0.90).abs() < 0.01 {
         1.282 // 90% one-tailed
     } else if (confidence_level - 0.95).abs() < 0.01 {
         1.645 // 95% one-tailed
@@ -53,16 +139,903 @@ pub fn confidence_interval(mean: f64, stddev: f64, confidence_level: f64) -> (f6
     } else {
         // Default to 95% two-tailed for other values
         1.96
+    }
+}
+
+/// z-score for 80% statistical power, used by [`minimum_detectable_effect`]
+const Z_POWER_80: f64 = 0.8416;
+
+/// Smallest change in mean, as a percentage of `baseline_mean`, that a comparison
+/// detects at `confidence_level` with 80% power.
+///
+/// The uncertainty of the difference combines the spread of the baseline window
+/// (`baseline_stddev`, the standard deviation of its run means) with the standard
+/// error of the current run's mean. Returns None for a non-positive baseline mean.
+pub fn minimum_detectable_effect(
+    baseline_mean: f64,
+    baseline_stddev: f64,
+    current_std_error: f64,
+    confidence_level: f64,
+) -> Option<f64> {
+    if baseline_mean <= 0.0 {
+        return None;
+    }
+
+    let combined = (baseline_stddev.powi(2) + current_std_error.powi(2)).sqrt();
+    Some((z_critical(confidence_level) + Z_POWER_80) * combined / baseline_mean * 100.0)
+}
+
+/// Standard normal cumulative distribution function
+///
+/// Uses the Abramowitz & Stegun 7.1.26 approximation of erf (absolute error
+/// below 1.5e-7), plenty for power estimates.
+pub fn normal_cdf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs() / std::f64::consts::SQRT_2);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-x * x / 2.0).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+/// Inverse of [`normal_cdf`]: the z below which a fraction `p` of the mass lies
+///
+/// Acklam's rational approximation (relative error below 1.2e-9). Returns
+/// infinities outside (0, 1).
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
     };
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
 
-    let margin = z_critical * stddev;
-    (mean - margin, mean + margin)
+/// Probability that a true change of `effect_percent` is detected, given the
+/// comparison's minimum detectable effect (which fixes its noise level).
+///
+/// Normal approximation of the one-tailed z-test behind
+/// [`minimum_detectable_effect`]: 80% at an effect equal to the MDE, higher above.
+pub fn power_at_effect(
+    effect_percent: f64,
+    min_detectable_effect: f64,
+    confidence_level: f64,
+) -> f64 {
+    let z = z_critical(confidence_level);
+    if min_detectable_effect <= 0.0 {
+        return if effect_percent > 0.0 {
+            1.0
+        } else {
+            1.0 - normal_cdf(z)
+        };
+    }
+    let noise = min_detectable_effect / (z + Z_POWER_80);
+    normal_cdf(effect_percent / noise - z)
+}
+
+/// Probability that an unchanged benchmark is flagged: its noise has to push
+/// the change past both the z-critical bound and `threshold_percent`.
+pub fn false_positive_rate(
+    threshold_percent: f64,
+    min_detectable_effect: f64,
+    confidence_level: f64,
+) -> f64 {
+    let z = z_critical(confidence_level);
+    if min_detectable_effect <= 0.0 {
+        return 0.0;
+    }
+    let noise = min_detectable_effect / (z + Z_POWER_80);
+    1.0 - normal_cdf(z.max(threshold_percent / noise))
+}
+
+/// Samples per run needed to detect `effect_percent` with 80% power.
+///
+/// `sample_cv_percent` is the coefficient of variation of individual samples and
+/// `window_cv_percent` that of the baseline window's run means. None when the
+/// window's spread alone already exceeds the detectable effect, so no sample
+/// count is enough.
+pub fn samples_for_power(
+    effect_percent: f64,
+    sample_cv_percent: f64,
+    window_cv_percent: f64,
+    confidence_level: f64,
+) -> Option<usize> {
+    let allowed_noise = effect_percent / (z_critical(confidence_level) + Z_POWER_80);
+    let room = allowed_noise.powi(2) - window_cv_percent.powi(2);
+    (room > 0.0).then(|| (sample_cv_percent.powi(2) / room).ceil().max(1.0) as usize)
+}
+
+/// Calculate the autocorrelation of a series at the given lag
+///
+/// Values near 0 mean consecutive samples are independent; values near 1 mean
+/// each sample predicts the next. Returns 0.0 when undefined (too few values or
+/// zero variance).
+pub fn autocorrelation(values: &[f64], lag: usize) -> f64 {
+    if lag == 0 || values.len() <= lag + 1 {
+        return 0.0;
+    }
+
+    let m = mean(values);
+    let denominator: f64 = values.iter().map(|&x| (x - m).powi(2)).sum();
+    if denominator < 1e-10 {
+        return 0.0;
+    }
+
+    let numerator: f64 = values
+        .windows(lag + 1)
+        .map(|w| (w[0] - m) * (w[lag] - m))
+        .sum();
+
+    numerator / denominator
+}
+
+/// Autocorrelation at which a repeating pattern is reported as periodicity
+pub const PERIODICITY_THRESHOLD: f64 = 0.3;
+
+/// Longest period searched for, in samples
+pub const MAX_PERIOD: usize = 2000;
+
+/// Samples of a series used to search for a period, keeping the O(n · lag)
+/// autocorrelogram cheap on million-sample runs
+const PERIODICITY_SERIES_LIMIT: usize = 50_000;
+
+/// Autocorrelation of a series at every lag from 1 to `max_lag`; element `i`
+/// holds lag `i + 1`
+///
+/// Empty when the series is too short or has no variance.
+pub fn autocorrelogram(values: &[f64], max_lag: usize) -> Vec<f64> {
+    let max_lag = max_lag.min(values.len().saturating_sub(2));
+    let m = mean(values);
+    let deviations: Vec<f64> = values.iter().map(|&x| x - m).collect();
+    let denominator: f64 = deviations.iter().map(|d| d * d).sum();
+    if max_lag == 0 || denominator < 1e-10 {
+        return Vec::new();
+    }
+
+    (1..=max_lag)
+        .map(|lag| {
+            let numerator: f64 = deviations
+                .iter()
+                .zip(&deviations[lag..])
+                .map(|(a, b)| a * b)
+                .sum();
+            numerator / denominator
+        })
+        .collect()
+}
+
+/// A repeating pattern in a sample series, such as a pooled allocator's
+/// compaction landing every few hundred samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Periodicity {
+    /// Samples between repeats
+    pub period: usize,
+    /// Autocorrelation at `period`, 0 to 1
+    pub strength: f64,
+    /// Mean of the slowest phase of the cycle over the series' median
+    pub amplitude: f64,
+}
+
+impl Periodicity {
+    /// "periodic spikes every ~200 samples, amplitude 12.0×"
+    pub fn format(&self) -> String {
+        format!(
+            "periodic spikes every ~{} samples, amplitude {:.1}×",
+            self.period, self.amplitude
+        )
+    }
+}
+
+/// Dominant period of a sample series (in sample order), if one repeats with
+/// an autocorrelation of at least `PERIODICITY_THRESHOLD`
+///
+/// Peaks are only looked for past the first lag where the autocorrelation
+/// turns negative, so a drifting or bimodal series (correlated at every short
+/// lag) isn't mistaken for a cycle. Among peaks nearly as strong as the best,
+/// the shortest wins, since multiples of the period correlate too. At least
+/// three full cycles are needed, and the slow phase must stand out in three
+/// quarters of them (give or take a sample), so a couple of outliers that
+/// happen to land a period apart don't count.
+pub fn detect_periodicity(samples: &[u128]) -> Option<Periodicity> {
+    let series: Vec<f64> = samples
+        .iter()
+        .take(PERIODICITY_SERIES_LIMIT)
+        .map(|&s| s as f64)
+        .collect();
+    let acf = autocorrelogram(&series, (series.len() / 3).min(MAX_PERIOD));
+    let first_negative = acf.iter().position(|&r| r < 0.0)?;
+
+    let peaks: Vec<(usize, f64)> = (first_negative + 1..acf.len().saturating_sub(1))
+        .filter(|&i| acf[i] > acf[i - 1] && acf[i] >= acf[i + 1])
+        .map(|i| (i + 1, acf[i]))
+        .collect();
+    let best = peaks.iter().map(|&(_, r)| r).fold(f64::MIN, f64::max);
+    if best < PERIODICITY_THRESHOLD {
+        return None;
+    }
+    let (period, strength) = peaks.into_iter().find(|&(_, r)| r >= best * 0.9)?;
+
+    let mut phase_sums = vec![(0.0, 0usize); period];
+    for (i, &x) in series.iter().enumerate() {
+        let phase = &mut phase_sums[i % period];
+        phase.0 += x;
+        phase.1 += 1;
+    }
+    let (phase, slowest_phase) = phase_sums
+        .iter()
+        .map(|&(sum, n)| sum / n as f64)
+        .enumerate()
+        .fold(
+            (0, f64::MIN),
+            |best, (i, m)| if m > best.1 { (i, m) } else { best },
+        );
+    let typical = median(&series);
+
+    let cutoff = typical + (slowest_phase - typical) / 2.0;
+    let cycles = series.len() / period;
+    let standing_out = (0..cycles)
+        .filter(|&cycle| {
+            let at = cycle * period + phase;
+            series[at.saturating_sub(1)..(at + 2).min(series.len())]
+                .iter()
+                .any(|&x| x > cutoff)
+        })
+        .count();
+    if standing_out * 4 < cycles * 3 {
+        return None;
+    }
+
+    Some(Periodicity {
+        period,
+        strength,
+        amplitude: if typical > 0.0 {
+            slowest_phase / typical
+        } else {
+            1.0
+        },
+    })
+}
+
+/// Result of a two-sided Mann-Whitney U test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MannWhitney {
+    /// U statistic of the first sample: pairs where it is larger, ties counting half
+    pub u: f64,
+    /// Normal approximation of U, tie-corrected; positive when the first sample is larger
+    pub z: f64,
+    /// Two-sided p-value for identical distributions
+    pub p_value: f64,
+    /// Rank-biserial correlation, from -1 to 1: the share of pairs where the
+    /// first sample is larger minus the share where it is smaller
+    pub rank_biserial: f64,
+}
+
+/// Mann-Whitney U test of whether `a` and `b` come from the same distribution
+///
+/// Rank-based, so unaffected by outliers and by the skew typical of timings.
+/// Uses the normal approximation, which is accurate from about 20 values per
+/// sample. Returns None if either sample is empty or all values are tied.
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> Option<MannWhitney> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+
+    let mut pooled: Vec<(f64, bool)> = a
+        .iter()
+        .map(|&x| (x, true))
+        .chain(b.iter().map(|&x| (x, false)))
+        .collect();
+    pooled.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    // Average ranks over ties, collecting the tie correction as we go
+    let n = pooled.len() as f64;
+    let mut rank_sum_a = 0.0;
+    let mut tie_term = 0.0;
+    let mut i = 0;
+    while i < pooled.len() {
+        let mut j = i;
+        while j + 1 < pooled.len() && pooled[j + 1].0 == pooled[i].0 {
+            j += 1;
+        }
+        let avg_rank = (i + j) as f64 / 2.0 + 1.0;
+        rank_sum_a += pooled[i..=j].iter().filter(|(_, in_a)| *in_a).count() as f64 * avg_rank;
+        let t = (j - i + 1) as f64;
+        tie_term += t * t * t - t;
+        i = j + 1;
+    }
+
+    let (n_a, n_b) = (a.len() as f64, b.len() as f64);
+    let u = rank_sum_a - n_a * (n_a + 1.0) / 2.0;
+    let variance = n_a * n_b / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)).max(1.0));
+    if variance <= 0.0 {
+        return None;
+    }
+
+    let z = (u - n_a * n_b / 2.0) / variance.sqrt();
+    Some(MannWhitney {
+        u,
+        z,
+        p_value: (2.0 * (1.0 - normal_cdf(z.abs()))).min(1.0),
+        rank_biserial: 2.0 * u / (n_a * n_b) - 1.0,
+    })
+}
+
+/// Mean, unbiased variance and size of a sample, the inputs of [`welch_t_test`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleMoments {
+    pub mean: f64,
+    pub variance: f64,
+    pub count: usize,
+}
+
+impl SampleMoments {
+    pub fn of(values: &[f64]) -> Self {
+        let n = values.len();
+        let correction = if n > 1 {
+            n as f64 / (n - 1) as f64
+        } else {
+            1.0
+        };
+        Self {
+            mean: mean(values),
+            variance: variance(values) * correction,
+            count: n,
+        }
+    }
+
+    /// From a stored mean and population variance ([`crate::Statistics`]
+    /// divides by n) over `count` samples
+    pub fn from_population(mean: f64, variance: f64, count: usize) -> Self {
+        let correction = if count > 1 {
+            count as f64 / (count - 1) as f64
+        } else {
+            1.0
+        };
+        Self {
+            mean,
+            variance: variance * correction,
+            count,
+        }
+    }
+}
+
+/// Result of a two-sided Welch's t-test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Welch {
+    /// Difference of the means in standard errors; positive when the first
+    /// sample's mean is larger
+    pub t: f64,
+    /// Welch–Satterthwaite degrees of freedom
+    pub degrees_of_freedom: f64,
+    /// Two-sided p-value for equal means
+    pub p_value: f64,
+}
+
+/// Welch's t-test of whether `a` and `b` have the same mean, without assuming
+/// equal variances
+///
+/// Needs only each side's moments, so a stored run's statistics stand in for
+/// its samples. Returns None with fewer than two values on either side or
+/// when neither varies.
+pub fn welch_t_test(a: SampleMoments, b: SampleMoments) -> Option<Welch> {
+    if a.count < 2 || b.count < 2 {
+        return None;
+    }
+    let se_a = a.variance / a.count as f64;
+    let se_b = b.variance / b.count as f64;
+    let se = se_a + se_b;
+    if se <= 0.0 || !se.is_finite() {
+        return None;
+    }
+
+    let t = (a.mean - b.mean) / se.sqrt();
+    let degrees_of_freedom =
+        se * se / (se_a * se_a / (a.count - 1) as f64 + se_b * se_b / (b.count - 1) as f64);
+    Some(Welch {
+        t,
+        degrees_of_freedom,
+        p_value: student_t_two_sided_p(t, degrees_of_freedom),
+    })
+}
+
+/// Student's t cumulative distribution function with `df` degrees of freedom
+pub fn student_t_cdf(t: f64, df: f64) -> f64 {
+    let tail = student_t_two_sided_p(t, df) / 2.0;
+    if t >= 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
+
+/// Probability of a Student's t at least as far from zero as `t`
+fn student_t_two_sided_p(t: f64, df: f64) -> f64 {
+    regularized_incomplete_beta(df / (df + t * t), df / 2.0, 0.5).clamp(0.0, 1.0)
+}
+
+/// Natural log of the gamma function for `x > 0` (Lanczos, g = 7)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection keeps the series in its accurate range
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, &c)| {
+            sum + c / (x + i as f64 + 1.0)
+        });
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// Regularized incomplete beta function I_x(a, b)
+///
+/// Continued fraction by the modified Lentz method, on whichever side of the
+/// distribution's mean converges quickly.
+fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - ln_front.exp() * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 500;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+    let nudge = |v: f64| if v.abs() < TINY { TINY } else { v };
+
+    let mut c = 1.0;
+    let mut d = 1.0 / nudge(1.0 - (a + b) * x / (a + 1.0));
+    let mut fraction = d;
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / nudge(1.0 + even * d);
+        c = nudge(1.0 + even / c);
+        fraction *= d * c;
+
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / nudge(1.0 + odd * d);
+        c = nudge(1.0 + odd / c);
+        let step = d * c;
+        fraction *= step;
+        if (step - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    fraction
+}
+
+/// Deterministic SplitMix64 generator for resampling and reservoir sampling
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform index below `len`
+    pub(crate) fn index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+}
+
+/// Mean of a resample (with replacement) of `values`
+fn resampled_mean(values: &[f64], rng: &mut SplitMix64) -> f64 {
+    (0..values.len())
+        .map(|_| values[rng.index(values.len())])
+        .sum::<f64>()
+        / values.len() as f64
+}
+
+/// Percentile bootstrap confidence interval of `mean(a) / mean(b)`
+///
+/// Draws `resamples` resamples of each sample with a fixed seed, so the same
+/// data always gives the same interval. `confidence_level` is two-sided (0.95
+/// gives the 2.5th to 97.5th percentile). Returns None if either sample is
+/// empty or `b` has a non-positive mean.
+pub fn bootstrap_ratio_ci(
+    a: &[f64],
+    b: &[f64],
+    resamples: usize,
+    confidence_level: f64,
+) -> Option<(f64, f64)> {
+    if a.is_empty() || b.is_empty() || mean(b) <= 0.0 || resamples == 0 {
+        return None;
+    }
+
+    let mut rng = SplitMix64(0x5EED);
+    let mut ratios: Vec<f64> = (0..resamples)
+        .map(|_| resampled_mean(a, &mut rng) / resampled_mean(b, &mut rng))
+        .filter(|r| r.is_finite())
+        .collect();
+    if ratios.is_empty() {
+        return None;
+    }
+    ratios.sort_by(f64::total_cmp);
+
+    let tail = (1.0 - confidence_level.clamp(0.0, 1.0)) / 2.0;
+    let at = |q: f64| ratios[((ratios.len() - 1) as f64 * q).round() as usize];
+    Some((at(tail), at(1.0 - tail)))
+}
+
+/// Values drawn per side of a resample by [`bootstrap_difference_ci`]
+///
+/// Larger samples are resampled m-out-of-n with the deviation rescaled to that
+/// of a full-size resample, which keeps a bootstrap over a window of raw
+/// samples to a few milliseconds.
+const MAX_BOOTSTRAP_DRAWS: usize = 500;
+
+/// Deviation of a resampled mean of `values` from `center`, rescaled to the
+/// spread of a full-size resample when fewer values are drawn
+fn resampled_deviation(values: &[f64], center: f64, rng: &mut SplitMix64) -> f64 {
+    let draws = values.len().min(MAX_BOOTSTRAP_DRAWS);
+    let resampled = (0..draws)
+        .map(|_| values[rng.index(values.len())])
+        .sum::<f64>()
+        / draws as f64;
+    (resampled - center) * (draws as f64 / values.len() as f64).sqrt()
+}
+
+/// Percentile bootstrap interval of `mean(current) - mean(baseline)`
+///
+/// Both sides are resampled `iterations` times with a fixed seed; a side with
+/// a single value (such as the current run's mean) is taken as exact. The
+/// bounds are the `1 - confidence_level` and `confidence_level` quantiles, so
+/// a lower bound above zero is a one-sided regression at that confidence, as
+/// with [`z_critical`]. None if either side is empty or `iterations` is 0.
+pub fn bootstrap_difference_ci(
+    current: &[f64],
+    baseline: &[f64],
+    iterations: usize,
+    confidence_level: f64,
+) -> Option<(f64, f64)> {
+    if current.is_empty() || baseline.is_empty() || iterations == 0 {
+        return None;
+    }
+
+    let (current_mean, baseline_mean) = (mean(current), mean(baseline));
+    let observed = current_mean - baseline_mean;
+    let mut rng = SplitMix64(0x5EED);
+    let mut differences: Vec<f64> = (0..iterations)
+        .map(|_| {
+            observed + resampled_deviation(current, current_mean, &mut rng)
+                - resampled_deviation(baseline, baseline_mean, &mut rng)
+        })
+        .collect();
+    differences.sort_by(f64::total_cmp);
+
+    let level = confidence_level.clamp(0.5, 1.0);
+    let at = |q: f64| differences[((differences.len() - 1) as f64 * q).round() as usize];
+    Some((at(1.0 - level), at(level)))
+}
+
+/// Mean and variance over the most recent `capacity` values
+///
+/// Updated incrementally with Welford's algorithm: pushing a value into a full
+/// window also removes the oldest one, so each update is O(1).
+/// Sample counts over log-scaled bins covering min..max, in nanoseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Histogram {
+    /// Bin boundaries, one more than `counts`; bin `i` covers
+    /// `edges[i]..edges[i + 1]`, the last one including its upper edge
+    pub edges: Vec<f64>,
+    pub counts: Vec<usize>,
+}
+
+/// Histogram of `samples` with `bins` log-scaled bins between their minimum and
+/// maximum, so a bimodal distribution shows as two humps that the percentiles
+/// would hide
+///
+/// Log scaling keeps the body of a right-skewed timing distribution from being
+/// squeezed into the first bin by its tail. All samples equal give a single
+/// bin; None without samples or bins.
+pub fn histogram(samples: &[u128], bins: usize) -> Option<Histogram> {
+    let min = *samples.iter().min()? as f64;
+    let max = *samples.iter().max()? as f64;
+    if bins == 0 {
+        return None;
+    }
+    if min == max {
+        return Some(Histogram {
+            edges: vec![min, max],
+            counts: vec![samples.len()],
+        });
+    }
+
+    // Zero-length samples have no logarithm; the first edge still starts at them
+    let (log_lo, log_hi) = (min.max(1.0).ln(), max.ln());
+    let mut edges: Vec<f64> = (0..=bins)
+        .map(|i| (log_lo + (log_hi - log_lo) * i as f64 / bins as f64).exp())
+        .collect();
+    edges[0] = min;
+    edges[bins] = max;
+
+    let mut counts = vec![0; bins];
+    for &sample in samples {
+        let sample = sample as f64;
+        let bin = edges[1..bins].partition_point(|&edge| edge <= sample);
+        counts[bin] += 1;
+    }
+    Some(Histogram { edges, counts })
+}
+
+/// Least-squares line through a series of run means, x being the run index
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Trend {
+    /// Change of the fitted line per run, in the values' unit
+    pub slope: f64,
+    /// Coefficient of determination of the fit; 0 when the values are all equal
+    pub r_squared: f64,
+    /// Mean of the fitted values, the reference for the percentages
+    pub mean: f64,
+    /// Number of values fitted
+    pub runs: usize,
+}
+
+impl Trend {
+    /// Slope as a percentage of the mean per run
+    pub fn slope_pct(&self) -> f64 {
+        self.slope / self.mean * 100.0
+    }
+
+    /// Drift the line projects from the first to the last run, in percent of the mean
+    pub fn drift_pct(&self) -> f64 {
+        self.slope_pct() * (self.runs - 1) as f64
+    }
+
+    /// e.g. "+0.8%/run (R²=0.91)"
+    pub fn format(&self) -> String {
+        format!("{:+.1}%/run (R²={:.2})", self.slope_pct(), self.r_squared)
+    }
+}
+
+/// Fit a line to `values` (oldest first) to catch drifts too slow for any
+/// single comparison to flag; None with fewer than three values or a
+/// non-positive mean
+pub fn linear_trend(values: &[f64]) -> Option<Trend> {
+    if values.len() < 3 {
+        return None;
+    }
+    let y_mean = mean(values);
+    if y_mean <= 0.0 {
+        return None;
+    }
+    let x_mean = (values.len() - 1) as f64 / 2.0;
+
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for (i, &y) in values.iter().enumerate() {
+        let dx = i as f64 - x_mean;
+        let dy = y - y_mean;
+        sxy += dx * dy;
+        sxx += dx * dx;
+        syy += dy * dy;
+    }
+
+    let slope = sxy / sxx;
+    let r_squared = if syy > 0.0 {
+        (sxy * sxy) / (sxx * syy)
+    } else {
+        0.0
+    };
+    Some(Trend {
+        slope,
+        r_squared,
+        mean: y_mean,
+        runs: values.len(),
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct RollingStats {
+    window: VecDeque<f64>,
+    capacity: usize,
+    mean: f64,
+    m2: f64,
+}
+
+impl RollingStats {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            window: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    /// Add a value, evicting the oldest once the window is full
+    pub fn push(&mut self, value: f64) {
+        if self.window.len() == self.capacity {
+            if let Some(old) = self.window.pop_front() {
+                let n = self.window.len() as f64;
+                if n == 0.0 {
+                    self.mean = 0.0;
+                    self.m2 = 0.0;
+                } else {
+                    let delta = old - self.mean;
+                    self.mean -= delta / n;
+                    self.m2 -= delta * (old - self.mean);
+                }
+            }
+        }
+
+        self.window.push_back(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.window.len() as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Number of values currently in the window
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+
+    /// Whether the window holds `capacity` values
+    pub fn is_full(&self) -> bool {
+        self.window.len() == self.capacity
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Population variance of the window, matching [`variance`]
+    pub fn variance(&self) -> f64 {
+        if self.window.len() < 2 {
+            return 0.0;
+        }
+        (self.m2 / self.window.len() as f64).max(0.0)
+    }
+
+    /// Coefficient of variation of the window as a percentage of the mean
+    ///
+    /// Returns infinity when the mean is zero, so an empty or all-zero window
+    /// never counts as stable.
+    pub fn cv_percent(&self) -> f64 {
+        if self.mean.abs() < 1e-10 {
+            return f64::INFINITY;
+        }
+        self.variance().sqrt() / self.mean * 100.0
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_linear_trend() {
+        // 1% of the first run per run, with a little noise
+        let drifting = [100.0, 101.2, 101.9, 103.1, 104.0, 104.8, 106.1];
+        let trend = linear_trend(&drifting).unwrap();
+        assert!((trend.slope - 1.0).abs() < 0.05);
+        assert!(trend.r_squared > 0.99);
+        assert!((trend.drift_pct() - 5.8).abs() < 0.1);
+        assert_eq!(trend.format(), "+1.0%/run (R²=1.00)");
+
+        let flat = linear_trend(&[50.0, 50.0, 50.0]).unwrap();
+        assert_eq!(flat.slope, 0.0);
+        assert_eq!(flat.r_squared, 0.0);
+
+        // Noise without direction fits badly
+        let noisy = linear_trend(&[100.0, 110.0, 95.0, 108.0, 97.0, 104.0]).unwrap();
+        assert!(noisy.r_squared < 0.2);
+
+        assert!(linear_trend(&[1.0, 2.0]).is_none());
+    }
+
+    #[test]
+    fn test_histogram_log_bins() {
+        // A fast mode at ~100ns and a slow one at ~10µs, 80/20
+        let samples: Vec<u128> = (0..100)
+            .map(|i| {
+                if i % 5 == 0 {
+                    10_000 + i % 10
+                } else {
+                    100 + i % 10
+                }
+            })
+            .collect();
+        let hist = histogram(&samples, 10).unwrap();
+        assert_eq!(hist.edges.len(), 11);
+        assert_eq!(hist.edges[0], 101.0);
+        assert_eq!(hist.edges[10], 10_005.0);
+        assert_eq!(hist.counts.iter().sum::<usize>(), 100);
+        // Both modes stand out, with empty bins between them
+        assert_eq!(hist.counts[0], 80);
+        assert_eq!(hist.counts[9], 20);
+        assert!(hist.counts[1..9].iter().all(|&c| c == 0));
+
+        let flat = histogram(&[500, 500, 500], 10).unwrap();
+        assert_eq!(flat.counts, vec![3]);
+        assert_eq!(histogram(&[7], 10).unwrap().counts, vec![1]);
+        assert_eq!(histogram(&[0, 10], 2).unwrap().counts, vec![1, 1]);
+        assert!(histogram(&[], 10).is_none());
+        assert!(histogram(&[1, 2], 0).is_none());
+    }
+
     #[test]
     fn test_mean() {
         let values = vec![1.0, 2.0, 3.0, 4.0, 5.0];
@@ -72,6 +1045,136 @@ mod tests {
         assert_eq!(mean(&empty), 0.0);
     }
 
+    #[test]
+    fn test_mann_whitney_u() {
+        // Fully separated samples: every value of `a` is larger
+        let a: Vec<f64> = (20..40).map(f64::from).collect();
+        let b: Vec<f64> = (0..20).map(f64::from).collect();
+        let test = mann_whitney_u(&a, &b).unwrap();
+        assert_eq!(test.u, 400.0);
+        assert_eq!(test.rank_biserial, 1.0);
+        assert!(test.z > 5.0);
+        assert!(test.p_value < 1e-6);
+
+        // Interleaved samples: no evidence of a difference
+        let evens: Vec<f64> = (0..40).step_by(2).map(f64::from).collect();
+        let odds: Vec<f64> = (1..40).step_by(2).map(f64::from).collect();
+        let interleaved = mann_whitney_u(&evens, &odds).unwrap();
+        assert!(interleaved.p_value > 0.5);
+        assert!(interleaved.rank_biserial.abs() < 0.1);
+
+        // Ties average their ranks; all-tied samples carry no information
+        let tied = mann_whitney_u(&[1.0, 2.0, 2.0], &[2.0, 3.0]).unwrap();
+        assert_eq!(tied.u, 1.0);
+        assert!(mann_whitney_u(&[1.0, 1.0], &[1.0]).is_none());
+        assert!(mann_whitney_u(&[], &[1.0]).is_none());
+    }
+
+    #[test]
+    fn test_student_t_cdf() {
+        // One degree of freedom is the Cauchy distribution
+        assert!((student_t_cdf(1.0, 1.0) - 0.75).abs() < 1e-9);
+        assert!((student_t_cdf(0.0, 7.0) - 0.5).abs() < 1e-12);
+        // Tabulated: t = 2.228 is the 97.5th percentile at 10 df
+        assert!((student_t_cdf(2.228, 10.0) - 0.975).abs() < 1e-4);
+        assert!((student_t_cdf(-2.228, 10.0) - 0.025).abs() < 1e-4);
+        // Many degrees of freedom approach the normal
+        assert!((student_t_cdf(1.96, 5000.0) - normal_cdf(1.96)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_welch_t_test() {
+        // Worked example: means 20.8 and 23.1, variances 7.9 and 3.8, 15 values each
+        let a = SampleMoments {
+            mean: 20.8,
+            variance: 7.9,
+            count: 15,
+        };
+        let b = SampleMoments {
+            mean: 23.1,
+            variance: 3.8,
+            count: 15,
+        };
+        let test = welch_t_test(a, b).unwrap();
+        assert!((test.t - -2.6042).abs() < 1e-3);
+        assert!((test.degrees_of_freedom - 24.938).abs() < 1e-2);
+        assert!((test.p_value - 0.01529).abs() < 1e-4);
+
+        // Moments from samples and from stored population variance agree
+        let values = [1.0, 2.0, 3.0, 4.0];
+        let moments = SampleMoments::of(&values);
+        assert!((moments.variance - 5.0 / 3.0).abs() < 1e-12);
+        assert_eq!(
+            SampleMoments::from_population(2.5, variance(&values), 4),
+            moments
+        );
+
+        let constant = SampleMoments::of(&[5.0, 5.0, 5.0]);
+        assert!(welch_t_test(constant, constant).is_none());
+        assert!(welch_t_test(SampleMoments::of(&[1.0]), moments).is_none());
+    }
+
+    #[test]
+    fn test_bootstrap_ratio_ci() {
+        let a: Vec<f64> = (0..200).map(|i| 200.0 + (i % 10) as f64).collect();
+        let b: Vec<f64> = (0..200).map(|i| 100.0 + (i % 10) as f64).collect();
+        let (low, high) = bootstrap_ratio_ci(&a, &b, 1000, 0.95).unwrap();
+        let ratio = mean(&a) / mean(&b);
+        assert!(low < ratio && ratio < high);
+        assert!(low > 1.9 && high < 2.1);
+
+        // Fixed seed: identical intervals for identical data
+        assert_eq!(bootstrap_ratio_ci(&a, &b, 1000, 0.95), Some((low, high)));
+        assert!(bootstrap_ratio_ci(&a, &[], 1000, 0.95).is_none());
+    }
+
+    #[test]
+    fn test_minimum_detectable_effect() {
+        // 3-4-5 triangle: the combined standard deviation is exactly 5
+        let mde = minimum_detectable_effect(100.0, 3.0, 4.0, 0.95).unwrap();
+        assert!((mde - (1.645 + 0.8416) * 5.0).abs() < 1e-9);
+
+        // Only the window's spread, at 99% confidence, relative to a mean of 1000
+        let mde = minimum_detectable_effect(1000.0, 10.0, 0.0, 0.99).unwrap();
+        assert!((mde - (2.326 + 0.8416)).abs() < 1e-9);
+
+        // Quadrupling the current run's samples halves its standard error
+        let few = minimum_detectable_effect(100.0, 0.0, 2.0, 0.95).unwrap();
+        let many = minimum_detectable_effect(100.0, 0.0, 1.0, 0.95).unwrap();
+        assert!((few - 2.0 * many).abs() < 1e-9);
+
+        assert_eq!(minimum_detectable_effect(100.0, 0.0, 0.0, 0.95), Some(0.0));
+        assert!(minimum_detectable_effect(0.0, 1.0, 1.0, 0.95).is_none());
+    }
+
+    #[test]
+    fn test_power_estimates() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.645) - 0.95).abs() < 1e-3);
+        assert!((normal_cdf(-1.96) - 0.025).abs() < 1e-3);
+        assert!((normal_quantile(0.975) - 1.959964).abs() < 1e-5);
+        assert!((normal_quantile(0.999) - 3.090232).abs() < 1e-5);
+        assert!((normal_quantile(0.01) + 2.326348).abs() < 1e-5);
+        assert_eq!(normal_quantile(0.5), 0.0);
+
+        // An effect equal to the MDE is detected with 80% power
+        assert!((power_at_effect(5.0, 5.0, 0.95) - 0.80).abs() < 1e-3);
+        assert!(power_at_effect(10.0, 5.0, 0.95) > 0.99);
+        assert!(power_at_effect(2.0, 5.0, 0.95) < 0.3);
+
+        // A threshold far above the noise almost never trips without a change
+        assert!(false_positive_rate(5.0, 1.0, 0.95) < 1e-6);
+        assert!((false_positive_rate(0.0, 5.0, 0.95) - 0.05).abs() < 1e-3);
+
+        // Noise of 1% per sample, no window spread: n = (1 / (5 / 2.4866))^2
+        assert_eq!(samples_for_power(5.0, 1.0, 0.0, 0.95), Some(1));
+        let n = samples_for_power(5.0, 20.0, 0.0, 0.95).unwrap();
+        let mde = minimum_detectable_effect(100.0, 0.0, 20.0 / (n as f64).sqrt(), 0.95).unwrap();
+        assert!(mde <= 5.0);
+        // A window spread of 3% can't resolve a 5% change at any sample count
+        assert_eq!(samples_for_power(5.0, 20.0, 3.0, 0.95), None);
+    }
+
     #[test]
     fn test_variance() {
         let values = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
@@ -88,6 +1191,49 @@ mod tests {
         assert!((std - 2.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_median_absolute_deviation() {
+        let values = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_eq!(median(&values), 4.5);
+        assert_eq!(median_absolute_deviation(&values), 0.5);
+
+        // One outlier dominates the standard deviation but not the MAD
+        let values = vec![1.0, 2.0, 3.0, 4.0, 1000.0];
+        assert_eq!(median(&values), 3.0);
+        assert_eq!(median_absolute_deviation(&values), 1.0);
+        assert!(standard_deviation(&values) > 300.0);
+        assert_eq!(median_absolute_deviation(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_bootstrap_difference_ci() {
+        let window = [100.0, 102.0, 98.0, 101.0, 99.0];
+        let (lower, upper) = bootstrap_difference_ci(&[110.0], &window, 1000, 0.95).unwrap();
+        assert!(lower > 0.0 && upper > lower);
+        assert!(lower < 10.0 && upper > 10.0);
+        let (lower, upper) = bootstrap_difference_ci(&[100.5], &window, 1000, 0.95).unwrap();
+        assert!(lower < 0.0 && upper > 0.0);
+        assert_eq!(bootstrap_difference_ci(&[], &window, 1000, 0.95), None);
+        assert_eq!(bootstrap_difference_ci(&[1.0], &window, 0, 0.95), None);
+
+        // Raw samples are resampled in bounded draws, so the cost stays flat
+        let samples: Vec<f64> = (0..10_000).map(|i| 100.0 + (i % 7) as f64).collect();
+        let (lower, upper) =
+            bootstrap_difference_ci(&samples[..1001], &samples, 1000, 0.95).unwrap();
+        assert!(lower < 0.0 && upper > 0.0);
+    }
+
+    #[test]
+    fn test_cohens_d() {
+        // Means 2 apart, both samples with variance 1 (sample sd 1)
+        let d = cohens_d(&[2.0, 3.0, 4.0], &[0.0, 1.0, 2.0]).unwrap();
+        assert!((d - 2.0).abs() < 1e-12);
+        assert!(cohens_d(&[0.0, 1.0, 2.0], &[2.0, 3.0, 4.0]).unwrap() < 0.0);
+
+        assert_eq!(cohens_d(&[1.0], &[1.0, 2.0]), None);
+        assert_eq!(cohens_d(&[5.0, 5.0], &[5.0, 5.0]), None);
+    }
+
     #[test]
     fn test_z_score() {
         let z = z_score(10.0, 5.0, 2.0);
@@ -105,4 +1251,104 @@ mod tests {
         assert!((lower - 83.55).abs() < 1.0);
         assert!((upper - 116.45).abs() < 1.0);
     }
+
+    #[test]
+    fn test_autocorrelation() {
+        // Alternating series: strongly negative at lag 1, positive at lag 2
+        let alternating: Vec<f64> = (0..100)
+            .map(|i| if i % 2 == 0 { 1.0 } else { -1.0 })
+            .collect();
+        assert!(autocorrelation(&alternating, 1) < -0.9);
+        assert!(autocorrelation(&alternating, 2) > 0.9);
+
+        // Slowly drifting series: strongly positive at lag 1
+        let drifting: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        assert!(autocorrelation(&drifting, 1) > 0.9);
+
+        // Degenerate inputs
+        assert_eq!(autocorrelation(&[5.0; 10], 1), 0.0);
+        assert_eq!(autocorrelation(&[1.0, 2.0], 1), 0.0);
+        assert_eq!(autocorrelation(&drifting, 0), 0.0);
+    }
+
+    /// ~1µs samples with uniform noise, spiking to `spike` every `period`
+    fn spiky_series(len: usize, period: usize, spike: u128) -> Vec<u128> {
+        let mut rng = SplitMix64(42);
+        (0..len)
+            .map(|i| {
+                let noise = rng.index(100) as u128;
+                if i % period == period / 2 {
+                    spike + noise
+                } else {
+                    1000 + noise
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_periodicity_finds_injected_spikes() {
+        let series = spiky_series(2000, 200, 12_000);
+        let periodicity = detect_periodicity(&series).unwrap();
+        assert_eq!(periodicity.period, 200);
+        assert!(periodicity.strength > 0.7);
+        assert!(periodicity.amplitude > 10.0 && periodicity.amplitude < 13.0);
+        assert!(periodicity
+            .format()
+            .starts_with("periodic spikes every ~200 samples, amplitude 11."));
+
+        // Short periods too, jittered by a sample either way
+        let mut jittered = spiky_series(1000, 13, 5_000);
+        jittered.swap(13 * 10 + 6, 13 * 10 + 7);
+        assert_eq!(detect_periodicity(&jittered).map(|p| p.period), Some(13));
+
+        // Two cycles of a 400-sample period aren't enough
+        assert_eq!(detect_periodicity(&spiky_series(1000, 400, 12_000)), None);
+
+        // Nor are two outliers that happen to be 135 samples apart
+        let mut outliers = spiky_series(400, 400, 1000);
+        outliers[0] = 15_000;
+        outliers[135] = 20_000;
+        assert_eq!(detect_periodicity(&outliers), None);
+    }
+
+    #[test]
+    fn test_detect_periodicity_ignores_noise_and_drift() {
+        let mut rng = SplitMix64(7);
+        let noise: Vec<u128> = (0..2000).map(|_| 1000 + rng.index(100) as u128).collect();
+        assert_eq!(detect_periodicity(&noise), None);
+
+        let drifting: Vec<u128> = (0..2000)
+            .map(|i| 1000 + i / 2 + rng.index(20) as u128)
+            .collect();
+        assert_eq!(detect_periodicity(&drifting), None);
+
+        let bimodal: Vec<u128> = (0..2000)
+            .map(|i| if i < 1000 { 1000 } else { 2000 })
+            .collect();
+        assert_eq!(detect_periodicity(&bimodal), None);
+
+        assert_eq!(detect_periodicity(&[1000; 500]), None);
+        assert_eq!(detect_periodicity(&[]), None);
+        assert!(autocorrelogram(&[1.0, 2.0], 5).is_empty());
+    }
+
+    #[test]
+    fn test_rolling_stats_matches_window() {
+        let values: Vec<f64> = (0..50).map(|i| ((i * 7) % 11) as f64 + 10.0).collect();
+        let mut rolling = RollingStats::new(8);
+        for &v in &values {
+            rolling.push(v);
+        }
+
+        let window = &values[values.len() - 8..];
+        assert!(rolling.is_full());
+        assert_eq!(rolling.len(), 8);
+        assert!((rolling.mean() - mean(window)).abs() < 1e-9);
+        assert!((rolling.variance() - variance(window)).abs() < 1e-9);
+
+        let expected_cv = standard_deviation(window) / mean(window) * 100.0;
+        assert!((rolling.cv_percent() - expected_cv).abs() < 1e-9);
+        assert!(RollingStats::new(4).cv_percent().is_infinite());
+    }
 }
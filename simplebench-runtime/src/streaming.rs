@@ -0,0 +1,359 @@
+//! Constant-memory statistics for runs that don't keep every sample.
+//!
+//! With `store_raw_samples = false`, the measurement loop feeds each timing
+//! into a [`StreamingStats`] (Welford mean and variance, exact min and max, and
+//! P² estimates of p50/p90/p99) and keeps only a uniform [`Reservoir`] of the
+//! samples themselves, so memory and baseline size stay flat however many
+//! samples are taken.
+
+use crate::statistics::SplitMix64;
+use crate::Statistics;
+
+/// Samples kept by the reservoir when raw samples aren't stored
+pub const RESERVOIR_SIZE: usize = 10_000;
+
+/// Running count, mean, variance, min and max (Welford's algorithm)
+#[derive(Debug, Clone, Default)]
+pub struct Moments {
+    count: u64,
+    mean: f64,
+    m2: f64,
+    min: u64,
+    max: u64,
+}
+
+impl Moments {
+    pub fn push(&mut self, value: u64) {
+        self.count += 1;
+        if self.count == 1 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        let x = value as f64;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Population variance, like [`crate::statistics::variance`]
+    pub fn variance(&self) -> f64 {
+        if self.count < 2 {
+            return 0.0;
+        }
+        (self.m2 / self.count as f64).max(0.0)
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+}
+
+impl FromIterator<u64> for Moments {
+    fn from_iter<T: IntoIterator<Item = u64>>(iter: T) -> Self {
+        let mut moments = Moments::default();
+        for value in iter {
+            moments.push(value);
+        }
+        moments
+    }
+}
+
+/// P² estimate of one quantile (Jain & Chlamtac), in constant memory
+///
+/// Five markers track the minimum, the quantile, the maximum and two points in
+/// between; each value moves them by at most one position, adjusting heights
+/// with a piecewise-parabolic fit. Until five values arrive the quantile is
+/// read off the sorted values exactly.
+#[derive(Debug, Clone)]
+pub struct P2Quantile {
+    p: f64,
+    count: usize,
+    /// Marker heights
+    heights: [f64; 5],
+    /// Actual marker positions (1-based)
+    positions: [f64; 5],
+    /// Desired marker positions
+    desired: [f64; 5],
+    /// Desired position increments per value
+    increments: [f64; 5],
+}
+
+impl P2Quantile {
+    /// Estimator of the `p` quantile, `p` in (0, 1)
+    pub fn new(p: f64) -> Self {
+        Self {
+            p,
+            count: 0,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    pub fn push(&mut self, value: f64) {
+        if self.count < 5 {
+            self.heights[self.count] = value;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        self.count += 1;
+
+        let h = &mut self.heights;
+        let cell = if value < h[0] {
+            h[0] = value;
+            0
+        } else if value >= h[4] {
+            h[4] = value;
+            3
+        } else {
+            (0..4).rfind(|&i| h[i] <= value).unwrap_or(0)
+        };
+        for position in &mut self.positions[cell + 1..] {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments) {
+            *desired += increment;
+        }
+
+        for i in 1..4 {
+            let offset = self.desired[i] - self.positions[i];
+            let room_above = self.positions[i + 1] - self.positions[i];
+            let room_below = self.positions[i - 1] - self.positions[i];
+            if (offset >= 1.0 && room_above > 1.0) || (offset <= -1.0 && room_below < -1.0) {
+                let d = offset.signum();
+                let parabolic = self.parabolic(i, d);
+                self.heights[i] =
+                    if self.heights[i - 1] < parabolic && parabolic < self.heights[i + 1] {
+                        parabolic
+                    } else {
+                        self.linear(i, d)
+                    };
+                self.positions[i] += d;
+            }
+        }
+    }
+
+    fn parabolic(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    fn linear(&self, i: usize, d: f64) -> f64 {
+        let j = if d > 0.0 { i + 1 } else { i - 1 };
+        self.heights[i]
+            + d * (self.heights[j] - self.heights[i]) / (self.positions[j] - self.positions[i])
+    }
+
+    /// Current estimate; 0 without values
+    pub fn value(&self) -> f64 {
+        match self.count {
+            0 => 0.0,
+            n if n < 5 => {
+                let mut seen = self.heights[..n].to_vec();
+                seen.sort_by(f64::total_cmp);
+                seen[((n as f64 * self.p) as usize).min(n - 1)]
+            }
+            _ => self.heights[2],
+        }
+    }
+}
+
+/// Summary of a stream of timings: exact moments and estimated percentiles
+#[derive(Debug, Clone)]
+pub struct StreamingStats {
+    moments: Moments,
+    p50: P2Quantile,
+    p90: P2Quantile,
+    p99: P2Quantile,
+    /// Estimators of the configured percentiles (0-100)
+    configured: Vec<(f64, P2Quantile)>,
+}
+
+impl Default for StreamingStats {
+    fn default() -> Self {
+        Self::new(&crate::DEFAULT_PERCENTILES)
+    }
+}
+
+impl StreamingStats {
+    /// Estimate `median`, `p90` and `p99` plus each of `percentiles` (0-100)
+    pub fn new(percentiles: &[f64]) -> Self {
+        Self {
+            moments: Moments::default(),
+            p50: P2Quantile::new(0.5),
+            p90: P2Quantile::new(0.9),
+            p99: P2Quantile::new(0.99),
+            configured: percentiles
+                .iter()
+                .map(|&p| (p, P2Quantile::new(p / 100.0)))
+                .collect(),
+        }
+    }
+
+    pub fn push(&mut self, nanos: u64) {
+        self.moments.push(nanos);
+        let x = nanos as f64;
+        self.p50.push(x);
+        self.p90.push(x);
+        self.p99.push(x);
+        for (_, estimator) in &mut self.configured {
+            estimator.push(x);
+        }
+    }
+
+    pub fn moments(&self) -> &Moments {
+        &self.moments
+    }
+
+    /// Statistics over everything pushed; percentiles are P² estimates
+    ///
+    /// MAD and IQR need the samples themselves and are left at 0, for the
+    /// caller to fill in from a reservoir.
+    pub fn statistics(&self) -> Statistics {
+        let m = &self.moments;
+        Statistics {
+            mean: m.mean().round() as u128,
+            median: self.p50.value().round() as u128,
+            p90: self.p90.value().round() as u128,
+            p99: self.p99.value().round() as u128,
+            std_dev: m.std_dev(),
+            variance: m.variance(),
+            min: m.min as u128,
+            max: m.max as u128,
+            sample_count: m.count() as usize,
+            mad: 0.0,
+            iqr: 0.0,
+            robust_cv: 0.0,
+            percentiles: self
+                .configured
+                .iter()
+                .map(|(p, estimator)| (*p, estimator.value().round() as u128))
+                .collect(),
+        }
+    }
+}
+
+/// Uniform sample of a stream in fixed memory (Algorithm R)
+///
+/// The reservoir only picks slots; callers keep the sampled values in their
+/// own vectors, so several index-aligned series share one sample.
+pub(crate) struct Reservoir {
+    capacity: usize,
+    seen: u64,
+    rng: SplitMix64,
+}
+
+impl Reservoir {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            seen: 0,
+            rng: SplitMix64(0x5EED),
+        }
+    }
+
+    /// Offer the next value; the slot to store it in, or None to drop it
+    ///
+    /// Slots below the capacity are handed out in order first (append), later
+    /// values replace a random slot with probability `capacity / seen`.
+    pub(crate) fn offer(&mut self) -> Option<usize> {
+        self.seen += 1;
+        if self.seen <= self.capacity as u64 {
+            return Some(self.seen as usize - 1);
+        }
+        let slot = self.rng.next_u64() % self.seen;
+        (slot < self.capacity as u64).then_some(slot as usize)
+    }
+}
+
+/// Store `value` in `slot` of `series`: append at the end, overwrite below it
+///
+/// A slot past the end means an earlier value of the series was missing; the
+/// value is dropped and the series ends up shorter than the timings.
+pub(crate) fn store_in_slot<T>(series: &mut Vec<T>, slot: usize, value: T) {
+    if slot < series.len() {
+        series[slot] = value;
+    } else if slot == series.len() {
+        series.push(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaming_stats_track_exact_statistics() {
+        // A shuffled 1..=10_000 ramp, so the estimators see values out of order
+        let values: Vec<u64> = (0..10_000u64).map(|i| (i * 7_919) % 10_000 + 1).collect();
+        let mut streaming = StreamingStats::default();
+        for &v in &values {
+            streaming.push(v);
+        }
+
+        let exact =
+            crate::calculate_statistics(&values.iter().map(|&v| v as u128).collect::<Vec<_>>());
+        let estimate = streaming.statistics();
+        assert_eq!(estimate.sample_count, 10_000);
+        assert_eq!((estimate.min, estimate.max), (1, 10_000));
+        assert!(estimate.mean.abs_diff(exact.mean) <= 1);
+        assert!((estimate.std_dev - exact.std_dev).abs() < 1e-6 * exact.std_dev);
+        for (estimated, exact) in [
+            (estimate.median, exact.median),
+            (estimate.p90, exact.p90),
+            (estimate.p99, exact.p99),
+        ] {
+            // Within 1% of the range
+            assert!(
+                estimated.abs_diff(exact) <= 100,
+                "{} vs {}",
+                estimated,
+                exact
+            );
+        }
+
+        // Short streams are exact
+        let mut short = P2Quantile::new(0.5);
+        for v in [5.0, 1.0, 3.0] {
+            short.push(v);
+        }
+        assert_eq!(short.value(), 3.0);
+    }
+
+    #[test]
+    fn test_reservoir_keeps_a_uniform_sample() {
+        let mut reservoir = Reservoir::new(100);
+        let mut kept: Vec<u64> = Vec::new();
+        for value in 0..10_000u64 {
+            if let Some(slot) = reservoir.offer() {
+                store_in_slot(&mut kept, slot, value);
+            }
+        }
+
+        assert_eq!(kept.len(), 100);
+        // Later values replace earlier ones: roughly half come from each half
+        let late = kept.iter().filter(|&&v| v >= 5_000).count();
+        assert!((30..=70).contains(&late), "{} late values", late);
+
+        let mut series = vec![1, 2];
+        store_in_slot(&mut series, 4, 9);
+        assert_eq!(series, vec![1, 2]);
+    }
+}
@@ -0,0 +1,167 @@
+//! The compiler, build and CPU a run was measured with, recorded with every
+//! stored run.
+//!
+//! The generated runner registers the build it was compiled from with
+//! [`set_build`]; the CPU model is read from `/proc/cpuinfo` once per process.
+//! A run measured outside a generated runner records only the CPU model.
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Compiler, build profile and CPU model of a run; None where unknown
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Toolchain {
+    /// Output of `rustc --version`, e.g. "rustc 1.78.0 (9b00956e5 2024-04-29)"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rustc_version: Option<String>,
+    /// Target triple the benchmark crates were compiled for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// `-C opt-level` the benchmark crates were compiled with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opt_level: Option<String>,
+    /// `model name` of the CPU, from /proc/cpuinfo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_model: Option<String>,
+}
+
+impl Toolchain {
+    /// The recorded value of `property`
+    pub fn get(&self, property: ToolchainProperty) -> Option<&str> {
+        match property {
+            ToolchainProperty::Rustc => self.rustc_version.as_deref(),
+            ToolchainProperty::Target => self.target.as_deref(),
+            ToolchainProperty::OptLevel => self.opt_level.as_deref(),
+            ToolchainProperty::CpuModel => self.cpu_model.as_deref(),
+        }
+    }
+}
+
+/// One recorded property of a [`Toolchain`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolchainProperty {
+    Rustc,
+    Target,
+    OptLevel,
+    CpuModel,
+}
+
+impl ToolchainProperty {
+    pub const ALL: [ToolchainProperty; 4] = [
+        ToolchainProperty::Rustc,
+        ToolchainProperty::Target,
+        ToolchainProperty::OptLevel,
+        ToolchainProperty::CpuModel,
+    ];
+}
+
+/// A toolchain property of the current run that differs from runs of the
+/// baseline window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolchainChange {
+    pub property: ToolchainProperty,
+    /// Value in the latest differing window run
+    pub baseline: String,
+    pub current: String,
+}
+
+impl ToolchainChange {
+    /// "baseline recorded with rustc 1.76.0, current 1.78.0"
+    pub fn format(&self) -> String {
+        match self.property {
+            ToolchainProperty::Rustc => format!(
+                "baseline recorded with rustc {}, current {}",
+                rustc_release(&self.baseline),
+                rustc_release(&self.current)
+            ),
+            ToolchainProperty::Target => format!(
+                "baseline recorded for {}, current {}",
+                self.baseline, self.current
+            ),
+            ToolchainProperty::OptLevel => format!(
+                "baseline recorded with opt-level {}, current {}",
+                self.baseline, self.current
+            ),
+            ToolchainProperty::CpuModel => format!(
+                "baseline recorded on {}, current {}",
+                self.baseline, self.current
+            ),
+        }
+    }
+}
+
+/// "1.78.0" of "rustc 1.78.0 (9b00956e5 2024-04-29)"; the whole string when
+/// it has another shape
+fn rustc_release(version: &str) -> &str {
+    version
+        .strip_prefix("rustc ")
+        .and_then(|rest| rest.split_whitespace().next())
+        .unwrap_or(version)
+}
+
+/// Compiler and profile registered by the generated runner
+static BUILD: OnceLock<Toolchain> = OnceLock::new();
+
+/// Record the compiler, target and opt-level the benchmarks were built with,
+/// for every run measured by this process; only the first call counts
+pub fn set_build(rustc_version: &str, target: &str, opt_level: &str) {
+    let non_empty = |value: &str| (!value.is_empty()).then(|| value.to_string());
+    let _ = BUILD.set(Toolchain {
+        rustc_version: non_empty(rustc_version),
+        target: non_empty(target),
+        opt_level: non_empty(opt_level),
+        cpu_model: None,
+    });
+}
+
+/// The toolchain of runs measured by this process; None when nothing is known
+pub fn current() -> Option<Toolchain> {
+    static CPU_MODEL: OnceLock<Option<String>> = OnceLock::new();
+    let cpu_model = CPU_MODEL
+        .get_or_init(|| {
+            std::fs::read_to_string("/proc/cpuinfo")
+                .ok()
+                .and_then(|cpuinfo| cpu_model(&cpuinfo))
+        })
+        .clone();
+    let toolchain = Toolchain {
+        cpu_model,
+        ..BUILD.get().cloned().unwrap_or_default()
+    };
+    (toolchain != Toolchain::default()).then_some(toolchain)
+}
+
+/// The first `model name` of /proc/cpuinfo contents
+fn cpu_model(cpuinfo: &str) -> Option<String> {
+    cpuinfo.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "model name" && !value.trim().is_empty()).then(|| value.trim().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_model_and_rustc_release() {
+        let cpuinfo = "processor\t: 0\nvendor_id\t: GenuineIntel\nmodel\t\t: 85\n\
+                       model name\t: Intel(R) Xeon(R) Gold 6148 CPU @ 2.40GHz\n\nprocessor\t: 1\n";
+        assert_eq!(
+            cpu_model(cpuinfo).as_deref(),
+            Some("Intel(R) Xeon(R) Gold 6148 CPU @ 2.40GHz")
+        );
+        assert_eq!(cpu_model("processor\t: 0\nBogoMIPS\t: 50.00\n"), None);
+
+        let change = ToolchainChange {
+            property: ToolchainProperty::Rustc,
+            baseline: "rustc 1.76.0 (07dca489a 2024-02-04)".to_string(),
+            current: "rustc 1.78.0 (9b00956e5 2024-04-29)".to_string(),
+        };
+        assert_eq!(
+            change.format(),
+            "baseline recorded with rustc 1.76.0, current 1.78.0"
+        );
+    }
+}
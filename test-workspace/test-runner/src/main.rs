@@ -12,7 +12,7 @@ fn main() {
     // Create a config with reasonable test defaults
     let mut config = BenchmarkConfig::default();
     config.measurement.samples = 10;
-    config.measurement.warmup_duration_secs = 1;
+    config.measurement.warmup_duration = std::time::Duration::from_secs(1);
 
     // Suppress unused warnings - these imports are needed to link the benchmarks
     let _ = (game_math::Vec3::new(0.0, 0.0, 0.0), game_entities::Entity::new(0));